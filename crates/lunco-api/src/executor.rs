@@ -23,6 +23,11 @@ use bevy::reflect::TypeRegistry;
 pub struct ApiRequestEvent {
     pub request: ApiRequest,
     pub correlation_id: u64,
+    /// The authenticated session a remote transport resolved the caller to.
+    /// `None` = host-trusted (loopback API, in-app REPL, wasm bridge): no gate,
+    /// exactly as before remote access existed. `Some` routes the request
+    /// through [`ApiAuthz`] and applies its command as that session.
+    pub origin: Option<lunco_core::SessionId>,
 }
 
 /// Events that the executor sends back to transports with results.
@@ -66,6 +71,94 @@ impl ApiIdCounter {
     }
 }
 
+/// The session each in-flight remote command is applied as, keyed by the
+/// [`ApiCommandEvent::id`] the executor minted for it. Written when the request
+/// is accepted, taken by [`api_command_dispatcher`] when it triggers the typed
+/// event. A side table rather than a field on [`ApiCommandEvent`] because that
+/// event is also raised in-process (scripts, tutorials), where there is no
+/// remote caller to name.
+#[derive(Resource, Default, Debug)]
+pub struct ApiCommandOrigins(std::collections::HashMap<u64, lunco_core::SessionId>);
+
+impl ApiCommandOrigins {
+    /// Apply the command minted as `id` as `session` when it is dispatched.
+    /// For in-process issuers that re-issue on someone's behalf (the command
+    /// journal's replayer); transports go through [`ApiRequestEvent::origin`].
+    pub fn insert(&mut self, id: u64, session: lunco_core::SessionId) {
+        self.0.insert(id, session);
+    }
+}
+
+/// Fired by [`api_command_dispatcher`] after a command's typed event has run,
+/// unless its handler recorded a `Rejected` outcome — i.e. once per command
+/// that actually executed. `params` are the wire params as the caller sent
+/// them (global ids, not local `Entity` bits), so a listener can store them and
+/// re-issue the same command in another session. `origin` is the session it
+/// was applied as; `None` = host-trusted/local.
+///
+/// The hook the command journal records from; nothing in this crate observes it.
+#[derive(Event, Debug, Clone)]
+pub struct CommandDispatched {
+    pub id: u64,
+    pub command: String,
+    pub params: serde_json::Value,
+    pub origin: Option<lunco_core::SessionId>,
+}
+
+/// The authority gate for requests that arrive with an [`ApiRequestEvent::origin`]
+/// — the SAME [`lunco_core::session::authorize`] the networked command path runs
+/// on the host (`lunco-networking`'s `apply_sync_command`): role lattice, the
+/// `#[authz_target]` ownership check, [`CommandPolicyRegistry`] overrides and the
+/// scripted `rbac.authorize` hook. A remote API caller is just another session.
+///
+/// [`CommandPolicyRegistry`]: lunco_core::session::CommandPolicyRegistry
+#[derive(bevy::ecs::system::SystemParam)]
+pub struct ApiAuthz<'w> {
+    sessions: Option<Res<'w, lunco_core::SessionRegistry>>,
+    rbac: Option<Res<'w, lunco_core::session::SessionRbac>>,
+    policies: Option<Res<'w, lunco_core::session::CommandPolicyRegistry>>,
+    paths: Option<Res<'w, lunco_core::session::ControlPathRegistry>>,
+}
+
+impl ApiAuthz<'_> {
+    /// May `origin` make `request`? A command is authorized by name against its
+    /// `#[authz_target]` gid (read from the raw wire params, like the host does);
+    /// every other request only reads, so it needs the `Observer` floor.
+    ///
+    /// Fails CLOSED when the session resources are missing: an origin only
+    /// exists because a remote transport authenticated someone, and a host that
+    /// cannot check them must not wave them through.
+    pub fn check(
+        &self,
+        origin: lunco_core::SessionId,
+        request: &ApiRequest,
+        type_reg: &TypeRegistry,
+    ) -> Result<(), String> {
+        use lunco_core::session::{authorize, AuthorityRole, ControlPathRegistry};
+        let (Some(sessions), Some(rbac), Some(policies)) =
+            (&self.sessions, &self.rbac, &self.policies)
+        else {
+            return Err(format!(
+                "session {origin} denied: session registries are unavailable"
+            ));
+        };
+        let ApiRequest::ExecuteCommand { command, params } = request else {
+            return if rbac.is_authorized(origin, AuthorityRole::Observer) {
+                Ok(())
+            } else {
+                Err(format!("session {origin} is not authorized"))
+            };
+        };
+        let target_gid = type_reg
+            .get_with_short_type_path(command)
+            .and_then(|r| authz_target_gid(params, r.type_id(), type_reg));
+        let no_blackouts = ControlPathRegistry::default();
+        let paths = self.paths.as_deref().unwrap_or(&no_blackouts);
+        authorize(sessions, rbac, policies, paths, origin, command, target_gid)
+            .map_err(|reject| reject.to_string())
+    }
+}
+
 /// Observer that processes API requests and produces responses.
 pub fn api_request_observer(
     trigger: On<ApiRequestEvent>,
@@ -84,12 +177,23 @@ pub fn api_request_observer(
     // Which commands answer later, on the correlation id. Populated by whichever crate owns
     // them (`register_deferred_command`), never by name here.
     deferred_commands: Option<Res<DeferredCommands>>,
+    authz: ApiAuthz,
 ) {
     let req = trigger.event();
     let correlation_id = req.correlation_id;
 
     let maybe_response = {
         let type_reg = type_registry.read();
+        if let Some(origin) = req.origin {
+            if let Err(message) = authz.check(origin, &req.request, &type_reg) {
+                warn!("[lunco-api] rejected remote request: {message}");
+                commands.trigger(ApiResponseEvent {
+                    response: ApiResponse::error(ApiErrorCode::Forbidden, message),
+                    correlation_id,
+                });
+                return;
+            }
+        }
         execute_request(
             &req.request,
             &mut commands,
@@ -102,6 +206,7 @@ pub fn api_request_observer(
             &q_meta,
             deferred_commands.as_deref(),
             correlation_id,
+            req.origin,
         )
     };
 
@@ -233,8 +338,13 @@ pub fn api_command_dispatcher(
             // 4. Trigger the event dynamically via commands.queue to access World
             let cmd_name = event.command.clone();
             let cmd_id = event.id;
+            let wire_params = event.params.clone();
 
             commands.queue(move |world: &mut World| {
+                // Taken first, so no early return below can strand it.
+                let origin = world
+                    .get_resource_mut::<ApiCommandOrigins>()
+                    .and_then(|mut origins| origins.0.remove(&cmd_id));
                 let registry = world.resource::<AppTypeRegistry>().clone();
                 let type_reg = registry.read();
 
@@ -284,8 +394,31 @@ pub fn api_command_dispatcher(
                     // outcome under this id. Observers run synchronously
                     // inside `trigger`, so set-before / clear-after is sound.
                     world.resource_mut::<lunco_core::ActiveCommandId>().set(Some(cmd_id));
+                    // A remote caller's command is applied AS that session, the
+                    // way the networked apply path does it: possession records
+                    // the session as owner, `RunRhai` gates the snippet's `cmd()`s
+                    // against it. Capture only runs on a client, so setting the
+                    // guard on a host suppresses no echo.
+                    if let Some(origin) = origin {
+                        world.resource_mut::<lunco_core::SyncApplyGuard>().0 = Some(origin);
+                    }
                     reflect_event.trigger(world, reflected.as_ref(), &type_reg);
+                    if origin.is_some() {
+                        world.resource_mut::<lunco_core::SyncApplyGuard>().0 = None;
+                    }
                     world.resource_mut::<lunco_core::ActiveCommandId>().set(None);
+                    let rejected = matches!(
+                        world.resource::<lunco_core::CommandResults>().get(cmd_id),
+                        Some(lunco_core::CommandOutcome::Rejected(_))
+                    );
+                    if !rejected {
+                        world.trigger(CommandDispatched {
+                            id: cmd_id,
+                            command: cmd_name.clone(),
+                            params: wire_params,
+                            origin,
+                        });
+                    }
                     // The pending correlation is a per-dispatch handoff to a
                     // deferred command handler. Clear it immediately after
                     // the reflected event so a later in-process trigger cannot
@@ -305,6 +438,9 @@ pub fn api_command_dispatcher(
             warn!("[lunco-api] {msg}; dropped");
             let cmd_id = event.id;
             commands.queue(move |world: &mut World| {
+                if let Some(mut origins) = world.get_resource_mut::<ApiCommandOrigins>() {
+                    origins.0.remove(&cmd_id);
+                }
                 world.resource_mut::<lunco_core::CommandResults>().insert(
                     cmd_id,
                     lunco_core::CommandOutcome::Rejected(lunco_core::Reject::InvalidOp(msg)),
@@ -574,7 +710,19 @@ fn execute_request(
     )>,
     deferred_commands: Option<&DeferredCommands>,
     correlation_id: u64,
+    origin: Option<lunco_core::SessionId>,
 ) -> Option<ApiResponse> {
+    // Queued ahead of the `ApiCommandEvent` trigger, so the dispatcher finds it.
+    let apply_as = |commands: &mut Commands, id: u64| {
+        if let Some(origin) = origin {
+            commands.queue(move |world: &mut World| {
+                world
+                    .get_resource_or_init::<ApiCommandOrigins>()
+                    .0
+                    .insert(id, origin);
+            });
+        }
+    };
     match request {
         ApiRequest::ExecuteCommand { command, params } => {
             // A DEFERRED command answers on this request's correlation id, later. The
@@ -601,10 +749,12 @@ fn execute_request(
                         deferred.outstanding.insert(correlation_id, deadline);
                     }
                 });
+                let command_id = id_counter.next_id();
+                apply_as(commands, command_id);
                 commands.trigger(ApiCommandEvent {
                     command: command.clone(),
                     params: params.clone(),
-                    id: id_counter.next_id(),
+                    id: command_id,
                 });
                 return None; // the handler answers on `correlation_id`
             }
@@ -670,6 +820,7 @@ fn execute_request(
 
             // Trigger as ApiCommandEvent — handled by api_command_dispatcher
             let command_id = id_counter.next_id();
+            apply_as(commands, command_id);
             commands.trigger(ApiCommandEvent {
                 command: command.clone(),
                 params: params.clone(),
@@ -918,6 +1069,10 @@ impl Plugin for ApiExecutorPlugin {
         // host that can receive a command can answer a readiness probe.
         crate::session::register_all_commands(app);
         app.init_resource::<ApiIdCounter>()
+            .init_resource::<ApiCommandOrigins>()
+            // Session-origin substrate the dispatcher applies remote commands
+            // under. Also init'd by lunco-core; idempotent.
+            .init_resource::<lunco_core::SyncApplyGuard>()
             // Command-result store + active-id scope. Also init'd by
             // lunco-core; idempotent, kept here so the API plugin is
             // self-contained (the executor reads CommandResults as a Res).
@@ -1054,6 +1209,61 @@ mod tests {
         );
     }
 
+    /// A remote caller goes through the same `authorize` the networked host
+    /// runs: an Observer may read but is refused a command whose policy needs
+    /// Operator, and a session the host never admitted may do nothing at all.
+    #[test]
+    fn remote_requests_go_through_the_authority_gate() {
+        use bevy::ecs::system::RunSystemOnce;
+        use lunco_core::session::{
+            AuthorityRole, CommandPolicy, CommandPolicyRegistry, SessionRbac, UserSession,
+        };
+        use lunco_core::{SessionId, SessionRegistry};
+
+        let (viewer, stranger) = (SessionId(41), SessionId(42));
+        let mut rbac = SessionRbac::default();
+        rbac.sessions.insert(
+            viewer.0,
+            UserSession {
+                session_id: viewer,
+                username: "viewer".into(),
+                role: AuthorityRole::Observer,
+                authenticated: true,
+                token: Some("issued".into()),
+            },
+        );
+        let mut policies = CommandPolicyRegistry::default();
+        policies.set_override(
+            "TestEcho",
+            CommandPolicy {
+                min_role: AuthorityRole::Operator,
+                ownership_gated: false,
+            },
+        );
+        let mut world = World::new();
+        world.insert_resource(rbac);
+        world.insert_resource(policies);
+        world.init_resource::<SessionRegistry>();
+
+        let reg = test_registry();
+        let echo = ApiRequest::ExecuteCommand {
+            command: "TestEcho".into(),
+            params: serde_json::json!({}),
+        };
+        let verdicts = world
+            .run_system_once(move |authz: ApiAuthz| {
+                (
+                    authz.check(viewer, &ApiRequest::ListEntities, &reg).is_ok(),
+                    authz.check(viewer, &echo, &reg).is_ok(),
+                    authz
+                        .check(stranger, &ApiRequest::ListEntities, &reg)
+                        .is_ok(),
+                )
+            })
+            .unwrap();
+        assert_eq!(verdicts, (true, false, false));
+    }
+
     #[test]
     fn internal_command_id_generation() {
         let mut counter = ApiIdCounter::default();
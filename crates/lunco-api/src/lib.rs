@@ -9,7 +9,7 @@
 //! ```text
 //! ┌────────────────────────────────────────────────────────────────┐
 //! │  Transports                                                    │
-//! │  HTTP (axum) │ ROS2 (topics) │ IPC (pipes) │ DDS │ WebSocket  │
+//! │  HTTP (axum) │ ROS2 (rosbridge) │ MAVLink (UDP) │ IPC │ WS     │
 //! │  Each handles: wire format, connection management, auth        │
 //! └────────────────────────┬───────────────────────────────────────┘
 //!                          │
@@ -68,6 +68,15 @@ pub struct LunCoApiConfig {
     /// HTTP server configuration (None = no HTTP transport).
     #[cfg(feature = "transport-http")]
     pub http_config: Option<transports::HttpServerConfig>,
+    /// Authenticated remote HTTP server (None = loopback only).
+    #[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
+    pub remote_http_config: Option<transports::remote::RemoteHttpConfig>,
+    /// rosbridge v2 WebSocket server (None = not started).
+    #[cfg(all(feature = "transport-rosbridge", not(target_arch = "wasm32")))]
+    pub rosbridge_config: Option<transports::rosbridge::RosbridgeConfig>,
+    /// MAVLink v2 UDP endpoints (None = not started).
+    #[cfg(all(feature = "transport-mavlink", not(target_arch = "wasm32")))]
+    pub mavlink_config: Option<transports::mavlink_udp::MavlinkConfig>,
 }
 
 impl LunCoApiConfig {
@@ -100,9 +109,66 @@ impl LunCoApiConfig {
             port.map(|p| transports::HttpServerConfig { port: p })
         };
 
+        // `--api-remote ADDR --api-auth FILE`: opt-in, and independent of
+        // `--api` — a host may serve both its trusted loopback port and the
+        // authenticated remote one. Checked in `build`, where errors can log.
+        #[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
+        let remote_http_config = {
+            use transports::remote::RemoteHttpConfig;
+            let args: Vec<String> = std::env::args().collect();
+            let value_of = |flag: &str| {
+                args.iter()
+                    .position(|a| a == flag)
+                    .map(|i| args.get(i + 1).cloned().unwrap_or_default())
+            };
+            value_of("--api-remote").map(|bind| RemoteHttpConfig {
+                bind,
+                auth_file: value_of("--api-auth").map(Into::into),
+            })
+        };
+
+        // `--rosbridge [PORT]`, same shape as `--api`; the default port is
+        // rosbridge_server's, so a client's stock URL works unchanged.
+        #[cfg(all(feature = "transport-rosbridge", not(target_arch = "wasm32")))]
+        let rosbridge_config = {
+            use transports::rosbridge::{RosbridgeConfig, DEFAULT_ROSBRIDGE_PORT};
+            let args: Vec<String> = std::env::args().collect();
+            args.iter().position(|a| a == "--rosbridge").map(|i| {
+                let port = args
+                    .get(i + 1)
+                    .and_then(|p| p.parse::<u16>().ok())
+                    .unwrap_or(DEFAULT_ROSBRIDGE_PORT);
+                RosbridgeConfig {
+                    port,
+                    twist: Default::default(),
+                }
+            })
+        };
+
+        // `--mavlink [GCS_PORT]`: where the ground station listens. Vehicles
+        // bind their own ports from `DEFAULT_MAVLINK_VEHICLE_PORT` up.
+        #[cfg(all(feature = "transport-mavlink", not(target_arch = "wasm32")))]
+        let mavlink_config = {
+            use transports::mavlink_udp::MavlinkConfig;
+            let args: Vec<String> = std::env::args().collect();
+            args.iter().position(|a| a == "--mavlink").map(|i| {
+                let mut config = MavlinkConfig::default();
+                if let Some(port) = args.get(i + 1).and_then(|p| p.parse::<u16>().ok()) {
+                    config.gcs_port = port;
+                }
+                config
+            })
+        };
+
         Self {
             #[cfg(feature = "transport-http")]
             http_config,
+            #[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
+            remote_http_config,
+            #[cfg(all(feature = "transport-rosbridge", not(target_arch = "wasm32")))]
+            rosbridge_config,
+            #[cfg(all(feature = "transport-mavlink", not(target_arch = "wasm32")))]
+            mavlink_config,
         }
     }
 }
@@ -190,6 +256,10 @@ impl Plugin for LunCoApiPlugin {
         add_plugin_once::<ApiVisibilityPlugin>(app, ApiVisibilityPlugin);
         add_plugin_once::<ApiDiscoveryPlugin>(app, ApiDiscoveryPlugin);
         add_plugin_once::<ApiTelemetryPlugin>(app, ApiTelemetryPlugin);
+        // Sensor producers check it before doing work nobody subscribed to.
+        app.init_resource::<transports::ros::RosTopics>();
+        // Vehicle-state producers publish only for the vessels listed here.
+        app.init_resource::<transports::mavlink::MavlinkVehicles>();
 
         // Built-in query providers — reachable over the API and via the
         // scripting `query()` verb. Domain-owned providers register the same
@@ -266,6 +336,66 @@ impl Plugin for LunCoApiPlugin {
                 transports::spawn_server(config.clone(), bridge.clone());
             }
 
+            // Remote: principals become RBAC sessions before the first request
+            // can arrive, so the executor's gate already knows their roles. A
+            // bad flag or auth file leaves remote access OFF, never open.
+            #[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
+            if let Some(config) = &self.config.remote_http_config {
+                match config.resolve() {
+                    Ok((bind, auth)) => {
+                        app.init_resource::<lunco_core::session::SessionRbac>();
+                        auth.register(
+                            &mut app
+                                .world_mut()
+                                .resource_mut::<lunco_core::session::SessionRbac>(),
+                        );
+                        transports::remote::spawn_remote_server(bind, auth, bridge.clone());
+                    }
+                    Err(e) => error!("[lunco-api] remote API not started: {e}"),
+                }
+            }
+
+            // rosbridge: telemetry and `RosPublish` fan out to WebSocket
+            // clients; their `Twist`s come back in through the same bridge.
+            #[cfg(all(feature = "transport-rosbridge", not(target_arch = "wasm32")))]
+            if let Some(config) = &self.config.rosbridge_config {
+                use transports::rosbridge::{
+                    forward_ros_publish, forward_telemetry_to_ros, spawn_rosbridge_server,
+                    RosbridgeOutbound, RosbridgeSubscriptions,
+                };
+                let (out_tx, _) = tokio::sync::broadcast::channel(1024);
+                let topics = app.world().resource::<transports::ros::RosTopics>().clone();
+                let subscriptions = RosbridgeSubscriptions::default();
+                app.insert_resource(RosbridgeOutbound(out_tx.clone()))
+                    .insert_resource(subscriptions.clone())
+                    .add_observer(forward_telemetry_to_ros)
+                    .add_observer(forward_ros_publish);
+                spawn_rosbridge_server(
+                    config.clone(),
+                    bridge.clone(),
+                    topics,
+                    subscriptions,
+                    out_tx,
+                );
+            }
+
+            // MAVLink: vehicle state out to the GCS, sticks and missions back in
+            // through the same bridge.
+            #[cfg(all(feature = "transport-mavlink", not(target_arch = "wasm32")))]
+            if let Some(config) = &self.config.mavlink_config {
+                use transports::mavlink_udp::{
+                    forward_mavlink_publish, spawn_mavlink_endpoint, MavlinkOutbound,
+                };
+                let (out_tx, out_rx) = tokio::sync::mpsc::channel(1024);
+                let vehicles = app
+                    .world()
+                    .resource::<transports::mavlink::MavlinkVehicles>()
+                    .clone();
+                app.insert_resource(MavlinkOutbound(out_tx))
+                    .add_observer(forward_mavlink_publish);
+                spawn_mavlink_endpoint(config.clone(), bridge.clone(), vehicles, out_rx);
+            }
+
             // Wasm: register the bridge behind the `window.lunco_api` JS export.
             #[cfg(target_arch = "wasm32")]
             transports::set_wasm_bridge(bridge.clone());
@@ -336,6 +466,7 @@ pub fn http_bridge_request_router(
         commands.trigger(executor::ApiRequestEvent {
             request: msg.request,
             correlation_id,
+            origin: msg.origin,
         });
     }
 }
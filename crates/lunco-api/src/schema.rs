@@ -71,6 +71,9 @@ pub enum ApiRequest {
 pub enum ApiErrorCode {
     EntityNotFound = 404,
     CommandNotFound = 400,
+    /// The calling session is authenticated but not allowed this request —
+    /// the [`lunco_core::session::authorize`] gate refused it.
+    Forbidden = 403,
     DeserializationError = 422,
     InternalError = 500,
 }
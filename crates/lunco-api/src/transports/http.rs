@@ -4,13 +4,27 @@ use crate::{
     transports::HttpBridge,
 };
 use axum::{
-    extract::{Json, State},
+    extract::{Extension, Json, State},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
 };
 
+/// The session the remote server authenticated this request as, put on the
+/// request by its auth layer. Absent on the loopback server: host-trusted.
+#[derive(Debug, Clone, Copy)]
+pub struct ApiCaller(pub lunco_core::SessionId);
+
+/// The bridge to send through: acting for the caller when there is one.
+fn acting(bridge: HttpBridge, caller: Option<Extension<ApiCaller>>) -> HttpBridge {
+    match caller {
+        Some(Extension(ApiCaller(session))) => bridge.as_session(session),
+        None => bridge,
+    }
+}
+
 pub async fn handle_api_commands(
     State(bridge): State<HttpBridge>,
+    caller: Option<Extension<ApiCaller>>,
     Json(req): Json<ApiRequestUnified>,
 ) -> Response {
     let api_req: ApiRequest = match req.try_into() {
@@ -26,7 +40,7 @@ pub async fn handle_api_commands(
                 .into_response();
         }
     };
-    execute_api_request(bridge, api_req).await
+    execute_api_request(acting(bridge, caller), api_req).await
 }
 
 /// `GET /api/health` — liveness. Answers from the transport thread without
@@ -49,10 +63,13 @@ pub async fn handle_health() -> impl IntoResponse {
 /// a scene load / program compile / participant init and no terminal runtime fault
 /// is latched. Answers `200` with the structured status either way — "not ready"
 /// is a valid state, not an error.
-pub async fn handle_ready(State(bridge): State<HttpBridge>) -> impl IntoResponse {
+pub async fn handle_ready(
+    State(bridge): State<HttpBridge>,
+    caller: Option<Extension<ApiCaller>>,
+) -> impl IntoResponse {
     // A query provider returns data inline through the same command channel.
     execute_api_request(
-        bridge,
+        acting(bridge, caller),
         ApiRequest::ExecuteCommand {
             command: "GetReadiness".to_string(),
             params: serde_json::json!({}),
@@ -66,10 +83,13 @@ pub async fn handle_ready(State(bridge): State<HttpBridge>) -> impl IntoResponse
 /// on the last propagation tick, each tagged `fault` (genuine dangling wire) vs
 /// structural/still-loading. `200` with the report either way — "some broken" is
 /// a valid state to report, not a request error.
-pub async fn handle_diagnostics(State(bridge): State<HttpBridge>) -> impl IntoResponse {
+pub async fn handle_diagnostics(
+    State(bridge): State<HttpBridge>,
+    caller: Option<Extension<ApiCaller>>,
+) -> impl IntoResponse {
     // A query provider returns data inline through the same command channel.
     execute_api_request(
-        bridge,
+        acting(bridge, caller),
         ApiRequest::ExecuteCommand {
             command: "GetBrokenConnections".to_string(),
             params: serde_json::json!({}),
@@ -81,9 +101,12 @@ pub async fn handle_diagnostics(State(bridge): State<HttpBridge>) -> impl IntoRe
 /// `GET /api/commands/schema` — the derived command schema (`DiscoverSchema`).
 /// Same data the MCP tool list is built from; a GET so it is trivially
 /// browsable and scriptable.
-pub async fn handle_schema(State(bridge): State<HttpBridge>) -> impl IntoResponse {
+pub async fn handle_schema(
+    State(bridge): State<HttpBridge>,
+    caller: Option<Extension<ApiCaller>>,
+) -> impl IntoResponse {
     // Schema discovery returns data directly through the same response channel.
-    execute_api_request(bridge, ApiRequest::DiscoverSchema).await
+    execute_api_request(acting(bridge, caller), ApiRequest::DiscoverSchema).await
 }
 
 pub async fn execute_api_request(bridge: HttpBridge, api_req: ApiRequest) -> Response {
@@ -0,0 +1,998 @@
+//! MAVLink v2 framing, the messages a ground station needs, and the table of
+//! vehicles the MAVLink transport exposes.
+//!
+//! Like [`super::ros`], this half is transport-free and compiles everywhere.
+//! The PRODUCERS of vehicle state live in a domain crate
+//! (`lunco-scene-commands` owns geodesy and waypoints, so it builds
+//! `GLOBAL_POSITION_INT` and turns a `MISSION_ITEM_INT` into a route); they
+//! wrap a [`MavMessage`] in a [`MavlinkPublish`] and trigger it. Only the
+//! native UDP endpoint ([`super::mavlink_udp`]) turns it into bytes.
+//!
+//! # Which vehicles
+//!
+//! A vessel is a MAVLink system while some session owns it in the
+//! `SessionRegistry` — a possessing user or an engaged autopilot. Nothing
+//! un-owned is announced: a GCS that could see every parked rover would also
+//! offer to task them, and an un-owned vessel has nobody driving it to obey.
+//! [`MavlinkVehicles`] hands each one a system id (1..=250) that is stable for
+//! as long as the ownership lasts.
+//!
+//! # Frames
+//!
+//! MAVLink is NED for the world (north, east, down) and FRD for a body
+//! (forward, right, down). [`NedBasis`] is the one conversion from our site
+//! ENU axes (east = +X, up = +Y, north = −Z) and body axes (forward = −Z,
+//! right = +X, up = +Y) — the same matrix serves both.
+//!
+//! # Scope
+//!
+//! The message set is exactly what a GCS needs to show and task a ground
+//! vehicle: HEARTBEAT, SYS_STATUS, ATTITUDE, GLOBAL_POSITION_INT and
+//! BATTERY_STATUS out; MANUAL_CONTROL, SET_POSITION_TARGET_{LOCAL_NED,GLOBAL_INT}
+//! and the mission-upload handshake in. Extension fields are decoded as zero
+//! and never sent. Signed (v2 signature) frames are accepted but not checked.
+
+use bevy::math::{DMat3, DQuat, DVec3, EulerRot};
+use bevy::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+
+/// v2 start-of-frame marker.
+pub const MAVLINK_STX_V2: u8 = 0xFD;
+/// `MAV_COMP_ID_AUTOPILOT1` — every vehicle speaks as its own autopilot.
+pub const MAV_COMP_ID_AUTOPILOT1: u8 = 1;
+/// Highest system id a vehicle may take; 251..=255 are GCS / companion ids.
+pub const MAX_VEHICLE_SYSTEM_ID: u8 = 250;
+
+const HEADER_LEN: usize = 10;
+const CHECKSUM_LEN: usize = 2;
+const SIGNATURE_LEN: usize = 13;
+const INCOMPAT_SIGNED: u8 = 0x01;
+
+/// `MAV_TYPE_GROUND_ROVER`.
+pub const MAV_TYPE_GROUND_ROVER: u8 = 10;
+/// `MAV_AUTOPILOT_GENERIC`.
+pub const MAV_AUTOPILOT_GENERIC: u8 = 0;
+/// `MAV_STATE_ACTIVE`.
+pub const MAV_STATE_ACTIVE: u8 = 4;
+/// `MAV_MODE_FLAG_*` bits of `HEARTBEAT.base_mode`.
+pub const MAV_MODE_FLAG_AUTO_ENABLED: u8 = 4;
+pub const MAV_MODE_FLAG_GUIDED_ENABLED: u8 = 8;
+pub const MAV_MODE_FLAG_MANUAL_INPUT_ENABLED: u8 = 64;
+pub const MAV_MODE_FLAG_SAFETY_ARMED: u8 = 128;
+/// `MAV_FRAME_*` values a target or mission item may be expressed in.
+pub const MAV_FRAME_GLOBAL: u8 = 0;
+pub const MAV_FRAME_LOCAL_NED: u8 = 1;
+pub const MAV_FRAME_GLOBAL_RELATIVE_ALT: u8 = 3;
+pub const MAV_FRAME_GLOBAL_INT: u8 = 5;
+pub const MAV_FRAME_GLOBAL_RELATIVE_ALT_INT: u8 = 6;
+pub const MAV_FRAME_GLOBAL_TERRAIN_ALT: u8 = 10;
+pub const MAV_FRAME_GLOBAL_TERRAIN_ALT_INT: u8 = 11;
+/// `MAV_CMD_NAV_WAYPOINT`.
+pub const MAV_CMD_NAV_WAYPOINT: u16 = 16;
+/// `MAV_MISSION_TYPE_MISSION` — fences and rally points are not supported.
+pub const MAV_MISSION_TYPE_MISSION: u8 = 0;
+/// `MAV_MISSION_RESULT` values used by the upload handshake.
+pub const MAV_MISSION_ACCEPTED: u8 = 0;
+pub const MAV_MISSION_ERROR: u8 = 1;
+pub const MAV_MISSION_UNSUPPORTED_FRAME: u8 = 2;
+pub const MAV_MISSION_UNSUPPORTED: u8 = 3;
+/// `POSITION_TARGET_TYPEMASK` bits that mark x / y / z as ignored.
+pub const POSITION_TARGET_IGNORE_POSITION: u16 = 0b111;
+
+/// Whether `frame` places a point on the body by latitude and longitude.
+pub fn is_global_frame(frame: u8) -> bool {
+    matches!(
+        frame,
+        MAV_FRAME_GLOBAL
+            | MAV_FRAME_GLOBAL_RELATIVE_ALT
+            | MAV_FRAME_GLOBAL_INT
+            | MAV_FRAME_GLOBAL_RELATIVE_ALT_INT
+            | MAV_FRAME_GLOBAL_TERRAIN_ALT
+            | MAV_FRAME_GLOBAL_TERRAIN_ALT_INT
+    )
+}
+
+/// CRC-16/MCRF4XX (the "X.25" accumulate of the MAVLink reference), folded
+/// over `bytes` starting from `crc`. A fresh checksum starts at `0xFFFF`.
+pub fn crc_accumulate(mut crc: u16, bytes: &[u8]) -> u16 {
+    for &b in bytes {
+        let mut tmp = b ^ (crc & 0xFF) as u8;
+        tmp ^= tmp << 4;
+        let tmp = tmp as u16;
+        crc = (crc >> 8) ^ (tmp << 8) ^ (tmp << 3) ^ (tmp >> 4);
+    }
+    crc
+}
+
+/// Site ENU / body (−Z forward) ↔ MAVLink NED / FRD.
+pub struct NedBasis;
+
+impl NedBasis {
+    /// Columns are where our +X, +Y, +Z land: east, up (= −down), south (= −north).
+    pub const TO_NED: DMat3 = DMat3::from_cols(
+        DVec3::new(0.0, 1.0, 0.0),
+        DVec3::new(0.0, 0.0, -1.0),
+        DVec3::new(-1.0, 0.0, 0.0),
+    );
+
+    /// A site-frame (or body-frame) vector in NED (or FRD).
+    pub fn vector(v: DVec3) -> DVec3 {
+        Self::TO_NED * v
+    }
+
+    /// Inverse of [`Self::vector`].
+    pub fn to_canonical(ned: DVec3) -> DVec3 {
+        Self::TO_NED.transpose() * ned
+    }
+
+    /// A body orientation relative to the site axes, as MAVLink's
+    /// `(roll, pitch, yaw)` of FRD relative to NED, radians. Yaw is clockwise
+    /// from north — a heading.
+    pub fn euler(rotation: DQuat) -> (f64, f64, f64) {
+        let r = Self::TO_NED * DMat3::from_quat(rotation) * Self::TO_NED.transpose();
+        let (yaw, pitch, roll) = DQuat::from_mat3(&r).to_euler(EulerRot::ZYX);
+        (roll, pitch, yaw)
+    }
+}
+
+/// Little-endian payload writer in MAVLink wire order.
+#[derive(Default)]
+struct Wire(Vec<u8>);
+
+impl Wire {
+    fn u8(&mut self, v: u8) -> &mut Self {
+        self.0.push(v);
+        self
+    }
+    fn i8(&mut self, v: i8) -> &mut Self {
+        self.u8(v as u8)
+    }
+    fn u16(&mut self, v: u16) -> &mut Self {
+        self.0.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+    fn i16(&mut self, v: i16) -> &mut Self {
+        self.0.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+    fn u32(&mut self, v: u32) -> &mut Self {
+        self.0.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+    fn i32(&mut self, v: i32) -> &mut Self {
+        self.0.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+    fn f32(&mut self, v: f32) -> &mut Self {
+        self.0.extend_from_slice(&v.to_le_bytes());
+        self
+    }
+}
+
+/// Payload reader. v2 senders strip trailing zero bytes, so reads past the
+/// received length yield zero — which is exactly the value that was stripped.
+struct Unwire<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Unwire<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, at: 0 }
+    }
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let mut out = [0u8; N];
+        for (i, b) in out.iter_mut().enumerate() {
+            *b = self.bytes.get(self.at + i).copied().unwrap_or(0);
+        }
+        self.at += N;
+        out
+    }
+    fn u8(&mut self) -> u8 {
+        self.take::<1>()[0]
+    }
+    fn u16(&mut self) -> u16 {
+        u16::from_le_bytes(self.take())
+    }
+    fn i16(&mut self) -> i16 {
+        i16::from_le_bytes(self.take())
+    }
+    fn u32(&mut self) -> u32 {
+        u32::from_le_bytes(self.take())
+    }
+    fn i32(&mut self) -> i32 {
+        i32::from_le_bytes(self.take())
+    }
+    fn f32(&mut self) -> f32 {
+        f32::from_le_bytes(self.take())
+    }
+}
+
+/// `HEARTBEAT` (#0).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Heartbeat {
+    pub custom_mode: u32,
+    pub mav_type: u8,
+    pub autopilot: u8,
+    pub base_mode: u8,
+    pub system_status: u8,
+}
+
+/// `SYS_STATUS` (#1). Unknown battery fields are `u16::MAX` / `-1`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SysStatus {
+    pub sensors_present: u32,
+    pub sensors_enabled: u32,
+    pub sensors_health: u32,
+    /// Main loop load, 0.1 %.
+    pub load: u16,
+    /// Millivolts.
+    pub voltage_battery: u16,
+    /// Centiamperes.
+    pub current_battery: i16,
+    /// Percent.
+    pub battery_remaining: i8,
+}
+
+/// `ATTITUDE` (#30): FRD relative to NED.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Attitude {
+    pub time_boot_ms: u32,
+    pub roll: f32,
+    pub pitch: f32,
+    pub yaw: f32,
+    pub rollspeed: f32,
+    pub pitchspeed: f32,
+    pub yawspeed: f32,
+}
+
+/// `GLOBAL_POSITION_INT` (#33).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GlobalPositionInt {
+    pub time_boot_ms: u32,
+    /// Degrees × 1e7.
+    pub lat: i32,
+    pub lon: i32,
+    /// Millimetres above the body's mean sphere (MAVLink's "MSL").
+    pub alt: i32,
+    /// Millimetres above the site origin (MAVLink's "home").
+    pub relative_alt: i32,
+    /// NED ground speed, cm/s.
+    pub vx: i16,
+    pub vy: i16,
+    pub vz: i16,
+    /// Centidegrees, 0..35999; `u16::MAX` if unknown.
+    pub hdg: u16,
+}
+
+/// `MISSION_REQUEST_LIST` (#43).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MissionRequestList {
+    pub target_system: u8,
+    pub target_component: u8,
+    pub mission_type: u8,
+}
+
+/// `MISSION_COUNT` (#44).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MissionCount {
+    pub count: u16,
+    pub target_system: u8,
+    pub target_component: u8,
+    pub mission_type: u8,
+}
+
+/// `MISSION_ACK` (#47).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MissionAck {
+    pub target_system: u8,
+    pub target_component: u8,
+    pub result: u8,
+    pub mission_type: u8,
+}
+
+/// `MISSION_REQUEST_INT` (#51).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MissionRequestInt {
+    pub seq: u16,
+    pub target_system: u8,
+    pub target_component: u8,
+    pub mission_type: u8,
+}
+
+/// `MANUAL_CONTROL` (#69). Axes are −1000..1000; `i16::MAX` marks an axis
+/// the sender does not drive.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ManualControl {
+    pub x: i16,
+    pub y: i16,
+    pub z: i16,
+    pub r: i16,
+    pub buttons: u16,
+    pub target: u8,
+}
+
+/// `MISSION_ITEM_INT` (#73).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MissionItemInt {
+    pub param1: f32,
+    pub param2: f32,
+    pub param3: f32,
+    pub param4: f32,
+    /// Degrees × 1e7 in a global frame, metres × 1e4 in a local one.
+    pub x: i32,
+    pub y: i32,
+    pub z: f32,
+    pub seq: u16,
+    pub command: u16,
+    pub target_system: u8,
+    pub target_component: u8,
+    pub frame: u8,
+    pub current: u8,
+    pub autocontinue: u8,
+    pub mission_type: u8,
+}
+
+/// `SET_POSITION_TARGET_LOCAL_NED` (#84). Velocity, acceleration and yaw
+/// fields are carried for the codec and ignored by the transport.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SetPositionTargetLocalNed {
+    pub time_boot_ms: u32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub vz: f32,
+    pub afx: f32,
+    pub afy: f32,
+    pub afz: f32,
+    pub yaw: f32,
+    pub yaw_rate: f32,
+    pub type_mask: u16,
+    pub target_system: u8,
+    pub target_component: u8,
+    pub coordinate_frame: u8,
+}
+
+/// `SET_POSITION_TARGET_GLOBAL_INT` (#86).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SetPositionTargetGlobalInt {
+    pub time_boot_ms: u32,
+    /// Degrees × 1e7.
+    pub lat_int: i32,
+    pub lon_int: i32,
+    /// Metres.
+    pub alt: f32,
+    pub vx: f32,
+    pub vy: f32,
+    pub vz: f32,
+    pub afx: f32,
+    pub afy: f32,
+    pub afz: f32,
+    pub yaw: f32,
+    pub yaw_rate: f32,
+    pub type_mask: u16,
+    pub target_system: u8,
+    pub target_component: u8,
+    pub coordinate_frame: u8,
+}
+
+/// `BATTERY_STATUS` (#147). Unknown cells are `u16::MAX`, unknown scalars −1.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BatteryStatus {
+    /// mAh.
+    pub current_consumed: i32,
+    /// hJ.
+    pub energy_consumed: i32,
+    /// cdegC; `i16::MAX` if unknown.
+    pub temperature: i16,
+    /// mV per cell.
+    pub voltages: [u16; 10],
+    /// cA.
+    pub current_battery: i16,
+    pub id: u8,
+    pub battery_function: u8,
+    pub battery_type: u8,
+    /// Percent.
+    pub battery_remaining: i8,
+}
+
+/// Every message this endpoint sends or understands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MavMessage {
+    Heartbeat(Heartbeat),
+    SysStatus(SysStatus),
+    Attitude(Attitude),
+    GlobalPositionInt(GlobalPositionInt),
+    MissionRequestList(MissionRequestList),
+    MissionCount(MissionCount),
+    MissionAck(MissionAck),
+    MissionRequestInt(MissionRequestInt),
+    ManualControl(ManualControl),
+    MissionItemInt(MissionItemInt),
+    SetPositionTargetLocalNed(SetPositionTargetLocalNed),
+    SetPositionTargetGlobalInt(SetPositionTargetGlobalInt),
+    BatteryStatus(BatteryStatus),
+}
+
+/// `(message id, CRC_EXTRA)` for every id in [`MavMessage`]. The extra byte
+/// is a hash of the message definition; a frame whose id is not listed here
+/// cannot be validated and is dropped.
+const MESSAGE_TABLE: [(u32, u8); 13] = [
+    (0, 50),
+    (1, 124),
+    (30, 39),
+    (33, 104),
+    (43, 132),
+    (44, 221),
+    (47, 153),
+    (51, 196),
+    (69, 243),
+    (73, 38),
+    (84, 143),
+    (86, 5),
+    (147, 154),
+];
+
+fn crc_extra(msgid: u32) -> Option<u8> {
+    MESSAGE_TABLE
+        .iter()
+        .find(|(id, _)| *id == msgid)
+        .map(|(_, extra)| *extra)
+}
+
+impl MavMessage {
+    pub fn id(&self) -> u32 {
+        match self {
+            Self::Heartbeat(_) => 0,
+            Self::SysStatus(_) => 1,
+            Self::Attitude(_) => 30,
+            Self::GlobalPositionInt(_) => 33,
+            Self::MissionRequestList(_) => 43,
+            Self::MissionCount(_) => 44,
+            Self::MissionAck(_) => 47,
+            Self::MissionRequestInt(_) => 51,
+            Self::ManualControl(_) => 69,
+            Self::MissionItemInt(_) => 73,
+            Self::SetPositionTargetLocalNed(_) => 84,
+            Self::SetPositionTargetGlobalInt(_) => 86,
+            Self::BatteryStatus(_) => 147,
+        }
+    }
+
+    /// The full-length payload, fields in wire order (largest type first).
+    fn payload(&self) -> Vec<u8> {
+        let mut w = Wire::default();
+        match *self {
+            Self::Heartbeat(m) => {
+                w.u32(m.custom_mode)
+                    .u8(m.mav_type)
+                    .u8(m.autopilot)
+                    .u8(m.base_mode)
+                    .u8(m.system_status)
+                    .u8(3);
+            }
+            Self::SysStatus(m) => {
+                w.u32(m.sensors_present)
+                    .u32(m.sensors_enabled)
+                    .u32(m.sensors_health)
+                    .u16(m.load)
+                    .u16(m.voltage_battery)
+                    .i16(m.current_battery)
+                    // drop_rate_comm, errors_comm, errors_count1..4
+                    .u16(0)
+                    .u16(0)
+                    .u16(0)
+                    .u16(0)
+                    .u16(0)
+                    .u16(0)
+                    .i8(m.battery_remaining);
+            }
+            Self::Attitude(m) => {
+                w.u32(m.time_boot_ms)
+                    .f32(m.roll)
+                    .f32(m.pitch)
+                    .f32(m.yaw)
+                    .f32(m.rollspeed)
+                    .f32(m.pitchspeed)
+                    .f32(m.yawspeed);
+            }
+            Self::GlobalPositionInt(m) => {
+                w.u32(m.time_boot_ms)
+                    .i32(m.lat)
+                    .i32(m.lon)
+                    .i32(m.alt)
+                    .i32(m.relative_alt)
+                    .i16(m.vx)
+                    .i16(m.vy)
+                    .i16(m.vz)
+                    .u16(m.hdg);
+            }
+            Self::MissionRequestList(m) => {
+                w.u8(m.target_system)
+                    .u8(m.target_component)
+                    .u8(m.mission_type);
+            }
+            Self::MissionCount(m) => {
+                w.u16(m.count)
+                    .u8(m.target_system)
+                    .u8(m.target_component)
+                    .u8(m.mission_type);
+            }
+            Self::MissionAck(m) => {
+                w.u8(m.target_system)
+                    .u8(m.target_component)
+                    .u8(m.result)
+                    .u8(m.mission_type);
+            }
+            Self::MissionRequestInt(m) => {
+                w.u16(m.seq)
+                    .u8(m.target_system)
+                    .u8(m.target_component)
+                    .u8(m.mission_type);
+            }
+            Self::ManualControl(m) => {
+                w.i16(m.x)
+                    .i16(m.y)
+                    .i16(m.z)
+                    .i16(m.r)
+                    .u16(m.buttons)
+                    .u8(m.target);
+            }
+            Self::MissionItemInt(m) => {
+                w.f32(m.param1)
+                    .f32(m.param2)
+                    .f32(m.param3)
+                    .f32(m.param4)
+                    .i32(m.x)
+                    .i32(m.y)
+                    .f32(m.z)
+                    .u16(m.seq)
+                    .u16(m.command)
+                    .u8(m.target_system)
+                    .u8(m.target_component)
+                    .u8(m.frame)
+                    .u8(m.current)
+                    .u8(m.autocontinue)
+                    .u8(m.mission_type);
+            }
+            Self::SetPositionTargetLocalNed(m) => {
+                w.u32(m.time_boot_ms)
+                    .f32(m.x)
+                    .f32(m.y)
+                    .f32(m.z)
+                    .f32(m.vx)
+                    .f32(m.vy)
+                    .f32(m.vz)
+                    .f32(m.afx)
+                    .f32(m.afy)
+                    .f32(m.afz)
+                    .f32(m.yaw)
+                    .f32(m.yaw_rate)
+                    .u16(m.type_mask)
+                    .u8(m.target_system)
+                    .u8(m.target_component)
+                    .u8(m.coordinate_frame);
+            }
+            Self::SetPositionTargetGlobalInt(m) => {
+                w.u32(m.time_boot_ms)
+                    .i32(m.lat_int)
+                    .i32(m.lon_int)
+                    .f32(m.alt)
+                    .f32(m.vx)
+                    .f32(m.vy)
+                    .f32(m.vz)
+                    .f32(m.afx)
+                    .f32(m.afy)
+                    .f32(m.afz)
+                    .f32(m.yaw)
+                    .f32(m.yaw_rate)
+                    .u16(m.type_mask)
+                    .u8(m.target_system)
+                    .u8(m.target_component)
+                    .u8(m.coordinate_frame);
+            }
+            Self::BatteryStatus(m) => {
+                w.i32(m.current_consumed)
+                    .i32(m.energy_consumed)
+                    .i16(m.temperature);
+                for v in m.voltages {
+                    w.u16(v);
+                }
+                w.i16(m.current_battery)
+                    .u8(m.id)
+                    .u8(m.battery_function)
+                    .u8(m.battery_type)
+                    .i8(m.battery_remaining);
+            }
+        }
+        w.0
+    }
+
+    fn parse(msgid: u32, payload: &[u8]) -> Option<Self> {
+        let mut r = Unwire::new(payload);
+        Some(match msgid {
+            0 => {
+                let m = Heartbeat {
+                    custom_mode: r.u32(),
+                    mav_type: r.u8(),
+                    autopilot: r.u8(),
+                    base_mode: r.u8(),
+                    system_status: r.u8(),
+                };
+                Self::Heartbeat(m)
+            }
+            1 => {
+                let (present, enabled, health, load, voltage, current) =
+                    (r.u32(), r.u32(), r.u32(), r.u16(), r.u16(), r.i16());
+                for _ in 0..6 {
+                    r.u16();
+                }
+                Self::SysStatus(SysStatus {
+                    sensors_present: present,
+                    sensors_enabled: enabled,
+                    sensors_health: health,
+                    load,
+                    voltage_battery: voltage,
+                    current_battery: current,
+                    battery_remaining: r.u8() as i8,
+                })
+            }
+            30 => Self::Attitude(Attitude {
+                time_boot_ms: r.u32(),
+                roll: r.f32(),
+                pitch: r.f32(),
+                yaw: r.f32(),
+                rollspeed: r.f32(),
+                pitchspeed: r.f32(),
+                yawspeed: r.f32(),
+            }),
+            33 => Self::GlobalPositionInt(GlobalPositionInt {
+                time_boot_ms: r.u32(),
+                lat: r.i32(),
+                lon: r.i32(),
+                alt: r.i32(),
+                relative_alt: r.i32(),
+                vx: r.i16(),
+                vy: r.i16(),
+                vz: r.i16(),
+                hdg: r.u16(),
+            }),
+            43 => Self::MissionRequestList(MissionRequestList {
+                target_system: r.u8(),
+                target_component: r.u8(),
+                mission_type: r.u8(),
+            }),
+            44 => Self::MissionCount(MissionCount {
+                count: r.u16(),
+                target_system: r.u8(),
+                target_component: r.u8(),
+                mission_type: r.u8(),
+            }),
+            47 => Self::MissionAck(MissionAck {
+                target_system: r.u8(),
+                target_component: r.u8(),
+                result: r.u8(),
+                mission_type: r.u8(),
+            }),
+            51 => Self::MissionRequestInt(MissionRequestInt {
+                seq: r.u16(),
+                target_system: r.u8(),
+                target_component: r.u8(),
+                mission_type: r.u8(),
+            }),
+            69 => Self::ManualControl(ManualControl {
+                x: r.i16(),
+                y: r.i16(),
+                z: r.i16(),
+                r: r.i16(),
+                buttons: r.u16(),
+                target: r.u8(),
+            }),
+            73 => Self::MissionItemInt(MissionItemInt {
+                param1: r.f32(),
+                param2: r.f32(),
+                param3: r.f32(),
+                param4: r.f32(),
+                x: r.i32(),
+                y: r.i32(),
+                z: r.f32(),
+                seq: r.u16(),
+                command: r.u16(),
+                target_system: r.u8(),
+                target_component: r.u8(),
+                frame: r.u8(),
+                current: r.u8(),
+                autocontinue: r.u8(),
+                mission_type: r.u8(),
+            }),
+            84 => Self::SetPositionTargetLocalNed(SetPositionTargetLocalNed {
+                time_boot_ms: r.u32(),
+                x: r.f32(),
+                y: r.f32(),
+                z: r.f32(),
+                vx: r.f32(),
+                vy: r.f32(),
+                vz: r.f32(),
+                afx: r.f32(),
+                afy: r.f32(),
+                afz: r.f32(),
+                yaw: r.f32(),
+                yaw_rate: r.f32(),
+                type_mask: r.u16(),
+                target_system: r.u8(),
+                target_component: r.u8(),
+                coordinate_frame: r.u8(),
+            }),
+            86 => Self::SetPositionTargetGlobalInt(SetPositionTargetGlobalInt {
+                time_boot_ms: r.u32(),
+                lat_int: r.i32(),
+                lon_int: r.i32(),
+                alt: r.f32(),
+                vx: r.f32(),
+                vy: r.f32(),
+                vz: r.f32(),
+                afx: r.f32(),
+                afy: r.f32(),
+                afz: r.f32(),
+                yaw: r.f32(),
+                yaw_rate: r.f32(),
+                type_mask: r.u16(),
+                target_system: r.u8(),
+                target_component: r.u8(),
+                coordinate_frame: r.u8(),
+            }),
+            147 => {
+                let (consumed, energy, temperature) = (r.i32(), r.i32(), r.i16());
+                let mut voltages = [0u16; 10];
+                for v in &mut voltages {
+                    *v = r.u16();
+                }
+                Self::BatteryStatus(BatteryStatus {
+                    current_consumed: consumed,
+                    energy_consumed: energy,
+                    temperature,
+                    voltages,
+                    current_battery: r.i16(),
+                    id: r.u8(),
+                    battery_function: r.u8(),
+                    battery_type: r.u8(),
+                    battery_remaining: r.u8() as i8,
+                })
+            }
+            _ => return None,
+        })
+    }
+}
+
+/// Who sent a decoded frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MavHeader {
+    pub seq: u8,
+    pub system_id: u8,
+    pub component_id: u8,
+}
+
+/// One v2 frame carrying `msg`, trailing payload zeros stripped as the
+/// protocol requires (a payload never shrinks below one byte).
+pub fn encode_frame(header: MavHeader, msg: &MavMessage) -> Vec<u8> {
+    let mut payload = msg.payload();
+    while payload.len() > 1 && payload.last() == Some(&0) {
+        payload.pop();
+    }
+    let id = msg.id();
+    let mut frame = Vec::with_capacity(HEADER_LEN + payload.len() + CHECKSUM_LEN);
+    frame.extend_from_slice(&[
+        MAVLINK_STX_V2,
+        payload.len() as u8,
+        0, // incompat_flags
+        0, // compat_flags
+        header.seq,
+        header.system_id,
+        header.component_id,
+    ]);
+    frame.extend_from_slice(&id.to_le_bytes()[..3]);
+    frame.extend_from_slice(&payload);
+    let extra = crc_extra(id).unwrap_or_default();
+    let crc = crc_accumulate(crc_accumulate(0xFFFF, &frame[1..]), &[extra]);
+    frame.extend_from_slice(&crc.to_le_bytes());
+    frame
+}
+
+/// Every valid frame in a datagram, in order. Bytes before a start marker,
+/// frames with a bad checksum and messages outside [`MavMessage`] are
+/// skipped — a GCS streams plenty this endpoint has no use for.
+pub fn decode_frames(mut bytes: &[u8]) -> Vec<(MavHeader, MavMessage)> {
+    let mut out = Vec::new();
+    while let Some(start) = bytes.iter().position(|&b| b == MAVLINK_STX_V2) {
+        bytes = &bytes[start..];
+        if bytes.len() < HEADER_LEN + CHECKSUM_LEN {
+            break;
+        }
+        let len = bytes[1] as usize;
+        let signed = bytes[2] & INCOMPAT_SIGNED != 0;
+        let end = HEADER_LEN + len + CHECKSUM_LEN;
+        let total = end + if signed { SIGNATURE_LEN } else { 0 };
+        if bytes.len() < total {
+            break;
+        }
+        let msgid = u32::from_le_bytes([bytes[7], bytes[8], bytes[9], 0]);
+        let header = MavHeader {
+            seq: bytes[4],
+            system_id: bytes[5],
+            component_id: bytes[6],
+        };
+        let payload = &bytes[HEADER_LEN..HEADER_LEN + len];
+        let received = u16::from_le_bytes([bytes[end - 2], bytes[end - 1]]);
+        let valid = crc_extra(msgid).is_some_and(|extra| {
+            crc_accumulate(crc_accumulate(0xFFFF, &bytes[1..end - 2]), &[extra]) == received
+        });
+        if valid {
+            if let Some(msg) = MavMessage::parse(msgid, payload) {
+                out.push((header, msg));
+            }
+            bytes = &bytes[total..];
+        } else {
+            // Not a frame after all (or a corrupt one): resync on the next marker.
+            bytes = &bytes[1..];
+        }
+    }
+    out
+}
+
+/// A message for the MAVLink transport to send as the vehicle with
+/// `GlobalEntityId` `gid`. Trigger it from any crate; unobserved when no
+/// endpoint runs, and dropped by it when `gid` is not in [`MavlinkVehicles`].
+#[derive(Event, Debug, Clone)]
+pub struct MavlinkPublish {
+    pub gid: u64,
+    pub message: MavMessage,
+}
+
+/// Vehicle gid → MAVLink system id.
+#[derive(Debug, Default)]
+pub struct MavlinkVehicleTable {
+    systems: BTreeMap<u64, u8>,
+}
+
+/// Shared between the ECS (which decides which vessels are owned) and the
+/// UDP endpoint (which opens one socket per system), the same way
+/// [`super::ros::RosTopics`] is.
+#[derive(Resource, Clone, Default)]
+pub struct MavlinkVehicles(pub Arc<Mutex<MavlinkVehicleTable>>);
+
+impl MavlinkVehicles {
+    fn table(&self) -> std::sync::MutexGuard<'_, MavlinkVehicleTable> {
+        // Same reasoning as `RosTopics::table`: the map is valid after a panic.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Make the table exactly `owned`: vessels that stay keep their system id,
+    /// new ones take the lowest free id. Past [`MAX_VEHICLE_SYSTEM_ID`] a vessel
+    /// is left out rather than sharing an id.
+    pub fn sync(&self, owned: impl IntoIterator<Item = u64>) {
+        let owned: std::collections::BTreeSet<u64> = owned.into_iter().collect();
+        let mut table = self.table();
+        table.systems.retain(|gid, _| owned.contains(gid));
+        for gid in owned {
+            if table.systems.contains_key(&gid) {
+                continue;
+            }
+            let taken: std::collections::HashSet<u8> = table.systems.values().copied().collect();
+            if let Some(id) = (1..=MAX_VEHICLE_SYSTEM_ID).find(|id| !taken.contains(id)) {
+                table.systems.insert(gid, id);
+            }
+        }
+    }
+
+    pub fn system_of(&self, gid: u64) -> Option<u8> {
+        self.table().systems.get(&gid).copied()
+    }
+
+    /// `(gid, system id)` for every exposed vehicle, sorted by gid.
+    pub fn vehicles(&self) -> Vec<(u64, u8)> {
+        self.table().systems.iter().map(|(g, s)| (*g, *s)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The published check value of CRC-16/MCRF4XX.
+    #[test]
+    fn checksum_matches_the_reference_check_value() {
+        assert_eq!(crc_accumulate(0xFFFF, b"123456789"), 0x6F91);
+    }
+
+    #[test]
+    fn frames_round_trip_with_stripped_trailing_zeros() {
+        let header = MavHeader {
+            seq: 7,
+            system_id: 3,
+            component_id: MAV_COMP_ID_AUTOPILOT1,
+        };
+        let item = MavMessage::MissionItemInt(MissionItemInt {
+            param1: 0.0,
+            param2: 2.0,
+            param3: 0.0,
+            param4: 0.0,
+            x: -434_391_234,
+            y: 1_776_543_210,
+            z: 0.0,
+            seq: 4,
+            command: MAV_CMD_NAV_WAYPOINT,
+            target_system: 3,
+            target_component: 1,
+            frame: MAV_FRAME_GLOBAL_RELATIVE_ALT_INT,
+            current: 0,
+            autocontinue: 1,
+            mission_type: 0,
+        });
+        let beat = MavMessage::Heartbeat(Heartbeat {
+            custom_mode: 0,
+            mav_type: MAV_TYPE_GROUND_ROVER,
+            autopilot: MAV_AUTOPILOT_GENERIC,
+            base_mode: MAV_MODE_FLAG_SAFETY_ARMED,
+            system_status: MAV_STATE_ACTIVE,
+        });
+        let mut datagram = vec![0x00, 0x42]; // line noise before the first marker
+        let first = encode_frame(header, &item);
+        // `mission_type` (0) is the last byte and must not be on the wire.
+        assert_eq!(first[1] as usize, 37);
+        datagram.extend(first);
+        datagram.extend(encode_frame(header, &beat));
+        let decoded = decode_frames(&datagram);
+        assert_eq!(decoded, vec![(header, item), (header, beat)]);
+    }
+
+    #[test]
+    fn a_corrupt_frame_is_dropped_and_the_next_still_decodes() {
+        let header = MavHeader {
+            seq: 0,
+            system_id: 255,
+            component_id: 190,
+        };
+        let stick = MavMessage::ManualControl(ManualControl {
+            x: 500,
+            y: 0,
+            z: 0,
+            r: -250,
+            buttons: 0,
+            target: 1,
+        });
+        let mut bad = encode_frame(header, &stick);
+        let last = bad.len() - 1;
+        bad[last] ^= 0xFF;
+        bad.extend(encode_frame(header, &stick));
+        assert_eq!(decode_frames(&bad), vec![(header, stick)]);
+    }
+
+    /// Facing north is heading 0; facing east (our +X) is +90°, clockwise
+    /// as seen from above — and both are level.
+    #[test]
+    fn headings_are_clockwise_from_north() {
+        let (roll, pitch, yaw) = NedBasis::euler(DQuat::IDENTITY);
+        assert!(roll.abs() < 1e-12 && pitch.abs() < 1e-12 && yaw.abs() < 1e-12);
+        let east = DQuat::from_rotation_y(-std::f64::consts::FRAC_PI_2);
+        let (roll, pitch, yaw) = NedBasis::euler(east);
+        assert!(roll.abs() < 1e-9 && pitch.abs() < 1e-9);
+        assert!((yaw - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        assert!(NedBasis::vector(DVec3::NEG_Z).abs_diff_eq(DVec3::X, 1e-12));
+        assert!(NedBasis::vector(DVec3::X).abs_diff_eq(DVec3::Y, 1e-12));
+        assert!(NedBasis::vector(DVec3::Y).abs_diff_eq(DVec3::NEG_Z, 1e-12));
+    }
+
+    #[test]
+    fn system_ids_are_stable_and_reused() {
+        let vehicles = MavlinkVehicles::default();
+        vehicles.sync([10, 20]);
+        assert_eq!(vehicles.vehicles(), vec![(10, 1), (20, 2)]);
+        vehicles.sync([20, 30]);
+        assert_eq!(vehicles.system_of(20), Some(2));
+        assert_eq!(vehicles.system_of(30), Some(1));
+        assert_eq!(vehicles.system_of(10), None);
+    }
+}
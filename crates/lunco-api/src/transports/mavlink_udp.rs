@@ -0,0 +1,556 @@
+//! MAVLink v2 over UDP — one endpoint per owned vehicle.
+//!
+//! Every vessel in [`MavlinkVehicles`] gets its own socket on
+//! `127.0.0.1:<vehicle_port + system_id − 1>` and streams to the ground
+//! station at `127.0.0.1:<gcs_port>` (14550 by default — where QGroundControl
+//! listens and auto-connects), exactly the layout of a PX4 SITL swarm. The GCS
+//! answers to the address a vehicle sent from, so replies and commands come
+//! back on the right socket without any routing on our side.
+//!
+//! | in | becomes |
+//! |---|---|
+//! | `MANUAL_CONTROL` | `SetPorts` — `x` → `throttle`, `r` → `steer` (both positive forward / right) |
+//! | `SET_POSITION_TARGET_LOCAL_NED` | `AddRuntimeWaypoint` at the site-frame point |
+//! | `SET_POSITION_TARGET_GLOBAL_INT` | `AddGeodeticWaypoint` |
+//! | `MISSION_COUNT` → `MISSION_ITEM_INT`… | `ClearRuntimeWaypoints`, one waypoint per `NAV_WAYPOINT` item, then `MISSION_ACK` — an upload replaces the route, `count = 0` clears it |
+//! | `MISSION_REQUEST_LIST` | an empty `MISSION_COUNT`: routes live in the autopilot, not here |
+//!
+//! Every one of those is an `ExecuteCommand` through the same [`HttpBridge`]
+//! funnel as HTTP and rosbridge, so a stick or a mission from a GCS is the
+//! same input a keyboard or a script would give. Outbound, whatever a domain
+//! crate triggers as a [`MavlinkPublish`] is framed and sent; this file never
+//! reads the world itself.
+//!
+//! Loopback-only, like the HTTP server, and for the same (deferred) reason:
+//! there is no auth on these sockets.
+
+use super::mavlink::{
+    decode_frames, encode_frame, is_global_frame, MavHeader, MavMessage, MavlinkPublish,
+    MavlinkVehicles, MissionAck, MissionCount, MissionItemInt, MissionRequestInt, NedBasis,
+    MAV_CMD_NAV_WAYPOINT, MAV_COMP_ID_AUTOPILOT1, MAV_FRAME_LOCAL_NED, MAV_MISSION_ACCEPTED,
+    MAV_MISSION_ERROR, MAV_MISSION_TYPE_MISSION, MAV_MISSION_UNSUPPORTED,
+    MAV_MISSION_UNSUPPORTED_FRAME, POSITION_TARGET_IGNORE_POSITION,
+};
+use super::HttpBridge;
+use crate::schema::{ApiRequest, ApiResponse};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet};
+use std::net::SocketAddr;
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+
+/// Where QGroundControl (and most GCSes) listen by default.
+pub const DEFAULT_MAVLINK_GCS_PORT: u16 = 14550;
+/// First vehicle's local port — PX4 SITL's, so GCS presets for a SITL swarm
+/// work unchanged.
+pub const DEFAULT_MAVLINK_VEHICLE_PORT: u16 = 18570;
+
+/// Pause after a failed `recv_from` before reading again, so a socket error
+/// that persists cannot spin the endpoint thread.
+const RECV_ERROR_BACKOFF: Duration = Duration::from_millis(250);
+
+#[derive(Debug, Clone)]
+pub struct MavlinkConfig {
+    /// The ground station's port on loopback.
+    pub gcs_port: u16,
+    /// System `n` binds `vehicle_port + n − 1`.
+    pub vehicle_port: u16,
+}
+
+impl Default for MavlinkConfig {
+    fn default() -> Self {
+        Self {
+            gcs_port: DEFAULT_MAVLINK_GCS_PORT,
+            vehicle_port: DEFAULT_MAVLINK_VEHICLE_PORT,
+        }
+    }
+}
+
+/// ECS → endpoint thread. Bounded: a stalled endpoint sheds state messages
+/// rather than growing without limit, and every one of them is re-sent on the
+/// producer's next period anyway.
+#[derive(Resource, Clone)]
+pub struct MavlinkOutbound(pub mpsc::Sender<MavlinkPublish>);
+
+/// Domain-published vehicle state ([`MavlinkPublish`]) → the endpoint thread.
+pub fn forward_mavlink_publish(trigger: On<MavlinkPublish>, out: Res<MavlinkOutbound>) {
+    // Ignored by design: `Full` drops one stale sample, `Closed` means the
+    // endpoint already logged why it stopped.
+    let _ = out.0.try_send(trigger.event().clone());
+}
+
+/// One vehicle's socket.
+struct Link {
+    socket: UdpSocket,
+    gid: u64,
+    system_id: u8,
+    seq: AtomicU8,
+    /// Where the GCS last spoke from; starts at the configured GCS port.
+    peer: Mutex<SocketAddr>,
+}
+
+impl Link {
+    async fn send(&self, message: &MavMessage) {
+        let header = MavHeader {
+            seq: self.seq.fetch_add(1, Ordering::Relaxed),
+            system_id: self.system_id,
+            component_id: MAV_COMP_ID_AUTOPILOT1,
+        };
+        let peer = *self.peer.lock().unwrap_or_else(|e| e.into_inner());
+        // Ignored by design: UDP to a GCS that is not running is the normal
+        // state of a sim nobody is watching.
+        let _ = self
+            .socket
+            .send_to(&encode_frame(header, message), peer)
+            .await;
+    }
+}
+
+/// Native endpoint thread — the same shape, and the same reasoning, as
+/// [`super::spawn_server`].
+#[allow(clippy::disallowed_methods)]
+pub fn spawn_mavlink_endpoint(
+    config: MavlinkConfig,
+    bridge: HttpBridge,
+    vehicles: MavlinkVehicles,
+    outbound: mpsc::Receiver<MavlinkPublish>,
+) {
+    std::thread::spawn(move || {
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                bevy::log::error!("[lunco-api] failed to start MAVLink runtime: {e}");
+                return;
+            }
+        };
+        bevy::log::info!(
+            "[lunco-api] MAVLink v2 vehicles stream to udp://127.0.0.1:{} from port {} up",
+            config.gcs_port,
+            config.vehicle_port
+        );
+        rt.block_on(run_endpoint(config, bridge, vehicles, outbound));
+    });
+}
+
+async fn run_endpoint(
+    config: MavlinkConfig,
+    bridge: HttpBridge,
+    vehicles: MavlinkVehicles,
+    mut outbound: mpsc::Receiver<MavlinkPublish>,
+) {
+    let gcs = SocketAddr::from(([127, 0, 0, 1], config.gcs_port));
+    let mut links: HashMap<u64, (Arc<Link>, tokio::task::JoinHandle<()>)> = HashMap::new();
+    // Vehicles whose port could not be bound: said once, not every frame.
+    let mut refused: HashSet<u64> = HashSet::new();
+    while let Some(publish) = outbound.recv().await {
+        // A vessel that lost its owner — or came back under a new id — closes
+        // its socket, so the GCS sees the link drop rather than a frozen vehicle.
+        links.retain(|gid, (link, task)| {
+            let keep = vehicles.system_of(*gid) == Some(link.system_id);
+            if !keep {
+                task.abort();
+            }
+            keep
+        });
+        refused.retain(|gid| vehicles.system_of(*gid).is_some());
+        let Some(system_id) = vehicles.system_of(publish.gid) else {
+            continue;
+        };
+        if !links.contains_key(&publish.gid) {
+            if refused.contains(&publish.gid) {
+                continue;
+            }
+            let port = config.vehicle_port.saturating_add(system_id as u16 - 1);
+            let socket = match UdpSocket::bind(("127.0.0.1", port)).await {
+                Ok(s) => s,
+                Err(e) => {
+                    bevy::log::error!(
+                        "[lunco-api] MAVLink system {system_id} failed to bind 127.0.0.1:{port}: \
+                         {e} (port already in use?) — that vehicle is not visible to the GCS"
+                    );
+                    refused.insert(publish.gid);
+                    continue;
+                }
+            };
+            let link = Arc::new(Link {
+                socket,
+                gid: publish.gid,
+                system_id,
+                seq: AtomicU8::new(0),
+                peer: Mutex::new(gcs),
+            });
+            let task = tokio::spawn(serve_link(link.clone(), bridge.clone()));
+            links.insert(publish.gid, (link, task));
+        }
+        if let Some((link, _)) = links.get(&publish.gid) {
+            link.send(&publish.message).await;
+        }
+    }
+}
+
+/// An upload in progress: the GCS announced `count` items and we have
+/// applied the ones before `next`.
+struct Upload {
+    count: u16,
+    next: u16,
+    /// The first item that failed, as a `MAV_MISSION_RESULT`; the rest still
+    /// go in so one bad item does not strand a route half-built.
+    result: u8,
+    gcs: (u8, u8),
+}
+
+async fn serve_link(link: Arc<Link>, bridge: HttpBridge) {
+    let mut buf = [0u8; 2048];
+    let mut upload: Option<Upload> = None;
+    // Logged on the first failure of a run, not on every retry.
+    let mut recv_failing = false;
+    loop {
+        let (len, from) = match link.socket.recv_from(&mut buf).await {
+            Ok(r) => {
+                recv_failing = false;
+                r
+            }
+            // Loopback ICMP "port unreachable" from a GCS that is not up yet
+            // surfaces here on some platforms; it is not a reason to stop, but
+            // it can repeat on every read, so back off before the next one.
+            Err(e) => {
+                if !recv_failing {
+                    bevy::log::warn!(
+                        "[lunco-api] MAVLink system {} receive failed: {e} — retrying",
+                        link.system_id
+                    );
+                    recv_failing = true;
+                }
+                tokio::time::sleep(RECV_ERROR_BACKOFF).await;
+                continue;
+            }
+        };
+        *link.peer.lock().unwrap_or_else(|e| e.into_inner()) = from;
+        for (header, message) in decode_frames(&buf[..len]) {
+            let gcs = (header.system_id, header.component_id);
+            match message {
+                MavMessage::ManualControl(m) if addressed(&link, m.target) => {
+                    // `i16::MAX` = "this axis is not driven".
+                    let axis = |v: i16| {
+                        if v == i16::MAX {
+                            0.0
+                        } else {
+                            (v as f64 / 1000.0).clamp(-1.0, 1.0)
+                        }
+                    };
+                    let writes = json!([["throttle", axis(m.x)], ["steer", axis(m.r)]]);
+                    let params = json!({ "target": link.gid, "writes": writes });
+                    if let Err(e) = execute(&bridge, "SetPorts", params).await {
+                        bevy::log::warn!("[lunco-api] MAVLink MANUAL_CONTROL: {e}");
+                    }
+                }
+                MavMessage::SetPositionTargetLocalNed(m) if addressed(&link, m.target_system) => {
+                    if m.type_mask & POSITION_TARGET_IGNORE_POSITION != 0
+                        || m.coordinate_frame != MAV_FRAME_LOCAL_NED
+                    {
+                        bevy::log::warn!(
+                            "[lunco-api] MAVLink SET_POSITION_TARGET_LOCAL_NED: only a full \
+                             position in MAV_FRAME_LOCAL_NED is supported"
+                        );
+                        continue;
+                    }
+                    let ned = DVec3::new(m.x as f64, m.y as f64, m.z as f64);
+                    if let Err(e) = local_waypoint(&bridge, link.gid, ned).await {
+                        bevy::log::warn!("[lunco-api] MAVLink position target: {e}");
+                    }
+                }
+                MavMessage::SetPositionTargetGlobalInt(m) if addressed(&link, m.target_system) => {
+                    if m.type_mask & POSITION_TARGET_IGNORE_POSITION != 0
+                        || !is_global_frame(m.coordinate_frame)
+                    {
+                        bevy::log::warn!(
+                            "[lunco-api] MAVLink SET_POSITION_TARGET_GLOBAL_INT: only a full \
+                             position in a global frame is supported"
+                        );
+                        continue;
+                    }
+                    if let Err(e) = global_waypoint(&bridge, link.gid, m.lat_int, m.lon_int).await {
+                        bevy::log::warn!("[lunco-api] MAVLink position target: {e}");
+                    }
+                }
+                MavMessage::MissionRequestList(m) if addressed(&link, m.target_system) => {
+                    link.send(&MavMessage::MissionCount(MissionCount {
+                        count: 0,
+                        target_system: gcs.0,
+                        target_component: gcs.1,
+                        mission_type: m.mission_type,
+                    }))
+                    .await;
+                }
+                MavMessage::MissionCount(m) if addressed(&link, m.target_system) => {
+                    if m.mission_type != MAV_MISSION_TYPE_MISSION {
+                        ack(&link, gcs, MAV_MISSION_UNSUPPORTED, m.mission_type).await;
+                        continue;
+                    }
+                    // An upload is the whole mission, not an addition to it:
+                    // the route it replaces goes first, and `count = 0` is
+                    // just that.
+                    upload = None;
+                    if let Err(e) = clear_route(&bridge, link.gid).await {
+                        bevy::log::warn!("[lunco-api] MAVLink mission upload: {e}");
+                        ack(&link, gcs, MAV_MISSION_ERROR, m.mission_type).await;
+                    } else if m.count == 0 {
+                        ack(&link, gcs, MAV_MISSION_ACCEPTED, m.mission_type).await;
+                    } else {
+                        upload = Some(Upload {
+                            count: m.count,
+                            next: 0,
+                            result: MAV_MISSION_ACCEPTED,
+                            gcs,
+                        });
+                        request_item(&link, gcs, 0).await;
+                    }
+                }
+                MavMessage::MissionItemInt(m) if addressed(&link, m.target_system) => {
+                    let Some(up) = upload.as_mut() else {
+                        // Outside an upload: a one-off "go here" (ArduPilot's
+                        // guided-mode item), acknowledged on its own.
+                        let result = apply_item(&bridge, link.gid, &m).await;
+                        ack(&link, gcs, result, m.mission_type).await;
+                        continue;
+                    };
+                    if m.seq != up.next {
+                        // A resend of one we already have, or one from the
+                        // future: ask again for the one we want.
+                        request_item(&link, up.gcs, up.next).await;
+                        continue;
+                    }
+                    let result = apply_item(&bridge, link.gid, &m).await;
+                    if up.result == MAV_MISSION_ACCEPTED {
+                        up.result = result;
+                    }
+                    up.next += 1;
+                    if up.next < up.count {
+                        request_item(&link, up.gcs, up.next).await;
+                    } else {
+                        let (gcs, result) = (up.gcs, up.result);
+                        upload = None;
+                        ack(&link, gcs, result, MAV_MISSION_TYPE_MISSION).await;
+                    }
+                }
+                // GCS heartbeats only matter for `peer`, updated above; the
+                // rest of a GCS's chatter has no meaning for a rover.
+                _ => {}
+            }
+        }
+    }
+}
+
+/// `0` is MAVLink's broadcast system id.
+fn addressed(link: &Link, target_system: u8) -> bool {
+    target_system == 0 || target_system == link.system_id
+}
+
+async fn request_item(link: &Link, gcs: (u8, u8), seq: u16) {
+    link.send(&MavMessage::MissionRequestInt(MissionRequestInt {
+        seq,
+        target_system: gcs.0,
+        target_component: gcs.1,
+        mission_type: MAV_MISSION_TYPE_MISSION,
+    }))
+    .await;
+}
+
+async fn ack(link: &Link, gcs: (u8, u8), result: u8, mission_type: u8) {
+    link.send(&MavMessage::MissionAck(MissionAck {
+        target_system: gcs.0,
+        target_component: gcs.1,
+        result,
+        mission_type,
+    }))
+    .await;
+}
+
+/// A mission item as a waypoint. Only `NAV_WAYPOINT` moves a rover; other
+/// commands (takeoff, loiter, camera triggers) are accepted and skipped, so a
+/// stock QGC mission with a takeoff item still uploads.
+async fn apply_item(bridge: &HttpBridge, gid: u64, item: &MissionItemInt) -> u8 {
+    if item.command != MAV_CMD_NAV_WAYPOINT {
+        return MAV_MISSION_ACCEPTED;
+    }
+    let applied = if is_global_frame(item.frame) {
+        global_waypoint(bridge, gid, item.x, item.y).await
+    } else if item.frame == MAV_FRAME_LOCAL_NED {
+        // `_INT` local items carry metres × 1e4.
+        let ned = DVec3::new(item.x as f64 / 1e4, item.y as f64 / 1e4, item.z as f64);
+        local_waypoint(bridge, gid, ned).await
+    } else {
+        return MAV_MISSION_UNSUPPORTED_FRAME;
+    };
+    match applied {
+        Ok(()) => MAV_MISSION_ACCEPTED,
+        Err(e) => {
+            bevy::log::warn!("[lunco-api] MAVLink mission item {}: {e}", item.seq);
+            MAV_MISSION_ERROR
+        }
+    }
+}
+
+async fn clear_route(bridge: &HttpBridge, gid: u64) -> Result<(), String> {
+    execute(bridge, "ClearRuntimeWaypoints", json!({ "target": gid })).await
+}
+
+async fn local_waypoint(bridge: &HttpBridge, gid: u64, ned: DVec3) -> Result<(), String> {
+    let position = NedBasis::to_canonical(ned).to_array();
+    execute(
+        bridge,
+        "AddRuntimeWaypoint",
+        json!({ "target": gid, "position": position }),
+    )
+    .await
+}
+
+/// The altitude is not forwarded: a rover's waypoint belongs on the ground it
+/// drives on, and a GCS fills altitude in whether or not the vehicle flies
+/// (QGroundControl defaults every new item to 50 m).
+async fn global_waypoint(bridge: &HttpBridge, gid: u64, lat: i32, lon: i32) -> Result<(), String> {
+    execute(
+        bridge,
+        "AddGeodeticWaypoint",
+        json!({ "target": gid, "lat_deg": lat as f64 / 1e7, "lon_deg": lon as f64 / 1e7 }),
+    )
+    .await
+}
+
+async fn execute(bridge: &HttpBridge, command: &str, params: Value) -> Result<(), String> {
+    let request = ApiRequest::ExecuteCommand {
+        command: command.to_string(),
+        params,
+    };
+    match bridge.execute(request).await {
+        Ok(ApiResponse::Error { message, .. }) => Err(message),
+        Ok(_) => Ok(()),
+        Err(()) => Err("the simulation is shutting down".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transports::mavlink::MAV_FRAME_GLOBAL_RELATIVE_ALT_INT;
+    use crate::transports::BridgeMessage;
+
+    /// A stand-in for the ECS end of the bridge: records every command name
+    /// and answers each one `ok`.
+    fn recording_bridge() -> (HttpBridge, Arc<Mutex<Vec<String>>>) {
+        let (tx, mut rx) = mpsc::channel::<BridgeMessage>(16);
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let log = seen.clone();
+        tokio::spawn(async move {
+            while let Some(message) = rx.recv().await {
+                if let ApiRequest::ExecuteCommand { command, .. } = &message.request {
+                    log.lock().unwrap().push(command.clone());
+                }
+                let _ = message.reply.send(ApiResponse::ok(Value::Null));
+            }
+        });
+        (HttpBridge::new(tx), seen)
+    }
+
+    async fn send(gcs: &UdpSocket, vehicle: SocketAddr, message: MavMessage) {
+        let header = MavHeader {
+            seq: 0,
+            system_id: 255,
+            component_id: 190,
+        };
+        gcs.send_to(&encode_frame(header, &message), vehicle)
+            .await
+            .unwrap();
+    }
+
+    /// Play the GCS side of one upload of `count` waypoints; the vehicle's
+    /// `MISSION_ACK` result.
+    async fn upload(gcs: &UdpSocket, vehicle: SocketAddr, count: u16) -> u8 {
+        send(
+            gcs,
+            vehicle,
+            MavMessage::MissionCount(MissionCount {
+                count,
+                target_system: 1,
+                target_component: 0,
+                mission_type: MAV_MISSION_TYPE_MISSION,
+            }),
+        )
+        .await;
+        let mut buf = [0u8; 2048];
+        loop {
+            let (len, _) = tokio::time::timeout(Duration::from_secs(5), gcs.recv_from(&mut buf))
+                .await
+                .expect("the vehicle answers the upload")
+                .unwrap();
+            for (_, message) in decode_frames(&buf[..len]) {
+                match message {
+                    MavMessage::MissionRequestInt(request) => {
+                        let item = MissionItemInt {
+                            param1: 0.0,
+                            param2: 0.0,
+                            param3: 0.0,
+                            param4: 0.0,
+                            x: 10_000_000 + request.seq as i32,
+                            y: 20_000_000,
+                            z: 0.0,
+                            seq: request.seq,
+                            command: MAV_CMD_NAV_WAYPOINT,
+                            target_system: 1,
+                            target_component: 0,
+                            frame: MAV_FRAME_GLOBAL_RELATIVE_ALT_INT,
+                            current: 0,
+                            autocontinue: 1,
+                            mission_type: MAV_MISSION_TYPE_MISSION,
+                        };
+                        send(gcs, vehicle, MavMessage::MissionItemInt(item)).await;
+                    }
+                    MavMessage::MissionAck(ack) => return ack.result,
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// THE CASE: a GCS that uploads the same two-item mission twice leaves a
+    /// two-waypoint route, not four — each upload clears first — and an empty
+    /// upload clears the route outright.
+    #[tokio::test]
+    async fn a_mission_re_upload_replaces_the_route() {
+        let (bridge, seen) = recording_bridge();
+        let gcs = UdpSocket::bind(("127.0.0.1", 0)).await.unwrap();
+        let link = Arc::new(Link {
+            socket: UdpSocket::bind(("127.0.0.1", 0)).await.unwrap(),
+            gid: 7,
+            system_id: 1,
+            seq: AtomicU8::new(0),
+            peer: Mutex::new(gcs.local_addr().unwrap()),
+        });
+        let vehicle = link.socket.local_addr().unwrap();
+        let task = tokio::spawn(serve_link(link, bridge));
+
+        for _ in 0..2 {
+            assert_eq!(upload(&gcs, vehicle, 2).await, MAV_MISSION_ACCEPTED);
+        }
+        assert_eq!(upload(&gcs, vehicle, 0).await, MAV_MISSION_ACCEPTED);
+        task.abort();
+
+        let route = [
+            "ClearRuntimeWaypoints",
+            "AddGeodeticWaypoint",
+            "AddGeodeticWaypoint",
+        ];
+        let expected: Vec<&str> = route
+            .iter()
+            .chain(&route)
+            .copied()
+            .chain(["ClearRuntimeWaypoints"])
+            .collect();
+        assert_eq!(*seen.lock().unwrap(), expected);
+    }
+}
@@ -21,11 +21,35 @@ mod http;
 #[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
 pub use http::*;
 
+/// Authenticated, role-scoped remote access (`--api-remote`): bearer tokens or
+/// TLS client certificates, each mapped to a session. Native-only, same
+/// reasoning as `http` above.
+#[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
+pub mod remote;
+
 /// Read-only content-addressed asset server (`GET /scenario-assets/<cid>`) — the
 /// bytes plane of scenario distribution. Native-only, same reasoning as `http` above.
 #[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
 pub mod assets;
 
+/// ROS 2 message shapes, the [`ros::RosPublish`] event and the topic table.
+/// Transport-free, so domain crates can produce sensor messages on any build.
+pub mod ros;
+
+/// rosbridge v2 (JSON over WebSocket) server for stock ROS 2 clients. Native
+/// only, same reasoning as `http` above.
+#[cfg(all(feature = "transport-rosbridge", not(target_arch = "wasm32")))]
+pub mod rosbridge;
+
+/// MAVLink v2 framing, messages and the exposed-vehicle table. Transport-free,
+/// like [`ros`], so the vehicle-state producers build on any target.
+pub mod mavlink;
+
+/// One MAVLink UDP endpoint per owned vehicle, for QGroundControl and other
+/// ground stations. Native only, same reasoning as `http` above.
+#[cfg(all(feature = "transport-mavlink", not(target_arch = "wasm32")))]
+pub mod mavlink_udp;
+
 /// In-browser JS bridge (`window.lunco_api`). Reuses the entire bridge core;
 /// replaces the TcpListener transport with a `#[wasm_bindgen]` async export.
 /// Always compiled on wasm32 — no feature gate.
@@ -44,6 +68,9 @@ pub struct HttpServerConfig {
 pub struct BridgeMessage {
     pub request: crate::schema::ApiRequest,
     pub reply: tokio::sync::oneshot::Sender<crate::schema::ApiResponse>,
+    /// The authenticated caller; `None` = host-trusted. See
+    /// [`crate::executor::ApiRequestEvent::origin`].
+    pub origin: Option<lunco_core::SessionId>,
 }
 
 /// Wakes the host event loop after pushing a message into the
@@ -60,12 +87,19 @@ pub type ApiWaker = std::sync::Arc<dyn Fn() + Send + Sync>;
 pub struct HttpBridge {
     pub tx: tokio::sync::mpsc::Sender<BridgeMessage>,
     pub waker: Option<ApiWaker>,
+    /// Stamped on every request sent through this handle. Only the remote
+    /// transport sets it, per request, from the principal it authenticated.
+    pub origin: Option<lunco_core::SessionId>,
 }
 
 #[cfg(any(feature = "transport-http", target_arch = "wasm32"))]
 impl HttpBridge {
     pub fn new(tx: tokio::sync::mpsc::Sender<BridgeMessage>) -> Self {
-        Self { tx, waker: None }
+        Self {
+            tx,
+            waker: None,
+            origin: None,
+        }
     }
 
     pub fn with_waker(mut self, waker: ApiWaker) -> Self {
@@ -73,6 +107,13 @@ impl HttpBridge {
         self
     }
 
+    /// The same bridge, acting for `session`: its requests are authorized and
+    /// applied as that session instead of host-trusted.
+    pub fn as_session(mut self, session: lunco_core::SessionId) -> Self {
+        self.origin = Some(session);
+        self
+    }
+
     pub async fn execute(
         &self,
         request: crate::schema::ApiRequest,
@@ -90,7 +131,11 @@ impl HttpBridge {
         // here means the ECS receiver is gone (app shutting down), which is the
         // existing contract for `Err(())`.
         self.tx
-            .send(BridgeMessage { request, reply: tx })
+            .send(BridgeMessage {
+                request,
+                reply: tx,
+                origin: self.origin,
+            })
             .await
             .map_err(|_| ())?;
         if let Some(waker) = &self.waker {
@@ -100,6 +145,39 @@ impl HttpBridge {
     }
 }
 
+/// The routes that reach the world, shared by the loopback and the remote
+/// server. `/api/health` is added by each server itself: it is the one route
+/// the remote server answers without credentials.
+///
+/// Five routes in all, every one real (the docs used to list ones that were
+/// never registered — every curl example 404'd):
+///   POST /api/commands        — the one command funnel
+///   GET  /api/health          — liveness; no world access
+///   GET  /api/ready           — readiness; reads the world's
+///                               `ReadinessRegistry` via `GetReadiness`
+///   GET  /api/diagnostics     — co-sim wiring health; reads the world's
+///                               `CosimDiagnostics` via `GetBrokenConnections`
+///   GET  /api/commands/schema — the `DiscoverSchema` result, i.e.
+///                               the same derived list the MCP tool
+///                               surface is built from
+#[cfg(all(feature = "transport-http", not(target_arch = "wasm32")))]
+pub(crate) fn api_routes() -> axum::Router<HttpBridge> {
+    axum::Router::new()
+        .route(
+            "/api/commands",
+            axum::routing::post(http::handle_api_commands),
+        )
+        .route(
+            "/api/commands/schema",
+            axum::routing::get(http::handle_schema),
+        )
+        .route("/api/ready", axum::routing::get(http::handle_ready))
+        .route(
+            "/api/diagnostics",
+            axum::routing::get(http::handle_diagnostics),
+        )
+}
+
 // A long-lived OS thread hosting a blocking tokio HTTP-server runtime is
 // the correct shape here — not an `AsyncComputeTaskPool` task (which is
 // for short compute jobs and would occupy a pool slot forever). The
@@ -121,39 +199,15 @@ pub fn spawn_server(config: HttpServerConfig, bridge: HttpBridge) {
             }
         };
         rt.block_on(async move {
-            // Four routes, all of them real (the docs used to list ones that were
-            // never registered — every curl example 404'd):
-            //   POST /api/commands        — the one command funnel
-            //   GET  /api/health          — liveness; no world access
-            //   GET  /api/ready           — readiness; reads the world's
-            //                               `ReadinessRegistry` via `GetReadiness`
-            //   GET  /api/diagnostics     — co-sim wiring health; reads the world's
-            //                               `CosimDiagnostics` via `GetBrokenConnections`
-            //   GET  /api/commands/schema — the `DiscoverSchema` result, i.e.
-            //                               the same derived list the MCP tool
-            //                               surface is built from
-            let app = axum::Router::new()
-                .route(
-                    "/api/commands",
-                    axum::routing::post(http::handle_api_commands),
-                )
-                .route(
-                    "/api/commands/schema",
-                    axum::routing::get(http::handle_schema),
-                )
+            let app = api_routes()
                 .route("/api/health", axum::routing::get(http::handle_health))
-                .route("/api/ready", axum::routing::get(http::handle_ready))
-                .route(
-                    "/api/diagnostics",
-                    axum::routing::get(http::handle_diagnostics),
-                )
                 .with_state(bridge);
 
-            // TODO(multiplayer): deferred — singleplayer focus for now, RBAC
-            // disabled for ease of debugging. Loopback-only bind, but the command
-            // API has zero local auth — any local process/user can drive the full
-            // command surface. Revisit before multiplayer hardening
-            // (REVIEW-2026-07-19.md API-1).
+            // Loopback and host-trusted BY DESIGN: a caller here already runs
+            // code on this machine, so requests carry no session and skip the
+            // authorization gate, as in-app callers do. Anything reachable from
+            // another machine goes through `remote::spawn_remote_server`, which
+            // authenticates every request to a session and authorizes it.
             let listener = match tokio::net::TcpListener::bind(format!("127.0.0.1:{}", port)).await
             {
                 Ok(l) => l,
@@ -0,0 +1,585 @@
+//! Authenticated remote access to the HTTP API (`--api-remote ADDR --api-auth FILE`).
+//!
+//! The `--api` server is loopback-only and host-trusted: whoever can reach it
+//! already runs code on this machine. Remote mode is the other case — a lab
+//! machine serving the team's analysis boxes — so it binds any address, and
+//! every request must name a **principal** from the auth file, by bearer token
+//! or by TLS client certificate:
+//!
+//! ```json
+//! {
+//!   "tls": { "cert": "api.pem", "key": "api.key", "client_ca": "lab-ca.pem" },
+//!   "principals": [
+//!     { "name": "analysis-1", "role": "Observer", "token_sha256": "9f86d0…" },
+//!     { "name": "ops",        "role": "Operator", "token_sha256": "2c26b4…" },
+//!     { "name": "ci",         "role": "Operator", "client_cert_sha256": "a1b2…" }
+//!   ]
+//! }
+//! ```
+//!
+//! The file holds **digests, never tokens**: `printf %s "$TOKEN" | sha256sum`
+//! for a token, the SHA-256 of the DER certificate for a client cert
+//! (`openssl x509 -in ci.pem -outform der | sha256sum`; `:` separators are
+//! accepted). `tls` paths are relative to the auth file. With `tls.client_ca`
+//! a client may present a certificate signed by that CA, and one whose digest
+//! is listed authenticates as its principal; everyone else falls back to the
+//! `Authorization: Bearer` header. Without `tls` the server speaks plain HTTP
+//! and tokens cross the network in clear, which is logged loudly.
+//!
+//! Each principal is a session: [`REMOTE_API_SESSION_BASE`] + its `session`
+//! index (default: its position in the file — give an explicit index if you
+//! reorder the file, or ownership follows the position). At startup the
+//! principals are entered into [`SessionRbac`] with their role, and from then
+//! on a remote request is just another session's: authorized by the same gate
+//! the networked command path uses (see [`crate::executor::ApiAuthz`]) and
+//! applied as that session. `/api/health` alone answers without credentials.
+
+use super::{http, ApiCaller, HttpBridge};
+use crate::schema::ApiResponse;
+use crate::transports::envelope::ApiResponseEnvelope;
+use axum::{
+    extract::{connect_info::Connected, ConnectInfo, Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use lunco_core::session::{AuthorityRole, SessionRbac, UserSession};
+use lunco_core::SessionId;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_rustls::rustls;
+
+/// Base of the reserved `SessionId` band for remote API principals. Clear of
+/// [`SessionId::LOCAL`], of host-minted client ids (allocated low, per
+/// connection) and of the autopilot band (`0xA0_7000`).
+pub const REMOTE_API_SESSION_BASE: u64 = 0xA9_1000;
+
+/// A TLS handshake that has not finished by then is dropped, so a stalled
+/// client cannot hold a socket forever.
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `--api-remote` as given on the command line. Resolved (auth file read and
+/// checked) in `Plugin::build`, where a failure can be logged.
+#[derive(Debug, Clone)]
+pub struct RemoteHttpConfig {
+    /// `IP:PORT`, or a bare IP for the default API port.
+    pub bind: String,
+    /// `--api-auth`. Required: remote mode without principals does not start.
+    pub auth_file: Option<PathBuf>,
+}
+
+impl RemoteHttpConfig {
+    /// The bind address and the loaded principals, or why remote mode must stay
+    /// off. Never falls back to an open server.
+    pub fn resolve(&self) -> Result<(SocketAddr, ApiAuth), String> {
+        let bind = self
+            .bind
+            .parse::<SocketAddr>()
+            .or_else(|_| {
+                self.bind
+                    .parse::<std::net::IpAddr>()
+                    .map(|ip| SocketAddr::new(ip, lunco_core::session::DEFAULT_API_PORT))
+            })
+            .map_err(|_| format!("--api-remote: '{}' is not an IP[:PORT]", self.bind))?;
+        let path = self
+            .auth_file
+            .as_deref()
+            .ok_or("--api-remote needs --api-auth FILE: remote access is never unauthenticated")?;
+        Ok((bind, ApiAuth::load(path)?))
+    }
+}
+
+/// Who may call, and how they prove it.
+#[derive(Debug, Clone)]
+pub struct Principal {
+    pub name: String,
+    pub role: AuthorityRole,
+    pub session: SessionId,
+}
+
+#[derive(Debug, Clone)]
+struct TlsPaths {
+    cert: PathBuf,
+    key: PathBuf,
+    client_ca: Option<PathBuf>,
+}
+
+/// The loaded auth file: principals, keyed by credential digest.
+#[derive(Debug, Clone)]
+pub struct ApiAuth {
+    principals: Vec<Principal>,
+    tokens: HashMap<String, usize>,
+    certs: HashMap<String, usize>,
+    tls: Option<TlsPaths>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct AuthFile {
+    #[serde(default)]
+    tls: Option<TlsFile>,
+    principals: Vec<PrincipalFile>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct TlsFile {
+    cert: PathBuf,
+    key: PathBuf,
+    #[serde(default)]
+    client_ca: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PrincipalFile {
+    name: String,
+    role: AuthorityRole,
+    #[serde(default)]
+    session: Option<u64>,
+    #[serde(default)]
+    token_sha256: Option<String>,
+    #[serde(default)]
+    client_cert_sha256: Option<String>,
+}
+
+fn sha256_hex(bytes: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// A digest as written in the file: hex, any case, optional `:` separators.
+fn normalize_digest(field: &str, value: &str) -> Result<String, String> {
+    let hex: String = value
+        .chars()
+        .filter(|c| *c != ':')
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    if hex.len() == 64 && hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        Ok(hex)
+    } else {
+        Err(format!(
+            "{field}: expected a SHA-256 hex digest, got '{value}'"
+        ))
+    }
+}
+
+impl ApiAuth {
+    /// Read and check an auth file. Every principal needs at least one
+    /// credential; sessions and credentials must be unique; a certificate
+    /// principal needs a `client_ca` to be verified against.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let text = std::fs::read_to_string(path).map_err(|e| format!("{}: {e}", path.display()))?;
+        let file: AuthFile =
+            serde_json::from_str(&text).map_err(|e| format!("{}: {e}", path.display()))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+        let tls = file.tls.map(|t| TlsPaths {
+            cert: dir.join(t.cert),
+            key: dir.join(t.key),
+            client_ca: t.client_ca.map(|ca| dir.join(ca)),
+        });
+
+        let mut auth = Self {
+            principals: Vec::new(),
+            tokens: HashMap::new(),
+            certs: HashMap::new(),
+            tls,
+        };
+        for (index, p) in file.principals.into_iter().enumerate() {
+            let who = format!("principal '{}'", p.name);
+            if p.token_sha256.is_none() && p.client_cert_sha256.is_none() {
+                return Err(format!("{who} has no credential"));
+            }
+            let session = SessionId(REMOTE_API_SESSION_BASE + p.session.unwrap_or(index as u64));
+            if auth.principals.iter().any(|q| q.session == session) {
+                return Err(format!("{who}: session {session} is already taken"));
+            }
+            let slot = auth.principals.len();
+            if let Some(token) = &p.token_sha256 {
+                let digest = normalize_digest(&format!("{who} token_sha256"), token)?;
+                if auth.tokens.insert(digest, slot).is_some() {
+                    return Err(format!("{who}: token is shared with another principal"));
+                }
+            }
+            if let Some(cert) = &p.client_cert_sha256 {
+                if auth.tls.as_ref().is_none_or(|t| t.client_ca.is_none()) {
+                    return Err(format!(
+                        "{who} authenticates by certificate, but tls.client_ca is not set"
+                    ));
+                }
+                let digest = normalize_digest(&format!("{who} client_cert_sha256"), cert)?;
+                if auth.certs.insert(digest, slot).is_some() {
+                    return Err(format!(
+                        "{who}: certificate is shared with another principal"
+                    ));
+                }
+            }
+            auth.principals.push(Principal {
+                name: p.name,
+                role: p.role,
+                session,
+            });
+        }
+        if auth.principals.is_empty() {
+            return Err(format!("{}: no principals", path.display()));
+        }
+        Ok(auth)
+    }
+
+    pub fn principals(&self) -> &[Principal] {
+        &self.principals
+    }
+
+    /// Enter every principal into the RBAC table the authorization gate reads.
+    /// The host issues the session token, as it does for its own session.
+    pub fn register(&self, rbac: &mut SessionRbac) {
+        for p in &self.principals {
+            rbac.sessions.insert(
+                p.session.0,
+                UserSession {
+                    session_id: p.session,
+                    username: p.name.clone(),
+                    role: p.role,
+                    authenticated: true,
+                    token: Some(lunco_core::ids::random_token()),
+                },
+            );
+        }
+    }
+
+    /// The principal a bearer token names. Compared by digest, so the lookup
+    /// never touches the secret byte by byte.
+    pub fn by_token(&self, token: &str) -> Option<&Principal> {
+        self.tokens
+            .get(&sha256_hex(token.as_bytes()))
+            .map(|&i| &self.principals[i])
+    }
+
+    /// The principal a (CA-verified) DER client certificate names.
+    pub fn by_certificate(&self, der: &[u8]) -> Option<&Principal> {
+        self.certs
+            .get(&sha256_hex(der))
+            .map(|&i| &self.principals[i])
+    }
+}
+
+/// The verified client certificate of a TLS connection, if one was presented.
+#[derive(Debug, Clone)]
+pub struct PeerCertificate(Option<Arc<Vec<u8>>>);
+
+impl<'a> Connected<axum::serve::IncomingStream<'a, TlsListener>> for PeerCertificate {
+    fn connect_info(stream: axum::serve::IncomingStream<'a, TlsListener>) -> Self {
+        let (_, connection) = stream.io().get_ref();
+        Self(
+            connection
+                .peer_certificates()
+                .and_then(|chain| chain.first())
+                .map(|leaf| Arc::new(leaf.as_ref().to_vec())),
+        )
+    }
+}
+
+/// Resolve the caller or answer `401`. A listed client certificate wins over a
+/// bearer token; the resolved session rides the request as [`ApiCaller`].
+async fn require_principal(
+    State(auth): State<Arc<ApiAuth>>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let by_certificate = request
+        .extensions()
+        .get::<ConnectInfo<PeerCertificate>>()
+        .and_then(|ConnectInfo(peer)| peer.0.as_deref())
+        .and_then(|der| auth.by_certificate(der));
+    let by_token = || {
+        request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.strip_prefix("Bearer "))
+            .and_then(|token| auth.by_token(token.trim()))
+    };
+    let Some(session) = by_certificate.or_else(by_token).map(|p| p.session) else {
+        let envelope = ApiResponseEnvelope::from(ApiResponse::Error {
+            code: StatusCode::UNAUTHORIZED.as_u16(),
+            message: "authentication required: a bearer token or client certificate".into(),
+        });
+        return (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(envelope),
+        )
+            .into_response();
+    };
+    request.extensions_mut().insert(ApiCaller(session));
+    next.run(request).await
+}
+
+/// TLS connections, handshaken off the accept loop (see [`accept_tls`]).
+pub struct TlsListener {
+    rx: tokio::sync::mpsc::Receiver<(
+        tokio_rustls::server::TlsStream<tokio::net::TcpStream>,
+        SocketAddr,
+    )>,
+    local: SocketAddr,
+}
+
+impl axum::serve::Listener for TlsListener {
+    type Io = tokio_rustls::server::TlsStream<tokio::net::TcpStream>;
+    type Addr = SocketAddr;
+
+    async fn accept(&mut self) -> (Self::Io, Self::Addr) {
+        match self.rx.recv().await {
+            Some(connection) => connection,
+            // The acceptor task is gone: nothing will ever connect again.
+            None => std::future::pending().await,
+        }
+    }
+
+    fn local_addr(&self) -> std::io::Result<Self::Addr> {
+        Ok(self.local)
+    }
+}
+
+/// Accept TCP and run each handshake in its own task, so one slow or hostile
+/// client never stalls the others.
+async fn accept_tls(
+    listener: tokio::net::TcpListener,
+    acceptor: tokio_rustls::TlsAcceptor,
+    tx: tokio::sync::mpsc::Sender<(
+        tokio_rustls::server::TlsStream<tokio::net::TcpStream>,
+        SocketAddr,
+    )>,
+) {
+    loop {
+        let (tcp, addr) = match listener.accept().await {
+            Ok(connection) => connection,
+            Err(e) => {
+                // Typically fd exhaustion; back off instead of spinning.
+                bevy::log::warn!("[lunco-api] remote accept failed: {e}");
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                continue;
+            }
+        };
+        let (acceptor, tx) = (acceptor.clone(), tx.clone());
+        tokio::spawn(async move {
+            match tokio::time::timeout(HANDSHAKE_TIMEOUT, acceptor.accept(tcp)).await {
+                Ok(Ok(tls)) => {
+                    // Ignored by design: a closed channel means the server is gone.
+                    let _ = tx.send((tls, addr)).await;
+                }
+                Ok(Err(e)) => bevy::log::debug!("[lunco-api] TLS handshake from {addr}: {e}"),
+                Err(_) => bevy::log::debug!("[lunco-api] TLS handshake from {addr} timed out"),
+            }
+        });
+    }
+}
+
+fn read_certificates(
+    path: &Path,
+) -> Result<Vec<rustls::pki_types::CertificateDer<'static>>, String> {
+    let file = std::fs::File::open(path).map_err(|e| format!("{}: {e}", path.display()))?;
+    let certs = rustls_pemfile::certs(&mut std::io::BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("{}: {e}", path.display()))?;
+    if certs.is_empty() {
+        return Err(format!("{}: no PEM certificates", path.display()));
+    }
+    Ok(certs)
+}
+
+/// The server's TLS config. Ring is named explicitly: the workspace links both
+/// rustls providers, and rustls refuses to guess between them.
+fn tls_acceptor(tls: &TlsPaths) -> Result<tokio_rustls::TlsAcceptor, String> {
+    let provider = Arc::new(rustls::crypto::ring::default_provider());
+    let certs = read_certificates(&tls.cert)?;
+    let key = {
+        let file =
+            std::fs::File::open(&tls.key).map_err(|e| format!("{}: {e}", tls.key.display()))?;
+        rustls_pemfile::private_key(&mut std::io::BufReader::new(file))
+            .map_err(|e| format!("{}: {e}", tls.key.display()))?
+            .ok_or_else(|| format!("{}: no PEM private key", tls.key.display()))?
+    };
+    let builder = rustls::ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| e.to_string())?;
+    let builder = match &tls.client_ca {
+        Some(ca) => {
+            let mut roots = rustls::RootCertStore::empty();
+            for cert in read_certificates(ca)? {
+                roots
+                    .add(cert)
+                    .map_err(|e| format!("{}: {e}", ca.display()))?;
+            }
+            // Unauthenticated is allowed at the TLS layer so token clients can
+            // connect too; `require_principal` is where a caller is refused.
+            let verifier = rustls::server::WebPkiClientVerifier::builder_with_provider(
+                Arc::new(roots),
+                provider,
+            )
+            .allow_unauthenticated()
+            .build()
+            .map_err(|e| format!("{}: {e}", ca.display()))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+        None => builder.with_no_client_auth(),
+    };
+    let config = builder
+        .with_single_cert(certs, key)
+        .map_err(|e| format!("{}: {e}", tls.cert.display()))?;
+    Ok(tokio_rustls::TlsAcceptor::from(Arc::new(config)))
+}
+
+/// Start the remote API server. Same thread-and-runtime shape, and the same
+/// routes, as [`super::spawn_server`]; every route but `/api/health` sits
+/// behind [`require_principal`].
+#[allow(clippy::disallowed_methods)]
+pub fn spawn_remote_server(bind: SocketAddr, auth: ApiAuth, bridge: HttpBridge) {
+    std::thread::spawn(move || {
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                bevy::log::error!("[lunco-api] failed to start remote API runtime: {e}");
+                return;
+            }
+        };
+        rt.block_on(async move {
+            let acceptor = match auth.tls.as_ref().map(tls_acceptor).transpose() {
+                Ok(acceptor) => acceptor,
+                Err(e) => {
+                    bevy::log::error!("[lunco-api] remote API TLS setup failed: {e} — not started");
+                    return;
+                }
+            };
+            let auth = Arc::new(auth);
+            let app = super::api_routes()
+                .route_layer(axum::middleware::from_fn_with_state(
+                    auth.clone(),
+                    require_principal,
+                ))
+                .route("/api/health", axum::routing::get(http::handle_health))
+                .with_state(bridge);
+
+            let listener = match tokio::net::TcpListener::bind(bind).await {
+                Ok(l) => l,
+                Err(e) => {
+                    bevy::log::error!(
+                        "[lunco-api] remote API failed to bind {bind}: {e} — not started"
+                    );
+                    return;
+                }
+            };
+            let names: Vec<&str> = auth.principals().iter().map(|p| p.name.as_str()).collect();
+            let served = match acceptor {
+                Some(acceptor) => {
+                    bevy::log::info!(
+                        "[lunco-api] remote API on https://{bind} for {}",
+                        names.join(", ")
+                    );
+                    let (tx, rx) = tokio::sync::mpsc::channel(64);
+                    tokio::spawn(accept_tls(listener, acceptor, tx));
+                    let listener = TlsListener { rx, local: bind };
+                    axum::serve(
+                        listener,
+                        app.into_make_service_with_connect_info::<PeerCertificate>(),
+                    )
+                    .await
+                }
+                None => {
+                    bevy::log::warn!(
+                        "[lunco-api] remote API on http://{bind} WITHOUT TLS for {} — bearer \
+                         tokens cross the network in clear; set `tls` in the auth file",
+                        names.join(", ")
+                    );
+                    axum::serve(listener, app).await
+                }
+            };
+            if let Err(e) = served {
+                bevy::log::error!("[lunco-api] remote API stopped with error: {e}");
+            }
+        });
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_auth(json: &str) -> (tempfile::TempDir, PathBuf) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("auth.json");
+        std::fs::write(&path, json).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn tokens_resolve_to_their_principal_and_session() {
+        let digest = sha256_hex(b"s3cret");
+        let (_dir, path) = write_auth(&format!(
+            r#"{{ "principals": [
+                {{ "name": "ops", "role": "Operator", "token_sha256": "{digest}" }},
+                {{ "name": "viewer", "role": "Observer", "session": 7,
+                   "token_sha256": "{}" }}
+            ] }}"#,
+            sha256_hex(b"look")
+        ));
+        let auth = ApiAuth::load(&path).unwrap();
+        let ops = auth.by_token("s3cret").unwrap();
+        assert_eq!(ops.name, "ops");
+        assert_eq!(ops.session, SessionId(REMOTE_API_SESSION_BASE));
+        assert_eq!(
+            auth.by_token("look").unwrap().session,
+            SessionId(REMOTE_API_SESSION_BASE + 7)
+        );
+        assert!(auth.by_token("S3cret").is_none());
+
+        let mut rbac = SessionRbac::default();
+        auth.register(&mut rbac);
+        assert!(rbac.is_authorized(ops.session, AuthorityRole::Operator));
+        assert!(!rbac.is_authorized(
+            SessionId(REMOTE_API_SESSION_BASE + 7),
+            AuthorityRole::Operator
+        ));
+    }
+
+    #[test]
+    fn bad_auth_files_are_refused() {
+        let token = sha256_hex(b"t");
+        for (why, json) in [
+            (
+                "no credential",
+                r#"{"principals":[{"name":"a","role":"Owner"}]}"#.to_string(),
+            ),
+            ("no principals", r#"{"principals":[]}"#.to_string()),
+            (
+                "short digest",
+                r#"{"principals":[{"name":"a","role":"Owner","token_sha256":"abc"}]}"#.to_string(),
+            ),
+            (
+                "shared token",
+                format!(
+                    r#"{{"principals":[
+                        {{"name":"a","role":"Owner","token_sha256":"{token}"}},
+                        {{"name":"b","role":"Observer","token_sha256":"{token}"}}]}}"#
+                ),
+            ),
+            (
+                "cert without client CA",
+                format!(
+                    r#"{{"principals":[{{"name":"a","role":"Owner","client_cert_sha256":"{token}"}}]}}"#
+                ),
+            ),
+        ] {
+            let (_dir, path) = write_auth(&json);
+            assert!(ApiAuth::load(&path).is_err(), "{why} must be refused");
+        }
+    }
+}
@@ -0,0 +1,484 @@
+//! ROS 2 message shapes and the topic table behind the rosbridge transport.
+//!
+//! This half is transport-free and compiles everywhere — wasm and
+//! `default-features = false` consumers included — because the PRODUCERS of
+//! sensor messages live in domain crates (`lunco-mobility` publishes odometry,
+//! IMU and point clouds off avian state) that must not pull a socket in. They
+//! build a typed message, wrap it in a [`RosPublish`] and trigger it; the
+//! native rosbridge server ([`super::rosbridge`]) is the only thing that turns
+//! it into bytes. With no server running the event is simply unobserved.
+//!
+//! # Frames
+//!
+//! Messages are in ROS's own convention (REP-103: Z-up, X-forward, Y-left,
+//! metres), NOT ours (Y-up, −Z-forward). [`RosBasis`] is the one place that
+//! conversion happens; a producer hands it canonical vectors and never
+//! swizzles by hand. The world frame is `odom`, a body's frame is `base_link`
+//! (REP-105), each namespaced under the body's topic prefix.
+//!
+//! # Topic names
+//!
+//! Everything lives under `/lunco`. An entity's topics are
+//! `/lunco/<api_id>/<leaf>` — the same `api_id` `ListEntities` returns, so a
+//! ROS client and an HTTP client name a rover identically. Telemetry channels
+//! appear as `/lunco/<api_id>/telemetry/<channel>` (see [`telemetry_topic`]).
+
+use bevy::math::{DMat3, DQuat, DVec3};
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Root namespace of every topic this process serves.
+pub const ROS_NAMESPACE: &str = "/lunco";
+
+/// A typed ROS message: knows its own `package/Type` name.
+pub trait RosMessage: Serialize {
+    const TYPE: &'static str;
+}
+
+/// `builtin_interfaces/Time`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Time {
+    pub sec: i32,
+    pub nanosec: u32,
+}
+
+impl Time {
+    /// Split simulation seconds into the ROS 2 `(sec, nanosec)` pair.
+    pub fn from_secs(secs: f64) -> Self {
+        let sec = secs.floor();
+        Self {
+            sec: sec as i32,
+            nanosec: (((secs - sec) * 1e9) as u32).min(999_999_999),
+        }
+    }
+}
+
+/// `std_msgs/Header`.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Header {
+    pub stamp: Time,
+    pub frame_id: String,
+}
+
+/// `geometry_msgs/Vector3` (also used for `Point`, whose fields are the same).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Vector3 {
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    #[serde(default)]
+    pub z: f64,
+}
+
+/// `geometry_msgs/Quaternion`.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Quaternion {
+    pub x: f64,
+    pub y: f64,
+    pub z: f64,
+    pub w: f64,
+}
+
+impl Default for Quaternion {
+    fn default() -> Self {
+        Self {
+            x: 0.0,
+            y: 0.0,
+            z: 0.0,
+            w: 1.0,
+        }
+    }
+}
+
+/// `geometry_msgs/Pose`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Pose {
+    pub position: Vector3,
+    pub orientation: Quaternion,
+}
+
+/// `geometry_msgs/Twist` — also the one message a client publishes to us.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Twist {
+    #[serde(default)]
+    pub linear: Vector3,
+    #[serde(default)]
+    pub angular: Vector3,
+}
+
+impl RosMessage for Twist {
+    const TYPE: &'static str = "geometry_msgs/Twist";
+}
+
+/// `geometry_msgs/PoseWithCovariance`. The covariance is row-major 6×6; serde
+/// has no array impl past 32, hence the `Vec`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PoseWithCovariance {
+    pub pose: Pose,
+    pub covariance: Vec<f64>,
+}
+
+/// `geometry_msgs/TwistWithCovariance`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TwistWithCovariance {
+    pub twist: Twist,
+    pub covariance: Vec<f64>,
+}
+
+/// `nav_msgs/Odometry`. Pose in `header.frame_id`, twist in `child_frame_id`
+/// (REP-105) — a nav stack reads the twist as body-frame.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Odometry {
+    pub header: Header,
+    pub child_frame_id: String,
+    pub pose: PoseWithCovariance,
+    pub twist: TwistWithCovariance,
+}
+
+impl RosMessage for Odometry {
+    const TYPE: &'static str = "nav_msgs/Odometry";
+}
+
+impl Odometry {
+    /// Ground truth: the simulator knows the pose exactly, so the covariances
+    /// are zero rather than a made-up noise model.
+    pub fn exact(header: Header, child_frame_id: String, pose: Pose, twist: Twist) -> Self {
+        Self {
+            header,
+            child_frame_id,
+            pose: PoseWithCovariance {
+                pose,
+                covariance: vec![0.0; 36],
+            },
+            twist: TwistWithCovariance {
+                twist,
+                covariance: vec![0.0; 36],
+            },
+        }
+    }
+}
+
+/// `sensor_msgs/Imu`. All three vectors in `header.frame_id` (the body).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Imu {
+    pub header: Header,
+    pub orientation: Quaternion,
+    pub orientation_covariance: [f64; 9],
+    pub angular_velocity: Vector3,
+    pub angular_velocity_covariance: [f64; 9],
+    pub linear_acceleration: Vector3,
+    pub linear_acceleration_covariance: [f64; 9],
+}
+
+impl RosMessage for Imu {
+    const TYPE: &'static str = "sensor_msgs/Imu";
+}
+
+/// `sensor_msgs/PointField`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointField {
+    pub name: String,
+    pub offset: u32,
+    pub datatype: u8,
+    pub count: u32,
+}
+
+impl PointField {
+    /// `sensor_msgs/PointField.FLOAT32`.
+    pub const FLOAT32: u8 = 7;
+}
+
+/// `sensor_msgs/PointCloud2`. `data` is base64 — rosbridge's encoding for every
+/// `uint8[]`, which a stock client decodes back to bytes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PointCloud2 {
+    pub header: Header,
+    pub height: u32,
+    pub width: u32,
+    pub fields: Vec<PointField>,
+    pub is_bigendian: bool,
+    pub point_step: u32,
+    pub row_step: u32,
+    pub data: String,
+    pub is_dense: bool,
+}
+
+impl RosMessage for PointCloud2 {
+    const TYPE: &'static str = "sensor_msgs/PointCloud2";
+}
+
+impl PointCloud2 {
+    /// An unorganised `x`/`y`/`z` float32 cloud, points already in ROS axes.
+    pub fn xyz(header: Header, points: &[[f32; 3]]) -> Self {
+        use base64::Engine as _;
+        let fields = ["x", "y", "z"]
+            .iter()
+            .enumerate()
+            .map(|(i, name)| PointField {
+                name: (*name).to_string(),
+                offset: 4 * i as u32,
+                datatype: PointField::FLOAT32,
+                count: 1,
+            })
+            .collect();
+        let bytes: Vec<u8> = points
+            .iter()
+            .flat_map(|p| p.iter().flat_map(|c| c.to_le_bytes()))
+            .collect();
+        Self {
+            header,
+            height: 1,
+            width: points.len() as u32,
+            fields,
+            is_bigendian: false,
+            point_step: 12,
+            row_step: 12 * points.len() as u32,
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+            is_dense: true,
+        }
+    }
+}
+
+/// Canonical (Y-up, −Z-forward) ⇄ ROS (REP-103: Z-up, X-forward, Y-left).
+///
+/// A proper rotation — both conventions are right-handed — so vectors map by
+/// the matrix and orientations by conjugation with it.
+pub struct RosBasis;
+
+impl RosBasis {
+    /// Rows: ROS x = forward = −z, ROS y = left = −x, ROS z = up = y.
+    const TO_ROS: DMat3 = DMat3::from_cols(
+        DVec3::new(0.0, -1.0, 0.0),
+        DVec3::new(0.0, 0.0, 1.0),
+        DVec3::new(-1.0, 0.0, 0.0),
+    );
+
+    /// A canonical point or free vector, in ROS axes.
+    pub fn vector(v: DVec3) -> Vector3 {
+        let r = Self::TO_ROS * v;
+        Vector3 {
+            x: r.x,
+            y: r.y,
+            z: r.z,
+        }
+    }
+
+    /// A ROS vector (an incoming command, a goal) back in canonical axes.
+    pub fn to_canonical(v: Vector3) -> DVec3 {
+        Self::TO_ROS.transpose() * DVec3::new(v.x, v.y, v.z)
+    }
+
+    /// A canonical body orientation as the rotation from the body's ROS frame
+    /// (`base_link`, FLU) to the ROS world frame.
+    pub fn quaternion(q: DQuat) -> Quaternion {
+        let basis = DQuat::from_mat3(&Self::TO_ROS);
+        let r = (basis * q * basis.inverse()).normalize();
+        Quaternion {
+            x: r.x,
+            y: r.y,
+            z: r.z,
+            w: r.w,
+        }
+    }
+}
+
+/// A message for the rosbridge transport to send to whoever subscribed to
+/// `topic`. Trigger it from any crate; unobserved when no server runs.
+#[derive(Event, Debug, Clone)]
+pub struct RosPublish {
+    pub topic: String,
+    pub msg_type: String,
+    pub msg: serde_json::Value,
+}
+
+impl RosPublish {
+    pub fn new<M: RosMessage>(topic: impl Into<String>, msg: &M) -> Self {
+        Self {
+            topic: topic.into(),
+            msg_type: M::TYPE.to_string(),
+            // A `RosMessage` is plain data; its encoding cannot fail.
+            msg: serde_json::to_value(msg).unwrap_or_default(),
+        }
+    }
+}
+
+/// Which topics exist and which ones a client is listening to.
+#[derive(Debug, Default)]
+pub struct RosTopicTable {
+    /// Producer-advertised topic → message type.
+    advertised: BTreeMap<String, String>,
+    /// Topic → number of live client subscriptions.
+    subscribed: HashMap<String, usize>,
+}
+
+/// Shared between the ECS (producers advertise, and ask whether anyone is
+/// listening before doing sensor work) and the transport thread (which counts
+/// subscriptions and answers `/rosapi/topics`). An `Arc<Mutex<_>>` rather than
+/// a channel because both sides only ever read a snapshot or flip a counter.
+#[derive(Resource, Clone, Default)]
+pub struct RosTopics(pub Arc<Mutex<RosTopicTable>>);
+
+impl RosTopics {
+    fn table(&self) -> std::sync::MutexGuard<'_, RosTopicTable> {
+        // A panic while holding this lock leaves a table that is still
+        // structurally valid; keep serving rather than poisoning every frame.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub fn advertise(&self, topic: impl Into<String>, msg_type: &str) {
+        self.table()
+            .advertised
+            .insert(topic.into(), msg_type.to_string());
+    }
+
+    pub fn unadvertise(&self, topic: &str) {
+        self.table().advertised.remove(topic);
+    }
+
+    /// `(topic, type)` for every advertised topic, sorted by topic.
+    pub fn advertised(&self) -> Vec<(String, String)> {
+        self.table()
+            .advertised
+            .iter()
+            .map(|(t, ty)| (t.clone(), ty.clone()))
+            .collect()
+    }
+
+    pub fn type_of(&self, topic: &str) -> Option<String> {
+        self.table().advertised.get(topic).cloned()
+    }
+
+    /// True when at least one client is subscribed — producers skip the work
+    /// (a point-cloud fan is hundreds of raycasts) otherwise.
+    pub fn is_subscribed(&self, topic: &str) -> bool {
+        self.table().subscribed.get(topic).is_some_and(|n| *n > 0)
+    }
+
+    /// Every topic with a live subscription.
+    pub fn subscribed(&self) -> Vec<String> {
+        self.table()
+            .subscribed
+            .iter()
+            .filter(|(_, n)| **n > 0)
+            .map(|(t, _)| t.clone())
+            .collect()
+    }
+
+    pub fn add_subscriber(&self, topic: &str) {
+        *self
+            .table()
+            .subscribed
+            .entry(topic.to_string())
+            .or_default() += 1;
+    }
+
+    pub fn remove_subscriber(&self, topic: &str) {
+        let mut table = self.table();
+        if let Some(n) = table.subscribed.get_mut(topic) {
+            *n = n.saturating_sub(1);
+            if *n == 0 {
+                table.subscribed.remove(topic);
+            }
+        }
+    }
+}
+
+/// A ROS graph name segment: `[A-Za-z0-9_]`, not starting with a digit.
+/// Telemetry channel names are free text (`motor.current`, `Battery SoC`).
+pub fn ros_name(raw: &str) -> String {
+    let mut out: String = raw
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+/// `/lunco/<api_id>/<leaf>`.
+pub fn entity_topic(api_id: u64, leaf: &str) -> String {
+    format!("{ROS_NAMESPACE}/{api_id}/{leaf}")
+}
+
+/// The entity and leaf of an `/lunco/<api_id>/<leaf>` topic.
+pub fn parse_entity_topic(topic: &str) -> Option<(u64, &str)> {
+    let rest = topic.strip_prefix(ROS_NAMESPACE)?.strip_prefix('/')?;
+    let (id, leaf) = rest.split_once('/')?;
+    Some((id.parse().ok()?, leaf))
+}
+
+/// Where a telemetry channel is published: under its owning entity when it
+/// has one, else under `/lunco/telemetry/`.
+pub fn telemetry_topic(source: Option<u64>, name: &str) -> String {
+    match source {
+        Some(id) => entity_topic(id, &format!("telemetry/{}", ros_name(name))),
+        None => format!("{ROS_NAMESPACE}/telemetry/{}", ros_name(name)),
+    }
+}
+
+/// `std_msgs` wrapper for a telemetry value: `(type, msg)`.
+pub fn telemetry_message(value: &serde_json::Value) -> (&'static str, serde_json::Value) {
+    let ty = match value {
+        serde_json::Value::Bool(_) => "std_msgs/Bool",
+        serde_json::Value::String(_) => "std_msgs/String",
+        serde_json::Value::Number(n) if n.is_i64() => "std_msgs/Int64",
+        _ => "std_msgs/Float64",
+    };
+    (ty, serde_json::json!({ "data": value }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn close(a: Vector3, b: [f64; 3]) -> bool {
+        (a.x - b[0]).abs() < 1e-12 && (a.y - b[1]).abs() < 1e-12 && (a.z - b[2]).abs() < 1e-12
+    }
+
+    /// REP-103 in one table: forward, left and up land on x, y and z.
+    #[test]
+    fn canonical_axes_land_on_rep103() {
+        assert!(close(RosBasis::vector(DVec3::NEG_Z), [1.0, 0.0, 0.0]));
+        assert!(close(RosBasis::vector(DVec3::NEG_X), [0.0, 1.0, 0.0]));
+        assert!(close(RosBasis::vector(DVec3::Y), [0.0, 0.0, 1.0]));
+        let v = DVec3::new(1.0, 2.0, 3.0);
+        assert!(RosBasis::to_canonical(RosBasis::vector(v)).abs_diff_eq(v, 1e-12));
+    }
+
+    /// A body yawed left about our up axis is yawed left about ROS's: same
+    /// angle, positive about +Z.
+    #[test]
+    fn a_left_yaw_is_a_positive_ros_yaw() {
+        let q = RosBasis::quaternion(DQuat::from_rotation_y(0.5));
+        let ros = DQuat::from_xyzw(q.x, q.y, q.z, q.w);
+        assert!(ros.abs_diff_eq(DQuat::from_rotation_z(0.5), 1e-12));
+    }
+
+    #[test]
+    fn topic_names_round_trip_and_sanitize() {
+        assert_eq!(entity_topic(7, "odom"), "/lunco/7/odom");
+        assert_eq!(parse_entity_topic("/lunco/7/odom"), Some((7, "odom")));
+        assert_eq!(parse_entity_topic("/other/7/odom"), None);
+        assert_eq!(
+            telemetry_topic(Some(7), "motor.current"),
+            "/lunco/7/telemetry/motor_current"
+        );
+        assert_eq!(ros_name("3d fix"), "_3d_fix");
+    }
+
+    #[test]
+    fn point_cloud_layout_is_packed_xyz() {
+        let cloud = PointCloud2::xyz(Header::default(), &[[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+        assert_eq!((cloud.width, cloud.point_step, cloud.row_step), (2, 12, 24));
+        use base64::Engine as _;
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(&cloud.data)
+            .unwrap();
+        assert_eq!(bytes.len(), 24);
+        assert_eq!(f32::from_le_bytes(bytes[20..24].try_into().unwrap()), 6.0);
+    }
+}
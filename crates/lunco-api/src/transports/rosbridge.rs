@@ -0,0 +1,672 @@
+//! rosbridge v2 — ROS 2 clients over JSON-on-WebSocket.
+//!
+//! A stock rosbridge client (`roslibpy`, `roslibjs`, the `rosbridge_client`
+//! relay a ROS 2 nav stack runs) connects to `ws://127.0.0.1:<port>/` and
+//! speaks the v2 protocol's `op`s; no ROS install is needed on our side.
+//!
+//! | op | what it does here |
+//! |---|---|
+//! | `subscribe` / `unsubscribe` | stream a topic, honouring `throttle_rate` (ms) |
+//! | `advertise` / `publish` | a `geometry_msgs/Twist` on `/lunco/<api_id>/cmd_vel` becomes `SetPorts`; every publish is also relayed to other clients |
+//! | `call_service` | `/rosapi/topics`, `/rosapi/topic_type` |
+//!
+//! What streams:
+//! - **Telemetry** — `/lunco/<api_id>/telemetry/<channel>` as `std_msgs`
+//!   scalars. Subscribing opens an ordinary `SubscribeTelemetry` for the
+//!   channel, so the decimation and filtering are the API's own; the channel
+//!   dictionary is the `ListTelemetryChannels` query. A sample goes only to
+//!   the clients whose subscriptions ([`RosbridgeSubscriptions`]) asked for it.
+//! - **Sensors** — whatever a domain crate triggers as a [`RosPublish`]
+//!   (`nav_msgs/Odometry`, `sensor_msgs/Imu`, `sensor_msgs/PointCloud2` from
+//!   `lunco-mobility`). They are advertised in [`RosTopics`], which is also how
+//!   a producer learns someone is listening.
+//!
+//! Everything reaches the world through the same [`HttpBridge`] funnel as HTTP
+//! — a `Twist` is a `SetPorts` like any keyboard or script input.
+//!
+//! Loopback-only, like the HTTP server, and for the same (deferred) reason:
+//! there is no auth on this socket.
+
+use super::ros::{
+    parse_entity_topic, telemetry_message, telemetry_topic, RosMessage, RosPublish, RosTopics,
+    Twist,
+};
+use super::HttpBridge;
+use crate::executor::ApiResponseEvent;
+use crate::schema::{ApiRequest, ApiResponse, TelemetryFilter};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::State;
+use axum::response::Response;
+use bevy::prelude::*;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::broadcast;
+
+/// rosbridge_server's own default, so a client's stock URL works unchanged.
+pub const DEFAULT_ROSBRIDGE_PORT: u16 = 9090;
+
+/// How a `geometry_msgs/Twist` becomes port writes. ROS speaks m/s and rad/s;
+/// our drive surfaces take normalized `[-1, 1]` commands, so each axis is one
+/// port and one scale, and the product is clamped.
+#[derive(Debug, Clone)]
+pub struct TwistMapping {
+    /// Port fed by `linear.x` (forward).
+    pub linear_port: String,
+    /// Port value per m/s.
+    pub linear_scale: f64,
+    /// Port fed by `angular.z` (yaw, positive = left).
+    pub angular_port: String,
+    /// Port value per rad/s. Negative by default: our `steer` is positive to
+    /// the RIGHT (`skid_mix_norm`), ROS yaw is positive to the left.
+    pub angular_scale: f64,
+}
+
+impl Default for TwistMapping {
+    fn default() -> Self {
+        Self {
+            linear_port: "throttle".to_string(),
+            linear_scale: 1.0,
+            angular_port: "steer".to_string(),
+            angular_scale: -1.0,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RosbridgeConfig {
+    pub port: u16,
+    pub twist: TwistMapping,
+}
+
+/// One message on its way to the connected clients that subscribed to it.
+#[derive(Debug, Clone)]
+pub struct RosOutbound {
+    pub topic: String,
+    pub msg_type: String,
+    pub msg: Value,
+    /// The one connection this is for: a telemetry sample goes only to the
+    /// client whose subscription it answers. `None` for sensor and relayed
+    /// messages, which every subscriber of `topic` takes.
+    pub client: Option<u64>,
+}
+
+/// ECS → transport fan-out. A `broadcast` because every connection filters the
+/// same stream by its own subscriptions; a lagging client drops its oldest
+/// messages rather than stalling the world.
+#[derive(Resource, Clone)]
+pub struct RosbridgeOutbound(pub broadcast::Sender<RosOutbound>);
+
+/// The `SubscribeTelemetry` ids this transport opened, each with the
+/// connection and channel it belongs to. The telemetry stream is shared by
+/// every transport, so this is how [`forward_telemetry_to_ros`] tells a sample
+/// a ROS client asked for from one an HTTP subscriber did, and which client to
+/// hand it to.
+#[derive(Resource, Clone, Default)]
+pub struct RosbridgeSubscriptions(Arc<Mutex<HashMap<u64, RosSubscriber>>>);
+
+struct RosSubscriber {
+    client: u64,
+    channel: String,
+}
+
+impl RosbridgeSubscriptions {
+    fn table(&self) -> MutexGuard<'_, HashMap<u64, RosSubscriber>> {
+        // Same reasoning as `RosTopics::table`: the map is valid after a panic.
+        self.0.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn insert(&self, id: u64, client: u64, channel: String) {
+        self.table().insert(id, RosSubscriber { client, channel });
+    }
+
+    fn remove(&self, id: u64) {
+        self.table().remove(&id);
+    }
+
+    /// Every client subscribed to `channel`, once each. A client holding two
+    /// topics on one channel (two rovers' `motor_current`) gets one copy and
+    /// picks the topic by `source`.
+    fn clients_of(&self, channel: &str) -> Vec<u64> {
+        let mut clients: Vec<u64> = self
+            .table()
+            .values()
+            .filter(|s| s.channel == channel)
+            .map(|s| s.client)
+            .collect();
+        clients.sort_unstable();
+        clients.dedup();
+        clients
+    }
+}
+
+/// Telemetry responses → their `std_msgs` topic, for the clients that
+/// subscribed to the channel.
+pub fn forward_telemetry_to_ros(
+    trigger: On<ApiResponseEvent>,
+    out: Res<RosbridgeOutbound>,
+    subscriptions: Res<RosbridgeSubscriptions>,
+) {
+    let ApiResponse::TelemetryEvent(t) = &trigger.event().response else {
+        return;
+    };
+    let clients = subscriptions.clients_of(&t.name);
+    if clients.is_empty() {
+        return;
+    }
+    let topic = telemetry_topic(t.source, &t.name);
+    let (msg_type, msg) = telemetry_message(&t.value);
+    for client in clients {
+        // Ignored by design: `Err` only means no client is connected right now.
+        let _ = out.0.send(RosOutbound {
+            topic: topic.clone(),
+            msg_type: msg_type.to_string(),
+            msg: msg.clone(),
+            client: Some(client),
+        });
+    }
+}
+
+/// Domain-published messages ([`RosPublish`]) → the wire.
+pub fn forward_ros_publish(trigger: On<RosPublish>, out: Res<RosbridgeOutbound>) {
+    let p = trigger.event();
+    // Ignored by design, as above.
+    let _ = out.0.send(RosOutbound {
+        topic: p.topic.clone(),
+        msg_type: p.msg_type.clone(),
+        msg: p.msg.clone(),
+        client: None,
+    });
+}
+
+#[derive(Clone)]
+struct RosbridgeState {
+    bridge: HttpBridge,
+    topics: RosTopics,
+    subscriptions: RosbridgeSubscriptions,
+    outbound: broadcast::Sender<RosOutbound>,
+    twist: TwistMapping,
+    next_client: Arc<AtomicU64>,
+}
+
+/// Native WebSocket server on its own thread — the same shape, and the same
+/// reasoning, as [`super::spawn_server`].
+#[allow(clippy::disallowed_methods)]
+pub fn spawn_rosbridge_server(
+    config: RosbridgeConfig,
+    bridge: HttpBridge,
+    topics: RosTopics,
+    subscriptions: RosbridgeSubscriptions,
+    outbound: broadcast::Sender<RosOutbound>,
+) {
+    let port = config.port;
+    let state = RosbridgeState {
+        bridge,
+        topics,
+        subscriptions,
+        outbound,
+        twist: config.twist,
+        next_client: Arc::default(),
+    };
+    std::thread::spawn(move || {
+        let rt = match tokio::runtime::Runtime::new() {
+            Ok(rt) => rt,
+            Err(e) => {
+                bevy::log::error!("[lunco-api] failed to start rosbridge runtime: {e}");
+                return;
+            }
+        };
+        rt.block_on(async move {
+            let app = axum::Router::new()
+                .route("/", axum::routing::get(handle_upgrade))
+                .with_state(state);
+            let listener = match tokio::net::TcpListener::bind(format!("127.0.0.1:{port}")).await {
+                Ok(l) => l,
+                Err(e) => {
+                    bevy::log::error!(
+                        "[lunco-api] rosbridge failed to bind 127.0.0.1:{port}: {e} \
+                         (port already in use?) — ROS clients cannot connect"
+                    );
+                    return;
+                }
+            };
+            bevy::log::info!("[lunco-api] rosbridge v2 listening on ws://127.0.0.1:{port}/");
+            if let Err(e) = axum::serve(listener, app).await {
+                bevy::log::error!("[lunco-api] rosbridge server stopped with error: {e}");
+            }
+        });
+    });
+}
+
+async fn handle_upgrade(ws: WebSocketUpgrade, State(state): State<RosbridgeState>) -> Response {
+    ws.on_upgrade(move |socket| serve_client(socket, state))
+}
+
+/// The client → server half of the protocol. Unknown ops decode as `Unknown`
+/// and are answered with a status error, as rosbridge_server does. Fields we
+/// have no use for (`id` on fire-and-forget ops, `type` on `subscribe`,
+/// `queue_length`) are accepted and ignored.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum ClientOp {
+    Advertise {
+        topic: String,
+        #[serde(rename = "type")]
+        msg_type: String,
+    },
+    Unadvertise {
+        topic: String,
+    },
+    Publish {
+        topic: String,
+        msg: Value,
+        #[serde(default)]
+        id: Option<Value>,
+    },
+    Subscribe {
+        topic: String,
+        #[serde(default)]
+        throttle_rate: Option<u64>,
+        #[serde(default)]
+        id: Option<Value>,
+    },
+    Unsubscribe {
+        topic: String,
+    },
+    CallService {
+        service: String,
+        #[serde(default)]
+        args: Option<Value>,
+        #[serde(default)]
+        id: Option<Value>,
+    },
+    SetLevel,
+    #[serde(other)]
+    Unknown,
+}
+
+struct Subscription {
+    throttle: Duration,
+    last: Option<tokio::time::Instant>,
+    /// The `SubscribeTelemetry` id backing a telemetry topic.
+    telemetry: Option<u64>,
+}
+
+/// One connection's view of the graph.
+struct Client {
+    /// Keys this connection's rows in [`RosbridgeSubscriptions`].
+    id: u64,
+    state: RosbridgeState,
+    subs: HashMap<String, Subscription>,
+    advertised: HashMap<String, String>,
+    /// Telemetry topic → channel name, from the last `ListTelemetryChannels`.
+    dictionary: HashMap<String, String>,
+}
+
+async fn serve_client(mut socket: WebSocket, state: RosbridgeState) {
+    let mut outbound = state.outbound.subscribe();
+    let mut client = Client {
+        id: state.next_client.fetch_add(1, Ordering::Relaxed),
+        state,
+        subs: HashMap::new(),
+        advertised: HashMap::new(),
+        dictionary: HashMap::new(),
+    };
+    'connection: loop {
+        tokio::select! {
+            incoming = socket.recv() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text.to_string(),
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break 'connection,
+                    // Binary (CBOR/BSON compression) is not negotiated; pings are
+                    // answered by axum.
+                    Some(Ok(_)) => continue,
+                };
+                for reply in client.handle(&text).await {
+                    if socket.send(Message::Text(reply.to_string().into())).await.is_err() {
+                        break 'connection;
+                    }
+                }
+            }
+            msg = outbound.recv() => match msg {
+                Ok(m) => {
+                    if m.client.is_none_or(|c| c == client.id) && client.due(&m.topic) {
+                        let frame = json!({ "op": "publish", "topic": m.topic, "msg": m.msg });
+                        if socket.send(Message::Text(frame.to_string().into())).await.is_err() {
+                            break 'connection;
+                        }
+                    }
+                }
+                // A slow client skips what it missed; the next message is current.
+                Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                Err(broadcast::error::RecvError::Closed) => break 'connection,
+            },
+        }
+    }
+    client.close().await;
+}
+
+fn status(level: &str, msg: impl Into<String>, id: Option<Value>) -> Value {
+    let mut frame = json!({ "op": "status", "level": level, "msg": msg.into() });
+    if let Some(id) = id {
+        frame["id"] = id;
+    }
+    frame
+}
+
+impl Client {
+    async fn handle(&mut self, text: &str) -> Vec<Value> {
+        let op: ClientOp = match serde_json::from_str(text) {
+            Ok(op) => op,
+            Err(e) => return vec![status("error", format!("malformed op: {e}"), None)],
+        };
+        match op {
+            ClientOp::Subscribe {
+                topic,
+                throttle_rate,
+                id,
+            } => self.subscribe(topic, throttle_rate.unwrap_or(0), id).await,
+            ClientOp::Unsubscribe { topic } => {
+                self.unsubscribe(&topic).await;
+                vec![]
+            }
+            ClientOp::Advertise { topic, msg_type } => {
+                self.advertised.insert(topic, msg_type);
+                vec![]
+            }
+            ClientOp::Unadvertise { topic } => {
+                self.advertised.remove(&topic);
+                vec![]
+            }
+            ClientOp::Publish { topic, msg, id } => self.publish(topic, msg, id).await,
+            ClientOp::CallService { service, args, id } => {
+                vec![self.call_service(&service, args, id).await]
+            }
+            // Log verbosity is ours to pick; accept and ignore.
+            ClientOp::SetLevel => vec![],
+            ClientOp::Unknown => vec![status("error", "unsupported op", None)],
+        }
+    }
+
+    /// Throttle gate for one outbound message: subscribed, and `throttle_rate`
+    /// elapsed since the last one sent on this topic.
+    fn due(&mut self, topic: &str) -> bool {
+        let Some(sub) = self.subs.get_mut(topic) else {
+            return false;
+        };
+        let now = tokio::time::Instant::now();
+        if sub.last.is_some_and(|last| now - last < sub.throttle) {
+            return false;
+        }
+        sub.last = Some(now);
+        true
+    }
+
+    async fn subscribe(
+        &mut self,
+        topic: String,
+        throttle_ms: u64,
+        id: Option<Value>,
+    ) -> Vec<Value> {
+        let throttle = Duration::from_millis(throttle_ms);
+        if let Some(sub) = self.subs.get_mut(&topic) {
+            sub.throttle = throttle;
+            return vec![];
+        }
+        let mut replies = vec![];
+        let telemetry = match self.channel_of(&topic).await {
+            Some(name) => {
+                let request = ApiRequest::SubscribeTelemetry {
+                    filter: Some(TelemetryFilter {
+                        names: vec![name.clone()],
+                        ..Default::default()
+                    }),
+                };
+                match self.state.bridge.execute(request).await {
+                    Ok(ApiResponse::Ok { data: Some(d) }) => {
+                        let sub = d["subscription_id"].as_u64();
+                        if let Some(sub) = sub {
+                            self.state.subscriptions.insert(sub, self.id, name);
+                        }
+                        sub
+                    }
+                    _ => {
+                        replies.push(status("error", "telemetry subscription failed", id));
+                        return replies;
+                    }
+                }
+            }
+            None => {
+                if self.state.topics.type_of(&topic).is_none() {
+                    // Not an error: a sensor advertises when its body spawns, and a
+                    // client relaying another client's topic subscribes first.
+                    replies.push(status(
+                        "warning",
+                        format!("{topic} is not advertised yet; it streams once published"),
+                        id,
+                    ));
+                }
+                None
+            }
+        };
+        self.state.topics.add_subscriber(&topic);
+        self.subs.insert(
+            topic,
+            Subscription {
+                throttle,
+                last: None,
+                telemetry,
+            },
+        );
+        replies
+    }
+
+    async fn unsubscribe(&mut self, topic: &str) {
+        let Some(sub) = self.subs.remove(topic) else {
+            return;
+        };
+        self.state.topics.remove_subscriber(topic);
+        if let Some(id) = sub.telemetry {
+            self.state.subscriptions.remove(id);
+            // Ignored by design: `Err` means the app is shutting down, which
+            // drops every subscription anyway.
+            let _ = self
+                .state
+                .bridge
+                .execute(ApiRequest::UnsubscribeTelemetry { id })
+                .await;
+        }
+    }
+
+    /// A dropped connection releases everything it held — the telemetry rows
+    /// are LOCAL-owned, so nothing else would ever reap them.
+    async fn close(&mut self) {
+        let topics: Vec<String> = self.subs.keys().cloned().collect();
+        for topic in topics {
+            self.unsubscribe(&topic).await;
+        }
+    }
+
+    /// The telemetry channel behind `topic`, refreshing the dictionary once on
+    /// a miss (a channel may have appeared since the last look).
+    async fn channel_of(&mut self, topic: &str) -> Option<String> {
+        if !topic.contains("/telemetry/") {
+            return None;
+        }
+        if !self.dictionary.contains_key(topic) {
+            self.refresh_dictionary().await;
+        }
+        self.dictionary.get(topic).cloned()
+    }
+
+    async fn refresh_dictionary(&mut self) {
+        let request = ApiRequest::ExecuteCommand {
+            command: "ListTelemetryChannels".to_string(),
+            params: json!({}),
+        };
+        let Ok(ApiResponse::Ok { data: Some(data) }) = self.state.bridge.execute(request).await
+        else {
+            // No telemetry crate in this app: there are no telemetry topics.
+            return;
+        };
+        self.dictionary = data["channels"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .filter_map(|c| {
+                let name = c["name"].as_str()?;
+                Some((
+                    telemetry_topic(c["source"].as_u64(), name),
+                    name.to_string(),
+                ))
+            })
+            .collect();
+    }
+
+    async fn publish(&mut self, topic: String, msg: Value, id: Option<Value>) -> Vec<Value> {
+        let msg_type = self.advertised.get(&topic).cloned().unwrap_or_default();
+        let mut replies = vec![];
+        let is_twist = msg_type == Twist::TYPE
+            || (msg_type.is_empty()
+                && parse_entity_topic(&topic).is_some_and(|(_, l)| l == "cmd_vel"));
+        if is_twist {
+            if let Some(error) = self.drive(&topic, &msg).await {
+                replies.push(status("error", error, id));
+            }
+        }
+        // Relay, as rosbridge does: another client may be listening.
+        // Ignored by design: `Err` only means nobody is.
+        let _ = self.state.outbound.send(RosOutbound {
+            topic,
+            msg_type,
+            msg,
+            client: None,
+        });
+        replies
+    }
+
+    /// `Twist` on `/lunco/<api_id>/cmd_vel` → `SetPorts` on that entity.
+    async fn drive(&self, topic: &str, msg: &Value) -> Option<String> {
+        let Some((target, _)) = parse_entity_topic(topic) else {
+            return Some(format!(
+                "{topic}: a Twist must be published on /lunco/<api_id>/cmd_vel"
+            ));
+        };
+        let twist: Twist = match serde_json::from_value(msg.clone()) {
+            Ok(t) => t,
+            Err(e) => return Some(format!("{topic}: not a geometry_msgs/Twist: {e}")),
+        };
+        let map = &self.state.twist;
+        let writes = [
+            (&map.linear_port, twist.linear.x * map.linear_scale),
+            (&map.angular_port, twist.angular.z * map.angular_scale),
+        ]
+        .map(|(port, v)| json!([port, v.clamp(-1.0, 1.0)]));
+        let request = ApiRequest::ExecuteCommand {
+            command: "SetPorts".to_string(),
+            params: json!({ "target": target, "writes": writes }),
+        };
+        match self.state.bridge.execute(request).await {
+            Ok(ApiResponse::Error { message, .. }) => Some(format!("{topic}: {message}")),
+            Ok(_) => None,
+            Err(()) => Some("the simulation is shutting down".to_string()),
+        }
+    }
+
+    async fn call_service(
+        &mut self,
+        service: &str,
+        args: Option<Value>,
+        id: Option<Value>,
+    ) -> Value {
+        let values = match service {
+            "/rosapi/topics" => {
+                self.refresh_dictionary().await;
+                let mut all = self.state.topics.advertised();
+                all.extend(
+                    self.dictionary
+                        .keys()
+                        .map(|t| (t.clone(), "std_msgs/Float64".to_string())),
+                );
+                all.extend(
+                    self.advertised
+                        .iter()
+                        .map(|(t, ty)| (t.clone(), ty.clone())),
+                );
+                all.sort();
+                all.dedup_by(|a, b| a.0 == b.0);
+                let (topics, types): (Vec<_>, Vec<_>) = all.into_iter().unzip();
+                Some(json!({ "topics": topics, "types": types }))
+            }
+            "/rosapi/topic_type" => {
+                let topic = args
+                    .as_ref()
+                    .and_then(|a| a["topic"].as_str())
+                    .unwrap_or_default()
+                    .to_string();
+                let ty = self
+                    .state
+                    .topics
+                    .type_of(&topic)
+                    .or_else(|| self.advertised.get(&topic).cloned())
+                    .or_else(|| {
+                        self.dictionary
+                            .contains_key(&topic)
+                            .then(|| "std_msgs/Float64".to_string())
+                    })
+                    .unwrap_or_default();
+                Some(json!({ "type": ty }))
+            }
+            _ => None,
+        };
+        let mut frame = json!({
+            "op": "service_response",
+            "service": service,
+            "result": values.is_some(),
+            "values": values.unwrap_or_else(|| json!(format!("unknown service {service}"))),
+        });
+        if let Some(id) = id {
+            frame["id"] = id;
+        }
+        frame
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn client_ops_decode_from_stock_frames() {
+        let op: ClientOp = serde_json::from_str(
+            r#"{"op":"subscribe","id":"subscribe:/odom:1","topic":"/lunco/3/odom","type":"nav_msgs/Odometry","throttle_rate":100}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            op,
+            ClientOp::Subscribe { ref topic, throttle_rate: Some(100), .. } if topic == "/lunco/3/odom"
+        ));
+        let op: ClientOp =
+            serde_json::from_str(r#"{"op":"fragment","id":"x","data":"","num":0,"total":2}"#)
+                .unwrap();
+        assert!(matches!(op, ClientOp::Unknown));
+    }
+
+    #[test]
+    fn telemetry_routes_to_the_subscribing_clients_only() {
+        let subs = RosbridgeSubscriptions::default();
+        subs.insert(7, 1, "battery_soc".into());
+        subs.insert(8, 2, "motor_current".into());
+        // Client 2 holds two rovers' `motor_current`: one copy, not two.
+        subs.insert(9, 2, "motor_current".into());
+        subs.insert(10, 3, "motor_current".into());
+        assert_eq!(subs.clients_of("battery_soc"), vec![1]);
+        assert_eq!(subs.clients_of("motor_current"), vec![2, 3]);
+        assert!(subs.clients_of("cmd:SpawnEntity").is_empty());
+        subs.remove(10);
+        assert_eq!(subs.clients_of("motor_current"), vec![2]);
+    }
+}
@@ -51,6 +51,9 @@ pub mod font;
 /// git-tracked content and externally-fetched binaries without any authored
 /// file naming the cache. See `docs/architecture/56-asset-resolution-and-cache.md`.
 pub mod lunco_source;
+/// OBJ/MTL, STL and glTF-material decode into one neutral model — what a USD
+/// `references` arc to a vendor mesh becomes before `lunco-usd-bevy` meshes it.
+pub mod mesh_import;
 pub mod missions;
 pub mod models;
 pub mod msl;
@@ -0,0 +1,653 @@
+//! Vendor mesh formats — OBJ (+ MTL), STL, and the material table of a glTF —
+//! decoded to one neutral triangle-soup model.
+//!
+//! Why this lives HERE: a CAD or vendor model is referenced from USD exactly like
+//! a `.glb` (`prepend references = @…/part.stl@`), and every byte of it arrives
+//! through this crate's asset sources. Bevy ships a glTF loader but nothing for
+//! OBJ or STL, and its glTF loader only builds materials when `bevy_pbr` is
+//! linked — which the headless server never does. So the FORMAT knowledge sits
+//! here, free of Bevy, and `lunco-usd-bevy` turns an [`ImportedModel`] into
+//! `Mesh` assets and `PbrLook`s.
+//!
+//! Scope:
+//! - **OBJ**: `v`/`vt`/`vn`/`f` (any polygon, fan-triangulated; negative
+//!   indices), split into one mesh per `usemtl`. `mtllib` names are returned for
+//!   the caller to fetch; [`apply_mtl`] fills the materials in. Groups/objects
+//!   (`g`/`o`) do not split — a part's identity is its material.
+//! - **STL**: binary and ASCII, flat-shaded. STL carries no unit; a millimetre
+//!   CAD export is scaled by the referencing prim's `xformOp:scale`, as any
+//!   other unit change is in USD.
+//! - **glTF / GLB**: the material table and which material each
+//!   `Mesh{m}/Primitive{p}` uses. Geometry stays with Bevy's glTF loader.
+
+use std::collections::HashMap;
+
+/// A source format this module decodes, by file extension.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MeshFormat {
+    Obj,
+    Stl,
+    Gltf,
+    Glb,
+}
+
+impl MeshFormat {
+    /// The format `uri` names, ignoring any `?query` / `#label`. `None` for
+    /// anything else (USD layers included).
+    pub fn from_uri(uri: &str) -> Option<Self> {
+        let stem = uri.split(['?', '#']).next().unwrap_or(uri);
+        let ext = stem.rsplit_once('.')?.1;
+        [
+            ("obj", Self::Obj),
+            ("stl", Self::Stl),
+            ("gltf", Self::Gltf),
+            ("glb", Self::Glb),
+        ]
+        .into_iter()
+        .find(|(e, _)| e.eq_ignore_ascii_case(ext))
+        .map(|(_, f)| f)
+    }
+
+    /// Formats whose geometry this module decodes (glTF's is Bevy's).
+    pub fn has_own_geometry(self) -> bool {
+        matches!(self, Self::Obj | Self::Stl)
+    }
+}
+
+/// Why a file could not be decoded.
+#[derive(Debug, thiserror::Error)]
+pub enum MeshImportError {
+    #[error("line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("truncated binary STL: header declares {declared} triangles, {present} present")]
+    TruncatedStl { declared: usize, present: usize },
+    #[error("not UTF-8 text")]
+    NotText,
+    #[error("glTF: {0}")]
+    Gltf(String),
+}
+
+/// Transparency, as glTF states it (OBJ/MTL `d` < 1 maps to `Blend`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ImportedAlpha {
+    #[default]
+    Opaque,
+    Mask(f32),
+    Blend,
+}
+
+/// A metallic-roughness surface. Colours are linear.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedMaterial {
+    pub name: String,
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    pub emissive: [f32; 3],
+    /// Relative to the model file.
+    pub base_color_texture: Option<String>,
+    pub alpha: ImportedAlpha,
+    pub double_sided: bool,
+}
+
+impl ImportedMaterial {
+    /// glTF's defaults, which are also the sensible reading of an empty MTL entry
+    /// — except metallic, which glTF defaults to 1 and every non-glTF source
+    /// means as 0.
+    pub fn named(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            base_color: [1.0; 4],
+            metallic: 0.0,
+            roughness: 1.0,
+            emissive: [0.0; 3],
+            base_color_texture: None,
+            alpha: ImportedAlpha::Opaque,
+            double_sided: false,
+        }
+    }
+}
+
+/// One indexed triangle list. `normals`/`uvs` are per-vertex or empty.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportedMesh {
+    pub positions: Vec<[f32; 3]>,
+    pub normals: Vec<[f32; 3]>,
+    pub uvs: Vec<[f32; 2]>,
+    pub indices: Vec<u32>,
+    /// Index into [`ImportedModel::materials`].
+    pub material: Option<usize>,
+}
+
+/// A decoded file: its meshes and the materials they use.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct ImportedModel {
+    pub meshes: Vec<ImportedMesh>,
+    pub materials: Vec<ImportedMaterial>,
+    /// OBJ `mtllib` files, relative to the model, for the caller to fetch and
+    /// hand to [`apply_mtl`].
+    pub material_libs: Vec<String>,
+}
+
+impl ImportedModel {
+    /// Every position of every mesh — the point cloud a convex hull is built from.
+    pub fn all_positions(&self) -> impl Iterator<Item = [f32; 3]> + '_ {
+        self.meshes.iter().flat_map(|m| m.positions.iter().copied())
+    }
+}
+
+// ─── OBJ ─────────────────────────────────────────────────────────────────────
+
+/// Decode a Wavefront OBJ. Materials are created by name from `usemtl` with
+/// default values; [`apply_mtl`] replaces them with the library's.
+pub fn parse_obj(text: &str) -> Result<ImportedModel, MeshImportError> {
+    let mut positions: Vec<[f32; 3]> = Vec::new();
+    let mut uvs: Vec<[f32; 2]> = Vec::new();
+    let mut normals: Vec<[f32; 3]> = Vec::new();
+    let mut model = ImportedModel::default();
+    // One mesh per material; a vertex is unique per (v, vt, vn) within it.
+    let mut meshes: Vec<(ImportedMesh, HashMap<ObjCorner, u32>)> = vec![Default::default()];
+    let mut current = 0usize;
+
+    for (n, raw) in text.lines().enumerate() {
+        let line = n + 1;
+        let syntax = |message: &str| MeshImportError::Syntax {
+            line,
+            message: message.to_string(),
+        };
+        let mut words = raw.split('#').next().unwrap_or("").split_whitespace();
+        let Some(tag) = words.next() else { continue };
+        match tag {
+            "v" => positions.push(floats::<3>(&mut words).ok_or_else(|| syntax("bad `v`"))?),
+            "vn" => normals.push(floats::<3>(&mut words).ok_or_else(|| syntax("bad `vn`"))?),
+            "vt" => {
+                let [u, v] = floats::<2>(&mut words).ok_or_else(|| syntax("bad `vt`"))?;
+                // OBJ's V runs up; every GPU convention runs down.
+                uvs.push([u, 1.0 - v]);
+            }
+            "usemtl" => {
+                let name = words.collect::<Vec<_>>().join(" ");
+                let material = match model.materials.iter().position(|m| m.name == name) {
+                    Some(i) => i,
+                    None => {
+                        model.materials.push(ImportedMaterial::named(&name));
+                        model.materials.len() - 1
+                    }
+                };
+                current = match meshes
+                    .iter()
+                    .position(|(m, _)| m.material == Some(material))
+                {
+                    Some(i) => i,
+                    None => {
+                        meshes.push((
+                            ImportedMesh {
+                                material: Some(material),
+                                ..Default::default()
+                            },
+                            HashMap::new(),
+                        ));
+                        meshes.len() - 1
+                    }
+                };
+            }
+            "mtllib" => model
+                .material_libs
+                .push(words.collect::<Vec<_>>().join(" ")),
+            "f" => {
+                let (mesh, seen) = &mut meshes[current];
+                let mut corners = Vec::new();
+                for word in words {
+                    let mut parts = word.split('/');
+                    let v = index(parts.next(), positions.len())
+                        .ok_or_else(|| syntax("bad vertex index"))?;
+                    let vt = match parts.next() {
+                        None | Some("") => None,
+                        t => Some(index(t, uvs.len()).ok_or_else(|| syntax("bad `vt` index"))?),
+                    };
+                    let vn = match parts.next() {
+                        None | Some("") => None,
+                        t => Some(index(t, normals.len()).ok_or_else(|| syntax("bad `vn` index"))?),
+                    };
+                    let key = (v, vt, vn);
+                    let i = *seen.entry(key).or_insert_with(|| {
+                        mesh.positions.push(positions[v]);
+                        if let Some(t) = vt {
+                            mesh.uvs.push(uvs[t]);
+                        }
+                        if let Some(nn) = vn {
+                            mesh.normals.push(normals[nn]);
+                        }
+                        (mesh.positions.len() - 1) as u32
+                    });
+                    corners.push(i);
+                }
+                if corners.len() < 3 {
+                    return Err(syntax("face with fewer than three corners"));
+                }
+                for k in 1..corners.len() - 1 {
+                    mesh.indices
+                        .extend([corners[0], corners[k], corners[k + 1]]);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (mut mesh, _) in meshes {
+        if mesh.indices.is_empty() {
+            continue;
+        }
+        // A file that gives texture coordinates or normals to only SOME
+        // corners has none that can be trusted per-vertex.
+        if mesh.uvs.len() != mesh.positions.len() {
+            mesh.uvs.clear();
+        }
+        if mesh.normals.len() != mesh.positions.len() {
+            mesh.normals.clear();
+        }
+        model.meshes.push(mesh);
+    }
+    Ok(model)
+}
+
+/// Fill `model`'s materials from an MTL library. Names the library does not
+/// define keep their defaults; extra library entries are ignored.
+///
+/// Phong → metallic-roughness is the usual approximation: `Kd` is the base
+/// colour, `Ns` (0–1000) maps to roughness `√(2/(Ns+2))`, `d`/`Tr` to alpha.
+/// The PBR extension keys (`Pr`, `Pm`, `Ke`) win where present.
+pub fn apply_mtl(model: &mut ImportedModel, text: &str) {
+    let mut current: Option<usize> = None;
+    for raw in text.lines() {
+        let mut words = raw.split('#').next().unwrap_or("").split_whitespace();
+        let Some(tag) = words.next() else { continue };
+        if tag == "newmtl" {
+            let name = words.collect::<Vec<_>>().join(" ");
+            current = model.materials.iter().position(|m| m.name == name);
+            continue;
+        }
+        let Some(m) = current.map(|i| &mut model.materials[i]) else {
+            continue;
+        };
+        match tag {
+            "Kd" => {
+                if let Some([r, g, b]) = floats::<3>(&mut words) {
+                    m.base_color = [r, g, b, m.base_color[3]];
+                }
+            }
+            "Ke" => {
+                if let Some(e) = floats::<3>(&mut words) {
+                    m.emissive = e;
+                }
+            }
+            "Ns" => {
+                if let Some([ns]) = floats::<1>(&mut words) {
+                    m.roughness = (2.0 / (ns.max(0.0) + 2.0)).sqrt();
+                }
+            }
+            "Pr" => {
+                if let Some([r]) = floats::<1>(&mut words) {
+                    m.roughness = r.clamp(0.0, 1.0);
+                }
+            }
+            "Pm" => {
+                if let Some([v]) = floats::<1>(&mut words) {
+                    m.metallic = v.clamp(0.0, 1.0);
+                }
+            }
+            "d" | "Tr" => {
+                if let Some([v]) = floats::<1>(&mut words) {
+                    let a = if tag == "Tr" { 1.0 - v } else { v }.clamp(0.0, 1.0);
+                    m.base_color[3] = a;
+                    m.alpha = if a < 1.0 {
+                        ImportedAlpha::Blend
+                    } else {
+                        ImportedAlpha::Opaque
+                    };
+                }
+            }
+            // The file name is the LAST word; options (`-s 1 1 1`) precede it.
+            "map_Kd" => m.base_color_texture = raw.split_whitespace().last().map(str::to_string),
+            _ => {}
+        }
+    }
+}
+
+/// An OBJ face corner: `(v, vt, vn)`, 0-based.
+type ObjCorner = (usize, Option<usize>, Option<usize>);
+
+/// `N` floats from the front of `words`.
+fn floats<'a, const N: usize>(words: &mut impl Iterator<Item = &'a str>) -> Option<[f32; N]> {
+    let mut out = [0.0; N];
+    for v in &mut out {
+        *v = words.next()?.parse().ok()?;
+    }
+    Some(out)
+}
+
+/// A 1-based (or negative, from-the-end) OBJ index → 0-based, bounds-checked.
+fn index(word: Option<&str>, len: usize) -> Option<usize> {
+    let i: i64 = word?.parse().ok()?;
+    let i = if i < 0 { len as i64 + i } else { i - 1 };
+    (0..len as i64).contains(&i).then_some(i as usize)
+}
+
+// ─── STL ─────────────────────────────────────────────────────────────────────
+
+/// Decode a binary or ASCII STL as one flat-shaded mesh. The facet normal is
+/// recomputed from the winding when the file leaves it zero, which many
+/// exporters do.
+pub fn parse_stl(bytes: &[u8]) -> Result<ImportedModel, MeshImportError> {
+    // `solid` opens an ASCII file — and, against the spec, the 80-byte header
+    // of plenty of binary ones. The size check is what actually decides.
+    let triangles = if is_binary_stl(bytes) {
+        binary_stl(bytes)?
+    } else {
+        ascii_stl(std::str::from_utf8(bytes).map_err(|_| MeshImportError::NotText)?)?
+    };
+    let mut mesh = ImportedMesh::default();
+    for (normal, corners) in triangles {
+        let n = if normal.iter().any(|c| *c != 0.0) {
+            normal
+        } else {
+            face_normal(&corners)
+        };
+        for c in corners {
+            mesh.indices.push(mesh.positions.len() as u32);
+            mesh.positions.push(c);
+            mesh.normals.push(n);
+        }
+    }
+    Ok(ImportedModel {
+        meshes: vec![mesh],
+        ..Default::default()
+    })
+}
+
+type Facet = ([f32; 3], [[f32; 3]; 3]);
+
+fn is_binary_stl(bytes: &[u8]) -> bool {
+    if bytes.len() < 84 {
+        return false;
+    }
+    let count = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    bytes.len() == 84 + count * 50 || !bytes.starts_with(b"solid")
+}
+
+fn binary_stl(bytes: &[u8]) -> Result<Vec<Facet>, MeshImportError> {
+    let declared = u32::from_le_bytes([bytes[80], bytes[81], bytes[82], bytes[83]]) as usize;
+    let present = (bytes.len() - 84) / 50;
+    if present < declared {
+        return Err(MeshImportError::TruncatedStl { declared, present });
+    }
+    let f =
+        |at: usize| f32::from_le_bytes([bytes[at], bytes[at + 1], bytes[at + 2], bytes[at + 3]]);
+    let v = |at: usize| [f(at), f(at + 4), f(at + 8)];
+    Ok((0..declared)
+        .map(|t| {
+            let at = 84 + t * 50;
+            (v(at), [v(at + 12), v(at + 24), v(at + 36)])
+        })
+        .collect())
+}
+
+fn ascii_stl(text: &str) -> Result<Vec<Facet>, MeshImportError> {
+    let mut out = Vec::new();
+    let mut normal = [0.0; 3];
+    let mut corners = Vec::with_capacity(3);
+    for (n, raw) in text.lines().enumerate() {
+        let mut words = raw.split_whitespace();
+        match words.next() {
+            Some("facet") => {
+                words.next(); // "normal"
+                normal = floats::<3>(&mut words).unwrap_or([0.0; 3]);
+                corners.clear();
+            }
+            Some("vertex") => {
+                corners.push(floats::<3>(&mut words).ok_or(MeshImportError::Syntax {
+                    line: n + 1,
+                    message: "bad `vertex`".into(),
+                })?);
+            }
+            Some("endfacet") => {
+                let [a, b, c] = corners[..] else {
+                    return Err(MeshImportError::Syntax {
+                        line: n + 1,
+                        message: format!("facet with {} vertices", corners.len()),
+                    });
+                };
+                out.push((normal, [a, b, c]));
+            }
+            _ => {}
+        }
+    }
+    Ok(out)
+}
+
+fn face_normal([a, b, c]: &[[f32; 3]; 3]) -> [f32; 3] {
+    let u = [b[0] - a[0], b[1] - a[1], b[2] - a[2]];
+    let v = [c[0] - a[0], c[1] - a[1], c[2] - a[2]];
+    let n = [
+        u[1] * v[2] - u[2] * v[1],
+        u[2] * v[0] - u[0] * v[2],
+        u[0] * v[1] - u[1] * v[0],
+    ];
+    let len = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+    if len > 0.0 {
+        n.map(|c| c / len)
+    } else {
+        [0.0, 1.0, 0.0]
+    }
+}
+
+// ─── glTF materials ──────────────────────────────────────────────────────────
+
+/// The material table of a `.gltf` (JSON) or `.glb` file, and per mesh, per
+/// primitive, the material index it uses. Only externally-referenced
+/// base-colour textures are reported; an embedded one (a `bufferView` image)
+/// has no path to hand back.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct GltfMaterials {
+    pub materials: Vec<ImportedMaterial>,
+    pub primitives: Vec<Vec<Option<usize>>>,
+}
+
+pub fn gltf_materials(bytes: &[u8]) -> Result<GltfMaterials, MeshImportError> {
+    let json = if bytes.starts_with(b"glTF") {
+        glb_json(bytes)?
+    } else {
+        bytes
+    };
+    let doc: serde_json::Value =
+        serde_json::from_slice(json).map_err(|e| MeshImportError::Gltf(e.to_string()))?;
+    let array = |v: &serde_json::Value, key: &str| -> Vec<serde_json::Value> {
+        v.get(key)
+            .and_then(|a| a.as_array())
+            .cloned()
+            .unwrap_or_default()
+    };
+    let num = |v: Option<&serde_json::Value>, default: f32| {
+        v.and_then(|n| n.as_f64()).map_or(default, |n| n as f32)
+    };
+    let textures = array(&doc, "textures");
+    let images = array(&doc, "images");
+    let image_uri = |texture: Option<&serde_json::Value>| -> Option<String> {
+        let t = texture?.get("index")?.as_u64()? as usize;
+        let src = textures.get(t)?.get("source")?.as_u64()? as usize;
+        let uri = images.get(src)?.get("uri")?.as_str()?;
+        (!uri.starts_with("data:")).then(|| uri.to_string())
+    };
+
+    let materials = array(&doc, "materials")
+        .iter()
+        .enumerate()
+        .map(|(i, m)| {
+            let pbr = m.get("pbrMetallicRoughness");
+            let field = |k: &str| pbr.and_then(|p| p.get(k));
+            let mut base_color = [1.0; 4];
+            if let Some(c) = field("baseColorFactor").and_then(|c| c.as_array()) {
+                for (dst, src) in base_color.iter_mut().zip(c) {
+                    *dst = src.as_f64().unwrap_or(1.0) as f32;
+                }
+            }
+            let mut emissive = [0.0; 3];
+            if let Some(c) = m.get("emissiveFactor").and_then(|c| c.as_array()) {
+                for (dst, src) in emissive.iter_mut().zip(c) {
+                    *dst = src.as_f64().unwrap_or(0.0) as f32;
+                }
+            }
+            ImportedMaterial {
+                name: m
+                    .get("name")
+                    .and_then(|n| n.as_str())
+                    .map_or_else(|| format!("Material{i}"), str::to_string),
+                base_color,
+                metallic: num(field("metallicFactor"), 1.0),
+                roughness: num(field("roughnessFactor"), 1.0),
+                emissive,
+                base_color_texture: image_uri(field("baseColorTexture")),
+                alpha: match m.get("alphaMode").and_then(|a| a.as_str()) {
+                    Some("MASK") => ImportedAlpha::Mask(num(m.get("alphaCutoff"), 0.5)),
+                    Some("BLEND") => ImportedAlpha::Blend,
+                    _ => ImportedAlpha::Opaque,
+                },
+                double_sided: m
+                    .get("doubleSided")
+                    .and_then(|d| d.as_bool())
+                    .unwrap_or(false),
+            }
+        })
+        .collect();
+    let primitives = array(&doc, "meshes")
+        .iter()
+        .map(|mesh| {
+            array(mesh, "primitives")
+                .iter()
+                .map(|p| {
+                    p.get("material")
+                        .and_then(|m| m.as_u64())
+                        .map(|m| m as usize)
+                })
+                .collect()
+        })
+        .collect();
+    Ok(GltfMaterials {
+        materials,
+        primitives,
+    })
+}
+
+/// The JSON chunk of a GLB container.
+fn glb_json(bytes: &[u8]) -> Result<&[u8], MeshImportError> {
+    let u32_at = |at: usize| -> Option<usize> {
+        Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?) as usize)
+    };
+    let len = u32_at(12).ok_or_else(|| MeshImportError::Gltf("GLB header truncated".into()))?;
+    if u32_at(16) != Some(0x4E4F_534A) {
+        return Err(MeshImportError::Gltf("first GLB chunk is not JSON".into()));
+    }
+    bytes
+        .get(20..20 + len)
+        .ok_or_else(|| MeshImportError::Gltf("GLB JSON chunk truncated".into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn obj_quads_split_by_material_and_share_corners() {
+        let obj = "mtllib parts.mtl\n\
+                   v 0 0 0\nv 1 0 0\nv 1 1 0\nv 0 1 0\nv 0 0 1\n\
+                   vt 0 0\nvt 1 0\nvt 1 1\nvt 0 1\n\
+                   usemtl red\nf 1/1 2/2 3/3 4/4\n\
+                   usemtl blue\nf -5/1 -4/2 -1/3\n";
+        let mut model = parse_obj(obj).unwrap();
+        assert_eq!(model.material_libs, ["parts.mtl"]);
+        assert_eq!(model.meshes.len(), 2);
+        let quad = &model.meshes[0];
+        assert_eq!((quad.positions.len(), quad.indices.len()), (4, 6));
+        assert_eq!(quad.uvs[2], [1.0, 0.0], "V is flipped");
+        assert_eq!(
+            model.meshes[1].positions[2],
+            [0.0, 0.0, 1.0],
+            "negative index"
+        );
+
+        apply_mtl(
+            &mut model,
+            "newmtl red\nKd 1 0 0\nNs 0\nd 0.5\nnewmtl blue\nKd 0 0 1\nPm 1\nmap_Kd -s 2 2 2 blue.png\n",
+        );
+        let red = &model.materials[model.meshes[0].material.unwrap()];
+        assert_eq!(red.base_color, [1.0, 0.0, 0.0, 0.5]);
+        assert_eq!((red.roughness, red.alpha), (1.0, ImportedAlpha::Blend));
+        let blue = &model.materials[model.meshes[1].material.unwrap()];
+        assert_eq!(blue.metallic, 1.0);
+        assert_eq!(blue.base_color_texture.as_deref(), Some("blue.png"));
+    }
+
+    #[test]
+    fn obj_rejects_out_of_range_indices() {
+        assert!(parse_obj("v 0 0 0\nf 1 2 3\n").is_err());
+    }
+
+    #[test]
+    fn stl_ascii_and_binary_decode_the_same_facet() {
+        let ascii = "solid t\nfacet normal 0 0 0\nouter loop\nvertex 0 0 0\nvertex 1 0 0\n\
+                     vertex 0 1 0\nendloop\nendfacet\nendsolid t\n";
+        let a = parse_stl(ascii.as_bytes()).unwrap();
+
+        // A binary file whose header ALSO starts with `solid`, as many do.
+        let mut bin = b"solid exported by a CAD tool".to_vec();
+        bin.resize(80, 0);
+        bin.extend(1u32.to_le_bytes());
+        for v in [0.0f32, 0.0, 0.0, 0., 0., 0., 1., 0., 0., 0., 1., 0.] {
+            bin.extend(v.to_le_bytes());
+        }
+        bin.extend([0, 0]);
+        let b = parse_stl(&bin).unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(
+            a.meshes[0].normals[0],
+            [0.0, 0.0, 1.0],
+            "normal from winding"
+        );
+    }
+
+    #[test]
+    fn gltf_material_table_reads_glb_json_chunk() {
+        let json = br#"{"materials":[{"name":"paint","pbrMetallicRoughness":{"baseColorFactor":[0.2,0.3,0.4,1],"metallicFactor":0,"baseColorTexture":{"index":0}},"alphaMode":"MASK"}],
+            "textures":[{"source":0}],"images":[{"uri":"paint.png"}],
+            "meshes":[{"primitives":[{"material":0},{}]}]}"#;
+        let mut glb = b"glTF".to_vec();
+        glb.extend(2u32.to_le_bytes());
+        glb.extend(((20 + json.len()) as u32).to_le_bytes());
+        glb.extend((json.len() as u32).to_le_bytes());
+        glb.extend(0x4E4F_534Au32.to_le_bytes());
+        glb.extend(json);
+
+        let table = gltf_materials(&glb).unwrap();
+        assert_eq!(table, gltf_materials(json).unwrap());
+        let paint = &table.materials[0];
+        assert_eq!(paint.base_color, [0.2, 0.3, 0.4, 1.0]);
+        assert_eq!((paint.metallic, paint.roughness), (0.0, 1.0));
+        assert_eq!(paint.alpha, ImportedAlpha::Mask(0.5));
+        assert_eq!(paint.base_color_texture.as_deref(), Some("paint.png"));
+        assert_eq!(table.primitives, [vec![Some(0), None]]);
+    }
+
+    #[test]
+    fn format_ignores_query_and_label() {
+        assert_eq!(
+            MeshFormat::from_uri("lunco://parts/arm.STL?v=2"),
+            Some(MeshFormat::Stl)
+        );
+        assert_eq!(
+            MeshFormat::from_uri("rover.glb#Scene0"),
+            Some(MeshFormat::Glb)
+        );
+        assert_eq!(MeshFormat::from_uri("rover.usda"), None);
+    }
+}
@@ -164,6 +164,8 @@ static TABLE: &[KindMap] = &[
     // Fires a named tool once per activation (e.g. `science::take_photo`). Round-trips as
     // `<Action ID="run_tool" tool="…" args="…"/>` — see `usd_tree.rs`.
     action("run_tool"),
+    // Cartesian manipulator tasking — `<Action ID="reach_to" effector="…" target="[…]"/>`.
+    action("reach_to"),
     // Condition leaves
     cond("arrived"),
     cond("facing"),
@@ -208,6 +210,7 @@ fn spec_kind(spec: &crate::BehaviorSpec) -> &'static str {
         B::Hold => "hold",
         B::SteerClear { .. } => "steer_clear",
         B::RunTool { .. } => "run_tool",
+        B::ReachTo { .. } => "reach_to",
     }
 }
 
@@ -910,6 +913,14 @@ mod tests {
                 tool: "science::take_photo".into(),
                 args: String::new(),
             },
+            B::ReachTo {
+                effector: 0,
+                target: [0.0; 3],
+                orientation: None,
+                tip_offset: [0.0; 3],
+                tolerance: 0.02,
+                angle_tolerance: 2.0,
+            },
         ];
         for spec in &every {
             let kind = spec_kind(spec);
@@ -29,7 +29,7 @@
 //! Rust; rhai stays glue-only. With no behaviour attached, the autopilot falls back
 //! to constant `throttle`/`steer` setpoints.
 
-use bevy::math::DVec3;
+use bevy::math::{DQuat, DVec3};
 use bevy::prelude::*;
 use lunco_behavior::{
     Action, BoxNode, Force, Invert, Node, Parallel, ParallelPolicy, ReactiveSelector,
@@ -135,6 +135,9 @@ pub struct TargetState {
     /// sighting and while the sim is paused. Lead-pursuit ([`BehaviorSpec::Intercept`])
     /// aims ahead along this; plain [`BehaviorSpec::Follow`] ignores it.
     pub vel: DVec3,
+    /// World orientation (grid-absolute). Only [`BehaviorSpec::ReachTo`] reads
+    /// it — a tip offset and a goal orientation are both judged in the link's frame.
+    pub rot: GridRot,
 }
 
 /// Live kinematic state of candidate targets this tick, keyed by [`GlobalEntityId`]
@@ -200,6 +203,25 @@ pub struct DriveCtx {
     /// the queue after the tick and re-emits each as a [`ToolFired`] event.
     /// Reset to empty every tick when the `DriveCtx` is constructed.
     pub fired: Vec<ToolInvocation>,
+    /// Manipulator reach requests queued this tick by [`BehaviorSpec::ReachTo`]
+    /// leaves. Same shape of hand-off as [`fired`](Self::fired): `drive_autopilots`
+    /// drains it and triggers one [`lunco_cosim::ReachTo`] per request.
+    pub reach: Vec<ReachRequest>,
+}
+
+/// A Cartesian reach queued by a [`BehaviorSpec::ReachTo`] leaf — the data of a
+/// [`lunco_cosim::ReachTo`] command with the effector still named by its
+/// [`GlobalEntityId`], since leaves cannot resolve entities.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReachRequest {
+    /// [`GlobalEntityId`] (api_id) of the end-effector link.
+    pub effector: u64,
+    /// Goal position of the effector `[x, y, z]` (active physics frame).
+    pub position: [f64; 3],
+    /// Goal orientation `[x, y, z, w]`, or `None` for position-only.
+    pub orientation: Option<[f64; 4]>,
+    /// Effector-tip offset in the link's local frame (m).
+    pub tip_offset: [f64; 3],
 }
 
 // `ToolInvocation` and `ToolFired` are the shared tool-call vocabulary. They
@@ -544,6 +566,33 @@ pub enum BehaviorSpec {
         #[serde(default)]
         args: String,
     },
+    /// Task a manipulator arm in Cartesian space: solve inverse kinematics for
+    /// the joint chain ending at the `effector` link and drive its joints there
+    /// (a [`lunco_cosim::ReachTo`] command, issued once per activation). Holds
+    /// the vessel braked — an arm works from a parked rover — and stays
+    /// `Running` until the effector tip is within `tolerance` of `target` (and,
+    /// with an `orientation`, within `angle_tolerance` of it), then `Success`;
+    /// `Failure` if the effector cannot be resolved. Wrap it in a `timeout` for
+    /// targets the arm might not reach.
+    ReachTo {
+        /// [`GlobalEntityId`] (api_id) of the end-effector link.
+        effector: u64,
+        /// Goal position `[x, y, z]` of the effector tip (active physics frame).
+        target: [f64; 3],
+        /// Goal orientation `[x, y, z, w]`; omit for position-only.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        orientation: Option<[f64; 4]>,
+        /// Tip offset in the effector link's local frame (m), as on
+        /// [`lunco_cosim::ReachTo`]; omit to reach with the link origin.
+        #[serde(default, skip_serializing_if = "is_zero_offset")]
+        tip_offset: [f64; 3],
+        /// Distance (m) that counts as reached.
+        #[serde(default = "default_reach_tol")]
+        tolerance: f32,
+        /// Angle (degrees) that counts as reached when `orientation` is set.
+        #[serde(default = "default_reach_angle_tol")]
+        angle_tolerance: f64,
+    },
 }
 
 /// Completion rule for a [`BehaviorSpec::Parallel`], mapped to
@@ -591,6 +640,15 @@ fn default_obstacle_dist() -> f32 {
 fn default_cone() -> f64 {
     60.0
 }
+fn default_reach_tol() -> f32 {
+    0.02
+}
+fn default_reach_angle_tol() -> f64 {
+    2.0
+}
+fn is_zero_offset(offset: &[f64; 3]) -> bool {
+    *offset == [0.0; 3]
+}
 
 impl BehaviorSpec {
     /// Whether this tree contains an authored movement or steering action.
@@ -631,7 +689,8 @@ impl BehaviorSpec {
             | Self::ObstacleAhead { .. }
             | Self::Hold
             | Self::PathBlocked { .. }
-            | Self::RunTool { .. } => false,
+            | Self::RunTool { .. }
+            | Self::ReachTo { .. } => false,
         }
     }
 
@@ -651,6 +710,11 @@ impl BehaviorSpec {
                 out.insert(*target);
                 false
             }
+            // The reach leaf completes off the effector link's live pose.
+            Self::ReachTo { effector, .. } => {
+                out.insert(*effector);
+                false
+            }
             Self::ObstacleAhead { .. } => true,
             Self::Sequence { children }
             | Self::Selector { children }
@@ -766,6 +830,25 @@ pub fn build_tree(spec: &BehaviorSpec) -> BoxNode<DriveCtx> {
         BehaviorSpec::RunTool { tool, args } => {
             Box::new(RunToolNode::new(tool.clone(), args.clone()))
         }
+        BehaviorSpec::ReachTo {
+            effector,
+            target,
+            orientation,
+            tip_offset,
+            tolerance,
+            angle_tolerance,
+        } => Box::new(
+            ReachToNode::new(
+                ReachRequest {
+                    effector: *effector,
+                    position: *target,
+                    orientation: *orientation,
+                    tip_offset: *tip_offset,
+                },
+                *tolerance,
+            )
+            .with_angle_tolerance(angle_tolerance.to_radians()),
+        ),
     }
 }
 
@@ -1259,6 +1342,73 @@ impl Node<DriveCtx> for RunToolNode {
     }
 }
 
+/// Leaf that tasks a manipulator once per activation and waits for the arm to
+/// get there. Like [`RunToolNode`] it latches so the request is queued on the
+/// first tick only — the joint targets persist on the motors, so re-solving
+/// every tick would only chase the arm's own transient. Completion is judged
+/// against the effector's live pose in [`DriveCtx::targets`], which
+/// [`BehaviorSpec::tracked_targets`] keeps populated for it, at the same tip
+/// point the solver drives to the goal.
+pub struct ReachToNode {
+    request: ReachRequest,
+    tolerance: f32,
+    /// Radians; only read when the request carries an orientation.
+    angle_tolerance: f64,
+    /// `true` once the request has been queued this activation.
+    issued: bool,
+}
+
+impl ReachToNode {
+    /// A reach leaf for `request`, done within `tolerance` metres.
+    pub fn new(request: ReachRequest, tolerance: f32) -> Self {
+        Self {
+            request,
+            tolerance,
+            angle_tolerance: default_reach_angle_tol().to_radians(),
+            issued: false,
+        }
+    }
+
+    /// Set the orientation tolerance (radians) for a request with a goal
+    /// orientation.
+    pub fn with_angle_tolerance(mut self, radians: f64) -> Self {
+        self.angle_tolerance = radians;
+        self
+    }
+}
+
+impl Node<DriveCtx> for ReachToNode {
+    fn tick(&mut self, ctx: &mut DriveCtx) -> Status {
+        // The arm works from a parked vessel.
+        ctx.out = (0.0, 0.0, 1.0);
+        let Some(effector) = ctx.targets.get(&self.request.effector) else {
+            return Status::Failure;
+        };
+        if !self.issued {
+            ctx.reach.push(self.request.clone());
+            self.issued = true;
+        }
+        let rot = effector.rot.0;
+        let tip = effector.pos.0 + rot * DVec3::from_array(self.request.tip_offset);
+        let goal = DVec3::from_array(self.request.position);
+        let placed = tip.distance(goal) <= self.tolerance as f64;
+        let aligned = self.request.orientation.is_none_or(|[x, y, z, w]| {
+            let q = DQuat::from_xyzw(x, y, z, w);
+            // A degenerate goal is refused by `ReachTo` itself; don't wait on it.
+            q.length_squared() < 1.0e-12 || rot.angle_between(q.normalize()) <= self.angle_tolerance
+        });
+        if placed && aligned {
+            Status::Success
+        } else {
+            Status::Running
+        }
+    }
+
+    fn reset(&mut self) {
+        self.issued = false;
+    }
+}
+
 /// Decorator: run `child`, but abort with `Failure` if it stays `Running` past
 /// `seconds` of **mission time** (a child terminal before then passes straight
 /// through). The clock is [`DriveCtx::now`], so — like [`WaitNode`] — the budget
@@ -1704,8 +1854,8 @@ pub fn drive_autopilots(
     let states: TargetStates = q_targets
         .iter()
         .filter(|(_, gid)| need_all || needed.contains(&gid.get()))
-        .filter_map(|(e, gid)| pose.position(e).map(|position| (gid, position)))
-        .map(|(gid, pos)| {
+        .filter_map(|(e, gid)| pose.pose(e).map(|(position, rot)| (gid, position, rot)))
+        .map(|(gid, pos, rot)| {
             let vel = if dt > 1e-6 {
                 prev.poses
                     .get(&gid.get())
@@ -1714,7 +1864,7 @@ pub fn drive_autopilots(
             } else {
                 DVec3::ZERO
             };
-            (gid.get(), TargetState { pos, vel })
+            (gid.get(), TargetState { pos, vel, rot })
         })
         .collect();
     prev.poses = states.iter().map(|(k, s)| (*k, s.pos)).collect();
@@ -1734,6 +1884,7 @@ pub fn drive_autopilots(
         // A physical body that has not been seeded is not navigable yet;
         // `SimulationPoseQuery` returns no presentation-transform substitute.
         let self_pose = pose.pose(ap.vessel);
+        let mut reaches = Vec::new();
         let (throttle, steer, brake, mut fired) = match (behavior.as_deref_mut(), self_pose) {
             (Some(tree), Some((self_pos, self_rotation))) => {
                 let clearance = clearances
@@ -1765,6 +1916,7 @@ pub fn drive_autopilots(
                     targets: targets.clone(),
                     clearance,
                     fired: Vec::new(),
+                    reach: Vec::new(),
                 };
                 if let Some(state) = execution.as_deref_mut() {
                     tree.tick_hosted(state, &mut ctx);
@@ -1775,6 +1927,7 @@ pub fn drive_autopilots(
                         commands.entity(actor).try_insert(state);
                     }
                 }
+                reaches = ctx.reach;
                 (ctx.out.0, ctx.out.1, ctx.out.2, ctx.fired)
             }
             // No behaviour tree: the explicit constant-cruise autopilot. A zero
@@ -1815,6 +1968,25 @@ pub fn drive_autopilots(
                 args: inv.args,
             });
         }
+
+        // Same hand-off for `reach_to` leaves: resolve the effector's api_id to
+        // its local entity (it is in the snapshot — the leaf only queues once it
+        // has seen it there) and issue the IK command.
+        for reach in reaches {
+            let Some(effector) = q_targets
+                .iter()
+                .find_map(|(e, g)| (g.get() == reach.effector).then_some(e))
+            else {
+                continue;
+            };
+            commands.trigger(lunco_cosim::ReachTo {
+                effector,
+                position: reach.position,
+                orientation: reach.orientation.map(Vec::from).unwrap_or_default(),
+                tip_offset: reach.tip_offset,
+                ..Default::default()
+            });
+        }
     }
 }
 
@@ -2509,6 +2681,7 @@ mod tests {
             targets: Default::default(),
             clearance: Default::default(),
             fired: Vec::new(),
+            reach: Vec::new(),
         }
     }
 
@@ -30,6 +30,7 @@ fn json_tree_drives_and_sequences_waypoints() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     behavior.0.tick(&mut ctx);
     assert!(
@@ -124,6 +125,7 @@ fn one_way_tree_completion_is_latched_by_its_host() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
 
     assert_eq!(
@@ -156,6 +158,7 @@ fn brake_ticks(behavior: &mut AutopilotBehavior, waypoints: &[DVec3], max: usize
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     let mut braked = 0;
     let mut wp = 0;
@@ -216,6 +219,7 @@ fn selector_with_arrived_guard_brakes_when_close_drives_when_far() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     behavior.0.tick(&mut far);
     assert!(
@@ -234,6 +238,7 @@ fn selector_with_arrived_guard_brakes_when_close_drives_when_far() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     behavior.0.tick(&mut near);
     assert!(near.out.2 > 0.5, "at goal → brake, got {:?}", near.out);
@@ -255,6 +260,7 @@ fn wait_latches_a_mission_time_deadline_and_resets_across_repeats() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(behavior.0.tick(&mut ctx), Status::Running);
     assert!(ctx.out.2 > 0.5, "wait holds the brakes");
@@ -325,6 +331,7 @@ fn parallel_require_one_succeeds_when_first_child_arrives() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(
         behavior.0.tick(&mut ctx),
@@ -350,6 +357,7 @@ fn face_pivots_in_place_then_succeeds_when_aligned() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(behavior.0.tick(&mut ctx), Status::Running);
     assert_eq!(ctx.out.0, 0.0, "face uses no throttle (pivot in place)");
@@ -382,6 +390,7 @@ fn invert_negates_the_arrived_condition() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(inv.0.tick(&mut far), Status::Success);
     // At the target → arrived Success → invert Failure.
@@ -394,6 +403,7 @@ fn invert_negates_the_arrived_condition() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(inv.0.tick(&mut near), Status::Failure);
 }
@@ -410,6 +420,7 @@ fn force_and_retry_decorators_map_and_re_attempt() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     // force_success swallows a failing child; force_failure overrides a success.
     let mut fs =
@@ -450,6 +461,7 @@ fn reactive_selector_switches_to_brake_the_instant_it_arrives() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     behavior.0.tick(&mut ctx);
     assert!(
@@ -481,6 +493,7 @@ fn timeout_aborts_a_running_child_on_the_mission_clock() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(
         behavior.0.tick(&mut ctx),
@@ -519,6 +532,7 @@ fn follow_tracks_a_live_target_and_fails_when_it_vanishes() {
         TargetState {
             pos: GridPos(DVec3::new(50.0, 0.0, 0.0)),
             vel: DVec3::ZERO,
+            rot: GridRot::default(),
         },
     );
     let mut ctx = DriveCtx {
@@ -530,6 +544,7 @@ fn follow_tracks_a_live_target_and_fails_when_it_vanishes() {
         targets: Arc::new(targets),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(
         behavior.0.tick(&mut ctx),
@@ -549,6 +564,7 @@ fn follow_tracks_a_live_target_and_fails_when_it_vanishes() {
         TargetState {
             pos: GridPos(DVec3::new(1.0, 0.0, 0.0)),
             vel: DVec3::ZERO,
+            rot: GridRot::default(),
         },
     );
     ctx.targets = Arc::new(near);
@@ -587,6 +603,7 @@ fn intercept_leads_a_moving_target_and_succeeds_on_contact() {
         TargetState {
             pos: GridPos(DVec3::new(20.0, 0.0, 0.0)),
             vel: DVec3::new(0.0, 0.0, 5.0),
+            rot: GridRot::default(),
         },
     );
     let mut ctx = DriveCtx {
@@ -598,6 +615,7 @@ fn intercept_leads_a_moving_target_and_succeeds_on_contact() {
         targets: Arc::new(targets),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     // Not yet in contact → Running, driving. The lead point is (20, 0, 10), i.e. off
     // to +Z of the target, so we steer toward the future position, not the tail.
@@ -647,6 +665,7 @@ fn obstacle_ahead_senses_a_vessel_in_the_forward_cone_and_excludes_self() {
         TargetState {
             pos: GridPos(DVec3::ZERO),
             vel: DVec3::ZERO,
+            rot: GridRot::default(),
         },
     ); // self
     targets.insert(
@@ -654,6 +673,7 @@ fn obstacle_ahead_senses_a_vessel_in_the_forward_cone_and_excludes_self() {
         TargetState {
             pos: GridPos(DVec3::new(4.0, 0.0, 0.0)),
             vel: DVec3::ZERO,
+            rot: GridRot::default(),
         },
     );
     let mut ctx = DriveCtx {
@@ -665,6 +685,7 @@ fn obstacle_ahead_senses_a_vessel_in_the_forward_cone_and_excludes_self() {
         targets: Arc::new(targets),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(
         behavior.0.tick(&mut ctx),
@@ -679,6 +700,7 @@ fn obstacle_ahead_senses_a_vessel_in_the_forward_cone_and_excludes_self() {
         TargetState {
             pos: GridPos(DVec3::ZERO),
             vel: DVec3::ZERO,
+            rot: GridRot::default(),
         },
     );
     behind.insert(
@@ -686,6 +708,7 @@ fn obstacle_ahead_senses_a_vessel_in_the_forward_cone_and_excludes_self() {
         TargetState {
             pos: GridPos(DVec3::new(-4.0, 0.0, 0.0)),
             vel: DVec3::ZERO,
+            rot: GridRot::default(),
         },
     );
     ctx.targets = Arc::new(behind);
@@ -702,6 +725,7 @@ fn obstacle_ahead_senses_a_vessel_in_the_forward_cone_and_excludes_self() {
         TargetState {
             pos: GridPos(DVec3::ZERO),
             vel: DVec3::ZERO,
+            rot: GridRot::default(),
         },
     );
     ctx.targets = Arc::new(just_me);
@@ -729,6 +753,7 @@ fn facing_guards_heading_and_hold_stays_running() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(facing.0.tick(&mut aligned), Status::Success);
     let mut off = DriveCtx {
@@ -740,6 +765,7 @@ fn facing_guards_heading_and_hold_stays_running() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(facing.0.tick(&mut off), Status::Failure);
 
@@ -767,6 +793,7 @@ fn cooldown_blocks_re_entry_for_the_lockout_window() {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(
         behavior.0.tick(&mut ctx),
@@ -806,6 +833,7 @@ fn path_blocked_reads_the_forward_raycast_clearance() {
             range: 20.0,
         },
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(
         behavior.0.tick(&mut ctx),
@@ -844,6 +872,7 @@ fn steer_clear_goes_straight_when_open_and_turns_toward_the_open_side() {
             range: 20.0,
         },
         fired: Vec::new(),
+        reach: Vec::new(),
     };
     assert_eq!(behavior.0.tick(&mut ctx), Status::Running);
     assert!(
@@ -882,3 +911,116 @@ fn steer_clear_goes_straight_when_open_and_turns_toward_the_open_side() {
     assert_eq!(behavior.0.tick(&mut ctx), Status::Running);
     assert!(ctx.out.2 > 0.5, "boxed in → brake, got {:?}", ctx.out);
 }
+
+#[test]
+fn reach_to_queues_one_request_and_completes_at_the_target() {
+    use lunco_behavior::Status;
+    let mut behavior = AutopilotBehavior::from_json(
+        r#"{"kind":"reach_to","effector":7,"target":[1.0,0.5,0.0],"tolerance":0.05}"#,
+    )
+    .unwrap();
+    let arm_at = |x: f64| {
+        let mut states = TargetStates::new();
+        states.insert(
+            7,
+            TargetState {
+                pos: GridPos(DVec3::new(x, 0.5, 0.0)),
+                vel: DVec3::ZERO,
+                rot: GridRot::default(),
+            },
+        );
+        Arc::new(states)
+    };
+    let mut ctx = DriveCtx {
+        pos: GridPos(DVec3::ZERO),
+        fwd: Vec3::X,
+        now: 0.0,
+        self_gid: 0,
+        out: (0.0, 0.0, 0.0),
+        targets: arm_at(0.0),
+        clearance: Default::default(),
+        fired: Vec::new(),
+        reach: Vec::new(),
+    };
+    assert_eq!(behavior.0.tick(&mut ctx), Status::Running);
+    assert_eq!(
+        ctx.out,
+        (0.0, 0.0, 1.0),
+        "the arm works from a braked vessel"
+    );
+    assert_eq!(ctx.reach.len(), 1);
+    assert_eq!(ctx.reach[0].effector, 7);
+
+    // Still travelling: no second solve is queued.
+    ctx.reach.clear();
+    assert_eq!(behavior.0.tick(&mut ctx), Status::Running);
+    assert!(
+        ctx.reach.is_empty(),
+        "the request is latched per activation"
+    );
+
+    ctx.targets = arm_at(0.98);
+    assert_eq!(behavior.0.tick(&mut ctx), Status::Success);
+
+    // An effector that is not in the snapshot cannot be tasked.
+    let mut lost =
+        AutopilotBehavior::from_json(r#"{"kind":"reach_to","effector":99,"target":[0.0,0.0,0.0]}"#)
+            .unwrap();
+    ctx.reach.clear();
+    assert_eq!(lost.0.tick(&mut ctx), Status::Failure);
+    assert!(ctx.reach.is_empty());
+}
+
+/// With a tip offset the leaf judges the tip — the point `ReachTo` drives to
+/// the goal — not the link origin, and a goal orientation must be met too.
+#[test]
+fn reach_to_completes_on_the_offset_tip_and_orientation() {
+    use lunco_behavior::Status;
+    // Tip 0.3 m along the link's local +X; goal also asks for a 90° yaw.
+    let yaw = DQuat::from_rotation_y(std::f64::consts::FRAC_PI_2);
+    let [x, y, z, w] = yaw.to_array();
+    let mut behavior = AutopilotBehavior::from_json(&format!(
+        r#"{{"kind":"reach_to","effector":7,"target":[1.0,0.5,0.0],"tip_offset":[0.3,0.0,0.0],
+            "orientation":[{x},{y},{z},{w}],"tolerance":0.01}}"#
+    ))
+    .unwrap();
+    let arm = |origin: DVec3, rot: DQuat| {
+        let mut states = TargetStates::new();
+        states.insert(
+            7,
+            TargetState {
+                pos: GridPos(origin),
+                vel: DVec3::ZERO,
+                rot: GridRot(rot),
+            },
+        );
+        Arc::new(states)
+    };
+    let goal = DVec3::new(1.0, 0.5, 0.0);
+    let mut ctx = DriveCtx {
+        pos: GridPos(DVec3::ZERO),
+        fwd: Vec3::X,
+        now: 0.0,
+        self_gid: 0,
+        out: (0.0, 0.0, 0.0),
+        targets: arm(goal, yaw),
+        clearance: Default::default(),
+        fired: Vec::new(),
+        reach: Vec::new(),
+    };
+    // Link origin on the goal puts the tip 0.3 m off it: not done.
+    assert_eq!(behavior.0.tick(&mut ctx), Status::Running);
+    assert_eq!(ctx.reach[0].tip_offset, [0.3, 0.0, 0.0]);
+
+    // Yawed 90°, local +X is world −Z: the tip sits on the goal when the
+    // origin is 0.3 m along +Z from it.
+    let origin = goal + DVec3::new(0.0, 0.0, 0.3);
+    ctx.targets = arm(origin, DQuat::IDENTITY);
+    assert_eq!(
+        behavior.0.tick(&mut ctx),
+        Status::Running,
+        "unrotated link: tip off target and attitude wrong"
+    );
+    ctx.targets = arm(origin, yaw);
+    assert_eq!(behavior.0.tick(&mut ctx), Status::Success);
+}
@@ -20,6 +20,7 @@ fn ctx() -> DriveCtx {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     }
 }
 
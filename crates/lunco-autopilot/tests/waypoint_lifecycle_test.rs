@@ -47,6 +47,7 @@ fn ctx_at(pos: [f64; 3]) -> DriveCtx {
         targets: Default::default(),
         clearance: Default::default(),
         fired: Vec::new(),
+        reach: Vec::new(),
     }
 }
 
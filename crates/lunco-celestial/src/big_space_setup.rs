@@ -39,6 +39,7 @@
 //!         │     │           └── terrain tiles + rovers + surface ops
 //!         │     ├── Earth Inertial Anchor (position only, NO spin)
 //!         │     │     └── Observer Camera  ← star-fixed
+//!         │     ├── Earth–Moon Synodic Grid (barycentre, turns with the Moon)
 //!         │     └── Moon Grid (ROTATING: ephemeris + IAU spin)
 //!         │           ├── Moon Body (mesh+collider, identity transform)
 //!         │           └── Moon Surface Grid (surface sub-frame, body-fixed)
@@ -526,6 +527,26 @@ pub fn setup_big_space_hierarchy(
         ))
         .id();
 
+    // ── Earth–Moon Synodic Grid (co-rotating with the pair) ────────────────
+    // Origin at the Earth–Moon barycentre, axes turning with the Moon: what
+    // `TrajectoryFrame::Synodic` draws a halo orbit in and what a `SoiMigrant`
+    // parked on one keeps. Posed by `synodic_frame_system`, not the ephemeris
+    // pass — the barycentre of a pair is not an ephemeris body.
+    commands.spawn((
+        ReferenceFrame::Synodic {
+            primary: crate::ephemeris_id::EARTH,
+            secondary: crate::ephemeris_id::MOON,
+        },
+        make_grid(),
+        CellCoord::default(),
+        Transform::default(),
+        GlobalTransform::default(),
+        Visibility::default(),
+        InheritedVisibility::default(),
+        Name::new("Earth-Moon Synodic Grid"),
+        ChildOf(emb_grid),
+    ));
+
     // ── Earth Body (visual/physical centre in the rotating body-fixed Grid) ─
     // Note: Body does NOT have CellCoord. It's a low-precision entity whose
     // GlobalTransform = Grid × local Transform. This allows rotation from
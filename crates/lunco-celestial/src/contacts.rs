@@ -0,0 +1,588 @@
+//! Ground-station contact scheduling — who talks to whom, and when.
+//!
+//! The link kernel connects every pair whose geometry allows it, so three rovers
+//! in view of one DSN complex all get a link from it at once. A real station has
+//! one antenna: it tracks one target, then slews and reconfigures before the
+//! next. [`ScheduleContacts`] turns contact REQUESTS (asset, minimum duration,
+//! priority, data volume) and STATION constraints (setup time, downlink rate)
+//! into a conflict-free [`ContactPlan`]:
+//!
+//! 1. Visibility for every eligible station↔asset pair comes from the window
+//!    predictor ([`crate::windows`]) — the live kernel's builtin geometry,
+//!    swept ahead, so a planned pass is one the kernel will actually close.
+//! 2. Requests are placed greedily, highest priority first (ties in request
+//!    order), each at the earliest-finishing slot on any eligible station. A
+//!    slot must fit inside one visibility window, leave `setup_s` clear on both
+//!    sides of the station's other contacts, and not overlap the asset's own
+//!    other contacts (one radio per asset).
+//! 3. What cannot be placed is reported with the reason, never dropped.
+//!
+//! A request is one contact. Its duration is the longer of `min_duration_s` and
+//! `data_volume_bits / rate_bps` of the station that takes it; a volume asked of
+//! a station with no authored rate cannot be timed, so that station is skipped.
+//! Split a large dump into several requests.
+//!
+//! # Publication
+//!
+//! The plan is a resource, read three ways:
+//!
+//! * **the link kernel** — while the plan is `enforce`d, a pair with a planned
+//!   station is passed to the `link.connected` verdict with `ctx.scheduled`
+//!   set, and connects only inside one of its contacts (see
+//!   [`ContactPlan::scheduled`]);
+//! * **timelines** — [`ContactPlan::timeline`] renders an asset's contacts as
+//!   `RunTimeline` data: waits and `contact.start` / `contact.end` emits, timed
+//!   from the plan start;
+//! * **the API** — `query("ContactPlan")` returns the plan and every asset's
+//!   timeline.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use lunco_api::queries::ApiQueryProvider;
+use lunco_api::schema::ApiResponse;
+use lunco_core::{on_command, register_commands, Ack, Command, OpId};
+
+use crate::windows::{
+    Window, WindowKind, WindowScene, WindowSpan, DEFAULT_SPAN_DAYS, DEFAULT_STEP_S,
+    DEFAULT_TOLERANCE_S, SECONDS_PER_DAY,
+};
+
+/// Telemetry emitted by a contact timeline when a pass opens; the value is the
+/// station's GID.
+pub const CONTACT_START_EVENT: &str = "contact.start";
+/// Emitted when a pass closes; the value is the station's GID.
+pub const CONTACT_END_EVENT: &str = "contact.end";
+
+/// One asked-for contact.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ContactRequest {
+    /// GID of the asset (a rover, a relay) — a [`LinkNode`](crate::LinkNode).
+    pub asset: u64,
+    /// Shortest useful pass, seconds.
+    #[serde(default)]
+    pub min_duration_s: f64,
+    /// Higher is placed first.
+    #[serde(default)]
+    pub priority: i32,
+    /// Data to move in this pass, bits. Zero means duration alone decides.
+    #[serde(default)]
+    pub data_volume_bits: f64,
+    /// Stations allowed to serve it; empty means any planned station.
+    #[serde(default)]
+    pub stations: Vec<u64>,
+}
+
+/// One station's constraints.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct StationSpec {
+    /// GID of the station's [`LinkNode`](crate::LinkNode).
+    pub station: u64,
+    /// Slew and reconfiguration time required between two contacts, seconds.
+    #[serde(default)]
+    pub setup_s: f64,
+    /// Downlink rate, bits/s — needed only to time a data volume.
+    #[serde(default)]
+    pub rate_bps: Option<f64>,
+}
+
+/// One placed pass.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Contact {
+    pub station: u64,
+    pub asset: u64,
+    pub start_jd: f64,
+    pub end_jd: f64,
+    pub priority: i32,
+    /// Index of the [`ContactRequest`] it serves.
+    pub request: usize,
+}
+
+/// A request the scheduler could not place.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Unscheduled {
+    pub request: usize,
+    pub asset: u64,
+    pub reason: String,
+}
+
+/// The published, conflict-free contact plan. Empty until a
+/// [`ScheduleContacts`] lands.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct ContactPlan {
+    pub start_jd: f64,
+    pub end_jd: f64,
+    /// Every station the plan governs, whether or not it was given a contact.
+    pub stations: Vec<u64>,
+    /// Sorted by start.
+    pub contacts: Vec<Contact>,
+    pub unscheduled: Vec<Unscheduled>,
+    /// Whether the live link graph honours the plan.
+    pub enforce: bool,
+}
+
+impl ContactPlan {
+    /// The plan's say on pair `a`↔`b` at `jd`: `None` when it has none (no
+    /// planned station in the pair, or `jd` outside the plan), otherwise whether
+    /// a contact between the two is open. A planned station talks only to the
+    /// asset its plan gives it — which is the whole point of having one.
+    pub fn scheduled(&self, a: u64, b: u64, jd: f64) -> Option<bool> {
+        if !(self.start_jd..=self.end_jd).contains(&jd) {
+            return None;
+        }
+        if !self.stations.contains(&a) && !self.stations.contains(&b) {
+            return None;
+        }
+        Some(self.contacts.iter().any(|c| {
+            ((c.station == a && c.asset == b) || (c.station == b && c.asset == a))
+                && (c.start_jd..c.end_jd).contains(&jd)
+        }))
+    }
+
+    /// `asset`'s contacts as `RunTimeline` steps, timed from the plan start: a
+    /// wait up to each pass, [`CONTACT_START_EVENT`], a wait through it,
+    /// [`CONTACT_END_EVENT`]. Run it at the plan's start epoch for the emits to
+    /// land on the planned instants.
+    pub fn timeline(&self, asset: u64) -> serde_json::Value {
+        let mut steps = Vec::new();
+        let mut cursor = self.start_jd;
+        for c in self.contacts.iter().filter(|c| c.asset == asset) {
+            let lead_s = (c.start_jd - cursor) * SECONDS_PER_DAY;
+            if lead_s > 0.0 {
+                steps.push(serde_json::json!({ "wait": lead_s }));
+            }
+            steps.push(serde_json::json!({ "emit": CONTACT_START_EVENT, "value": c.station }));
+            steps.push(serde_json::json!({ "wait": (c.end_jd - c.start_jd) * SECONDS_PER_DAY }));
+            steps.push(serde_json::json!({ "emit": CONTACT_END_EVENT, "value": c.station }));
+            cursor = c.end_jd;
+        }
+        serde_json::json!({ "name": format!("contacts_{asset}"), "steps": steps })
+    }
+
+    /// Every asset that holds at least one contact, in first-contact order.
+    pub fn assets(&self) -> Vec<u64> {
+        let mut assets: Vec<u64> = Vec::new();
+        for c in &self.contacts {
+            if !assets.contains(&c.asset) {
+                assets.push(c.asset);
+            }
+        }
+        assets
+    }
+
+    /// The plan as API JSON: contacts, what was left out, and each asset's
+    /// timeline keyed by stringified GID.
+    pub fn to_json(&self) -> serde_json::Value {
+        let contacts: Vec<serde_json::Value> = self
+            .contacts
+            .iter()
+            .map(|c| {
+                serde_json::json!({
+                    "station": c.station,
+                    "asset": c.asset,
+                    "start_jd": c.start_jd,
+                    "end_jd": c.end_jd,
+                    "duration_s": (c.end_jd - c.start_jd) * SECONDS_PER_DAY,
+                    "priority": c.priority,
+                    "request": c.request,
+                })
+            })
+            .collect();
+        let unscheduled: Vec<serde_json::Value> = self
+            .unscheduled
+            .iter()
+            .map(|u| {
+                serde_json::json!({ "request": u.request, "asset": u.asset, "reason": u.reason })
+            })
+            .collect();
+        let timelines: serde_json::Map<String, serde_json::Value> = self
+            .assets()
+            .into_iter()
+            .map(|a| (a.to_string(), self.timeline(a)))
+            .collect();
+        serde_json::json!({
+            "start_jd": self.start_jd,
+            "end_jd": self.end_jd,
+            "enforce": self.enforce,
+            "stations": self.stations,
+            "contacts": contacts,
+            "unscheduled": unscheduled,
+            "timelines": timelines,
+        })
+    }
+}
+
+/// The earliest start in `window` for a pass of `dur` days that overlaps none of
+/// `blocked` (half-open `[start, end)` intervals, any order).
+fn earliest_fit(window: &Window, dur: f64, blocked: &[(f64, f64)]) -> Option<f64> {
+    let mut start = window.start_jd;
+    loop {
+        let end = start + dur;
+        if end > window.end_jd {
+            return None;
+        }
+        // Jump past the latest-ending interval this slot collides with; stop when
+        // nothing collides. Each jump moves strictly forward, so this terminates.
+        match blocked
+            .iter()
+            .filter(|(b0, b1)| *b0 < end && *b1 > start)
+            .map(|(_, b1)| *b1)
+            .reduce(f64::max)
+        {
+            Some(next) => start = next,
+            None => return Some(start),
+        }
+    }
+}
+
+/// Place `requests` on `stations` (the pure half of [`ScheduleContacts`]).
+/// `visibility(station, asset)` returns that pair's windows; it is asked at most
+/// once per pair. See the module docs for the placement rule.
+pub fn schedule_contacts(
+    requests: &[ContactRequest],
+    stations: &[StationSpec],
+    mut visibility: impl FnMut(u64, u64) -> Vec<Window>,
+) -> (Vec<Contact>, Vec<Unscheduled>) {
+    let mut order: Vec<usize> = (0..requests.len()).collect();
+    order.sort_by_key(|&i| std::cmp::Reverse(requests[i].priority));
+
+    let mut windows: std::collections::HashMap<(u64, u64), Vec<Window>> = Default::default();
+    let mut contacts: Vec<Contact> = Vec::new();
+    let mut unscheduled = Vec::new();
+    for i in order {
+        let req = &requests[i];
+        let mut best: Option<Contact> = None;
+        let mut why = "no eligible station";
+        for spec in stations
+            .iter()
+            .filter(|s| req.stations.is_empty() || req.stations.contains(&s.station))
+        {
+            let volume_s = if req.data_volume_bits > 0.0 {
+                match spec.rate_bps.filter(|r| *r > 0.0) {
+                    Some(rate) => req.data_volume_bits / rate,
+                    None => {
+                        why = "data volume needs a station rate_bps";
+                        continue;
+                    }
+                }
+            } else {
+                0.0
+            };
+            let dur = req.min_duration_s.max(volume_s).max(0.0) / SECONDS_PER_DAY;
+            let setup = spec.setup_s.max(0.0) / SECONDS_PER_DAY;
+            // The station is held for setup on both sides of each of its passes;
+            // the asset has one radio and is held only for the passes themselves.
+            let blocked: Vec<(f64, f64)> = contacts
+                .iter()
+                .filter_map(|c| {
+                    if c.station == spec.station {
+                        Some((c.start_jd - setup, c.end_jd + setup))
+                    } else if c.asset == req.asset {
+                        Some((c.start_jd, c.end_jd))
+                    } else {
+                        None
+                    }
+                })
+                .collect();
+            let pair = windows
+                .entry((spec.station, req.asset))
+                .or_insert_with(|| visibility(spec.station, req.asset));
+            if pair.is_empty() {
+                why = "never in view";
+                continue;
+            }
+            let slot = pair
+                .iter()
+                .filter_map(|w| earliest_fit(w, dur, &blocked))
+                .reduce(f64::min);
+            let Some(start) = slot else {
+                why = "no free window long enough";
+                continue;
+            };
+            let candidate = Contact {
+                station: spec.station,
+                asset: req.asset,
+                start_jd: start,
+                end_jd: start + dur,
+                priority: req.priority,
+                request: i,
+            };
+            if best.is_none_or(|b| candidate.end_jd < b.end_jd) {
+                best = Some(candidate);
+            }
+        }
+        match best {
+            Some(c) => contacts.push(c),
+            None => unscheduled.push(Unscheduled {
+                request: i,
+                asset: req.asset,
+                reason: why.to_string(),
+            }),
+        }
+    }
+    contacts.sort_by(|a, b| a.start_jd.total_cmp(&b.start_jd));
+    unscheduled.sort_by_key(|u| u.request);
+    (contacts, unscheduled)
+}
+
+/// Build and publish a conflict-free [`ContactPlan`]. `requests` and `stations`
+/// are JSON arrays of [`ContactRequest`] and [`StationSpec`]. Zero fields take
+/// the defaults: the current epoch, 14 days, 60 s visibility sampling. With
+/// `enforce`, the live link graph follows the plan (see [`ContactPlan::scheduled`]).
+/// Replaces any previous plan.
+#[Command(default)]
+pub struct ScheduleContacts {
+    /// JSON: `[{ asset, min_duration_s, priority?, data_volume_bits?, stations? }]`.
+    pub requests: String,
+    /// JSON: `[{ station, setup_s?, rate_bps? }]`.
+    pub stations: String,
+    /// Plan start, TDB Julian date.
+    pub start_jd: f64,
+    /// Plan length in days.
+    pub days: f64,
+    /// Visibility sampling step, seconds — the shortest pass it reliably finds.
+    pub step_s: f64,
+    /// Gate live links by the plan.
+    pub enforce: bool,
+}
+
+#[on_command(ScheduleContacts)]
+fn on_schedule_contacts(
+    _t: On<ScheduleContacts>,
+    scene: WindowScene,
+    mut commands: Commands,
+) -> Result<Ack, String> {
+    let requests: Vec<ContactRequest> = serde_json::from_str(&cmd.requests)
+        .map_err(|e| format!("ScheduleContacts: `requests`: {e}"))?;
+    let stations: Vec<StationSpec> = serde_json::from_str(&cmd.stations)
+        .map_err(|e| format!("ScheduleContacts: `stations`: {e}"))?;
+    if stations.is_empty() {
+        return Err("ScheduleContacts: no stations".into());
+    }
+    let or_default = |v: f64, d: f64| if v > 0.0 { v } else { d };
+    let start_jd = match scene.now_jd() {
+        Some(now) => or_default(cmd.start_jd, now),
+        None if cmd.start_jd > 0.0 => cmd.start_jd,
+        None => return Err("ScheduleContacts: no clock; pass `start_jd`".into()),
+    };
+    let span = WindowSpan {
+        t0: start_jd,
+        t1: start_jd + or_default(cmd.days, DEFAULT_SPAN_DAYS),
+        step_s: or_default(cmd.step_s, DEFAULT_STEP_S),
+        tolerance_s: DEFAULT_TOLERANCE_S,
+    };
+
+    // Resolve every GID up front: a typo is an error, not an empty plan.
+    let mut entities = std::collections::HashMap::new();
+    for gid in stations
+        .iter()
+        .map(|s| s.station)
+        .chain(requests.iter().map(|r| r.asset))
+    {
+        let e = scene
+            .resolve(gid)
+            .map_err(|e| format!("ScheduleContacts: {e}"))?;
+        entities.insert(gid, e);
+    }
+    let mut failure = None;
+    let (contacts, unscheduled) = schedule_contacts(&requests, &stations, |station, asset| {
+        scene
+            .predict(
+                WindowKind::Los,
+                entities[&station],
+                Some(entities[&asset]),
+                &span,
+            )
+            .unwrap_or_else(|e| {
+                failure.get_or_insert(e);
+                Vec::new()
+            })
+    });
+    if let Some(e) = failure {
+        return Err(format!("ScheduleContacts: {e}"));
+    }
+
+    info!(
+        "[contacts] planned {} contact(s), {} unscheduled, JD {:.2}..{:.2}{}",
+        contacts.len(),
+        unscheduled.len(),
+        span.t0,
+        span.t1,
+        if cmd.enforce { " (enforced)" } else { "" }
+    );
+    let plan = ContactPlan {
+        start_jd: span.t0,
+        end_jd: span.t1,
+        stations: stations.iter().map(|s| s.station).collect(),
+        contacts,
+        unscheduled,
+        enforce: cmd.enforce,
+    };
+    let mut ack = Ack::new(OpId::new());
+    ack.assigned = plan.to_json();
+    commands.insert_resource(plan);
+    Ok(ack)
+}
+
+/// Drop the current [`ContactPlan`]: every station sees every asset again
+/// whenever geometry allows.
+#[Command(default)]
+pub struct ClearContactPlan {}
+
+#[on_command(ClearContactPlan)]
+fn on_clear_contact_plan(_t: On<ClearContactPlan>, mut plan: ResMut<ContactPlan>) {
+    *plan = ContactPlan::default();
+}
+
+register_commands!(on_schedule_contacts, on_clear_contact_plan);
+
+/// `ContactPlan` — the published plan: `{ start_jd, end_jd, enforce, stations,
+/// contacts: [{station, asset, start_jd, end_jd, duration_s, priority, request}],
+/// unscheduled: [{request, asset, reason}], timelines: { "<asset gid>": timeline } }`.
+/// Each timeline is ready for `RunTimeline` on its asset.
+pub struct ContactPlanProvider;
+
+impl ApiQueryProvider for ContactPlanProvider {
+    fn name(&self) -> &'static str {
+        "ContactPlan"
+    }
+
+    fn execute(&self, world: &mut World, _params: &serde_json::Value) -> ApiResponse {
+        let plan = world
+            .get_resource::<ContactPlan>()
+            .cloned()
+            .unwrap_or_default();
+        ApiResponse::ok(plan.to_json())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const S: f64 = 1.0 / SECONDS_PER_DAY;
+
+    fn window(start_s: f64, end_s: f64) -> Window {
+        Window {
+            start_jd: start_s * S,
+            end_jd: end_s * S,
+            start_open: false,
+            end_open: false,
+        }
+    }
+
+    fn request(asset: u64, min_duration_s: f64, priority: i32) -> ContactRequest {
+        ContactRequest {
+            asset,
+            min_duration_s,
+            priority,
+            data_volume_bits: 0.0,
+            stations: Vec::new(),
+        }
+    }
+
+    fn station(station: u64, setup_s: f64) -> StationSpec {
+        StationSpec {
+            station,
+            setup_s,
+            rate_bps: None,
+        }
+    }
+
+    fn secs(jd: f64) -> f64 {
+        (jd / S * 1e3).round() / 1e3
+    }
+
+    /// Two rovers in view of one dish at once: the dish takes the higher
+    /// priority first, then slews, then the other — never both.
+    #[test]
+    fn one_antenna_serves_one_asset_at_a_time_with_setup_between() {
+        let requests = [request(10, 600.0, 1), request(20, 600.0, 5)];
+        let (contacts, unscheduled) = schedule_contacts(&requests, &[station(1, 120.0)], |_, _| {
+            vec![window(0.0, 3600.0)]
+        });
+        assert!(unscheduled.is_empty(), "{unscheduled:?}");
+        assert_eq!(contacts.len(), 2);
+        assert_eq!((contacts[0].asset, secs(contacts[0].start_jd)), (20, 0.0));
+        assert_eq!((contacts[1].asset, secs(contacts[1].start_jd)), (10, 720.0));
+    }
+
+    /// The loser of a conflict goes to the station that can take it, and what
+    /// fits nowhere is reported with its reason.
+    #[test]
+    fn a_second_station_absorbs_the_conflict_and_the_rest_is_reported() {
+        let requests = [
+            request(10, 600.0, 5),
+            request(20, 600.0, 1),
+            request(30, 4000.0, 1),
+        ];
+        let (contacts, unscheduled) =
+            schedule_contacts(&requests, &[station(1, 0.0), station(2, 0.0)], |s, _| {
+                if s == 1 {
+                    vec![window(0.0, 700.0)]
+                } else {
+                    vec![window(0.0, 3600.0)]
+                }
+            });
+        assert_eq!(contacts.len(), 2, "{contacts:?}");
+        assert_eq!((contacts[0].asset, contacts[0].station), (10, 1));
+        assert_eq!((contacts[1].asset, contacts[1].station), (20, 2));
+        assert_eq!(unscheduled.len(), 1);
+        assert_eq!(unscheduled[0].asset, 30);
+        assert_eq!(unscheduled[0].reason, "no free window long enough");
+    }
+
+    /// A data volume sets the pass length from the station's rate; without a
+    /// rate the station cannot time it.
+    #[test]
+    fn data_volume_sizes_the_pass_by_station_rate() {
+        let mut req = request(10, 60.0, 0);
+        req.data_volume_bits = 8.0e6;
+        let fast = StationSpec {
+            rate_bps: Some(1.0e4),
+            ..station(1, 0.0)
+        };
+        let (contacts, _) =
+            schedule_contacts(&[req.clone()], &[fast], |_, _| vec![window(100.0, 5000.0)]);
+        assert_eq!(secs(contacts[0].end_jd - contacts[0].start_jd), 800.0);
+
+        let (contacts, unscheduled) = schedule_contacts(&[req], &[station(1, 0.0)], |_, _| {
+            vec![window(100.0, 5000.0)]
+        });
+        assert!(contacts.is_empty());
+        assert_eq!(
+            unscheduled[0].reason,
+            "data volume needs a station rate_bps"
+        );
+    }
+
+    #[test]
+    fn the_plan_gates_only_pairs_with_a_planned_station() {
+        let plan = ContactPlan {
+            start_jd: 0.0,
+            end_jd: 1.0,
+            stations: vec![1],
+            contacts: vec![Contact {
+                station: 1,
+                asset: 10,
+                start_jd: 0.25,
+                end_jd: 0.5,
+                priority: 0,
+                request: 0,
+            }],
+            unscheduled: Vec::new(),
+            enforce: true,
+        };
+        assert_eq!(plan.scheduled(10, 1, 0.3), Some(true));
+        assert_eq!(plan.scheduled(1, 10, 0.6), Some(false));
+        assert_eq!(plan.scheduled(1, 20, 0.3), Some(false));
+        assert_eq!(plan.scheduled(10, 20, 0.3), None, "no planned station");
+        assert_eq!(plan.scheduled(1, 10, 2.0), None, "after the plan");
+
+        let steps = plan.timeline(10)["steps"].clone();
+        assert_eq!(steps[0]["wait"], serde_json::json!(0.25 * SECONDS_PER_DAY));
+        assert_eq!(steps[1]["emit"], CONTACT_START_EVENT);
+        assert_eq!(steps[1]["value"], 1);
+        assert_eq!(steps[3]["emit"], CONTACT_END_EVENT);
+    }
+}
@@ -0,0 +1,918 @@
+//! **The circular restricted three-body problem** — halo orbits, NRHOs, and the
+//! placement that flies a relay on one.
+//!
+//! [`LibrationAnchor`] parks a node *at* an L-point: the right fidelity for "is
+//! there a relay out there", the wrong one for Gateway. A real libration-point
+//! spacecraft flies a periodic orbit *around* the point, and for lunar-south-pole
+//! coverage the orbit IS the answer — a 9:2 southern near-rectilinear halo orbit
+//! (NRHO) hangs ~70,000 km over the south pole for most of its 6.6-day period and
+//! whips through a ~3,200 km perilune over the north pole once per revolution.
+//!
+//! # What is solved
+//!
+//! Non-dimensional CR3BP in the **standard** synodic axes: +x primary→secondary,
+//! +y along the secondary's motion, +z the orbit normal; unit length the pair's
+//! separation, unit time `1/n`. A halo orbit is symmetric about the xz-plane, so
+//! it is fixed by one crossing `(x₀, 0, z₀, 0, ẏ₀, 0)` and corrected by the
+//! classic half-period shooting: integrate the state and its transition matrix to
+//! the next `y = 0` crossing and drive `ẋ` and `ż` there to zero.
+//!
+//! A **family** is a curve of such crossings. [`HaloFamily`] walks it by
+//! pseudo-arclength continuation from a tabulated Earth–Moon seed, which is what
+//! makes the NRHOs reachable at all: natural-parameter continuation in `z₀`
+//! folds at the family's maximum out-of-plane amplitude, and the NRHOs sit past
+//! that fold. A member is picked by period (the 9:2 NRHO is `2/9` of a synodic
+//! month) or by perilune radius.
+//!
+//! # How it meets the ephemeris
+//!
+//! It does not get corrected against it. The CR3BP is circular; the Moon is
+//! not. [`HaloPath`] only *places* the periodic CR3BP solution in the
+//! **pulsating** synodic frame of the real pair — origin at the instantaneous
+//! barycentre, axes from [`FrameTree::synodic_basis`], length scaled by the
+//! instantaneous separation — so the orbit keeps its shape relative to where
+//! the Moon actually is. Time advances at the pair's mean motion. Nothing here
+//! propagates in the ephemeris: there is no multiple-shooting correction, no
+//! solar perturbation and no station-keeping, and over weeks a real spacecraft
+//! would depart from this path by the kilometres those terms buy. For coverage
+//! and link geometry that is the right trade; for ΔV budgets it is not.
+//!
+//! [`LibrationAnchor`]: crate::transform::LibrationAnchor
+//! [`FrameTree::synodic_basis`]: crate::transform::FrameTree
+
+use bevy::math::DVec3;
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use bevy::tasks::{AsyncComputeTaskPool, Task};
+use futures_lite::future;
+use std::sync::Arc;
+
+use lunco_time::WorldTime;
+
+use crate::coords::ecliptic_to_bevy;
+use crate::ephemeris::{EphemerisProvider, EphemerisResource};
+use crate::frames::{BodyId, LPoint, Pair};
+use crate::registry::CelestialBodyRegistry;
+
+/// A CR3BP state `[x, y, z, ẋ, ẏ, ż]`, non-dimensional, standard axes.
+pub type State = [f64; 6];
+
+/// Relative and absolute tolerance of the adaptive integrator, per component of
+/// the state (the transition matrix rides along uncontrolled).
+const TOLERANCE: f64 = 1e-12;
+/// Integrator steps before a propagation is abandoned as not converging.
+const MAX_STEPS: usize = 200_000;
+/// Time past the start before a `y = 0` crossing counts: the initial state sits
+/// on the plane.
+const MIN_HALF_PERIOD: f64 = 1e-2;
+/// Crossing residual `|ẋ|, |ż|` at which a halo orbit counts as periodic.
+const PERIODIC_TOLERANCE: f64 = 1e-11;
+/// Pseudo-arclength step along a family, in `(x₀, z₀, ẏ₀)`.
+const FAMILY_STEP: f64 = 0.01;
+/// Family steps before a selection gives up.
+const MAX_FAMILY_STEPS: usize = 400;
+/// Samples per period of a [`HaloPath`].
+const PATH_SAMPLES: usize = 4096;
+
+/// The CR3BP of one pair: `µ = m₂ / (m₁ + m₂)`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Cr3bp {
+    pub mu: f64,
+}
+
+impl Cr3bp {
+    pub fn new(mu: f64) -> Self {
+        Self { mu }
+    }
+
+    /// The pair's mass ratio from the registry. Both masses required, as for
+    /// every other pair quantity in the crate.
+    pub fn for_pair(registry: &CelestialBodyRegistry, pair: Pair) -> Option<Self> {
+        let gm_p = registry.get(pair.primary)?.gm;
+        let gm_s = registry.get(pair.secondary)?.gm;
+        (gm_p > 0.0 && gm_s > 0.0).then(|| Self::new(gm_s / (gm_p + gm_s)))
+    }
+
+    /// `ds/dt`.
+    pub fn derivative(&self, s: &State) -> State {
+        let [x, y, z, vx, vy, vz] = *s;
+        let mu = self.mu;
+        let r1 = ((x + mu).powi(2) + y * y + z * z).sqrt().powi(3);
+        let r2 = ((x - 1.0 + mu).powi(2) + y * y + z * z).sqrt().powi(3);
+        [
+            vx,
+            vy,
+            vz,
+            2.0 * vy + x - (1.0 - mu) * (x + mu) / r1 - mu * (x - 1.0 + mu) / r2,
+            -2.0 * vx + y - (1.0 - mu) * y / r1 - mu * y / r2,
+            -(1.0 - mu) * z / r1 - mu * z / r2,
+        ]
+    }
+
+    /// The state and its 6×6 transition matrix (row-major, after the state).
+    fn derivative_with_stm(&self, y: &[f64; 42]) -> [f64; 42] {
+        let s: State = y[..6].try_into().unwrap_or_default();
+        let [x, yy, z, ..] = s;
+        let mu = self.mu;
+        let (d1, d2) = (x + mu, x - 1.0 + mu);
+        let r1 = (d1 * d1 + yy * yy + z * z).sqrt();
+        let r2 = (d2 * d2 + yy * yy + z * z).sqrt();
+        let (k1, k2) = ((1.0 - mu) / r1.powi(3), mu / r2.powi(3));
+        let (q1, q2) = (3.0 * (1.0 - mu) / r1.powi(5), 3.0 * mu / r2.powi(5));
+        // Hessian of the effective potential.
+        let uxx = 1.0 - k1 - k2 + q1 * d1 * d1 + q2 * d2 * d2;
+        let uyy = 1.0 - k1 - k2 + (q1 + q2) * yy * yy;
+        let uzz = -k1 - k2 + (q1 + q2) * z * z;
+        let uxy = (q1 * d1 + q2 * d2) * yy;
+        let uxz = (q1 * d1 + q2 * d2) * z;
+        let uyz = (q1 + q2) * yy * z;
+        let a = [
+            [0.0, 0.0, 0.0, 1.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 1.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0, 0.0, 1.0],
+            [uxx, uxy, uxz, 0.0, 2.0, 0.0],
+            [uxy, uyy, uyz, -2.0, 0.0, 0.0],
+            [uxz, uyz, uzz, 0.0, 0.0, 0.0],
+        ];
+        let mut out = [0.0; 42];
+        out[..6].copy_from_slice(&self.derivative(&s));
+        for (i, row) in a.iter().enumerate() {
+            for j in 0..6 {
+                out[6 + i * 6 + j] = (0..6).map(|k| row[k] * y[6 + k * 6 + j]).sum();
+            }
+        }
+        out
+    }
+
+    /// The Jacobi constant — conserved along any trajectory, so a propagation
+    /// that drifts in it has lost accuracy.
+    pub fn jacobi(&self, s: &State) -> f64 {
+        let [x, y, z, vx, vy, vz] = *s;
+        let mu = self.mu;
+        let r1 = ((x + mu).powi(2) + y * y + z * z).sqrt();
+        let r2 = ((x - 1.0 + mu).powi(2) + y * y + z * z).sqrt();
+        x * x + y * y + 2.0 * (1.0 - mu) / r1 + 2.0 * mu / r2 - (vx * vx + vy * vy + vz * vz)
+    }
+
+    /// `s` after `t` time units. `None` if the integrator cannot get there (a
+    /// trajectory through a primary's centre).
+    pub fn propagate(&self, s: &State, t: f64) -> Option<State> {
+        integrate(|y| self.derivative(y), *s, t, false).map(|(y, _)| y)
+    }
+
+    /// The x of a collinear libration point, by Newton on the x-axis force
+    /// balance. `None` for L4/L5, which are not on the axis.
+    pub fn collinear_x(&self, point: LPoint) -> Option<f64> {
+        let mu = self.mu;
+        let mut x = match point {
+            LPoint::L1 => 1.0 - mu - (mu / 3.0).cbrt(),
+            LPoint::L2 => 1.0 - mu + (mu / 3.0).cbrt(),
+            LPoint::L3 => -1.0 - 5.0 * mu / 12.0,
+            LPoint::L4 | LPoint::L5 => return None,
+        };
+        for _ in 0..50 {
+            let f = self.derivative(&[x, 0.0, 0.0, 0.0, 0.0, 0.0])[3];
+            let h = 1e-7;
+            let df = (self.derivative(&[x + h, 0.0, 0.0, 0.0, 0.0, 0.0])[3] - f) / h;
+            let dx = f / df;
+            x -= dx;
+            if dx.abs() < 1e-15 {
+                break;
+            }
+        }
+        Some(x)
+    }
+}
+
+/// Dormand–Prince 5(4) with adaptive steps, from `0` to `t_end`. With `crossing`,
+/// stops instead at the first `y = 0` crossing after [`MIN_HALF_PERIOD`] and
+/// returns its time. Error is controlled on the first six components.
+fn integrate<const N: usize>(
+    f: impl Fn(&[f64; N]) -> [f64; N],
+    y0: [f64; N],
+    t_end: f64,
+    crossing: bool,
+) -> Option<([f64; N], f64)> {
+    const C: [[f64; 6]; 6] = [
+        [1.0 / 5.0, 0.0, 0.0, 0.0, 0.0, 0.0],
+        [3.0 / 40.0, 9.0 / 40.0, 0.0, 0.0, 0.0, 0.0],
+        [44.0 / 45.0, -56.0 / 15.0, 32.0 / 9.0, 0.0, 0.0, 0.0],
+        [
+            19372.0 / 6561.0,
+            -25360.0 / 2187.0,
+            64448.0 / 6561.0,
+            -212.0 / 729.0,
+            0.0,
+            0.0,
+        ],
+        [
+            9017.0 / 3168.0,
+            -355.0 / 33.0,
+            46732.0 / 5247.0,
+            49.0 / 176.0,
+            -5103.0 / 18656.0,
+            0.0,
+        ],
+        [
+            35.0 / 384.0,
+            0.0,
+            500.0 / 1113.0,
+            125.0 / 192.0,
+            -2187.0 / 6784.0,
+            11.0 / 84.0,
+        ],
+    ];
+    const B5: [f64; 7] = [
+        35.0 / 384.0,
+        0.0,
+        500.0 / 1113.0,
+        125.0 / 192.0,
+        -2187.0 / 6784.0,
+        11.0 / 84.0,
+        0.0,
+    ];
+    const B4: [f64; 7] = [
+        5179.0 / 57600.0,
+        0.0,
+        7571.0 / 16695.0,
+        393.0 / 640.0,
+        -92097.0 / 339200.0,
+        187.0 / 2100.0,
+        1.0 / 40.0,
+    ];
+    let step = |y: &[f64; N], h: f64| -> ([f64; N], f64) {
+        let mut k = [[0.0; N]; 7];
+        k[0] = f(y);
+        for (i, row) in C.iter().enumerate() {
+            let mut yi = *y;
+            for (n, v) in yi.iter_mut().enumerate() {
+                *v += h * (0..=i).map(|j| row[j] * k[j][n]).sum::<f64>();
+            }
+            k[i + 1] = f(&yi);
+        }
+        let mut y5 = *y;
+        let mut err: f64 = 0.0;
+        for (n, v) in y5.iter_mut().enumerate() {
+            let d5: f64 = (0..7).map(|i| B5[i] * k[i][n]).sum();
+            let d4: f64 = (0..7).map(|i| B4[i] * k[i][n]).sum();
+            *v += h * d5;
+            if n < 6 {
+                let scale = TOLERANCE * (1.0 + v.abs());
+                err = err.max((h * (d5 - d4)).abs() / scale);
+            }
+        }
+        (y5, err)
+    };
+
+    let (mut y, mut t, mut h) = (y0, 0.0, 1e-3_f64.min(t_end.abs()));
+    for _ in 0..MAX_STEPS {
+        if t_end - t <= 0.0 {
+            // A crossing search that runs out of time has found nothing.
+            return (!crossing).then_some((y, t));
+        }
+        let hh = h.min(t_end - t);
+        let (next, err) = step(&y, hh);
+        if !err.is_finite() {
+            return None;
+        }
+        if err <= 1.0 {
+            if crossing && t + hh > MIN_HALF_PERIOD && y[1] * next[1] < 0.0 {
+                // Regula falsi on the step length: one Dormand–Prince step from
+                // the accepted state is a smooth function of `h`.
+                let (mut a, mut ya, mut b, mut yb) = (0.0, y[1], hh, next[1]);
+                let mut at = next;
+                for _ in 0..60 {
+                    let m = a - ya * (b - a) / (yb - ya);
+                    at = step(&y, m).0;
+                    if at[1].abs() < 1e-14 {
+                        return Some((at, t + m));
+                    }
+                    if ya * at[1] < 0.0 {
+                        (b, yb) = (m, at[1]);
+                    } else {
+                        (a, ya) = (m, at[1]);
+                    }
+                }
+                return Some((at, t + a));
+            }
+            y = next;
+            t += hh;
+        }
+        h = hh * (0.9 * err.max(1e-10).powf(-0.2)).clamp(0.2, 4.0);
+    }
+    None
+}
+
+/// A periodic orbit: its xz-plane crossing and its period, non-dimensional.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PeriodicOrbit {
+    pub state: State,
+    pub period: f64,
+}
+
+impl PeriodicOrbit {
+    /// Closest approach to the secondary, in units of the pair's separation.
+    pub fn perilune(&self, cr: &Cr3bp) -> f64 {
+        const SAMPLES: usize = 512;
+        let mut s = self.state;
+        let mut best = f64::INFINITY;
+        for _ in 0..SAMPLES {
+            best = best.min(DVec3::new(s[0] - 1.0 + cr.mu, s[1], s[2]).length());
+            match cr.propagate(&s, self.period / SAMPLES as f64) {
+                Some(next) => s = next,
+                None => break,
+            }
+        }
+        best
+    }
+
+    /// The same orbit reflected through the `z = 0` plane: the other branch of
+    /// a halo family.
+    pub fn mirrored(&self) -> Self {
+        let mut state = self.state;
+        state[2] = -state[2];
+        state[5] = -state[5];
+        Self {
+            state,
+            period: self.period,
+        }
+    }
+}
+
+/// The half-period shooting map of a symmetric crossing `(x₀, z₀, ẏ₀)`: the
+/// residual `(ẋ, ż)` at the next crossing, its Jacobian in `(x₀, z₀, ẏ₀)` (the
+/// crossing-time variation folded in), and the half period.
+fn half_period_map(cr: &Cr3bp, x: [f64; 3]) -> Option<([f64; 2], [[f64; 3]; 2], f64)> {
+    let mut y = [0.0; 42];
+    y[0] = x[0];
+    y[2] = x[1];
+    y[4] = x[2];
+    for i in 0..6 {
+        y[6 + i * 6 + i] = 1.0;
+    }
+    let (yf, half) = integrate(|y| cr.derivative_with_stm(y), y, 10.0, true)?;
+    let s: State = yf[..6].try_into().ok()?;
+    let d = cr.derivative(&s);
+    let phi = |r: usize, c: usize| yf[6 + r * 6 + c];
+    let row = |r: usize| [0, 2, 4].map(|c| phi(r, c) - d[r] / s[4] * phi(1, c));
+    Some(([s[3], s[5]], [row(3), row(5)], half))
+}
+
+fn cross(a: [f64; 3], b: [f64; 3]) -> [f64; 3] {
+    DVec3::from_array(a).cross(DVec3::from_array(b)).to_array()
+}
+
+fn crossing_state(x: [f64; 3]) -> State {
+    [x[0], 0.0, x[1], 0.0, x[2], 0.0]
+}
+
+/// Correct `guess` to a periodic halo orbit, holding `z₀` and varying `x₀`,
+/// `ẏ₀`.
+pub fn correct_halo(cr: &Cr3bp, guess: State) -> Result<PeriodicOrbit, String> {
+    let mut x = [guess[0], guess[2], guess[4]];
+    for _ in 0..30 {
+        let (f, j, half) = half_period_map(cr, x).ok_or("halo correction: propagation failed")?;
+        if f[0].abs() < PERIODIC_TOLERANCE && f[1].abs() < PERIODIC_TOLERANCE {
+            return Ok(PeriodicOrbit {
+                state: crossing_state(x),
+                period: 2.0 * half,
+            });
+        }
+        // Columns x₀ and ẏ₀ of the 2×3 Jacobian.
+        let (a, b, c, d) = (j[0][0], j[0][2], j[1][0], j[1][2]);
+        let det = a * d - b * c;
+        if det.abs() < 1e-14 {
+            return Err("halo correction: singular Jacobian".into());
+        }
+        x[0] += (-d * f[0] + b * f[1]) / det;
+        x[2] += (c * f[0] - a * f[1]) / det;
+    }
+    Err("halo correction did not converge".into())
+}
+
+/// Which side of the plane of the pair a halo's apolune hangs on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Reflect)]
+pub enum HaloBranch {
+    /// Apolune south of the orbit plane — the branch that serves a south pole.
+    #[default]
+    South,
+    North,
+}
+
+impl HaloBranch {
+    /// Parse an authored token (`"south"` / `"north"`, case-insensitive).
+    pub fn from_token(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "south" | "southern" => Some(Self::South),
+            "north" | "northern" => Some(Self::North),
+            _ => None,
+        }
+    }
+}
+
+/// What picks a member out of a halo family.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HaloSelect {
+    /// Period, non-dimensional.
+    Period(f64),
+    /// Closest approach to the secondary, non-dimensional.
+    Perilune(f64),
+}
+
+/// One member on the continuation curve: the crossing, the family tangent
+/// there, and the period.
+#[derive(Debug, Clone, Copy)]
+struct Member {
+    x: [f64; 3],
+    tangent: [f64; 3],
+    period: f64,
+}
+
+/// A halo family of a collinear point — L1 or L2 — on one branch.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HaloFamily {
+    pub point: LPoint,
+    pub branch: HaloBranch,
+}
+
+impl HaloFamily {
+    /// A member of the southern family, tabulated for Earth–Moon (`µ ≈ 0.01215`)
+    /// and re-corrected for the pair's own `µ`: the 9:2 NRHO for L2, a small
+    /// halo for L1. A pair far from Earth–Moon's mass ratio needs its own seed.
+    fn seed(&self) -> Result<State, String> {
+        match self.point {
+            LPoint::L1 => Ok([0.823_386, 0.0, -0.0224, 0.0, 0.134_266, 0.0]),
+            LPoint::L2 => Ok([1.022_026, 0.0, -0.1821, 0.0, -0.103_267, 0.0]),
+            other => Err(format!("{other:?} has no halo family")),
+        }
+    }
+
+    fn member(cr: &Cr3bp, orbit: PeriodicOrbit, previous: Option<[f64; 3]>) -> Option<Member> {
+        let x = [orbit.state[0], orbit.state[2], orbit.state[4]];
+        let (_, j, _) = half_period_map(cr, x)?;
+        let t = DVec3::from_array(cross(j[0], j[1])).normalize_or_zero();
+        // Keep walking the same way: the null vector's sign is arbitrary.
+        let t = match previous {
+            Some(p) if t.dot(DVec3::from_array(p)) < 0.0 => -t,
+            _ => t,
+        };
+        Some(Member {
+            x,
+            tangent: t.to_array(),
+            period: orbit.period,
+        })
+    }
+
+    /// The next member `ds` along the family from `from`: predictor along the
+    /// tangent, Newton on the residual plus the arclength constraint.
+    fn step(cr: &Cr3bp, from: &Member, ds: f64) -> Option<Member> {
+        let t = DVec3::from_array(from.tangent);
+        let x0 = DVec3::from_array(from.x);
+        let mut x = x0 + t * ds;
+        for _ in 0..20 {
+            let (f, j, half) = half_period_map(cr, x.to_array())?;
+            let g = t.dot(x - x0) - ds;
+            if f[0].abs() < PERIODIC_TOLERANCE && f[1].abs() < PERIODIC_TOLERANCE && g.abs() < 1e-12
+            {
+                let orbit = PeriodicOrbit {
+                    state: crossing_state(x.to_array()),
+                    period: 2.0 * half,
+                };
+                return Self::member(cr, orbit, Some(from.tangent));
+            }
+            let m =
+                bevy::math::DMat3::from_cols(DVec3::from_array(j[0]), DVec3::from_array(j[1]), t)
+                    .transpose();
+            if m.determinant().abs() < 1e-16 {
+                return None;
+            }
+            x -= m.inverse() * DVec3::new(f[0], f[1], g);
+        }
+        None
+    }
+
+    /// `count` southern members spaced `ds` apart from the seed; `ds < 0`
+    /// walks the other way. Stops early where the family leaves the
+    /// corrector's reach (an impact, a bifurcation).
+    pub fn members(&self, cr: &Cr3bp, ds: f64, count: usize) -> Result<Vec<PeriodicOrbit>, String> {
+        let seed = correct_halo(cr, self.seed()?)?;
+        let mut at = Self::member(cr, seed, None).ok_or("halo family: no tangent at the seed")?;
+        let mut out = vec![self.oriented(seed)];
+        while out.len() < count {
+            let Some(next) = Self::step(cr, &at, ds) else {
+                break;
+            };
+            at = next;
+            out.push(self.oriented(PeriodicOrbit {
+                state: crossing_state(at.x),
+                period: at.period,
+            }));
+        }
+        Ok(out)
+    }
+
+    fn oriented(&self, orbit: PeriodicOrbit) -> PeriodicOrbit {
+        match self.branch {
+            HaloBranch::South => orbit,
+            HaloBranch::North => orbit.mirrored(),
+        }
+    }
+
+    /// The member matching `select`, by walking the family from the seed toward
+    /// it and bisecting the bracketing step.
+    pub fn select(&self, cr: &Cr3bp, select: HaloSelect) -> Result<PeriodicOrbit, String> {
+        let orbit = |m: &Member| PeriodicOrbit {
+            state: crossing_state(m.x),
+            period: m.period,
+        };
+        let residual = |m: &Member| match select {
+            HaloSelect::Period(p) => m.period - p,
+            HaloSelect::Perilune(r) => orbit(m).perilune(cr) - r,
+        };
+        let seed = correct_halo(cr, self.seed()?)?;
+        let start = Self::member(cr, seed, None).ok_or("halo family: no tangent at the seed")?;
+        let g0 = residual(&start);
+        if g0.abs() < 1e-12 {
+            return Ok(self.oriented(seed));
+        }
+        // Head whichever way the residual shrinks.
+        let probe =
+            Self::step(cr, &start, FAMILY_STEP).ok_or("halo family: cannot leave the seed")?;
+        let ds = if residual(&probe).abs() < g0.abs() {
+            FAMILY_STEP
+        } else {
+            -FAMILY_STEP
+        };
+        let (mut at, mut g) = (start, g0);
+        for _ in 0..MAX_FAMILY_STEPS {
+            let Some(next) = Self::step(cr, &at, ds) else {
+                break;
+            };
+            let g_next = residual(&next);
+            if g.signum() != g_next.signum() {
+                // Bisect the arclength between `at` and `next`.
+                let (mut lo, mut hi) = (0.0, ds);
+                let mut best = next;
+                for _ in 0..40 {
+                    let mid = 0.5 * (lo + hi);
+                    let Some(m) = Self::step(cr, &at, mid) else {
+                        break;
+                    };
+                    let gm = residual(&m);
+                    best = m;
+                    if gm.abs() < 1e-10 {
+                        break;
+                    }
+                    if gm.signum() == g.signum() {
+                        lo = mid;
+                    } else {
+                        hi = mid;
+                    }
+                }
+                return Ok(self.oriented(orbit(&best)));
+            }
+            (at, g) = (next, g_next);
+        }
+        Err(format!(
+            "no {:?} {:?} halo with {select:?} within reach of the seed",
+            self.branch, self.point
+        ))
+    }
+}
+
+/// Places the prim on a **halo orbit** of a body pair — by default the
+/// Earth–Moon 9:2 southern L2 NRHO, Gateway's orbit.
+///
+/// The fourth placement kind, beside geodetic, Kepler and libration. Authored
+/// as a family and a selector, not as a state vector: the orbit is corrected
+/// against the pair's own mass ratio when the component lands (see
+/// [`solve_halo_orbits`]), and resolved per epoch through [`HaloPath`].
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct HaloOrbit {
+    pub primary: BodyId,
+    pub secondary: BodyId,
+    /// L1 or L2.
+    pub point: LPoint,
+    pub branch: HaloBranch,
+    /// Select the member with this period. Takes precedence over
+    /// `perilune_m`; `0` to select by perilune instead.
+    pub period_days: f64,
+    /// Select the member with this closest approach to the secondary's
+    /// centre, metres. Used only when `period_days` is `0`.
+    pub perilune_m: f64,
+    /// The epoch at which the spacecraft is at `phase`; `0` = the clock when
+    /// the orbit is solved.
+    pub epoch_jd: f64,
+    /// Fraction of a period past apolune at `epoch_jd`.
+    pub phase: f64,
+}
+
+impl Default for HaloOrbit {
+    /// The 9:2 southern L2 NRHO: nine revolutions per two synodic months.
+    fn default() -> Self {
+        Self {
+            primary: crate::registry::ephemeris_id::EARTH,
+            secondary: crate::registry::ephemeris_id::MOON,
+            point: LPoint::L2,
+            branch: HaloBranch::South,
+            period_days: 2.0 / 9.0 * 29.530_589,
+            perilune_m: 0.0,
+            epoch_jd: 0.0,
+            phase: 0.0,
+        }
+    }
+}
+
+/// A solved [`HaloOrbit`]: one period of the CR3BP solution, sampled, plus what
+/// it takes to place it against the real pair at any epoch.
+#[derive(Debug)]
+pub struct HaloPath {
+    pub spec: HaloOrbit,
+    pub pair: Pair,
+    pub orbit: PeriodicOrbit,
+    /// The pair's mean motion, rad/s — the CR3BP time unit is `1/n`.
+    pub mean_motion: f64,
+    /// The epoch of `spec.phase`.
+    pub epoch_jd: f64,
+    samples: Vec<State>,
+}
+
+impl HaloPath {
+    /// Sample `orbit` for placement. `separation_m` is the pair's characteristic
+    /// distance, which with the total mass fixes the time unit.
+    pub fn new(
+        spec: HaloOrbit,
+        orbit: PeriodicOrbit,
+        cr: &Cr3bp,
+        gm_total: f64,
+        separation_m: f64,
+        epoch_jd: f64,
+    ) -> Option<Self> {
+        let h = orbit.period / PATH_SAMPLES as f64;
+        let mut samples = Vec::with_capacity(PATH_SAMPLES + 1);
+        let mut s = orbit.state;
+        samples.push(s);
+        for _ in 0..PATH_SAMPLES {
+            s = cr.propagate(&s, h)?;
+            samples.push(s);
+        }
+        Some(Self {
+            spec,
+            pair: Pair {
+                primary: spec.primary,
+                secondary: spec.secondary,
+            },
+            orbit,
+            mean_motion: (gm_total / separation_m.powi(3)).sqrt(),
+            epoch_jd,
+            samples,
+        })
+    }
+
+    /// The period in days.
+    pub fn period_days(&self) -> f64 {
+        self.orbit.period / self.mean_motion / 86_400.0
+    }
+
+    /// Position at `jd` in the engine's synodic axes ([`crate::frames::Synodic`]:
+    /// +X primary→secondary, +Y the orbit normal), in units of the pair's
+    /// separation. Cubic Hermite between samples, using the sampled velocities.
+    pub fn position_nd(&self, jd: f64) -> DVec3 {
+        let tau = self.spec.phase * self.orbit.period
+            + (jd - self.epoch_jd) * 86_400.0 * self.mean_motion;
+        let h = self.orbit.period / PATH_SAMPLES as f64;
+        let u = tau.rem_euclid(self.orbit.period) / h;
+        let i = (u.floor() as usize).min(PATH_SAMPLES - 1);
+        let f = u - i as f64;
+        let (a, b) = (&self.samples[i], &self.samples[i + 1]);
+        let p = |s: &State| DVec3::new(s[0], s[1], s[2]);
+        let v = |s: &State| DVec3::new(s[3], s[4], s[5]) * h;
+        let (f2, f3) = (f * f, f * f * f);
+        let standard = p(a) * (2.0 * f3 - 3.0 * f2 + 1.0)
+            + v(a) * (f3 - 2.0 * f2 + f)
+            + p(b) * (-2.0 * f3 + 3.0 * f2)
+            + v(b) * (f3 - f2);
+        // Standard CR3BP axes → engine synodic: the normal is +Y, so the
+        // secondary's direction of motion is −Z.
+        DVec3::new(standard.x, standard.z, -standard.y)
+    }
+}
+
+/// The solved orbit of a [`HaloOrbit`] prim; its presence is what makes the
+/// prim placeable.
+#[derive(Component, Debug, Clone)]
+pub struct HaloTrajectory(pub Arc<HaloPath>);
+
+/// Mean separation of a pair over a month of daily samples around `jd` — the
+/// CR3BP length unit.
+fn mean_separation(provider: &dyn EphemerisProvider, pair: Pair, jd: f64) -> Option<f64> {
+    const DAYS: i32 = 28;
+    let mut sum = 0.0;
+    for d in -DAYS / 2..DAYS / 2 {
+        let t = jd + d as f64;
+        let p = provider.global_position(pair.primary, t)?;
+        let s = provider.global_position(pair.secondary, t)?;
+        sum += (ecliptic_to_bevy(s).raw() - ecliptic_to_bevy(p).raw()).length();
+    }
+    Some(sum / DAYS as f64)
+}
+
+/// Solve `spec` against the registry and ephemeris at `now_jd`.
+pub fn solve_halo(
+    spec: &HaloOrbit,
+    registry: &CelestialBodyRegistry,
+    provider: &dyn EphemerisProvider,
+    now_jd: f64,
+) -> Result<HaloPath, String> {
+    let pair = Pair {
+        primary: spec.primary,
+        secondary: spec.secondary,
+    };
+    let cr = Cr3bp::for_pair(registry, pair)
+        .ok_or_else(|| format!("no masses for pair {}/{}", pair.primary, pair.secondary))?;
+    let gm_total = registry.get(pair.primary).map_or(0.0, |b| b.gm)
+        + registry.get(pair.secondary).map_or(0.0, |b| b.gm);
+    let epoch_jd = if spec.epoch_jd > 0.0 {
+        spec.epoch_jd
+    } else {
+        now_jd
+    };
+    let length = mean_separation(provider, pair, epoch_jd)
+        .filter(|l| *l > 0.0)
+        .ok_or("no ephemeris for the pair")?;
+    let n = (gm_total / length.powi(3)).sqrt();
+    let select = if spec.period_days > 0.0 {
+        HaloSelect::Period(spec.period_days * 86_400.0 * n)
+    } else if spec.perilune_m > 0.0 {
+        HaloSelect::Perilune(spec.perilune_m / length)
+    } else {
+        return Err("needs `period_days` or `perilune_m`".into());
+    };
+    let family = HaloFamily {
+        point: spec.point,
+        branch: spec.branch,
+    };
+    let orbit = family.select(&cr, select)?;
+    let radius = registry.get(pair.secondary).map_or(0.0, |b| b.radius_m);
+    let perilune = orbit.perilune(&cr) * length;
+    if perilune <= radius {
+        return Err(format!(
+            "perilune {:.0} km is inside the secondary ({:.0} km)",
+            perilune / 1000.0,
+            radius / 1000.0
+        ));
+    }
+    HaloPath::new(*spec, orbit, &cr, gm_total, length, epoch_jd)
+        .ok_or_else(|| "orbit sampling failed".into())
+}
+
+/// An in-flight [`solve_halo`] for the spec it was started from. A family walk
+/// is seconds of integration in a debug build, so it runs on the async compute
+/// pool rather than stalling the frame or the per-frame compute work.
+#[derive(Component)]
+pub struct HaloSolveTask {
+    spec: HaloOrbit,
+    task: Task<Result<HaloPath, String>>,
+}
+
+/// Solve every [`HaloOrbit`] whose [`HaloTrajectory`] is missing or stale, and
+/// land finished solves. A spec that failed is not retried until it changes;
+/// the record of it goes with the entity.
+#[allow(clippy::type_complexity)]
+pub fn solve_halo_orbits(
+    mut commands: Commands,
+    world_time: Option<Res<WorldTime>>,
+    registry: Option<Res<CelestialBodyRegistry>>,
+    ephemeris: Option<Res<EphemerisResource>>,
+    mut q: Query<(
+        Entity,
+        &HaloOrbit,
+        Option<&HaloTrajectory>,
+        Option<&mut HaloSolveTask>,
+    )>,
+    mut failed: Local<HashMap<Entity, HaloOrbit>>,
+) {
+    let (Some(world_time), Some(registry), Some(ephemeris)) = (world_time, registry, ephemeris)
+    else {
+        return;
+    };
+    failed.retain(|entity, _| q.contains(*entity));
+    let pool = AsyncComputeTaskPool::get();
+    for (entity, spec, solved, pending) in &mut q {
+        if let Some(mut pending) = pending {
+            let Some(result) = future::block_on(future::poll_once(&mut pending.task)) else {
+                continue;
+            };
+            commands.entity(entity).remove::<HaloSolveTask>();
+            // Re-authored while solving: the next pass starts over.
+            if pending.spec != *spec {
+                continue;
+            }
+            match result {
+                Ok(path) => {
+                    info!(
+                        "[cr3bp] {entity}: {:?} {:?} halo of {}/{}, period {:.3} d",
+                        spec.branch,
+                        spec.point,
+                        spec.primary,
+                        spec.secondary,
+                        path.period_days()
+                    );
+                    failed.remove(&entity);
+                    commands
+                        .entity(entity)
+                        .try_insert(HaloTrajectory(Arc::new(path)));
+                }
+                Err(e) => {
+                    warn!("[cr3bp] {entity}: halo orbit not solved: {e}");
+                    failed.insert(entity, *spec);
+                    commands.entity(entity).try_remove::<HaloTrajectory>();
+                }
+            }
+            continue;
+        }
+        if solved.is_some_and(|t| t.0.spec == *spec) || failed.get(&entity) == Some(spec) {
+            continue;
+        }
+        let provider = Arc::clone(&ephemeris.provider);
+        let registry = (*registry).clone();
+        let (task_spec, now_jd) = (*spec, world_time.epoch_jd);
+        let task =
+            pool.spawn(async move { solve_halo(&task_spec, &registry, provider.as_ref(), now_jd) });
+        commands
+            .entity(entity)
+            .try_insert(HaloSolveTask { spec: *spec, task });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EARTH_MOON: Cr3bp = Cr3bp {
+        mu: 4.904_869_5e12 / (3.986_004_418e14 + 4.904_869_5e12),
+    };
+    /// Earth–Moon time unit for a 384,400 km separation, days.
+    const TU_DAYS: f64 = 4.342_5;
+
+    #[test]
+    fn collinear_points_are_equilibria() {
+        for point in [LPoint::L1, LPoint::L2, LPoint::L3] {
+            let x = EARTH_MOON.collinear_x(point).unwrap();
+            let a = EARTH_MOON.derivative(&[x, 0.0, 0.0, 0.0, 0.0, 0.0]);
+            assert!(a[3].abs() < 1e-12, "{point:?} at x = {x}");
+        }
+        // L2 sits ~64,500 km beyond the Moon.
+        let l2 = EARTH_MOON.collinear_x(LPoint::L2).unwrap();
+        assert!((l2 - (1.0 - EARTH_MOON.mu) - 0.1678).abs() < 1e-3);
+    }
+
+    #[test]
+    fn the_9_2_nrho_is_periodic_and_keeps_its_jacobi_constant() {
+        let family = HaloFamily {
+            point: LPoint::L2,
+            branch: HaloBranch::South,
+        };
+        let target = 2.0 / 9.0 * 29.530_589 / TU_DAYS;
+        let orbit = family
+            .select(&EARTH_MOON, HaloSelect::Period(target))
+            .unwrap();
+        assert!((orbit.period - target).abs() < 1e-6);
+        // Southern: the crossing it is stored at is the apolune, below the plane.
+        assert!(orbit.state[2] < -0.15);
+        // Perilune ~3,200 km from the Moon's centre — above the surface.
+        let rp_km = orbit.perilune(&EARTH_MOON) * 384_400.0;
+        assert!((2_900.0..3_600.0).contains(&rp_km), "perilune {rp_km} km");
+
+        let end = EARTH_MOON.propagate(&orbit.state, orbit.period).unwrap();
+        let miss = DVec3::new(end[0] - orbit.state[0], end[1], end[2] - orbit.state[2]);
+        assert!(miss.length() < 1e-6, "closes to {miss:?}");
+        let drift = EARTH_MOON.jacobi(&end) - EARTH_MOON.jacobi(&orbit.state);
+        assert!(drift.abs() < 1e-9);
+    }
+
+    #[test]
+    fn the_north_branch_mirrors_the_south() {
+        let south = correct_halo(
+            &EARTH_MOON,
+            HaloFamily {
+                point: LPoint::L2,
+                branch: HaloBranch::South,
+            }
+            .seed()
+            .unwrap(),
+        )
+        .unwrap();
+        let north = south.mirrored();
+        let (a, b) = (
+            EARTH_MOON
+                .propagate(&south.state, 0.3 * south.period)
+                .unwrap(),
+            EARTH_MOON
+                .propagate(&north.state, 0.3 * north.period)
+                .unwrap(),
+        );
+        assert!((a[0] - b[0]).abs() < 1e-9 && (a[2] + b[2]).abs() < 1e-9);
+    }
+}
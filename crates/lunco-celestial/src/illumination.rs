@@ -0,0 +1,213 @@
+//! Ephemeris sweep for the long-duration illumination bake.
+//!
+//! `lunco-environment` marches every texel of a terrain's heightfield through a
+//! window of sun and Earth directions and reduces the result to illumination,
+//! longest-darkness and Earth-visibility maps. It cannot produce those
+//! directions itself — the ephemeris lives here — so [`BakeIlluminationMaps`]
+//! samples it, rotates each step into the site's ENU frame with the same
+//! [`crate::systems::solar_to_site_rotation`] that aims the scene sun, and then
+//! into each terrain's local frame.
+//!
+//! When a bake lands, [`publish_illumination_layers`] mirrors the environment's
+//! [`TerrainIllumination`] into terrain-surface's [`TerrainIlluminationLayers`],
+//! the two crates not depending on each other — the same split as the radiation
+//! sky fraction.
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use lunco_core::{on_command, register_commands, Ack, Command, OpId};
+use lunco_environment::{
+    pick_sun, start_illumination_bake, HorizonMap, HorizonShadowCacheConfig, IlluminationBake,
+    IlluminationSweep, SunQuery, TerrainIllumination,
+};
+use lunco_terrain_surface::TerrainIlluminationLayers;
+use lunco_time::WorldTime;
+
+use crate::coords::ecliptic_to_bevy;
+use crate::ephemeris::EphemerisResource;
+use crate::registry::CelestialBodyRegistry;
+use crate::systems::solar_to_site_rotation;
+
+/// Twelve synodic months — one pass of the sun through its full range of
+/// declinations as seen from the Moon.
+const LUNAR_YEAR_DAYS: f64 = 354.367;
+const DEFAULT_STEP_HOURS: f64 = 6.0;
+const DEFAULT_RESOLUTION: u32 = 128;
+/// Largest raster side a bake may request; 1024² × the sweep is already minutes.
+const MAX_RESOLUTION: u32 = 1024;
+/// Sweep steps cap: a year at one-hour steps, with headroom.
+const MAX_STEPS: usize = 20_000;
+/// tan of the sun's angular radius (0.2666°) for a scene sun without a
+/// `SunAngularDiameter`.
+const FALLBACK_TAN_SUN_R: f32 = 0.004_653;
+
+/// Bake illumination, longest-darkness and Earth-visibility maps for every
+/// horizon-mapped terrain over an ephemeris window. Zero fields take the
+/// defaults: the current epoch, one lunar year, 6 h steps, 128² rasters.
+/// Results land asynchronously and are cached on disk by sweep and heightfield.
+#[Command(default)]
+pub struct BakeIlluminationMaps {
+    /// Window start, TDB Julian date.
+    pub start_jd: f64,
+    /// Window length in days.
+    pub days: f64,
+    /// Sample spacing in hours.
+    pub step_hours: f64,
+    /// Texels per side of each map.
+    pub resolution: u32,
+}
+
+#[on_command(BakeIlluminationMaps)]
+fn on_bake_illumination_maps(
+    _t: On<BakeIlluminationMaps>,
+    mut commands: Commands,
+    world_time: Res<WorldTime>,
+    ephemeris: Option<Res<EphemerisResource>>,
+    registry: Res<CelestialBodyRegistry>,
+    horizon: Option<Res<HorizonShadowCacheConfig>>,
+    q_site: Query<&crate::geo::GeodeticAnchor, With<crate::geo::SiteAnchor>>,
+    sun: SunQuery,
+    terrains: Query<(Entity, &GlobalTransform, &HorizonMap)>,
+) -> Result<Ack, String> {
+    let or_default = |v: f64, d: f64| if v > 0.0 { v } else { d };
+    let start_jd = or_default(cmd.start_jd, world_time.epoch_jd);
+    let days = or_default(cmd.days, LUNAR_YEAR_DAYS);
+    let step_hours = or_default(cmd.step_hours, DEFAULT_STEP_HOURS);
+    let resolution = if cmd.resolution == 0 {
+        DEFAULT_RESOLUTION
+    } else {
+        cmd.resolution
+    };
+    if !(start_jd.is_finite() && days.is_finite() && step_hours.is_finite()) {
+        return Err("BakeIlluminationMaps: non-finite window".into());
+    }
+    if !(2..=MAX_RESOLUTION).contains(&resolution) {
+        return Err(format!(
+            "BakeIlluminationMaps: resolution must be 2..={MAX_RESOLUTION}, got {resolution}"
+        ));
+    }
+    let steps = (days * 24.0 / step_hours).ceil() as usize;
+    if steps > MAX_STEPS {
+        return Err(format!(
+            "BakeIlluminationMaps: {steps} steps exceeds {MAX_STEPS}; widen step_hours"
+        ));
+    }
+
+    let Some(ephemeris) = ephemeris else {
+        return Err("BakeIlluminationMaps: no ephemeris loaded".into());
+    };
+    let Some(anchor) = q_site.iter().next() else {
+        return Err("BakeIlluminationMaps: no site anchor in the scene".into());
+    };
+    let Some(site_body) = registry.get(anchor.body) else {
+        return Err(format!(
+            "BakeIlluminationMaps: site body {} is not registered",
+            anchor.body
+        ));
+    };
+    let earth_radius_m = registry
+        .get(crate::ephemeris_id::EARTH)
+        .map_or(6_371_000.0, |d| d.radius_m);
+
+    // Site-ENU directions for every step; terrain-local per terrain below.
+    let mut to_sun = Vec::with_capacity(steps);
+    let mut to_earth = Vec::with_capacity(steps);
+    let mut tan_earth_sum = 0.0;
+    for k in 0..steps {
+        let jd = start_jd + k as f64 * step_hours / 24.0;
+        let provider = &ephemeris.provider;
+        let (Some(p_sun), Some(p_earth), Some(p_obs)) = (
+            provider.global_position(crate::ephemeris_id::SUN, jd),
+            provider.global_position(crate::ephemeris_id::EARTH, jd),
+            provider.global_position(anchor.body, jd),
+        ) else {
+            return Err(format!(
+                "BakeIlluminationMaps: ephemeris has no coverage at JD {jd:.3}"
+            ));
+        };
+        let solar_to_site = solar_to_site_rotation(
+            site_body,
+            &anchor.geodetic,
+            ecliptic_to_bevy(p_obs).raw(),
+            jd,
+        );
+        let sun_vec = ecliptic_to_bevy(p_sun - p_obs).raw();
+        let earth_vec = ecliptic_to_bevy(p_earth - p_obs).raw();
+        if sun_vec.length_squared() == 0.0 || earth_vec.length_squared() == 0.0 {
+            return Err("BakeIlluminationMaps: degenerate ephemeris (no-op provider?)".into());
+        }
+        to_sun.push((solar_to_site * sun_vec.normalize()).as_vec3());
+        to_earth.push((solar_to_site * earth_vec.normalize()).as_vec3());
+        tan_earth_sum += earth_radius_m / earth_vec.length();
+    }
+    let tan_sun_r = pick_sun(&sun)
+        .map(|(_, tan, _)| tan)
+        .filter(|tan| *tan > 0.0)
+        .unwrap_or(FALLBACK_TAN_SUN_R);
+    let tan_earth_r = (tan_earth_sum / steps.max(1) as f64) as f32;
+    let march_steps = horizon.map(|h| h.march_steps).unwrap_or_default().max(1);
+
+    let mut started = 0;
+    for (entity, gt, map) in &terrains {
+        let to_local = gt.affine().inverse();
+        let local = |dirs: &[Vec3]| -> Vec<Vec3> {
+            dirs.iter()
+                .map(|d| to_local.transform_vector3(*d).normalize_or_zero())
+                .collect()
+        };
+        let sweep = IlluminationSweep {
+            start_jd,
+            step_hours,
+            to_sun: local(&to_sun),
+            to_earth: local(&to_earth),
+            tan_sun_r,
+            tan_earth_r,
+        };
+        start_illumination_bake(
+            &mut commands,
+            entity,
+            IlluminationBake {
+                field: map.field.clone(),
+                sweep: Arc::new(sweep),
+                resolution,
+                march_steps,
+            },
+        );
+        started += 1;
+    }
+    if started == 0 {
+        return Err("BakeIlluminationMaps: no horizon-mapped terrain to bake".into());
+    }
+    info!(
+        "[illumination] {started} bake(s): JD {start_jd:.2} + {days:.1} d at {step_hours} h, \
+         {resolution}² texels"
+    );
+    Ok(Ack::new(OpId::new()))
+}
+
+register_commands!(on_bake_illumination_maps);
+
+/// Mirror a landed [`TerrainIllumination`] onto the same terrain as
+/// terrain-surface's [`TerrainIlluminationLayers`], which drives the overlay
+/// drapes and the `TerrainField` illumination fields.
+pub(crate) fn publish_illumination_layers(
+    mut commands: Commands,
+    q: Query<(Entity, &TerrainIllumination), Changed<TerrainIllumination>>,
+) {
+    for (entity, TerrainIllumination(maps)) in &q {
+        commands
+            .entity(entity)
+            .try_insert(TerrainIlluminationLayers {
+                res: maps.res as usize,
+                min: maps.min.as_dvec2().to_array(),
+                size: maps.size.as_dvec2().to_array(),
+                start_jd: maps.start_jd,
+                end_jd: maps.end_jd,
+                step_hours: maps.step_hours,
+                illumination_pct: Arc::from(maps.illumination_pct.as_slice()),
+                longest_darkness_hours: Arc::from(maps.longest_darkness_hours.as_slice()),
+                earth_visibility_pct: Arc::from(maps.earth_visibility_pct.as_slice()),
+            });
+    }
+}
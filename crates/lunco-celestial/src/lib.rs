@@ -18,7 +18,9 @@ mod big_space_setup;
 /// re-implementing `ecliptic_to_bevy` by hand for want of access — a conversion people copy
 /// is a conversion that drifts.
 pub mod cadence;
+pub mod contacts;
 pub mod coords;
+pub mod cr3bp;
 mod embedded_assets;
 pub mod ephemeris;
 /// Coordinate-frame newtypes. Zero-cost, and they make the two silent frame-mix incidents
@@ -29,6 +31,7 @@ pub mod geo;
 mod globe_lod;
 mod gravity;
 pub mod iau;
+pub mod illumination;
 mod imagery;
 pub mod kepler;
 pub mod link;
@@ -36,10 +39,12 @@ mod missions;
 pub mod placement;
 pub mod pose;
 pub mod queries;
+pub mod radiation;
 pub mod registry;
 mod soi;
 mod surface_pose;
 mod systems;
+pub mod tf;
 mod trajectories;
 /// Frame CONVERSIONS over [`frames`] — hub-and-spoke through `Solar`, like SPICE.
 ///
@@ -50,6 +55,7 @@ mod trajectories;
 /// been reachable from a scene.
 pub mod transform;
 pub mod wifi;
+pub mod windows;
 
 pub mod commands;
 /// UI panels for celestial time control and body browser.
@@ -234,6 +240,14 @@ impl Plugin for CelestialPlugin {
             Update,
             pose::update_solar_poses.run_if(cadence::tracked_needs_solve()),
         );
+        // Halo/NRHO placements: solved once per authored spec on the async
+        // compute pool, then evaluated per epoch by the pose layer like any other
+        // placement.
+        app.register_type::<cr3bp::HaloOrbit>();
+        app.add_systems(
+            Update,
+            cr3bp::solve_halo_orbits.before(pose::update_solar_poses),
+        );
 
         // Generic connectivity kernel: cadence-gated pairwise link solving in
         // Rust, verdict via the language-neutral `link.connected` hook, cadence
@@ -254,6 +268,30 @@ impl Plugin for CelestialPlugin {
         // twin/terrain despawns and tripped avian's island bookkeeping.)
         app.add_systems(Update, link::update_links.after(pose::update_solar_poses));
         app.add_systems(Update, wifi::update_wifi_links.after(link::update_links));
+        // Ground-station contact plans: scheduled over the window predictor and
+        // honoured by `update_links` while enforced.
+        app.init_resource::<contacts::ContactPlan>();
+        contacts::register_all_commands(app);
+        // Named frame tree: `LookupTransform` between any two frames at any epoch,
+        // static frames registered by command, and `/tf`-style telemetry streams.
+        app.init_resource::<tf::TfStaticFrames>();
+        app.init_resource::<tf::TfStreams>();
+        tf::register_all_commands(app);
+        app.add_systems(
+            Update,
+            tf::publish_tf_streams.after(pose::update_solar_poses),
+        );
+        // Body shadowing for the radiation domain: the environment crate declares
+        // `RadiationSkyFraction`, this crate owns the ephemeris that fills it.
+        app.add_systems(
+            Update,
+            radiation::update_radiation_sky_fraction.after(pose::update_solar_poses),
+        );
+        // Long-duration illumination maps: the ephemeris sweep is built here, the
+        // march runs in `lunco-environment`, and the landed maps are mirrored into
+        // terrain-surface's overlay/`TerrainField` layers.
+        illumination::register_all_commands(app);
+        app.add_systems(Update, illumination::publish_illumination_layers);
         // Expose the working peer's range + verdict as PORTS, so an authored RF model
         // (`assets/models/CommsLink.mo`) can turn metres into bits/s off an ordinary
         // output→input wire.
@@ -404,6 +442,7 @@ impl Plugin for CelestialPlugin {
             PreUpdate,
             (
                 ephemeris_update_system.run_if(cadence::tracked_needs_solve()),
+                synodic_frame_system.run_if(cadence::tracked_needs_solve()),
                 body_rotation_system.run_if(cadence::tracked_needs_solve()),
                 // The solar hierarchy stays inertial. Site content is mounted
                 // once beneath its body's rotating surface grid; no ancestor is
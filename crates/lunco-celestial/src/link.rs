@@ -259,10 +259,12 @@ pub struct LinkPeer {
 /// (the peers' GIDs — the same ids `find()` returns), `name_a`, `name_b`,
 /// `class_a`, `class_b`, `range_m`, `light_time_s`, `elev_a`, `elev_b`,
 /// `min_elev_a`, `min_elev_b`, `occluded`, `occluded_by`, `terrain_blocked`,
-/// `occluder_blocked`, `max_range_m`, `builtin`. Return bool. `builtin` is the
-/// range/mask/occlusion result, supplied so a policy can add role restrictions
-/// without reimplementing geometry. No hook → the builtin
-/// range+mask+occlusion rule
+/// `occluder_blocked`, `max_range_m`, `builtin`, `scheduled`. Return bool.
+/// `builtin` is the range/mask/occlusion result, supplied so a policy can add role
+/// restrictions without reimplementing geometry. `scheduled` is an enforced
+/// [`ContactPlan`](crate::contacts::ContactPlan)'s say on the pair — `()` when it
+/// has none. No hook → the builtin range+mask+occlusion rule, gated by
+/// `scheduled` when present
 /// (which does NOT gate on delay — a policy that refuses links slower than some
 /// latency budget is exactly the kind of thing this hook is for).
 pub const LINK_HOOK: &str = "link.connected";
@@ -348,6 +350,7 @@ pub(crate) fn update_links(
     q_spatial: Query<(Option<&CellCoord>, &Transform)>,
     mut q_state: Query<&mut LinkState>,
     mut q_geometry: Query<&mut LinkGeometryState>,
+    plan: Option<Res<crate::contacts::ContactPlan>>,
     mut state: Local<LinkSolverState>,
     mut commands: Commands,
 ) {
@@ -394,18 +397,7 @@ pub(crate) fn update_links(
 
     // Body centers for analytic occlusion.
     let bodies: Vec<(String, DVec3, f64)> = match (ephemeris.as_deref(), registry.as_deref()) {
-        (Some(eph), Some(reg)) => reg
-            .bodies
-            .iter()
-            .filter(|b| b.radius_m > 0.0)
-            // A body we cannot place cannot occlude anything. Skipping it is right; placing it
-            // at the Sun's centre (the old behaviour) would have it eclipse everything.
-            .filter_map(|b| {
-                let p = eph.provider.global_position(b.ephemeris_id, jd)?;
-                Some((b.name.clone(), ecliptic_to_bevy(p), b.radius_m))
-            })
-            .map(|(n, p, r)| (n, p.raw(), r))
-            .collect(),
+        (Some(eph), Some(reg)) => occluding_bodies(eph, reg, jd),
         _ => Vec::new(),
     };
 
@@ -445,49 +437,28 @@ pub(crate) fn update_links(
             if nested_link_nodes(a.entity, b.entity, &q_parents) {
                 continue;
             }
-            let d = b.pose.pos - a.pose.pos;
-            let range_m = d.length();
-            let dir = if range_m > 1e-6 {
-                d / range_m
-            } else {
-                DVec3::ZERO
-            };
-            // Elevation above the endpoint's OWN horizon — `None` when it has none.
-            // The `Option` is the contract: a free-flyer is not at 0° and not at 90°,
-            // it is unmeasured, and a mask has nothing to compare against.
-            let elev = |h: crate::pose::Horizon, dir: DVec3| -> Option<f64> {
-                h.up()
-                    .map(|up| up.dot(dir).clamp(-1.0, 1.0).asin().to_degrees())
-            };
-            let elev_a = elev(a.pose.horizon, dir);
-            let elev_b = elev(b.pose.horizon, -dir);
-            // No horizon ⇒ the mask does not gate. Said once, here, instead of at
-            // every reader.
-            let above_mask = |elev: Option<f64>, min: f64| elev.is_none_or(|e| e >= min);
-
-            let occluded_by = bodies
-                .iter()
-                .find(|(_, c, r)| segment_hits_sphere(a.pose.pos, b.pose.pos, *c, *r))
-                .map(|(n, _, _)| n.clone());
-
-            let cheap_ok = range_m <= a.node.max_range_m.min(b.node.max_range_m)
-                && above_mask(elev_a, a.node.min_elevation_deg)
-                && above_mask(elev_b, b.node.min_elevation_deg)
-                && occluded_by.is_none();
-            // Terrain relief (a rille rim / hill between the endpoints) shadows the
-            // link. March the DEM in the site-local frame — `SolarFramePose::local`
-            // IS the terrain oracle frame (see `pose.rs`). Skipped when the analytic
-            // body check already severs, and cheap when no terrain is loaded.
-            let terrain_blocked = cheap_ok && terrain_blocks(a.pose.local, b.pose.local, &terrains);
-            // Authored geometry between the endpoints (a wall, a habitat). Skipped
-            // once something cheaper already severs — the verdict is the same and
-            // the hook reads the first cause, not every cause.
-            let occluder_blocked = cheap_ok
-                && !terrain_blocked
-                // Occluders and endpoints are both solar-frame poses. Their
-                // local scene positions are reserved for the DEM oracle.
-                && occluder_blocks(a.pose.pos, b.pose.pos, &occluders);
-            let builtin = cheap_ok && !terrain_blocked && !occluder_blocked;
+            let PairGeometry {
+                range_m,
+                elev_a,
+                elev_b,
+                occluded_by,
+                terrain_blocked,
+                occluder_blocked,
+                builtin,
+            } = pair_geometry(
+                (&a.pose, &a.node),
+                (&b.pose, &b.node),
+                &bodies,
+                &terrains,
+                &occluders,
+            );
+            // An enforced contact plan gives each planned station one asset at a
+            // time. `None` when the plan has no say on this pair.
+            let scheduled = plan
+                .as_deref()
+                .filter(|p| p.enforce)
+                .and_then(|p| p.scheduled(a.gid, b.gid, jd));
+            let verdict = builtin && scheduled.unwrap_or(true);
 
             let ctx = HookValue::map([
                 // Identity first (the ids `find()` speaks), labels alongside for a
@@ -529,10 +500,16 @@ pub(crate) fn update_links(
                 // add only its domain/routing rule. This keeps a policy from
                 // accidentally reopening an occluded or out-of-range pair.
                 ("builtin", HookValue::Bool(builtin)),
+                // The contact plan's verdict (`crate::contacts`): `()` when no
+                // enforced plan governs this pair.
+                (
+                    "scheduled",
+                    scheduled.map_or(HookValue::Unit, HookValue::Bool),
+                ),
             ]);
             let raw = match lunco_hooks::invoke(LINK_HOOK, &[ctx]) {
-                Some(Ok(v)) => v.as_bool().unwrap_or(builtin),
-                _ => builtin,
+                Some(Ok(v)) => v.as_bool().unwrap_or(verdict),
+                _ => verdict,
             };
 
             // Publish the domain-free geometry observation before applying the
@@ -647,6 +624,86 @@ pub(crate) fn update_links(
     }
 }
 
+/// The builtin geometry of one pair at one epoch: range, each endpoint's
+/// elevation, and the first cause that severs it. Shared by [`update_links`] and
+/// the window predictor (`crate::windows`), so a predicted AOS is exactly the
+/// live kernel's AOS.
+pub(crate) struct PairGeometry {
+    pub range_m: f64,
+    pub elev_a: Option<f64>,
+    pub elev_b: Option<f64>,
+    pub occluded_by: Option<String>,
+    pub terrain_blocked: bool,
+    pub occluder_blocked: bool,
+    /// Range, masks and every occlusion clear.
+    pub builtin: bool,
+}
+
+/// Solve [`PairGeometry`] for endpoints `a` and `b`. `bodies`, `terrains` and
+/// `occluders` are the same epoch's snapshots (see [`update_links`] for their
+/// frames).
+pub(crate) fn pair_geometry(
+    (a, a_node): (&SolarFramePose, &LinkNode),
+    (b, b_node): (&SolarFramePose, &LinkNode),
+    bodies: &[(String, DVec3, f64)],
+    terrains: &[(DVec3, DQuat, Arc<SurfaceOracle>)],
+    occluders: &[(DVec3, DQuat, DVec3)],
+) -> PairGeometry {
+    let d = b.pos - a.pos;
+    let range_m = d.length();
+    let dir = if range_m > 1e-6 {
+        d / range_m
+    } else {
+        DVec3::ZERO
+    };
+    let elev_a = elevation_deg(a.horizon, dir);
+    let elev_b = elevation_deg(b.horizon, -dir);
+    // No horizon ⇒ the mask does not gate. Said once, here, instead of at
+    // every reader.
+    let above_mask = |elev: Option<f64>, min: f64| elev.is_none_or(|e| e >= min);
+
+    let occluded_by = bodies
+        .iter()
+        .find(|(_, c, r)| segment_hits_sphere(a.pos, b.pos, *c, *r))
+        .map(|(n, _, _)| n.clone());
+
+    let cheap_ok = range_m <= a_node.max_range_m.min(b_node.max_range_m)
+        && above_mask(elev_a, a_node.min_elevation_deg)
+        && above_mask(elev_b, b_node.min_elevation_deg)
+        && occluded_by.is_none();
+    // Terrain relief (a rille rim / hill between the endpoints) shadows the
+    // link. March the DEM in the site-local frame — `SolarFramePose::local`
+    // IS the terrain oracle frame (see `pose.rs`). Skipped when the analytic
+    // body check already severs, and cheap when no terrain is loaded.
+    let terrain_blocked = cheap_ok && terrain_blocks(a.local, b.local, terrains);
+    // Authored geometry between the endpoints (a wall, a habitat). Skipped
+    // once something cheaper already severs — the verdict is the same and
+    // the hook reads the first cause, not every cause.
+    let occluder_blocked = cheap_ok
+        && !terrain_blocked
+        // Occluders and endpoints are both solar-frame poses. Their
+        // local scene positions are reserved for the DEM oracle.
+        && occluder_blocks(a.pos, b.pos, occluders);
+    PairGeometry {
+        range_m,
+        elev_a,
+        elev_b,
+        occluded_by,
+        terrain_blocked,
+        occluder_blocked,
+        builtin: cheap_ok && !terrain_blocked && !occluder_blocked,
+    }
+}
+
+/// Elevation of `dir` above a horizon's own plane, degrees — `None` when it has
+/// none. The `Option` is the contract: a free-flyer is not at 0° and not at 90°,
+/// it is unmeasured, and a mask has nothing to compare against.
+pub(crate) fn elevation_deg(horizon: crate::pose::Horizon, dir: DVec3) -> Option<f64> {
+    horizon
+        .up()
+        .map(|up| up.dot(dir).clamp(-1.0, 1.0).asin().to_degrees())
+}
+
 /// True when one endpoint is authored inside the other endpoint's subtree.
 /// Such a pair describes one physical assembly twice, not a usable path.
 fn nested_link_nodes(a: Entity, b: Entity, parents: &Query<&ChildOf>) -> bool {
@@ -693,7 +750,11 @@ pub fn node_label(class: Option<&str>, name: Option<&Name>, e: Entity) -> String
 /// `terrains` carries each DEM's grid-absolute pose (see the frame note on
 /// [`update_links`]). Scale is not composed: DEM surfaces are authored unscaled,
 /// and a scaled heightfield would need the oracle's own spacing rescaled too.
-fn terrain_blocks(a: DVec3, b: DVec3, terrains: &[(DVec3, DQuat, Arc<SurfaceOracle>)]) -> bool {
+pub(crate) fn terrain_blocks(
+    a: DVec3,
+    b: DVec3,
+    terrains: &[(DVec3, DQuat, Arc<SurfaceOracle>)],
+) -> bool {
     if terrains.is_empty() {
         return false;
     }
@@ -726,6 +787,26 @@ fn terrain_blocks(a: DVec3, b: DVec3, terrains: &[(DVec3, DQuat, Arc<SurfaceOrac
     false
 }
 
+/// Every registered body that can occlude, as `(name, solar-frame centre,
+/// radius_m)` at epoch `jd` — the sphere set behind the link kernel's analytic
+/// body occlusion, shared with the radiation sky-fraction solve.
+pub(crate) fn occluding_bodies(
+    eph: &EphemerisResource,
+    reg: &CelestialBodyRegistry,
+    jd: f64,
+) -> Vec<(String, DVec3, f64)> {
+    reg.bodies
+        .iter()
+        .filter(|b| b.radius_m > 0.0)
+        // A body we cannot place cannot occlude anything. Skipping it is right; placing it
+        // at the Sun's centre (the old behaviour) would have it eclipse everything.
+        .filter_map(|b| {
+            let p = eph.provider.global_position(b.ephemeris_id, jd)?;
+            Some((b.name.clone(), ecliptic_to_bevy(p).raw(), b.radius_m))
+        })
+        .collect()
+}
+
 /// Does any authored [`LinkOccluder`] box block the segment `a→b` (both
 /// grid-absolute)? Pure analytic geometry — no march, no physics query.
 /// `occluders` carries each box's grid-absolute `(center, rotation, half_extents)`.
@@ -57,8 +57,11 @@ pub struct MissionTrajectoryDecl {
     pub color: [f32; 4],
     pub sampling_days: f64,
     pub sampling_step: f64,
-    /// `"BodyFixed"` or `"Inertial"`.
+    /// `"BodyFixed"`, `"Inertial"` or `"Synodic"`.
     pub frame: String,
+    /// The pair's secondary for a `"Synodic"` frame (`reference_id` is the
+    /// primary).
+    pub secondary_id: Option<i32>,
     pub user_visible: Option<bool>,
     pub start_epoch_jd: Option<f64>,
     pub end_epoch_jd: Option<f64>,
@@ -234,6 +237,17 @@ pub fn spawn_declared_missions(
     for (decl_entity, traj) in q_trajectories.iter() {
         let frame = match traj.frame.as_str() {
             "BodyFixed" => TrajectoryFrame::BodyFixed,
+            "Synodic" => match traj.secondary_id {
+                Some(secondary) => TrajectoryFrame::Synodic { secondary },
+                None => {
+                    warn!(
+                        "[mission] {}: a Synodic trajectory needs a secondary id; skipped",
+                        traj.name
+                    );
+                    commands.entity(decl_entity).try_insert(MissionSpawned);
+                    continue;
+                }
+            },
             _ => TrajectoryFrame::Inertial,
         };
 
@@ -3,7 +3,8 @@
 //! `update_solar_poses` writes each tracked entity's position (+ local up for
 //! surface points) in the solar frame to a [`SolarFramePose`] component, resolved
 //! from its `GeodeticAnchor` (ground stations), `KeplerOrbit` (satellites, incl.
-//! LEO / lunar-orbit relays), or — for scene-local prims that move with a body
+//! LEO / lunar-orbit relays), `LibrationAnchor` / `HaloOrbit` (libration-point
+//! relays), or — for scene-local prims that move with a body
 //! (a rover-mounted antenna) — the site tangent frame. The scene-local path needs
 //! the big_space `Query` context that a read-only `query("SolarPose")` provider
 //! cannot get, which is exactly why this is a SYSTEM (docs 10/12).
@@ -16,10 +17,12 @@ use bevy::math::{DMat3, DQuat, DVec3};
 use bevy::platform::collections::HashMap;
 use bevy::prelude::*;
 use big_space::prelude::{CellCoord, Grid};
+use std::sync::Arc;
 
 use lunco_time::WorldTime;
 
 use crate::coords::ecliptic_to_bevy;
+use crate::cr3bp::{HaloOrbit, HaloPath, HaloTrajectory};
 use crate::ephemeris::EphemerisResource;
 use crate::frames::{BodyInertial, Pos};
 use crate::geo::{solar_tangent_frame, GeodeticAnchor, SiteAnchor};
@@ -111,11 +114,11 @@ impl SolarFramePose {
 
 /// Rotation that maps the scene's ENU axes (East=+X, Up=+Y, North=−Z) into the
 /// frame coordinates represented by `frame`.
-fn tangent_rotation(frame: &crate::geo::LocalTangentFrame) -> DQuat {
+pub(crate) fn tangent_rotation(frame: &crate::geo::LocalTangentFrame) -> DQuat {
     DQuat::from_mat3(&DMat3::from_cols(frame.east, frame.up, -frame.north))
 }
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 enum Placement {
     Geodetic {
         entity: Entity,
@@ -129,6 +132,11 @@ enum Placement {
         entity: Entity,
         anchor: LibrationAnchor,
     },
+    /// `None` until `solve_halo_orbits` has solved it.
+    Halo {
+        entity: Entity,
+        path: Option<Arc<HaloPath>>,
+    },
 }
 
 /// Find the nearest authored placement, including ancestors. Link endpoints are
@@ -140,6 +148,7 @@ fn nearest_placement(
     q_anchor: &Query<&GeodeticAnchor>,
     q_orbit: &Query<&KeplerOrbit>,
     q_libration: &Query<&LibrationAnchor>,
+    q_halo: &Query<(&HaloOrbit, Option<&HaloTrajectory>)>,
 ) -> Option<Placement> {
     std::iter::successors(Some(entity), |e| {
         q_parents.get(*e).ok().map(|child| child.parent())
@@ -173,6 +182,15 @@ fn nearest_placement(
                         anchor,
                     })
             })
+            .or_else(|| {
+                q_halo
+                    .get(candidate)
+                    .ok()
+                    .map(|(_, solved)| Placement::Halo {
+                        entity: candidate,
+                        path: solved.map(|t| Arc::clone(&t.0)),
+                    })
+            })
     })
 }
 
@@ -195,6 +213,276 @@ fn placement_offset(
     Some(entity_pos.0 - placement_pos.0)
 }
 
+/// The epoch-independent half of a pose: where an entity sits relative to its
+/// placement, read once from the scene. [`PoseContext::pose`] evaluates it at any
+/// epoch, which is how [`update_solar_poses`] and the window predictor
+/// (`crate::windows`) share one placement rule — a scene-local offset does not
+/// move while the ephemeris is swept ahead.
+#[derive(Debug, Clone)]
+pub(crate) enum PoseSource {
+    /// Under a `GeodeticAnchor`: `offset` from the anchor in its tangent frame.
+    Geodetic {
+        anchor: GeodeticAnchor,
+        offset: DVec3,
+        rotation: DQuat,
+    },
+    /// Under a `KeplerOrbit`.
+    Orbit {
+        orbit: KeplerOrbit,
+        offset: DVec3,
+        rotation: DQuat,
+    },
+    /// Under a `LibrationAnchor`.
+    Libration {
+        anchor: LibrationAnchor,
+        offset: DVec3,
+        rotation: DQuat,
+    },
+    /// Under a solved `HaloOrbit`.
+    Halo {
+        path: Arc<HaloPath>,
+        offset: DVec3,
+        rotation: DQuat,
+    },
+    /// No placement in the ancestry: positioned through the site frame.
+    SiteLocal { position: DVec3, rotation: DQuat },
+}
+
+/// Resolve an entity's [`PoseSource`] from the scene. `None` when its transform
+/// chain cannot be walked yet, or its halo orbit is not solved yet.
+#[allow(clippy::too_many_arguments)]
+pub(crate) fn pose_source(
+    entity: Entity,
+    q_anchor: &Query<&GeodeticAnchor>,
+    q_orbit: &Query<&KeplerOrbit>,
+    q_libration: &Query<&LibrationAnchor>,
+    q_halo: &Query<(&HaloOrbit, Option<&HaloTrajectory>)>,
+    q_parents: &Query<&ChildOf>,
+    q_grids: &Query<&Grid>,
+    q_spatial: &Query<(Option<&CellCoord>, &Transform)>,
+) -> Option<PoseSource> {
+    // ONE RULE: a node is placed by the nearest placement in its ancestry,
+    // INCLUDING itself. Its horizon belongs to that placement's body. This
+    // is the normal shape for a ground station: the link feed is several
+    // prims below the station's GeodeticAnchor.
+    let placement = nearest_placement(entity, q_parents, q_anchor, q_orbit, q_libration, q_halo);
+    let (position, rotation) =
+        lunco_core::coords::world_pose(entity, q_parents, q_grids, q_spatial).ok()?;
+    let offset_from = |placement_entity| {
+        placement_offset(entity, placement_entity, q_parents, q_grids, q_spatial)
+    };
+    Some(match placement {
+        Some(Placement::Geodetic {
+            entity: anchor_entity,
+            anchor,
+        }) => PoseSource::Geodetic {
+            anchor,
+            offset: offset_from(anchor_entity)?,
+            rotation: rotation.0,
+        },
+        Some(Placement::Orbit {
+            entity: orbit_entity,
+            orbit,
+        }) => PoseSource::Orbit {
+            orbit,
+            offset: offset_from(orbit_entity)?,
+            rotation: rotation.0,
+        },
+        Some(Placement::Libration {
+            entity: anchor_entity,
+            anchor,
+        }) => PoseSource::Libration {
+            anchor,
+            offset: offset_from(anchor_entity)?,
+            rotation: rotation.0,
+        },
+        Some(Placement::Halo {
+            entity: halo_entity,
+            path,
+        }) => PoseSource::Halo {
+            path: path?,
+            offset: offset_from(halo_entity)?,
+            rotation: rotation.0,
+        },
+        // Scene-local: the position is wherever the transform hierarchy puts it.
+        None => PoseSource::SiteLocal {
+            position: position.0,
+            rotation: rotation.0,
+        },
+    })
+}
+
+/// Everything a pose needs at one epoch: the ephemeris, the body registry, the
+/// site frame, and a per-epoch memo of body centres.
+pub(crate) struct PoseContext<'a> {
+    jd: f64,
+    registry: &'a CelestialBodyRegistry,
+    provider: &'a dyn crate::ephemeris::EphemerisProvider,
+    tree: FrameTree<'a>,
+    // A body's position is a full analytic series (VSOP87 / ELP-MPP02 — three
+    // series for the Moon). Evaluating it per TRACKED ENTITY made the cost
+    // O(entities x series) when it is O(bodies x series). Memoised per body for
+    // this context's one epoch; the map dies with it, so no epoch can ever be
+    // served a stale centre. `None` ⇒ no ephemeris for that body.
+    centers: HashMap<i32, Option<DVec3>>,
+    /// The site frame (scene-root anchor), for scene-local prims.
+    site: Option<(i32, crate::geo::LocalTangentFrame)>,
+}
+
+impl<'a> PoseContext<'a> {
+    pub(crate) fn new(
+        jd: f64,
+        registry: &'a CelestialBodyRegistry,
+        provider: &'a dyn crate::ephemeris::EphemerisProvider,
+        site_anchor: Option<&GeodeticAnchor>,
+    ) -> Self {
+        let mut ctx = Self {
+            jd,
+            registry,
+            provider,
+            // Loop-invariant like `centers`: `FrameTree` is a view over (jd,
+            // registry, provider), so rebuilding it per libration entity bought
+            // nothing.
+            tree: FrameTree::new(jd, registry, provider),
+            centers: HashMap::default(),
+            site: None,
+        };
+        let site = site_anchor.and_then(|anchor| {
+            let desc = ctx.body(anchor.body)?;
+            let center = ctx.body_center(anchor.body)?;
+            Some((
+                anchor.body,
+                solar_tangent_frame(desc, &anchor.geodetic, center, jd),
+            ))
+        });
+        ctx.site = site;
+        ctx
+    }
+
+    fn body(&self, naif: i32) -> Option<&'a crate::registry::BodyDescriptor> {
+        self.registry.bodies.iter().find(|b| b.ephemeris_id == naif)
+    }
+
+    /// Solar-frame centre of body `naif` at this epoch, memoised.
+    pub(crate) fn body_center(&mut self, naif: i32) -> Option<DVec3> {
+        let provider = self.provider;
+        let jd = self.jd;
+        *self.centers.entry(naif).or_insert_with(|| {
+            provider
+                .global_position(naif, jd)
+                .map(|p| ecliptic_to_bevy(p).raw())
+        })
+    }
+
+    /// The site tangent frame at this epoch, if the scene has a site anchor.
+    pub(crate) fn site_frame(&self) -> Option<&crate::geo::LocalTangentFrame> {
+        self.site.as_ref().map(|(_, f)| f)
+    }
+
+    /// Evaluate `source` at this context's epoch. `None` when a body it needs
+    /// has no ephemeris or registry entry — callers skip it rather than report
+    /// a pose at the Sun's centre that looks exactly like a real one.
+    pub(crate) fn pose(&mut self, source: &PoseSource) -> Option<SolarFramePose> {
+        let jd = self.jd;
+        let (pos, rotation, horizon) = match source.clone() {
+            PoseSource::Geodetic {
+                anchor,
+                offset,
+                rotation,
+            } => {
+                let desc = self.body(anchor.body)?;
+                let center = self.body_center(anchor.body)?;
+                let frame = solar_tangent_frame(desc, &anchor.geodetic, center, jd);
+                let pos = frame.to_frame(offset);
+                let up = (pos - center).normalize_or_zero();
+                (
+                    pos,
+                    tangent_rotation(&frame) * rotation,
+                    Horizon::Surface {
+                        body: anchor.body,
+                        up,
+                    },
+                )
+            }
+            PoseSource::Orbit {
+                orbit,
+                offset,
+                rotation,
+            } => {
+                let desc = self.body(orbit.body)?;
+                let body_inertial = Pos::<BodyInertial>::at_body(
+                    orbit.body,
+                    orbit.elements.position_bevy_m(desc.gm, jd),
+                );
+                let solar = self.tree.body_inertial_to_solar(body_inertial)?;
+                (
+                    solar.raw() + offset,
+                    rotation,
+                    Horizon::Free { body: orbit.body },
+                )
+            }
+            PoseSource::Libration {
+                anchor,
+                offset,
+                rotation,
+            } => {
+                let pos =
+                    self.tree
+                        .libration_in_solar(anchor.primary, anchor.secondary, anchor.point)?;
+                (
+                    pos.raw() + offset,
+                    rotation,
+                    Horizon::Free {
+                        body: anchor.secondary,
+                    },
+                )
+            }
+            PoseSource::Halo {
+                path,
+                offset,
+                rotation,
+            } => {
+                let pair = path.pair;
+                let origin = self.tree.pair_barycenter(pair.primary, pair.secondary)?;
+                let basis = self.tree.synodic_basis(pair.primary, pair.secondary)?;
+                // Pulsating frame: the CR3BP length unit is the separation NOW.
+                let separation =
+                    (self.body_center(pair.secondary)? - self.body_center(pair.primary)?).length();
+                (
+                    origin.raw() + basis * (path.position_nd(jd) * separation) + offset,
+                    rotation,
+                    Horizon::Free {
+                        body: pair.secondary,
+                    },
+                )
+            }
+            PoseSource::SiteLocal { position, rotation } => {
+                let (site_body, frame) = self.site.as_ref()?;
+                (
+                    frame.to_frame(position),
+                    tangent_rotation(frame) * rotation,
+                    Horizon::Surface {
+                        body: *site_body,
+                        up: frame.up,
+                    },
+                )
+            }
+        };
+        // Site-local position (terrain frame); = solar pos when unanchored.
+        let local = self
+            .site
+            .as_ref()
+            .map(|(_, f)| f.from_frame(pos))
+            .unwrap_or(pos);
+        Some(SolarFramePose {
+            pos,
+            rotation,
+            local,
+            horizon,
+        })
+    }
+}
+
 /// Refresh [`SolarFramePose`] for every tracked entity. Headless-safe; a no-op
 /// until `WorldTime` + ephemeris + registry exist.
 #[allow(clippy::too_many_arguments)]
@@ -208,17 +496,21 @@ pub fn update_solar_poses(
             With<GeodeticAnchor>,
             With<KeplerOrbit>,
             With<LibrationAnchor>,
+            With<HaloOrbit>,
             With<SolarTracked>,
             With<LinkNode>,
+            // Radiation body shadowing needs each probe's solar-frame position.
+            With<lunco_environment::EnvironmentProbe>,
         )>,
     >,
     q_site: Query<&GeodeticAnchor, With<SiteAnchor>>,
     // A link node is usually a deep child of the thing that IS anchored (a dish's
     // feed aperture, six prims under the ground station), so its own entity carries
-    // no anchor. Looked up by ancestry below.
+    // no anchor. Looked up by ancestry in `pose_source`.
     q_anchor: Query<&GeodeticAnchor>,
     q_orbit: Query<&KeplerOrbit>,
     q_libration: Query<&LibrationAnchor>,
+    q_halo: Query<(&HaloOrbit, Option<&HaloTrajectory>)>,
     q_parents: Query<&ChildOf>,
     q_grids: Query<&Grid>,
     q_spatial: Query<(Option<&CellCoord>, &Transform)>,
@@ -229,172 +521,28 @@ pub fn update_solar_poses(
     else {
         return;
     };
-    let jd = world_time.epoch_jd;
-    let body_of = |naif: i32| registry.bodies.iter().find(|b| b.ephemeris_id == naif);
-
-    // `jd` is fixed for this whole solve and only a handful of distinct bodies
-    // are referenced, but a body's position is a full analytic series (VSOP87 /
-    // ELP-MPP02 — three series for the Moon). Evaluating it per TRACKED ENTITY
-    // made the cost O(entities x series) when it is O(bodies x series): the same
-    // shape of bug as the over-broad query this system's sibling already fixed.
-    // Memoised per (body, jd) for the duration of the call; the map dies with it,
-    // so no epoch can ever be served a stale centre.
-    let mut centers: HashMap<i32, Option<DVec3>> = HashMap::default();
-    // `None` ⇒ no ephemeris for that body. Callers skip it rather than reporting a pose at the
-    // Sun's centre that looks exactly like a real one.
-    let provider = ephemeris.provider.as_ref();
-    let body_center = |naif: i32, centers: &mut HashMap<i32, Option<DVec3>>| -> Option<DVec3> {
-        *centers.entry(naif).or_insert_with(|| {
-            provider
-                .global_position(naif, jd)
-                .map(|p| ecliptic_to_bevy(p).raw())
-        })
-    };
-
-    // Loop-invariant like `centers`: `FrameTree` is a view over (jd, registry,
-    // provider), so rebuilding it per libration entity bought nothing.
-    let tree = FrameTree::new(jd, &registry, provider);
-
-    // The site frame (scene-root anchor), for scene-local prims.
-    let site = match q_site.iter().next() {
-        Some(anchor) => match (body_of(anchor.body), body_center(anchor.body, &mut centers)) {
-            (Some(desc), Some(center)) => Some((
-                anchor.body,
-                solar_tangent_frame(desc, &anchor.geodetic, center, jd),
-            )),
-            _ => None,
-        },
-        None => None,
-    };
+    let mut ctx = PoseContext::new(
+        world_time.epoch_jd,
+        &registry,
+        ephemeris.provider.as_ref(),
+        q_site.iter().next(),
+    );
 
     for entity in q_tracked.iter() {
-        // ONE RULE: a node is placed by the nearest placement in its ancestry,
-        // INCLUDING itself. Its horizon belongs to that placement's body. This
-        // is the normal shape for a ground station: the link feed is several
-        // prims below the station's GeodeticAnchor.
-        let placement = nearest_placement(entity, &q_parents, &q_anchor, &q_orbit, &q_libration);
-        let (pos, rotation, horizon) = if let Some(placement) = placement {
-            match placement {
-                Placement::Geodetic {
-                    entity: anchor_entity,
-                    anchor,
-                } => {
-                    let Some(desc) = body_of(anchor.body) else {
-                        continue;
-                    };
-                    let Some(center) = body_center(anchor.body, &mut centers) else {
-                        continue;
-                    };
-                    let Some(offset) =
-                        placement_offset(entity, anchor_entity, &q_parents, &q_grids, &q_spatial)
-                    else {
-                        continue;
-                    };
-                    let Some((_, entity_rotation)) =
-                        lunco_core::coords::world_pose(entity, &q_parents, &q_grids, &q_spatial)
-                            .ok()
-                    else {
-                        continue;
-                    };
-                    let frame = solar_tangent_frame(desc, &anchor.geodetic, center, jd);
-                    let pos = frame.to_frame(offset);
-                    let rotation = tangent_rotation(&frame) * entity_rotation.0;
-                    let up = (pos - center).normalize_or_zero();
-                    (
-                        pos,
-                        rotation,
-                        Horizon::Surface {
-                            body: anchor.body,
-                            up,
-                        },
-                    )
-                }
-                Placement::Orbit {
-                    entity: orbit_entity,
-                    orbit,
-                } => {
-                    let Some(desc) = body_of(orbit.body) else {
-                        continue;
-                    };
-                    let Some(offset) =
-                        placement_offset(entity, orbit_entity, &q_parents, &q_grids, &q_spatial)
-                    else {
-                        continue;
-                    };
-                    let Some((_, entity_rotation)) =
-                        lunco_core::coords::world_pose(entity, &q_parents, &q_grids, &q_spatial)
-                            .ok()
-                    else {
-                        continue;
-                    };
-                    let body_inertial = Pos::<BodyInertial>::at_body(
-                        orbit.body,
-                        orbit.elements.position_bevy_m(desc.gm, jd),
-                    );
-                    let Some(solar) = tree.body_inertial_to_solar(body_inertial) else {
-                        continue;
-                    };
-                    (
-                        solar.raw() + offset,
-                        entity_rotation.0,
-                        Horizon::Free { body: orbit.body },
-                    )
-                }
-                Placement::Libration {
-                    entity: anchor_entity,
-                    anchor,
-                } => {
-                    let Some(pos) =
-                        tree.libration_in_solar(anchor.primary, anchor.secondary, anchor.point)
-                    else {
-                        continue;
-                    };
-                    let Some(offset) =
-                        placement_offset(entity, anchor_entity, &q_parents, &q_grids, &q_spatial)
-                    else {
-                        continue;
-                    };
-                    let Some((_, entity_rotation)) =
-                        lunco_core::coords::world_pose(entity, &q_parents, &q_grids, &q_spatial)
-                            .ok()
-                    else {
-                        continue;
-                    };
-                    (
-                        pos.raw() + offset,
-                        entity_rotation.0,
-                        Horizon::Free {
-                            body: anchor.secondary,
-                        },
-                    )
-                }
-            }
-        } else if let Some((site_body, frame)) = &site {
-            // Scene-local: the position is wherever the transform hierarchy puts it.
-            let Some((world_position, world_rotation)) =
-                lunco_core::coords::world_pose(entity, &q_parents, &q_grids, &q_spatial).ok()
-            else {
-                continue;
-            };
-            let pos = frame.to_frame(world_position.0);
-            (
-                pos,
-                tangent_rotation(frame) * world_rotation.0,
-                Horizon::Surface {
-                    body: *site_body,
-                    up: frame.up,
-                },
-            )
-        } else {
+        let Some(source) = pose_source(
+            entity,
+            &q_anchor,
+            &q_orbit,
+            &q_libration,
+            &q_halo,
+            &q_parents,
+            &q_grids,
+            &q_spatial,
+        ) else {
             continue;
         };
-        // Site-local position (terrain frame); = solar pos when unanchored.
-        let local = site.as_ref().map(|(_, f)| f.from_frame(pos)).unwrap_or(pos);
-        let pose = SolarFramePose {
-            pos,
-            rotation,
-            local,
-            horizon,
+        let Some(pose) = ctx.pose(&source) else {
+            continue;
         };
 
         // Update in place (avoid per-tick insert churn); insert on first sight.
@@ -418,4 +418,7 @@ pub fn register_celestial_queries(app: &mut App) {
     reg.register(SolarPoseProvider);
     reg.register(LinksProvider);
     reg.register(WifiLinksProvider);
+    reg.register(crate::windows::PredictWindowsProvider);
+    reg.register(crate::contacts::ContactPlanProvider);
+    reg.register(crate::tf::LookupTransformProvider);
 }
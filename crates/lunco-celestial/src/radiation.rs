@@ -0,0 +1,162 @@
+//! Body shadowing for the radiation environment.
+//!
+//! `lunco-environment` models galactic cosmic rays and solar particle events as
+//! isotropic fields, so a planetary body shields a probe by exactly the solid
+//! angle it subtends. Working that out needs every body's position at the
+//! current epoch — the ephemeris, which lives here — so this crate fills the
+//! [`RadiationSkyFraction`] slot the environment crate declares, the same split
+//! as `EarthDirectionWorld`.
+//!
+//! The sphere set is the link kernel's own ([`crate::link::occluding_bodies`]),
+//! so a body that cuts a radio link and a body that shades a dosimeter are, by
+//! construction, the same body in the same place.
+
+use bevy::math::DVec3;
+use bevy::prelude::*;
+
+use lunco_environment::{EnvironmentProbe, RadiationSkyFraction};
+use lunco_time::WorldTime;
+
+use crate::ephemeris::EphemerisResource;
+use crate::pose::SolarFramePose;
+use crate::registry::CelestialBodyRegistry;
+
+/// Fraction of an isotropic sky left open around `pos` by a set of spheres
+/// `(centre, radius_m)`.
+///
+/// A sphere of radius `R` at distance `d` subtends the cap `(1 − cos θ) / 2` of
+/// the sky, with `sin θ = R / d`. A point on (or, through DEM relief, just
+/// inside) a body sees it block exactly half. Overlapping caps (a mutual
+/// eclipse) are counted once: largest first, each later cap less its overlap
+/// with every earlier one. That is exact for two bodies; with three sharing one
+/// patch of sky it subtracts the patch twice, so the blocked area comes out low
+/// and the dose high, never the other way round.
+pub fn open_sky_fraction(pos: DVec3, spheres: impl IntoIterator<Item = (DVec3, f64)>) -> f64 {
+    // (direction, angular radius θ) of each body as seen from `pos`.
+    let mut caps: Vec<(DVec3, f64)> = spheres
+        .into_iter()
+        .map(|(centre, radius)| {
+            let offset = centre - pos;
+            let d = offset.length();
+            let theta = if d <= radius {
+                std::f64::consts::FRAC_PI_2
+            } else {
+                (radius / d).asin()
+            };
+            (offset.normalize_or_zero(), theta)
+        })
+        .collect();
+    caps.sort_by(|a, b| b.1.total_cmp(&a.1));
+    let mut blocked = 0.0;
+    for (i, &(dir, theta)) in caps.iter().enumerate() {
+        let overlap: f64 = caps[..i]
+            .iter()
+            .map(|&(other, phi)| {
+                let separation = dir.dot(other).clamp(-1.0, 1.0).acos();
+                cap_overlap(theta, phi, separation)
+            })
+            .sum();
+        blocked += (cap_fraction(theta) - overlap).max(0.0);
+    }
+    (1.0 - blocked).clamp(0.0, 1.0)
+}
+
+/// Sky fraction of a cap of angular radius `theta`.
+fn cap_fraction(theta: f64) -> f64 {
+    (1.0 - theta.cos()) * 0.5
+}
+
+/// Sky fraction shared by two caps of angular radii `a` and `b` whose centres
+/// are `c` apart — the solid angle of two intersecting cones, over 4π.
+fn cap_overlap(a: f64, b: f64, c: f64) -> f64 {
+    use std::f64::consts::PI;
+    if c >= a + b {
+        return 0.0;
+    }
+    if c <= (a - b).abs() {
+        return cap_fraction(a.min(b));
+    }
+    let (ca, cb, cc) = (a.cos(), b.cos(), c.cos());
+    let (sa, sb, sc) = (a.sin(), b.sin(), c.sin());
+    let acos = |x: f64| x.clamp(-1.0, 1.0).acos();
+    let solid_angle = 2.0
+        * (PI
+            - acos((cc - ca * cb) / (sa * sb))
+            - acos((cb - cc * ca) / (sc * sa)) * ca
+            - acos((ca - cc * cb) / (sc * sb)) * cb);
+    (solid_angle / (4.0 * PI)).max(0.0)
+}
+
+/// Writes [`RadiationSkyFraction`] on every placed environment probe.
+///
+/// Probes are pose-tracked by [`crate::pose::update_solar_poses`]; one it could
+/// not place keeps no fraction and the environment reads it as free space.
+/// Change-guarded: the fraction moves only as a probe climbs or orbits.
+pub(crate) fn update_radiation_sky_fraction(
+    mut commands: Commands,
+    world_time: Option<Res<WorldTime>>,
+    ephemeris: Option<Res<EphemerisResource>>,
+    registry: Option<Res<CelestialBodyRegistry>>,
+    q_probes: Query<
+        (Entity, &SolarFramePose, Option<&RadiationSkyFraction>),
+        With<EnvironmentProbe>,
+    >,
+) {
+    let (Some(world_time), Some(ephemeris), Some(registry)) = (world_time, ephemeris, registry)
+    else {
+        return;
+    };
+    if q_probes.is_empty() {
+        return;
+    }
+    let bodies = crate::link::occluding_bodies(&ephemeris, &registry, world_time.epoch_jd);
+    for (entity, pose, existing) in &q_probes {
+        let open = open_sky_fraction(pose.pos, bodies.iter().map(|(_, c, r)| (*c, *r)));
+        let next = RadiationSkyFraction(open as f32);
+        if existing == Some(&next) {
+            continue;
+        }
+        commands.entity(entity).try_insert(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn surface_blocks_half_and_orbit_blocks_a_cap() {
+        let moon = (DVec3::ZERO, 1_737_400.0);
+        let surface = open_sky_fraction(DVec3::new(0.0, 1_737_400.0, 0.0), [moon]);
+        assert!((surface - 0.5).abs() < 1e-9);
+        // 100 km circular orbit: the Moon fills ~36% of the sky.
+        let llo = open_sky_fraction(DVec3::new(0.0, 1_837_400.0, 0.0), [moon]);
+        assert!(llo > 0.6 && llo < 0.7, "LLO open sky {llo}");
+        let far = open_sky_fraction(DVec3::new(0.0, 1.0e12, 0.0), [moon]);
+        assert!(far > 0.999_999);
+    }
+
+    /// A mutual eclipse: the smaller body sits wholly inside the larger one's
+    /// cap and blocks nothing extra; two bodies side by side overlapping by a
+    /// sliver block close to the sum of their caps, never more.
+    #[test]
+    fn overlapping_bodies_block_their_union() {
+        let probe = DVec3::ZERO;
+        let near = (DVec3::new(0.0, 0.0, 2.0), 1.0); // θ = 30°
+        let behind = (DVec3::new(0.0, 0.0, 10.0), 1.0); // θ ≈ 5.7°, inside `near`
+        let alone = open_sky_fraction(probe, [near]);
+        let both = open_sky_fraction(probe, [near, behind]);
+        assert!(
+            (both - alone).abs() < 1e-12,
+            "hidden body changed the sky: {both} vs {alone}"
+        );
+
+        // Two 30° caps 50° apart: the overlap is counted once.
+        let tilt = 50f64.to_radians();
+        let side = (DVec3::new(2.0 * tilt.sin(), 0.0, 2.0 * tilt.cos()), 1.0);
+        let cap = 1.0 - alone;
+        let union = 1.0 - open_sky_fraction(probe, [near, side]);
+        assert!(union < 2.0 * cap - 1e-4, "overlap double-counted: {union}");
+        assert!(union > cap, "second body blocked nothing: {union}");
+    }
+}
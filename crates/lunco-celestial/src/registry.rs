@@ -97,6 +97,10 @@ pub enum ReferenceFrame {
     EclipticJ2000 { center: i32 },
     /// IAU/WGCCRE body-fixed axes rotating with the named body.
     BodyFixed { body: i32 },
+    /// The pair's co-rotating (synodic) frame: origin at the pair barycentre,
+    /// +X primary→secondary, +Y the orbit normal. A libration point or a halo
+    /// orbit is stationary or closed here.
+    Synodic { primary: i32, secondary: i32 },
 }
 
 impl ReferenceFrame {
@@ -106,6 +110,17 @@ impl ReferenceFrame {
             Self::World => None,
             Self::EclipticJ2000 { center } => Some(center),
             Self::BodyFixed { body } => Some(body),
+            // The barycentre of a pair is not an ephemeris body; the frame is
+            // posed by `synodic_frame_system`, not the ephemeris pass.
+            Self::Synodic { .. } => None,
+        }
+    }
+
+    /// The primary/secondary pair of a synodic frame.
+    pub const fn pair(self) -> Option<(i32, i32)> {
+        match self {
+            Self::Synodic { primary, secondary } => Some((primary, secondary)),
+            Self::World | Self::EclipticJ2000 { .. } | Self::BodyFixed { .. } => None,
         }
     }
 
@@ -113,7 +128,7 @@ impl ReferenceFrame {
     pub const fn body_fixed(self) -> Option<i32> {
         match self {
             Self::BodyFixed { body } => Some(body),
-            Self::World | Self::EclipticJ2000 { .. } => None,
+            Self::World | Self::EclipticJ2000 { .. } | Self::Synodic { .. } => None,
         }
     }
 }
@@ -9,7 +9,8 @@
 //! object already in Earth's body-fixed frame remains there while Earth is
 //! dominant; an incoming object enters Earth's ecliptic-inertial frame. A
 //! landing/contact controller may then explicitly request Earth-fixed without
-//! exposing a grid entity to user code.
+//! exposing a grid entity to user code. Likewise an object parked in a pair's
+//! synodic frame stays there while either member of the pair is dominant.
 
 use bevy::prelude::*;
 use big_space::prelude::*;
@@ -77,6 +78,17 @@ pub fn soi_transition_system(
         if current_frame.is_some_and(|frame| frame.center() == Some(target_center)) {
             continue;
         }
+        // A pair's synodic frame is the natural frame for anything flying in
+        // that pair's three-body regime — an NRHO crosses the Moon's SOI twice a
+        // revolution. It is kept while either member dominates.
+        if current_frame
+            .and_then(ReferenceFrame::pair)
+            .is_some_and(|(primary, secondary)| {
+                target_center == primary || target_center == secondary
+            })
+        {
+            continue;
+        }
 
         let target_frame = ReferenceFrame::EclipticJ2000 {
             center: target_center,
@@ -282,4 +294,61 @@ mod tests {
             "SOI ownership chooses a centre; contact/orbit policy chooses orientation"
         );
     }
+
+    #[test]
+    fn a_synodic_migrant_stays_while_either_pair_member_dominates() {
+        let mut app = app_with_soi_system();
+        let root = app
+            .world_mut()
+            .spawn((
+                Grid::new(EDGE_M, 1_000.0),
+                CellCoord::ZERO,
+                Transform::IDENTITY,
+            ))
+            .id();
+        let sun = grid_at(
+            app.world_mut(),
+            root,
+            ReferenceFrame::EclipticJ2000 {
+                center: crate::ephemeris_id::SUN,
+            },
+            DVec3::ZERO,
+        );
+        let earth_offset = DVec3::new(2.0e9, 0.0, 0.0);
+        let _earth_inertial = grid_at(
+            app.world_mut(),
+            sun,
+            ReferenceFrame::EclipticJ2000 {
+                center: crate::ephemeris_id::EARTH,
+            },
+            earth_offset,
+        );
+        let synodic = grid_at(
+            app.world_mut(),
+            sun,
+            ReferenceFrame::Synodic {
+                primary: crate::ephemeris_id::EARTH,
+                secondary: crate::ephemeris_id::MOON,
+            },
+            earth_offset,
+        );
+        let synodic_grid = app.world().get::<Grid>(synodic).unwrap();
+        let (cell, translation) = synodic_grid.translation_to_grid(DVec3::new(50_000.0, 0.0, 0.0));
+        let migrant = app
+            .world_mut()
+            .spawn((
+                SoiMigrant,
+                cell,
+                Transform::from_translation(translation),
+                ChildOf(synodic),
+            ))
+            .id();
+
+        app.update();
+
+        assert_eq!(
+            app.world().get::<ChildOf>(migrant).unwrap().parent(),
+            synodic
+        );
+    }
 }
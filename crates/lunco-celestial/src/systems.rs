@@ -68,6 +68,67 @@ pub fn ephemeris_update_system(
     }
 }
 
+/// Pose each [`ReferenceFrame::Synodic`] grid: at the pair barycentre relative
+/// to its parent grid's centre, rotated onto the pair's synodic axes. Runs in
+/// the same gated chain as [`ephemeris_update_system`], after it.
+pub fn synodic_frame_system(
+    world: Res<WorldTime>,
+    ephemeris: Option<Res<EphemerisResource>>,
+    registry: Res<CelestialBodyRegistry>,
+    mut q_frames: Query<(&mut CellCoord, &mut Transform, &ReferenceFrame, &ChildOf)>,
+    q_parents: Query<(&Grid, &ReferenceFrame)>,
+) {
+    let Some(ephemeris) = ephemeris else {
+        return;
+    };
+    let tree =
+        crate::transform::FrameTree::new(world.epoch_jd, &registry, ephemeris.provider.as_ref());
+    for (mut cell, mut tf, frame, child_of) in &mut q_frames {
+        let Some((primary, secondary)) = frame.pair() else {
+            continue;
+        };
+        let Ok((parent_grid, parent_frame)) = q_parents.get(child_of.parent()) else {
+            error_once!(
+                "[celestial] synodic frame {:?} is not directly parented to a celestial Grid",
+                frame
+            );
+            continue;
+        };
+        // The parent must be an inertial, centred frame: the synodic axes are
+        // expressed in Solar axes, which only non-rotating grids share.
+        let Some(parent_center) = parent_frame
+            .center()
+            .filter(|_| parent_frame.body_fixed().is_none())
+        else {
+            error_once!(
+                "[celestial] synodic frame {:?} needs an inertial parent, got {:?}",
+                frame,
+                parent_frame
+            );
+            continue;
+        };
+        // No ephemeris for either member ⇒ leave the frame where it is.
+        let (Some(origin), Some(basis), Some(parent)) = (
+            tree.pair_barycenter(primary, secondary),
+            tree.synodic_basis(primary, secondary),
+            tree.center_in_solar(crate::frames::Center::Body(parent_center)),
+        ) else {
+            continue;
+        };
+        let (new_cell, new_translation) = parent_grid.translation_to_grid((origin - parent).raw());
+        if *cell != new_cell {
+            *cell = new_cell;
+        }
+        if tf.translation != new_translation {
+            tf.translation = new_translation;
+        }
+        let rotation = basis.as_quat();
+        if tf.rotation != rotation {
+            tf.rotation = rotation;
+        }
+    }
+}
+
 /// Rotate each celestial body's Grid around its polar axis.
 /// Per big_space docs: "if you have a planet rotating and orbiting around
 /// its star... you can place the planet and all objects on its surface in
@@ -130,6 +191,27 @@ pub fn sun_emit_direction(
     Some(-to_sun)
 }
 
+/// Rotation from the inertial solar frame into a site grid's local axes at
+/// `epoch_jd`. The site grid is ENU (+X east, +Y up, -Z north); the tangent
+/// basis is expressed in the inertial ecliptic frame, so this is the one
+/// explicit conversion for anything authored under that site. It is derived
+/// from the same body-fixed pose as terrain and physics, rather than from an
+/// ECS entity re-posed as a camera pin.
+pub(crate) fn solar_to_site_rotation(
+    desc: &crate::registry::BodyDescriptor,
+    geodetic: &crate::geo::Geodetic,
+    body_center_solar: bevy::math::DVec3,
+    epoch_jd: f64,
+) -> bevy::math::DQuat {
+    let site_frame = solar_tangent_frame(desc, geodetic, body_center_solar, epoch_jd);
+    bevy::math::DQuat::from_mat3(&bevy::math::DMat3::from_cols(
+        site_frame.east,
+        site_frame.up,
+        -site_frame.north,
+    ))
+    .inverse()
+}
+
 /// Point the scene's primary `DirectionalLight` along the **ephemeris** Sun
 /// direction at the current epoch (architecture doc 19 — T2; replaces the old
 /// hardcoded `Vec3::NEG_Z`).
@@ -222,23 +304,12 @@ pub fn update_sun_light_system(
         // implicit-frame bug.
         return;
     };
-    let site_frame = solar_tangent_frame(
+    let solar_to_site = solar_to_site_rotation(
         observer_desc,
         &anchor.geodetic,
         ecliptic_to_bevy(p_observer).raw(),
         world.epoch_jd,
     );
-    // The site grid's local axes are ENU (+X east, +Y up, -Z north). The
-    // tangent basis is expressed in the inertial ecliptic frame, so this is
-    // the one explicit conversion for a light authored under that site.
-    // It is derived from the same body-fixed pose as terrain and physics,
-    // rather than from an ECS entity re-posed as a camera pin.
-    let solar_to_site = bevy::math::DQuat::from_mat3(&bevy::math::DMat3::from_cols(
-        site_frame.east,
-        site_frame.up,
-        -site_frame.north,
-    ))
-    .inverse();
     let dir = (solar_to_site
         * bevy::math::DVec3::new(
             ecliptic_dir.x as f64,
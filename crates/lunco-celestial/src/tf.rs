@@ -0,0 +1,706 @@
+//! The frame tree (TF) — named frames, and the transform between any two of them
+//! at any epoch.
+//!
+//! [`FrameTree`] converts *positions* between the typed frames through the
+//! [`Solar`](crate::frames::Solar) hub. This module gives those frames NAMES a
+//! script or API client can speak, adds the scene's own frames beside them, and
+//! answers "where is frame A, and how is it turned, seen from frame B?" as one
+//! f64 pose:
+//!
+//! ```text
+//!   solar                  the hub (ecliptic J2000, Bevy axes, metres)
+//!   inertial:<naif>        body-centred, equatorial, not spinning   (Kepler elements)
+//!   fixed:<naif>           body-centred, rotating with the body     (IAU/WGCCRE)
+//!   synodic:<p>:<s>        the pair's co-rotating frame             (L-points stand still)
+//!   site                   the scene's topocentric ENU frame        (+X E, +Y U, −Z N)
+//!   entity:<gid>           a prim's own frame                       (pose layer placement)
+//!   <name>                 a static frame registered with RegisterFrame
+//! ```
+//!
+//! A static frame is a fixed offset from a parent frame — a sensor mount on a
+//! mast prim, a survey marker on the site, a star tracker boresight on an
+//! orbiter. Every other edge is dynamic: it is evaluated at the requested epoch.
+//!
+//! # One hub, again
+//!
+//! Every frame resolves to a [`FramePose`] *into Solar* at the epoch, and
+//! `LookupTransform {from, to}` is `to⁻¹ · from`. There is no graph search and
+//! no N×N table, for the reason [`crate::transform`] gives.
+//!
+//! # What is frozen
+//!
+//! The same rule as the window predictor (`crate::windows`): the ephemeris and
+//! body rotation are evaluated at the requested epoch; a prim's offset from its
+//! placement is read once, now. `entity:` frames at a future epoch are where the
+//! prim would be if the scene held still.
+
+use bevy::ecs::system::{SystemParam, SystemState};
+use bevy::math::{DQuat, DVec3};
+use bevy::platform::collections::HashMap;
+use bevy::prelude::*;
+use big_space::prelude::{CellCoord, Grid};
+use std::fmt;
+
+use lunco_api::queries::ApiQueryProvider;
+use lunco_api::registry::ApiEntityRegistry;
+use lunco_api::schema::ApiResponse;
+use lunco_core::{
+    on_command, register_commands, Ack, Command, GlobalEntityId, OpId, Severity, TelemetryEvent,
+    TelemetryValue,
+};
+use lunco_time::WorldTime;
+
+use crate::cr3bp::{HaloOrbit, HaloTrajectory};
+use crate::ephemeris::EphemerisResource;
+use crate::frames::{BodyId, Center, Pair};
+use crate::geo::{self, GeodeticAnchor, SiteAnchor};
+use crate::kepler::KeplerOrbit;
+use crate::pose::{pose_source, tangent_rotation, PoseContext};
+use crate::registry::{BodyDescriptor, CelestialBodyRegistry};
+use crate::transform::{FrameTree, LibrationAnchor};
+use crate::windows::QueryError;
+
+/// The telemetry event name a transform stream publishes under.
+pub const TF_EVENT: &str = "tf";
+
+/// Deepest chain of static frames a lookup will follow. Registration refuses
+/// cycles; this bounds a chain that is merely absurd.
+const MAX_STATIC_DEPTH: usize = 32;
+
+/// A frame of the tree, by name. See the module docs for the grammar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TfFrame {
+    Solar,
+    Inertial(BodyId),
+    Fixed(BodyId),
+    Synodic(Pair),
+    Site,
+    Entity(u64),
+    Named(String),
+}
+
+impl TfFrame {
+    /// Parse the frame grammar. A bare word that is not a keyword names a
+    /// static frame; `:` is reserved for the parameterised kinds.
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let s = s.trim();
+        let body = |v: &str| {
+            v.parse::<BodyId>()
+                .map_err(|_| format!("`{s}`: `{v}` is not a NAIF id"))
+        };
+        let mut parts = s.split(':');
+        let head = parts.next().unwrap_or_default();
+        let args: Vec<&str> = parts.collect();
+        Ok(match (head, args.as_slice()) {
+            ("", []) => return Err("empty frame name".into()),
+            ("solar", []) => Self::Solar,
+            ("site", []) => Self::Site,
+            ("inertial", [id]) => Self::Inertial(body(id)?),
+            ("fixed", [id]) => Self::Fixed(body(id)?),
+            ("synodic", [p, s]) => Self::Synodic(Pair {
+                primary: body(p)?,
+                secondary: body(s)?,
+            }),
+            ("entity", [gid]) => Self::Entity(
+                gid.parse()
+                    .map_err(|_| format!("`{s}`: `{gid}` is not a gid"))?,
+            ),
+            (name, []) => Self::Named(name.to_string()),
+            _ => return Err(format!("unknown frame `{s}`")),
+        })
+    }
+
+    /// The GID a stream event is attributed to: the entity, for an entity frame.
+    fn source(&self) -> u64 {
+        match self {
+            Self::Entity(gid) => *gid,
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for TfFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Solar => write!(f, "solar"),
+            Self::Inertial(id) => write!(f, "inertial:{id}"),
+            Self::Fixed(id) => write!(f, "fixed:{id}"),
+            Self::Synodic(pair) => write!(f, "synodic:{}:{}", pair.primary, pair.secondary),
+            Self::Site => write!(f, "site"),
+            Self::Entity(gid) => write!(f, "entity:{gid}"),
+            Self::Named(name) => write!(f, "{name}"),
+        }
+    }
+}
+
+/// A rigid transform: `p_parent = rotation · p_child + translation`. As the pose
+/// of a frame, it is that frame's origin and axes expressed in its parent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FramePose {
+    pub translation: DVec3,
+    pub rotation: DQuat,
+}
+
+impl FramePose {
+    pub const IDENTITY: Self = Self {
+        translation: DVec3::ZERO,
+        rotation: DQuat::IDENTITY,
+    };
+
+    /// A point of this frame, in the parent.
+    pub fn apply(&self, p: DVec3) -> DVec3 {
+        self.rotation * p + self.translation
+    }
+
+    /// `child`'s pose (given in this frame) in this frame's parent.
+    pub fn compose(&self, child: &FramePose) -> FramePose {
+        FramePose {
+            translation: self.apply(child.translation),
+            rotation: (self.rotation * child.rotation).normalize(),
+        }
+    }
+
+    /// The parent's pose in this frame.
+    pub fn inverse(&self) -> FramePose {
+        let rotation = self.rotation.inverse();
+        FramePose {
+            translation: -(rotation * self.translation),
+            rotation,
+        }
+    }
+
+    /// This pose re-expressed in `base`, when both share a parent.
+    pub fn relative_to(&self, base: &FramePose) -> FramePose {
+        base.inverse().compose(self)
+    }
+}
+
+/// Static frames registered by name: each a fixed [`FramePose`] in its parent.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct TfStaticFrames {
+    frames: HashMap<String, (TfFrame, FramePose)>,
+}
+
+impl TfStaticFrames {
+    /// Register (or replace) `name` as `offset` in `parent`. Refuses a parent
+    /// that is an unknown static frame, and any parent chain that leads back to
+    /// `name` — a cycle has no pose.
+    pub fn insert(&mut self, name: &str, parent: TfFrame, offset: FramePose) -> Result<(), String> {
+        if TfFrame::parse(name)? != TfFrame::Named(name.to_string()) {
+            return Err(format!("`{name}` is a reserved frame name"));
+        }
+        let mut up = parent.clone();
+        while let TfFrame::Named(ancestor) = up {
+            if ancestor == name {
+                return Err(format!("`{name}` would be its own ancestor"));
+            }
+            up = match self.frames.get(&ancestor) {
+                Some((next, _)) => next.clone(),
+                None => return Err(format!("unknown parent frame `{ancestor}`")),
+            };
+        }
+        self.frames.insert(name.to_string(), (parent, offset));
+        Ok(())
+    }
+
+    /// Drop `name`. Frames parented to it are dropped with it: they are
+    /// defined relative to it and would otherwise dangle.
+    pub fn remove(&mut self, name: &str) -> bool {
+        if self.frames.remove(name).is_none() {
+            return false;
+        }
+        let orphans: Vec<String> = self
+            .frames
+            .iter()
+            .filter(|(_, (parent, _))| *parent == TfFrame::Named(name.to_string()))
+            .map(|(child, _)| child.clone())
+            .collect();
+        for child in orphans {
+            self.remove(&child);
+        }
+        true
+    }
+
+    pub fn get(&self, name: &str) -> Option<&(TfFrame, FramePose)> {
+        self.frames.get(name)
+    }
+
+    /// `(name, parent)` of every static frame, sorted by name.
+    pub fn edges(&self) -> Vec<(String, String)> {
+        let mut edges: Vec<_> = self
+            .frames
+            .iter()
+            .map(|(name, (parent, _))| (name.clone(), parent.to_string()))
+            .collect();
+        edges.sort();
+        edges
+    }
+}
+
+/// The scene a lookup is resolved against. Read-only, so the query provider, the
+/// stream system and a command handler share it.
+#[derive(SystemParam)]
+pub(crate) struct TfScene<'w, 's> {
+    world_time: Option<Res<'w, WorldTime>>,
+    ephemeris: Option<Res<'w, EphemerisResource>>,
+    registry: Option<Res<'w, CelestialBodyRegistry>>,
+    ids: Option<Res<'w, ApiEntityRegistry>>,
+    statics: Option<Res<'w, TfStaticFrames>>,
+    q_site: Query<'w, 's, &'static GeodeticAnchor, With<SiteAnchor>>,
+    q_anchor: Query<'w, 's, &'static GeodeticAnchor>,
+    q_orbit: Query<'w, 's, &'static KeplerOrbit>,
+    q_libration: Query<'w, 's, &'static LibrationAnchor>,
+    q_halo: Query<'w, 's, (&'static HaloOrbit, Option<&'static HaloTrajectory>)>,
+    q_parents: Query<'w, 's, &'static ChildOf>,
+    q_grids: Query<'w, 's, &'static Grid>,
+    q_spatial: Query<'w, 's, (Option<&'static CellCoord>, &'static Transform)>,
+}
+
+impl TfScene<'_, '_> {
+    /// The sim clock's epoch — the default epoch of a lookup.
+    pub(crate) fn now_jd(&self) -> Option<f64> {
+        self.world_time.as_deref().map(|t| t.epoch_jd)
+    }
+
+    /// The pose of `from` in `to` at `jd`: maps `from` coordinates into `to`.
+    pub(crate) fn lookup(
+        &self,
+        from: &TfFrame,
+        to: &TfFrame,
+        jd: f64,
+    ) -> Result<FramePose, QueryError> {
+        let (Some(ephemeris), Some(registry)) =
+            (self.ephemeris.as_deref(), self.registry.as_deref())
+        else {
+            return Err("needs an ephemeris and a body registry".into());
+        };
+        let provider = ephemeris.provider.as_ref();
+        let site_anchor = self.q_site.iter().next().copied();
+        let mut ctx = PoseContext::new(jd, registry, provider, site_anchor.as_ref());
+        let tree = FrameTree::new(jd, registry, provider);
+        let from = self.in_solar(from, &mut ctx, &tree, 0)?;
+        let to = self.in_solar(to, &mut ctx, &tree, 0)?;
+        Ok(from.relative_to(&to))
+    }
+
+    /// `frame`'s pose in Solar at the context's epoch. A frame or entity the
+    /// scene does not have is [`QueryError::not_found`].
+    fn in_solar(
+        &self,
+        frame: &TfFrame,
+        ctx: &mut PoseContext,
+        tree: &FrameTree,
+        depth: usize,
+    ) -> Result<FramePose, QueryError> {
+        let no_body = |id: BodyId| {
+            QueryError::not_found(format!("no ephemeris or registry entry for body {id}"))
+        };
+        let body_centred = |id: BodyId, rotation: fn(&BodyDescriptor, f64) -> DQuat| {
+            let desc = tree.registry.get(id).ok_or_else(|| no_body(id))?;
+            let center = tree
+                .center_in_solar(Center::Body(id))
+                .ok_or_else(|| no_body(id))?;
+            Ok::<_, QueryError>(FramePose {
+                translation: center.raw(),
+                rotation: rotation(desc, tree.jd),
+            })
+        };
+        match frame {
+            TfFrame::Solar => Ok(FramePose::IDENTITY),
+            TfFrame::Inertial(id) => body_centred(*id, geo::equatorial_frame),
+            TfFrame::Fixed(id) => body_centred(*id, geo::body_rotation),
+            TfFrame::Synodic(Pair { primary, secondary }) => {
+                let origin = tree.pair_barycenter(*primary, *secondary);
+                let basis = tree.synodic_basis(*primary, *secondary);
+                match (origin, basis) {
+                    (Some(origin), Some(rotation)) => Ok(FramePose {
+                        translation: origin.raw(),
+                        rotation,
+                    }),
+                    _ => Err(QueryError::not_found(format!(
+                        "no synodic frame for {primary}/{secondary}"
+                    ))),
+                }
+            }
+            TfFrame::Site => ctx
+                .site_frame()
+                .map(|f| FramePose {
+                    translation: f.origin,
+                    rotation: tangent_rotation(f),
+                })
+                .ok_or_else(|| QueryError::not_found("the scene has no site anchor")),
+            TfFrame::Entity(gid) => {
+                let entity = self
+                    .ids
+                    .as_deref()
+                    .and_then(|r| r.resolve(&GlobalEntityId::from_raw(*gid)))
+                    .ok_or_else(|| QueryError::not_found(format!("no entity with gid {gid}")))?;
+                let source = pose_source(
+                    entity,
+                    &self.q_anchor,
+                    &self.q_orbit,
+                    &self.q_libration,
+                    &self.q_halo,
+                    &self.q_parents,
+                    &self.q_grids,
+                    &self.q_spatial,
+                )
+                .ok_or_else(|| format!("{entity} has no resolvable placement"))?;
+                let pose = ctx
+                    .pose(&source)
+                    .ok_or_else(|| format!("{entity} cannot be placed at this epoch"))?;
+                Ok(FramePose {
+                    translation: pose.pos,
+                    rotation: pose.rotation,
+                })
+            }
+            TfFrame::Named(name) => {
+                if depth >= MAX_STATIC_DEPTH {
+                    return Err(format!("`{name}`: static frame chain too deep").into());
+                }
+                let (parent, offset) = self
+                    .statics
+                    .as_deref()
+                    .and_then(|s| s.get(name))
+                    .ok_or_else(|| QueryError::not_found(format!("unknown frame `{name}`")))?;
+                Ok(self.in_solar(parent, ctx, tree, depth + 1)?.compose(offset))
+            }
+        }
+    }
+}
+
+/// A lookup's JSON shape, shared by the query and the stream.
+fn pose_json(from: &TfFrame, to: &TfFrame, jd: f64, pose: &FramePose) -> serde_json::Value {
+    serde_json::json!({
+        "from": from.to_string(),
+        "to": to.to_string(),
+        "epoch": jd,
+        "translation": pose.translation.to_array(),
+        "rotation": pose.rotation.to_array(),
+    })
+}
+
+/// A rotation given as `[x, y, z, w]`. All zeros — the default of an omitted
+/// field — is identity; anything else is normalised, and a near-zero or
+/// non-finite quaternion is refused rather than guessed at.
+fn rotation_from(q: [f64; 4]) -> Result<DQuat, String> {
+    if q == [0.0; 4] {
+        return Ok(DQuat::IDENTITY);
+    }
+    let q = DQuat::from_array(q);
+    if !q.is_finite() || q.length_squared() < 1e-12 {
+        return Err(format!("degenerate rotation {q:?}"));
+    }
+    Ok(q.normalize())
+}
+
+/// Register a static frame: `translation` (metres) and `rotation` (`[x, y, z,
+/// w]`) of the new frame in `parent`. Replaces a frame of the same name.
+#[Command(default)]
+pub struct RegisterFrame {
+    pub name: String,
+    /// Any frame of the grammar (`entity:<gid>` for a sensor mount).
+    pub parent: String,
+    pub translation: [f64; 3],
+    /// All zeros (omitted) is identity.
+    pub rotation: [f64; 4],
+}
+
+#[on_command(RegisterFrame)]
+fn on_register_frame(
+    _t: On<RegisterFrame>,
+    mut statics: ResMut<TfStaticFrames>,
+) -> Result<Ack, String> {
+    let parent = TfFrame::parse(&cmd.parent).map_err(|e| format!("RegisterFrame: {e}"))?;
+    let offset = FramePose {
+        translation: DVec3::from_array(cmd.translation),
+        rotation: rotation_from(cmd.rotation).map_err(|e| format!("RegisterFrame: {e}"))?,
+    };
+    statics
+        .insert(&cmd.name, parent, offset)
+        .map_err(|e| format!("RegisterFrame: {e}"))?;
+    Ok(Ack::new(OpId::new()))
+}
+
+/// Remove a static frame, and every static frame parented to it.
+#[Command(default)]
+pub struct RemoveFrame {
+    pub name: String,
+}
+
+#[on_command(RemoveFrame)]
+fn on_remove_frame(
+    _t: On<RemoveFrame>,
+    mut statics: ResMut<TfStaticFrames>,
+) -> Result<Ack, String> {
+    if !statics.remove(&cmd.name) {
+        return Err(format!("RemoveFrame: unknown frame `{}`", cmd.name));
+    }
+    Ok(Ack::new(OpId::new()))
+}
+
+struct TfStream {
+    from: TfFrame,
+    to: TfFrame,
+    period_s: f64,
+    next_s: f64,
+}
+
+/// Active `/tf`-style streams: each publishes `from` in `to` as a [`TF_EVENT`].
+#[derive(Resource, Default)]
+pub struct TfStreams {
+    streams: Vec<TfStream>,
+}
+
+/// Publish `from` in `to` as a `"tf"` telemetry event at `rate_hz` of sim time
+/// (default 10). The payload is the `LookupTransform` JSON as a string; `source`
+/// is the `from` entity's GID for an entity frame. Replaces a stream of the
+/// same pair.
+#[Command(default)]
+pub struct StreamTransform {
+    pub from: String,
+    pub to: String,
+    pub rate_hz: f64,
+}
+
+#[on_command(StreamTransform)]
+fn on_stream_transform(
+    _t: On<StreamTransform>,
+    mut streams: ResMut<TfStreams>,
+) -> Result<Ack, String> {
+    let from = TfFrame::parse(&cmd.from).map_err(|e| format!("StreamTransform: {e}"))?;
+    let to = TfFrame::parse(&cmd.to).map_err(|e| format!("StreamTransform: {e}"))?;
+    let rate_hz = if cmd.rate_hz > 0.0 { cmd.rate_hz } else { 10.0 };
+    streams.streams.retain(|s| s.from != from || s.to != to);
+    streams.streams.push(TfStream {
+        from,
+        to,
+        period_s: 1.0 / rate_hz,
+        next_s: 0.0,
+    });
+    Ok(Ack::new(OpId::new()))
+}
+
+/// Stop the stream of `from` in `to`; both empty stops every stream.
+#[Command(default)]
+pub struct StopTransformStream {
+    pub from: String,
+    pub to: String,
+}
+
+#[on_command(StopTransformStream)]
+fn on_stop_transform_stream(_t: On<StopTransformStream>, mut streams: ResMut<TfStreams>) {
+    if cmd.from.is_empty() && cmd.to.is_empty() {
+        streams.streams.clear();
+        return;
+    }
+    let (Ok(from), Ok(to)) = (TfFrame::parse(&cmd.from), TfFrame::parse(&cmd.to)) else {
+        warn!(
+            "StopTransformStream: bad frame `{}` / `{}`",
+            cmd.from, cmd.to
+        );
+        return;
+    };
+    streams.streams.retain(|s| s.from != from || s.to != to);
+}
+
+register_commands!(
+    on_register_frame,
+    on_remove_frame,
+    on_stream_transform,
+    on_stop_transform_stream
+);
+
+/// Emit every due stream at the current epoch. A lookup that fails (a frame
+/// not yet in the scene) skips that beat rather than ending the stream.
+pub fn publish_tf_streams(
+    time: Res<Time>,
+    mut streams: ResMut<TfStreams>,
+    scene: TfScene,
+    mut commands: Commands,
+) {
+    if streams.streams.is_empty() {
+        return;
+    }
+    let Some(jd) = scene.now_jd() else {
+        return;
+    };
+    let now = time.elapsed_secs_f64();
+    for stream in streams.streams.iter_mut().filter(|s| now >= s.next_s) {
+        stream.next_s = now + stream.period_s;
+        match scene.lookup(&stream.from, &stream.to, jd) {
+            Ok(pose) => commands.trigger(TelemetryEvent {
+                name: TF_EVENT.to_string(),
+                source: stream.from.source(),
+                severity: Severity::Info,
+                data: TelemetryValue::String(
+                    pose_json(&stream.from, &stream.to, jd, &pose).to_string(),
+                ),
+                timestamp: jd,
+            }),
+            Err(e) => debug!("[tf] {} in {}: {e}", stream.from, stream.to),
+        }
+    }
+}
+
+/// `LookupTransform` — the pose of `from` in `to`, f64.
+///
+/// params:
+/// ```text
+/// { from: <frame> | <gid>, to: <frame> | <gid>, epoch?: jd }   # default: now (TDB JD)
+/// ```
+/// returns
+/// ```text
+/// { from, to, epoch, translation: [x,y,z], rotation: [x,y,z,w] }
+/// ```
+/// `p_to = rotation · p_from + translation`; equivalently, `from`'s origin and
+/// axes seen from `to`. An integer frame is an entity GID. `statics` lists the
+/// registered static frames as `[name, parent]` when `from` is omitted.
+pub struct LookupTransformProvider;
+
+impl ApiQueryProvider for LookupTransformProvider {
+    fn name(&self) -> &'static str {
+        "LookupTransform"
+    }
+
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        match lookup(world, params) {
+            Ok(v) => ApiResponse::ok(v),
+            Err(e) => e.respond("LookupTransform"),
+        }
+    }
+}
+
+fn lookup(world: &mut World, params: &serde_json::Value) -> Result<serde_json::Value, QueryError> {
+    let frame = |key: &str| -> Result<Option<TfFrame>, String> {
+        match params.get(key) {
+            None | Some(serde_json::Value::Null) => Ok(None),
+            Some(serde_json::Value::String(s)) => TfFrame::parse(s).map(Some),
+            Some(v) => v
+                .as_u64()
+                .map(|gid| Some(TfFrame::Entity(gid)))
+                .ok_or_else(|| format!("`{key}` must be a frame name or a gid")),
+        }
+    };
+    let Some(from) = frame("from")? else {
+        let edges = world
+            .get_resource::<TfStaticFrames>()
+            .map(TfStaticFrames::edges)
+            .unwrap_or_default();
+        return Ok(serde_json::json!({ "statics": edges }));
+    };
+    let to = frame("to")?.unwrap_or(TfFrame::Solar);
+    let mut state: SystemState<TfScene> = SystemState::new(world);
+    let scene = state
+        .get(world)
+        .map_err(|e| format!("scene unavailable: {e}"))?;
+    let jd = match params.get("epoch").and_then(serde_json::Value::as_f64) {
+        Some(jd) => jd,
+        None => scene.now_jd().ok_or("no clock; pass `epoch`")?,
+    };
+    let pose = scene.lookup(&from, &to, jd)?;
+    Ok(pose_json(&from, &to, jd, &pose))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::math::EulerRot;
+
+    fn close(a: DVec3, b: DVec3) -> bool {
+        (a - b).length() < 1e-9
+    }
+
+    #[test]
+    fn frame_names_round_trip() {
+        for name in [
+            "solar",
+            "inertial:399",
+            "fixed:301",
+            "synodic:399:301",
+            "site",
+            "entity:42",
+            "mast_camera",
+        ] {
+            assert_eq!(TfFrame::parse(name).unwrap().to_string(), name);
+        }
+        assert!(TfFrame::parse("fixed:moon").is_err());
+        assert!(TfFrame::parse("synodic:399").is_err());
+        assert!(TfFrame::parse("").is_err());
+    }
+
+    #[test]
+    fn lookup_is_the_inverse_of_its_reverse() {
+        let a = FramePose {
+            translation: DVec3::new(1.0e11, -3.0, 7.0),
+            rotation: DQuat::from_euler(EulerRot::YXZ, 0.3, -1.1, 2.0),
+        };
+        let b = FramePose {
+            translation: DVec3::new(1.0e11 + 4.0e8, 12.0, -5.0),
+            rotation: DQuat::from_rotation_y(0.7),
+        };
+        let ab = a.relative_to(&b);
+        let ba = b.relative_to(&a);
+        let p = DVec3::new(3.0, 2.0, 1.0);
+        // p in a → b → a is p again.
+        assert!(close(ba.apply(ab.apply(p)), p));
+        // And the a→b map agrees with going through the hub.
+        assert!((b.apply(ab.apply(p)) - a.apply(p)).length() < 1e-3);
+        assert!(close(
+            a.compose(&a.inverse()).translation,
+            FramePose::IDENTITY.translation
+        ));
+    }
+
+    #[test]
+    fn a_missing_frame_or_entity_is_not_found() {
+        let mut world = World::new();
+        world.insert_resource(EphemerisResource {
+            provider: std::sync::Arc::new(crate::ephemeris::NoOpEphemerisProvider),
+        });
+        world.insert_resource(CelestialBodyRegistry::default_system());
+        let mut code = |params: serde_json::Value| match LookupTransformProvider
+            .execute(&mut world, &params)
+        {
+            ApiResponse::Error { code, .. } => code,
+            other => panic!("expected an error, got {other:?}"),
+        };
+        let not_found = lunco_api::schema::ApiErrorCode::EntityNotFound as u16;
+        let epoch = 2_460_000.5;
+        assert_eq!(
+            code(serde_json::json!({ "from": 42, "epoch": epoch })),
+            not_found
+        );
+        assert_eq!(
+            code(serde_json::json!({ "from": "mast_camera", "epoch": epoch })),
+            not_found
+        );
+        // A frame name that does not parse is the caller's mistake, not a miss.
+        assert_eq!(
+            code(serde_json::json!({ "from": "fixed:moon", "epoch": epoch })),
+            lunco_api::schema::ApiErrorCode::DeserializationError as u16
+        );
+    }
+
+    #[test]
+    fn static_frames_refuse_cycles_and_drop_orphans() {
+        let mut frames = TfStaticFrames::default();
+        let at = |x| FramePose {
+            translation: DVec3::X * x,
+            rotation: DQuat::IDENTITY,
+        };
+        frames.insert("mast", TfFrame::Entity(7), at(1.0)).unwrap();
+        frames
+            .insert("camera", TfFrame::Named("mast".into()), at(0.5))
+            .unwrap();
+        assert!(frames
+            .insert("mast", TfFrame::Named("camera".into()), at(0.0))
+            .is_err());
+        assert!(frames
+            .insert("probe", TfFrame::Named("nowhere".into()), at(0.0))
+            .is_err());
+        assert!(frames.insert("fixed:399", TfFrame::Solar, at(0.0)).is_err());
+        assert!(frames.remove("mast"));
+        assert!(frames.get("camera").is_none());
+    }
+}
@@ -45,6 +45,11 @@ pub enum TrajectoryFrame {
     #[default]
     Inertial,
     BodyFixed,
+    /// The co-rotating frame of `reference_id` (primary) and `secondary`, drawn
+    /// on that pair's synodic grid — where a halo orbit closes on itself.
+    Synodic {
+        secondary: i32,
+    },
 }
 
 impl Default for TrajectoryView {
@@ -528,6 +533,19 @@ pub fn spawn_trajectory_update_task(
                                 rel_pos = crate::geo::body_rotation(desc, jd).inverse() * rel_pos;
                             }
                         }
+                        if let TrajectoryFrame::Synodic { secondary } = view_copy.frame {
+                            let Some(synodic) = synodic_sample(
+                                provider.as_ref(),
+                                &registry_arc,
+                                view_copy.tracked_id,
+                                view_copy.reference_id,
+                                secondary,
+                                jd,
+                            ) else {
+                                continue;
+                            };
+                            rel_pos = synodic;
+                        }
 
                         points.push(rel_pos - anchor);
                     }
@@ -562,6 +580,19 @@ pub fn spawn_trajectory_update_task(
                                 rel_pos = crate::geo::body_rotation(desc, jd).inverse() * rel_pos;
                             }
                         }
+                        if let TrajectoryFrame::Synodic { secondary } = view_copy.frame {
+                            let Some(synodic) = synodic_sample(
+                                provider.as_ref(),
+                                &registry_arc,
+                                view_copy.tracked_id,
+                                view_copy.reference_id,
+                                secondary,
+                                jd,
+                            ) else {
+                                continue;
+                            };
+                            rel_pos = synodic;
+                        }
 
                         points.push(rel_pos - anchor);
                     }
@@ -579,6 +610,22 @@ pub fn spawn_trajectory_update_task(
     }
 }
 
+/// `tracked` in the synodic frame of `primary`/`secondary` at `jd`: metres from
+/// the pair barycentre, synodic axes.
+fn synodic_sample(
+    provider: &dyn crate::ephemeris::EphemerisProvider,
+    registry: &CelestialBodyRegistry,
+    tracked: i32,
+    primary: i32,
+    secondary: i32,
+    jd: f64,
+) -> Option<bevy::math::DVec3> {
+    let tree = crate::transform::FrameTree::new(jd, registry, provider);
+    let solar = tree.center_in_solar(crate::frames::Center::Body(tracked))?;
+    let pair = crate::frames::Pair { primary, secondary };
+    Some(tree.solar_to_synodic(solar, pair)?.raw())
+}
+
 pub fn handle_trajectory_tasks(
     mut commands: Commands,
     mut q_tasks: Query<(
@@ -866,6 +913,17 @@ pub fn trajectory_alignment_system(
                 target_parent = Some(f_entity);
                 parent_grid = q_grids.get(f_entity).ok();
             }
+        } else if let TrajectoryFrame::Synodic { secondary } = view.frame {
+            // Synodic points are barycentre-relative in the pair's rotating
+            // axes — exactly the synodic grid's own frame, so the view sits at
+            // its origin unrotated.
+            if let Some(f_entity) = frame_index.resolve(ReferenceFrame::Synodic {
+                primary: view.reference_id,
+                secondary,
+            }) {
+                target_parent = Some(f_entity);
+                parent_grid = q_grids.get(f_entity).ok();
+            }
         } else if path.anchor != bevy::math::DVec3::ZERO {
             // ANCHORED body-orbit view (points stored relative to the tracked
             // body at the rebuild epoch). Parent to the TRACKED body's frame;
@@ -324,7 +324,8 @@ impl<'a> FrameTree<'a> {
         Some(Pos::<Synodic>::in_pair(pair, rot * rel.raw()))
     }
 
-    fn synodic_basis(&self, primary: BodyId, secondary: BodyId) -> Option<DQuat> {
+    /// The synodic axes in Solar: +X primary→secondary, +Y the orbit normal.
+    pub(crate) fn synodic_basis(&self, primary: BodyId, secondary: BodyId) -> Option<DQuat> {
         let p = self.center_in_solar(Center::Body(primary))?;
         let s = self.center_in_solar(Center::Body(secondary))?;
         let x = (s - p).raw();
@@ -341,7 +342,8 @@ impl<'a> FrameTree<'a> {
         Some(DQuat::from_mat3(&bevy::math::DMat3::from_cols(x, y, z)))
     }
 
-    fn pair_barycenter(&self, primary: BodyId, secondary: BodyId) -> Option<Pos<Solar>> {
+    /// The synodic origin: the pair's mass-weighted barycentre.
+    pub(crate) fn pair_barycenter(&self, primary: BodyId, secondary: BodyId) -> Option<Pos<Solar>> {
         let p = self.center_in_solar(Center::Body(primary))?;
         let s = self.center_in_solar(Center::Body(secondary))?;
         // Both masses REQUIRED — a missing gm read as 0.0 silently puts the
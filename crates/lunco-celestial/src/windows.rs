@@ -0,0 +1,627 @@
+//! Predicted windows — when a link will be up, when a point will see the Sun,
+//! and when it will be eclipsed, over a span of FUTURE epochs.
+//!
+//! The link kernel answers "is this pair connected *now*?" at the sim clock, on a
+//! cadence. A mission planner needs the next fourteen days of contacts. The
+//! [`PredictWindowsProvider`] query answers that by evaluating the kernel's own
+//! geometry — [`pair_geometry`] for range, masks, body and terrain occlusion;
+//! [`PoseContext`] for placement — at sampled epochs, then bisecting every state
+//! change down to a tolerance. The clock is never touched: each sample builds a
+//! fresh [`PoseContext`] at its own epoch, and nothing is written to the world.
+//!
+//! # What is frozen
+//!
+//! Only the ephemeris sweeps. A node's offset from its placement, and a
+//! scene-local node's (or occluder's) position in the site frame, are read once
+//! at query time and held. A rover that will drive off in an hour is predicted
+//! from where it stands. That is the honest reading of "ahead of time": the
+//! scene's future motion is not known to the engine.
+//!
+//! # What differs from the live kernel
+//!
+//! - **No verdict hook.** A `link.connected` policy can read script state that
+//!   has no future value, so `los` reports the BUILTIN verdict (range, elevation
+//!   masks, body, terrain and authored occluders) — the verdict the live link
+//!   uses when no hook is registered.
+//! - **No debounce.** Transitions are geometric instants, not the kernel's
+//!   N-sweep filtered flips.
+//! - **Centre of the Sun only.** `sun` and `eclipse` test the line to the Sun's
+//!   centre: rise/set and entry/exit are the half-disc instants, with no
+//!   penumbra. The environment domain owns partial illumination.
+
+use bevy::ecs::system::{SystemParam, SystemState};
+use bevy::math::{DQuat, DVec3};
+use bevy::prelude::*;
+use big_space::prelude::{CellCoord, Grid};
+use std::sync::Arc;
+
+use lunco_api::queries::ApiQueryProvider;
+use lunco_api::registry::ApiEntityRegistry;
+use lunco_api::schema::{ApiErrorCode, ApiResponse};
+use lunco_core::coords::world_pose;
+use lunco_core::GlobalEntityId;
+use lunco_terrain_surface::{DemHeightField, SurfaceOracle};
+use lunco_time::WorldTime;
+
+use crate::cr3bp::{HaloOrbit, HaloTrajectory};
+use crate::ephemeris::EphemerisResource;
+use crate::geo::{segment_hits_sphere, GeodeticAnchor, SiteAnchor};
+use crate::kepler::KeplerOrbit;
+use crate::link::{
+    elevation_deg, occluding_bodies, pair_geometry, terrain_blocks, LinkNode, LinkOccluder,
+};
+use crate::pose::{pose_source, Horizon, PoseContext, PoseSource};
+use crate::registry::CelestialBodyRegistry;
+use crate::transform::LibrationAnchor;
+
+pub(crate) const DEFAULT_SPAN_DAYS: f64 = 14.0;
+pub(crate) const DEFAULT_STEP_S: f64 = 60.0;
+pub(crate) const DEFAULT_TOLERANCE_S: f64 = 1.0;
+/// Most coarse samples one query may take — a fortnight at roughly 6 s steps.
+const MAX_SAMPLES: usize = 200_000;
+pub(crate) const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// What a window is a window OF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum WindowKind {
+    /// `a`↔`b` builtin link verdict. Transitions: `aos` / `los`.
+    Los,
+    /// The Sun's centre above `a`'s horizon and clear of terrain. `rise` / `set`.
+    Sun,
+    /// The Sun's centre hidden from `a` by a body. `entry` / `exit`.
+    Eclipse,
+}
+
+impl WindowKind {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "los" => Some(Self::Los),
+            "sun" => Some(Self::Sun),
+            "eclipse" => Some(Self::Eclipse),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Self::Los => "los",
+            Self::Sun => "sun",
+            Self::Eclipse => "eclipse",
+        }
+    }
+
+    /// `(opens, closes)` transition names.
+    fn events(self) -> (&'static str, &'static str) {
+        match self {
+            Self::Los => ("aos", "los"),
+            Self::Sun => ("rise", "set"),
+            Self::Eclipse => ("entry", "exit"),
+        }
+    }
+}
+
+/// One interval over which the predicate held. `start_open` / `end_open` mark a
+/// window clipped by the search span rather than bounded by a transition.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Window {
+    pub start_jd: f64,
+    pub end_jd: f64,
+    pub start_open: bool,
+    pub end_open: bool,
+}
+
+/// The intervals in `[t0, t1]` over which `f` holds.
+///
+/// `f` is sampled every `step` (the last sample lands on `t1`); each sign change
+/// between neighbouring samples is bisected until the bracket is narrower than
+/// `tol`, and the transition is reported at the bracket's midpoint. A window
+/// shorter than `step` that falls between two samples is missed — the step is the
+/// resolution of DISCOVERY, the tolerance only of TIMING.
+pub fn find_windows(
+    t0: f64,
+    t1: f64,
+    step: f64,
+    tol: f64,
+    mut f: impl FnMut(f64) -> bool,
+) -> Vec<Window> {
+    let mut windows = Vec::new();
+    if !(t1 > t0 && step > 0.0) {
+        return windows;
+    }
+    let tol = tol.max(f64::EPSILON * t1.abs());
+    let mut prev_t = t0;
+    let mut prev = f(t0);
+    let mut open = prev.then_some((t0, true));
+    let mut k = 1u64;
+    loop {
+        let t = (t0 + k as f64 * step).min(t1);
+        let now = f(t);
+        if now != prev {
+            // Bisect (prev_t, t]: `lo` keeps `prev`'s state, `hi` keeps `now`'s.
+            let (mut lo, mut hi) = (prev_t, t);
+            while hi - lo > tol {
+                let mid = 0.5 * (lo + hi);
+                if f(mid) == prev {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            let at = 0.5 * (lo + hi);
+            match open.take() {
+                Some((start, start_open)) => windows.push(Window {
+                    start_jd: start,
+                    end_jd: at,
+                    start_open,
+                    end_open: false,
+                }),
+                None => open = Some((at, false)),
+            }
+        }
+        prev_t = t;
+        prev = now;
+        if t >= t1 {
+            break;
+        }
+        k += 1;
+    }
+    if let Some((start, start_open)) = open {
+        windows.push(Window {
+            start_jd: start,
+            end_jd: t1,
+            start_open,
+            end_open: true,
+        });
+    }
+    windows
+}
+
+/// The span and resolution of one search. `step_s` bounds discovery,
+/// `tolerance_s` the timing of each transition (see [`find_windows`]).
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct WindowSpan {
+    pub t0: f64,
+    pub t1: f64,
+    pub step_s: f64,
+    pub tolerance_s: f64,
+}
+
+impl WindowSpan {
+    fn validate(&self) -> Result<(), String> {
+        let Self {
+            t0,
+            t1,
+            step_s,
+            tolerance_s,
+        } = *self;
+        if !(t0.is_finite() && t1.is_finite() && t1 > t0) {
+            return Err(format!("need t0 < t1, got {t0} .. {t1}"));
+        }
+        if !(step_s > 0.0 && tolerance_s > 0.0) {
+            return Err("step_s and tolerance_s must be positive".into());
+        }
+        let samples = ((t1 - t0) * SECONDS_PER_DAY / step_s).ceil();
+        if samples > MAX_SAMPLES as f64 {
+            return Err(format!(
+                "{samples} samples exceeds {MAX_SAMPLES}; widen step_s or shorten the span"
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// The read-only scene a prediction reads once: the clock, ephemeris and
+/// registry, and the queries that resolve placements, terrain and occluders.
+/// Usable as a system parameter (the contact scheduler) or through a
+/// `SystemState` (the `PredictWindows` query).
+#[derive(SystemParam)]
+pub(crate) struct WindowScene<'w, 's> {
+    world_time: Option<Res<'w, WorldTime>>,
+    ephemeris: Option<Res<'w, EphemerisResource>>,
+    registry: Option<Res<'w, CelestialBodyRegistry>>,
+    ids: Option<Res<'w, ApiEntityRegistry>>,
+    q_site: Query<'w, 's, &'static GeodeticAnchor, With<SiteAnchor>>,
+    q_anchor: Query<'w, 's, &'static GeodeticAnchor>,
+    q_orbit: Query<'w, 's, &'static KeplerOrbit>,
+    q_libration: Query<'w, 's, &'static LibrationAnchor>,
+    q_halo: Query<'w, 's, (&'static HaloOrbit, Option<&'static HaloTrajectory>)>,
+    q_parents: Query<'w, 's, &'static ChildOf>,
+    q_grids: Query<'w, 's, &'static Grid>,
+    q_spatial: Query<'w, 's, (Option<&'static CellCoord>, &'static Transform)>,
+    q_nodes: Query<'w, 's, &'static LinkNode>,
+    q_terrain: Query<'w, 's, (Entity, &'static DemHeightField)>,
+    q_occluders: Query<'w, 's, (Entity, &'static LinkOccluder, &'static Transform)>,
+}
+
+impl WindowScene<'_, '_> {
+    /// The sim clock's epoch — the default start of a search.
+    pub(crate) fn now_jd(&self) -> Option<f64> {
+        self.world_time.as_deref().map(|t| t.epoch_jd)
+    }
+
+    /// The entity behind a GID.
+    pub(crate) fn resolve(&self, gid: u64) -> Result<Entity, String> {
+        self.ids
+            .as_deref()
+            .and_then(|r| r.resolve(&GlobalEntityId::from_raw(gid)))
+            .ok_or_else(|| format!("no entity with gid {gid}"))
+    }
+
+    fn source(&self, entity: Entity) -> Option<PoseSource> {
+        pose_source(
+            entity,
+            &self.q_anchor,
+            &self.q_orbit,
+            &self.q_libration,
+            &self.q_halo,
+            &self.q_parents,
+            &self.q_grids,
+            &self.q_spatial,
+        )
+    }
+
+    /// The windows of `kind` for `a` (and `b`, for `los`) over `span`.
+    pub(crate) fn predict(
+        &self,
+        kind: WindowKind,
+        a: Entity,
+        b: Option<Entity>,
+        span: &WindowSpan,
+    ) -> Result<Vec<Window>, String> {
+        span.validate()?;
+        let (Some(ephemeris), Some(registry)) =
+            (self.ephemeris.as_deref(), self.registry.as_deref())
+        else {
+            return Err("needs an ephemeris and a body registry".into());
+        };
+        if kind == WindowKind::Los && b.is_none() {
+            return Err("`b` required for kind = los".into());
+        }
+        let place = |e: Entity| {
+            self.source(e)
+                .ok_or_else(|| format!("{e} has no resolvable placement"))
+        };
+        let a_src = place(a)?;
+        let b_src = b.map(place).transpose()?;
+        let site_anchor = self.q_site.iter().next().copied();
+
+        // Terrain and occluders snapshotted once, exactly as the live kernel does
+        // per sweep: DEMs grid-absolute (the `local` frame), occluders as pose
+        // sources so each sample can place them in the solar frame at its own
+        // epoch.
+        let terrains: Vec<(DVec3, DQuat, Arc<SurfaceOracle>)> = self
+            .q_terrain
+            .iter()
+            .filter_map(|(e, hf)| {
+                let (p, r) = world_pose(e, &self.q_parents, &self.q_grids, &self.q_spatial).ok()?;
+                Some((p.0, r.0, hf.0.clone()))
+            })
+            .collect();
+        let occluders: Vec<(PoseSource, DVec3, DVec3)> = self
+            .q_occluders
+            .iter()
+            .filter_map(|(e, occ, tf)| {
+                let (center, half) = occ.box_for(tf.scale);
+                Some((self.source(e)?, center, half))
+            })
+            .collect();
+
+        let sun = crate::ephemeris_id::SUN;
+        let provider = ephemeris.provider.as_ref();
+        let ctx_at = |jd: f64| PoseContext::new(jd, registry, provider, site_anchor.as_ref());
+
+        // Validate once at t0 what the predicate will need, so a misconfigured
+        // query reports why instead of an empty schedule.
+        let mut probe = ctx_at(span.t0);
+        let a0 = probe
+            .pose(&a_src)
+            .ok_or("`a` cannot be placed (no ephemeris for its body, or no site anchor)")?;
+        let nodes = match (kind, b, b_src.as_ref()) {
+            (WindowKind::Los, Some(b), Some(b_src)) => {
+                let node = |e: Entity| {
+                    self.q_nodes
+                        .get(e)
+                        .cloned()
+                        .map_err(|_| format!("{e} is not a link node"))
+                };
+                probe.pose(b_src).ok_or("`b` cannot be placed")?;
+                Some((node(a)?, node(b)?))
+            }
+            (WindowKind::Sun, ..) if matches!(a0.horizon, Horizon::Free { .. }) => {
+                return Err("`a` is free-flying and has no horizon; use kind = eclipse".into());
+            }
+            _ => None,
+        };
+
+        let predicate = |jd: f64| -> bool {
+            let mut ctx = ctx_at(jd);
+            let Some(pa) = ctx.pose(&a_src) else {
+                return false;
+            };
+            match kind {
+                WindowKind::Los => {
+                    let (Some(pb), Some((na, nb))) =
+                        (b_src.as_ref().and_then(|s| ctx.pose(s)), &nodes)
+                    else {
+                        return false;
+                    };
+                    let boxes: Vec<(DVec3, DQuat, DVec3)> = occluders
+                        .iter()
+                        .filter_map(|(src, center, half)| {
+                            let p = ctx.pose(src)?;
+                            Some((p.pos + p.rotation * *center, p.rotation, *half))
+                        })
+                        .collect();
+                    let bodies = occluding_bodies(ephemeris, registry, jd);
+                    pair_geometry((&pa, na), (&pb, nb), &bodies, &terrains, &boxes).builtin
+                }
+                WindowKind::Sun => {
+                    let Some(sun_pos) = ctx.body_center(sun) else {
+                        return false;
+                    };
+                    let dir = (sun_pos - pa.pos).normalize_or_zero();
+                    if elevation_deg(pa.horizon, dir).is_none_or(|e| e < 0.0) {
+                        return false;
+                    }
+                    // The DEM march runs in the site frame; with no site there is
+                    // no terrain to march and `local` is the solar frame already.
+                    let sun_local = ctx
+                        .site_frame()
+                        .map(|f| f.from_frame(sun_pos))
+                        .unwrap_or(sun_pos);
+                    !terrain_blocks(pa.local, sun_local, &terrains)
+                }
+                WindowKind::Eclipse => {
+                    let Some(sun_pos) = ctx.body_center(sun) else {
+                        return false;
+                    };
+                    // The body a surface node stands on sets the Sun; it does not
+                    // eclipse it — that is `kind = sun`.
+                    let home = match pa.horizon {
+                        Horizon::Surface { body, .. } => registry
+                            .bodies
+                            .iter()
+                            .find(|d| d.ephemeris_id == body)
+                            .map(|d| d.name.clone()),
+                        Horizon::Free { .. } => None,
+                    };
+                    let sun_name = registry
+                        .bodies
+                        .iter()
+                        .find(|d| d.ephemeris_id == sun)
+                        .map(|d| d.name.clone());
+                    occluding_bodies(ephemeris, registry, jd)
+                        .iter()
+                        .filter(|(n, _, _)| {
+                            Some(n) != home.as_ref() && Some(n) != sun_name.as_ref()
+                        })
+                        .any(|(_, c, r)| segment_hits_sphere(pa.pos, sun_pos, *c, *r))
+                }
+            }
+        };
+
+        Ok(find_windows(
+            span.t0,
+            span.t1,
+            span.step_s / SECONDS_PER_DAY,
+            span.tolerance_s / SECONDS_PER_DAY,
+            predicate,
+        ))
+    }
+}
+
+/// `PredictWindows` — link contact, sunlight and eclipse windows over future
+/// epochs, without advancing the sim clock. See the module docs for what is
+/// frozen and what differs from the live kernel.
+///
+/// params:
+/// ```text
+/// { a: <gid>, b?: <gid>,            # b required for kind = "los"
+///   kind: "los" | "sun" | "eclipse",
+///   t0?: jd, t1?: jd,               # default: now → now + 14 d (TDB JD)
+///   step_s?: 60, tolerance_s?: 1 }
+/// ```
+/// returns
+/// ```text
+/// { kind, a, b, t0, t1,
+///   windows:     [{ start_jd, end_jd, duration_s, start_open, end_open }],
+///   transitions: [{ jd, event }] }   # aos/los · rise/set · entry/exit
+/// ```
+/// `sun` needs a horizon; for an orbiter or libration-point node use `eclipse`.
+pub struct PredictWindowsProvider;
+
+impl ApiQueryProvider for PredictWindowsProvider {
+    fn name(&self) -> &'static str {
+        "PredictWindows"
+    }
+
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        match predict(world, params) {
+            Ok(v) => ApiResponse::ok(v),
+            Err(e) => e.respond("PredictWindows"),
+        }
+    }
+}
+
+/// A failed query and the code it answers with: a gid that names no entity is
+/// `EntityNotFound`, as in `lunco_api::queries`; anything else is a parameter
+/// the caller got wrong.
+pub(crate) struct QueryError {
+    code: ApiErrorCode,
+    msg: String,
+}
+
+impl QueryError {
+    pub(crate) fn not_found(msg: impl Into<String>) -> Self {
+        Self {
+            code: ApiErrorCode::EntityNotFound,
+            msg: msg.into(),
+        }
+    }
+
+    pub(crate) fn respond(self, query: &str) -> ApiResponse {
+        ApiResponse::error(self.code, format!("{query}: {}", self.msg))
+    }
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.msg)
+    }
+}
+
+impl From<String> for QueryError {
+    fn from(msg: String) -> Self {
+        Self {
+            code: ApiErrorCode::DeserializationError,
+            msg,
+        }
+    }
+}
+
+impl From<&str> for QueryError {
+    fn from(msg: &str) -> Self {
+        msg.to_string().into()
+    }
+}
+
+fn predict(world: &mut World, params: &serde_json::Value) -> Result<serde_json::Value, QueryError> {
+    let kind = params
+        .get("kind")
+        .and_then(serde_json::Value::as_str)
+        .ok_or("`kind` (los | sun | eclipse) required")?;
+    let kind = WindowKind::parse(kind)
+        .ok_or_else(|| format!("unknown kind `{kind}` (los | sun | eclipse)"))?;
+    let gid = |key: &str| params.get(key).and_then(serde_json::Value::as_u64);
+    let a_gid = gid("a").ok_or("`a` (gid) required")?;
+    let b_gid = gid("b");
+    let num = |key: &str| params.get(key).and_then(serde_json::Value::as_f64);
+
+    let mut state: SystemState<WindowScene> = SystemState::new(world);
+    let scene = state
+        .get(world)
+        .map_err(|e| format!("scene unavailable: {e}"))?;
+    let t0 = match num("t0") {
+        Some(t0) => t0,
+        None => scene.now_jd().ok_or("no clock; pass `t0`")?,
+    };
+    let span = WindowSpan {
+        t0,
+        t1: num("t1").unwrap_or(t0 + DEFAULT_SPAN_DAYS),
+        step_s: num("step_s").unwrap_or(DEFAULT_STEP_S),
+        tolerance_s: num("tolerance_s").unwrap_or(DEFAULT_TOLERANCE_S),
+    };
+    let a = scene.resolve(a_gid).map_err(QueryError::not_found)?;
+    let b = b_gid
+        .map(|g| scene.resolve(g))
+        .transpose()
+        .map_err(QueryError::not_found)?;
+    let windows = scene.predict(kind, a, b, &span)?;
+
+    let (opens, closes) = kind.events();
+    let mut transitions = Vec::new();
+    for w in &windows {
+        if !w.start_open {
+            transitions.push(serde_json::json!({ "jd": w.start_jd, "event": opens }));
+        }
+        if !w.end_open {
+            transitions.push(serde_json::json!({ "jd": w.end_jd, "event": closes }));
+        }
+    }
+    let windows: Vec<serde_json::Value> = windows
+        .iter()
+        .map(|w| {
+            serde_json::json!({
+                "start_jd": w.start_jd,
+                "end_jd": w.end_jd,
+                "duration_s": (w.end_jd - w.start_jd) * SECONDS_PER_DAY,
+                "start_open": w.start_open,
+                "end_open": w.end_open,
+            })
+        })
+        .collect();
+    Ok(serde_json::json!({
+        "kind": kind.name(),
+        "a": a_gid,
+        "b": b_gid,
+        "t0": span.t0,
+        "t1": span.t1,
+        "windows": windows,
+        "transitions": transitions,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A square wave: up on [0.25, 0.5) of every unit period.
+    fn pulse(t: f64) -> bool {
+        let f = t.rem_euclid(1.0);
+        (0.25..0.5).contains(&f)
+    }
+
+    #[test]
+    fn transitions_are_bisected_to_the_tolerance() {
+        let w = find_windows(0.0, 2.0, 0.1, 1e-6, pulse);
+        assert_eq!(w.len(), 2, "{w:?}");
+        for (win, base) in w.iter().zip([0.0, 1.0]) {
+            assert!((win.start_jd - (base + 0.25)).abs() < 1e-6, "{win:?}");
+            assert!((win.end_jd - (base + 0.5)).abs() < 1e-6, "{win:?}");
+            assert!(!win.start_open && !win.end_open);
+        }
+    }
+
+    /// A window already in progress at `t0`, or still open at `t1`, is clipped to
+    /// the span and says so — a planner must not read the clip as an AOS.
+    #[test]
+    fn windows_clipped_by_the_span_are_marked_open() {
+        let w = find_windows(0.3, 1.4, 0.05, 1e-6, pulse);
+        assert_eq!(w.len(), 2, "{w:?}");
+        assert_eq!(w[0].start_jd, 0.3);
+        assert!(w[0].start_open && !w[0].end_open);
+        assert!((w[1].start_jd - 1.25).abs() < 1e-6);
+        assert_eq!(w[1].end_jd, 1.4);
+        assert!(!w[1].start_open && w[1].end_open);
+
+        let always = find_windows(0.0, 1.0, 0.1, 1e-6, |_| true);
+        assert_eq!(
+            always,
+            vec![Window {
+                start_jd: 0.0,
+                end_jd: 1.0,
+                start_open: true,
+                end_open: true,
+            }]
+        );
+        assert!(find_windows(0.0, 1.0, 0.1, 1e-6, |_| false).is_empty());
+    }
+
+    /// The step bounds discovery: a window narrower than it can slip between two
+    /// samples. The contract is stated, so a caller picks a step under the
+    /// shortest pass they care about.
+    #[test]
+    fn a_window_narrower_than_the_step_can_be_missed() {
+        let narrow = |t: f64| (0.42..0.44).contains(&t);
+        assert!(find_windows(0.0, 1.0, 0.1, 1e-6, narrow).is_empty());
+        assert_eq!(find_windows(0.0, 1.0, 0.01, 1e-6, narrow).len(), 1);
+    }
+
+    fn error_code(params: serde_json::Value) -> u16 {
+        match PredictWindowsProvider.execute(&mut World::new(), &params) {
+            ApiResponse::Error { code, .. } => code,
+            other => panic!("expected an error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn an_unknown_gid_is_not_found_and_a_bad_kind_is_a_bad_parameter() {
+        let unknown = serde_json::json!({ "kind": "sun", "a": 42, "t0": 2_460_000.5 });
+        assert_eq!(error_code(unknown), ApiErrorCode::EntityNotFound as u16);
+        let bad_kind = serde_json::json!({ "kind": "dusk", "a": 42, "t0": 2_460_000.5 });
+        assert_eq!(
+            error_code(bad_kind),
+            ApiErrorCode::DeserializationError as u16
+        );
+    }
+}
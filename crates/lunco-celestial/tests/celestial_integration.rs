@@ -306,7 +306,8 @@ fn each_builtin_orbit_target_has_one_colocated_star_fixed_grid() {
                     Some((entity, center, *cell, *transform))
                 }
                 lunco_celestial::ReferenceFrame::World
-                | lunco_celestial::ReferenceFrame::BodyFixed { .. } => None,
+                | lunco_celestial::ReferenceFrame::BodyFixed { .. }
+                | lunco_celestial::ReferenceFrame::Synodic { .. } => None,
             })
             .collect()
     };
@@ -41,6 +41,8 @@ mod scene_lifecycle;
 /// drives; trivially inert in single-player.
 pub mod session;
 pub mod telemetry;
+/// Physical units (Modelica syntax) — parse, dimension algebra, wire checks.
+pub mod units;
 /// The persistent big_space world shell (single root + `WorldGrid` + one
 /// `FloatingOrigin`) that every scene mounts into.
 pub mod world;
@@ -0,0 +1,570 @@
+//! Physical units as models and ports declare them — parse, compare, convert.
+//!
+//! Every participant already SAYS what its numbers mean: a Modelica variable
+//! carries `unit = "rad/s"`, an avian port is SI by construction, and a wire
+//! carries an SSP `factor`/`offset` to bridge the two. Nothing used to check that
+//! the three agree, so `rpm` landing in a `rad/s` input simply ran 9.55× slow and
+//! every plot looked plausible. This module is the arithmetic both checks share:
+//! the Modelica unit-inference lint and the cosim wire check.
+//!
+//! # The model
+//!
+//! A [`Unit`] is `scale × dimension + offset` over the seven SI base dimensions
+//! (`m kg s A K mol cd`). The syntax is Modelica's (MLS §19.1): factors joined by
+//! `.`, an integer exponent written straight after the symbol (`m2`, `s-1`), and
+//! one `/` — `kg.m2/s2`, `N.m/rad`, `1/s`. SI prefixes apply to every symbol
+//! (`km`, `mA`, `kPa`).
+//!
+//! **Angles are dimensionless**, as in SI and throughout the MSL: `rad` is `1`,
+//! `deg` is `π/180`, `rev` is `2π`. That is what makes `N.m/rad × rad = N.m`
+//! consistent, and it is also what makes `rpm → rad/s` a SCALE error rather than
+//! a dimension error — the two are the same dimension, `1/s`, at different
+//! scales, which is exactly the missing-factor case the wire check reports.
+//!
+//! Temperatures keep their offset (`degC` is `K + 273.15`) so a Celsius→Kelvin
+//! wire needs its SSP offset, not just a factor.
+
+use std::fmt;
+
+/// The seven SI base dimensions, in exponent-slot order.
+pub const BASE_SYMBOLS: [&str; 7] = ["m", "kg", "s", "A", "K", "mol", "cd"];
+
+/// Exponents over [`BASE_SYMBOLS`]. `Dimension::ONE` is dimensionless.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Dimension(pub [i8; 7]);
+
+impl Dimension {
+    /// Dimensionless.
+    pub const ONE: Dimension = Dimension([0; 7]);
+
+    const fn base(slot: usize) -> Dimension {
+        let mut d = [0; 7];
+        d[slot] = 1;
+        Dimension(d)
+    }
+
+    /// Whether every exponent is zero.
+    pub fn is_one(self) -> bool {
+        self == Self::ONE
+    }
+
+    /// Product of two dimensions.
+    pub fn mul(self, other: Dimension) -> Dimension {
+        let mut d = self.0;
+        for (a, b) in d.iter_mut().zip(other.0) {
+            *a += b;
+        }
+        Dimension(d)
+    }
+
+    /// Quotient of two dimensions.
+    pub fn div(self, other: Dimension) -> Dimension {
+        self.mul(other.powi(-1))
+    }
+
+    /// Integer power.
+    pub fn powi(self, n: i32) -> Dimension {
+        let mut d = self.0;
+        for a in &mut d {
+            *a = (i32::from(*a) * n) as i8;
+        }
+        Dimension(d)
+    }
+
+    /// The `n`-th root, when every exponent divides evenly — `sqrt(m2)` is `m`,
+    /// `sqrt(m)` has no dimension to give.
+    pub fn root(self, n: i8) -> Option<Dimension> {
+        if n == 0 || self.0.iter().any(|a| a % n != 0) {
+            return None;
+        }
+        let mut d = self.0;
+        for a in &mut d {
+            *a /= n;
+        }
+        Some(Dimension(d))
+    }
+}
+
+impl fmt::Display for Dimension {
+    /// Modelica spelling over base units: `m.kg/s2`, `1/s`, `1`.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let part = |positive: bool| -> Vec<String> {
+            BASE_SYMBOLS
+                .iter()
+                .zip(self.0)
+                .filter(|(_, e)| if positive { *e > 0 } else { *e < 0 })
+                .map(|(s, e)| match e.abs() {
+                    1 => (*s).to_string(),
+                    n => format!("{s}{n}"),
+                })
+                .collect()
+        };
+        let num = part(true);
+        let den = part(false);
+        let num = if num.is_empty() {
+            "1".to_string()
+        } else {
+            num.join(".")
+        };
+        match den.len() {
+            0 => write!(f, "{num}"),
+            1 => write!(f, "{num}/{}", den[0]),
+            _ => write!(f, "{num}/({})", den.join(".")),
+        }
+    }
+}
+
+/// A parsed unit: a value `v` in this unit is `v * scale + offset` in SI base
+/// units of [`dimension`](Self::dimension).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Unit {
+    /// Multiplier to the coherent SI unit.
+    pub scale: f64,
+    /// Additive offset to the coherent SI unit — non-zero only for `degC`/`degF`.
+    pub offset: f64,
+    /// Physical dimension.
+    pub dimension: Dimension,
+}
+
+/// Why a unit string did not parse.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UnitError {
+    /// The text as declared.
+    pub text: String,
+    /// What was wrong with it.
+    pub reason: String,
+}
+
+impl fmt::Display for UnitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unit \"{}\": {}", self.text, self.reason)
+    }
+}
+
+impl std::error::Error for UnitError {}
+
+/// `value_to = value_from * factor + offset` — the SSP `LinearTransformation` a
+/// wire needs between two units of the same dimension.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Conversion {
+    /// SSP factor.
+    pub factor: f64,
+    /// SSP offset.
+    pub offset: f64,
+}
+
+impl Conversion {
+    /// Whether this is `× 1 + 0` to within rounding.
+    pub fn is_identity(self) -> bool {
+        close(self.factor, 1.0) && close(self.offset, 0.0)
+    }
+
+    /// Whether `factor`/`offset` as authored on a wire realise this conversion.
+    pub fn matches(self, factor: f64, offset: f64) -> bool {
+        close(self.factor, factor) && close(self.offset, offset)
+    }
+}
+
+/// Relative comparison for scales: unit factors are products of decimal
+/// prefixes and π, so they never agree bit-for-bit.
+fn close(a: f64, b: f64) -> bool {
+    (a - b).abs() <= 1e-9 * a.abs().max(b.abs()).max(1e-12)
+}
+
+impl Unit {
+    /// The unit `1`.
+    pub const ONE: Unit = Unit {
+        scale: 1.0,
+        offset: 0.0,
+        dimension: Dimension::ONE,
+    };
+
+    /// Parse a Modelica unit string. `""` is an error — it means "undeclared",
+    /// and the caller has to treat that as unknown rather than as `1`.
+    pub fn parse(text: &str) -> Result<Unit, UnitError> {
+        let fail = |reason: &str| UnitError {
+            text: text.to_string(),
+            reason: reason.to_string(),
+        };
+        let trimmed = text.trim();
+        if trimmed.is_empty() {
+            return Err(fail("no unit declared"));
+        }
+        let mut parser = Parser {
+            chars: trimmed.chars().collect(),
+            pos: 0,
+        };
+        let unit = parser.expression().map_err(|r| fail(&r))?;
+        if parser.pos != parser.chars.len() {
+            return Err(fail(&format!(
+                "unexpected `{}`",
+                parser.chars[parser.pos..].iter().collect::<String>()
+            )));
+        }
+        Ok(unit)
+    }
+
+    /// Whether the dimension is `1` (angles, ratios, `%`).
+    pub fn is_dimensionless(&self) -> bool {
+        self.dimension.is_one()
+    }
+
+    /// Product. Offsets do not survive multiplication: `degC.m` is a
+    /// temperature DIFFERENCE times a length.
+    pub fn mul(&self, other: &Unit) -> Unit {
+        Unit {
+            scale: self.scale * other.scale,
+            offset: 0.0,
+            dimension: self.dimension.mul(other.dimension),
+        }
+    }
+
+    /// Quotient; see [`mul`](Self::mul) for offsets.
+    pub fn div(&self, other: &Unit) -> Unit {
+        Unit {
+            scale: self.scale / other.scale,
+            offset: 0.0,
+            dimension: self.dimension.div(other.dimension),
+        }
+    }
+
+    /// Integer power.
+    pub fn powi(&self, n: i32) -> Unit {
+        Unit {
+            scale: self.scale.powi(n),
+            offset: 0.0,
+            dimension: self.dimension.powi(n),
+        }
+    }
+
+    /// The transform taking a value in `self` to a value in `to`, or `None` when
+    /// the dimensions differ and no affine map can.
+    pub fn conversion_to(&self, to: &Unit) -> Option<Conversion> {
+        (self.dimension == to.dimension).then(|| Conversion {
+            factor: self.scale / to.scale,
+            offset: (self.offset - to.offset) / to.scale,
+        })
+    }
+}
+
+/// A symbol and what it is in coherent SI: `(symbol, scale, offset, dimension)`.
+type Symbol = (&'static str, f64, f64, Dimension);
+
+const M: Dimension = Dimension::base(0);
+const KG: Dimension = Dimension::base(1);
+const S: Dimension = Dimension::base(2);
+const A: Dimension = Dimension::base(3);
+const K: Dimension = Dimension::base(4);
+const MOL: Dimension = Dimension::base(5);
+const CD: Dimension = Dimension::base(6);
+
+/// `m^a kg^b s^c A^d K^e` — the derived units below are written with it so
+/// each row reads like the SI brochure.
+const fn dim(m: i8, kg: i8, s: i8, a: i8, k: i8) -> Dimension {
+    Dimension([m, kg, s, a, k, 0, 0])
+}
+
+const PI: f64 = std::f64::consts::PI;
+
+/// Every symbol the parser knows. Whole symbols are matched before a prefix is
+/// split off, so `min`, `mol`, `cd`, `Pa` and `degC` are never read as
+/// `m·in`, `m·ol`, `c·d`, `P·a` or `d·egC`.
+const SYMBOLS: &[Symbol] = &[
+    // Base units. `kg` is the prefix `k` on `g`.
+    ("m", 1.0, 0.0, M),
+    ("g", 1e-3, 0.0, KG),
+    ("s", 1.0, 0.0, S),
+    ("A", 1.0, 0.0, A),
+    ("K", 1.0, 0.0, K),
+    ("mol", 1.0, 0.0, MOL),
+    ("cd", 1.0, 0.0, CD),
+    // Derived units with special names.
+    ("rad", 1.0, 0.0, Dimension::ONE),
+    ("sr", 1.0, 0.0, Dimension::ONE),
+    ("Hz", 1.0, 0.0, dim(0, 0, -1, 0, 0)),
+    ("N", 1.0, 0.0, dim(1, 1, -2, 0, 0)),
+    ("Pa", 1.0, 0.0, dim(-1, 1, -2, 0, 0)),
+    ("J", 1.0, 0.0, dim(2, 1, -2, 0, 0)),
+    ("W", 1.0, 0.0, dim(2, 1, -3, 0, 0)),
+    ("C", 1.0, 0.0, dim(0, 0, 1, 1, 0)),
+    ("V", 1.0, 0.0, dim(2, 1, -3, -1, 0)),
+    ("F", 1.0, 0.0, dim(-2, -1, 4, 2, 0)),
+    ("Ohm", 1.0, 0.0, dim(2, 1, -3, -2, 0)),
+    ("S", 1.0, 0.0, dim(-2, -1, 3, 2, 0)),
+    ("Wb", 1.0, 0.0, dim(2, 1, -2, -1, 0)),
+    ("T", 1.0, 0.0, dim(0, 1, -2, -1, 0)),
+    ("H", 1.0, 0.0, dim(2, 1, -2, -2, 0)),
+    ("lm", 1.0, 0.0, CD),
+    ("lx", 1.0, 0.0, Dimension([-2, 0, 0, 0, 0, 0, 1])),
+    ("Bq", 1.0, 0.0, dim(0, 0, -1, 0, 0)),
+    ("Gy", 1.0, 0.0, dim(2, 0, -2, 0, 0)),
+    ("Sv", 1.0, 0.0, dim(2, 0, -2, 0, 0)),
+    ("kat", 1.0, 0.0, Dimension([0, 0, -1, 0, 0, 1, 0])),
+    // Non-SI units the MSL's `NonSIunits` and common authoring use.
+    ("min", 60.0, 0.0, S),
+    ("h", 3600.0, 0.0, S),
+    ("d", 86400.0, 0.0, S),
+    ("deg", PI / 180.0, 0.0, Dimension::ONE),
+    ("rev", 2.0 * PI, 0.0, Dimension::ONE),
+    ("rpm", 2.0 * PI / 60.0, 0.0, dim(0, 0, -1, 0, 0)),
+    ("l", 1e-3, 0.0, dim(3, 0, 0, 0, 0)),
+    ("L", 1e-3, 0.0, dim(3, 0, 0, 0, 0)),
+    ("bar", 1e5, 0.0, dim(-1, 1, -2, 0, 0)),
+    ("degC", 1.0, 273.15, K),
+    ("degF", 5.0 / 9.0, 273.15 - 32.0 * 5.0 / 9.0, K),
+    ("%", 0.01, 0.0, Dimension::ONE),
+];
+
+const PREFIXES: &[(&str, f64)] = &[
+    ("da", 1e1),
+    ("Y", 1e24),
+    ("Z", 1e21),
+    ("E", 1e18),
+    ("P", 1e15),
+    ("T", 1e12),
+    ("G", 1e9),
+    ("M", 1e6),
+    ("k", 1e3),
+    ("h", 1e2),
+    ("d", 1e-1),
+    ("c", 1e-2),
+    ("m", 1e-3),
+    ("u", 1e-6),
+    ("µ", 1e-6),
+    ("n", 1e-9),
+    ("p", 1e-12),
+    ("f", 1e-15),
+    ("a", 1e-18),
+    ("z", 1e-21),
+    ("y", 1e-24),
+];
+
+fn symbol(name: &str) -> Option<Unit> {
+    let whole = |n: &str| {
+        SYMBOLS
+            .iter()
+            .find(|(s, ..)| *s == n)
+            .map(|&(_, scale, offset, dimension)| Unit {
+                scale,
+                offset,
+                dimension,
+            })
+    };
+    if let Some(unit) = whole(name) {
+        return Some(unit);
+    }
+    PREFIXES.iter().find_map(|(prefix, factor)| {
+        let rest = name.strip_prefix(prefix)?;
+        // A prefixed offset unit (`kdegC`) means nothing.
+        let unit = whole(rest).filter(|u| u.offset == 0.0)?;
+        Some(Unit {
+            scale: unit.scale * factor,
+            ..unit
+        })
+    })
+}
+
+/// Recursive descent over MLS §19.1, lenient in one way: more than one `/` is
+/// read left to right (`m/s/s` is `m/s2`) instead of rejected.
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn expression(&mut self) -> Result<Unit, String> {
+        let mut unit = self.factors()?;
+        while self.peek() == Some('/') {
+            self.pos += 1;
+            let den = match self.peek() {
+                Some('(') => self.group()?,
+                _ => self.factor()?,
+            };
+            unit = unit.div(&den);
+        }
+        Ok(unit)
+    }
+
+    fn group(&mut self) -> Result<Unit, String> {
+        self.pos += 1;
+        let inner = self.expression()?;
+        if self.peek() != Some(')') {
+            return Err("unbalanced `(`".to_string());
+        }
+        self.pos += 1;
+        Ok(inner)
+    }
+
+    fn factors(&mut self) -> Result<Unit, String> {
+        if self.peek() == Some('(') {
+            return self.group();
+        }
+        if self.peek() == Some('1') {
+            self.pos += 1;
+            return Ok(Unit::ONE);
+        }
+        let mut unit = self.factor()?;
+        while self.peek() == Some('.') {
+            self.pos += 1;
+            unit = unit.mul(&self.factor()?);
+        }
+        Ok(unit)
+    }
+
+    fn factor(&mut self) -> Result<Unit, String> {
+        let start = self.pos;
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphabetic() || c == '%' || c == 'µ')
+        {
+            self.pos += 1;
+        }
+        let name: String = self.chars[start..self.pos].iter().collect();
+        if name.is_empty() {
+            return Err(match self.peek() {
+                Some(c) => format!("expected a unit symbol at `{c}`"),
+                None => "expected a unit symbol".to_string(),
+            });
+        }
+        let unit = symbol(&name).ok_or_else(|| format!("unknown unit symbol `{name}`"))?;
+        let exp_start = self.pos;
+        if matches!(self.peek(), Some('+' | '-')) {
+            self.pos += 1;
+        }
+        while self.peek().is_some_and(|c| c.is_ascii_digit()) {
+            self.pos += 1;
+        }
+        let exponent: String = self.chars[exp_start..self.pos].iter().collect();
+        if exponent.is_empty() {
+            return Ok(unit);
+        }
+        let n: i32 = exponent
+            .parse()
+            .map_err(|_| format!("bad exponent `{exponent}` on `{name}`"))?;
+        if unit.offset != 0.0 {
+            return Err(format!(
+                "`{name}` has an offset and cannot take an exponent"
+            ));
+        }
+        Ok(unit.powi(n))
+    }
+}
+
+/// What a wire's units say about its `factor`/`offset`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum WireUnits {
+    /// The units agree under the wire's transform.
+    Consistent,
+    /// Nothing to check: a side declares no unit (or one this parser does not
+    /// know), or the wire authors a gain out of a dimensionless port — a
+    /// normalised command scaled to newtons is a design, not a mistake.
+    Unchecked,
+    /// Different physical dimensions.
+    Incompatible {
+        /// Dimension at the source port.
+        from: Dimension,
+        /// Dimension at the target port.
+        to: Dimension,
+    },
+    /// Same dimension at different scales, and the wire applies no transform —
+    /// `rpm` into `rad/s`.
+    MissingScale(Conversion),
+    /// Same dimension, and the wire applies a transform — just not this one.
+    WrongScale(Conversion),
+}
+
+/// Judge one wire `from → to` carrying `value * factor + offset`.
+pub fn check_wire(from: &str, to: &str, factor: f64, offset: f64) -> WireUnits {
+    let (Ok(src), Ok(dst)) = (Unit::parse(from), Unit::parse(to)) else {
+        return WireUnits::Unchecked;
+    };
+    let authored = Conversion { factor, offset };
+    match src.conversion_to(&dst) {
+        Some(needed) if needed.matches(factor, offset) => WireUnits::Consistent,
+        Some(needed) if authored.is_identity() => WireUnits::MissingScale(needed),
+        Some(needed) => WireUnits::WrongScale(needed),
+        None if !authored.is_identity() && (src.is_dimensionless() || dst.is_dimensionless()) => {
+            WireUnits::Unchecked
+        }
+        None => WireUnits::Incompatible {
+            from: src.dimension,
+            to: dst.dimension,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn unit(s: &str) -> Unit {
+        Unit::parse(s).unwrap_or_else(|e| panic!("{e}"))
+    }
+
+    #[test]
+    fn derived_units_reduce_to_base_dimensions() {
+        assert_eq!(unit("N").dimension, unit("kg.m/s2").dimension);
+        assert_eq!(unit("N.m/rad").dimension, unit("J").dimension);
+        assert_eq!(unit("1/s"), unit("s-1"));
+        assert_eq!(unit("W").dimension.to_string(), "m2.kg/s3");
+        assert_eq!(unit("m/s/s").dimension, unit("m/s2").dimension);
+        assert!(close(unit("km").scale, 1e3));
+        assert!(close(unit("kg").scale, 1.0));
+        assert!(unit("mol").dimension == MOL && close(unit("mol").scale, 1.0));
+    }
+
+    #[test]
+    fn malformed_and_unknown_units_are_errors_not_guesses() {
+        assert!(Unit::parse("").is_err());
+        assert!(Unit::parse("MB").is_err());
+        assert!(Unit::parse("m/(s").is_err());
+        assert!(Unit::parse("degC2").is_err());
+    }
+
+    /// THE CASE: rpm and rad/s are one dimension at two scales, so joining them
+    /// with an identity wire is a missing factor, not an incompatibility — and
+    /// the factor it names is the one that went missing.
+    #[test]
+    fn rpm_into_rad_per_second_needs_its_factor() {
+        let WireUnits::MissingScale(needed) = check_wire("rpm", "rad/s", 1.0, 0.0) else {
+            panic!("rpm → rad/s with no factor must be a missing scale");
+        };
+        assert!(close(needed.factor, PI / 30.0));
+        assert_eq!(
+            check_wire("rpm", "rad/s", PI / 30.0, 0.0),
+            WireUnits::Consistent
+        );
+        assert!(matches!(
+            check_wire("rad/s", "rpm", 9.55, 0.0),
+            WireUnits::WrongScale(_)
+        ));
+    }
+
+    #[test]
+    fn temperatures_need_their_offset() {
+        assert_eq!(check_wire("degC", "K", 1.0, 273.15), WireUnits::Consistent);
+        assert!(matches!(
+            check_wire("degC", "K", 1.0, 0.0),
+            WireUnits::MissingScale(c) if close(c.offset, 273.15)
+        ));
+    }
+
+    #[test]
+    fn different_dimensions_are_incompatible_unless_a_gain_leaves_a_ratio() {
+        assert!(matches!(
+            check_wire("m", "N", 1.0, 0.0),
+            WireUnits::Incompatible { .. }
+        ));
+        assert!(matches!(
+            check_wire("1", "N", 1.0, 0.0),
+            WireUnits::Incompatible { .. }
+        ));
+        assert_eq!(check_wire("1", "N", 500.0, 0.0), WireUnits::Unchecked);
+        assert_eq!(check_wire("", "N", 1.0, 0.0), WireUnits::Unchecked);
+        assert_eq!(check_wire("N", "N", 1.0, 0.0), WireUnits::Consistent);
+    }
+}
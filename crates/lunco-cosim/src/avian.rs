@@ -352,6 +352,7 @@ pub const COLLIDER_CONTACT_GROUP: AvianGroup = AvianGroup {
             write: None,
         },
     ],
+    units: &[("contact_force", "N"), ("mass", "kg")],
 };
 
 /// A USD-authored force actuator. Its command is scalar force; position and
@@ -364,6 +365,7 @@ pub const FORCE_ACTUATOR_GROUP: AvianGroup = AvianGroup {
         read: Some(|w, e| Some(w.get::<PendingActuatorCommand>(e).map_or(0.0, |p| p.value))),
         write: Some(with_pending_actuator_command),
     }],
+    units: &[("force_command", "N")],
 };
 
 /// A USD-authored torque actuator. Its command is scalar torque; its axis and
@@ -376,6 +378,7 @@ pub const TORQUE_ACTUATOR_GROUP: AvianGroup = AvianGroup {
         read: Some(|w, e| Some(w.get::<PendingActuatorCommand>(e).map_or(0.0, |p| p.value))),
         write: Some(with_pending_actuator_command),
     }],
+    units: &[("torque_command", "N.m")],
 };
 
 /// The rigid-body port group: position/velocity outputs + force inputs.
@@ -683,6 +686,40 @@ pub const RIGID_BODY_GROUP: AvianGroup = AvianGroup {
             write: Some(|w, e, v| write_com_axis(w, e, 2, v)),
         },
     ],
+    units: &[
+        ("position_x", "m"),
+        ("position_y", "m"),
+        ("position_z", "m"),
+        ("velocity_x", "m/s"),
+        ("velocity_y", "m/s"),
+        ("velocity_z", "m/s"),
+        ("acceleration_x", "m/s2"),
+        ("acceleration_y", "m/s2"),
+        ("acceleration_z", "m/s2"),
+        ("speed", "m/s"),
+        ("yaw", "rad"),
+        ("pitch", "rad"),
+        ("roll", "rad"),
+        ("angvel_x", "rad/s"),
+        ("angvel_y", "rad/s"),
+        ("angvel_z", "rad/s"),
+        ("force_x", "N"),
+        ("force_y", "N"),
+        ("force_z", "N"),
+        ("force_local_x", "N"),
+        ("force_local_y", "N"),
+        ("force_local_z", "N"),
+        ("torque_x", "N.m"),
+        ("torque_y", "N.m"),
+        ("torque_z", "N.m"),
+        ("mass", "kg"),
+        ("inertia_xx", "kg.m2"),
+        ("inertia_yy", "kg.m2"),
+        ("inertia_zz", "kg.m2"),
+        ("com_x", "m"),
+        ("com_y", "m"),
+        ("com_z", "m"),
+    ],
 };
 
 /// Position inputs for an authored kinematic body.
@@ -722,6 +759,11 @@ pub const KINEMATIC_POSITION_GROUP: AvianGroup = AvianGroup {
             write: Some(|w, e, value| write_kinematic_position_axis(w, e, value, 2)),
         },
     ],
+    units: &[
+        ("position_x", "m"),
+        ("position_y", "m"),
+        ("position_z", "m"),
+    ],
 };
 
 fn write_kinematic_position_axis(
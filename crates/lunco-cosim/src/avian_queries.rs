@@ -160,6 +160,13 @@ pub const RAYCAST_GROUP: AvianGroup = AvianGroup {
             write: None,
         },
     ],
+    units: &[
+        ("ray_distance", "m"),
+        ("ray_hit_position_x", "m"),
+        ("ray_hit_position_y", "m"),
+        ("ray_hit_position_z", "m"),
+        ("ray_sample_time", "s"),
+    ],
 };
 
 /// Sample every mounted ray after Avian has written back the completed physics
@@ -18,6 +18,17 @@ pub struct DeclaredOutputPorts {
     pub names: HashSet<String>,
 }
 
+/// The physical unit each port of an endpoint declares, in Modelica unit syntax
+/// (`"rad/s"`, `"N.m"`). Inserted by the engine that knows them — a Modelica
+/// model's `unit` attributes — and read by
+/// [`crate::systems::wire_units::check_wire_units`]. Avian ports declare theirs
+/// in their port table instead; an entry here takes precedence over it.
+#[derive(Component, Debug, Clone, Default)]
+pub struct PortUnits {
+    /// Port name → declared unit.
+    pub units: HashMap<String, String>,
+}
+
 /// A co-simulation model on an entity.
 ///
 /// Created by engine plugins (e.g., `lunco-modelica`) when a model is loaded/compiled.
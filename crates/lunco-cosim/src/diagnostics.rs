@@ -19,13 +19,19 @@
 //! * [`CosimDiagnostics::broken`] holds only terminal failures: a ready (or
 //!   failed) endpoint that still cannot accept the named input.
 //!
-//! It does NOT invent a finer "pending vs structural vs type-mismatch"
-//! classification the substrate can't yet vouch for — that needs the typed
-//! causality/unit metadata a later stage adds. Reporting only what is known keeps
-//! the endpoint truthful, the property the report's "queued ≠ succeeded" critique
-//! is about.
+//! * [`CosimDiagnostics::unit_mismatches`] holds BOUND wires whose ends declare
+//!   units the wire's `factor`/`offset` does not reconcile — a different
+//!   dimension, or the same dimension at another scale with no factor between
+//!   them. Such a wire carries values every tick; they are just the wrong values.
+//!
+//! It does NOT invent a finer "pending vs structural" classification the
+//! substrate can't yet vouch for, and it only judges units somebody declared: a
+//! wire with an undeclared end is unchecked, never assumed consistent. Reporting
+//! only what is known keeps the endpoint truthful, the property the report's
+//! "queued ≠ succeeded" critique is about.
 
 use bevy::prelude::*;
+use lunco_core::units::WireUnits;
 use lunco_core::GlobalEntityId;
 
 /// One connection target that did not accept its write on the last propagation
@@ -68,6 +74,68 @@ pub struct AlgebraicLoopDiagnostic {
     pub rejected: bool,
 }
 
+/// A bound wire whose declared units disagree with its transform. Rebuilt by
+/// [`crate::systems::wire_units::check_wire_units`] whenever a wire binds or
+/// unbinds, or a unit declaration changes.
+#[derive(Debug, Clone)]
+pub struct WireUnitMismatch {
+    /// The [`crate::SimConnection`] entity.
+    pub connection: Entity,
+    /// Entity owning the source port.
+    pub source: Entity,
+    /// Its stable id, when assigned.
+    pub source_id: Option<GlobalEntityId>,
+    /// Source port name.
+    pub source_port: String,
+    /// Unit the source port declares.
+    pub source_unit: String,
+    /// Entity owning the target port.
+    pub target: Entity,
+    /// Its stable id, when assigned.
+    pub target_id: Option<GlobalEntityId>,
+    /// Target port name.
+    pub target_port: String,
+    /// Unit the target port declares.
+    pub target_unit: String,
+    /// The wire's authored SSP factor.
+    pub scale: f64,
+    /// The wire's authored SSP offset.
+    pub offset: f64,
+    /// What is wrong: [`WireUnits::Incompatible`], [`WireUnits::MissingScale`]
+    /// or [`WireUnits::WrongScale`] — never a consistent or unchecked verdict.
+    pub verdict: WireUnits,
+}
+
+impl WireUnitMismatch {
+    /// One line naming both ends, both units, and the transform that would fix
+    /// it when there is one.
+    pub fn detail(&self) -> String {
+        let wire = format!(
+            "wire {}.{} [{}] -> {}.{} [{}]",
+            self.source,
+            self.source_port,
+            self.source_unit,
+            self.target,
+            self.target_port,
+            self.target_unit
+        );
+        match &self.verdict {
+            WireUnits::Incompatible { from, to } => {
+                format!("{wire} joins incompatible dimensions ({from} vs {to})")
+            }
+            WireUnits::MissingScale(needed) => format!(
+                "{wire} has no unit conversion; it needs factor {} offset {}",
+                needed.factor, needed.offset
+            ),
+            WireUnits::WrongScale(needed) => format!(
+                "{wire} converts with factor {} offset {}; the units need factor {} offset {}",
+                self.scale, self.offset, needed.factor, needed.offset
+            ),
+            WireUnits::Consistent | WireUnits::Unchecked => format!("{wire} is consistent"),
+        }
+    }
+}
+
 /// The live set of unresolved connection targets, refreshed every propagation
 /// tick. Empty when every wire resolves. Read by the API's `GetBrokenConnections`
 /// query (registered in `lunco-usd-sim`, which sees both this crate and the API).
@@ -93,6 +161,10 @@ pub struct CosimDiagnostics {
     /// Topology cycles in the current wiring fabric. These are not missing
     /// ports and therefore never enter [`Self::faults`].
     pub algebraic_loops: Vec<AlgebraicLoopDiagnostic>,
+    /// Bound wires whose declared units their transform does not reconcile.
+    /// Like [`Self::algebraic_loops`] these are not missing ports and never
+    /// enter [`Self::faults`]: the value lands, wrongly scaled.
+    pub unit_mismatches: Vec<WireUnitMismatch>,
     /// Wires that have NEVER successfully written, keyed by `(entity, port)` so a
     /// wire that drops on a thousand ticks is one entry.
     ///
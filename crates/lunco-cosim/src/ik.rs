@@ -0,0 +1,810 @@
+//! Inverse kinematics for USD-authored manipulator joint chains.
+//!
+//! A robotic arm built from `PhysicsRevoluteJoint` / `PhysicsPrismaticJoint`
+//! prims is, at runtime, a chain of avian joints whose `angle` /
+//! `displacement` ports ([`crate::joint`]) accept position targets. This module
+//! turns a **Cartesian** request — "put this link's tip at that pose" — into
+//! those per-joint targets, so a sample-handling or scooping arm is tasked in
+//! task space instead of joint by joint.
+//!
+//! ## Solver
+//!
+//! Damped least squares (Levenberg–Marquardt on the geometric Jacobian):
+//!
+//! ```text
+//! Δq = Jᵀ (J Jᵀ + λ² I)⁻¹ e
+//! ```
+//!
+//! `e` is the position error (3 rows), extended with the orientation error as a
+//! rotation vector (3 more rows) when the target names an orientation. Damping
+//! keeps the step bounded near singularities (a fully stretched arm), where a
+//! plain pseudo-inverse would fling the joints. Each iterate is clamped to the
+//! joint's authored `physics:lowerLimit` / `physics:upperLimit`, so the result is
+//! always a configuration the chain can actually hold.
+//!
+//! The chain is captured ONCE from the live world ([`IkChain::from_samples`]):
+//! every joint frame is stored relative to the previous joint's moving frame at
+//! the current configuration, so forward kinematics for any trial `q` is a pure
+//! product of rigid transforms — no world access, no physics stepping, and the
+//! whole solve is unit-testable.
+//!
+//! ## Realization
+//!
+//! The solve never moves a body. [`ReachTo`] writes each joint's target through
+//! the ordinary [`crate::SetPorts`] command on the joint entity (`angle` or
+//! `displacement`), so the joint motors chase it under the same hold, fence and
+//! diagnostics rules as any other setpoint, and the arm arrives under physics.
+
+use avian3d::prelude::{FixedJoint, Position, PrismaticJoint, RevoluteJoint, Rotation};
+use bevy::math::{DQuat, DVec3};
+use bevy::prelude::*;
+use lunco_core::{on_command, register_commands, Ack, Command, OpId};
+
+use crate::joint::{
+    displacement_along_axis, dquat_to_quat, slider_axis_world_from_rotation, twist_angle,
+    JOINT_ANGLE_PORT, JOINT_DISPLACEMENT_PORT,
+};
+use crate::SetPorts;
+
+/// Longest chain [`chain_to_effector`] will walk before giving up. A real arm
+/// has a handful of joints; a loop in the joint graph (a closed mechanism) must
+/// not spin the walk forever.
+const MAX_CHAIN_JOINTS: usize = 32;
+
+/// The single DOF a chain joint contributes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IkJointKind {
+    /// Rotation (rad) about the joint axis through the joint origin.
+    Revolute,
+    /// Translation (m) along the joint axis.
+    Prismatic,
+}
+
+/// A rigid transform in f64: the joint frames and the effector tip.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IkFrame {
+    /// Origin, in the parent frame.
+    pub position: DVec3,
+    /// Orientation, in the parent frame.
+    pub rotation: DQuat,
+}
+
+impl IkFrame {
+    /// The identity transform.
+    pub const IDENTITY: Self = Self {
+        position: DVec3::ZERO,
+        rotation: DQuat::IDENTITY,
+    };
+
+    /// A frame at `position` with orientation `rotation`.
+    pub fn new(position: DVec3, rotation: DQuat) -> Self {
+        Self { position, rotation }
+    }
+
+    /// `self ∘ child`: `child` expressed in `self`'s parent frame.
+    pub fn compose(&self, child: &IkFrame) -> IkFrame {
+        IkFrame {
+            position: self.position + self.rotation * child.position,
+            rotation: (self.rotation * child.rotation).normalize(),
+        }
+    }
+
+    /// The inverse transform.
+    pub fn inverse(&self) -> IkFrame {
+        let inv = self.rotation.inverse();
+        IkFrame {
+            position: inv * -self.position,
+            rotation: inv,
+        }
+    }
+}
+
+/// One joint of a captured chain.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IkJoint {
+    /// Revolute or prismatic.
+    pub kind: IkJointKind,
+    /// Joint frame relative to the previous joint's MOVING frame (the chain base
+    /// for the first joint), taken at the captured configuration.
+    pub offset: IkFrame,
+    /// Unit motion axis, in the joint frame.
+    pub axis: DVec3,
+    /// Joint value at capture (rad or m). Motion is applied relative to it.
+    pub value: f64,
+    /// Lower limit (rad or m); `-inf` when unlimited.
+    pub lower: f64,
+    /// Upper limit (rad or m); `+inf` when unlimited.
+    pub upper: f64,
+}
+
+/// A world sample of one joint, as read from the live chain. The input to
+/// [`IkChain::from_samples`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IkJointSample {
+    /// Revolute or prismatic.
+    pub kind: IkJointKind,
+    /// World frame of the joint anchor on its PARENT body.
+    pub frame: IkFrame,
+    /// Motion axis in world space.
+    pub axis_world: DVec3,
+    /// Current joint value (rad or m).
+    pub value: f64,
+    /// Lower limit; `-inf` when unlimited.
+    pub lower: f64,
+    /// Upper limit; `+inf` when unlimited.
+    pub upper: f64,
+}
+
+/// A serial chain, base → tip, in the form forward kinematics needs.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IkChain {
+    /// World frame of the first joint's parent side.
+    pub base: IkFrame,
+    /// Joints in base → tip order.
+    pub joints: Vec<IkJoint>,
+    /// Effector tip relative to the last joint's moving frame.
+    pub tip: IkFrame,
+}
+
+impl IkChain {
+    /// Capture a chain from world samples of its joints (base → tip) and the
+    /// effector tip's current world frame. `None` for an empty chain.
+    pub fn from_samples(samples: &[IkJointSample], effector: IkFrame) -> Option<Self> {
+        let first = samples.first()?;
+        let base = first.frame;
+        let mut joints = Vec::with_capacity(samples.len());
+        // At the captured configuration every joint's relative motion is the
+        // identity, so a joint's moving frame coincides with its anchor frame.
+        let mut previous = base;
+        for s in samples {
+            let axis = (s.frame.rotation.inverse() * s.axis_world).normalize_or_zero();
+            if axis == DVec3::ZERO {
+                return None;
+            }
+            joints.push(IkJoint {
+                kind: s.kind,
+                offset: previous.inverse().compose(&s.frame),
+                axis,
+                value: s.value,
+                lower: s.lower,
+                upper: s.upper,
+            });
+            previous = s.frame;
+        }
+        Some(Self {
+            base,
+            joints,
+            tip: previous.inverse().compose(&effector),
+        })
+    }
+
+    /// Current joint values, base → tip.
+    pub fn values(&self) -> Vec<f64> {
+        self.joints.iter().map(|j| j.value).collect()
+    }
+
+    /// Forward kinematics: the world frame of every joint anchor at `q` plus the
+    /// effector tip. `frames[i]` is joint `i`'s anchor (before its own motion).
+    fn forward(&self, q: &[f64]) -> (Vec<IkFrame>, IkFrame) {
+        let mut frames = Vec::with_capacity(self.joints.len());
+        let mut moving = self.base;
+        for (i, joint) in self.joints.iter().enumerate() {
+            // The first joint's offset is the identity relative to `base`.
+            let anchor = moving.compose(&joint.offset);
+            frames.push(anchor);
+            let delta = q[i] - joint.value;
+            let motion = match joint.kind {
+                IkJointKind::Revolute => {
+                    IkFrame::new(DVec3::ZERO, DQuat::from_axis_angle(joint.axis, delta))
+                }
+                IkJointKind::Prismatic => IkFrame::new(joint.axis * delta, DQuat::IDENTITY),
+            };
+            moving = anchor.compose(&motion);
+        }
+        (frames, moving.compose(&self.tip))
+    }
+
+    /// The effector's world frame at joint values `q`.
+    pub fn effector_at(&self, q: &[f64]) -> IkFrame {
+        self.forward(q).1
+    }
+}
+
+/// What the effector should reach.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IkTarget {
+    /// Goal position of the effector tip (world).
+    pub position: DVec3,
+    /// Goal orientation, or `None` to solve for position only — the usual case
+    /// for a scoop or probe, where a 3-DOF arm cannot also dictate attitude.
+    pub orientation: Option<DQuat>,
+}
+
+/// Solver tuning.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IkParams {
+    /// Damping λ (m). Larger is steadier near singularities and slower to converge.
+    pub damping: f64,
+    /// Iteration cap.
+    pub max_iterations: usize,
+    /// Position error (m) that counts as reached.
+    pub position_tolerance: f64,
+    /// Orientation error (rad) that counts as reached.
+    pub orientation_tolerance: f64,
+    /// Metres of position error one radian of orientation error is worth. Mixes
+    /// the two into one least-squares problem.
+    pub orientation_weight: f64,
+    /// Largest error norm fed to a single iteration. Clamping the error (not the
+    /// step) keeps the linearisation honest when the target is far away.
+    pub max_error_step: f64,
+}
+
+impl Default for IkParams {
+    fn default() -> Self {
+        Self {
+            damping: 0.05,
+            max_iterations: 200,
+            position_tolerance: 1.0e-3,
+            orientation_tolerance: 1.0e-2,
+            orientation_weight: 0.5,
+            max_error_step: 0.2,
+        }
+    }
+}
+
+/// Result of [`solve_dls`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct IkSolution {
+    /// Joint targets, base → tip, within the authored limits.
+    pub values: Vec<f64>,
+    /// Remaining position error (m) at `values`.
+    pub position_error: f64,
+    /// Remaining orientation error (rad) at `values`; `0` for position-only.
+    pub orientation_error: f64,
+    /// Whether both errors are within tolerance. An unreachable target still
+    /// returns the closest configuration found, with this `false`.
+    pub converged: bool,
+    /// Iterations spent.
+    pub iterations: usize,
+}
+
+/// Rotation vector (axis × angle, rad) taking `from` to `to`, shortest way.
+fn rotation_error(from: DQuat, to: DQuat) -> DVec3 {
+    let mut d = (to * from.inverse()).normalize();
+    if d.w < 0.0 {
+        d = -d;
+    }
+    let (axis, angle) = d.to_axis_angle();
+    if angle.is_finite() && axis.is_finite() {
+        axis * angle
+    } else {
+        DVec3::ZERO
+    }
+}
+
+/// Solve `a x = b` for a small dense square system (row-major `n × n`) by
+/// Gaussian elimination with partial pivoting. `None` if singular. `J Jᵀ + λ²I`
+/// is symmetric positive definite for `λ > 0`, so this only fails on NaN input.
+fn solve_linear(mut a: Vec<f64>, mut b: Vec<f64>, n: usize) -> Option<Vec<f64>> {
+    for col in 0..n {
+        let pivot = (col..n).max_by(|&i, &j| a[i * n + col].abs().total_cmp(&a[j * n + col].abs()))?;
+        if a[pivot * n + col].abs() < 1.0e-14 {
+            return None;
+        }
+        if pivot != col {
+            for k in 0..n {
+                a.swap(pivot * n + k, col * n + k);
+            }
+            b.swap(pivot, col);
+        }
+        for row in col + 1..n {
+            let f = a[row * n + col] / a[col * n + col];
+            for k in col..n {
+                a[row * n + k] -= f * a[col * n + k];
+            }
+            b[row] -= f * b[col];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let s: f64 = (row + 1..n).map(|k| a[row * n + k] * x[k]).sum();
+        x[row] = (b[row] - s) / a[row * n + row];
+    }
+    Some(x)
+}
+
+/// Damped-least-squares IK over `chain` toward `target`.
+///
+/// Starts from the captured configuration (so a small re-target makes a small
+/// move), and returns the best configuration found even when the target is out
+/// of reach — the arm then points at it as closely as its limits allow.
+///
+/// The damping is adaptive in the Levenberg–Marquardt sense: a trial step that
+/// does not reduce the weighted error is rejected and retried with heavier
+/// damping, and an accepted step relaxes it back toward
+/// [`IkParams::damping`]. A fixed small λ oscillates at the stretched-arm
+/// singularity — exactly where an out-of-reach target parks the chain.
+pub fn solve_dls(chain: &IkChain, target: &IkTarget, params: &IkParams) -> IkSolution {
+    let n = chain.joints.len();
+    let mut q = chain.values();
+    for (v, j) in q.iter_mut().zip(&chain.joints) {
+        *v = v.clamp(j.lower, j.upper);
+    }
+    let rows = if target.orientation.is_some() { 6 } else { 3 };
+    let w = params.orientation_weight;
+    let mut lambda = params.damping.max(1.0e-6);
+
+    let errors = |effector: &IkFrame| -> (DVec3, DVec3) {
+        let ep = target.position - effector.position;
+        let eo = target
+            .orientation
+            .map_or(DVec3::ZERO, |goal| rotation_error(effector.rotation, goal));
+        (ep, eo)
+    };
+    let cost = |(ep, eo): (DVec3, DVec3)| ep.length_squared() + (eo * w).length_squared();
+
+    let (mut frames, mut effector) = chain.forward(&q);
+    let mut current = errors(&effector);
+    let mut iterations = 0;
+    loop {
+        let (ep, eo) = current;
+        let reached = ep.length() <= params.position_tolerance
+            && eo.length() <= params.orientation_tolerance;
+        // A damping factor this large means no direction reduces the error any
+        // more: the chain is at the closest configuration it can reach.
+        let stalled = lambda > 1.0e6;
+        if reached || stalled || iterations >= params.max_iterations || n == 0 {
+            return IkSolution {
+                values: q,
+                position_error: ep.length(),
+                orientation_error: eo.length(),
+                converged: reached,
+                iterations,
+            };
+        }
+        iterations += 1;
+
+        // Error vector (orientation rows weighted into metres), clamped.
+        let mut e: Vec<f64> = ep.to_array().to_vec();
+        if rows == 6 {
+            e.extend((eo * w).to_array());
+        }
+        let norm = e.iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > params.max_error_step {
+            let s = params.max_error_step / norm;
+            e.iter_mut().for_each(|x| *x *= s);
+        }
+
+        // Geometric Jacobian, column per joint (rows × n, column-major).
+        let mut jac = vec![0.0; rows * n];
+        for (i, joint) in chain.joints.iter().enumerate() {
+            let axis = frames[i].rotation * joint.axis;
+            let (lin, ang) = match joint.kind {
+                IkJointKind::Revolute => (axis.cross(effector.position - frames[i].position), axis),
+                IkJointKind::Prismatic => (axis, DVec3::ZERO),
+            };
+            jac[i * rows..i * rows + 3].copy_from_slice(&lin.to_array());
+            if rows == 6 {
+                jac[i * rows + 3..i * rows + 6].copy_from_slice(&(ang * w).to_array());
+            }
+        }
+
+        // (J Jᵀ + λ² I) y = e, then Δq = Jᵀ y.
+        let mut jjt = vec![0.0; rows * rows];
+        for r in 0..rows {
+            for c in 0..rows {
+                jjt[r * rows + c] = (0..n).map(|k| jac[k * rows + r] * jac[k * rows + c]).sum();
+            }
+            jjt[r * rows + r] += lambda * lambda;
+        }
+        let Some(y) = solve_linear(jjt, e, rows) else {
+            lambda *= 4.0;
+            continue;
+        };
+        let trial: Vec<f64> = chain
+            .joints
+            .iter()
+            .enumerate()
+            .map(|(i, joint)| {
+                let dq: f64 = (0..rows).map(|r| jac[i * rows + r] * y[r]).sum();
+                (q[i] + dq).clamp(joint.lower, joint.upper)
+            })
+            .collect();
+        let (trial_frames, trial_effector) = chain.forward(&trial);
+        let trial_errors = errors(&trial_effector);
+        if cost(trial_errors) < cost(current) {
+            q = trial;
+            frames = trial_frames;
+            effector = trial_effector;
+            current = trial_errors;
+            lambda = (lambda * 0.5).max(params.damping);
+        } else {
+            lambda *= 4.0;
+        }
+    }
+}
+
+/// One movable joint found by [`chain_to_effector`], base → tip.
+#[derive(Clone, Copy, Debug)]
+pub struct ChainJoint {
+    /// The joint entity — the one carrying the `angle`/`displacement` port.
+    pub entity: Entity,
+    /// The world sample it contributes to the chain.
+    pub sample: IkJointSample,
+}
+
+/// Walk from `effector_body` up the joint graph to the chain base and sample
+/// each movable joint.
+///
+/// Avian's `body2` is the driven (child) body — the USD loader builds
+/// `new(body0, body1)` — so the walk repeatedly finds the joint whose `body2`
+/// is the current body and steps to its `body1`. Fixed joints are crossed
+/// without contributing a DOF (a bolted wrist plate is part of the link), and
+/// the walk stops at `base` when given, or where no joint drives the body.
+pub fn chain_to_effector(
+    effector_body: Entity,
+    base: Option<Entity>,
+    joints: &Query<(
+        Entity,
+        Option<&RevoluteJoint>,
+        Option<&PrismaticJoint>,
+        Option<&FixedJoint>,
+    )>,
+    bodies: &Query<(&Position, &Rotation)>,
+) -> Vec<ChainJoint> {
+    let mut chain = Vec::new();
+    let mut current = effector_body;
+    for _ in 0..MAX_CHAIN_JOINTS {
+        if Some(current) == base {
+            break;
+        }
+        let parent = joints.iter().find_map(|(entity, revolute, prismatic, fixed)| {
+            if let Some(j) = revolute.filter(|j| j.body2 == current) {
+                return Some((entity, j.body1, sample_revolute(j, bodies)));
+            }
+            if let Some(j) = prismatic.filter(|j| j.body2 == current) {
+                return Some((entity, j.body1, sample_prismatic(j, bodies)));
+            }
+            fixed
+                .filter(|j| j.body2 == current)
+                .map(|j| (entity, j.body1, None))
+        });
+        let Some((entity, parent_body, sample)) = parent else {
+            break;
+        };
+        if let Some(sample) = sample {
+            chain.push(ChainJoint { entity, sample });
+        }
+        current = parent_body;
+    }
+    chain.reverse();
+    chain
+}
+
+/// Lower/upper bounds from an optional limit pair, unlimited when absent.
+fn limits(bounds: Option<(f64, f64)>) -> (f64, f64) {
+    bounds.map_or((f64::NEG_INFINITY, f64::INFINITY), |(lo, hi)| {
+        (lo.min(hi), hi.max(lo))
+    })
+}
+
+fn sample_revolute(
+    j: &RevoluteJoint,
+    bodies: &Query<(&Position, &Rotation)>,
+) -> Option<IkJointSample> {
+    let (p1, r1) = bodies.get(j.body1).ok()?;
+    let (_, r2) = bodies.get(j.body2).ok()?;
+    // Same convention as the `angle` port's measured read: the hinge axis is
+    // body1-local, and the value is body2's twist about it.
+    let axis = j.hinge_axis;
+    let value = twist_angle(dquat_to_quat(r1.0), dquat_to_quat(r2.0), axis.as_vec3()) as f64;
+    let (lower, upper) = limits(j.angle_limit.map(|l| (l.min, l.max)));
+    Some(IkJointSample {
+        kind: IkJointKind::Revolute,
+        frame: IkFrame::new(
+            p1.0 + r1.0 * j.local_anchor1().unwrap_or(DVec3::ZERO),
+            r1.0,
+        ),
+        axis_world: r1.0 * axis,
+        value,
+        lower,
+        upper,
+    })
+}
+
+fn sample_prismatic(
+    j: &PrismaticJoint,
+    bodies: &Query<(&Position, &Rotation)>,
+) -> Option<IkJointSample> {
+    let (p1, r1) = bodies.get(j.body1).ok()?;
+    let (p2, r2) = bodies.get(j.body2).ok()?;
+    let anchor1 = j.local_anchor1().unwrap_or(DVec3::ZERO);
+    let axis_world = slider_axis_world_from_rotation(r1.0, j);
+    let value = displacement_along_axis(
+        p1.0,
+        r1.0,
+        anchor1,
+        p2.0,
+        r2.0,
+        j.local_anchor2().unwrap_or(DVec3::ZERO),
+        axis_world,
+    );
+    let (lower, upper) = limits(j.free_axis_limits.map(|l| (l.min, l.max)));
+    Some(IkJointSample {
+        kind: IkJointKind::Prismatic,
+        frame: IkFrame::new(p1.0 + r1.0 * anchor1, r1.0),
+        axis_world,
+        value,
+        lower,
+        upper,
+    })
+}
+
+/// Reach a Cartesian pose with a manipulator: solve inverse kinematics over the
+/// joint chain ending at `effector` and write every joint's target through
+/// `SetPorts` (`angle` / `displacement`). The arm then moves under its joint
+/// motors; nothing is teleported.
+///
+/// `position` (and the optional `orientation`, a quaternion `[x, y, z, w]`) are
+/// in the active physics frame — the same frame `world_pos` reads and
+/// `set_world_pos` writes. The Ack carries the solved `joints` (`[{entity,
+/// port, value}]`), the residual errors, and `converged`; an unreachable target
+/// still drives the arm to its closest configuration and reports
+/// `converged: false`.
+#[Command(reflect_default)]
+pub struct ReachTo {
+    /// The end-effector LINK — the rigid body at the tip of the chain.
+    #[authz_target]
+    pub effector: Entity,
+    /// Effector-tip offset in the link's local frame (m), e.g. the scoop lip
+    /// relative to the wrist body. `[0, 0, 0]` reaches with the body origin.
+    #[serde(default)]
+    #[reflect(default)]
+    pub tip_offset: [f64; 3],
+    /// Goal position `[x, y, z]`.
+    pub position: [f64; 3],
+    /// Goal orientation `[x, y, z, w]`; empty for position-only.
+    #[serde(default)]
+    #[reflect(default)]
+    pub orientation: Vec<f64>,
+    /// Optional chain base body. The walk stops there instead of at the first
+    /// undriven body — use it to exclude a mast or turret from the solve.
+    #[serde(default)]
+    #[reflect(default)]
+    pub base: Option<Entity>,
+}
+
+impl Default for ReachTo {
+    fn default() -> Self {
+        Self {
+            effector: Entity::PLACEHOLDER,
+            tip_offset: [0.0; 3],
+            position: [0.0; 3],
+            orientation: Vec::new(),
+            base: None,
+        }
+    }
+}
+
+#[on_command(ReachTo)]
+fn on_reach_to(
+    _t: On<ReachTo>,
+    joints: Query<(
+        Entity,
+        Option<&RevoluteJoint>,
+        Option<&PrismaticJoint>,
+        Option<&FixedJoint>,
+    )>,
+    bodies: Query<(&Position, &Rotation)>,
+    mut commands: Commands,
+) -> Result<Ack, String> {
+    let orientation = match cmd.orientation.as_slice() {
+        [] => None,
+        [x, y, z, w] => {
+            let q = DQuat::from_xyzw(*x, *y, *z, *w);
+            if !q.is_finite() || q.length_squared() < 1.0e-12 {
+                return Err("ReachTo: degenerate orientation quaternion".into());
+            }
+            Some(q.normalize())
+        }
+        other => {
+            return Err(format!(
+                "ReachTo: orientation must be [x, y, z, w] or empty, got {} values",
+                other.len()
+            ))
+        }
+    };
+    let position = DVec3::from_array(cmd.position);
+    if !position.is_finite() {
+        return Err("ReachTo: non-finite target position".into());
+    }
+    let Ok((p, r)) = bodies.get(cmd.effector) else {
+        return Err(format!(
+            "ReachTo: effector {:?} is not a simulated body",
+            cmd.effector
+        ));
+    };
+    let effector = IkFrame::new(p.0 + r.0 * DVec3::from_array(cmd.tip_offset), r.0);
+
+    let found = chain_to_effector(cmd.effector, cmd.base, &joints, &bodies);
+    let samples: Vec<IkJointSample> = found.iter().map(|j| j.sample).collect();
+    let Some(chain) = IkChain::from_samples(&samples, effector) else {
+        return Err(format!(
+            "ReachTo: no revolute/prismatic joint chain drives {:?}",
+            cmd.effector
+        ));
+    };
+    let solution = solve_dls(
+        &chain,
+        &IkTarget {
+            position,
+            orientation,
+        },
+        &IkParams::default(),
+    );
+
+    let mut solved = Vec::with_capacity(found.len());
+    for (joint, value) in found.iter().zip(&solution.values) {
+        let port = match joint.sample.kind {
+            IkJointKind::Revolute => JOINT_ANGLE_PORT,
+            IkJointKind::Prismatic => JOINT_DISPLACEMENT_PORT,
+        };
+        commands.trigger(SetPorts {
+            target: joint.entity,
+            writes: vec![(port.to_string(), *value)],
+            seq: 0,
+            tick: 0,
+        });
+        solved.push(serde_json::json!({
+            "entity": joint.entity.to_bits(),
+            "port": port,
+            "value": value,
+        }));
+    }
+    if !solution.converged {
+        warn!(
+            "[ik] ReachTo {:?}: target not reached ({:.3} m, {:.3} rad residual) — \
+             driving to the closest reachable configuration",
+            cmd.effector, solution.position_error, solution.orientation_error
+        );
+    }
+    let mut ack = Ack::new(OpId::new());
+    ack.assigned = serde_json::json!({
+        "joints": solved,
+        "converged": solution.converged,
+        "position_error": solution.position_error,
+        "orientation_error": solution.orientation_error,
+        "iterations": solution.iterations,
+    });
+    Ok(ack)
+}
+
+register_commands!(on_reach_to);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A planar two-link arm in the XY plane: shoulder at the origin, elbow
+    /// `l1` out along +X, tip `l2` further. Both hinges about +Z.
+    fn planar_arm(l1: f64, l2: f64, limit: Option<(f64, f64)>) -> IkChain {
+        let (lower, upper) = limits(limit);
+        let sample = |x: f64| IkJointSample {
+            kind: IkJointKind::Revolute,
+            frame: IkFrame::new(DVec3::new(x, 0.0, 0.0), DQuat::IDENTITY),
+            axis_world: DVec3::Z,
+            value: 0.0,
+            lower,
+            upper,
+        };
+        IkChain::from_samples(
+            &[sample(0.0), sample(l1)],
+            IkFrame::new(DVec3::new(l1 + l2, 0.0, 0.0), DQuat::IDENTITY),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn forward_kinematics_reproduces_the_captured_pose() {
+        let arm = planar_arm(1.0, 0.5, None);
+        let tip = arm.effector_at(&arm.values());
+        assert!((tip.position - DVec3::new(1.5, 0.0, 0.0)).length() < 1e-12);
+        // A quarter turn at the shoulder swings the whole arm onto +Y.
+        let tip = arm.effector_at(&[std::f64::consts::FRAC_PI_2, 0.0]);
+        assert!((tip.position - DVec3::new(0.0, 1.5, 0.0)).length() < 1e-9);
+    }
+
+    #[test]
+    fn reaches_a_point_inside_the_workspace() {
+        let arm = planar_arm(1.0, 1.0, None);
+        let target = IkTarget {
+            position: DVec3::new(1.0, 1.0, 0.0),
+            orientation: None,
+        };
+        let s = solve_dls(&arm, &target, &IkParams::default());
+        assert!(s.converged, "did not converge: {s:?}");
+        let tip = arm.effector_at(&s.values).position;
+        assert!((tip - target.position).length() < 2e-3, "tip {tip}");
+    }
+
+    #[test]
+    fn unreachable_target_stretches_toward_it_without_converging() {
+        let arm = planar_arm(1.0, 1.0, None);
+        let target = IkTarget {
+            position: DVec3::new(0.0, 5.0, 0.0),
+            orientation: None,
+        };
+        let s = solve_dls(&arm, &target, &IkParams::default());
+        assert!(!s.converged);
+        let tip = arm.effector_at(&s.values).position;
+        // Fully stretched along +Y: 2 m reach, 3 m short.
+        assert!((s.position_error - 3.0).abs() < 0.05, "{s:?}");
+        assert!(tip.y > 1.9, "tip {tip}");
+    }
+
+    #[test]
+    fn solution_respects_joint_limits() {
+        let arm = planar_arm(1.0, 1.0, Some((-0.3, 0.3)));
+        let target = IkTarget {
+            position: DVec3::new(-1.0, 1.0, 0.0),
+            orientation: None,
+        };
+        let s = solve_dls(&arm, &target, &IkParams::default());
+        assert!(s.values.iter().all(|q| (-0.3..=0.3).contains(q)), "{s:?}");
+        assert!(!s.converged);
+    }
+
+    #[test]
+    fn prismatic_joint_extends_along_its_axis() {
+        let chain = IkChain::from_samples(
+            &[IkJointSample {
+                kind: IkJointKind::Prismatic,
+                frame: IkFrame::IDENTITY,
+                axis_world: DVec3::Y,
+                value: 0.1,
+                lower: 0.0,
+                upper: 1.0,
+            }],
+            IkFrame::new(DVec3::new(0.0, 0.1, 0.0), DQuat::IDENTITY),
+        )
+        .unwrap();
+        let s = solve_dls(
+            &chain,
+            &IkTarget {
+                position: DVec3::new(0.0, 0.6, 0.0),
+                orientation: None,
+            },
+            &IkParams::default(),
+        );
+        assert!(s.converged, "{s:?}");
+        assert!((s.values[0] - 0.6).abs() < 2e-3, "{s:?}");
+    }
+
+    #[test]
+    fn orientation_target_fixes_the_elbow_branch() {
+        // A 3-joint planar arm has a redundant DOF for position; asking for the
+        // tip to point along +Y pins it.
+        let sample = |x: f64| IkJointSample {
+            kind: IkJointKind::Revolute,
+            frame: IkFrame::new(DVec3::new(x, 0.0, 0.0), DQuat::IDENTITY),
+            axis_world: DVec3::Z,
+            value: 0.0,
+            lower: f64::NEG_INFINITY,
+            upper: f64::INFINITY,
+        };
+        let arm = IkChain::from_samples(
+            &[sample(0.0), sample(1.0), sample(2.0)],
+            IkFrame::new(DVec3::new(2.5, 0.0, 0.0), DQuat::IDENTITY),
+        )
+        .unwrap();
+        let goal = DQuat::from_rotation_z(std::f64::consts::FRAC_PI_2);
+        let target = IkTarget {
+            position: DVec3::new(1.5, 1.0, 0.0),
+            orientation: Some(goal),
+        };
+        let s = solve_dls(&arm, &target, &IkParams::default());
+        assert!(s.converged, "{s:?}");
+        let tip = arm.effector_at(&s.values);
+        assert!(rotation_error(tip.rotation, goal).length() < 2e-2);
+    }
+}
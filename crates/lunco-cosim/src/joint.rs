@@ -341,6 +341,7 @@ pub const REVOLUTE_JOINT_GROUP: AvianGroup = AvianGroup {
             write: Some(write_motor_angle),
         },
     ],
+    units: &[(JOINT_ANGLE_PORT, "rad")],
 };
 
 /// Measured angle (`Out`): the twist of `body2`'s orientation relative to
@@ -418,6 +419,11 @@ pub const PRISMATIC_JOINT_GROUP: AvianGroup = AvianGroup {
         PRISMATIC_STATE_PORTS[1],
         PRISMATIC_STATE_PORTS[2],
     ],
+    units: &[
+        (JOINT_DISPLACEMENT_PORT, "m"),
+        (JOINT_VELOCITY_PORT, "m/s"),
+        (JOINT_FORCE_PORT, "N"),
+    ],
 };
 
 /// Measured displacement (`Out`): the signed offset (m) of `body2` relative to
@@ -456,7 +462,7 @@ fn slider_axis_world(world: &World, j: &PrismaticJoint) -> Option<DVec3> {
 /// Every public state port uses this conversion. In particular, a joint axis
 /// must never be projected in an unrotated frame: doing so changes the measured
 /// displacement as the whole vehicle yaws.
-fn slider_axis_world_from_rotation(rotation1: DQuat, j: &PrismaticJoint) -> DVec3 {
+pub(crate) fn slider_axis_world_from_rotation(rotation1: DQuat, j: &PrismaticJoint) -> DVec3 {
     rotation1 * j.local_slider_axis1().unwrap_or(j.slider_axis)
 }
 
@@ -601,7 +607,7 @@ fn write_motor_displacement(world: &mut World, entity: Entity, value: f64) -> bo
 /// from the two anchors' world positions. Pure (no `World`) so it is
 /// unit-testable; shared convention with the motor's `target_position` (zero
 /// when the anchors coincide along the axis).
-fn displacement_along_axis(
+pub(crate) fn displacement_along_axis(
     p1: DVec3,
     r1: DQuat,
     anchor1: DVec3,
@@ -640,13 +646,13 @@ pub fn joint_angle_holder(world: &mut World, root: Entity) -> Option<Entity> {
 /// component-wise for the twist computation. Field-wise conversion avoids
 /// depending on a specific glam helper name across versions.
 #[inline]
-fn dquat_to_quat(q: bevy::math::DQuat) -> Quat {
+pub(crate) fn dquat_to_quat(q: bevy::math::DQuat) -> Quat {
     Quat::from_xyzw(q.x as f32, q.y as f32, q.z as f32, q.w as f32)
 }
 
 /// Signed twist angle (rad, normalized to `(-π, π]`) of `q2` relative to `q1`
 /// about `axis` (swing-twist decomposition).
-fn twist_angle(q1: Quat, q2: Quat, axis: Vec3) -> f32 {
+pub(crate) fn twist_angle(q1: Quat, q2: Quat, axis: Vec3) -> f32 {
     let axis = axis.normalize_or_zero();
     if axis == Vec3::ZERO {
         return 0.0;
@@ -45,6 +45,7 @@ pub mod binding;
 pub mod component;
 pub mod connection;
 pub mod diagnostics;
+pub mod ik;
 pub mod joint;
 pub mod ports;
 pub mod suggestion;
@@ -54,7 +55,10 @@ pub use avian::*;
 pub use binding::*;
 pub use component::*;
 pub use connection::*;
-pub use diagnostics::{AlgebraicLoopDiagnostic, BrokenConnection, CosimDiagnostics};
+pub use diagnostics::{
+    AlgebraicLoopDiagnostic, BrokenConnection, CosimDiagnostics, WireUnitMismatch,
+};
+pub use ik::ReachTo;
 pub use joint::*;
 pub use ports::*;
 pub use suggestion::*;
@@ -227,6 +231,15 @@ impl Plugin for CoSimPlugin {
             PostUpdate,
             binding::bind_connections.run_if(binding::binding_requested),
         );
+        // Unit consistency of the wires that just bound or unbound — or of every
+        // wire, when an endpoint's declared units change. Edge-triggered, never
+        // per tick.
+        app.add_systems(
+            PostUpdate,
+            systems::wire_units::check_wire_units
+                .run_if(systems::wire_units::wire_units_changed)
+                .after(binding::bind_connections),
+        );
         {
             let mut registry = app
                 .world_mut()
@@ -378,6 +391,9 @@ impl Plugin for CoSimPlugin {
         // Register the typed command observers generated below (the
         // `register_commands!` list turns into `register_all_commands(app)`).
         register_all_commands(app);
+        // Cartesian manipulator tasking (`ReachTo`) — solves over the joint
+        // chain and lands as `SetPorts` on the joint ports above.
+        ik::register_all_commands(app);
     }
 }
 
@@ -59,6 +59,26 @@ pub struct AvianGroup {
     pub present: fn(&World, Entity) -> bool,
     /// The ports this kind exposes.
     pub ports: &'static [AvianPort],
+    /// `(port, unit)` for the ports that carry a physical quantity, in Modelica
+    /// unit syntax. What [`crate::systems::wire_units`] checks a wire's ends
+    /// against; a port absent here (a 0/1 flag, a quaternion component) declares
+    /// no unit and is never checked.
+    pub units: &'static [(&'static str, &'static str)],
+}
+
+/// The unit an avian port on `entity` declares, from the first present group
+/// that lists it — the same precedence port resolution uses.
+pub(crate) fn avian_port_unit(world: &World, entity: Entity, port: &str) -> Option<&'static str> {
+    AVIAN
+        .iter()
+        .filter(|group| (group.present)(world, entity))
+        .find_map(|group| {
+            group
+                .units
+                .iter()
+                .find(|(name, _)| *name == port)
+                .map(|(_, unit)| *unit)
+        })
 }
 
 /// The avian backend table: every avian kind we expose, in one place.
@@ -387,4 +407,23 @@ mod tests {
             .iter()
             .any(|port| port.name == "piloted"));
     }
+
+    /// Tripwire: a unit declared for a port the group does not expose is a
+    /// rename that left the unit table behind, and a unit that does not parse
+    /// makes every wire to that port silently unchecked.
+    #[test]
+    fn every_declared_avian_unit_names_a_port_and_parses() {
+        for group in AVIAN {
+            for (port, unit) in group.units {
+                assert!(
+                    group.ports.iter().any(|p| p.name == *port),
+                    "unit declared for `{port}`, which its group does not expose"
+                );
+                assert!(
+                    lunco_core::units::Unit::parse(unit).is_ok(),
+                    "`{port}` declares unparseable unit `{unit}`"
+                );
+            }
+        }
+    }
 }
@@ -74,6 +74,42 @@ pub const EARTH_MOUNT_X_CONNECTOR: &str = "earth_mount_x";
 pub const EARTH_MOUNT_Y_CONNECTOR: &str = "earth_mount_y";
 pub const EARTH_MOUNT_Z_CONNECTOR: &str = "earth_mount_z";
 
+/// SimComponent output connectors carrying the radiative environment seen by
+/// the probe's mount `+Y` face, in W/m² incident on that face: direct sunlight
+/// (distance-scaled and horizon-shadowed), reflected sunlight (regolith plus
+/// Earth albedo) and regolith thermal IR.  The face also sees deep space over
+/// [`SPACE_VIEW_FACTOR_CONNECTOR`] of its hemisphere at
+/// [`SINK_TEMPERATURE_CONNECTOR`] kelvin.  A thermal model applies its own
+/// absorptivity/emissivity; the environment publishes incident flux only.
+pub const SOLAR_FLUX_CONNECTOR: &str = "solar_flux";
+pub const ALBEDO_FLUX_CONNECTOR: &str = "albedo_flux";
+pub const PLANET_IR_FLUX_CONNECTOR: &str = "planet_ir_flux";
+pub const SPACE_VIEW_FACTOR_CONNECTOR: &str = "space_view_factor";
+pub const SINK_TEMPERATURE_CONNECTOR: &str = "sink_temperature";
+/// Modelled regolith surface temperature under the probe (K) — the source
+/// temperature behind [`PLANET_IR_FLUX_CONNECTOR`].
+pub const SURFACE_TEMPERATURE_CONNECTOR: &str = "surface_temperature";
+
+/// SimComponent output connectors carrying the sunlight reaching a probe's solar
+/// array: the normal-incidence irradiance over the whole face in W/m²
+/// (distance-scaled, and shaded by terrain and nearby structure), and the lit
+/// fraction of the face, 0..1.  The panel model applies its own cosine from
+/// [`SUN_MOUNT_X_CONNECTOR`] and siblings.
+pub const SOLAR_IRRADIANCE_CONNECTOR: &str = "solar_irradiance";
+pub const ILLUMINATED_FRACTION_CONNECTOR: &str = "illuminated_fraction";
+
+/// SimComponent output connectors carrying the particle radiation environment
+/// at the probe, after body shadowing and the probe's authored shielding:
+/// absorbed dose rates in Gy/s (GCR, SPE and their sum), the dose-equivalent
+/// rate in Sv/s, and the dose and dose equivalent accumulated since the probe
+/// appeared (Gy, Sv).
+pub const GCR_DOSE_RATE_CONNECTOR: &str = "gcr_dose_rate";
+pub const SPE_DOSE_RATE_CONNECTOR: &str = "spe_dose_rate";
+pub const DOSE_RATE_CONNECTOR: &str = "dose_rate";
+pub const DOSE_EQUIVALENT_RATE_CONNECTOR: &str = "dose_equivalent_rate";
+pub const ACCUMULATED_DOSE_CONNECTOR: &str = "accumulated_dose";
+pub const ACCUMULATED_DOSE_EQUIVALENT_CONNECTOR: &str = "accumulated_dose_equivalent";
+
 /// The complete output contract of a `LunCoEnvironmentProbeAPI` source prim.
 ///
 /// These are schema-declared properties, so they are not necessarily present in
@@ -91,6 +127,20 @@ pub const ENVIRONMENT_PROBE_OUTPUTS: &[&str] = &[
     EARTH_MOUNT_X_CONNECTOR,
     EARTH_MOUNT_Y_CONNECTOR,
     EARTH_MOUNT_Z_CONNECTOR,
+    SOLAR_FLUX_CONNECTOR,
+    ALBEDO_FLUX_CONNECTOR,
+    PLANET_IR_FLUX_CONNECTOR,
+    SPACE_VIEW_FACTOR_CONNECTOR,
+    SINK_TEMPERATURE_CONNECTOR,
+    SURFACE_TEMPERATURE_CONNECTOR,
+    SOLAR_IRRADIANCE_CONNECTOR,
+    ILLUMINATED_FRACTION_CONNECTOR,
+    GCR_DOSE_RATE_CONNECTOR,
+    SPE_DOSE_RATE_CONNECTOR,
+    DOSE_RATE_CONNECTOR,
+    DOSE_EQUIVALENT_RATE_CONNECTOR,
+    ACCUMULATED_DOSE_CONNECTOR,
+    ACCUMULATED_DOSE_EQUIVALENT_CONNECTOR,
 ];
 
 /// Prefix of the SimComponent **output** connectors `lunco-celestial`'s link bridge
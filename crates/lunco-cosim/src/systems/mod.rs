@@ -3,3 +3,4 @@
 pub mod apply_forces;
 pub mod collider;
 pub mod propagate;
+pub mod wire_units;
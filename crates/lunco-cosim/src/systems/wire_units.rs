@@ -0,0 +1,216 @@
+//! Unit check on bound wires — does each wire's SSP `factor`/`offset` carry its
+//! source's unit to its target's?
+//!
+//! A [`SimConnection`] says how to transform a value and nothing about what the
+//! value IS. Both ends usually do: a Modelica variable declares `unit = "rpm"`
+//! ([`PortUnits`], inserted by whoever loads the model), an avian port is SI by
+//! construction ([`crate::ports::AvianGroup::units`]). This system puts the three
+//! side by side with [`lunco_core::units::check_wire`] and records every wire
+//! that joins incompatible dimensions, or joins one dimension at two scales
+//! without the factor between them, in [`CosimDiagnostics::unit_mismatches`].
+//!
+//! It runs when a wire binds or unbinds, or a unit declaration changes — never
+//! per tick; an unbound wire's mismatch leaves the list with it. A wire whose
+//! ends declare nothing is unchecked, not assumed fine: the check only ever
+//! speaks about units somebody wrote down.
+
+use bevy::prelude::*;
+use lunco_core::units::{check_wire, WireUnits};
+use lunco_core::GlobalEntityId;
+
+use crate::binding::BoundConnection;
+use crate::diagnostics::WireUnitMismatch;
+use crate::{CosimDiagnostics, PortUnits, SimConnection};
+
+/// Run condition: a wire newly bound or unbound (despawned included), or a
+/// port's declared units changed.
+pub fn wire_units_changed(
+    bound: Query<(), Added<BoundConnection>>,
+    mut unbound: RemovedComponents<BoundConnection>,
+    units: Query<(), Changed<PortUnits>>,
+) -> bool {
+    // Drained, not peeked: one unbinding runs the check once.
+    let unbound = unbound.read().count() > 0;
+    unbound || !bound.is_empty() || !units.is_empty()
+}
+
+/// The unit `port` on `entity` declares: an explicit [`PortUnits`] entry first
+/// (a model's own declaration), then the avian table.
+fn port_unit(world: &World, entity: Entity, port: &str) -> Option<String> {
+    if let Some(unit) = world
+        .get::<PortUnits>(entity)
+        .and_then(|units| units.units.get(port))
+    {
+        return Some(unit.clone());
+    }
+    crate::ports::avian_port_unit(world, entity, port).map(str::to_string)
+}
+
+/// Rebuild [`CosimDiagnostics::unit_mismatches`] from every bound wire, logging
+/// each mismatch once per scene.
+pub fn check_wire_units(world: &mut World) {
+    let mut wires = world.query_filtered::<(Entity, &SimConnection), With<BoundConnection>>();
+    let mut found = Vec::new();
+    for (connection, spec) in wires.iter(world) {
+        let (Some(source_unit), Some(target_unit)) = (
+            port_unit(world, spec.start_element, &spec.start_connector),
+            port_unit(world, spec.end_element, &spec.end_connector),
+        ) else {
+            continue;
+        };
+        let verdict = check_wire(&source_unit, &target_unit, spec.scale, spec.offset);
+        if matches!(verdict, WireUnits::Consistent | WireUnits::Unchecked) {
+            continue;
+        }
+        found.push(WireUnitMismatch {
+            connection,
+            source: spec.start_element,
+            source_id: world.get::<GlobalEntityId>(spec.start_element).copied(),
+            source_port: spec.start_connector.clone(),
+            source_unit,
+            target: spec.end_element,
+            target_id: world.get::<GlobalEntityId>(spec.end_element).copied(),
+            target_port: spec.end_connector.clone(),
+            target_unit,
+            scale: spec.scale,
+            offset: spec.offset,
+            verdict,
+        });
+    }
+    found.sort_by_key(|m| m.connection);
+
+    let mut diagnostics = world.resource_mut::<CosimDiagnostics>();
+    for mismatch in &found {
+        let key = format!(
+            "wire-units:{}:{}->{}:{}",
+            mismatch.source, mismatch.source_port, mismatch.target, mismatch.target_port
+        );
+        if diagnostics.report_once(key) {
+            warn!("[cosim] {}", mismatch.detail());
+        }
+    }
+    diagnostics.unit_mismatches = found;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn units(pairs: &[(&str, &str)]) -> PortUnits {
+        PortUnits {
+            units: pairs
+                .iter()
+                .map(|(p, u)| (p.to_string(), u.to_string()))
+                .collect::<HashMap<_, _>>(),
+        }
+    }
+
+    fn wire(world: &mut World, from: Entity, out: &str, to: Entity, input: &str, scale: f64) {
+        world.spawn((
+            SimConnection {
+                start_element: from,
+                start_connector: out.into(),
+                start_is_input: false,
+                end_element: to,
+                end_connector: input.into(),
+                scale,
+                offset: 0.0,
+            },
+            BoundConnection,
+        ));
+    }
+
+    /// THE CASE: a motor model publishing `rpm` wired straight into a
+    /// controller's `rad/s` input is reported with the factor it is missing;
+    /// the same wire carrying that factor is not.
+    #[test]
+    fn an_rpm_output_wired_into_rad_per_second_is_reported() {
+        let mut world = World::new();
+        world.init_resource::<CosimDiagnostics>();
+        let motor = world.spawn(units(&[("shaft_speed", "rpm")])).id();
+        let controller = world.spawn(units(&[("w_meas", "rad/s")])).id();
+        wire(&mut world, motor, "shaft_speed", controller, "w_meas", 1.0);
+
+        check_wire_units(&mut world);
+        let diagnostics = world.resource::<CosimDiagnostics>();
+        assert_eq!(diagnostics.unit_mismatches.len(), 1);
+        let WireUnits::MissingScale(needed) = diagnostics.unit_mismatches[0].verdict else {
+            panic!("{:?}", diagnostics.unit_mismatches[0]);
+        };
+        assert!((needed.factor - std::f64::consts::PI / 30.0).abs() < 1e-12);
+
+        let mut world = World::new();
+        world.init_resource::<CosimDiagnostics>();
+        let motor = world.spawn(units(&[("shaft_speed", "rpm")])).id();
+        let controller = world.spawn(units(&[("w_meas", "rad/s")])).id();
+        wire(
+            &mut world,
+            motor,
+            "shaft_speed",
+            controller,
+            "w_meas",
+            std::f64::consts::PI / 30.0,
+        );
+        check_wire_units(&mut world);
+        assert!(world
+            .resource::<CosimDiagnostics>()
+            .unit_mismatches
+            .is_empty());
+    }
+
+    /// A position wired into a force, and an end with no declared unit: the
+    /// first is an incompatibility, the second is not checked at all.
+    #[test]
+    fn incompatible_dimensions_are_reported_and_undeclared_ends_are_not() {
+        let mut world = World::new();
+        world.init_resource::<CosimDiagnostics>();
+        let a = world.spawn(units(&[("x", "m"), ("raw", "")])).id();
+        let b = world.spawn(units(&[("f", "N")])).id();
+        wire(&mut world, a, "x", b, "f", 1.0);
+        wire(&mut world, a, "raw", b, "f", 1.0);
+        wire(&mut world, a, "undeclared", b, "f", 1.0);
+
+        check_wire_units(&mut world);
+        let mismatches = &world.resource::<CosimDiagnostics>().unit_mismatches;
+        assert_eq!(mismatches.len(), 1, "{mismatches:?}");
+        assert!(matches!(
+            mismatches[0].verdict,
+            WireUnits::Incompatible { .. }
+        ));
+        assert_eq!(mismatches[0].source_port, "x");
+    }
+
+    /// Unbinding or despawning a mismatched wire takes its entry with it.
+    #[test]
+    fn a_removed_wire_leaves_the_mismatch_list() {
+        let mut app = App::new();
+        app.init_resource::<CosimDiagnostics>()
+            .add_systems(Update, check_wire_units.run_if(wire_units_changed));
+        let world = app.world_mut();
+        let a = world.spawn(units(&[("x", "m")])).id();
+        let b = world.spawn(units(&[("f", "N"), ("g", "N")])).id();
+        wire(world, a, "x", b, "f", 1.0);
+        wire(world, a, "x", b, "g", 1.0);
+        app.update();
+        let mismatches = |app: &App| {
+            app.world()
+                .resource::<CosimDiagnostics>()
+                .unit_mismatches
+                .len()
+        };
+        assert_eq!(mismatches(&app), 2);
+
+        let first = app.world().resource::<CosimDiagnostics>().unit_mismatches[0].connection;
+        app.world_mut()
+            .entity_mut(first)
+            .remove::<BoundConnection>();
+        app.update();
+        assert_eq!(mismatches(&app), 1);
+
+        let second = app.world().resource::<CosimDiagnostics>().unit_mismatches[0].connection;
+        app.world_mut().despawn(second);
+        app.update();
+        assert_eq!(mismatches(&app), 0);
+    }
+}
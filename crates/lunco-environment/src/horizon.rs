@@ -257,6 +257,12 @@ impl HeightField {
         self.min
     }
 
+    /// The raw `resolution²` heights, row-major over (x, z) — for content keys
+    /// of bakes derived from this field.
+    pub(crate) fn heights(&self) -> &[f32] {
+        &self.heights
+    }
+
     /// Bakes the sun-visibility cache — a `target_res²` grid of `u8` values
     /// (0..255 ← 0..1 visibility) sampled from [`sun_visibility`] over the
     /// heightfield footprint, for the given terrain-local sun direction.
@@ -438,6 +444,85 @@ impl HorizonShadowCache {
         let threshold = threshold_deg.to_radians();
         self.last_sun_local.dot(sun_local) >= threshold.cos()
     }
+
+    /// Bilinear read of the baked visibility at a terrain-local XZ position —
+    /// the CPU twin of the shader's single `textureSampleLevel`.
+    ///
+    /// `None` when the image is not resident in `images`, carries no CPU data,
+    /// or `local_xz` lies outside the heightfield footprint. Freshness is the
+    /// caller's decision, exactly as for the shader: check
+    /// [`is_valid_for_sun`](Self::is_valid_for_sun) first.
+    pub fn visibility_at(
+        &self,
+        images: &Assets<Image>,
+        field: &HeightField,
+        local_xz: Vec2,
+    ) -> Option<f32> {
+        let image = images.get(&self.image)?;
+        let data = image.data.as_ref()?;
+        let res = image.width() as usize;
+        if res < 2 || data.len() < res * res {
+            return None;
+        }
+        let p = local_xz - field.min();
+        let size = field.size();
+        if p.x < 0.0 || p.y < 0.0 || p.x > size.x || p.y > size.y {
+            return None;
+        }
+        // Same texel mapping as `bake_visibility_cache`: texel (x, y) sits at
+        // `min + (x, y) / (res - 1) * size`.
+        let g = p / size * (res - 1) as f32;
+        let (x0, y0) = (g.x as usize, g.y as usize);
+        let (x1, y1) = ((x0 + 1).min(res - 1), (y0 + 1).min(res - 1));
+        let (fx, fy) = (g.x.fract(), g.y.fract());
+        let v = |x: usize, y: usize| data[y * res + x] as f32 / 255.0;
+        let top = v(x0, y0) + (v(x1, y0) - v(x0, y0)) * fx;
+        let bot = v(x0, y1) + (v(x1, y1) - v(x0, y1)) * fx;
+        Some(top + (bot - top) * fy)
+    }
+}
+
+/// Terrain sun visibility (0..1) at a WORLD position, for CPU consumers that
+/// need the shadow the terrain shader draws — thermal flux, panel shading.
+///
+/// Walks every baked horizon terrain and answers from the first whose
+/// footprint contains the point: its [`HorizonShadowCache`] when that is fresh
+/// for this sun (one texel read), else the live [`HeightField::sun_visibility`]
+/// march the cache is baked from, so a stale or still-baking cache can never
+/// hand out a shadow that belongs to an old sun. `None` when no terrain covers
+/// the point — the caller decides what "no terrain" means (for an orbiting
+/// vehicle it means unshadowed).
+///
+/// `to_sun` is the world-space unit direction toward the sun (`*sun_gt.back()`),
+/// and `tan_sun_r` the value [`pick_sun`] returns alongside it.
+#[allow(clippy::type_complexity)]
+pub fn terrain_sun_visibility(
+    world_pos: Vec3,
+    to_sun: Vec3,
+    tan_sun_r: f32,
+    config: &HorizonShadowCacheConfig,
+    images: Option<&Assets<Image>>,
+    terrains: &Query<(&GlobalTransform, &HorizonMap, Option<&HorizonShadowCache>)>,
+) -> Option<f32> {
+    for (gt, map, cache) in terrains {
+        let local = gt.affine().inverse().transform_point3(world_pos);
+        let sun_local = (gt.rotation().inverse() * to_sun).normalize_or_zero();
+        let local_xz = Vec2::new(local.x, local.z);
+        let cached = cache
+            .zip(images)
+            .filter(|(cache, _)| {
+                config.enabled && cache.is_valid_for_sun(sun_local, config.sun_threshold_deg)
+            })
+            .and_then(|(cache, images)| cache.visibility_at(images, &map.field, local_xz));
+        let visibility = cached.or_else(|| {
+            map.field
+                .sun_visibility(local_xz, sun_local, tan_sun_r, config.march_steps)
+        });
+        if visibility.is_some() {
+            return visibility;
+        }
+    }
+    None
 }
 
 /// In-flight async visibility-cache bake for a terrain entity (native only).
@@ -0,0 +1,369 @@
+//! Long-duration illumination and Earth-visibility maps — the horizon march
+//! swept over an ephemeris window instead of evaluated for one sun.
+//!
+//! [`HeightField::sun_visibility`] answers "is this spot lit *now*". Landing-site
+//! selection near the poles asks a different question: over a lunar year, what
+//! fraction of the time is this spot lit, how long is its worst night, and how
+//! often can it see Earth. This module answers it by marching the SAME
+//! heightfield for every step of an [`IlluminationSweep`] and reducing the
+//! per-step visibilities into three rasters ([`IlluminationMaps`]):
+//!
+//! - `illumination_pct` — mean visible fraction of the solar disk, 0..100;
+//! - `longest_darkness_hours` — the longest unbroken run of steps whose sun
+//!   visibility stayed below [`DARK_THRESHOLD`], in hours;
+//! - `earth_visibility_pct` — mean visible fraction of Earth's disk, 0..100.
+//!
+//! **Directions are an input, not computed here.** The ephemeris lives in
+//! `lunco-celestial`, which depends on this crate, so the sweep of terrain-local
+//! sun/Earth directions is built there (`BakeIlluminationMaps`) and handed in —
+//! the same split as [`EarthDirectionWorld`](crate::EarthDirectionWorld).
+//!
+//! The bake is a pure function of the heightfield and the sweep, so it goes
+//! through `lunco-precompute`: a second request for the same site and window
+//! (or a second peer) loads the rasters instead of re-marching a year. It runs
+//! on the async pool and lands as a [`TerrainIllumination`] component on the
+//! terrain entity, where the overlay and `TerrainField` glue pick it up.
+
+use std::path::Path;
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy::tasks::{futures_lite::future, AsyncComputeTaskPool, Task};
+
+use crate::horizon::HeightField;
+
+/// Sun visibility below which a step counts as dark for
+/// `longest_darkness_hours`: half the disk hidden. A grazing sun that only
+/// lights a rim sliver does not end a night for a solar array.
+pub const DARK_THRESHOLD: f32 = 0.5;
+
+/// Bump when the reduction or the stored layout changes, so stale cache
+/// entries are never matched.
+const CACHE_FORMAT_VERSION: u64 = 1;
+
+/// The time series a bake sweeps: one terrain-local to-sun and to-Earth unit
+/// direction per step, `step_hours` apart from `start_jd`.
+///
+/// A `to_earth` entry of `Vec3::ZERO` means the ephemeris had no Earth at that
+/// step; it counts as Earth not visible rather than being skipped, so the
+/// percentage is always over the whole window.
+#[derive(Clone, Debug, Default)]
+pub struct IlluminationSweep {
+    pub start_jd: f64,
+    pub step_hours: f64,
+    pub to_sun: Vec<Vec3>,
+    pub to_earth: Vec<Vec3>,
+    /// Tangent of the sun's angular radius — the penumbra of the march.
+    pub tan_sun_r: f32,
+    /// Tangent of Earth's mean angular radius over the window.
+    pub tan_earth_r: f32,
+}
+
+impl IlluminationSweep {
+    /// Julian date one step past the last sample — the end of the window.
+    pub fn end_jd(&self) -> f64 {
+        self.start_jd + self.to_sun.len() as f64 * self.step_hours / 24.0
+    }
+}
+
+/// The three reduced rasters, row-major `res × res` over the heightfield's
+/// terrain-local footprint. Texel `(ix, iz)` sits at the texel CENTRE,
+/// `min + ((ix, iz) + 0.5) / res * size` — the convention of the terrain
+/// field rasters, so a map and a `TerrainField` region line up.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IlluminationMaps {
+    pub res: u32,
+    pub min: Vec2,
+    pub size: Vec2,
+    pub start_jd: f64,
+    pub end_jd: f64,
+    pub step_hours: f64,
+    pub illumination_pct: Vec<f32>,
+    pub longest_darkness_hours: Vec<f32>,
+    pub earth_visibility_pct: Vec<f32>,
+}
+
+impl IlluminationMaps {
+    /// Terrain-local XZ of texel `(ix, iz)`'s centre.
+    pub fn texel_xz(&self, ix: u32, iz: u32) -> Vec2 {
+        self.min + (Vec2::new(ix as f32, iz as f32) + 0.5) / self.res as f32 * self.size
+    }
+}
+
+/// The sweep as a [`lunco_precompute::Bake`]: what is marched, how it keys and
+/// how the rasters serialise.
+pub struct IlluminationBake {
+    pub field: HeightField,
+    pub sweep: Arc<IlluminationSweep>,
+    /// Texels per side of the output rasters.
+    pub resolution: u32,
+    /// Iterations of each horizon march (see [`HeightField::sun_visibility`]).
+    pub march_steps: usize,
+}
+
+/// March every texel of `field` through `sweep` and reduce to the three maps.
+/// Pure and deterministic: the same field and sweep give the same bytes.
+pub fn bake_illumination(
+    field: &HeightField,
+    sweep: &IlluminationSweep,
+    resolution: u32,
+    march_steps: usize,
+) -> IlluminationMaps {
+    let res = resolution.max(1);
+    let texels = (res as usize) * (res as usize);
+    let mut maps = IlluminationMaps {
+        res,
+        min: field.min(),
+        size: field.size(),
+        start_jd: sweep.start_jd,
+        end_jd: sweep.end_jd(),
+        step_hours: sweep.step_hours,
+        illumination_pct: vec![0.0; texels],
+        longest_darkness_hours: vec![0.0; texels],
+        earth_visibility_pct: vec![0.0; texels],
+    };
+    let steps = sweep.to_sun.len();
+    if steps == 0 || march_steps == 0 {
+        return maps;
+    }
+    for iz in 0..res {
+        for ix in 0..res {
+            let xz = maps.texel_xz(ix, iz);
+            let (mut lit, mut earth) = (0.0f64, 0.0f64);
+            let (mut run, mut longest) = (0usize, 0usize);
+            for (i, &to_sun) in sweep.to_sun.iter().enumerate() {
+                let sun = field
+                    .sun_visibility(xz, to_sun, sweep.tan_sun_r, march_steps)
+                    .unwrap_or(0.0);
+                lit += sun as f64;
+                if sun < DARK_THRESHOLD {
+                    run += 1;
+                    longest = longest.max(run);
+                } else {
+                    run = 0;
+                }
+                let to_earth = sweep.to_earth.get(i).copied().unwrap_or(Vec3::ZERO);
+                if to_earth != Vec3::ZERO {
+                    earth += field
+                        .sun_visibility(xz, to_earth, sweep.tan_earth_r, march_steps)
+                        .unwrap_or(0.0) as f64;
+                }
+            }
+            let t = (iz as usize) * (res as usize) + ix as usize;
+            maps.illumination_pct[t] = (lit / steps as f64 * 100.0) as f32;
+            maps.earth_visibility_pct[t] = (earth / steps as f64 * 100.0) as f32;
+            maps.longest_darkness_hours[t] = (longest as f64 * sweep.step_hours) as f32;
+        }
+    }
+    maps
+}
+
+impl lunco_precompute::Bake for IlluminationBake {
+    type Output = IlluminationMaps;
+    const NAMESPACE: &'static str = "environment/illumination";
+
+    /// Heights, footprint, every sweep direction and every bake parameter —
+    /// version first, so a reduction change invalidates old entries.
+    fn key(&self) -> u64 {
+        let mut h = lunco_precompute::Fnv1a::new();
+        h.write_u64(CACHE_FORMAT_VERSION);
+        h.write_u64(self.resolution as u64);
+        h.write_u64(self.march_steps as u64);
+        h.write_u64(self.field.resolution() as u64);
+        for v in [self.field.min(), self.field.size()] {
+            h.write_u64(v.x.to_bits() as u64);
+            h.write_u64(v.y.to_bits() as u64);
+        }
+        for &v in self.field.heights() {
+            h.write_u64(v.to_bits() as u64);
+        }
+        let sweep = &self.sweep;
+        h.write_u64(sweep.start_jd.to_bits());
+        h.write_u64(sweep.step_hours.to_bits());
+        h.write_u64(sweep.tan_sun_r.to_bits() as u64);
+        h.write_u64(sweep.tan_earth_r.to_bits() as u64);
+        h.write_u64(sweep.to_sun.len() as u64);
+        h.write_u64(sweep.to_earth.len() as u64);
+        for d in sweep.to_sun.iter().chain(&sweep.to_earth) {
+            for c in d.to_array() {
+                h.write_u64(c.to_bits() as u64);
+            }
+        }
+        h.finish()
+    }
+
+    fn bake(&self) -> IlluminationMaps {
+        bake_illumination(&self.field, &self.sweep, self.resolution, self.march_steps)
+    }
+
+    /// `meta.bin`: `[res u32][min f32×2][size f32×2][start f64][end f64][step f64]`,
+    /// `layers.bin`: the three rasters back to back as `f32`, all little-endian.
+    fn store(dir: &Path, maps: &IlluminationMaps) -> lunco_precompute::StorageResult<()> {
+        let mut meta = Vec::with_capacity(44);
+        meta.extend_from_slice(&maps.res.to_le_bytes());
+        for c in [maps.min.x, maps.min.y, maps.size.x, maps.size.y] {
+            meta.extend_from_slice(&c.to_le_bytes());
+        }
+        for c in [maps.start_jd, maps.end_jd, maps.step_hours] {
+            meta.extend_from_slice(&c.to_le_bytes());
+        }
+        let layers: Vec<u8> = maps
+            .illumination_pct
+            .iter()
+            .chain(&maps.longest_darkness_hours)
+            .chain(&maps.earth_visibility_pct)
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        lunco_precompute::store_blob(dir, "meta.bin", &meta)?;
+        lunco_precompute::store_blob(dir, "layers.bin", &layers)
+    }
+
+    /// `None` on any miss or size mismatch → the orchestrator rebakes.
+    fn load(dir: &Path) -> Option<IlluminationMaps> {
+        let meta = lunco_precompute::load_blob(dir, "meta.bin")?;
+        let layers = lunco_precompute::load_blob(dir, "layers.bin")?;
+        if meta.len() != 44 {
+            return None;
+        }
+        let f32_at = |o: usize| f32::from_le_bytes(meta[o..o + 4].try_into().unwrap());
+        let f64_at = |o: usize| f64::from_le_bytes(meta[o..o + 8].try_into().unwrap());
+        let res = u32::from_le_bytes(meta[0..4].try_into().ok()?);
+        let texels = (res as usize).checked_mul(res as usize)?;
+        if res == 0 || layers.len() != texels.checked_mul(12)? {
+            return None;
+        }
+        let floats: Vec<f32> = layers
+            .chunks_exact(4)
+            .map(|b| f32::from_le_bytes(b.try_into().unwrap()))
+            .collect();
+        Some(IlluminationMaps {
+            res,
+            min: Vec2::new(f32_at(4), f32_at(8)),
+            size: Vec2::new(f32_at(12), f32_at(16)),
+            start_jd: f64_at(20),
+            end_jd: f64_at(28),
+            step_hours: f64_at(36),
+            illumination_pct: floats[..texels].to_vec(),
+            longest_darkness_hours: floats[texels..2 * texels].to_vec(),
+            earth_visibility_pct: floats[2 * texels..].to_vec(),
+        })
+    }
+}
+
+/// The published maps for a terrain. Replaced wholesale by the next bake;
+/// consumers read it as data (overlay drapes, `TerrainField`, scripts).
+#[derive(Component, Clone)]
+pub struct TerrainIllumination(pub Arc<IlluminationMaps>);
+
+/// In-flight illumination bake for a terrain entity.
+#[derive(Component)]
+pub struct IlluminationBakeTask(Task<IlluminationMaps>);
+
+/// Start (or restart — the old task is dropped) an illumination bake for
+/// `entity` on the async pool, through the content-addressed cache on native.
+pub fn start_illumination_bake(commands: &mut Commands, entity: Entity, bake: IlluminationBake) {
+    info!(
+        "[illumination] baking {}² maps for {entity:?} over {} steps in background…",
+        bake.resolution,
+        bake.sweep.to_sun.len()
+    );
+    let task = AsyncComputeTaskPool::get().spawn(async move {
+        let _span = bevy::log::info_span!("illumination_maps_bake").entered();
+        bake_or_load(&bake)
+    });
+    commands
+        .entity(entity)
+        .try_insert(IlluminationBakeTask(task));
+}
+
+/// Load the maps from the `cache://` dir when this exact field and sweep were
+/// baked before, otherwise march and write them through.
+#[cfg(not(target_arch = "wasm32"))]
+fn bake_or_load(bake: &IlluminationBake) -> IlluminationMaps {
+    lunco_precompute::bake_or_load(bake, &lunco_assets::cache_dir())
+}
+
+/// `lunco_precompute`'s fs tier is native-only; wasm re-marches.
+#[cfg(target_arch = "wasm32")]
+fn bake_or_load(bake: &IlluminationBake) -> IlluminationMaps {
+    lunco_precompute::Bake::bake(bake)
+}
+
+/// Collects finished bakes and publishes them as [`TerrainIllumination`].
+pub fn finish_illumination_bakes(
+    mut commands: Commands,
+    mut q: Query<(Entity, &mut IlluminationBakeTask)>,
+) {
+    for (entity, mut task) in &mut q {
+        let Some(maps) = future::block_on(future::poll_once(&mut task.0)) else {
+            continue;
+        };
+        info!(
+            "[illumination] maps ready for {entity:?}: {}² over JD {:.2}–{:.2}",
+            maps.res, maps.start_jd, maps.end_jd
+        );
+        commands
+            .entity(entity)
+            .remove::<IlluminationBakeTask>()
+            .try_insert(TerrainIllumination(Arc::new(maps)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Flat 40 m square with a 3 m wall along its west edge.
+    fn walled_field() -> HeightField {
+        let res = 41u32;
+        let heights = (0..res * res)
+            .map(|i| if i % res < 4 { 3.0 } else { 0.0 })
+            .collect();
+        HeightField::from_grid(res, Vec2::ZERO, Vec2::splat(40.0), Arc::new(heights))
+    }
+
+    /// Four steps: sun high, sun low in the west (behind the wall), twice
+    /// below the horizon. Earth stays high in the east the whole time.
+    fn sweep() -> IlluminationSweep {
+        let west_low = Vec3::new(-1.0, 0.2, 0.0).normalize();
+        IlluminationSweep {
+            start_jd: 2_461_000.0,
+            step_hours: 6.0,
+            to_sun: vec![Vec3::Y, west_low, Vec3::NEG_Y, Vec3::NEG_Y],
+            to_earth: vec![Vec3::new(1.0, 1.0, 0.0).normalize(); 4],
+            tan_sun_r: 0.0046,
+            tan_earth_r: 0.0166,
+        }
+    }
+
+    #[test]
+    fn sweep_reduces_to_percentages_and_longest_night() {
+        let maps = bake_illumination(&walled_field(), &sweep(), 8, 48);
+        assert_eq!(maps.end_jd, 2_461_001.0);
+        // A texel well east of the wall: lit for the two above-horizon steps.
+        let east = (4 * 8 + 7) as usize;
+        assert!((maps.illumination_pct[east] - 50.0).abs() < 1.0);
+        assert_eq!(maps.longest_darkness_hours[east], 12.0);
+        assert!((maps.earth_visibility_pct[east] - 100.0).abs() < 1.0);
+        // Hard against the wall the low western sun is gone too: one lit step,
+        // then three dark ones in a row.
+        let west = (4 * 8 + 1) as usize;
+        assert!((maps.illumination_pct[west] - 25.0).abs() < 1.0);
+        assert_eq!(maps.longest_darkness_hours[west], 18.0);
+    }
+
+    #[test]
+    fn stored_maps_round_trip() {
+        use lunco_precompute::Bake;
+        let bake = IlluminationBake {
+            field: walled_field(),
+            sweep: Arc::new(sweep()),
+            resolution: 4,
+            march_steps: 16,
+        };
+        let maps = bake.bake();
+        let dir = tempfile::tempdir().unwrap();
+        IlluminationBake::store(dir.path(), &maps).unwrap();
+        assert_eq!(IlluminationBake::load(dir.path()), Some(maps));
+    }
+}
@@ -3,10 +3,13 @@
 //! Per-entity environmental state computed from celestial body providers.
 //!
 //! See `README.md` for the full architecture, rationale, and how to add new
-//! environment domains (atmosphere, radiation, magnetic field, etc.).
+//! environment domains (atmosphere, magnetic field, etc.).
 //!
-//! Currently implements **gravity only**. Other domains follow the same
-//! pattern — see the README for templates.
+//! Currently implements **gravity**, the Sun/Earth direction bridges, the
+//! radiative **thermal** environment, solar-array **irradiance**, particle
+//! **radiation** dose and baked long-duration **illumination** maps. Other
+//! domains (atmosphere, magnetic field) follow the same pattern — see the README
+//! for templates.
 
 use avian3d::prelude::{ComputedMass, Forces, RigidBody, WriteRigidBodyForces};
 use bevy::math::DVec3;
@@ -108,8 +111,47 @@ pub use earth::{
 /// `lunco-render-bevy::horizon_shade`. See the module docs.
 pub mod horizon;
 pub use horizon::{
-    install_horizon_map_from_field, pick_sun, HeightField, HorizonMap, HorizonShadowCache,
-    HorizonShadowCacheConfig, HorizonShadowPlugin, SunQuery,
+    install_horizon_map_from_field, pick_sun, terrain_sun_visibility, HeightField, HorizonMap,
+    HorizonShadowCache, HorizonShadowCacheConfig, HorizonShadowPlugin, SunQuery,
+};
+
+/// Long-duration illumination, longest-darkness and Earth-visibility rasters:
+/// the horizon march swept over an ephemeris window (directions supplied by
+/// `lunco-celestial`) and cached through `lunco-precompute`.
+pub mod illumination;
+pub use illumination::{
+    bake_illumination, start_illumination_bake, IlluminationBake, IlluminationMaps,
+    IlluminationSweep, TerrainIllumination,
+};
+
+/// Radiative thermal environment as a co-simulation source (`LocalThermal` +
+/// the thermal→cosim bridge): solar, albedo and planetary-IR flux on a probe's
+/// `+Y` face, plus the deep-space sink. Built on the same sun, Earth and horizon
+/// providers as the direction bridges.
+pub mod thermal;
+pub use thermal::{
+    compute_local_thermal, inject_local_thermal_into_cosim, LocalThermal, ThermalEnvironment,
+};
+
+/// Sunlight reaching a solar array as a co-simulation source
+/// (`LocalSolarIrradiance` + its cosim bridge): the panel face sampled against
+/// the horizon shadow and nearby colliders, published as a normal-incidence
+/// irradiance for `LunCo.Electrical.SolarPanel`.
+pub mod solar_array;
+pub use solar_array::{
+    compute_local_solar_irradiance, inject_local_solar_irradiance_into_cosim, LocalSolarIrradiance,
+    SolarArrayFootprint,
+};
+
+/// Particle radiation as a co-simulation source (`LocalRadiation` + the
+/// radiation→cosim bridge): GCR by solar-cycle phase, scheduled SPE profiles,
+/// body shadowing (written by `lunco-celestial`) and equivalent-aluminium
+/// shielding, with accumulated dose.
+pub mod radiation;
+pub use radiation::{
+    compute_local_radiation, inject_local_radiation_into_cosim, LocalRadiation, RadiationDoseLimit,
+    RadiationEnvironment, RadiationShielding, RadiationSkyFraction, ScheduleSolarParticleEvent,
+    SetSolarCyclePhase, SolarParticleEvent,
 };
 
 /// The sun's angles as ports (`sun_azimuth` / `sun_elevation`) — a `PortBackend`
@@ -748,6 +790,19 @@ impl Plugin for EnvironmentPlugin {
         app.register_type::<EnvironmentProbe>();
         app.register_type::<EarthDirectionRequired>();
         app.register_type::<Earthshine>();
+        app.register_type::<LocalThermal>();
+        app.register_type::<ThermalEnvironment>();
+        // Radiative parameters of the surface — lunar defaults unless the scene
+        // inserted its own first, the same convention as `LunarSun`.
+        app.init_resource::<ThermalEnvironment>();
+        app.register_type::<LocalSolarIrradiance>();
+        app.register_type::<SolarArrayFootprint>();
+        app.register_type::<LocalRadiation>();
+        app.register_type::<RadiationShielding>();
+        app.register_type::<RadiationDoseLimit>();
+        app.register_type::<RadiationSkyFraction>();
+        app.register_type::<RadiationEnvironment>();
+        app.init_resource::<RadiationEnvironment>();
         // Declared here, WRITTEN by lunco-celestial (which depends on this crate,
         // so the dependency cannot run the other way). Init'd unconditionally and
         // left at ZERO — the "not known" state — so a scene with no celestial
@@ -776,6 +831,23 @@ impl Plugin for EnvironmentPlugin {
                 inject_local_earth_into_cosim
                     .in_set(EnvironmentSet::Apply)
                     .before(lunco_cosim::systems::propagate::CosimSet::Propagate),
+                // Thermal fluxes read the same sun (and Earth) the same tick, so a
+                // radiator model never sees the sun in one place and its heat in
+                // another.
+                compute_local_thermal.in_set(EnvironmentSet::Compute),
+                inject_local_thermal_into_cosim
+                    .in_set(EnvironmentSet::Apply)
+                    .before(lunco_cosim::systems::propagate::CosimSet::Propagate),
+                // Panel irradiance shares the thermal sun and horizon shadow, so
+                // a panel and the radiator beside it agree on where the shade is.
+                compute_local_solar_irradiance.in_set(EnvironmentSet::Compute),
+                inject_local_solar_irradiance_into_cosim
+                    .in_set(EnvironmentSet::Apply)
+                    .before(lunco_cosim::systems::propagate::CosimSet::Propagate),
+                compute_local_radiation.in_set(EnvironmentSet::Compute),
+                inject_local_radiation_into_cosim
+                    .in_set(EnvironmentSet::Apply)
+                    .before(lunco_cosim::systems::propagate::CosimSet::Propagate),
             ),
         );
 
@@ -786,6 +858,9 @@ impl Plugin for EnvironmentPlugin {
         // is simply absent headless. Inert until a terrain carries the
         // `HorizonShadowTerrain` marker (USD-stamped).
         app.add_plugins(HorizonShadowPlugin);
+        // Long-duration illumination maps: started on request by
+        // `lunco-celestial` (which owns the ephemeris), collected here.
+        app.add_systems(Update, illumination::finish_illumination_bakes);
 
         // Register environment commands (SetEnvironmentLight). The macro-built
         // `register_all_commands` does `register_type` + `add_observer` so the
@@ -793,6 +868,8 @@ impl Plugin for EnvironmentPlugin {
         // render-free now — its `bloom_intensity` field is applied by a second
         // observer over in `lunco-render-bevy`.
         register_all_commands(app);
+        // SetSolarCyclePhase / ScheduleSolarParticleEvent.
+        radiation::register_all_commands(app);
     }
 }
 
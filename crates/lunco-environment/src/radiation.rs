@@ -0,0 +1,547 @@
+//! Space radiation domain — galactic cosmic ray and solar particle event dose
+//! as a co-simulation source.
+//!
+//! The particle analog of the thermal bridge. Every
+//! [`crate::EnvironmentProbe`] gets a [`LocalRadiation`]: the absorbed dose rate
+//! from galactic cosmic rays (GCR) and from any scheduled solar particle event
+//! (SPE), the dose-equivalent rate, and the dose both have accumulated since the
+//! probe appeared. They are published as ordinary `SimComponent` outputs, so an
+//! EVA consumables model or an electronics total-ionizing-dose budget receives
+//! them through a plain output→input wire, and the accumulated values are
+//! reflected fields a telemetry `Parameter` can sample.
+//!
+//! ## The model
+//!
+//! ```text
+//! D_gcr = D_gcr,free(phase) · f_sky · exp(−x / λ_gcr)
+//! D_spe = Σ D_spe,free(t)   · f_sky · exp(−x / λ_spe)
+//! H     = Q_gcr · D_gcr + Q_spe · D_spe
+//! ```
+//!
+//! * `D_gcr,free(phase)` — free-space GCR dose rate, interpolated between the
+//!   solar-minimum and solar-maximum values by the solar-cycle phase held in
+//!   [`RadiationEnvironment`]. GCR is anti-correlated with solar activity: the
+//!   heliospheric field screens it hardest at solar maximum.
+//! * `D_spe,free(t)` — the authored free-space time profile of each scheduled
+//!   [`SolarParticleEvent`], piecewise-linear in hours after onset.
+//! * `f_sky` — the fraction of the sky NOT blocked by a planetary body, in
+//!   [`RadiationSkyFraction`]. Both fields are modelled as isotropic, so a body
+//!   blocks the solid angle it subtends: one half on the lunar surface, a few
+//!   percent in low lunar orbit. It is computed in `lunco-celestial` from the
+//!   same body-occlusion geometry its link kernel uses, because that is the crate
+//!   that can see the ephemeris; this crate only declares the slot, exactly as
+//!   [`EarthDirectionWorld`](crate::EarthDirectionWorld). A probe it has not
+//!   placed reads as free space — the conservative answer.
+//! * `x` — the probe's equivalent-aluminium [`RadiationShielding`], g/cm². The
+//!   two attenuation lengths differ by an order of magnitude because SPE protons
+//!   are soft and GCR heavy ions are not: a few g/cm² stops most of an SPE and
+//!   barely dents GCR.
+//!
+//! ## Limits (v1)
+//!
+//! - Exponential attenuation is a bulk fit. It has no secondary build-up, so
+//!   thick GCR shielding is optimistic.
+//! - SPE onsets are isotropic. The real onset is beamed along the interplanetary
+//!   field line and isotropises over hours.
+//! - Trapped belts are not modelled. On the Moon there are none; an Earth-orbit
+//!   scene needs an AP8/AE8 provider added alongside these.
+
+use bevy::prelude::*;
+
+use lunco_core::{
+    on_command, register_commands, Ack, Command, GlobalEntityId, OpId, Severity, TelemetryEvent,
+    TelemetryValue,
+};
+use lunco_cosim::{
+    ACCUMULATED_DOSE_CONNECTOR, ACCUMULATED_DOSE_EQUIVALENT_CONNECTOR,
+    DOSE_EQUIVALENT_RATE_CONNECTOR, DOSE_RATE_CONNECTOR, GCR_DOSE_RATE_CONNECTOR,
+    SPE_DOSE_RATE_CONNECTOR,
+};
+use lunco_time::WorldTime;
+
+const SECONDS_PER_HOUR: f64 = 3600.0;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// A solar particle event as an authored free-space dose-rate time profile.
+#[derive(Debug, Clone, PartialEq, Reflect, Default)]
+pub struct SolarParticleEvent {
+    /// Label for logs and the dose-limit event.
+    pub name: String,
+    /// Onset epoch (TDB Julian Date) — hour zero of `profile`.
+    pub onset_jd: f64,
+    /// `(hours after onset, free-space dose rate in Gy/h)` points, ascending in
+    /// time. Linear between points, zero outside them.
+    pub profile: Vec<(f64, f64)>,
+}
+
+impl SolarParticleEvent {
+    /// Free-space dose rate of this event at `jd`, Gy/h.
+    pub fn dose_rate_at(&self, jd: f64) -> f64 {
+        let hours = (jd - self.onset_jd) * 24.0;
+        let mut points = self.profile.iter();
+        let Some(&(mut t0, mut d0)) = points.next() else {
+            return 0.0;
+        };
+        if hours < t0 {
+            return 0.0;
+        }
+        for &(t1, d1) in points {
+            if hours <= t1 {
+                let span = t1 - t0;
+                return if span > 0.0 {
+                    d0 + (d1 - d0) * (hours - t0) / span
+                } else {
+                    d1
+                };
+            }
+            (t0, d0) = (t1, d1);
+        }
+        if hours == t0 {
+            d0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Radiation provider: the free-space particle environment and the scheduled
+/// events, shared by every probe.
+///
+/// Defaults are the 2019 solar-minimum Moon (Chang'E-4 LND surface dose of
+/// ≈13 µGy/h ⇒ ≈26 µGy/h free space) with roughly half that at solar maximum.
+#[derive(Resource, Debug, Clone, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct RadiationEnvironment {
+    /// Position in the ~11-year solar cycle, `0..1`: `0` (and `1`) is solar
+    /// minimum, `0.5` solar maximum.
+    pub solar_cycle_phase: f64,
+    /// Free-space GCR absorbed dose rate at solar minimum, Gy/h.
+    pub gcr_dose_rate_solar_min_gy_h: f64,
+    /// Free-space GCR absorbed dose rate at solar maximum, Gy/h.
+    pub gcr_dose_rate_solar_max_gy_h: f64,
+    /// Mean quality factor of the GCR field (LND: ≈4.3–4.5).
+    pub gcr_quality_factor: f64,
+    /// Mean quality factor of SPE protons.
+    pub spe_quality_factor: f64,
+    /// e-folding areal density of GCR dose in aluminium, g/cm².
+    pub gcr_attenuation_g_cm2: f64,
+    /// e-folding areal density of SPE dose in aluminium, g/cm².
+    pub spe_attenuation_g_cm2: f64,
+    /// Scheduled solar particle events.
+    pub events: Vec<SolarParticleEvent>,
+}
+
+impl Default for RadiationEnvironment {
+    fn default() -> Self {
+        Self {
+            solar_cycle_phase: 0.0,
+            gcr_dose_rate_solar_min_gy_h: 26.0e-6,
+            gcr_dose_rate_solar_max_gy_h: 12.0e-6,
+            gcr_quality_factor: 4.4,
+            spe_quality_factor: 1.5,
+            gcr_attenuation_g_cm2: 100.0,
+            spe_attenuation_g_cm2: 4.0,
+            events: Vec::new(),
+        }
+    }
+}
+
+impl RadiationEnvironment {
+    /// Free-space GCR dose rate at the current solar-cycle phase, Gy/h.
+    pub fn gcr_free_space_gy_h(&self) -> f64 {
+        let activity = (1.0 - (std::f64::consts::TAU * self.solar_cycle_phase).cos()) * 0.5;
+        self.gcr_dose_rate_solar_min_gy_h
+            + (self.gcr_dose_rate_solar_max_gy_h - self.gcr_dose_rate_solar_min_gy_h) * activity
+    }
+
+    /// Summed free-space dose rate of every event active at `jd`, Gy/h.
+    pub fn spe_free_space_gy_h(&self, jd: f64) -> f64 {
+        self.events.iter().map(|e| e.dose_rate_at(jd)).sum()
+    }
+}
+
+/// Equivalent-aluminium shielding around a probe, g/cm².
+///
+/// Absent means unshielded. USD: `float lunco:radiation:shielding` on the
+/// `LunCoEnvironmentProbeAPI` prim.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, Default)]
+#[reflect(Component)]
+pub struct RadiationShielding {
+    pub areal_density_g_cm2: f32,
+}
+
+/// Accumulated dose-equivalent limit for a probe, Sv. Crossing it fires one
+/// `radiation.dose_limit` [`TelemetryEvent`] from the probe.
+///
+/// USD: `float lunco:radiation:doseLimit` on the `LunCoEnvironmentProbeAPI` prim.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, Default)]
+#[reflect(Component)]
+pub struct RadiationDoseLimit {
+    pub sievert: f64,
+}
+
+/// Fraction of the sky around a probe not blocked by a planetary body, `0..1`.
+///
+/// Declared here, WRITTEN by `lunco-celestial` (which owns the ephemeris and
+/// depends on this crate). Absent means free space.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct RadiationSkyFraction(pub f32);
+
+impl Default for RadiationSkyFraction {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Dose rates at a probe and what they have accumulated, SI units.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, Default)]
+#[reflect(Component)]
+pub struct LocalRadiation {
+    /// GCR absorbed dose rate, Gy/s.
+    pub gcr_dose_rate: f64,
+    /// SPE absorbed dose rate, Gy/s.
+    pub spe_dose_rate: f64,
+    /// Dose-equivalent rate, Sv/s.
+    pub dose_equivalent_rate: f64,
+    /// Absorbed dose accumulated since the probe appeared, Gy.
+    pub accumulated_dose: f64,
+    /// Dose equivalent accumulated since the probe appeared, Sv.
+    pub accumulated_dose_equivalent: f64,
+    /// Epoch of the last accumulation step (TDB JD).
+    pub last_jd: f64,
+    /// Set once the probe's [`RadiationDoseLimit`] has been reported.
+    pub limit_reported: bool,
+}
+
+impl LocalRadiation {
+    /// Total absorbed dose rate, Gy/s.
+    pub fn dose_rate(&self) -> f64 {
+        self.gcr_dose_rate + self.spe_dose_rate
+    }
+}
+
+/// Shielded, body-shadowed GCR and SPE dose rates (Gy/s) for one probe.
+pub fn shielded_dose_rates(
+    env: &RadiationEnvironment,
+    jd: f64,
+    sky_fraction: f64,
+    shielding_g_cm2: f64,
+) -> (f64, f64) {
+    let sky = sky_fraction.clamp(0.0, 1.0);
+    let x = shielding_g_cm2.max(0.0);
+    let attenuate = |lambda: f64| {
+        if lambda > 0.0 {
+            (-x / lambda).exp()
+        } else {
+            1.0
+        }
+    };
+    let gcr = env.gcr_free_space_gy_h() * sky * attenuate(env.gcr_attenuation_g_cm2);
+    let spe = env.spe_free_space_gy_h(jd) * sky * attenuate(env.spe_attenuation_g_cm2);
+    (gcr / SECONDS_PER_HOUR, spe / SECONDS_PER_HOUR)
+}
+
+/// Computes and integrates [`LocalRadiation`] for every environment probe.
+///
+/// Rates are re-derived every tick; dose is accumulated over the WORLD epoch's
+/// advance, so time warp accumulates warp-scaled dose and a paused or rewound
+/// clock accumulates none. Unlike the direction bridges this is not
+/// change-guarded: an accumulator changes every tick by definition.
+#[allow(clippy::type_complexity)]
+pub fn compute_local_radiation(
+    mut commands: Commands,
+    env: Option<Res<RadiationEnvironment>>,
+    world_time: Option<Res<WorldTime>>,
+    mut q_targets: Query<
+        (
+            Entity,
+            Option<&mut LocalRadiation>,
+            Option<&RadiationShielding>,
+            Option<&RadiationSkyFraction>,
+            Option<&RadiationDoseLimit>,
+            Option<&GlobalEntityId>,
+        ),
+        With<crate::EnvironmentProbe>,
+    >,
+) {
+    let (Some(env), Some(world_time)) = (env, world_time) else {
+        return;
+    };
+    let jd = world_time.epoch_jd;
+    for (entity, existing, shielding, sky, limit, gid) in &mut q_targets {
+        let (gcr, spe) = shielded_dose_rates(
+            &env,
+            jd,
+            sky.copied().unwrap_or_default().0 as f64,
+            shielding.map_or(0.0, |s| s.areal_density_g_cm2 as f64),
+        );
+        let equivalent = env.gcr_quality_factor * gcr + env.spe_quality_factor * spe;
+        let Some(mut local) = existing else {
+            commands.entity(entity).try_insert(LocalRadiation {
+                gcr_dose_rate: gcr,
+                spe_dose_rate: spe,
+                dose_equivalent_rate: equivalent,
+                last_jd: jd,
+                ..default()
+            });
+            continue;
+        };
+        let dt = (jd - local.last_jd) * SECONDS_PER_DAY;
+        if dt > 0.0 {
+            local.accumulated_dose += (gcr + spe) * dt;
+            local.accumulated_dose_equivalent += equivalent * dt;
+        }
+        local.last_jd = jd;
+        local.gcr_dose_rate = gcr;
+        local.spe_dose_rate = spe;
+        local.dose_equivalent_rate = equivalent;
+
+        let Some(limit) = limit.filter(|l| l.sievert > 0.0) else {
+            continue;
+        };
+        if !local.limit_reported && local.accumulated_dose_equivalent >= limit.sievert {
+            local.limit_reported = true;
+            warn!(
+                "[environment] probe {entity:?} crossed its {} Sv dose limit ({:.4} Sv)",
+                limit.sievert, local.accumulated_dose_equivalent
+            );
+            commands.trigger(TelemetryEvent {
+                name: "radiation.dose_limit".into(),
+                source: gid.map_or(0, |g| g.get()),
+                severity: Severity::Warning,
+                data: TelemetryValue::F64(local.accumulated_dose_equivalent),
+                timestamp: jd,
+            });
+        }
+    }
+}
+
+/// Publishes each probe's [`LocalRadiation`] as `SimComponent` **outputs**
+/// ([`DOSE_RATE_CONNECTOR`] and siblings). Same contract as the other bridges:
+/// written every tick before propagation, and removed when there is no data.
+pub fn inject_local_radiation_into_cosim(
+    mut q: Query<
+        (Option<&LocalRadiation>, &mut lunco_cosim::SimComponent),
+        With<crate::EnvironmentProbe>,
+    >,
+) {
+    for (radiation, mut comp) in &mut q {
+        let Some(r) = radiation else {
+            for name in [
+                GCR_DOSE_RATE_CONNECTOR,
+                SPE_DOSE_RATE_CONNECTOR,
+                DOSE_RATE_CONNECTOR,
+                DOSE_EQUIVALENT_RATE_CONNECTOR,
+                ACCUMULATED_DOSE_CONNECTOR,
+                ACCUMULATED_DOSE_EQUIVALENT_CONNECTOR,
+            ] {
+                comp.outputs.remove(name);
+            }
+            continue;
+        };
+        for (name, value) in [
+            (GCR_DOSE_RATE_CONNECTOR, r.gcr_dose_rate),
+            (SPE_DOSE_RATE_CONNECTOR, r.spe_dose_rate),
+            (DOSE_RATE_CONNECTOR, r.dose_rate()),
+            (DOSE_EQUIVALENT_RATE_CONNECTOR, r.dose_equivalent_rate),
+            (ACCUMULATED_DOSE_CONNECTOR, r.accumulated_dose),
+            (
+                ACCUMULATED_DOSE_EQUIVALENT_CONNECTOR,
+                r.accumulated_dose_equivalent,
+            ),
+        ] {
+            comp.outputs.insert(name.to_string(), value);
+        }
+    }
+}
+
+/// Moves the solar-cycle phase that sets the GCR baseline.
+#[Command(default)]
+pub struct SetSolarCyclePhase {
+    /// `0..1`; `0` is solar minimum, `0.5` solar maximum.
+    pub phase: f64,
+}
+
+#[on_command(SetSolarCyclePhase)]
+fn on_set_solar_cycle_phase(
+    _t: On<SetSolarCyclePhase>,
+    mut env: ResMut<RadiationEnvironment>,
+) -> Result<Ack, String> {
+    if !cmd.phase.is_finite() || !(0.0..=1.0).contains(&cmd.phase) {
+        return Err(format!(
+            "SetSolarCyclePhase: phase must be in [0, 1], got {}",
+            cmd.phase
+        ));
+    }
+    env.solar_cycle_phase = cmd.phase;
+    info!(
+        "[environment] solar-cycle phase {:.2} → free-space GCR {:.1} µGy/h",
+        cmd.phase,
+        env.gcr_free_space_gy_h() * 1.0e6
+    );
+    Ok(Ack::new(OpId::new()))
+}
+
+/// Schedules a solar particle event from an authored free-space profile.
+/// Re-scheduling an existing `name` replaces it.
+#[Command(default)]
+pub struct ScheduleSolarParticleEvent {
+    pub name: String,
+    /// Onset epoch, TDB Julian Date.
+    pub onset_jd: f64,
+    /// `(hours after onset, free-space Gy/h)` points, ascending in time.
+    pub profile: Vec<(f64, f64)>,
+}
+
+#[on_command(ScheduleSolarParticleEvent)]
+fn on_schedule_solar_particle_event(
+    _t: On<ScheduleSolarParticleEvent>,
+    mut env: ResMut<RadiationEnvironment>,
+) -> Result<Ack, String> {
+    if cmd.name.is_empty() {
+        return Err("ScheduleSolarParticleEvent: name must not be empty".into());
+    }
+    if !cmd.onset_jd.is_finite() {
+        return Err("ScheduleSolarParticleEvent: onset_jd must be finite".into());
+    }
+    if cmd.profile.is_empty() {
+        return Err("ScheduleSolarParticleEvent: profile has no points".into());
+    }
+    if cmd
+        .profile
+        .iter()
+        .any(|(t, d)| !t.is_finite() || !d.is_finite() || *d < 0.0)
+    {
+        return Err(
+            "ScheduleSolarParticleEvent: profile points must be finite with non-negative dose"
+                .into(),
+        );
+    }
+    if cmd.profile.windows(2).any(|w| w[1].0 < w[0].0) {
+        return Err("ScheduleSolarParticleEvent: profile times must ascend".into());
+    }
+    let event = SolarParticleEvent {
+        name: cmd.name.clone(),
+        onset_jd: cmd.onset_jd,
+        profile: cmd.profile.clone(),
+    };
+    env.events.retain(|e| e.name != event.name);
+    info!(
+        "[environment] SPE '{}' scheduled at JD {:.5} ({} profile points)",
+        event.name,
+        event.onset_jd,
+        event.profile.len()
+    );
+    env.events.push(event);
+    Ok(Ack::new(OpId::new()))
+}
+
+register_commands!(on_set_solar_cycle_phase, on_schedule_solar_particle_event);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcr_is_highest_at_solar_minimum() {
+        let mut env = RadiationEnvironment::default();
+        let min = env.gcr_free_space_gy_h();
+        env.solar_cycle_phase = 0.5;
+        let max = env.gcr_free_space_gy_h();
+        assert!((min - env.gcr_dose_rate_solar_min_gy_h).abs() < 1e-12);
+        assert!((max - env.gcr_dose_rate_solar_max_gy_h).abs() < 1e-12);
+    }
+
+    #[test]
+    fn spe_profile_interpolates_and_is_zero_outside() {
+        let event = SolarParticleEvent {
+            name: "test".into(),
+            onset_jd: 100.0,
+            profile: vec![(0.0, 0.0), (2.0, 0.01), (10.0, 0.0)],
+        };
+        assert_eq!(event.dose_rate_at(99.0), 0.0);
+        assert!((event.dose_rate_at(100.0 + 1.0 / 24.0) - 0.005).abs() < 1e-9);
+        assert!((event.dose_rate_at(100.0 + 6.0 / 24.0) - 0.005).abs() < 1e-9);
+        assert_eq!(event.dose_rate_at(101.0), 0.0);
+    }
+
+    #[test]
+    fn shielding_stops_spe_far_more_than_gcr() {
+        let mut env = RadiationEnvironment::default();
+        env.events.push(SolarParticleEvent {
+            name: "flat".into(),
+            onset_jd: 0.0,
+            profile: vec![(0.0, 0.01), (48.0, 0.01)],
+        });
+        let (gcr0, spe0) = shielded_dose_rates(&env, 0.5, 1.0, 0.0);
+        let (gcr, spe) = shielded_dose_rates(&env, 0.5, 1.0, 10.0);
+        assert!(spe / spe0 < 0.1);
+        assert!(gcr / gcr0 > 0.9);
+        // The lunar surface blocks half the sky.
+        let (surface, _) = shielded_dose_rates(&env, 0.5, 0.5, 0.0);
+        assert!((surface - gcr0 * 0.5).abs() < 1e-15);
+    }
+
+    #[test]
+    fn dose_accumulates_over_world_epoch_advance() {
+        let mut app = App::new();
+        app.init_resource::<RadiationEnvironment>();
+        app.insert_resource(WorldTime::default());
+        app.add_systems(Update, compute_local_radiation);
+        let probe = app.world_mut().spawn(crate::EnvironmentProbe).id();
+        app.update();
+        app.world_mut().resource_mut::<WorldTime>().epoch_jd += 1.0 / 24.0;
+        app.update();
+        let local = app.world().get::<LocalRadiation>(probe).unwrap();
+        let expected = RadiationEnvironment::default().gcr_free_space_gy_h();
+        assert!((local.accumulated_dose - expected).abs() < expected * 1e-6);
+    }
+
+    #[derive(Resource, Default)]
+    struct Fired(Vec<(String, u64)>);
+
+    /// Crossing the limit fires `radiation.dose_limit` from the probe's global
+    /// id once, not on every tick the probe stays over it.
+    #[test]
+    fn crossing_the_dose_limit_fires_once() {
+        let mut app = App::new();
+        app.init_resource::<RadiationEnvironment>();
+        app.insert_resource(WorldTime::default());
+        app.init_resource::<Fired>();
+        app.add_observer(|t: On<TelemetryEvent>, mut fired: ResMut<Fired>| {
+            fired.0.push((t.event().name.clone(), t.event().source));
+        });
+        app.add_systems(Update, compute_local_radiation);
+        // Free-space GCR at solar minimum is ~114 µSv/h of dose equivalent, so
+        // a 200 µSv limit is crossed during the second hour.
+        let gid = GlobalEntityId::from_raw(42);
+        let probe = app
+            .world_mut()
+            .spawn((
+                crate::EnvironmentProbe,
+                RadiationDoseLimit { sievert: 200.0e-6 },
+                gid,
+            ))
+            .id();
+        let advance_an_hour = |app: &mut App| {
+            app.world_mut().resource_mut::<WorldTime>().epoch_jd += 1.0 / 24.0;
+            app.update();
+        };
+        app.update();
+        advance_an_hour(&mut app);
+        assert!(app.world().resource::<Fired>().0.is_empty());
+        advance_an_hour(&mut app);
+        advance_an_hour(&mut app);
+        assert_eq!(
+            app.world().resource::<Fired>().0,
+            [("radiation.dose_limit".to_string(), gid.get())]
+        );
+        assert!(
+            app.world()
+                .get::<LocalRadiation>(probe)
+                .unwrap()
+                .limit_reported
+        );
+    }
+}
@@ -0,0 +1,298 @@
+//! Solar array illumination — how much of a panel the sun actually reaches, as
+//! a co-simulation source.
+//!
+//! `LunCo.Electrical.SolarPanel` turns an `irradiance` input and a mount-frame
+//! sun vector into power. The sun vector has always come from the probe; the
+//! irradiance was an authored constant, so a panel at the bottom of a polar
+//! crater, or behind its own rover's mast at a grazing sun, still made full
+//! power. This module supplies the real number.
+//!
+//! ## The footprint
+//!
+//! A probe carrying a [`SolarArrayFootprint`] stands for a rectangular face
+//! centred on it, spanning the probe's mount `X` (width) and `Z` (length) axes
+//! with the mount `+Y` axis as its normal — the illuminated-face convention the
+//! panel model already uses. The face is sampled on an `n × n` grid of cell
+//! centres. A probe without one is a single sample at its own origin.
+//!
+//! ## The shading
+//!
+//! Each sample is lit by the product of two occluders:
+//!
+//! * **terrain** — [`crate::horizon::terrain_sun_visibility`], the cached
+//!   horizon shadow the terrain shader draws and the thermal domain reads, so a
+//!   panel in a crater's shadow is dark exactly where the viewer sees shadow;
+//! * **structure** — a ray toward the sun through the avian colliders within
+//!   [`STRUCTURE_OCCLUDER_RANGE_M`]: the vehicle's own mast, antenna and body,
+//!   and anything parked beside it. Beyond that range the horizon owns the
+//!   shading. The ray leaves from just above the face
+//!   ([`SAMPLE_STANDOFF_M`] along its normal), so the panel never shades itself.
+//!
+//! The published irradiance is the solar constant of
+//! [`ThermalEnvironment`](crate::ThermalEnvironment), scaled by the live sun's
+//! 1/r² and by the lit fraction. It stays a NORMAL-INCIDENCE value: the panel
+//! model applies the cosine itself from the sun vector it already receives.
+//! A face turned away from the sun is reported unlit.
+
+use bevy::math::Dir3;
+use bevy::prelude::*;
+
+use avian3d::prelude::SpatialQueryFilter;
+use lunco_core::coords::RenderPos;
+use lunco_cosim::{ILLUMINATED_FRACTION_CONNECTOR, SOLAR_IRRADIANCE_CONNECTOR};
+use lunco_physics::GridSpatialQuery;
+
+use crate::horizon::{HorizonMap, HorizonShadowCache, HorizonShadowCacheConfig};
+
+/// Reach of the structure ray, metres. Covers a vehicle and its neighbours;
+/// farther occluders are terrain, which the horizon cache already shades.
+pub const STRUCTURE_OCCLUDER_RANGE_M: f64 = 50.0;
+
+/// Lift of each sample off the face along its normal before the structure ray
+/// leaves, metres — clears the panel's own cell and frame colliders.
+pub const SAMPLE_STANDOFF_M: f32 = 0.05;
+
+/// Most samples per axis a footprint may request (32² rays per panel per tick).
+const MAX_SAMPLES_PER_AXIS: u32 = 32;
+
+/// The illuminated face a probe stands for. Dimensions are in the probe's own
+/// local units, so a panel referenced with a scale shrinks its footprint with it.
+///
+/// Authored on the probe prim as `lunco:solar:width`, `lunco:solar:length` and
+/// `lunco:solar:samples`.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct SolarArrayFootprint {
+    /// Extent along the mount `X` axis.
+    pub width: f32,
+    /// Extent along the mount `Z` axis.
+    pub length: f32,
+    /// Samples per axis; the face is tested at `samples²` points.
+    pub samples: u32,
+}
+
+impl Default for SolarArrayFootprint {
+    fn default() -> Self {
+        Self {
+            width: 1.0,
+            length: 1.0,
+            samples: 4,
+        }
+    }
+}
+
+impl SolarArrayFootprint {
+    /// Mount-local sample points: the cell centres of a `samples × samples`
+    /// grid over the face, in the mount `XZ` plane.
+    pub fn sample_points(&self) -> impl Iterator<Item = Vec3> + '_ {
+        let n = self.samples.clamp(1, MAX_SAMPLES_PER_AXIS);
+        (0..n * n).map(move |i| {
+            let u = ((i % n) as f32 + 0.5) / n as f32 - 0.5;
+            let v = ((i / n) as f32 + 0.5) / n as f32 - 0.5;
+            Vec3::new(u * self.width, 0.0, v * self.length)
+        })
+    }
+}
+
+/// Sunlight reaching an entity's array, cached per entity like
+/// [`LocalThermal`](crate::LocalThermal).
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, Default)]
+#[reflect(Component)]
+pub struct LocalSolarIrradiance {
+    /// Fraction of the face the sun reaches, 0..1.
+    pub illuminated_fraction: f32,
+    /// Normal-incidence irradiance over the whole face, W/m²: the distance-scaled
+    /// solar constant times [`illuminated_fraction`](Self::illuminated_fraction).
+    pub irradiance: f32,
+}
+
+/// Mean lit fraction over a set of sample points — the pure half of
+/// [`compute_local_solar_irradiance`]. `lit` returns each point's visibility,
+/// 0..1; an empty set is dark.
+pub fn lit_fraction(
+    points: impl IntoIterator<Item = Vec3>,
+    mut lit: impl FnMut(Vec3) -> f32,
+) -> f32 {
+    let (sum, count) = points.into_iter().fold((0.0, 0u32), |(sum, count), p| {
+        (sum + lit(p).clamp(0.0, 1.0), count + 1)
+    });
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f32
+    }
+}
+
+/// Computes [`LocalSolarIrradiance`] for every explicit environment probe.
+///
+/// Same sun, same 1/r² and same horizon shadow as
+/// [`compute_local_thermal`](crate::compute_local_thermal), plus the structure
+/// ray per sample. Change-guarded; with no sun the cache is removed so the
+/// panel reads "unavailable" rather than a frozen noon.
+#[allow(clippy::type_complexity)]
+pub fn compute_local_solar_irradiance(
+    mut commands: Commands,
+    env: Option<Res<crate::ThermalEnvironment>>,
+    sun_cal: Option<Res<crate::LunarSun>>,
+    shadow_config: Option<Res<HorizonShadowCacheConfig>>,
+    images: Option<Res<Assets<Image>>>,
+    spatial: GridSpatialQuery,
+    q_sun: crate::horizon::SunQuery,
+    q_terrain: Query<(&GlobalTransform, &HorizonMap, Option<&HorizonShadowCache>)>,
+    q_targets: Query<
+        (
+            Entity,
+            Option<&LocalSolarIrradiance>,
+            Option<&GlobalTransform>,
+            Option<&SolarArrayFootprint>,
+        ),
+        With<crate::EnvironmentProbe>,
+    >,
+) {
+    if q_targets.is_empty() {
+        return;
+    }
+    let sun = crate::horizon::pick_sun(&q_sun).and_then(|(sun_gt, tan_sun_r, _)| {
+        let d: Vec3 = *sun_gt.back();
+        (d.is_finite() && d.length_squared() > 1e-12).then(|| (d.normalize(), tan_sun_r))
+    });
+    let Some((to_sun, tan_sun_r)) = sun else {
+        for (entity, existing, _, _) in &q_targets {
+            if existing.is_some() {
+                commands.entity(entity).remove::<LocalSolarIrradiance>();
+            }
+        }
+        return;
+    };
+    let Ok(sun_dir) = Dir3::new(to_sun) else {
+        return;
+    };
+    let solar_constant = env.map(|e| e.solar_constant_w_m2).unwrap_or(1361.0);
+    let sun_scale = crate::thermal::sun_distance_scale(&q_sun, sun_cal.as_deref());
+    let shadow_config = shadow_config.map(|c| *c).unwrap_or_default();
+    let filter = SpatialQueryFilter::default();
+
+    for (entity, existing, mount, footprint) in &q_targets {
+        let mount = mount.copied().unwrap_or_default();
+        let normal = (mount.rotation() * Vec3::Y).normalize_or(Vec3::Y);
+        let fraction = if normal.dot(to_sun) <= 0.0 {
+            0.0
+        } else {
+            let points: Vec<Vec3> = match footprint {
+                Some(f) => f.sample_points().collect(),
+                None => vec![Vec3::ZERO],
+            };
+            lit_fraction(points, |local| {
+                let world = mount.transform_point(local);
+                let terrain = crate::horizon::terrain_sun_visibility(
+                    world,
+                    to_sun,
+                    tan_sun_r,
+                    &shadow_config,
+                    images.as_deref(),
+                    &q_terrain,
+                )
+                .unwrap_or(if to_sun.y > 0.0 { 1.0 } else { 0.0 });
+                if terrain <= 0.0 {
+                    return 0.0;
+                }
+                let origin = (world + normal * SAMPLE_STANDOFF_M).as_dvec3();
+                let blocked = spatial
+                    .cast_ray_render(
+                        RenderPos(origin),
+                        sun_dir,
+                        STRUCTURE_OCCLUDER_RANGE_M,
+                        true,
+                        &filter,
+                    )
+                    .is_some();
+                if blocked {
+                    0.0
+                } else {
+                    terrain
+                }
+            })
+        };
+        let next = LocalSolarIrradiance {
+            illuminated_fraction: fraction,
+            irradiance: solar_constant * sun_scale.max(0.0) * fraction,
+        };
+        if existing == Some(&next) {
+            continue;
+        }
+        commands.entity(entity).try_insert(next);
+    }
+}
+
+/// Publishes each entity's [`LocalSolarIrradiance`] as `SimComponent`
+/// **outputs** [`SOLAR_IRRADIANCE_CONNECTOR`] and
+/// [`ILLUMINATED_FRACTION_CONNECTOR`].
+///
+/// Same contract as the thermal bridge: writes every tick before cosim
+/// propagation, and with no data removes only its own outputs.
+pub fn inject_local_solar_irradiance_into_cosim(
+    mut q: Query<
+        (
+            Option<&LocalSolarIrradiance>,
+            &mut lunco_cosim::SimComponent,
+        ),
+        With<crate::EnvironmentProbe>,
+    >,
+) {
+    for (solar, mut comp) in &mut q {
+        let Some(s) = solar else {
+            comp.outputs.remove(SOLAR_IRRADIANCE_CONNECTOR);
+            comp.outputs.remove(ILLUMINATED_FRACTION_CONNECTOR);
+            continue;
+        };
+        comp.outputs
+            .insert(SOLAR_IRRADIANCE_CONNECTOR.to_string(), s.irradiance as f64);
+        comp.outputs.insert(
+            ILLUMINATED_FRACTION_CONNECTOR.to_string(),
+            s.illuminated_fraction as f64,
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn footprint_samples_cell_centres_over_the_face() {
+        let f = SolarArrayFootprint {
+            width: 6.0,
+            length: 3.0,
+            samples: 2,
+        };
+        let points: Vec<Vec3> = f.sample_points().collect();
+        assert_eq!(
+            points,
+            vec![
+                Vec3::new(-1.5, 0.0, -0.75),
+                Vec3::new(1.5, 0.0, -0.75),
+                Vec3::new(-1.5, 0.0, 0.75),
+                Vec3::new(1.5, 0.0, 0.75),
+            ]
+        );
+        // A zero request still tests the centre rather than nothing.
+        let single = SolarArrayFootprint { samples: 0, ..f };
+        assert_eq!(single.sample_points().collect::<Vec<_>>(), vec![Vec3::ZERO]);
+    }
+
+    #[test]
+    fn a_mast_shadow_over_a_quarter_of_the_face_costs_a_quarter() {
+        let f = SolarArrayFootprint {
+            width: 4.0,
+            length: 4.0,
+            samples: 8,
+        };
+        // A shadow covering x > 1 m: the outer quarter of the width.
+        let fraction = lit_fraction(f.sample_points(), |p| if p.x > 1.0 { 0.0 } else { 1.0 });
+        assert!((fraction - 0.75).abs() < 1e-6, "got {fraction}");
+        // Terrain penumbra multiplies in as a partial visibility.
+        let dim = lit_fraction(f.sample_points(), |_| 0.4);
+        assert!((dim - 0.4).abs() < 1e-6);
+        assert_eq!(lit_fraction(std::iter::empty(), |_| 1.0), 0.0);
+    }
+}
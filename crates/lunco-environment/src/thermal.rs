@@ -0,0 +1,361 @@
+//! Thermal environment domain — the radiative fluxes a surface sees, as a
+//! co-simulation source.
+//!
+//! A rover electronics box does not care where the sun is; it cares how many
+//! watts land on each face and how cold the sky it radiates into is. This
+//! module turns the same providers the direction bridges read (the scene sun,
+//! [`EarthDirectionWorld`](crate::EarthDirectionWorld), the baked horizon
+//! terrain) into those watts, per [`crate::EnvironmentProbe`] and per face, and
+//! publishes them as ordinary `SimComponent` outputs so a Modelica thermal
+//! network receives them through a plain output→input wire.
+//!
+//! ## The face
+//!
+//! The probe's mount `+Y` axis is the surface normal — the same authored mount
+//! frame the Sun/Earth vectors are expressed in. A box with six radiating faces
+//! authors six probes, one per face orientation; the environment stays a
+//! per-normal source and never learns what the model's geometry is.
+//!
+//! ## The fluxes
+//!
+//! With `n` the face normal, `s` the direction to the Sun, `e` to Earth and `u`
+//! the local vertical (world `+Y`, site ENU):
+//!
+//! * **solar** — `S · max(0, n·s) · v`, where `S` is the solar constant scaled by
+//!   the live sun's 1/r² (the key light already carries it; see
+//!   [`LunarSun`](crate::LunarSun)) and `v` the terrain sun visibility from
+//!   [`crate::horizon::terrain_sun_visibility`] — the cached horizon shadow the
+//!   terrain shader draws, so a probe in a crater's shadow reads the shadow the
+//!   viewer sees.
+//! * **albedo** — regolith: `A · S · max(0, s·u) · v · F_g`, a Lambertian ground
+//!   seen over the view factor `F_g = (1 − n·u)/2`; plus Earth: `A_E · S ·
+//!   φ · (R_E/d_E)² · max(0, n·e)` with `φ` Earth's lit fraction (the same
+//!   phase [`drive_earthshine_from_phase`](crate::drive_earthshine_from_phase)
+//!   uses).
+//! * **planetary IR** — `ε σ T_s⁴ · F_g`, with the regolith surface temperature
+//!   `T_s` from instantaneous radiative equilibrium,
+//!   `T_s = max(T_night, (S (1−A) max(0, s·u) v / (ε σ))^¼)`. Lunar regolith has
+//!   a tiny thermal inertia, so equilibrium tracks the measured Diviner day-side
+//!   curve closely; the night floor stands in for the slow conductive tail.
+//! * **deep-space sink** — `T_sink` over the remaining view factor
+//!   `F_s = (1 + n·u)/2`.
+//!
+//! The environment publishes INCIDENT flux only. Absorptivity, emissivity and
+//! the face area belong to the thermal model, which is where they are authored.
+//!
+//! ## Limits (v1)
+//!
+//! - The ground is an infinite plane under the probe: view factors ignore local
+//!   slopes and crater walls, and the shadow is sampled at the probe's own cell.
+//! - A probe outside every baked horizon terrain falls back to the geometric
+//!   horizon (`s·u > 0`).
+
+use bevy::prelude::*;
+
+use lunco_cosim::{
+    ALBEDO_FLUX_CONNECTOR, PLANET_IR_FLUX_CONNECTOR, SINK_TEMPERATURE_CONNECTOR,
+    SOLAR_FLUX_CONNECTOR, SPACE_VIEW_FACTOR_CONNECTOR, SURFACE_TEMPERATURE_CONNECTOR,
+};
+
+use crate::horizon::{HorizonMap, HorizonShadowCache, HorizonShadowCacheConfig};
+
+/// Stefan–Boltzmann constant, W/(m²·K⁴).
+pub const STEFAN_BOLTZMANN: f32 = 5.670_374e-8;
+
+/// Radiative parameters of the thermal environment — the provider half of the
+/// domain, the thermal analog of `GravityProvider`.
+///
+/// Defaults describe the Moon at 1 AU. A scene elsewhere `insert_resource`s
+/// its own before [`EnvironmentPlugin`](crate::EnvironmentPlugin) runs
+/// (`init_resource` then leaves it alone), exactly as [`LunarSun`](crate::LunarSun).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct ThermalEnvironment {
+    /// Total solar irradiance at 1 AU, W/m². The live 1/r² scaling comes from
+    /// the sun light, not from here.
+    pub solar_constant_w_m2: f32,
+    /// Bond albedo of the surface regolith (~0.12 for the lunar maria/highland mix).
+    pub regolith_albedo: f32,
+    /// Broadband IR emissivity of the surface regolith.
+    pub regolith_emissivity: f32,
+    /// Night-side surface temperature floor, K (Diviner equatorial pre-dawn ≈ 95 K).
+    pub night_temperature_k: f32,
+    /// Bond albedo of Earth, for reflected earthlight.
+    pub earth_albedo: f32,
+    /// Earth's radius over its distance — the angular-size factor of earthlight.
+    /// Mean Earth–Moon geometry by default (6371 km / 384 400 km).
+    pub earth_radius_over_distance: f32,
+    /// Deep-space radiative sink temperature, K.
+    pub sink_temperature_k: f32,
+}
+
+impl Default for ThermalEnvironment {
+    fn default() -> Self {
+        Self {
+            solar_constant_w_m2: 1361.0,
+            regolith_albedo: 0.12,
+            regolith_emissivity: 0.95,
+            night_temperature_k: 95.0,
+            earth_albedo: 0.3,
+            earth_radius_over_distance: 6_371.0 / 384_400.0,
+            sink_temperature_k: 2.7,
+        }
+    }
+}
+
+/// Radiative environment incident on an entity's mount `+Y` face.
+///
+/// Cached per-entity like [`LocalSolar`](crate::LocalSolar): two probes on
+/// differently-oriented faces of the same box read genuinely different fluxes.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Reflect, Default)]
+#[reflect(Component)]
+pub struct LocalThermal {
+    /// Direct sunlight on the face, W/m².
+    pub solar_flux: f32,
+    /// Reflected sunlight on the face (regolith + Earth albedo), W/m².
+    pub albedo_flux: f32,
+    /// Regolith thermal IR on the face, W/m².
+    pub planet_ir_flux: f32,
+    /// Fraction of the face's hemisphere that sees deep space, 0..1.
+    pub space_view_factor: f32,
+    /// Deep-space sink temperature, K.
+    pub sink_temperature: f32,
+    /// Modelled regolith surface temperature under the probe, K.
+    pub surface_temperature: f32,
+}
+
+/// The fluxes on one face — the pure half of [`compute_local_thermal`], kept
+/// separate so the radiometry is testable without a scene.
+///
+/// All directions are unit world vectors (`to_earth` may be `None` when no
+/// ephemeris has placed Earth). `sun_scale` is the live 1/r² factor and
+/// `visibility` the terrain sun visibility at the probe, 0..1.
+pub fn face_thermal(
+    env: &ThermalEnvironment,
+    normal: Vec3,
+    to_sun: Vec3,
+    to_earth: Option<Vec3>,
+    sun_scale: f32,
+    visibility: f32,
+) -> LocalThermal {
+    let up = Vec3::Y;
+    let s = env.solar_constant_w_m2 * sun_scale.max(0.0);
+    let vis = visibility.clamp(0.0, 1.0);
+    let n_up = normal.dot(up).clamp(-1.0, 1.0);
+    let ground_view = (1.0 - n_up) * 0.5;
+    let space_view = (1.0 + n_up) * 0.5;
+
+    let solar_flux = s * normal.dot(to_sun).max(0.0) * vis;
+
+    // Sunlight reaching the ground under the probe: the same shadow applies —
+    // the probe samples its own cell, so its ground is lit exactly when it is.
+    let ground_insolation = s * to_sun.dot(up).max(0.0) * vis;
+    let regolith_albedo_flux = env.regolith_albedo * ground_insolation * ground_view;
+
+    let earth_albedo_flux = to_earth
+        .filter(|e| e.dot(up) > 0.0)
+        .map(|e| {
+            let lit_fraction = ((1.0 - to_sun.dot(e)) * 0.5).clamp(0.0, 1.0);
+            s * env.earth_albedo
+                * lit_fraction
+                * env.earth_radius_over_distance.powi(2)
+                * normal.dot(e).max(0.0)
+        })
+        .unwrap_or(0.0);
+
+    let emissive = env.regolith_emissivity * STEFAN_BOLTZMANN;
+    let equilibrium = if emissive > 0.0 {
+        (ground_insolation * (1.0 - env.regolith_albedo) / emissive).powf(0.25)
+    } else {
+        0.0
+    };
+    let surface_temperature = equilibrium.max(env.night_temperature_k);
+    let planet_ir_flux = emissive * surface_temperature.powi(4) * ground_view;
+
+    LocalThermal {
+        solar_flux,
+        albedo_flux: regolith_albedo_flux + earth_albedo_flux,
+        planet_ir_flux,
+        space_view_factor: space_view,
+        sink_temperature: env.sink_temperature_k,
+        surface_temperature,
+    }
+}
+
+/// The live sun's 1/r² factor: the key light's illuminance over the 1 AU
+/// [`LunarSun`](crate::LunarSun) calibration, or 1 without a calibration. Call
+/// after [`crate::horizon::pick_sun`] has succeeded, so the query holds exactly
+/// one light.
+pub(crate) fn sun_distance_scale(
+    q_sun: &crate::horizon::SunQuery,
+    sun_cal: Option<&crate::LunarSun>,
+) -> f32 {
+    let calibration = sun_cal.map(|c| c.illuminance_lux).unwrap_or_default();
+    match q_sun.iter().next() {
+        Some((_, light, _, _)) if calibration > 0.0 => light.illuminance / calibration,
+        _ => 1.0,
+    }
+}
+
+/// Computes [`LocalThermal`] for every explicit environment probe.
+///
+/// Reads the scene sun through [`crate::horizon::pick_sun`] (the one sun every
+/// horizon system agrees on), scales the solar constant by the key light's
+/// illuminance over the 1 AU [`LunarSun`](crate::LunarSun) calibration, and
+/// shadows it with the baked horizon terrain. Change-guarded like
+/// `compute_local_solar`; with no sun the cache is removed rather than left to
+/// publish a stale day.
+#[allow(clippy::type_complexity)]
+pub fn compute_local_thermal(
+    mut commands: Commands,
+    env: Option<Res<ThermalEnvironment>>,
+    sun_cal: Option<Res<crate::LunarSun>>,
+    earth_dir: Option<Res<crate::EarthDirectionWorld>>,
+    shadow_config: Option<Res<HorizonShadowCacheConfig>>,
+    images: Option<Res<Assets<Image>>>,
+    q_sun: crate::horizon::SunQuery,
+    q_terrain: Query<(&GlobalTransform, &HorizonMap, Option<&HorizonShadowCache>)>,
+    q_targets: Query<
+        (Entity, Option<&LocalThermal>, Option<&GlobalTransform>),
+        With<crate::EnvironmentProbe>,
+    >,
+) {
+    if q_targets.is_empty() {
+        return;
+    }
+    let env = env.map(|e| *e).unwrap_or_default();
+    let sun = crate::horizon::pick_sun(&q_sun).and_then(|(sun_gt, tan_sun_r, _)| {
+        let d: Vec3 = *sun_gt.back();
+        (d.is_finite() && d.length_squared() > 1e-12).then(|| (d.normalize(), tan_sun_r))
+    });
+    let Some((to_sun, tan_sun_r)) = sun else {
+        for (entity, existing, _) in &q_targets {
+            if existing.is_some() {
+                commands.entity(entity).remove::<LocalThermal>();
+            }
+        }
+        return;
+    };
+
+    let sun_scale = sun_distance_scale(&q_sun, sun_cal.as_deref());
+    let to_earth = earth_dir
+        .map(|e| e.0)
+        .filter(|e| e.is_finite() && e.length_squared() > 1e-12)
+        .map(Vec3::normalize);
+    let shadow_config = shadow_config.map(|c| *c).unwrap_or_default();
+
+    for (entity, existing, mount) in &q_targets {
+        let (normal, position) = mount
+            .map(|gt| (gt.rotation() * Vec3::Y, gt.translation()))
+            .unwrap_or((Vec3::Y, Vec3::ZERO));
+        let visibility = crate::horizon::terrain_sun_visibility(
+            position,
+            to_sun,
+            tan_sun_r,
+            &shadow_config,
+            images.as_deref(),
+            &q_terrain,
+        )
+        .unwrap_or(if to_sun.y > 0.0 { 1.0 } else { 0.0 });
+        let next = face_thermal(
+            &env,
+            normal.normalize_or(Vec3::Y),
+            to_sun,
+            to_earth,
+            sun_scale,
+            visibility,
+        );
+        if existing == Some(&next) {
+            continue;
+        }
+        commands.entity(entity).try_insert(next);
+    }
+}
+
+/// Publishes each entity's [`LocalThermal`] as `SimComponent` **outputs**
+/// ([`SOLAR_FLUX_CONNECTOR`] and siblings).
+///
+/// Same contract as the solar bridge: runs after [`compute_local_thermal`] and
+/// before cosim propagation, writes every tick, and with no data removes only
+/// its own outputs so a thermal model reads "unavailable" rather than a frozen
+/// noon.
+pub fn inject_local_thermal_into_cosim(
+    mut q: Query<
+        (Option<&LocalThermal>, &mut lunco_cosim::SimComponent),
+        With<crate::EnvironmentProbe>,
+    >,
+) {
+    for (thermal, mut comp) in &mut q {
+        let Some(t) = thermal else {
+            for name in [
+                SOLAR_FLUX_CONNECTOR,
+                ALBEDO_FLUX_CONNECTOR,
+                PLANET_IR_FLUX_CONNECTOR,
+                SPACE_VIEW_FACTOR_CONNECTOR,
+                SINK_TEMPERATURE_CONNECTOR,
+                SURFACE_TEMPERATURE_CONNECTOR,
+            ] {
+                comp.outputs.remove(name);
+            }
+            continue;
+        };
+        for (name, value) in [
+            (SOLAR_FLUX_CONNECTOR, t.solar_flux),
+            (ALBEDO_FLUX_CONNECTOR, t.albedo_flux),
+            (PLANET_IR_FLUX_CONNECTOR, t.planet_ir_flux),
+            (SPACE_VIEW_FACTOR_CONNECTOR, t.space_view_factor),
+            (SINK_TEMPERATURE_CONNECTOR, t.sink_temperature),
+            (SURFACE_TEMPERATURE_CONNECTOR, t.surface_temperature),
+        ] {
+            comp.outputs.insert(name.to_string(), value as f64);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upward_face_at_subsolar_noon_sees_full_sun_and_no_ground() {
+        let env = ThermalEnvironment::default();
+        let t = face_thermal(&env, Vec3::Y, Vec3::Y, None, 1.0, 1.0);
+        assert!((t.solar_flux - env.solar_constant_w_m2).abs() < 1e-3);
+        assert_eq!(t.albedo_flux, 0.0);
+        assert_eq!(t.planet_ir_flux, 0.0);
+        assert_eq!(t.space_view_factor, 1.0);
+        // Equatorial noon on the Moon is ~390 K.
+        assert!(
+            (370.0..400.0).contains(&t.surface_temperature),
+            "noon regolith at {} K",
+            t.surface_temperature
+        );
+    }
+
+    #[test]
+    fn shadowed_side_face_sees_only_the_cold_ground() {
+        let env = ThermalEnvironment::default();
+        let sun = Vec3::new(1.0, 1.0, 0.0).normalize();
+        let t = face_thermal(&env, Vec3::X, sun, None, 1.0, 0.0);
+        assert_eq!(t.solar_flux, 0.0);
+        assert_eq!(t.albedo_flux, 0.0);
+        assert_eq!(t.surface_temperature, env.night_temperature_k);
+        let expected =
+            env.regolith_emissivity * STEFAN_BOLTZMANN * env.night_temperature_k.powi(4) * 0.5;
+        assert!((t.planet_ir_flux - expected).abs() < 1e-4);
+        assert!((t.space_view_factor - 0.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn earthlight_follows_phase_and_distance_scaling() {
+        let env = ThermalEnvironment::default();
+        let earth = Vec3::new(0.0, 0.5, -1.0).normalize();
+        // Sun opposite Earth → full Earth; sun beside Earth → new Earth.
+        let full = face_thermal(&env, earth, -earth, Some(earth), 1.0, 0.0);
+        let new = face_thermal(&env, earth, earth, Some(earth), 1.0, 0.0);
+        assert!(full.albedo_flux > 0.05 && full.albedo_flux < 0.2);
+        assert!(new.albedo_flux.abs() < 1e-6);
+        let far = face_thermal(&env, earth, -earth, Some(earth), 0.25, 0.0);
+        assert!((far.albedo_flux - full.albedo_flux * 0.25).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,1328 @@
+//! Parameter estimation — fit model parameters to measured trajectories.
+//!
+//! An [`EstimationSpec`] names the parameters to fit (each a [`ParamPath`]
+//! with bounds), the signals the fit is judged on, and the [`Optimizer`]. The
+//! host crate supplies a `simulate` callback that runs the model with a set of
+//! parameter values — for Modelica, one compiled DAE rebound per call, so a
+//! fit never recompiles — and [`estimate`] drives it against the measured
+//! [`RunResult`] (a reference run, or recorded telemetry via
+//! [`measured_from_samples`]).
+//!
+//! ## The cost
+//!
+//! Weighted least squares. Every measured sample inside the run's span adds a
+//! residual `√(w/N)·(ŷ − y)/s`: `ŷ` the run interpolated at the sample time,
+//! `w` the signal's weight, `N` its sample count and `s` the RMS of its
+//! measurements. The `1/N` and `1/s` make signals of different length and unit
+//! comparable, so a fit over a motor current in amps and a winding temperature
+//! in kelvin is not decided by whichever happens to have the larger numbers.
+//! The cost is `Σ r²`. A run that fails costs `+∞`, which every optimizer
+//! treats as "not here".
+//!
+//! ## The optimizers
+//!
+//! All three search the unit box the bounds map onto, so one step size suits
+//! every parameter.
+//!
+//! - **Nelder–Mead** — derivative-free simplex; the robust default for a few
+//!   parameters and a cost that is noisy or kinked by events.
+//! - **L-BFGS-B** — limited-memory quasi-Newton with the bound constraints
+//!   handled by projection: variables at a bound whose gradient points out
+//!   are held, the rest take the L-BFGS step. Gradients are finite
+//!   differences, `2n` runs each; the fastest on smooth costs.
+//! - **CMA-ES** — covariance-matrix adaptation; a population search for
+//!   multi-modal costs and many parameters. Draws come from a ChaCha8 stream
+//!   seeded with [`EstimationSpec::seed`], so a fit is reproducible.
+//!
+//! ## Confidence
+//!
+//! At the optimum the residual Jacobian `J` (central differences, `2n` more
+//! runs) gives the covariance `s²(JᵀJ)⁻¹` with `s² = cost / (N − n)`, the
+//! standard error of each parameter, their correlations and a 95% interval
+//! from the large-sample normal approximation. A singular `JᵀJ` means the
+//! data cannot tell some parameters apart; their errors are left empty rather
+//! than reported as huge numbers.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use rand::RngExt;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::linear::solve;
+use crate::regression::{interpolate, xml_escape};
+use crate::{ParamPath, ParamValue, RunMeta, RunResult};
+
+/// One parameter to fit, searched within `[lower, upper]`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EstimatedParam {
+    pub path: ParamPath,
+    pub lower: f64,
+    pub upper: f64,
+    /// Starting value. `None` → the middle of the bounds.
+    #[serde(default)]
+    pub initial: Option<f64>,
+}
+
+impl EstimatedParam {
+    fn start(&self) -> f64 {
+        self.initial.unwrap_or(0.5 * (self.lower + self.upper))
+    }
+}
+
+/// One signal the fit is judged on.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FitSignal {
+    /// Model variable.
+    pub name: String,
+    /// Measured series to compare it with. `None` → the same name.
+    #[serde(default)]
+    pub measured: Option<String>,
+    /// Relative weight in the cost.
+    #[serde(default = "unit_weight")]
+    pub weight: f64,
+}
+
+fn unit_weight() -> f64 {
+    1.0
+}
+
+impl FitSignal {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            measured: None,
+            weight: 1.0,
+        }
+    }
+
+    pub fn measured_name(&self) -> &str {
+        self.measured.as_deref().unwrap_or(&self.name)
+    }
+}
+
+/// The search algorithm.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Optimizer {
+    #[default]
+    NelderMead,
+    Lbfgsb,
+    CmaEs,
+}
+
+impl Optimizer {
+    /// Parse the command / CLI token. Empty means the default.
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "" | "nelder_mead" | "simplex" => Some(Self::NelderMead),
+            "lbfgsb" | "l_bfgs_b" | "lbfgs" => Some(Self::Lbfgsb),
+            "cma_es" | "cmaes" | "cma" => Some(Self::CmaEs),
+            _ => None,
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::NelderMead => "Nelder–Mead",
+            Self::Lbfgsb => "L-BFGS-B",
+            Self::CmaEs => "CMA-ES",
+        }
+    }
+}
+
+/// What to fit, against what, and how.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EstimationSpec {
+    pub parameters: Vec<EstimatedParam>,
+    pub signals: Vec<FitSignal>,
+    pub optimizer: Optimizer,
+    /// Run budget for the search (the confidence step adds `2n` more).
+    pub max_evaluations: usize,
+    /// Relative cost change below which the search has converged.
+    pub tolerance: f64,
+    /// Seed for CMA-ES draws.
+    pub seed: u64,
+    /// Measured time minus model time — the model's `t = 0` on the
+    /// measurement's clock.
+    pub time_offset: f64,
+}
+
+impl Default for EstimationSpec {
+    fn default() -> Self {
+        Self {
+            parameters: Vec::new(),
+            signals: Vec::new(),
+            optimizer: Optimizer::NelderMead,
+            max_evaluations: 400,
+            tolerance: 1e-8,
+            seed: 0,
+            time_offset: 0.0,
+        }
+    }
+}
+
+impl EstimationSpec {
+    /// Check the spec against the measured data before any run is spent.
+    pub fn validate(&self, measured: &RunResult) -> Result<(), String> {
+        if self.parameters.is_empty() {
+            return Err("no parameters to estimate".into());
+        }
+        if self.signals.is_empty() {
+            return Err("no signals to fit".into());
+        }
+        for p in &self.parameters {
+            let name = &p.path.0;
+            if !(p.lower.is_finite() && p.upper.is_finite() && p.lower < p.upper) {
+                return Err(format!(
+                    "`{name}`: bounds [{}, {}] must be finite with lower < upper",
+                    p.lower, p.upper
+                ));
+            }
+            let start = p.start();
+            if !(p.lower..=p.upper).contains(&start) {
+                return Err(format!(
+                    "`{name}`: initial value {start} is outside [{}, {}]",
+                    p.lower, p.upper
+                ));
+            }
+        }
+        for s in &self.signals {
+            let column = s.measured_name();
+            let Some(values) = measured.series.get(column) else {
+                return Err(format!("no measured series `{column}`"));
+            };
+            if !values.iter().any(|v| v.is_finite()) {
+                return Err(format!("measured series `{column}` has no samples"));
+            }
+            if !(s.weight.is_finite() && s.weight > 0.0) {
+                return Err(format!("`{}`: weight must be positive", s.name));
+            }
+        }
+        Ok(())
+    }
+
+    /// Model-time span the runs must cover: the measured samples of every
+    /// fitted signal, shifted by [`time_offset`](Self::time_offset).
+    pub fn time_span(&self, measured: &RunResult) -> Option<(f64, f64)> {
+        let mut span: Option<(f64, f64)> = None;
+        for s in &self.signals {
+            let Some(values) = measured.series.get(s.measured_name()) else {
+                continue;
+            };
+            for (t, v) in measured.times.iter().zip(values) {
+                if v.is_finite() && t.is_finite() {
+                    let t = t - self.time_offset;
+                    span = Some(span.map_or((t, t), |(lo, hi)| (lo.min(t), hi.max(t))));
+                }
+            }
+        }
+        span
+    }
+}
+
+/// One fitted parameter.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FittedParam {
+    pub path: ParamPath,
+    pub initial: f64,
+    pub value: f64,
+    pub lower: f64,
+    pub upper: f64,
+    /// Standard error; `None` when the data do not determine the parameter.
+    pub std_error: Option<f64>,
+    /// 95% confidence interval (normal approximation).
+    pub ci_low: Option<f64>,
+    pub ci_high: Option<f64>,
+    /// The optimum sits on a bound — widen it, or the model cannot fit
+    /// inside it.
+    pub at_bound: bool,
+}
+
+/// How one signal fits at the optimum, on the measured samples.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SignalFit {
+    pub name: String,
+    pub measured_name: String,
+    /// Model time of each sample.
+    pub times: Vec<f64>,
+    pub measured: Vec<f64>,
+    pub fitted: Vec<f64>,
+    /// `fitted − measured`, in the signal's unit.
+    pub residuals: Vec<f64>,
+    pub rmse: f64,
+}
+
+/// The outcome of [`estimate`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct EstimationReport {
+    pub model: String,
+    pub optimizer: Optimizer,
+    pub parameters: Vec<FittedParam>,
+    pub cost_initial: f64,
+    pub cost: f64,
+    /// Runs spent, confidence step included.
+    pub evaluations: usize,
+    pub iterations: usize,
+    pub converged: bool,
+    /// Residual degrees of freedom, `N − n`.
+    pub dof: usize,
+    /// Parameter correlations, row-major; empty when the covariance is not
+    /// available.
+    pub correlation: Vec<Vec<f64>>,
+    /// Best cost after each iteration.
+    pub history: Vec<f64>,
+    pub signals: Vec<SignalFit>,
+    /// The run at the fitted values.
+    #[serde(skip)]
+    pub run: Option<RunResult>,
+}
+
+impl EstimationReport {
+    /// Fitted values as overrides, for a run or a sweep base.
+    pub fn overrides(&self) -> BTreeMap<ParamPath, ParamValue> {
+        self.parameters
+            .iter()
+            .map(|p| (p.path.clone(), ParamValue::Real(p.value)))
+            .collect()
+    }
+}
+
+/// Build a measured [`RunResult`] from per-channel `(t, v)` samples —
+/// recorded telemetry, where each channel keeps its own rate. The time grid
+/// is the union of the sample times; a channel's missing slots are `NaN`
+/// (never interpolated), which the cost skips.
+pub fn measured_from_samples(channels: Vec<(String, Vec<(f64, f64)>)>) -> RunResult {
+    let mut times: Vec<f64> = channels
+        .iter()
+        .flat_map(|(_, pts)| pts.iter().map(|p| p.0))
+        .filter(|t| t.is_finite())
+        .collect();
+    times.sort_by(f64::total_cmp);
+    times.dedup();
+    let series = channels
+        .into_iter()
+        .map(|(name, mut pts)| {
+            pts.sort_by(|a, b| a.0.total_cmp(&b.0));
+            let mut column = vec![f64::NAN; times.len()];
+            for (t, v) in pts {
+                if let Ok(i) = times.binary_search_by(|probe| probe.total_cmp(&t)) {
+                    column[i] = v;
+                }
+            }
+            (name, column)
+        })
+        .collect();
+    RunResult {
+        meta: RunMeta {
+            sample_count: times.len(),
+            ..Default::default()
+        },
+        times,
+        series,
+    }
+}
+
+/// A measured signal, shifted onto model time.
+struct Target {
+    times: Vec<f64>,
+    values: Vec<f64>,
+    /// `√(w/N)/s`.
+    factor: f64,
+}
+
+impl Target {
+    fn of(spec: &EstimationSpec, signal: &FitSignal, measured: &RunResult) -> Self {
+        let (times, values): (Vec<f64>, Vec<f64>) = measured
+            .times
+            .iter()
+            .zip(&measured.series[signal.measured_name()])
+            .filter(|(t, v)| t.is_finite() && v.is_finite())
+            .map(|(t, v)| (t - spec.time_offset, *v))
+            .unzip();
+        let n = values.len().max(1) as f64;
+        let rms = (values.iter().map(|v| v * v).sum::<f64>() / n).sqrt();
+        let scale = if rms > 1e-12 { rms } else { 1.0 };
+        Self {
+            factor: (signal.weight / n).sqrt() / scale,
+            times,
+            values,
+        }
+    }
+}
+
+/// Scaled residuals of `run` against every target, in signal order.
+fn residuals(
+    signals: &[FitSignal],
+    targets: &[Target],
+    run: &RunResult,
+) -> Result<Vec<f64>, String> {
+    let (Some(&t0), Some(&t1)) = (run.times.first(), run.times.last()) else {
+        return Err("the run has no samples".into());
+    };
+    let slack = 1e-9 * (t1 - t0).abs().max(1.0);
+    let mut out = Vec::new();
+    for (signal, target) in signals.iter().zip(targets) {
+        let Some(ys) = run.series.get(&signal.name) else {
+            return Err(format!("the model has no variable `{}`", signal.name));
+        };
+        for (&t, &y) in target.times.iter().zip(&target.values) {
+            if t < t0 - slack || t > t1 + slack {
+                return Err(format!(
+                    "the run spans t = {t0} … {t1} but `{}` is measured at t = {t}",
+                    signal.name
+                ));
+            }
+            let fitted = interpolate(&run.times, ys, t);
+            if !fitted.is_finite() {
+                return Err(format!("`{}` is not finite at t = {t}", signal.name));
+            }
+            out.push(target.factor * (fitted - y));
+        }
+    }
+    Ok(out)
+}
+
+/// Fit `spec.parameters` so runs reproduce `measured`. `simulate` runs the
+/// model with the given parameter values (the host's other overrides already
+/// applied) over a span covering [`EstimationSpec::time_span`].
+pub fn estimate(
+    model: &str,
+    spec: &EstimationSpec,
+    measured: &RunResult,
+    mut simulate: impl FnMut(&BTreeMap<ParamPath, ParamValue>) -> Result<RunResult, String>,
+) -> Result<EstimationReport, String> {
+    spec.validate(measured)?;
+    let targets: Vec<Target> = spec
+        .signals
+        .iter()
+        .map(|s| Target::of(spec, s, measured))
+        .collect();
+    let params = &spec.parameters;
+    let to_physical = |z: &[f64]| -> Vec<f64> {
+        params
+            .iter()
+            .zip(z)
+            .map(|(p, z)| p.lower + z.clamp(0.0, 1.0) * (p.upper - p.lower))
+            .collect()
+    };
+    let bindings = |x: &[f64]| -> BTreeMap<ParamPath, ParamValue> {
+        params
+            .iter()
+            .zip(x)
+            .map(|(p, v)| (p.path.clone(), ParamValue::Real(*v)))
+            .collect()
+    };
+
+    let mut evaluations = 0usize;
+    let mut last_error: Option<String> = None;
+    let mut run_at = |x: &[f64]| -> Result<(Vec<f64>, RunResult), String> {
+        evaluations += 1;
+        let run = simulate(&bindings(x))?;
+        Ok((residuals(&spec.signals, &targets, &run)?, run))
+    };
+
+    let z0: Vec<f64> = params
+        .iter()
+        .map(|p| (p.start() - p.lower) / (p.upper - p.lower))
+        .collect();
+    let cost_initial = match run_at(&to_physical(&z0)) {
+        Ok((r, _)) => sum_sq(&r),
+        Err(e) => return Err(format!("the run at the initial values failed: {e}")),
+    };
+
+    let mut objective = Objective {
+        cost: &mut |z: &[f64]| match run_at(&to_physical(z)) {
+            Ok((r, _)) => sum_sq(&r),
+            Err(e) => {
+                last_error = Some(e);
+                f64::INFINITY
+            }
+        },
+        evaluations: 1,
+        budget: spec.max_evaluations.max(2),
+        best_z: z0.clone(),
+        best: cost_initial,
+        history: Vec::new(),
+    };
+    let (iterations, converged) = match spec.optimizer {
+        Optimizer::NelderMead => nelder_mead(&mut objective, &z0, spec.tolerance),
+        Optimizer::Lbfgsb => lbfgsb(&mut objective, &z0, spec.tolerance),
+        Optimizer::CmaEs => cma_es(&mut objective, &z0, spec.tolerance, spec.seed),
+    };
+    let best_z = objective.best_z.clone();
+    let history = std::mem::take(&mut objective.history);
+    drop(objective);
+
+    let x = to_physical(&best_z);
+    let (r, run) = run_at(&x).map_err(|e| {
+        format!(
+            "the run at the fitted values failed: {e}{}",
+            last_error
+                .as_deref()
+                .map(|l| format!(" (last search error: {l})"))
+                .unwrap_or_default()
+        )
+    })?;
+    let cost = sum_sq(&r);
+
+    // ── Confidence ──
+    let n = params.len();
+    let dof = r.len().saturating_sub(n);
+    let mut jac = vec![vec![0.0; n]; r.len()];
+    let mut jac_ok = dof > 0;
+    for (j, p) in params.iter().enumerate() {
+        if !jac_ok {
+            break;
+        }
+        let h = 1e-4 * (p.upper - p.lower);
+        let (lo, hi) = ((x[j] - h).max(p.lower), (x[j] + h).min(p.upper));
+        let mut at = |v: f64| {
+            let mut xv = x.clone();
+            xv[j] = v;
+            run_at(&xv).ok().map(|(r, _)| r)
+        };
+        match (at(lo), at(hi)) {
+            (Some(rl), Some(rh)) if rl.len() == r.len() && rh.len() == r.len() => {
+                for (row, (a, b)) in jac.iter_mut().zip(rl.iter().zip(&rh)) {
+                    row[j] = (b - a) / (hi - lo);
+                }
+            }
+            _ => jac_ok = false,
+        }
+    }
+    let covariance = if jac_ok {
+        covariance(&jac, cost / dof as f64)
+    } else {
+        None
+    };
+
+    let parameters = params
+        .iter()
+        .enumerate()
+        .map(|(j, p)| {
+            let std_error = covariance.as_ref().map(|c| c[j][j].sqrt());
+            let width = (p.upper - p.lower) * 1e-6;
+            FittedParam {
+                path: p.path.clone(),
+                initial: p.start(),
+                value: x[j],
+                lower: p.lower,
+                upper: p.upper,
+                std_error,
+                ci_low: std_error.map(|s| x[j] - 1.96 * s),
+                ci_high: std_error.map(|s| x[j] + 1.96 * s),
+                at_bound: x[j] - p.lower <= width || p.upper - x[j] <= width,
+            }
+        })
+        .collect();
+    let correlation = covariance
+        .map(|c| {
+            (0..n)
+                .map(|i| {
+                    (0..n)
+                        .map(|j| c[i][j] / (c[i][i] * c[j][j]).sqrt())
+                        .collect()
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let signals = spec
+        .signals
+        .iter()
+        .zip(&targets)
+        .map(|(s, target)| {
+            let ys = &run.series[&s.name];
+            let fitted: Vec<f64> = target
+                .times
+                .iter()
+                .map(|&t| interpolate(&run.times, ys, t))
+                .collect();
+            let residuals: Vec<f64> = fitted
+                .iter()
+                .zip(&target.values)
+                .map(|(f, m)| f - m)
+                .collect();
+            let rmse = (sum_sq(&residuals) / residuals.len().max(1) as f64).sqrt();
+            SignalFit {
+                name: s.name.clone(),
+                measured_name: s.measured_name().to_string(),
+                times: target.times.clone(),
+                measured: target.values.clone(),
+                fitted,
+                residuals,
+                rmse,
+            }
+        })
+        .collect();
+
+    Ok(EstimationReport {
+        model: model.to_string(),
+        optimizer: spec.optimizer,
+        parameters,
+        cost_initial,
+        cost,
+        evaluations,
+        iterations,
+        converged,
+        dof,
+        correlation,
+        history,
+        signals,
+        run: Some(run),
+    })
+}
+
+fn sum_sq(v: &[f64]) -> f64 {
+    v.iter().map(|r| r * r).sum()
+}
+
+/// `s²(JᵀJ)⁻¹`, or `None` when `JᵀJ` is singular.
+fn covariance(jac: &[Vec<f64>], s2: f64) -> Option<Vec<Vec<f64>>> {
+    let n = jac.first()?.len();
+    let jtj: Vec<Vec<f64>> = (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| jac.iter().map(|row| row[i] * row[j]).sum())
+                .collect()
+        })
+        .collect();
+    // Columns of the inverse, one solve each.
+    let mut cov = vec![vec![0.0; n]; n];
+    for k in 0..n {
+        let mut e = vec![0.0; n];
+        e[k] = 1.0;
+        let col = solve(jtj.clone(), e)?;
+        for (row, v) in cov.iter_mut().zip(col) {
+            row[k] = v * s2;
+        }
+    }
+    cov.iter()
+        .enumerate()
+        .all(|(i, row)| row[i].is_finite() && row[i] >= 0.0)
+        .then_some(cov)
+}
+
+// ─── Optimizers ──────────────────────────────────────────────────────────────
+//
+// Each works in the unit box, evaluates through `Objective` (which clamps,
+// counts against the budget and keeps the best point seen) and returns
+// `(iterations, converged)`.
+
+struct Objective<'a> {
+    cost: &'a mut dyn FnMut(&[f64]) -> f64,
+    evaluations: usize,
+    budget: usize,
+    best_z: Vec<f64>,
+    best: f64,
+    history: Vec<f64>,
+}
+
+impl Objective<'_> {
+    fn eval(&mut self, z: &[f64]) -> f64 {
+        let z: Vec<f64> = z.iter().map(|v| v.clamp(0.0, 1.0)).collect();
+        self.evaluations += 1;
+        let f = (self.cost)(&z);
+        if f < self.best {
+            self.best = f;
+            self.best_z = z;
+        }
+        f
+    }
+
+    fn exhausted(&self) -> bool {
+        self.evaluations >= self.budget
+    }
+
+    fn end_iteration(&mut self) {
+        self.history.push(self.best);
+    }
+}
+
+fn nelder_mead(obj: &mut Objective, z0: &[f64], tol: f64) -> (usize, bool) {
+    let n = z0.len();
+    let mut simplex: Vec<(Vec<f64>, f64)> = Vec::with_capacity(n + 1);
+    simplex.push((z0.to_vec(), obj.best));
+    for i in 0..n {
+        let mut z = z0.to_vec();
+        z[i] = if z[i] + 0.1 <= 1.0 {
+            z[i] + 0.1
+        } else {
+            z[i] - 0.1
+        };
+        let f = obj.eval(&z);
+        simplex.push((z, f));
+    }
+    let centroid = |s: &[(Vec<f64>, f64)]| -> Vec<f64> {
+        (0..n)
+            .map(|i| s[..n].iter().map(|v| v.0[i]).sum::<f64>() / n as f64)
+            .collect()
+    };
+    let along = |c: &[f64], w: &[f64], t: f64| -> Vec<f64> {
+        c.iter()
+            .zip(w)
+            .map(|(c, w)| (c + t * (w - c)).clamp(0.0, 1.0))
+            .collect()
+    };
+    let mut iterations = 0;
+    while !obj.exhausted() {
+        simplex.sort_by(|a, b| a.1.total_cmp(&b.1));
+        iterations += 1;
+        obj.end_iteration();
+        let (best, worst) = (simplex[0].1, simplex[n].1);
+        let size = simplex[1..]
+            .iter()
+            .flat_map(|v| v.0.iter().zip(&simplex[0].0).map(|(a, b)| (a - b).abs()))
+            .fold(0.0, f64::max);
+        if worst - best <= tol * (1.0 + best.abs()) && size <= tol.sqrt() {
+            return (iterations, true);
+        }
+        let c = centroid(&simplex);
+        let reflected = along(&c, &simplex[n].0, -1.0);
+        let fr = obj.eval(&reflected);
+        if fr < simplex[0].1 {
+            let expanded = along(&c, &simplex[n].0, -2.0);
+            let fe = obj.eval(&expanded);
+            simplex[n] = if fe < fr {
+                (expanded, fe)
+            } else {
+                (reflected, fr)
+            };
+        } else if fr < simplex[n - 1].1 {
+            simplex[n] = (reflected, fr);
+        } else {
+            let contracted = if fr < simplex[n].1 {
+                along(&c, &reflected, 0.5)
+            } else {
+                along(&c, &simplex[n].0, 0.5)
+            };
+            let fc = obj.eval(&contracted);
+            if fc < fr.min(simplex[n].1) {
+                simplex[n] = (contracted, fc);
+            } else {
+                // Shrink toward the best vertex.
+                let z_best = simplex[0].0.clone();
+                for v in simplex.iter_mut().skip(1) {
+                    if obj.exhausted() {
+                        break;
+                    }
+                    v.0 = along(&z_best, &v.0, 0.5);
+                    v.1 = obj.eval(&v.0);
+                }
+            }
+        }
+    }
+    (iterations, false)
+}
+
+/// Central-difference gradient in the unit box, one-sided at a bound.
+fn gradient(obj: &mut Objective, z: &[f64], f: f64) -> Option<Vec<f64>> {
+    const H: f64 = 1e-5;
+    let mut g = vec![0.0; z.len()];
+    for i in 0..z.len() {
+        if obj.exhausted() {
+            return None;
+        }
+        let mut zp = z.to_vec();
+        let mut zm = z.to_vec();
+        zp[i] = (z[i] + H).min(1.0);
+        zm[i] = (z[i] - H).max(0.0);
+        let fp = if zp[i] > z[i] { obj.eval(&zp) } else { f };
+        let fm = if zm[i] < z[i] { obj.eval(&zm) } else { f };
+        g[i] = (fp - fm) / (zp[i] - zm[i]);
+        if !g[i].is_finite() {
+            return None;
+        }
+    }
+    Some(g)
+}
+
+fn lbfgsb(obj: &mut Objective, z0: &[f64], tol: f64) -> (usize, bool) {
+    const MEMORY: usize = 6;
+    let n = z0.len();
+    let mut z = z0.to_vec();
+    let mut f = obj.best;
+    let Some(mut g) = gradient(obj, &z, f) else {
+        return (0, false);
+    };
+    let mut memory: Vec<(Vec<f64>, Vec<f64>)> = Vec::new();
+    let dot = |a: &[f64], b: &[f64]| a.iter().zip(b).map(|(x, y)| x * y).sum::<f64>();
+    let mut iterations = 0;
+    while !obj.exhausted() {
+        iterations += 1;
+        obj.end_iteration();
+        // Variables held at a bound by a gradient pointing out of the box.
+        let free: Vec<bool> = (0..n)
+            .map(|i| !((z[i] <= 0.0 && g[i] > 0.0) || (z[i] >= 1.0 && g[i] < 0.0)))
+            .collect();
+        let pg: Vec<f64> = (0..n).map(|i| if free[i] { g[i] } else { 0.0 }).collect();
+        if pg.iter().fold(0.0f64, |m, v| m.max(v.abs())) <= tol.sqrt() * (1.0 + f.abs()) {
+            return (iterations, true);
+        }
+        // Two-loop recursion on the free subspace.
+        let mut q = pg.clone();
+        let mut alphas = Vec::with_capacity(memory.len());
+        for (s, y) in memory.iter().rev() {
+            let a = dot(s, &q) / dot(y, s);
+            for (qi, yi) in q.iter_mut().zip(y) {
+                *qi -= a * yi;
+            }
+            alphas.push(a);
+        }
+        if let Some((s, y)) = memory.last() {
+            let gamma = dot(s, y) / dot(y, y);
+            q.iter_mut().for_each(|v| *v *= gamma);
+        }
+        for ((s, y), a) in memory.iter().zip(alphas.into_iter().rev()) {
+            let b = dot(y, &q) / dot(y, s);
+            for (qi, si) in q.iter_mut().zip(s) {
+                *qi += (a - b) * si;
+            }
+        }
+        let mut d: Vec<f64> = (0..n).map(|i| if free[i] { -q[i] } else { 0.0 }).collect();
+        if dot(&d, &g) >= 0.0 {
+            d = pg.iter().map(|v| -v).collect();
+            memory.clear();
+        }
+        // Projected backtracking (Armijo).
+        let mut step = 1.0;
+        let mut accepted = None;
+        for _ in 0..30 {
+            if obj.exhausted() {
+                break;
+            }
+            let trial: Vec<f64> = z
+                .iter()
+                .zip(&d)
+                .map(|(a, b)| (a + step * b).clamp(0.0, 1.0))
+                .collect();
+            let moved: Vec<f64> = trial.iter().zip(&z).map(|(a, b)| a - b).collect();
+            let ft = obj.eval(&trial);
+            if ft <= f + 1e-4 * dot(&g, &moved) && ft.is_finite() {
+                accepted = Some((trial, ft, moved));
+                break;
+            }
+            step *= 0.5;
+        }
+        let Some((z_new, f_new, s)) = accepted else {
+            if memory.is_empty() {
+                return (iterations, false);
+            }
+            memory.clear();
+            continue;
+        };
+        let Some(g_new) = gradient(obj, &z_new, f_new) else {
+            return (iterations, false);
+        };
+        let y: Vec<f64> = g_new.iter().zip(&g).map(|(a, b)| a - b).collect();
+        let decrease = f - f_new;
+        if dot(&s, &y) > 1e-12 {
+            memory.push((s, y));
+            if memory.len() > MEMORY {
+                memory.remove(0);
+            }
+        }
+        z = z_new;
+        f = f_new;
+        g = g_new;
+        if decrease <= tol * (1.0 + f.abs()) {
+            return (iterations, true);
+        }
+    }
+    (iterations, false)
+}
+
+/// Lower Cholesky factor of `c`, or `None` when not positive definite.
+fn cholesky(c: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let n = c.len();
+    let mut l = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        for j in 0..=i {
+            let s: f64 = (0..j).map(|k| l[i][k] * l[j][k]).sum();
+            if i == j {
+                let d = c[i][i] - s;
+                if d <= 0.0 || !d.is_finite() {
+                    return None;
+                }
+                l[i][i] = d.sqrt();
+            } else {
+                l[i][j] = (c[i][j] - s) / l[j][j];
+            }
+        }
+    }
+    Some(l)
+}
+
+/// A CMA-ES draw: repaired point, cost, `y = (x − m)/σ` and `z = A⁻¹y`.
+type Candidate = (Vec<f64>, f64, Vec<f64>, Vec<f64>);
+
+/// CMA-ES (μ/μ_w, λ) in the unit box. Out-of-box samples are repaired by
+/// clamping and the repaired points feed the update, so the distribution
+/// learns where the box is. Sampling uses the Cholesky factor `A` of `C`
+/// (`AAᵀ = C`) in place of the symmetric root; the evolution path for `σ`
+/// is then built from the `A⁻¹`-whitened steps.
+fn cma_es(obj: &mut Objective, z0: &[f64], tol: f64, seed: u64) -> (usize, bool) {
+    let n = z0.len();
+    let nf = n as f64;
+    let lambda = 4 + (3.0 * nf.ln()).floor() as usize;
+    let mu = lambda / 2;
+    let raw: Vec<f64> = (0..mu)
+        .map(|i| (mu as f64 + 0.5).ln() - ((i + 1) as f64).ln())
+        .collect();
+    let total: f64 = raw.iter().sum();
+    let weights: Vec<f64> = raw.iter().map(|w| w / total).collect();
+    let mu_eff = 1.0 / weights.iter().map(|w| w * w).sum::<f64>();
+    let c_sigma = (mu_eff + 2.0) / (nf + mu_eff + 5.0);
+    let d_sigma = 1.0 + 2.0 * (((mu_eff - 1.0) / (nf + 1.0)).sqrt() - 1.0).max(0.0) + c_sigma;
+    let c_c = (4.0 + mu_eff / nf) / (nf + 4.0 + 2.0 * mu_eff / nf);
+    let c_1 = 2.0 / ((nf + 1.3).powi(2) + mu_eff);
+    let c_mu = (1.0 - c_1).min(2.0 * (mu_eff - 2.0 + 1.0 / mu_eff) / ((nf + 2.0).powi(2) + mu_eff));
+    let chi_n = nf.sqrt() * (1.0 - 1.0 / (4.0 * nf) + 1.0 / (21.0 * nf * nf));
+
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+    let mut normal = move || {
+        let u: f64 = rng.random::<f64>();
+        crate::sweep::inverse_normal_cdf(u.clamp(1e-16, 1.0 - 1e-16))
+    };
+    let mut mean = z0.to_vec();
+    let mut sigma = 0.3;
+    let mut cov: Vec<Vec<f64>> = (0..n)
+        .map(|i| (0..n).map(|j| if i == j { 1.0 } else { 0.0 }).collect())
+        .collect();
+    let mut p_c = vec![0.0; n];
+    let mut p_sigma = vec![0.0; n];
+    let mut generation = 0;
+    while !obj.exhausted() {
+        generation += 1;
+        let a = match cholesky(&cov) {
+            Some(a) => a,
+            None => {
+                // Numerically broken covariance: restart its shape.
+                for (i, row) in cov.iter_mut().enumerate() {
+                    row.iter_mut()
+                        .enumerate()
+                        .for_each(|(j, v)| *v = f64::from(u8::from(i == j)));
+                }
+                cholesky(&cov).expect("identity is positive definite")
+            }
+        };
+        let mut population: Vec<Candidate> = Vec::with_capacity(lambda);
+        for _ in 0..lambda {
+            if obj.exhausted() {
+                break;
+            }
+            let z: Vec<f64> = (0..n).map(|_| normal()).collect();
+            let x: Vec<f64> = (0..n)
+                .map(|i| {
+                    let y: f64 = (0..=i).map(|k| a[i][k] * z[k]).sum();
+                    (mean[i] + sigma * y).clamp(0.0, 1.0)
+                })
+                .collect();
+            let f = obj.eval(&x);
+            let y: Vec<f64> = x.iter().zip(&mean).map(|(x, m)| (x - m) / sigma).collect();
+            // Forward substitution: A z' = y.
+            let mut zr = vec![0.0; n];
+            for i in 0..n {
+                let s: f64 = (0..i).map(|k| a[i][k] * zr[k]).sum();
+                zr[i] = (y[i] - s) / a[i][i];
+            }
+            population.push((x, f, y, zr));
+        }
+        if population.len() < mu {
+            break;
+        }
+        population.sort_by(|p, q| p.1.total_cmp(&q.1));
+        obj.end_iteration();
+
+        let spread = population[population.len() - 1].1 - population[0].1;
+        let mut y_w = vec![0.0; n];
+        let mut z_w = vec![0.0; n];
+        for (w, (_, _, y, z)) in weights.iter().zip(&population) {
+            for i in 0..n {
+                y_w[i] += w * y[i];
+                z_w[i] += w * z[i];
+            }
+        }
+        for i in 0..n {
+            mean[i] = (mean[i] + sigma * y_w[i]).clamp(0.0, 1.0);
+        }
+        let k_sigma = (c_sigma * (2.0 - c_sigma) * mu_eff).sqrt();
+        for i in 0..n {
+            p_sigma[i] = (1.0 - c_sigma) * p_sigma[i] + k_sigma * z_w[i];
+        }
+        let ps_norm = p_sigma.iter().map(|v| v * v).sum::<f64>().sqrt();
+        let h_sigma = ps_norm / (1.0 - (1.0 - c_sigma).powi(2 * generation as i32)).sqrt()
+            < (1.4 + 2.0 / (nf + 1.0)) * chi_n;
+        let h = if h_sigma { 1.0 } else { 0.0 };
+        let k_c = (c_c * (2.0 - c_c) * mu_eff).sqrt();
+        for i in 0..n {
+            p_c[i] = (1.0 - c_c) * p_c[i] + h * k_c * y_w[i];
+        }
+        for i in 0..n {
+            for j in 0..n {
+                let rank_mu: f64 = weights
+                    .iter()
+                    .zip(&population)
+                    .map(|(w, (_, _, y, _))| w * y[i] * y[j])
+                    .sum();
+                cov[i][j] = (1.0 - c_1 - c_mu) * cov[i][j]
+                    + c_1 * (p_c[i] * p_c[j] + (1.0 - h) * c_c * (2.0 - c_c) * cov[i][j])
+                    + c_mu * rank_mu;
+            }
+        }
+        sigma = (sigma * ((c_sigma / d_sigma) * (ps_norm / chi_n - 1.0)).exp()).min(1.0);
+
+        let reach = sigma * (0..n).map(|i| cov[i][i]).fold(0.0, f64::max).sqrt();
+        if reach <= tol.sqrt() || (spread <= tol * (1.0 + population[0].1.abs()) && reach < 1e-3) {
+            return (generation, true);
+        }
+    }
+    (generation, false)
+}
+
+// ─── Report ──────────────────────────────────────────────────────────────────
+
+/// A self-contained HTML page for `report`: the fitted values with their
+/// intervals, the correlations, the cost history and, per signal, measured
+/// against fitted and the residuals.
+pub fn html_report(report: &EstimationReport) -> String {
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{model} — fit</title>\n<style>\
+         body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #ccc;padding:2px 8px;text-align:right}}\
+         th:first-child,td:first-child{{text-align:left}}.warn{{color:#c33}}\
+         svg{{background:#fafafa;border:1px solid #ddd}}\
+         </style></head><body>\n<h1>{model}</h1>\n<p>{optimizer}: cost {c0:.6e} → {c:.6e} \
+         in {evals} runs, {iters} iterations — {verdict}</p>\n",
+        model = xml_escape(&report.model),
+        optimizer = report.optimizer.label(),
+        c0 = report.cost_initial,
+        c = report.cost,
+        evals = report.evaluations,
+        iters = report.iterations,
+        verdict = if report.converged {
+            "converged"
+        } else {
+            "<b class=\"warn\">stopped at the run budget</b>"
+        },
+    );
+    out.push_str(
+        "<table><tr><th>Parameter</th><th>Initial</th><th>Fitted</th><th>Std. error</th>\
+         <th>95% interval</th><th>Bounds</th></tr>\n",
+    );
+    let fmt = |v: Option<f64>| v.map_or_else(|| "—".to_string(), |v| format!("{v:.6e}"));
+    for p in &report.parameters {
+        let _ = writeln!(
+            out,
+            "<tr><td>{}</td><td>{:.6e}</td><td>{:.6e}</td><td>{}</td><td>{} … {}</td>\
+             <td{}>[{}, {}]{}</td></tr>",
+            xml_escape(&p.path.0),
+            p.initial,
+            p.value,
+            fmt(p.std_error),
+            fmt(p.ci_low),
+            fmt(p.ci_high),
+            if p.at_bound { " class=\"warn\"" } else { "" },
+            p.lower,
+            p.upper,
+            if p.at_bound { " at bound" } else { "" },
+        );
+    }
+    out.push_str("</table>\n");
+    if report.correlation.is_empty() {
+        out.push_str(
+            "<p class=\"warn\">No covariance: the data do not determine every parameter \
+             (or there are no more samples than parameters).</p>\n",
+        );
+    } else if report.parameters.len() > 1 {
+        out.push_str("<h2>Correlation</h2>\n<table><tr><th></th>");
+        for p in &report.parameters {
+            let _ = write!(out, "<th>{}</th>", xml_escape(&p.path.0));
+        }
+        out.push_str("</tr>\n");
+        for (p, row) in report.parameters.iter().zip(&report.correlation) {
+            let _ = write!(out, "<tr><td>{}</td>", xml_escape(&p.path.0));
+            for v in row {
+                let _ = write!(out, "<td>{v:+.3}</td>");
+            }
+            out.push_str("</tr>\n");
+        }
+        out.push_str("</table>\n");
+    }
+    if !report.history.is_empty() {
+        out.push_str("<h2>Cost</h2>\n");
+        let steps: Vec<f64> = (1..=report.history.len()).map(|i| i as f64).collect();
+        let log_cost: Vec<f64> = report
+            .history
+            .iter()
+            .map(|c| c.max(f64::MIN_POSITIVE).log10())
+            .collect();
+        out.push_str(&curves_svg(
+            &[(&steps, &log_cost, "#246", false)],
+            false,
+            "iteration",
+            "log₁₀ cost",
+        ));
+    }
+    for s in &report.signals {
+        let _ = writeln!(
+            out,
+            "<h2>{}</h2>\n<p>against <code>{}</code>, RMSE {:.6e}</p>",
+            xml_escape(&s.name),
+            xml_escape(&s.measured_name),
+            s.rmse
+        );
+        out.push_str(&curves_svg(
+            &[
+                (&s.times, &s.measured, "#246", true),
+                (&s.times, &s.fitted, "#2a7", false),
+            ],
+            false,
+            "t",
+            "measured (dashed) · fitted",
+        ));
+        out.push_str(&curves_svg(
+            &[(&s.times, &s.residuals, "#c33", false)],
+            true,
+            "t",
+            "residual",
+        ));
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+const PLOT_W: f64 = 720.0;
+const PLOT_H: f64 = 180.0;
+const PLOT_PAD: f64 = 36.0;
+const PLOT_POINTS: usize = 600;
+
+/// Line plot of `(xs, ys, colour, dashed)` curves on shared axes, with a
+/// `y = 0` rule when `zero` is set.
+fn curves_svg(
+    curves: &[(&[f64], &[f64], &str, bool)],
+    zero: bool,
+    x_label: &str,
+    y_label: &str,
+) -> String {
+    let finite = |v: &f64| v.is_finite();
+    let xs = curves.iter().flat_map(|c| c.0.iter()).filter(|v| finite(v));
+    let ys = curves.iter().flat_map(|c| c.1.iter()).filter(|v| finite(v));
+    let (x_min, x_max) = xs.fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| {
+        (a.min(v), b.max(v))
+    });
+    let (mut y_min, mut y_max) = ys.fold((f64::INFINITY, f64::NEG_INFINITY), |(a, b), &v| {
+        (a.min(v), b.max(v))
+    });
+    if !(x_min.is_finite() && y_min.is_finite()) {
+        return String::from("<p>(nothing to plot)</p>\n");
+    }
+    if zero {
+        y_min = y_min.min(0.0);
+        y_max = y_max.max(0.0);
+    }
+    let x_span = (x_max - x_min).max(f64::MIN_POSITIVE);
+    let y_span = if y_max > y_min { y_max - y_min } else { 1.0 };
+    let px = |v: f64| PLOT_PAD + (v - x_min) / x_span * (PLOT_W - 2.0 * PLOT_PAD);
+    let py = |v: f64| PLOT_H - PLOT_PAD - (v - y_min) / y_span * (PLOT_H - 2.0 * PLOT_PAD);
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{PLOT_W}\" height=\"{PLOT_H}\" viewBox=\"0 0 {PLOT_W} {PLOT_H}\">"
+    );
+    if zero {
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{PLOT_PAD}\" x2=\"{:.1}\" y1=\"{y:.1}\" y2=\"{y:.1}\" stroke=\"#999\"/>",
+            PLOT_W - PLOT_PAD,
+            y = py(0.0)
+        );
+    }
+    for (xs, ys, colour, dashed) in curves {
+        let n = xs.len().min(ys.len());
+        let stride = n.div_ceil(PLOT_POINTS).max(1);
+        let mut pts = String::new();
+        for i in (0..n).step_by(stride).chain(n.checked_sub(1)) {
+            if xs[i].is_finite() && ys[i].is_finite() {
+                let _ = write!(pts, "{:.1},{:.1} ", px(xs[i]), py(ys[i]));
+            }
+        }
+        let _ = writeln!(
+            svg,
+            "<polyline points=\"{pts}\" fill=\"none\" stroke=\"{colour}\" stroke-width=\"1.5\"{}/>",
+            if *dashed {
+                " stroke-dasharray=\"4 3\""
+            } else {
+                ""
+            }
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<text x=\"{PLOT_PAD}\" y=\"14\" font-size=\"11\">{y_max:.4e} · {}</text>\
+         <text x=\"{PLOT_PAD}\" y=\"{:.0}\" font-size=\"11\">{y_min:.4e}</text>\
+         <text x=\"{:.0}\" y=\"{:.0}\" font-size=\"11\" text-anchor=\"end\">{} = {x_min} … {x_max}</text>",
+        xml_escape(y_label),
+        PLOT_H - 6.0,
+        PLOT_W - PLOT_PAD,
+        PLOT_H - 6.0,
+        xml_escape(x_label),
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `y = a·e^(−t/τ)` sampled at 0, 0.1, … 5 s.
+    fn decay(a: f64, tau: f64, noise: f64) -> RunResult {
+        let times: Vec<f64> = (0..=50).map(|i| i as f64 * 0.1).collect();
+        let y = times
+            .iter()
+            .enumerate()
+            .map(|(i, t)| {
+                // Deterministic zero-mean "noise".
+                let wiggle = ((i * 7919 % 101) as f64 / 50.0 - 1.0) * noise;
+                a * (-t / tau).exp() + wiggle
+            })
+            .collect();
+        RunResult {
+            times,
+            series: BTreeMap::from([("y".to_string(), y)]),
+            meta: RunMeta::default(),
+        }
+    }
+
+    fn model(b: &BTreeMap<ParamPath, ParamValue>) -> Result<RunResult, String> {
+        let get = |k: &str| match b.get(&ParamPath(k.into())) {
+            Some(ParamValue::Real(v)) => Ok(*v),
+            _ => Err(format!("missing {k}")),
+        };
+        Ok(decay(get("a")?, get("tau")?, 0.0))
+    }
+
+    fn spec(optimizer: Optimizer) -> EstimationSpec {
+        EstimationSpec {
+            parameters: vec![
+                EstimatedParam {
+                    path: ParamPath("a".into()),
+                    lower: 0.1,
+                    upper: 10.0,
+                    initial: Some(1.0),
+                },
+                EstimatedParam {
+                    path: ParamPath("tau".into()),
+                    lower: 0.1,
+                    upper: 5.0,
+                    initial: Some(2.5),
+                },
+            ],
+            signals: vec![FitSignal::new("y")],
+            optimizer,
+            max_evaluations: 3000,
+            seed: 3,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn every_optimizer_recovers_the_parameters() {
+        let measured = decay(3.0, 0.8, 0.0);
+        for optimizer in [Optimizer::NelderMead, Optimizer::Lbfgsb, Optimizer::CmaEs] {
+            let report = estimate("Decay", &spec(optimizer), &measured, model).expect("fits");
+            let a = report.parameters[0].value;
+            let tau = report.parameters[1].value;
+            assert!((a - 3.0).abs() < 1e-3, "{optimizer:?}: a = {a}");
+            assert!((tau - 0.8).abs() < 1e-3, "{optimizer:?}: tau = {tau}");
+            assert!(report.cost < report.cost_initial, "{optimizer:?}");
+            assert!(
+                report.converged,
+                "{optimizer:?} used {} runs",
+                report.evaluations
+            );
+            assert!(!report.history.is_empty());
+        }
+    }
+
+    #[test]
+    fn confidence_intervals_cover_the_truth_under_noise() {
+        let measured = decay(3.0, 0.8, 0.02);
+        let report =
+            estimate("Decay", &spec(Optimizer::NelderMead), &measured, model).expect("fits");
+        assert_eq!(report.dof, 51 - 2);
+        for (p, truth) in report.parameters.iter().zip([3.0, 0.8]) {
+            let se = p.std_error.expect("identifiable");
+            assert!(se > 0.0 && se < 0.05, "{}: se = {se}", p.path.0);
+            assert!(p.ci_low.unwrap() <= truth && truth <= p.ci_high.unwrap());
+            assert!(!p.at_bound);
+        }
+        assert!((report.correlation[0][0] - 1.0).abs() < 1e-9);
+        assert!(report.correlation[0][1].abs() < 1.0);
+        let fit = &report.signals[0];
+        assert_eq!(fit.residuals.len(), 51);
+        assert!(fit.rmse < 0.03, "rmse {}", fit.rmse);
+        let html = html_report(&report);
+        assert!(html.contains("<svg") && html.contains("tau"));
+    }
+
+    #[test]
+    fn a_parameter_the_data_cannot_see_has_no_error_bar() {
+        let measured = decay(3.0, 0.8, 0.01);
+        let mut spec = spec(Optimizer::NelderMead);
+        spec.parameters.push(EstimatedParam {
+            path: ParamPath("unused".into()),
+            lower: 0.0,
+            upper: 1.0,
+            initial: None,
+        });
+        let report = estimate("Decay", &spec, &measured, model).expect("fits");
+        assert!(report.parameters.iter().all(|p| p.std_error.is_none()));
+        assert!(report.correlation.is_empty());
+    }
+
+    #[test]
+    fn bad_specs_fail_before_any_run() {
+        let measured = decay(1.0, 1.0, 0.0);
+        let mut bad = spec(Optimizer::NelderMead);
+        bad.parameters[0].upper = bad.parameters[0].lower;
+        let mut runs = 0;
+        let err = estimate("Decay", &bad, &measured, |_| {
+            runs += 1;
+            Err("unreachable".into())
+        })
+        .unwrap_err();
+        assert!(err.contains("lower < upper"), "{err}");
+        assert_eq!(runs, 0);
+        let mut missing = spec(Optimizer::NelderMead);
+        missing.signals = vec![FitSignal::new("z")];
+        assert!(missing.validate(&measured).unwrap_err().contains("`z`"));
+        assert_eq!(Optimizer::from_token("cma-es"), Some(Optimizer::CmaEs));
+        assert_eq!(Optimizer::from_token("L-BFGS-B"), Some(Optimizer::Lbfgsb));
+    }
+
+    #[test]
+    fn telemetry_channels_share_a_union_grid() {
+        let measured = measured_from_samples(vec![
+            ("a".into(), vec![(0.0, 1.0), (1.0, 2.0)]),
+            ("b".into(), vec![(0.5, 5.0), (1.0, 6.0)]),
+        ]);
+        assert_eq!(measured.times, [0.0, 0.5, 1.0]);
+        assert_eq!(measured.series["a"][2], 2.0);
+        assert!(measured.series["a"][1].is_nan());
+        assert!(measured.series["b"][0].is_nan());
+        let spec = EstimationSpec {
+            signals: vec![FitSignal::new("b")],
+            time_offset: 0.5,
+            ..Default::default()
+        };
+        assert_eq!(spec.time_span(&measured), Some((0.0, 0.5)));
+    }
+}
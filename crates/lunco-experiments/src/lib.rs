@@ -8,6 +8,34 @@
 //! per [`TwinId`]; the registry caps each twin at 20 runs and evicts
 //! the oldest finished run on overflow.
 //!
+//! A [`Sweep`] is a family of experiments expanded from one design-of-
+//! experiments [`SweepSpec`] (see [`sweep`]). Its members are ordinary
+//! experiments tagged with a [`SweepMember`]; they are exempt from the
+//! per-twin cap, since evicting one would silently thin the design.
+//!
+//! The registry is the working set; finished runs outlive it in the
+//! twin folder's experiment store (see [`store`] for the on-disk format).
+//!
+//! A *reference run* is an experiment imported from another tool's result
+//! file (MAT v4 / v7.3, see [`result_file`]) rather than simulated. It has a
+//! trajectory but no model source to re-run, so it is read-only, and like a
+//! sweep member it is exempt from the cap — it is only ever removed by hand.
+//!
+//! [`regression`] judges a run against such a reference through a tolerance
+//! tube and reports the verdicts as JUnit XML and HTML.
+//!
+//! [`linear`] holds the other artifact a model can yield besides a
+//! trajectory: a [`StateSpace`] linearized around an operating point, with
+//! its poles, zeros and frequency response.
+//!
+//! [`sensitivity`] ranks a sweep's factors by their influence on scalar
+//! outputs of its runs — Morris elementary effects and Sobol indices, over
+//! the two sensitivity designs of [`sweep`].
+//!
+//! [`estimation`] fits model parameters to measured trajectories — a
+//! reference run or recorded telemetry — by driving the backend through a
+//! `simulate` callback, and reports the fitted values with their confidence.
+//!
 //! The simulation backend is plugged in via the [`ExperimentRunner`]
 //! trait. This crate has no rumoca / modelica dependency; the binding
 //! lives in `lunco-modelica`. Future backends (FMU, codegen, remote)
@@ -17,6 +45,29 @@ pub mod solver;
 pub use solver::{
     RuntimeProfile, SolverCaps, SolverError, SolverId, SolverParams, SolverRequest, SolverSpec,
 };
+pub mod sweep;
+pub use sweep::{
+    Design, Distribution, SweepError, SweepFactor, SweepId, SweepMember, SweepSpec, MAX_SWEEP_RUNS,
+    MORRIS_DEFAULT_LEVELS,
+};
+pub mod store;
+pub use store::{IndexEntry, StoreError, StoreIndex, StoreQuery, StoredRun, StoredSweep};
+pub mod result_file;
+pub use result_file::{decode_mat4, encode_mat4, sniff, ResultFileError, ResultFileKind};
+pub mod estimation;
+pub mod linear;
+pub mod regression;
+pub mod sensitivity;
+pub use estimation::{
+    EstimatedParam, EstimationReport, EstimationSpec, FitSignal, FittedParam, Optimizer, SignalFit,
+};
+pub use linear::{Complex, FrequencyPoint, JacobianMethod, OperatingPoint, StateSpace};
+#[cfg(feature = "hdf5")]
+pub mod result_hdf5;
+pub use regression::{CaseReport, CaseSpec, SignalVerdict, SuiteReport, SuiteSpec, Tube};
+pub use sensitivity::{
+    MorrisIndex, OutputKind, OutputSensitivity, ScalarOutput, SensitivityReport, SobolIndex,
+};
 
 use std::collections::BTreeMap;
 use web_time::SystemTime;
@@ -49,9 +100,9 @@ impl ExperimentId {
         *self == Self::live()
     }
 
-    /// Filename stem for this experiment's result artifact (`<stem>.json` under
-    /// `<twin>/results/`). Single source of truth shared by the writer + loader
-    /// so the round-trip agrees on the name.
+    /// Path segment naming this experiment on disk — its store directory
+    /// (`<twin>/experiments/<stem>/`, see [`store`]). Single source of truth
+    /// shared by the writer + loader so the round-trip agrees on the name.
     pub fn as_artifact_stem(&self) -> String {
         self.0.to_string()
     }
@@ -322,13 +373,44 @@ pub struct Experiment {
     /// palette chosen by the UI; allocated when the experiment is
     /// first inserted into the registry.
     pub color_hint: u8,
+    /// Set when this run is one point of a [`Sweep`].
+    #[serde(default)]
+    pub sweep: Option<SweepMember>,
+    /// Set for a reference run: the result file it was imported from.
+    #[serde(default)]
+    pub reference: Option<String>,
+}
+
+/// A family of experiments expanded from one [`SweepSpec`]. Holds the
+/// provenance needed to regenerate or explain every member: the spec (with
+/// its seed), the overrides and inputs the members share, and the bounds.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct Sweep {
+    pub id: SweepId,
+    pub twin_id: TwinId,
+    pub model_ref: ModelRef,
+    /// Display name. Auto-set to `Sweep N` on creation; user-editable.
+    pub name: String,
+    pub spec: SweepSpec,
+    /// Overrides every member carries. A swept factor wins over a base
+    /// override of the same path.
+    pub base_overrides: BTreeMap<ParamPath, ParamValue>,
+    pub inputs: BTreeMap<ParamPath, ParamValue>,
+    pub bounds: RunBounds,
+    /// Member ids in design order (`members[i]` has `SweepMember::index == i`
+    /// until members are deleted).
+    pub members: Vec<ExperimentId>,
+    pub created_at: SystemTime,
+    /// The family's swatch; member `i` is coloured `color_hint + i`.
+    pub color_hint: u8,
 }
 
 // ---------- Registry ----------
 
 /// Per-twin cap. v1: 20 finished runs per twin, oldest evicted.
 /// In-flight runs (Pending / Running) never count against the cap and
-/// are never evicted.
+/// are never evicted; neither do sweep members, which leave only with
+/// their family ([`ExperimentRegistry::delete_sweep`]).
 pub const REGISTRY_CAP_PER_TWIN: usize = 20;
 
 /// Process-wide experiment store, keyed by twin.
@@ -340,6 +422,9 @@ pub struct ExperimentRegistry {
     name_counter: BTreeMap<(TwinId, ModelRef), u32>,
     /// Color rotation index per twin.
     color_counter: BTreeMap<TwinId, u8>,
+    sweeps: BTreeMap<TwinId, Vec<Sweep>>,
+    /// Monotonic counter for `Sweep N` names per twin.
+    sweep_counter: BTreeMap<TwinId, u32>,
 }
 
 impl ExperimentRegistry {
@@ -366,12 +451,7 @@ impl ExperimentRegistry {
         // belongs in row metadata, not every legend entry.
         let name = format!("Run {}", n);
 
-        let color_hint = {
-            let c = self.color_counter.entry(twin_id.clone()).or_insert(0);
-            let v = *c;
-            *c = c.wrapping_add(1);
-            v
-        };
+        let color_hint = self.next_color(&twin_id);
 
         let exp = Experiment {
             id: ExperimentId::new(),
@@ -385,6 +465,8 @@ impl ExperimentRegistry {
             result: None,
             created_at: SystemTime::now(),
             color_hint,
+            sweep: None,
+            reference: None,
         };
         let id = exp.id;
         let bucket = self.by_twin.entry(twin_id).or_default();
@@ -393,10 +475,206 @@ impl ExperimentRegistry {
         id
     }
 
+    /// Insert a finished reference run holding `result`, imported from the
+    /// file `source`. `name` is its display name (the file stem, usually);
+    /// the bounds span the trajectory.
+    pub fn insert_reference(
+        &mut self,
+        twin_id: TwinId,
+        model_ref: ModelRef,
+        name: String,
+        source: String,
+        result: RunResult,
+    ) -> ExperimentId {
+        let color_hint = self.next_color(&twin_id);
+        let bounds = RunBounds {
+            t_start: result.times.first().copied().unwrap_or(0.0),
+            t_end: result.times.last().copied().unwrap_or(0.0),
+            ..Default::default()
+        };
+        let exp = Experiment {
+            id: ExperimentId::new(),
+            twin_id: twin_id.clone(),
+            model_ref,
+            name,
+            overrides: BTreeMap::new(),
+            inputs: BTreeMap::new(),
+            bounds,
+            status: RunStatus::Done {
+                wall_time_ms: result.meta.wall_time_ms,
+            },
+            result: Some(result),
+            created_at: SystemTime::now(),
+            color_hint,
+            sweep: None,
+            reference: Some(source),
+        };
+        let id = exp.id;
+        self.by_twin.entry(twin_id).or_default().push(exp);
+        id
+    }
+
+    fn next_color(&mut self, twin_id: &TwinId) -> u8 {
+        let c = self.color_counter.entry(twin_id.clone()).or_insert(0);
+        let v = *c;
+        *c = c.wrapping_add(1);
+        v
+    }
+
+    /// Expand `spec` and insert one Pending experiment per design point,
+    /// grouped under a new [`Sweep`]. Each member's overrides are
+    /// `base_overrides` with the point's values on top. `name` replaces the
+    /// auto `Sweep N`. Nothing is inserted when the spec does not expand.
+    #[allow(clippy::too_many_arguments)]
+    pub fn insert_sweep(
+        &mut self,
+        twin_id: TwinId,
+        model_ref: ModelRef,
+        name: Option<String>,
+        spec: SweepSpec,
+        base_overrides: BTreeMap<ParamPath, ParamValue>,
+        inputs: BTreeMap<ParamPath, ParamValue>,
+        bounds: RunBounds,
+    ) -> Result<SweepId, SweepError> {
+        let points = spec.expand()?;
+        let name = name.unwrap_or_else(|| {
+            let n = self
+                .sweep_counter
+                .entry(twin_id.clone())
+                .and_modify(|c| *c += 1)
+                .or_insert(1);
+            format!("Sweep {n}")
+        });
+        let color_hint = self.next_color(&twin_id);
+        let created_at = SystemTime::now();
+        let id = SweepId::new();
+        let members: Vec<Experiment> = points
+            .into_iter()
+            .enumerate()
+            .map(|(i, point)| {
+                let mut overrides = base_overrides.clone();
+                overrides.extend(point);
+                Experiment {
+                    id: ExperimentId::new(),
+                    twin_id: twin_id.clone(),
+                    model_ref: model_ref.clone(),
+                    name: format!("{name} #{}", i + 1),
+                    overrides,
+                    inputs: inputs.clone(),
+                    bounds: bounds.clone(),
+                    status: RunStatus::Pending,
+                    result: None,
+                    created_at,
+                    color_hint: color_hint.wrapping_add(i as u8),
+                    sweep: Some(SweepMember {
+                        sweep: id,
+                        index: i as u32,
+                    }),
+                    reference: None,
+                }
+            })
+            .collect();
+        let sweep = Sweep {
+            id,
+            twin_id: twin_id.clone(),
+            model_ref,
+            name,
+            spec,
+            base_overrides,
+            inputs,
+            bounds,
+            members: members.iter().map(|e| e.id).collect(),
+            created_at,
+            color_hint,
+        };
+        self.by_twin
+            .entry(twin_id.clone())
+            .or_default()
+            .extend(members);
+        self.sweeps.entry(twin_id).or_default().push(sweep);
+        Ok(id)
+    }
+
+    pub fn sweep(&self, id: SweepId) -> Option<&Sweep> {
+        self.sweeps.values().flatten().find(|s| s.id == id)
+    }
+
+    pub fn sweeps_for_twin(&self, twin: &TwinId) -> &[Sweep] {
+        self.sweeps.get(twin).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Iterate every sweep across every twin bucket.
+    pub fn iter_sweeps(&self) -> impl Iterator<Item = &Sweep> {
+        self.sweeps.values().flatten()
+    }
+
+    /// A sweep's members that are still in the registry, in design order.
+    pub fn sweep_members(&self, id: SweepId) -> Vec<&Experiment> {
+        self.sweep(id)
+            .map(|s| s.members.iter().filter_map(|m| self.get(*m)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Sensitivity of `outputs` to a Morris / Saltelli sweep's factors, from
+    /// the members that have finished (see [`sensitivity`]).
+    pub fn sweep_sensitivity(
+        &self,
+        id: SweepId,
+        outputs: &[ScalarOutput],
+    ) -> Result<SensitivityReport, String> {
+        let sweep = self.sweep(id).ok_or("no such sweep")?;
+        let count = sweep.spec.run_count().map_err(|e| e.to_string())?;
+        let finished = self.sweep_members(id).into_iter().filter_map(|e| {
+            let done = matches!(e.status, RunStatus::Done { .. });
+            Some((e.sweep?.index, e.result.as_ref().filter(|_| done)?))
+        });
+        sensitivity::analyze(
+            &sweep.spec,
+            &sensitivity::runs_by_index(count, finished),
+            outputs,
+        )
+    }
+
+    /// Rename a sweep. Member names are left as they were.
+    pub fn set_sweep_name(&mut self, id: SweepId, name: String) -> bool {
+        match self.sweeps.values_mut().flatten().find(|s| s.id == id) {
+            Some(s) => {
+                s.name = name;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Delete every terminal member of a sweep, and the sweep itself once no
+    /// member remains. In-flight members stay (cancel them first). Returns
+    /// the removed member ids.
+    pub fn delete_sweep(&mut self, id: SweepId) -> Vec<ExperimentId> {
+        let members = match self.sweep(id) {
+            Some(s) => s.members.clone(),
+            None => return Vec::new(),
+        };
+        members.into_iter().filter(|m| self.delete(*m)).collect()
+    }
+
+    /// Insert a fully-formed sweep record under its own id — the replay
+    /// counterpart of [`insert_with_id`](Self::insert_with_id). Members are
+    /// replayed separately.
+    pub fn insert_sweep_with_id(&mut self, sweep: Sweep) {
+        let bucket = self.sweeps.entry(sweep.twin_id.clone()).or_default();
+        if let Some(pos) = bucket.iter().position(|s| s.id == sweep.id) {
+            bucket[pos] = sweep;
+        } else {
+            bucket.push(sweep);
+        }
+    }
+
     fn evict_if_needed_in(bucket: &mut Vec<Experiment>) {
-        // Cap counts only terminal runs. If terminal count exceeds cap,
-        // evict oldest terminal.
-        let terminal_count = bucket.iter().filter(|e| e.status.is_terminal()).count();
+        // Cap counts only terminal simulated runs outside a sweep. If that
+        // count exceeds the cap, evict the oldest such run.
+        let evictable =
+            |e: &Experiment| e.status.is_terminal() && e.sweep.is_none() && e.reference.is_none();
+        let terminal_count = bucket.iter().filter(|e| evictable(e)).count();
         if terminal_count <= REGISTRY_CAP_PER_TWIN {
             return;
         }
@@ -404,7 +682,7 @@ impl ExperimentRegistry {
         if let Some((idx, _)) = bucket
             .iter()
             .enumerate()
-            .filter(|(_, e)| e.status.is_terminal())
+            .filter(|(_, e)| evictable(e))
             .min_by_key(|(_, e)| e.created_at)
         {
             bucket.remove(idx);
@@ -466,9 +744,13 @@ impl ExperimentRegistry {
         let removed = self.by_twin.remove(twin).map(|v| v.len()).unwrap_or(0);
         self.name_counter.retain(|(t, _), _| t != twin);
         self.color_counter.remove(twin);
+        self.sweeps.remove(twin);
+        self.sweep_counter.remove(twin);
         removed
     }
 
+    /// Delete one terminal run. A sweep member also leaves its family's
+    /// member list, and a family left with no members is dropped.
     pub fn delete(&mut self, id: ExperimentId) -> bool {
         for bucket in self.by_twin.values_mut() {
             if let Some(pos) = bucket.iter().position(|e| e.id == id) {
@@ -476,7 +758,15 @@ impl ExperimentRegistry {
                 if !bucket[pos].status.is_terminal() {
                     return false;
                 }
-                bucket.remove(pos);
+                let removed = bucket.remove(pos);
+                if let Some(member) = removed.sweep {
+                    if let Some(family) = self.sweeps.get_mut(&removed.twin_id) {
+                        for s in family.iter_mut().filter(|s| s.id == member.sweep) {
+                            s.members.retain(|m| *m != id);
+                        }
+                        family.retain(|s| !s.members.is_empty());
+                    }
+                }
                 return true;
             }
         }
@@ -810,6 +1100,99 @@ mod tests {
         assert!(reg.delete(id));
     }
 
+    fn two_point_sweep(reg: &mut ExperimentRegistry, twin: &TwinId) -> SweepId {
+        let spec = SweepSpec {
+            design: Design::FullFactorial { levels: 2 },
+            factors: vec![SweepFactor {
+                path: ParamPath("k".into()),
+                distribution: Distribution::Uniform {
+                    low: 1.0,
+                    high: 2.0,
+                },
+            }],
+            seed: 0,
+        };
+        let base: BTreeMap<_, _> = [
+            (ParamPath("k".into()), ParamValue::Real(0.0)),
+            (ParamPath("m".into()), ParamValue::Real(5.0)),
+        ]
+        .into();
+        reg.insert_sweep(
+            twin.clone(),
+            ModelRef("M".into()),
+            None,
+            spec,
+            base,
+            Default::default(),
+            Default::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn sweep_members_carry_base_overrides_under_the_design_point() {
+        let mut reg = ExperimentRegistry::new();
+        let twin = TwinId("t".into());
+        let id = two_point_sweep(&mut reg, &twin);
+        let members = reg.sweep_members(id);
+        assert_eq!(members.len(), 2);
+        assert_eq!(members[1].name, "Sweep 1 #2");
+        assert_eq!(
+            members[1].overrides[&ParamPath("k".into())],
+            ParamValue::Real(2.0)
+        );
+        assert_eq!(
+            members[1].overrides[&ParamPath("m".into())],
+            ParamValue::Real(5.0)
+        );
+        assert_eq!(
+            members[1].sweep,
+            Some(SweepMember {
+                sweep: id,
+                index: 1
+            })
+        );
+    }
+
+    #[test]
+    fn sweep_members_are_never_evicted() {
+        let mut reg = ExperimentRegistry::new();
+        let twin = TwinId("t".into());
+        let id = two_point_sweep(&mut reg, &twin);
+        for m in reg.sweep(id).unwrap().members.clone() {
+            reg.set_status(m, RunStatus::Done { wall_time_ms: 0 });
+        }
+        for _ in 0..(REGISTRY_CAP_PER_TWIN + 5) {
+            let run = reg.insert_new(
+                twin.clone(),
+                ModelRef("M".into()),
+                Default::default(),
+                Default::default(),
+                Default::default(),
+            );
+            reg.set_status(run, RunStatus::Done { wall_time_ms: 0 });
+        }
+        assert_eq!(reg.sweep_members(id).len(), 2);
+        assert_eq!(reg.list_for_twin(&twin).len(), REGISTRY_CAP_PER_TWIN + 2);
+    }
+
+    #[test]
+    fn deleting_a_sweep_removes_its_finished_members_and_then_itself() {
+        let mut reg = ExperimentRegistry::new();
+        let twin = TwinId("t".into());
+        let id = two_point_sweep(&mut reg, &twin);
+        let members = reg.sweep(id).unwrap().members.clone();
+        reg.set_status(members[0], RunStatus::Done { wall_time_ms: 0 });
+        // The running member holds the family open.
+        reg.set_status(members[1], RunStatus::Running { t_current: 0.0 });
+        assert_eq!(reg.delete_sweep(id), vec![members[0]]);
+        assert_eq!(reg.sweep(id).unwrap().members, vec![members[1]]);
+        reg.set_status(members[1], RunStatus::Cancelled);
+        assert_eq!(reg.delete_sweep(id), vec![members[1]]);
+        assert!(reg.sweep(id).is_none());
+        assert!(reg.list_for_twin(&twin).is_empty());
+    }
+
     #[test]
     fn merge_deltas() {
         let mut base = RunResult {
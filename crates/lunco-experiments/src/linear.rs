@@ -0,0 +1,884 @@
+//! Linear state-space models — what linearizing a model around an operating
+//! point produces, and the frequency-domain views of one.
+//!
+//! ```text
+//! ẋ = A·x + B·u
+//! y = C·x + D·u
+//! ```
+//!
+//! `x`, `u` and `y` are deviations from the [`OperatingPoint`]. The host crate
+//! computes the matrices (from the compiled model's Jacobians); this module
+//! only does the dense linear algebra on the result — transfer functions,
+//! poles, SISO zeros — and the MAT v4 export. Models are controller-design
+//! sized (tens of states), so everything is plain dense `O(n³)` code with no
+//! linear-algebra dependency.
+
+use serde::{Deserialize, Serialize};
+
+use crate::result_file::{put_header, MAT4_F64, MAT4_TEXT};
+
+/// A complex number, as transfer-function values and eigenvalues need.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Complex {
+    pub const fn new(re: f64, im: f64) -> Self {
+        Self { re, im }
+    }
+
+    pub fn norm(self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    /// Argument in radians, `(-π, π]`.
+    pub fn arg(self) -> f64 {
+        self.im.atan2(self.re)
+    }
+
+    fn add(self, o: Self) -> Self {
+        Self::new(self.re + o.re, self.im + o.im)
+    }
+
+    fn sub(self, o: Self) -> Self {
+        Self::new(self.re - o.re, self.im - o.im)
+    }
+
+    fn mul(self, o: Self) -> Self {
+        Self::new(
+            self.re * o.re - self.im * o.im,
+            self.re * o.im + self.im * o.re,
+        )
+    }
+
+    fn div(self, o: Self) -> Self {
+        let d = o.re * o.re + o.im * o.im;
+        Self::new(
+            (self.re * o.re + self.im * o.im) / d,
+            (self.im * o.re - self.re * o.im) / d,
+        )
+    }
+}
+
+/// How the Jacobians were taken.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JacobianMethod {
+    /// One-sided differences, `n + 1` evaluations per Jacobian; `O(h)`.
+    Forward,
+    /// Central differences, `2n` evaluations; `O(h²)`.
+    #[default]
+    Central,
+    /// Central differences at `h` and `h/2` combined by Richardson
+    /// extrapolation, `4n` evaluations; `O(h⁴)` — close to an exact
+    /// Jacobian for smooth models.
+    Richardson,
+}
+
+impl JacobianMethod {
+    /// Parse the command / CLI token. Empty means the default.
+    pub fn from_token(token: &str) -> Option<Self> {
+        match token.trim().to_ascii_lowercase().as_str() {
+            "" | "central" => Some(Self::Central),
+            "forward" => Some(Self::Forward),
+            "richardson" | "extrapolated" => Some(Self::Richardson),
+            _ => None,
+        }
+    }
+
+    /// Differentiate `f` at `x` along every coordinate. Column `j` of the
+    /// returned row-major matrix is `∂f/∂x_j`; `f0` is `f(x)`, which the
+    /// forward scheme reuses.
+    pub fn jacobian<E>(
+        self,
+        x: &[f64],
+        f0: &[f64],
+        mut f: impl FnMut(&[f64]) -> Result<Vec<f64>, E>,
+    ) -> Result<Vec<Vec<f64>>, E> {
+        let mut jac = vec![vec![0.0; x.len()]; f0.len()];
+        let mut probe = x.to_vec();
+        let mut at = |probe: &mut Vec<f64>, j: usize, dx: f64| {
+            probe[j] = x[j] + dx;
+            let v = f(probe);
+            probe[j] = x[j];
+            v
+        };
+        for j in 0..x.len() {
+            let scale = x[j].abs().max(1.0);
+            let column = match self {
+                Self::Forward => {
+                    let h = f64::EPSILON.sqrt() * scale;
+                    let up = at(&mut probe, j, h)?;
+                    up.iter().zip(f0).map(|(u, c)| (u - c) / h).collect()
+                }
+                Self::Central => {
+                    let h = f64::EPSILON.cbrt() * scale;
+                    central(&at(&mut probe, j, h)?, &at(&mut probe, j, -h)?, h)
+                }
+                Self::Richardson => {
+                    let h = 1e-3 * scale;
+                    let coarse = central(&at(&mut probe, j, h)?, &at(&mut probe, j, -h)?, h);
+                    let fine = central(
+                        &at(&mut probe, j, h / 2.0)?,
+                        &at(&mut probe, j, -h / 2.0)?,
+                        h / 2.0,
+                    );
+                    fine.iter()
+                        .zip(&coarse)
+                        .map(|(f, c)| (4.0 * f - c) / 3.0)
+                        .collect::<Vec<_>>()
+                }
+            };
+            for (row, d) in jac.iter_mut().zip(column) {
+                row[j] = d;
+            }
+        }
+        Ok(jac)
+    }
+}
+
+fn central(up: &[f64], down: &[f64], h: f64) -> Vec<f64> {
+    up.iter()
+        .zip(down)
+        .map(|(u, d)| (u - d) / (2.0 * h))
+        .collect()
+}
+
+/// Where the model was linearized. Vectors are in the order of the
+/// [`StateSpace`] names.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OperatingPoint {
+    pub time: f64,
+    /// Found by a steady-state search (`ẋ = 0`) rather than by simulating to
+    /// `time`.
+    pub steady_state: bool,
+    pub states: Vec<f64>,
+    pub inputs: Vec<f64>,
+    pub outputs: Vec<f64>,
+    /// `max |ẋ|` at the point — zero at a true equilibrium; a large value
+    /// means the linear model describes a trajectory, not an equilibrium.
+    pub residual: f64,
+}
+
+/// A linear model `(A, B, C, D)` with named states, inputs and outputs.
+/// Matrices are row-major: `a[i][j] = ∂ẋ_i/∂x_j`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StateSpace {
+    /// Class the model was linearized from.
+    pub model: String,
+    pub states: Vec<String>,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+    pub a: Vec<Vec<f64>>,
+    pub b: Vec<Vec<f64>>,
+    pub c: Vec<Vec<f64>>,
+    pub d: Vec<Vec<f64>>,
+    pub operating_point: OperatingPoint,
+    pub jacobian: JacobianMethod,
+}
+
+/// One point of a frequency response.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FrequencyPoint {
+    /// Angular frequency, rad/s.
+    pub omega: f64,
+    pub value: Complex,
+    pub magnitude_db: f64,
+    /// Unwrapped along the sweep, degrees.
+    pub phase_deg: f64,
+}
+
+impl StateSpace {
+    /// Check that the matrix shapes agree with the name lists.
+    pub fn validate(&self) -> Result<(), String> {
+        let (n, m, p) = (self.states.len(), self.inputs.len(), self.outputs.len());
+        let shape = |name: &str, mat: &[Vec<f64>], rows: usize, cols: usize| {
+            if mat.len() != rows || mat.iter().any(|r| r.len() != cols) {
+                Err(format!("{name} is not {rows}×{cols}"))
+            } else {
+                Ok(())
+            }
+        };
+        shape("A", &self.a, n, n)?;
+        shape("B", &self.b, n, m)?;
+        shape("C", &self.c, p, n)?;
+        shape("D", &self.d, p, m)
+    }
+
+    /// `G(s) = C (sI − A)⁻¹ B + D` for one input/output pair. `None` when `s`
+    /// is a pole (or the pair is out of range).
+    pub fn transfer(&self, input: usize, output: usize, s: Complex) -> Option<Complex> {
+        let n = self.states.len();
+        let d = Complex::new(*self.d.get(output)?.get(input)?, 0.0);
+        if n == 0 {
+            return Some(d);
+        }
+        let mut m: Vec<Vec<Complex>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| {
+                        let diag = if i == j { s } else { Complex::default() };
+                        diag.sub(Complex::new(self.a[i][j], 0.0))
+                    })
+                    .collect()
+            })
+            .collect();
+        let mut rhs: Vec<Complex> = (0..n)
+            .map(|i| Complex::new(self.b[i][input], 0.0))
+            .collect();
+        let x = complex_solve(&mut m, &mut rhs)?;
+        let c = self.c.get(output)?;
+        Some(
+            x.iter()
+                .zip(c)
+                .fold(d, |acc, (xi, ci)| acc.add(xi.mul(Complex::new(*ci, 0.0)))),
+        )
+    }
+
+    /// Frequency response of one input/output pair at `omegas` (rad/s).
+    /// Frequencies that land exactly on a pole are skipped.
+    pub fn frequency_response(
+        &self,
+        input: usize,
+        output: usize,
+        omegas: &[f64],
+    ) -> Vec<FrequencyPoint> {
+        let mut out: Vec<FrequencyPoint> = Vec::with_capacity(omegas.len());
+        for &omega in omegas {
+            let Some(value) = self.transfer(input, output, Complex::new(0.0, omega)) else {
+                continue;
+            };
+            if !value.re.is_finite() || !value.im.is_finite() {
+                continue;
+            }
+            let mut phase = value.arg().to_degrees();
+            if let Some(prev) = out.last() {
+                phase += 360.0 * ((prev.phase_deg - phase) / 360.0).round();
+            }
+            out.push(FrequencyPoint {
+                omega,
+                value,
+                magnitude_db: 20.0 * value.norm().log10(),
+                phase_deg: phase,
+            });
+        }
+        out
+    }
+
+    /// A frequency range covering the dynamics: a decade below the slowest
+    /// and above the fastest non-zero pole/zero, `[0.01, 100]` rad/s when
+    /// there are none.
+    pub fn frequency_range(&self, input: usize, output: usize) -> (f64, f64) {
+        let corners: Vec<f64> = self
+            .poles()
+            .into_iter()
+            .chain(self.zeros(input, output))
+            .map(Complex::norm)
+            .filter(|w| *w > 1e-9 && w.is_finite())
+            .collect();
+        let lo = corners.iter().copied().fold(f64::INFINITY, f64::min);
+        let hi = corners.iter().copied().fold(0.0, f64::max);
+        if corners.is_empty() {
+            (1e-2, 1e2)
+        } else {
+            (lo / 10.0, hi * 10.0)
+        }
+    }
+
+    /// Eigenvalues of `A`.
+    pub fn poles(&self) -> Vec<Complex> {
+        eigenvalues(&self.a)
+    }
+
+    /// Transmission zeros of one input/output pair: the roots of the
+    /// numerator of `G(s)`. With `Δ(s) = det(sI − A)`,
+    /// `det(sI − A + b·c) = Δ(s)·(1 + c(sI − A)⁻¹b)`, so the numerator is
+    /// `det(sI − A + b·c) − Δ(s) + d·Δ(s)` — two characteristic polynomials.
+    pub fn zeros(&self, input: usize, output: usize) -> Vec<Complex> {
+        let n = self.states.len();
+        let (Some(c), Some(d)) = (
+            self.c.get(output),
+            self.d.get(output).and_then(|r| r.get(input)),
+        ) else {
+            return Vec::new();
+        };
+        if n == 0 || input >= self.inputs.len() {
+            return Vec::new();
+        }
+        let closed: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| self.a[i][j] - self.b[i][input] * c[j])
+                    .collect()
+            })
+            .collect();
+        let open = characteristic_polynomial(&self.a);
+        let shifted = characteristic_polynomial(&closed);
+        let numerator: Vec<f64> = shifted
+            .iter()
+            .zip(&open)
+            .map(|(s, o)| s - o + d * o)
+            .collect();
+        polynomial_roots(&numerator)
+    }
+
+    /// The matrices as a MAT v4 file: `A`, `B`, `C`, `D`, the operating
+    /// point `x0`, `u0`, `y0`, `t0`, and the names as char matrices
+    /// `stateName`, `inputName`, `outputName` (one row per name) — what
+    /// `load` in MATLAB / Octave or `scipy.io.loadmat` hands straight to a
+    /// control toolbox.
+    pub fn encode_mat4(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        let (n, m, p) = (self.states.len(), self.inputs.len(), self.outputs.len());
+        put_matrix(&mut out, "A", &self.a, n, n);
+        put_matrix(&mut out, "B", &self.b, n, m);
+        put_matrix(&mut out, "C", &self.c, p, n);
+        put_matrix(&mut out, "D", &self.d, p, m);
+        let op = &self.operating_point;
+        put_column(&mut out, "x0", &op.states);
+        put_column(&mut out, "u0", &op.inputs);
+        put_column(&mut out, "y0", &op.outputs);
+        put_column(&mut out, "t0", &[op.time]);
+        put_names(&mut out, "stateName", &self.states);
+        put_names(&mut out, "inputName", &self.inputs);
+        put_names(&mut out, "outputName", &self.outputs);
+        out
+    }
+}
+
+/// `count` angular frequencies spaced logarithmically over `[lo, hi]`.
+pub fn log_frequencies(lo: f64, hi: f64, count: usize) -> Vec<f64> {
+    let (a, b) = (lo.max(f64::MIN_POSITIVE).log10(), hi.max(lo).log10());
+    let steps = count.max(2) - 1;
+    (0..=steps)
+        .map(|k| 10f64.powf(a + (b - a) * k as f64 / steps as f64))
+        .collect()
+}
+
+// ---------- MAT v4 ----------
+
+/// MAT v4 numeric matrices are column-major.
+fn put_matrix(out: &mut Vec<u8>, name: &str, mat: &[Vec<f64>], rows: usize, cols: usize) {
+    put_header(out, name, MAT4_F64, rows, cols);
+    for j in 0..cols {
+        for row in mat.iter().take(rows) {
+            out.extend_from_slice(&row.get(j).copied().unwrap_or(0.0).to_le_bytes());
+        }
+    }
+}
+
+fn put_column(out: &mut Vec<u8>, name: &str, values: &[f64]) {
+    put_header(out, name, MAT4_F64, values.len(), 1);
+    for v in values {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+}
+
+/// A char matrix with one blank-padded name per row (unlike the trajectory
+/// files' `binTrans` text, which stores one per column).
+fn put_names(out: &mut Vec<u8>, name: &str, names: &[String]) {
+    let width = names.iter().map(String::len).max().unwrap_or(0);
+    put_header(out, name, MAT4_TEXT, names.len(), width);
+    for c in 0..width {
+        for s in names {
+            out.push(*s.as_bytes().get(c).unwrap_or(&b' '));
+        }
+    }
+}
+
+// ---------- Dense linear algebra ----------
+//
+// The elimination and QR loops address rows and columns by position, the way
+// the textbook algorithms are written; clippy's iterator rewrites would only
+// obscure which element each update touches.
+
+/// Solve `m·x = rhs` by Gaussian elimination with partial pivoting. `None`
+/// when `m` is singular. Both arguments are consumed as scratch.
+#[allow(clippy::needless_range_loop)]
+pub fn solve(mut m: Vec<Vec<f64>>, mut rhs: Vec<f64>) -> Option<Vec<f64>> {
+    let n = rhs.len();
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&i, &j| m[i][k].abs().total_cmp(&m[j][k].abs()))?;
+        if m[pivot][k] == 0.0 || !m[pivot][k].is_finite() {
+            return None;
+        }
+        m.swap(k, pivot);
+        rhs.swap(k, pivot);
+        for i in k + 1..n {
+            let f = m[i][k] / m[k][k];
+            if f == 0.0 {
+                continue;
+            }
+            for j in k..n {
+                m[i][j] -= f * m[k][j];
+            }
+            rhs[i] -= f * rhs[k];
+        }
+    }
+    let mut x = vec![0.0; n];
+    for k in (0..n).rev() {
+        let tail: f64 = (k + 1..n).map(|j| m[k][j] * x[j]).sum();
+        x[k] = (rhs[k] - tail) / m[k][k];
+    }
+    Some(x)
+}
+
+#[allow(clippy::needless_range_loop)]
+fn complex_solve(m: &mut [Vec<Complex>], rhs: &mut [Complex]) -> Option<Vec<Complex>> {
+    let n = rhs.len();
+    for k in 0..n {
+        let pivot = (k..n).max_by(|&i, &j| m[i][k].norm().total_cmp(&m[j][k].norm()))?;
+        if m[pivot][k].norm() == 0.0 {
+            return None;
+        }
+        m.swap(k, pivot);
+        rhs.swap(k, pivot);
+        for i in k + 1..n {
+            let f = m[i][k].div(m[k][k]);
+            for j in k..n {
+                m[i][j] = m[i][j].sub(f.mul(m[k][j]));
+            }
+            rhs[i] = rhs[i].sub(f.mul(rhs[k]));
+        }
+    }
+    let mut x = vec![Complex::default(); n];
+    for k in (0..n).rev() {
+        let tail = (k + 1..n).fold(Complex::default(), |acc, j| acc.add(m[k][j].mul(x[j])));
+        x[k] = rhs[k].sub(tail).div(m[k][k]);
+    }
+    Some(x)
+}
+
+/// Eigenvalues of a real square matrix: balance, reduce to upper Hessenberg
+/// form, then the shifted QR iteration (the classic `balanc` / `elmhes` /
+/// `hqr` sequence). An eigenvalue the iteration cannot isolate comes back
+/// as `NaN`.
+pub fn eigenvalues(matrix: &[Vec<f64>]) -> Vec<Complex> {
+    let n = matrix.len();
+    let mut a: Vec<Vec<f64>> = matrix.to_vec();
+    if n == 0 || a.iter().any(|r| r.len() != n) {
+        return Vec::new();
+    }
+    balance(&mut a);
+    hessenberg(&mut a);
+    hqr(&mut a)
+}
+
+#[allow(clippy::needless_range_loop)]
+fn balance(a: &mut [Vec<f64>]) {
+    const RADIX: f64 = 2.0;
+    let n = a.len();
+    let mut done = false;
+    while !done {
+        done = true;
+        for i in 0..n {
+            let (mut c, mut r) = (0.0, 0.0);
+            for j in (0..n).filter(|&j| j != i) {
+                c += a[j][i].abs();
+                r += a[i][j].abs();
+            }
+            if c == 0.0 || r == 0.0 {
+                continue;
+            }
+            let s = c + r;
+            let mut f = 1.0;
+            let mut g = r / RADIX;
+            while c < g {
+                f *= RADIX;
+                c *= RADIX * RADIX;
+            }
+            g = r * RADIX;
+            while c > g {
+                f /= RADIX;
+                c /= RADIX * RADIX;
+            }
+            if (c + r) / f < 0.95 * s {
+                done = false;
+                for j in 0..n {
+                    a[i][j] /= f;
+                    a[j][i] *= f;
+                }
+            }
+        }
+    }
+}
+
+/// Similarity reduction to upper Hessenberg form by stabilized elementary
+/// transformations; everything below the subdiagonal is zeroed.
+#[allow(clippy::needless_range_loop)]
+fn hessenberg(a: &mut [Vec<f64>]) {
+    let n = a.len();
+    for m in 1..n.saturating_sub(1) {
+        let mut x = 0.0f64;
+        let mut pivot = m;
+        for j in m..n {
+            if a[j][m - 1].abs() > x.abs() {
+                x = a[j][m - 1];
+                pivot = j;
+            }
+        }
+        if pivot != m {
+            a.swap(pivot, m);
+            for row in a.iter_mut() {
+                row.swap(pivot, m);
+            }
+        }
+        if x == 0.0 {
+            continue;
+        }
+        for i in m + 1..n {
+            let y = a[i][m - 1] / x;
+            if y == 0.0 {
+                continue;
+            }
+            for j in m..n {
+                a[i][j] -= y * a[m][j];
+            }
+            for row in a.iter_mut() {
+                row[m] += y * row[i];
+            }
+        }
+    }
+    for (i, row) in a.iter_mut().enumerate() {
+        for v in row.iter_mut().take(i.saturating_sub(1)) {
+            *v = 0.0;
+        }
+    }
+}
+
+/// Francis double-shift QR on an upper Hessenberg matrix (destroyed).
+#[allow(clippy::needless_range_loop)]
+fn hqr(a: &mut [Vec<f64>]) -> Vec<Complex> {
+    let n = a.len();
+    let mut eig = vec![Complex::default(); n];
+    let mut norm = 0.0;
+    for i in 0..n {
+        for j in i.saturating_sub(1)..n {
+            norm += a[i][j].abs();
+        }
+    }
+    let mut shift = 0.0;
+    let mut remaining = n;
+    'deflate: while remaining > 0 {
+        let nn = remaining - 1;
+        let mut its = 0;
+        loop {
+            // Find a negligible subdiagonal element to split the problem at.
+            let mut l = nn;
+            while l >= 1 {
+                let mut s = a[l - 1][l - 1].abs() + a[l][l].abs();
+                if s == 0.0 {
+                    s = norm;
+                }
+                if a[l][l - 1].abs() + s == s {
+                    a[l][l - 1] = 0.0;
+                    break;
+                }
+                l -= 1;
+            }
+            let mut x = a[nn][nn];
+            if l == nn {
+                eig[nn] = Complex::new(x + shift, 0.0);
+                remaining -= 1;
+                continue 'deflate;
+            }
+            let mut y = a[nn - 1][nn - 1];
+            let mut w = a[nn][nn - 1] * a[nn - 1][nn];
+            if l == nn - 1 {
+                // A 2×2 block: a real pair or a complex-conjugate pair.
+                let p = 0.5 * (y - x);
+                let q = p * p + w;
+                let z = q.abs().sqrt();
+                x += shift;
+                if q >= 0.0 {
+                    let z = p + z.copysign(p);
+                    let lower = if z != 0.0 { x - w / z } else { x + z };
+                    eig[nn - 1] = Complex::new(x + z, 0.0);
+                    eig[nn] = Complex::new(lower, 0.0);
+                } else {
+                    eig[nn - 1] = Complex::new(x + p, -z);
+                    eig[nn] = Complex::new(x + p, z);
+                }
+                remaining -= 2;
+                continue 'deflate;
+            }
+            if its == 60 {
+                for e in eig.iter_mut().take(nn + 1) {
+                    *e = Complex::new(f64::NAN, f64::NAN);
+                }
+                break 'deflate;
+            }
+            if its == 10 || its == 20 {
+                // Exceptional shift to break a cycle.
+                shift += x;
+                for (i, row) in a.iter_mut().enumerate().take(nn + 1) {
+                    row[i] -= x;
+                }
+                let s = a[nn][nn - 1].abs() + a[nn - 1][nn - 2].abs();
+                x = 0.75 * s;
+                y = x;
+                w = -0.4375 * s * s;
+            }
+            its += 1;
+
+            // Look for two consecutive small subdiagonal elements.
+            let (mut p, mut q, mut r);
+            let mut m = nn - 2;
+            loop {
+                let z = a[m][m];
+                let rr = x - z;
+                let ss = y - z;
+                p = (rr * ss - w) / a[m + 1][m] + a[m][m + 1];
+                q = a[m + 1][m + 1] - z - rr - ss;
+                r = a[m + 2][m + 1];
+                let s = p.abs() + q.abs() + r.abs();
+                p /= s;
+                q /= s;
+                r /= s;
+                if m == l {
+                    break;
+                }
+                let u = a[m][m - 1].abs() * (q.abs() + r.abs());
+                let v = p.abs() * (a[m - 1][m - 1].abs() + z.abs() + a[m + 1][m + 1].abs());
+                if u + v == v {
+                    break;
+                }
+                m -= 1;
+            }
+            for i in m + 2..=nn {
+                a[i][i - 2] = 0.0;
+                if i != m + 2 {
+                    a[i][i - 3] = 0.0;
+                }
+            }
+
+            // Double QR step on rows l..=nn and columns m..=nn.
+            for k in m..nn {
+                if k != m {
+                    p = a[k][k - 1];
+                    q = a[k + 1][k - 1];
+                    r = if k != nn - 1 { a[k + 2][k - 1] } else { 0.0 };
+                    x = p.abs() + q.abs() + r.abs();
+                    if x != 0.0 {
+                        p /= x;
+                        q /= x;
+                        r /= x;
+                    }
+                }
+                let s = (p * p + q * q + r * r).sqrt().copysign(p);
+                if s == 0.0 {
+                    continue;
+                }
+                if k == m {
+                    if l != m {
+                        a[k][k - 1] = -a[k][k - 1];
+                    }
+                } else {
+                    a[k][k - 1] = -s * x;
+                }
+                p += s;
+                x = p / s;
+                y = q / s;
+                let z = r / s;
+                q /= p;
+                r /= p;
+                for j in k..=nn {
+                    let mut pp = a[k][j] + q * a[k + 1][j];
+                    if k != nn - 1 {
+                        pp += r * a[k + 2][j];
+                        a[k + 2][j] -= pp * z;
+                    }
+                    a[k + 1][j] -= pp * y;
+                    a[k][j] -= pp * x;
+                }
+                for row in a.iter_mut().take(nn.min(k + 3) + 1).skip(l) {
+                    let mut pp = x * row[k] + y * row[k + 1];
+                    if k != nn - 1 {
+                        pp += z * row[k + 2];
+                        row[k + 2] -= pp * r;
+                    }
+                    row[k + 1] -= pp * q;
+                    row[k] -= pp;
+                }
+            }
+        }
+    }
+    eig
+}
+
+/// Coefficients of `det(sI − M)`, highest power first (monic, length
+/// `n + 1`), from the eigenvalues of `M`.
+fn characteristic_polynomial(m: &[Vec<f64>]) -> Vec<f64> {
+    let mut coeffs = vec![Complex::new(1.0, 0.0)];
+    for root in eigenvalues(m) {
+        let mut next = coeffs.clone();
+        next.push(Complex::default());
+        for (k, c) in coeffs.iter().enumerate() {
+            next[k + 1] = next[k + 1].sub(c.mul(root));
+        }
+        coeffs = next;
+    }
+    coeffs.into_iter().map(|c| c.re).collect()
+}
+
+/// Roots of a real polynomial (highest power first) as the eigenvalues of
+/// its companion matrix. Leading coefficients that are round-off relative
+/// to the largest one are dropped first.
+fn polynomial_roots(coeffs: &[f64]) -> Vec<Complex> {
+    let scale = coeffs.iter().fold(0.0f64, |m, c| m.max(c.abs()));
+    if scale == 0.0 {
+        return Vec::new();
+    }
+    let Some(lead) = coeffs.iter().position(|c| c.abs() > 1e-9 * scale) else {
+        return Vec::new();
+    };
+    let poly = &coeffs[lead..];
+    let degree = poly.len() - 1;
+    if degree == 0 {
+        return Vec::new();
+    }
+    let mut companion = vec![vec![0.0; degree]; degree];
+    for (j, c) in poly[1..].iter().enumerate() {
+        companion[0][j] = -c / poly[0];
+    }
+    for i in 1..degree {
+        companion[i][i - 1] = 1.0;
+    }
+    eigenvalues(&companion)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(mut v: Vec<Complex>) -> Vec<Complex> {
+        v.sort_by(|a, b| a.re.total_cmp(&b.re).then(a.im.total_cmp(&b.im)));
+        v
+    }
+
+    fn close(a: Complex, b: Complex) -> bool {
+        (a.re - b.re).abs() < 1e-8 && (a.im - b.im).abs() < 1e-8
+    }
+
+    /// Mass-spring-damper `ẍ + 2ζω·ẋ + ω²x = u`, `y = x`, with `ω = 2`,
+    /// `ζ = 0.25`, and a feedthrough-free zero added via `y = x + 0.5·ẋ`.
+    fn oscillator() -> StateSpace {
+        StateSpace {
+            model: "Oscillator".into(),
+            states: vec!["x".into(), "v".into()],
+            inputs: vec!["u".into()],
+            outputs: vec!["y".into()],
+            a: vec![vec![0.0, 1.0], vec![-4.0, -1.0]],
+            b: vec![vec![0.0], vec![1.0]],
+            c: vec![vec![1.0, 0.5]],
+            d: vec![vec![0.0]],
+            ..StateSpace::default()
+        }
+    }
+
+    #[test]
+    fn poles_and_zeros_of_a_second_order_system() {
+        let ss = oscillator();
+        ss.validate().unwrap();
+        // s² + s + 4 → −0.5 ± j·√3.75
+        let poles = sorted(ss.poles());
+        let im = 3.75f64.sqrt();
+        assert!(close(poles[0], Complex::new(-0.5, -im)), "{poles:?}");
+        assert!(close(poles[1], Complex::new(-0.5, im)), "{poles:?}");
+        // Numerator 0.5s + 1 → zero at −2.
+        let zeros = ss.zeros(0, 0);
+        assert_eq!(zeros.len(), 1, "{zeros:?}");
+        assert!(close(zeros[0], Complex::new(-2.0, 0.0)), "{zeros:?}");
+    }
+
+    #[test]
+    fn eigenvalues_of_a_larger_matrix_match_its_trace_and_known_roots() {
+        // Companion of (s+1)(s+2)(s+3)(s+4)(s+5) = s⁵+15s⁴+85s³+225s²+274s+120.
+        let roots = sorted(polynomial_roots(&[1.0, 15.0, 85.0, 225.0, 274.0, 120.0]));
+        for (k, r) in roots.iter().enumerate() {
+            assert!(close(*r, Complex::new(-5.0 + k as f64, 0.0)), "{roots:?}");
+        }
+        let a = vec![
+            vec![4.0, 1.0, -2.0, 2.0],
+            vec![1.0, 2.0, 0.0, 1.0],
+            vec![-2.0, 0.0, 3.0, -2.0],
+            vec![2.0, 1.0, -2.0, -1.0],
+        ];
+        let eig = eigenvalues(&a);
+        let trace: f64 = eig.iter().map(|e| e.re).sum();
+        assert!((trace - 8.0).abs() < 1e-9, "{eig:?}");
+        assert!(eig.iter().all(|e| e.im.abs() < 1e-9), "symmetric: {eig:?}");
+    }
+
+    #[test]
+    fn frequency_response_has_the_dc_gain_and_unwrapped_phase() {
+        let ss = oscillator();
+        let omegas = log_frequencies(1e-3, 1e3, 200);
+        let resp = ss.frequency_response(0, 0, &omegas);
+        assert_eq!(resp.len(), 200);
+        // DC gain 1/4 → −12.04 dB, phase ≈ 0.
+        assert!((resp[0].magnitude_db - 20.0 * 0.25f64.log10()).abs() < 1e-3);
+        assert!(resp[0].phase_deg.abs() < 0.1);
+        // Two poles, one zero → −90° at high frequency, continuously.
+        assert!((resp[199].phase_deg + 90.0).abs() < 1.0, "{:?}", resp[199]);
+        assert!(resp
+            .windows(2)
+            .all(|w| (w[1].phase_deg - w[0].phase_deg).abs() < 90.0));
+        let (lo, hi) = ss.frequency_range(0, 0);
+        assert!(
+            (lo - 0.2).abs() < 1e-9 && (hi - 20.0).abs() < 1e-9,
+            "{lo} {hi}"
+        );
+    }
+
+    #[test]
+    fn jacobian_schemes_recover_a_known_derivative() {
+        let f = |x: &[f64]| -> Result<Vec<f64>, ()> { Ok(vec![x[0].sin() * x[1], x[1].exp()]) };
+        let x = [0.3, 1.2];
+        let f0 = f(&x).unwrap();
+        let exact = [[0.3f64.cos() * 1.2, 0.3f64.sin()], [0.0, 1.2f64.exp()]];
+        for (method, tol) in [
+            (JacobianMethod::Forward, 1e-6),
+            (JacobianMethod::Central, 1e-9),
+            (JacobianMethod::Richardson, 1e-10),
+        ] {
+            let jac = method.jacobian(&x, &f0, f).unwrap();
+            for i in 0..2 {
+                for j in 0..2 {
+                    assert!((jac[i][j] - exact[i][j]).abs() < tol, "{method:?} {jac:?}");
+                }
+            }
+        }
+        assert_eq!(
+            JacobianMethod::from_token(""),
+            Some(JacobianMethod::Central)
+        );
+        assert_eq!(JacobianMethod::from_token("bogus"), None);
+    }
+
+    #[test]
+    fn mat4_export_is_column_major_with_name_rows() {
+        let ss = oscillator();
+        let bytes = ss.encode_mat4();
+        // First matrix: A, 2×2, header of five i32s then "A\0".
+        let word = |i: usize| i32::from_le_bytes(bytes[i * 4..i * 4 + 4].try_into().unwrap());
+        assert_eq!((word(0), word(1), word(2), word(4)), (0, 2, 2, 2));
+        let data: Vec<f64> = (0..4)
+            .map(|k| f64::from_le_bytes(bytes[22 + 8 * k..30 + 8 * k].try_into().unwrap()))
+            .collect();
+        assert_eq!(data, vec![0.0, -4.0, 1.0, -1.0]);
+        assert!(bytes.windows(9).any(|w| w == b"stateName"));
+        assert_eq!(
+            solve(vec![vec![2.0, 1.0], vec![1.0, 3.0]], vec![3.0, 5.0]).map(|x| {
+                x.iter()
+                    .map(|v| (v * 1e9).round() / 1e9)
+                    .collect::<Vec<_>>()
+            }),
+            Some(vec![0.8, 1.4])
+        );
+    }
+}
@@ -0,0 +1,784 @@
+//! Regression checks: does a model still reproduce a stored trajectory?
+//!
+//! A [`SuiteSpec`] lists cases — model, bounds, overrides, the signals to
+//! check and the reference result to check them against. The host crate runs
+//! each case; this module judges the outcome and reports it.
+//!
+//! The judgement is a csv-compare-style **tolerance tube** around the
+//! reference. At time `t` the tube spans every reference value within
+//! `t ± tube.time` (so a curve that is merely shifted in time, e.g. an event
+//! found a step later, still passes), widened by `max(tube.abs, tube.rel·|y|)`
+//! around each of them. The run passes a signal when it stays inside the tube
+//! at every run sample and at every reference sample, and covers the
+//! reference's time span. Reports carry the largest plain deviation
+//! `|run − reference|` and how far the worst sample left the tube.
+//!
+//! Reports render as JUnit XML (one `testsuite` per case, one `testcase` per
+//! signal) for CI, and as a self-contained HTML page with an SVG diff plot per
+//! signal.
+
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ParamPath, ParamValue, RunBounds, RunResult, RuntimeMode, SolverId};
+
+/// Tolerance tube half-widths.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Tube {
+    /// Relative tolerance on the value, as a fraction of `|reference|`.
+    pub rel: f64,
+    /// Absolute tolerance on the value — the floor under `rel` near zero.
+    pub abs: f64,
+    /// Time-shift tolerance, seconds.
+    pub time: f64,
+}
+
+impl Default for Tube {
+    fn default() -> Self {
+        Self {
+            rel: 1e-3,
+            abs: 1e-6,
+            time: 0.0,
+        }
+    }
+}
+
+impl Tube {
+    fn half_height(&self, y: f64) -> f64 {
+        self.abs.max(self.rel * y.abs())
+    }
+}
+
+/// A regression suite, as stored on disk (JSON). Paths in cases are relative
+/// to the suite file.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct SuiteSpec {
+    /// Suite name — the JUnit `testsuites` name. Empty → the file stem.
+    #[serde(default)]
+    pub name: String,
+    /// Tube for every signal a case does not override.
+    #[serde(default)]
+    pub tube: Tube,
+    pub cases: Vec<CaseSpec>,
+}
+
+/// One simulation and the signals it must reproduce.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct CaseSpec {
+    pub name: String,
+    /// `.mo` file holding the model.
+    pub model: String,
+    /// Class to simulate. `None` → the file's model.
+    #[serde(default)]
+    pub class: Option<String>,
+    /// Bounds; each `None` falls back to the class's `experiment(...)`
+    /// annotation, then to the runner defaults.
+    #[serde(default)]
+    pub t_start: Option<f64>,
+    #[serde(default)]
+    pub t_end: Option<f64>,
+    #[serde(default)]
+    pub dt: Option<f64>,
+    #[serde(default)]
+    pub n_intervals: Option<u32>,
+    #[serde(default)]
+    pub tolerance: Option<f64>,
+    #[serde(default)]
+    pub solver: Option<SolverId>,
+    /// Parameter overrides, `name → value`.
+    #[serde(default)]
+    pub overrides: BTreeMap<String, f64>,
+    /// Input values held for the run, `name → value`.
+    #[serde(default)]
+    pub inputs: BTreeMap<String, f64>,
+    /// Signals to check. Empty → every reference variable the run also has.
+    #[serde(default)]
+    pub signals: Vec<String>,
+    /// Reference result (`.mat` MAT v4 / v7.3, `.h5`, `.csv`).
+    pub reference: String,
+    /// Tube for this case's signals; `None` → the suite's.
+    #[serde(default)]
+    pub tube: Option<Tube>,
+    /// Per-signal tubes, over `tube`.
+    #[serde(default)]
+    pub signal_tubes: BTreeMap<String, Tube>,
+}
+
+impl CaseSpec {
+    /// The run bounds: this case's fields over `fallback` (the model's
+    /// annotation, or the runner defaults).
+    pub fn bounds(&self, fallback: RunBounds) -> RunBounds {
+        RunBounds {
+            t_start: self.t_start.unwrap_or(fallback.t_start),
+            t_end: self.t_end.unwrap_or(fallback.t_end),
+            dt: self.dt.or(fallback.dt),
+            n_intervals: self.n_intervals.or(fallback.n_intervals),
+            tolerance: self.tolerance.or(fallback.tolerance),
+            solver: self.solver.clone().or(fallback.solver),
+            h0: fallback.h0,
+            runtime: RuntimeMode::Batch,
+        }
+    }
+
+    /// Overrides and inputs as one binding set — the runner applies both the
+    /// same way.
+    pub fn bindings(&self) -> BTreeMap<ParamPath, ParamValue> {
+        self.inputs
+            .iter()
+            .chain(&self.overrides)
+            .map(|(k, v)| (ParamPath(k.clone()), ParamValue::Real(*v)))
+            .collect()
+    }
+
+    fn tube_for(&self, signal: &str, suite: Tube) -> Tube {
+        self.signal_tubes
+            .get(signal)
+            .copied()
+            .unwrap_or(self.tube.unwrap_or(suite))
+    }
+}
+
+/// How one signal fared.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SignalVerdict {
+    pub name: String,
+    pub passed: bool,
+    pub tube: Tube,
+    /// Largest `|run − reference|` over the checked samples.
+    pub max_deviation: f64,
+    /// How far the worst sample lay outside the tube (0 when inside).
+    pub max_excess: f64,
+    /// Where the worst sample is — the largest excess when the signal
+    /// failed, the largest deviation when it passed.
+    pub worst_time: f64,
+    /// Samples checked.
+    pub checked: usize,
+    /// Why the signal failed when it is not a tube violation (missing
+    /// variable, short run, non-finite samples).
+    pub failure: Option<String>,
+}
+
+impl SignalVerdict {
+    fn failed(name: &str, tube: Tube, why: impl Into<String>) -> Self {
+        Self {
+            name: name.to_string(),
+            passed: false,
+            tube,
+            max_deviation: f64::NAN,
+            max_excess: f64::NAN,
+            worst_time: f64::NAN,
+            checked: 0,
+            failure: Some(why.into()),
+        }
+    }
+
+    /// One-line summary for logs and JUnit messages.
+    pub fn summary(&self) -> String {
+        if let Some(why) = &self.failure {
+            return why.clone();
+        }
+        if self.passed {
+            format!(
+                "max deviation {:.4e} at t={:.6} ({} samples)",
+                self.max_deviation, self.worst_time, self.checked
+            )
+        } else {
+            format!(
+                "left the tube by {:.4e} at t={:.6} (max deviation {:.4e})",
+                self.max_excess, self.worst_time, self.max_deviation
+            )
+        }
+    }
+}
+
+/// One case's outcome. `error` is set when the case never got to the
+/// comparison (model did not compile, run failed, reference unreadable).
+#[derive(Clone, Debug, Default)]
+pub struct CaseReport {
+    pub name: String,
+    pub model: String,
+    pub wall_time_ms: u64,
+    pub error: Option<String>,
+    pub signals: Vec<SignalVerdict>,
+    /// Kept for the HTML plots.
+    pub reference: Option<RunResult>,
+    pub actual: Option<RunResult>,
+}
+
+impl CaseReport {
+    pub fn passed(&self) -> bool {
+        self.error.is_none() && self.signals.iter().all(|s| s.passed)
+    }
+}
+
+/// A whole suite's outcome.
+#[derive(Clone, Debug, Default)]
+pub struct SuiteReport {
+    pub name: String,
+    pub cases: Vec<CaseReport>,
+}
+
+impl SuiteReport {
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(CaseReport::passed)
+    }
+
+    /// `(signals checked, signals failed, cases errored)`.
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let checked = self.cases.iter().map(|c| c.signals.len()).sum();
+        let failed = self
+            .cases
+            .iter()
+            .flat_map(|c| &c.signals)
+            .filter(|s| !s.passed)
+            .count();
+        let errored = self.cases.iter().filter(|c| c.error.is_some()).count();
+        (checked, failed, errored)
+    }
+}
+
+/// Check `actual` against `reference` for `case`'s signals.
+pub fn check_case(
+    case: &CaseSpec,
+    suite_tube: Tube,
+    reference: &RunResult,
+    actual: &RunResult,
+) -> Vec<SignalVerdict> {
+    let signals: Vec<String> = if case.signals.is_empty() {
+        reference
+            .series
+            .keys()
+            .filter(|name| actual.series.contains_key(*name))
+            .cloned()
+            .collect()
+    } else {
+        case.signals.clone()
+    };
+    if signals.is_empty() {
+        return vec![SignalVerdict::failed(
+            "(signals)",
+            case.tube.unwrap_or(suite_tube),
+            "the run and the reference share no variables",
+        )];
+    }
+    signals
+        .iter()
+        .map(|name| {
+            let tube = case.tube_for(name, suite_tube);
+            match (reference.series.get(name), actual.series.get(name)) {
+                (None, _) => SignalVerdict::failed(name, tube, "not in the reference"),
+                (_, None) => SignalVerdict::failed(name, tube, "not in the run"),
+                (Some(r), Some(a)) => {
+                    check_signal(name, &reference.times, r, &actual.times, a, tube)
+                }
+            }
+        })
+        .collect()
+}
+
+/// Check one signal of a run against its reference through `tube`.
+pub fn check_signal(
+    name: &str,
+    ref_t: &[f64],
+    ref_y: &[f64],
+    act_t: &[f64],
+    act_y: &[f64],
+    tube: Tube,
+) -> SignalVerdict {
+    if ref_t.is_empty() || ref_t.len() != ref_y.len() {
+        return SignalVerdict::failed(name, tube, "reference has no samples");
+    }
+    if act_t.is_empty() || act_t.len() != act_y.len() {
+        return SignalVerdict::failed(name, tube, "run has no samples");
+    }
+    let (r0, r1) = (ref_t[0], ref_t[ref_t.len() - 1]);
+    let (a0, a1) = (act_t[0], act_t[act_t.len() - 1]);
+    let slack = tube.time + 1e-9 * (r1 - r0).abs().max(1.0);
+    if a0 > r0 + slack || a1 < r1 - slack {
+        return SignalVerdict::failed(
+            name,
+            tube,
+            format!("run covers t={a0}..{a1}, reference t={r0}..{r1}"),
+        );
+    }
+
+    let mut verdict = SignalVerdict {
+        name: name.to_string(),
+        passed: true,
+        tube,
+        max_deviation: 0.0,
+        max_excess: 0.0,
+        worst_time: r0,
+        checked: 0,
+        failure: None,
+    };
+    let mut worst_deviation_time = r0;
+    let run_samples = act_t.iter().copied().zip(act_y.iter().copied());
+    let ref_samples = ref_t
+        .iter()
+        .filter(|t| (a0..=a1).contains(*t))
+        .map(|&t| (t, interpolate(act_t, act_y, t)));
+    for (t, y) in run_samples.chain(ref_samples) {
+        if !(r0..=r1).contains(&t) {
+            continue;
+        }
+        if !y.is_finite() {
+            return SignalVerdict::failed(name, tube, format!("run is not finite at t={t}"));
+        }
+        verdict.checked += 1;
+        let deviation = (y - interpolate(ref_t, ref_y, t)).abs();
+        if deviation > verdict.max_deviation {
+            verdict.max_deviation = deviation;
+            worst_deviation_time = t;
+        }
+        let (lo, hi) = tube_bounds(ref_t, ref_y, t, tube);
+        let excess = (lo - y).max(y - hi).max(0.0);
+        if excess > verdict.max_excess {
+            verdict.max_excess = excess;
+            verdict.worst_time = t;
+        }
+    }
+    verdict.passed = verdict.max_excess == 0.0;
+    if verdict.passed {
+        verdict.worst_time = worst_deviation_time;
+    }
+    verdict
+}
+
+/// The tube's `(lower, upper)` edge at `t`: every reference value within
+/// `t ± tube.time` — the window's ends interpolated, samples inside taken
+/// as they are, so both sides of an event jump count — widened by its
+/// half-height.
+pub fn tube_bounds(ref_t: &[f64], ref_y: &[f64], t: f64, tube: Tube) -> (f64, f64) {
+    let (from, to) = (t - tube.time, t + tube.time);
+    let mut lo = f64::INFINITY;
+    let mut hi = f64::NEG_INFINITY;
+    let mut take = |y: f64| {
+        if y.is_finite() {
+            let h = tube.half_height(y);
+            lo = lo.min(y - h);
+            hi = hi.max(y + h);
+        }
+    };
+    take(interpolate(ref_t, ref_y, from));
+    take(interpolate(ref_t, ref_y, to));
+    let start = ref_t.partition_point(|&s| s < from);
+    let end = ref_t.partition_point(|&s| s <= to).min(ref_y.len());
+    for &y in &ref_y[start.min(end)..end] {
+        take(y);
+    }
+    (lo, hi)
+}
+
+/// Linear interpolation in a time-sorted trajectory, clamped at the ends.
+/// At a repeated time (an event) it takes the later sample.
+pub(crate) fn interpolate(ts: &[f64], ys: &[f64], t: f64) -> f64 {
+    let n = ts.len().min(ys.len());
+    if n == 0 {
+        return f64::NAN;
+    }
+    let i = ts[..n].partition_point(|&s| s <= t);
+    if i == 0 {
+        return ys[0];
+    }
+    if i == n {
+        return ys[n - 1];
+    }
+    let (t0, t1) = (ts[i - 1], ts[i]);
+    if t1 <= t0 {
+        return ys[i];
+    }
+    let f = (t - t0) / (t1 - t0);
+    ys[i - 1] + f * (ys[i] - ys[i - 1])
+}
+
+/// JUnit XML for `report`: a `testsuite` per case, a `testcase` per signal.
+/// A case that never reached the comparison is one `testcase` with an
+/// `error`.
+pub fn junit_xml(report: &SuiteReport) -> String {
+    let (checked, failed, errored) = report.counts();
+    let total_s: f64 = report
+        .cases
+        .iter()
+        .map(|c| c.wall_time_ms as f64 / 1000.0)
+        .sum();
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        out,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{failed}\" errors=\"{errored}\" time=\"{total_s:.3}\">",
+        xml_escape(&report.name),
+        checked + errored,
+    );
+    for case in &report.cases {
+        let class = xml_escape(&format!("{}.{}", report.name, case.name));
+        let case_failed = case.signals.iter().filter(|s| !s.passed).count();
+        let case_errors = usize::from(case.error.is_some());
+        let _ = writeln!(
+            out,
+            "  <testsuite name=\"{class}\" tests=\"{}\" failures=\"{case_failed}\" errors=\"{case_errors}\" time=\"{:.3}\">",
+            case.signals.len() + case_errors,
+            case.wall_time_ms as f64 / 1000.0,
+        );
+        if let Some(error) = &case.error {
+            let _ = writeln!(
+                out,
+                "    <testcase classname=\"{class}\" name=\"simulate\">\n      <error message=\"{}\"/>\n    </testcase>",
+                xml_escape(error)
+            );
+        }
+        for signal in &case.signals {
+            let _ = write!(
+                out,
+                "    <testcase classname=\"{class}\" name=\"{}\"",
+                xml_escape(&signal.name)
+            );
+            if signal.passed {
+                let _ = writeln!(out, "/>");
+            } else {
+                let _ = writeln!(
+                    out,
+                    ">\n      <failure message=\"{}\">rel={} abs={} time={}</failure>\n    </testcase>",
+                    xml_escape(&signal.summary()),
+                    signal.tube.rel,
+                    signal.tube.abs,
+                    signal.tube.time,
+                );
+            }
+        }
+        let _ = writeln!(out, "  </testsuite>");
+    }
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// A self-contained HTML page for `report`: a summary table, then per case
+/// an SVG plot per signal — the tube shaded, the reference dashed, the run
+/// solid (red when it failed), the worst sample marked.
+pub fn html_report(report: &SuiteReport) -> String {
+    let (checked, failed, errored) = report.counts();
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>{name}</title>\n<style>\
+         body{{font-family:sans-serif;margin:2em}}table{{border-collapse:collapse}}\
+         td,th{{border:1px solid #ccc;padding:2px 8px;text-align:left}}\
+         .pass{{color:#2a7}}.fail{{color:#c33}}svg{{background:#fafafa;border:1px solid #ddd}}\
+         </style></head><body>\n<h1>{name}</h1>\n<p>{checked} signal(s), {failed} failed, {errored} case error(s) — <b class=\"{class}\">{verdict}</b></p>\n",
+        name = xml_escape(&report.name),
+        class = if report.passed() { "pass" } else { "fail" },
+        verdict = if report.passed() { "PASS" } else { "FAIL" },
+    );
+    out.push_str("<table><tr><th>Case</th><th>Signal</th><th>Result</th><th>Detail</th></tr>\n");
+    for case in &report.cases {
+        if let Some(error) = &case.error {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td>—</td><td class=\"fail\">ERROR</td><td>{}</td></tr>",
+                xml_escape(&case.name),
+                xml_escape(error)
+            );
+        }
+        for s in &case.signals {
+            let _ = writeln!(
+                out,
+                "<tr><td>{}</td><td><a href=\"#{}\">{}</a></td><td class=\"{}\">{}</td><td>{}</td></tr>",
+                xml_escape(&case.name),
+                anchor(&case.name, &s.name),
+                xml_escape(&s.name),
+                if s.passed { "pass" } else { "fail" },
+                if s.passed { "pass" } else { "FAIL" },
+                xml_escape(&s.summary())
+            );
+        }
+    }
+    out.push_str("</table>\n");
+    for case in &report.cases {
+        let _ = writeln!(
+            out,
+            "<h2>{}</h2>\n<p><code>{}</code></p>",
+            xml_escape(&case.name),
+            xml_escape(&case.model)
+        );
+        let (Some(reference), Some(actual)) = (&case.reference, &case.actual) else {
+            continue;
+        };
+        for s in &case.signals {
+            let (Some(ry), Some(ay)) = (reference.series.get(&s.name), actual.series.get(&s.name))
+            else {
+                continue;
+            };
+            let _ = writeln!(
+                out,
+                "<h3 id=\"{}\" class=\"{}\">{}</h3>",
+                anchor(&case.name, &s.name),
+                if s.passed { "pass" } else { "fail" },
+                xml_escape(&s.name)
+            );
+            out.push_str(&signal_svg(&reference.times, ry, &actual.times, ay, s));
+        }
+    }
+    out.push_str("</body></html>\n");
+    out
+}
+
+const PLOT_W: f64 = 720.0;
+const PLOT_H: f64 = 220.0;
+const PLOT_PAD: f64 = 36.0;
+/// Points per drawn curve; longer trajectories are strided down.
+const PLOT_POINTS: usize = 600;
+
+fn signal_svg(
+    ref_t: &[f64],
+    ref_y: &[f64],
+    act_t: &[f64],
+    act_y: &[f64],
+    verdict: &SignalVerdict,
+) -> String {
+    let stride = |n: usize| n.div_ceil(PLOT_POINTS).max(1);
+    let band: Vec<(f64, f64, f64)> = ref_t
+        .iter()
+        .step_by(stride(ref_t.len()))
+        .chain(ref_t.last())
+        .map(|&t| {
+            let (lo, hi) = tube_bounds(ref_t, ref_y, t, verdict.tube);
+            (t, lo, hi)
+        })
+        .filter(|(_, lo, hi)| lo.is_finite() && hi.is_finite())
+        .collect();
+    let finite = |v: &&f64| v.is_finite();
+    let t_min = ref_t
+        .iter()
+        .chain(act_t)
+        .filter(finite)
+        .fold(f64::INFINITY, |a, &b| a.min(b));
+    let t_max = ref_t
+        .iter()
+        .chain(act_t)
+        .filter(finite)
+        .fold(f64::NEG_INFINITY, |a, &b| a.max(b));
+    let y_min = band
+        .iter()
+        .map(|b| b.1)
+        .chain(act_y.iter().copied())
+        .filter(|v| v.is_finite())
+        .fold(f64::INFINITY, f64::min);
+    let y_max = band
+        .iter()
+        .map(|b| b.2)
+        .chain(act_y.iter().copied())
+        .filter(|v| v.is_finite())
+        .fold(f64::NEG_INFINITY, f64::max);
+    if !(t_min.is_finite() && t_max.is_finite() && y_min.is_finite() && y_max.is_finite()) {
+        return String::from("<p>(nothing to plot)</p>\n");
+    }
+    let t_span = (t_max - t_min).max(f64::MIN_POSITIVE);
+    let y_span = if y_max > y_min { y_max - y_min } else { 1.0 };
+    let x = |t: f64| PLOT_PAD + (t - t_min) / t_span * (PLOT_W - 2.0 * PLOT_PAD);
+    let y = |v: f64| PLOT_H - PLOT_PAD - (v - y_min) / y_span * (PLOT_H - 2.0 * PLOT_PAD);
+    let polyline = |ts: &[f64], ys: &[f64]| {
+        let mut pts = String::new();
+        let n = ts.len().min(ys.len());
+        let idx = (0..n).step_by(stride(n)).chain(n.checked_sub(1));
+        for i in idx {
+            if ys[i].is_finite() {
+                let _ = write!(pts, "{:.1},{:.1} ", x(ts[i]), y(ys[i]));
+            }
+        }
+        pts
+    };
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{PLOT_W}\" height=\"{PLOT_H}\" viewBox=\"0 0 {PLOT_W} {PLOT_H}\">"
+    );
+    let mut outline = String::new();
+    for (t, _, hi) in &band {
+        let _ = write!(outline, "{:.1},{:.1} ", x(*t), y(*hi));
+    }
+    for (t, lo, _) in band.iter().rev() {
+        let _ = write!(outline, "{:.1},{:.1} ", x(*t), y(*lo));
+    }
+    let _ = writeln!(
+        svg,
+        "<polygon points=\"{outline}\" fill=\"#9cc3e6\" fill-opacity=\"0.45\" stroke=\"none\"/>"
+    );
+    let _ = writeln!(
+        svg,
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"#246\" stroke-width=\"1\" stroke-dasharray=\"4 3\"/>",
+        polyline(ref_t, ref_y)
+    );
+    let _ = writeln!(
+        svg,
+        "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"/>",
+        polyline(act_t, act_y),
+        if verdict.passed { "#2a7" } else { "#c33" }
+    );
+    if !verdict.passed && verdict.worst_time.is_finite() {
+        let _ = writeln!(
+            svg,
+            "<line x1=\"{0:.1}\" x2=\"{0:.1}\" y1=\"{1}\" y2=\"{2}\" stroke=\"#c33\" stroke-dasharray=\"2 2\"/>",
+            x(verdict.worst_time),
+            PLOT_PAD,
+            PLOT_H - PLOT_PAD
+        );
+    }
+    let _ = writeln!(
+        svg,
+        "<text x=\"{PLOT_PAD}\" y=\"14\" font-size=\"11\">{y_max:.4e}</text>\
+         <text x=\"{PLOT_PAD}\" y=\"{:.0}\" font-size=\"11\">{y_min:.4e}</text>\
+         <text x=\"{:.0}\" y=\"{:.0}\" font-size=\"11\" text-anchor=\"end\">t = {t_min} … {t_max}</text>",
+        PLOT_H - 6.0,
+        PLOT_W - PLOT_PAD,
+        PLOT_H - 6.0
+    );
+    svg.push_str("</svg>\n");
+    svg
+}
+
+fn anchor(case: &str, signal: &str) -> String {
+    format!("{case}--{signal}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn xml_escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ramp(n: usize, shift: f64, gain: f64) -> (Vec<f64>, Vec<f64>) {
+        let t: Vec<f64> = (0..n).map(|i| i as f64 * 0.1).collect();
+        let y = t.iter().map(|t| gain * (t - shift).max(0.0)).collect();
+        (t, y)
+    }
+
+    #[test]
+    fn identical_run_passes_with_zero_deviation() {
+        let (t, y) = ramp(11, 0.0, 1.0);
+        let v = check_signal("x", &t, &y, &t, &y, Tube::default());
+        assert!(v.passed, "{v:?}");
+        assert_eq!(v.max_deviation, 0.0);
+        assert_eq!(v.checked, 22);
+    }
+
+    #[test]
+    fn relative_tube_flags_a_gain_error() {
+        let (t, r) = ramp(11, 0.0, 1.0);
+        let (_, a) = ramp(11, 0.0, 1.01);
+        let tight = check_signal("x", &t, &r, &t, &a, Tube::default());
+        assert!(!tight.passed);
+        assert!((tight.worst_time - 1.0).abs() < 1e-12, "{tight:?}");
+        let loose = Tube {
+            rel: 0.02,
+            ..Tube::default()
+        };
+        assert!(check_signal("x", &t, &r, &t, &a, loose).passed);
+    }
+
+    #[test]
+    fn time_tolerance_absorbs_a_shifted_step() {
+        let t: Vec<f64> = (0..21).map(|i| i as f64 * 0.1).collect();
+        let step = |at: f64| {
+            t.iter()
+                .map(|&s| if s >= at { 1.0 } else { 0.0 })
+                .collect::<Vec<_>>()
+        };
+        let (r, a) = (step(1.0), step(1.1));
+        assert!(!check_signal("x", &t, &r, &t, &a, Tube::default()).passed);
+        let shifted = Tube {
+            time: 0.15,
+            ..Tube::default()
+        };
+        let v = check_signal("x", &t, &r, &t, &a, shifted);
+        assert!(v.passed, "{v:?}");
+        assert_eq!(v.max_deviation, 1.0);
+    }
+
+    #[test]
+    fn short_run_and_missing_signals_fail() {
+        let (t, y) = ramp(11, 0.0, 1.0);
+        let v = check_signal("x", &t, &y, &t[..5], &y[..5], Tube::default());
+        assert!(!v.passed && v.failure.is_some());
+
+        let mut series = BTreeMap::new();
+        series.insert("x".to_string(), y.clone());
+        let reference = RunResult {
+            times: t.clone(),
+            series: series.clone(),
+            meta: Default::default(),
+        };
+        series.insert("y".to_string(), y);
+        let actual = RunResult {
+            times: t,
+            series,
+            meta: Default::default(),
+        };
+        let case = CaseSpec {
+            signals: vec!["x".into(), "y".into()],
+            ..CaseSpec::default()
+        };
+        let verdicts = check_case(&case, Tube::default(), &reference, &actual);
+        assert!(verdicts[0].passed);
+        assert_eq!(verdicts[1].failure.as_deref(), Some("not in the reference"));
+        // No explicit signals: only the shared ones are checked.
+        let all = check_case(&CaseSpec::default(), Tube::default(), &reference, &actual);
+        assert_eq!(all.len(), 1);
+    }
+
+    #[test]
+    fn junit_counts_failures_and_errors() {
+        let (t, y) = ramp(11, 0.0, 1.0);
+        let ok = check_signal("a<b", &t, &y, &t, &y, Tube::default());
+        let bad = SignalVerdict::failed("c", Tube::default(), "not in the run");
+        let report = SuiteReport {
+            name: "lib".into(),
+            cases: vec![
+                CaseReport {
+                    name: "one".into(),
+                    signals: vec![ok, bad],
+                    ..CaseReport::default()
+                },
+                CaseReport {
+                    name: "two".into(),
+                    error: Some("compile failed".into()),
+                    ..CaseReport::default()
+                },
+            ],
+        };
+        let xml = junit_xml(&report);
+        assert!(
+            xml.contains("tests=\"3\" failures=\"1\" errors=\"1\""),
+            "{xml}"
+        );
+        assert!(xml.contains("name=\"a&lt;b\"/>"));
+        assert!(xml.contains("<failure message=\"not in the run\">"));
+        assert!(xml.contains("<error message=\"compile failed\"/>"));
+        assert!(!report.passed());
+        assert!(html_report(&report).contains("FAIL"));
+    }
+}
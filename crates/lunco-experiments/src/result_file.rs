@@ -0,0 +1,576 @@
+//! Modelica-tool result files — the trajectory layout Dymola and
+//! OpenModelica write (`dsres.mat`, `<Model>_res.mat`), so a [`RunResult`]
+//! can be opened in those tools and their results overlaid on ours.
+//!
+//! A trajectory file is six matrices:
+//!
+//! ```text
+//! Aclass       char  ["Atrajectory", "1.1", "", "binTrans"]
+//! name         char  one variable name per column
+//! description  char  one description per column
+//! dataInfo     int32 per variable [matrix, column, interpolation, extrapolation]
+//! data_1       f64   time-invariant columns, sampled at [t_start, t_end]
+//! data_2       f64   time-varying columns, one sample per output step
+//! ```
+//!
+//! `dataInfo[0]` is `1` (in `data_1`), `2` (in `data_2`) or `0` for the
+//! abscissa (time, column 1 of both matrices); a negative column index is a
+//! negated alias of that column. `binTrans` stores every matrix transposed
+//! so each variable's samples are contiguous; `binNormal` (older Dymola) does
+//! not. Both are read; `binTrans` is written.
+//!
+//! This module handles MAT v4, the container those tools default to, and does
+//! no I/O. MAT v7.3 — the same six matrices in an HDF5 file — is
+//! `result_hdf5.rs`, behind the `hdf5` feature.
+
+use std::collections::BTreeMap;
+
+use crate::{RunMeta, RunResult};
+
+/// What a result file turned out to be, from its first bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResultFileKind {
+    /// MAT v4 (no header — the first matrix starts at byte 0).
+    Mat4,
+    /// MAT v7.3: an HDF5 file behind a 512-byte MATLAB text header.
+    Mat73,
+    /// A plain HDF5 file.
+    Hdf5,
+}
+
+const HDF5_SIGNATURE: &[u8; 8] = b"\x89HDF\r\n\x1a\n";
+
+/// Identify a result file. MAT v5 (MATLAB's binary default before v7.3) is
+/// refused by name rather than misread as v4.
+pub fn sniff(bytes: &[u8]) -> Result<ResultFileKind, ResultFileError> {
+    if bytes.starts_with(HDF5_SIGNATURE) {
+        return Ok(ResultFileKind::Hdf5);
+    }
+    if bytes.starts_with(b"MATLAB 7.3 MAT-file") {
+        return Ok(ResultFileKind::Mat73);
+    }
+    if bytes.starts_with(b"MATLAB 5.0 MAT-file") {
+        return Err(ResultFileError::Unsupported(
+            "MAT v5 — save the result as MAT v4 or v7.3".into(),
+        ));
+    }
+    Ok(ResultFileKind::Mat4)
+}
+
+/// Why a result file could not be read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResultFileError {
+    /// The file ends before a matrix header says it should.
+    Truncated,
+    /// A valid container holding something this reader doesn't handle
+    /// (MAT v5, complex or sparse matrices, a non-IEEE byte order).
+    Unsupported(String),
+    /// Readable, but not a Modelica trajectory (missing `Aclass`, a
+    /// `dataInfo` pointing at a column that isn't there, …).
+    NotTrajectory(String),
+    /// The HDF5 library refused the file (MAT v7.3 / HDF5 only).
+    Hdf5(String),
+}
+
+impl std::fmt::Display for ResultFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "result file is truncated"),
+            Self::Unsupported(what) => write!(f, "unsupported result file: {what}"),
+            Self::NotTrajectory(why) => write!(f, "not a Modelica trajectory: {why}"),
+            Self::Hdf5(e) => write!(f, "HDF5: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ResultFileError {}
+
+/// A [`RunResult`] laid out as a trajectory file. Shared by the MAT v4 and
+/// HDF5 writers so both files carry the same columns.
+pub(crate) struct Layout {
+    pub names: Vec<String>,
+    pub descriptions: Vec<String>,
+    pub data_info: Vec<[i32; 4]>,
+    /// `data_1` columns, each sampled at `[t_start, t_end]`; column 0 is time.
+    pub data_1: Vec<[f64; 2]>,
+    /// `data_2` columns, each `steps` long; column 0 is time.
+    pub data_2: Vec<Vec<f64>>,
+    pub steps: usize,
+}
+
+impl Layout {
+    /// Series that never change (parameters, mostly) go to `data_1`, the way
+    /// the tools file them; everything else to `data_2`. A series shorter
+    /// than `times` is padded with `NaN`, which also keeps it in `data_2`.
+    pub fn of(result: &RunResult) -> Self {
+        let steps = result.times.len();
+        let t0 = result.times.first().copied().unwrap_or(0.0);
+        let t1 = result.times.last().copied().unwrap_or(t0);
+        let mut layout = Layout {
+            names: vec!["time".into()],
+            descriptions: vec!["Simulation time [s]".into()],
+            data_info: vec![[0, 1, 0, -1]],
+            data_1: vec![[t0, t1]],
+            data_2: vec![result.times.clone()],
+            steps,
+        };
+        for (name, column) in &result.series {
+            if name == "time" {
+                continue;
+            }
+            let constant = column.len() == steps
+                && column.first().is_some_and(|v| {
+                    v.is_finite() && column.iter().all(|x| x.to_bits() == v.to_bits())
+                });
+            layout.names.push(name.clone());
+            layout.descriptions.push(String::new());
+            if constant {
+                layout.data_1.push([column[0], column[0]]);
+                layout.data_info.push([1, layout.data_1.len() as i32, 0, 0]);
+            } else {
+                let mut padded = column.clone();
+                padded.resize(steps, f64::NAN);
+                layout.data_2.push(padded);
+                layout
+                    .data_info
+                    .push([2, layout.data_2.len() as i32, 0, -1]);
+            }
+        }
+        layout
+    }
+}
+
+/// One matrix as read from a file, column-major (MATLAB order) and widened
+/// to `f64` whatever its stored type — text matrices hold character codes.
+pub(crate) struct Matrix {
+    pub rows: usize,
+    pub cols: usize,
+    pub data: Vec<f64>,
+}
+
+impl Matrix {
+    fn at(&self, row: usize, col: usize) -> f64 {
+        self.data[col * self.rows + row]
+    }
+
+    /// The strings of a text matrix: one per column when `by_column`, else
+    /// one per row. Trailing blanks and NULs are padding.
+    fn strings(&self, by_column: bool) -> Vec<String> {
+        let (count, len) = if by_column {
+            (self.cols, self.rows)
+        } else {
+            (self.rows, self.cols)
+        };
+        (0..count)
+            .map(|i| {
+                let s: String = (0..len)
+                    .map(|j| {
+                        let code = if by_column {
+                            self.at(j, i)
+                        } else {
+                            self.at(i, j)
+                        };
+                        char::from_u32(code as u32).unwrap_or('?')
+                    })
+                    .collect();
+                s.trim_end_matches([' ', '\0']).to_string()
+            })
+            .collect()
+    }
+}
+
+/// A `data_1` / `data_2` matrix seen as variables × points, whichever way it
+/// is stored.
+struct DataMatrix<'a> {
+    m: &'a Matrix,
+    transposed: bool,
+}
+
+impl DataMatrix<'_> {
+    fn points(&self) -> usize {
+        if self.transposed {
+            self.m.cols
+        } else {
+            self.m.rows
+        }
+    }
+
+    fn vars(&self) -> usize {
+        if self.transposed {
+            self.m.rows
+        } else {
+            self.m.cols
+        }
+    }
+
+    fn value(&self, var: usize, point: usize) -> f64 {
+        if self.transposed {
+            self.m.at(var, point)
+        } else {
+            self.m.at(point, var)
+        }
+    }
+}
+
+/// Rebuild a [`RunResult`] from a file's matrices, keyed by name.
+/// `data_1` variables become constant series over the `data_2` time axis.
+pub(crate) fn trajectory_from_matrices(
+    mut matrices: BTreeMap<String, Matrix>,
+    format: &str,
+) -> Result<RunResult, ResultFileError> {
+    let missing = |name: &str| ResultFileError::NotTrajectory(format!("no `{name}` matrix"));
+    let aclass = matrices.remove("Aclass").ok_or_else(|| missing("Aclass"))?;
+    let aclass = aclass.strings(false);
+    if aclass.first().map(String::as_str) != Some("Atrajectory") {
+        return Err(ResultFileError::NotTrajectory(format!(
+            "Aclass is {:?}, expected Atrajectory",
+            aclass.first().cloned().unwrap_or_default()
+        )));
+    }
+    let transposed = aclass.get(3).map(String::as_str) == Some("binTrans");
+    let names = matrices
+        .remove("name")
+        .ok_or_else(|| missing("name"))?
+        .strings(transposed);
+    let info_matrix = matrices
+        .remove("dataInfo")
+        .ok_or_else(|| missing("dataInfo"))?;
+    let data_1 = matrices.remove("data_1");
+    let data_2 = matrices.remove("data_2");
+    let data_1 = data_1.as_ref().map(|m| DataMatrix { m, transposed });
+    let data_2 = data_2.as_ref().map(|m| DataMatrix { m, transposed });
+    let info = |k: usize, field: usize| -> f64 {
+        if transposed {
+            info_matrix.at(field, k)
+        } else {
+            info_matrix.at(k, field)
+        }
+    };
+    let (info_count, info_fields) = if transposed {
+        (info_matrix.cols, info_matrix.rows)
+    } else {
+        (info_matrix.rows, info_matrix.cols)
+    };
+    if info_count < names.len() || info_fields < 2 {
+        return Err(ResultFileError::NotTrajectory(
+            "dataInfo is smaller than the name list".into(),
+        ));
+    }
+
+    // The abscissa is column 1 of data_2 — or of data_1 when nothing varies.
+    let times: Vec<f64> = match (&data_2, &data_1) {
+        (Some(d2), _) if d2.vars() > 0 => (0..d2.points()).map(|p| d2.value(0, p)).collect(),
+        (_, Some(d1)) if d1.vars() > 0 => (0..d1.points()).map(|p| d1.value(0, p)).collect(),
+        _ => return Err(missing("data_2")),
+    };
+
+    let mut series = BTreeMap::new();
+    for (k, name) in names.into_iter().enumerate() {
+        let matrix = info(k, 0) as i32;
+        let column = info(k, 1) as i32;
+        if matrix == 0 || name.is_empty() {
+            continue;
+        }
+        let sign = if column < 0 { -1.0 } else { 1.0 };
+        let col = column.unsigned_abs() as usize;
+        let source = match matrix {
+            1 => data_1.as_ref(),
+            2 => data_2.as_ref(),
+            other => {
+                return Err(ResultFileError::NotTrajectory(format!(
+                    "`{name}` is in data_{other}"
+                )))
+            }
+        };
+        let Some(source) = source.filter(|s| col >= 1 && col <= s.vars()) else {
+            return Err(ResultFileError::NotTrajectory(format!(
+                "`{name}` points at data_{matrix} column {col}, which doesn't exist"
+            )));
+        };
+        let values = if matrix == 1 {
+            vec![sign * source.value(col - 1, 0); times.len()]
+        } else {
+            (0..source.points())
+                .map(|p| sign * source.value(col - 1, p))
+                .collect()
+        };
+        series.insert(name, values);
+    }
+    let sample_count = times.len();
+    Ok(RunResult {
+        times,
+        series,
+        meta: RunMeta {
+            wall_time_ms: 0,
+            sample_count,
+            notes: Some(format!("imported from {format}")),
+        },
+    })
+}
+
+// ---------- MAT v4 ----------
+
+/// `MOPT` type codes: little-endian IEEE (`M=0`), numeric / text (`T`).
+pub(crate) const MAT4_F64: i32 = 0;
+const MAT4_I32: i32 = 20;
+pub(crate) const MAT4_TEXT: i32 = 51;
+
+/// Encode `result` as a MAT v4 `binTrans` trajectory — what OpenModelica
+/// writes and Dymola, OMEdit and the usual Python/Matlab readers load.
+/// `data_1`/`data_2` are `f64`.
+pub fn encode_mat4(result: &RunResult) -> Vec<u8> {
+    let layout = Layout::of(result);
+    let mut out = Vec::with_capacity(256 + 8 * layout.steps * layout.data_2.len());
+
+    let aclass = ["Atrajectory", "1.1", "", "binTrans"];
+    put_header(&mut out, "Aclass", MAT4_TEXT, 4, 11);
+    for c in 0..11 {
+        for s in aclass {
+            out.push(*s.as_bytes().get(c).unwrap_or(&b' '));
+        }
+    }
+    put_text(&mut out, "name", &layout.names);
+    put_text(&mut out, "description", &layout.descriptions);
+
+    put_header(&mut out, "dataInfo", MAT4_I32, 4, layout.data_info.len());
+    for info in &layout.data_info {
+        for v in info {
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+
+    put_header(&mut out, "data_1", MAT4_F64, layout.data_1.len(), 2);
+    for point in 0..2 {
+        for column in &layout.data_1 {
+            out.extend_from_slice(&column[point].to_le_bytes());
+        }
+    }
+
+    put_header(
+        &mut out,
+        "data_2",
+        MAT4_F64,
+        layout.data_2.len(),
+        layout.steps,
+    );
+    for step in 0..layout.steps {
+        for column in &layout.data_2 {
+            out.extend_from_slice(&column[step].to_le_bytes());
+        }
+    }
+    out
+}
+
+pub(crate) fn put_header(out: &mut Vec<u8>, name: &str, kind: i32, rows: usize, cols: usize) {
+    for v in [kind, rows as i32, cols as i32, 0, name.len() as i32 + 1] {
+        out.extend_from_slice(&v.to_le_bytes());
+    }
+    out.extend_from_slice(name.as_bytes());
+    out.push(0);
+}
+
+/// A `binTrans` text matrix: one blank-padded string per column.
+fn put_text(out: &mut Vec<u8>, name: &str, strings: &[String]) {
+    let width = strings.iter().map(String::len).max().unwrap_or(0).max(1);
+    put_header(out, name, MAT4_TEXT, width, strings.len());
+    for s in strings {
+        out.extend_from_slice(s.as_bytes());
+        out.resize(out.len() + width - s.len(), b' ');
+    }
+}
+
+/// Decode a MAT v4 trajectory (`binTrans` or `binNormal`, either byte
+/// order, any numeric precision).
+pub fn decode_mat4(bytes: &[u8]) -> Result<RunResult, ResultFileError> {
+    let mut matrices = BTreeMap::new();
+    let mut at = 0;
+    while at < bytes.len() {
+        let (name, matrix, next) = read_mat4_matrix(bytes, at)?;
+        matrices.insert(name, matrix);
+        at = next;
+    }
+    trajectory_from_matrices(matrices, "MAT v4")
+}
+
+fn read_mat4_matrix(bytes: &[u8], at: usize) -> Result<(String, Matrix, usize), ResultFileError> {
+    let header = bytes.get(at..at + 20).ok_or(ResultFileError::Truncated)?;
+    let word = |i: usize, big: bool| {
+        let b = [header[i], header[i + 1], header[i + 2], header[i + 3]];
+        if big {
+            i32::from_be_bytes(b)
+        } else {
+            i32::from_le_bytes(b)
+        }
+    };
+    // The type word is small in the file's own byte order; that is the only
+    // byte-order marker v4 has.
+    let big = !(0..5000).contains(&word(0, false));
+    let kind = word(0, big);
+    let (rows, cols, imag, name_len) = (word(4, big), word(8, big), word(12, big), word(16, big));
+    if !(0..5000).contains(&kind) || rows < 0 || cols < 0 || name_len < 1 {
+        return Err(ResultFileError::Unsupported(
+            "not a MAT v4 file (bad matrix header)".into(),
+        ));
+    }
+    if kind / 1000 > 1 {
+        return Err(ResultFileError::Unsupported(
+            "VAX / Cray number format".into(),
+        ));
+    }
+    if kind % 10 == 2 {
+        return Err(ResultFileError::Unsupported("sparse matrix".into()));
+    }
+    if imag != 0 {
+        return Err(ResultFileError::Unsupported("complex matrix".into()));
+    }
+    let precision = (kind / 10) % 10;
+    let width = match precision {
+        0 => 8,
+        1 | 2 => 4,
+        3 | 4 => 2,
+        5 => 1,
+        _ => {
+            return Err(ResultFileError::Unsupported(format!(
+                "matrix precision code {precision}"
+            )))
+        }
+    };
+    let name_start = at + 20;
+    let name_end = name_start + name_len as usize;
+    let name = bytes
+        .get(name_start..name_end)
+        .ok_or(ResultFileError::Truncated)?;
+    let name = String::from_utf8_lossy(name)
+        .trim_end_matches('\0')
+        .to_string();
+    let (rows, cols) = (rows as usize, cols as usize);
+    let len = rows
+        .checked_mul(cols)
+        .and_then(|n| n.checked_mul(width))
+        .ok_or(ResultFileError::Truncated)?;
+    let raw = bytes
+        .get(
+            name_end
+                ..name_end
+                    .checked_add(len)
+                    .ok_or(ResultFileError::Truncated)?,
+        )
+        .ok_or(ResultFileError::Truncated)?;
+    let data = raw
+        .chunks_exact(width)
+        .map(|b| {
+            let mut w = [0u8; 8];
+            w[..width].copy_from_slice(b);
+            if big {
+                w[..width].reverse();
+            }
+            match precision {
+                0 => f64::from_le_bytes(w),
+                1 => f32::from_le_bytes([w[0], w[1], w[2], w[3]]) as f64,
+                2 => i32::from_le_bytes([w[0], w[1], w[2], w[3]]) as f64,
+                3 => i16::from_le_bytes([w[0], w[1]]) as f64,
+                4 => u16::from_le_bytes([w[0], w[1]]) as f64,
+                _ => w[0] as f64,
+            }
+        })
+        .collect();
+    Ok((name, Matrix { rows, cols, data }, name_end + len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> RunResult {
+        let mut series = BTreeMap::new();
+        series.insert("x".to_string(), vec![1.0, 0.5, 0.25]);
+        series.insert("der(x)".to_string(), vec![-0.5, -0.25, f64::NAN]);
+        series.insert("k".to_string(), vec![2.0, 2.0, 2.0]);
+        RunResult {
+            times: vec![0.0, 1.0, 2.0],
+            series,
+            meta: RunMeta::default(),
+        }
+    }
+
+    #[test]
+    fn mat4_round_trips_series_and_parameters() {
+        let back = decode_mat4(&encode_mat4(&sample())).unwrap();
+        assert_eq!(back.times, vec![0.0, 1.0, 2.0]);
+        assert_eq!(back.series["x"], vec![1.0, 0.5, 0.25]);
+        assert_eq!(back.series["k"], vec![2.0, 2.0, 2.0]);
+        assert!(back.series["der(x)"][2].is_nan());
+        assert!(!back.series.contains_key("time"));
+        assert_eq!(back.meta.sample_count, 3);
+    }
+
+    #[test]
+    fn parameters_are_filed_in_data_1() {
+        let layout = Layout::of(&sample());
+        let k = layout.names.iter().position(|n| n == "k").unwrap();
+        assert_eq!(layout.data_info[k], [1, 2, 0, 0]);
+        assert_eq!(layout.data_2.len(), 3, "time, der(x), x");
+    }
+
+    /// Older Dymola `binNormal`, single precision, a negated alias, and the
+    /// `Time` abscissa — built by hand, the way the tool lays it out.
+    #[test]
+    fn reads_bin_normal_with_negated_alias() {
+        fn matrix(out: &mut Vec<u8>, name: &str, kind: i32, rows: usize, cols: usize, data: &[u8]) {
+            put_header(out, name, kind, rows, cols);
+            out.extend_from_slice(data);
+        }
+        let text = |rows: &[&str], width: usize| -> Vec<u8> {
+            let mut d = Vec::new();
+            for c in 0..width {
+                for r in rows {
+                    d.push(*r.as_bytes().get(c).unwrap_or(&b' '));
+                }
+            }
+            d
+        };
+        let ints = |v: &[i32]| v.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+        let floats = |v: &[f32]| v.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<u8>>();
+        let mut f = Vec::new();
+        let aclass = ["Atrajectory", "1.1", "", "binNormal"];
+        matrix(&mut f, "Aclass", 51, 4, 11, &text(&aclass, 11));
+        matrix(&mut f, "name", 51, 3, 4, &text(&["Time", "v", "w"], 4));
+        // dataInfo rows = variables: column-major, so field by field.
+        matrix(
+            &mut f,
+            "dataInfo",
+            20,
+            3,
+            4,
+            &ints(&[0, 2, 2, 1, 2, -2, 0, 0, 0, -1, -1, -1]),
+        );
+        matrix(&mut f, "data_1", 10, 2, 1, &floats(&[0.0, 1.0]));
+        // data_2 rows = samples: column 1 time, column 2 v.
+        matrix(&mut f, "data_2", 10, 2, 2, &floats(&[0.0, 1.0, 3.0, 4.0]));
+
+        let back = decode_mat4(&f).unwrap();
+        assert_eq!(back.times, vec![0.0, 1.0]);
+        assert_eq!(back.series["v"], vec![3.0, 4.0]);
+        assert_eq!(back.series["w"], vec![-3.0, -4.0]);
+    }
+
+    #[test]
+    fn refuses_non_trajectories() {
+        assert_eq!(
+            decode_mat4(&[1, 2, 3]).unwrap_err(),
+            ResultFileError::Truncated
+        );
+        let mut f = Vec::new();
+        put_header(&mut f, "x", 0, 1, 1);
+        f.extend_from_slice(&1.0f64.to_le_bytes());
+        assert!(matches!(
+            decode_mat4(&f),
+            Err(ResultFileError::NotTrajectory(_))
+        ));
+        assert!(sniff(b"MATLAB 5.0 MAT-file, Platform").is_err());
+        assert_eq!(
+            sniff(b"MATLAB 7.3 MAT-file").unwrap(),
+            ResultFileKind::Mat73
+        );
+    }
+}
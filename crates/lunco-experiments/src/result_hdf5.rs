@@ -0,0 +1,194 @@
+//! MAT v7.3 / HDF5 trajectory files — the [`result_file`](crate::result_file)
+//! matrices in an HDF5 container. Behind the `hdf5` feature: it links the
+//! HDF5 C library, so it is native-only and off by default.
+//!
+//! MATLAB stores an `m × n` array as an HDF5 dataset of shape `[n, m]` — its
+//! column-major data read as row-major — tagged with a `MATLAB_class`
+//! attribute; text is `uint16` with `MATLAB_int_decode = 2`. A MAT v7.3 file
+//! is that HDF5 file behind a 512-byte user block opening with MATLAB's text
+//! header. A plain HDF5 export is the same datasets with no user block.
+
+use std::collections::BTreeMap;
+use std::io::Write;
+use std::path::Path;
+
+use hdf5::types::FixedAscii;
+
+use crate::result_file::{trajectory_from_matrices, Layout, Matrix, ResultFileError};
+use crate::RunResult;
+
+/// User-block size MATLAB uses for v7.3 files; the header fills its first
+/// 128 bytes.
+const USERBLOCK: u64 = 512;
+
+fn h5(e: hdf5::Error) -> ResultFileError {
+    ResultFileError::Hdf5(e.to_string())
+}
+
+/// Write `result` to `path` as a trajectory — MAT v7.3 when `mat73`, else a
+/// plain HDF5 file.
+pub fn write_hdf5(path: &Path, result: &RunResult, mat73: bool) -> Result<(), ResultFileError> {
+    let layout = Layout::of(result);
+    {
+        let mut builder = hdf5::File::with_options();
+        if mat73 {
+            builder.with_fcpl(|p| p.userblock(USERBLOCK));
+        }
+        let file = builder.create(path).map_err(h5)?;
+
+        let aclass = ["Atrajectory", "1.1", "", "binTrans"];
+        let mut chars = Vec::with_capacity(44);
+        for c in 0..11 {
+            for s in aclass {
+                chars.push(u16::from(*s.as_bytes().get(c).unwrap_or(&b' ')));
+            }
+        }
+        put_text(&file, "Aclass", (11, 4), &chars)?;
+        put_strings(&file, "name", &layout.names)?;
+        put_strings(&file, "description", &layout.descriptions)?;
+
+        let info: Vec<i32> = layout.data_info.iter().flatten().copied().collect();
+        put(
+            &file,
+            "dataInfo",
+            (layout.data_info.len(), 4),
+            &info,
+            "int32",
+        )?;
+
+        let mut data_1 = Vec::with_capacity(2 * layout.data_1.len());
+        for point in 0..2 {
+            data_1.extend(layout.data_1.iter().map(|column| column[point]));
+        }
+        put(&file, "data_1", (2, layout.data_1.len()), &data_1, "double")?;
+
+        let mut data_2 = Vec::with_capacity(layout.steps * layout.data_2.len());
+        for step in 0..layout.steps {
+            data_2.extend(layout.data_2.iter().map(|column| column[step]));
+        }
+        put(
+            &file,
+            "data_2",
+            (layout.steps, layout.data_2.len()),
+            &data_2,
+            "double",
+        )?;
+    }
+    if mat73 {
+        // MATLAB recognises v7.3 by this header, not by anything in the HDF5
+        // structure; the library has to be done with the file first.
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .open(path)
+            .map_err(|e| ResultFileError::Hdf5(e.to_string()))?;
+        file.write_all(&mat73_header())
+            .map_err(|e| ResultFileError::Hdf5(e.to_string()))?;
+    }
+    Ok(())
+}
+
+fn mat73_header() -> [u8; 128] {
+    let mut header = [b' '; 128];
+    let text = b"MATLAB 7.3 MAT-file, Platform: LunCoSim, Created by: lunco-experiments HDF5 schema 1.00 .";
+    header[..text.len()].copy_from_slice(text);
+    // Subsystem offset (unused), version 0x0200, little-endian marker.
+    header[116..124].fill(0);
+    header[124..126].copy_from_slice(&0x0200u16.to_le_bytes());
+    header[126..128].copy_from_slice(b"IM");
+    header
+}
+
+fn put<T: hdf5::H5Type>(
+    file: &hdf5::File,
+    name: &str,
+    shape: (usize, usize),
+    data: &[T],
+    class: &str,
+) -> Result<hdf5::Dataset, ResultFileError> {
+    let ds = file
+        .new_dataset::<T>()
+        .shape(shape)
+        .create(name)
+        .map_err(h5)?;
+    ds.write_raw(data).map_err(h5)?;
+    let class =
+        FixedAscii::<8>::from_ascii(class).map_err(|e| ResultFileError::Hdf5(e.to_string()))?;
+    ds.new_attr::<FixedAscii<8>>()
+        .shape(())
+        .create("MATLAB_class")
+        .map_err(h5)?
+        .write_scalar(&class)
+        .map_err(h5)?;
+    Ok(ds)
+}
+
+fn put_text(
+    file: &hdf5::File,
+    name: &str,
+    shape: (usize, usize),
+    chars: &[u16],
+) -> Result<(), ResultFileError> {
+    let ds = put(file, name, shape, chars, "char")?;
+    ds.new_attr::<i32>()
+        .shape(())
+        .create("MATLAB_int_decode")
+        .map_err(h5)?
+        .write_scalar(&2)
+        .map_err(h5)?;
+    Ok(())
+}
+
+/// A `binTrans` text matrix: one blank-padded string per MATLAB column,
+/// i.e. per dataset row.
+fn put_strings(file: &hdf5::File, name: &str, strings: &[String]) -> Result<(), ResultFileError> {
+    let width = strings.iter().map(String::len).max().unwrap_or(0).max(1);
+    let mut chars = Vec::with_capacity(width * strings.len());
+    for s in strings {
+        chars.extend(s.bytes().map(u16::from));
+        chars.resize(chars.len() + width - s.len(), u16::from(b' '));
+    }
+    put_text(file, name, (strings.len(), width), &chars)
+}
+
+/// Read a MAT v7.3 or plain HDF5 trajectory. Datasets are widened to `f64`
+/// by the library, so any stored precision reads.
+pub fn read_hdf5(path: &Path) -> Result<RunResult, ResultFileError> {
+    let file = hdf5::File::open(path).map_err(h5)?;
+    let mut matrices = BTreeMap::new();
+    for name in ["Aclass", "name", "dataInfo", "data_1", "data_2"] {
+        let Ok(ds) = file.dataset(name) else {
+            continue;
+        };
+        // MATLAB writes an empty array as its dimensions plus this marker.
+        if ds.attr("MATLAB_empty").is_ok() {
+            matrices.insert(
+                name.to_string(),
+                Matrix {
+                    rows: 0,
+                    cols: 0,
+                    data: Vec::new(),
+                },
+            );
+            continue;
+        }
+        let data = ds.read_raw::<f64>().map_err(h5)?;
+        let (rows, cols) = match ds.shape().as_slice() {
+            [n, m] => (*m, *n),
+            _ => (1, data.len()),
+        };
+        if rows * cols != data.len() {
+            return Err(ResultFileError::Unsupported(format!(
+                "`{name}` has {} dimensions",
+                ds.ndim()
+            )));
+        }
+        matrices.insert(name.to_string(), Matrix { rows, cols, data });
+    }
+    let mut head = [0u8; 10];
+    let mat73 = std::fs::File::open(path)
+        .and_then(|mut f| std::io::Read::read_exact(&mut f, &mut head))
+        .is_ok()
+        && &head == b"MATLAB 7.3";
+    let format = if mat73 { "MAT v7.3" } else { "HDF5" };
+    trajectory_from_matrices(matrices, format)
+}
@@ -0,0 +1,555 @@
+//! Global sensitivity analysis over a sweep — which parameters move an
+//! output, and by how much.
+//!
+//! The sweep is generated by one of the two sensitivity designs in
+//! [`sweep`](crate::sweep); the analysis regenerates its layout from the
+//! [`SweepSpec`] and reads one number per run through a [`ScalarOutput`] (the
+//! final, largest or smallest value of a series, its integral, or the time it
+//! first crosses a threshold).
+//!
+//! ## Morris
+//!
+//! Each step of a Morris trajectory moves one factor by half its grid, so the
+//! change in the output over that step, divided by the move as a fraction of
+//! the factor's range, is one *elementary effect* of that factor. Over the
+//! trajectories a factor gets `μ` (mean effect), `μ*` (mean absolute effect —
+//! the ranking measure, immune to effects of opposite sign cancelling) and
+//! `σ` (spread — large when the factor acts non-linearly or through
+//! interactions). Cheap: `r·(k + 1)` runs for `k` factors, so it is the screen
+//! to run over forty parameters before spending a Sobol budget on the few
+//! that matter.
+//!
+//! ## Sobol
+//!
+//! A Saltelli design evaluates the base samples `A` and `B` and, per factor
+//! `i`, `A` with column `i` from `B`. With `V` the output variance over `A`
+//! and `B`, the first-order index is Saltelli's (2010) estimator
+//! `Sᵢ = mean(f_B·(f_ABᵢ − f_A)) / V` — the share of variance the factor
+//! explains alone — and the total index Jansen's
+//! `Sₜᵢ = mean((f_A − f_ABᵢ)²) / 2V` — its share including every
+//! interaction. 95% intervals come from a seeded bootstrap over the sample
+//! rows. `N·(k + 2)` runs for `N` base samples.
+//!
+//! A failed or missing run drops only what it takes part in: its elementary
+//! effects, or its row of the Saltelli design.
+
+use std::collections::BTreeMap;
+
+use rand::RngExt;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::sweep::{Design, SweepSpec};
+use crate::{ParamPath, RunResult};
+
+/// Bootstrap resamples behind the Sobol intervals.
+const BOOTSTRAP: usize = 200;
+
+/// How a series is reduced to one number.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum OutputKind {
+    /// The last sample.
+    #[default]
+    Final,
+    Max,
+    Min,
+    /// Trapezoidal integral over the run.
+    Integral,
+    /// The first time the series crosses `threshold` (linearly
+    /// interpolated; the start time when it starts there). A run that never
+    /// crosses counts as its end time — the crossing is at least that late.
+    TimeToThreshold {
+        threshold: f64,
+    },
+}
+
+/// One scalar output of a run.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ScalarOutput {
+    pub series: String,
+    #[serde(flatten)]
+    pub kind: OutputKind,
+}
+
+impl ScalarOutput {
+    pub fn new(series: impl Into<String>, kind: OutputKind) -> Self {
+        Self {
+            series: series.into(),
+            kind,
+        }
+    }
+
+    /// Parse `final`, `max`, `min`, `integral` or `time_to_threshold` (with
+    /// `threshold`) for `series`.
+    pub fn from_token(series: &str, token: &str, threshold: Option<f64>) -> Option<Self> {
+        let kind = match token
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['-', ' '], "_")
+            .as_str()
+        {
+            "" | "final" | "last" => OutputKind::Final,
+            "max" | "maximum" => OutputKind::Max,
+            "min" | "minimum" => OutputKind::Min,
+            "integral" | "int" => OutputKind::Integral,
+            "time_to_threshold" | "time_to" | "crossing" => OutputKind::TimeToThreshold {
+                threshold: threshold?,
+            },
+            _ => return None,
+        };
+        Some(Self::new(series, kind))
+    }
+
+    /// Display name: `max(battery.T)`, `t(soc = 0.2)`.
+    pub fn label(&self) -> String {
+        match self.kind {
+            OutputKind::Final => format!("final({})", self.series),
+            OutputKind::Max => format!("max({})", self.series),
+            OutputKind::Min => format!("min({})", self.series),
+            OutputKind::Integral => format!("∫{}", self.series),
+            OutputKind::TimeToThreshold { threshold } => {
+                format!("t({} = {threshold})", self.series)
+            }
+        }
+    }
+
+    /// The output of `run`; `None` when the run lacks the series or a usable
+    /// sample.
+    pub fn evaluate(&self, run: &RunResult) -> Option<f64> {
+        let ys = run.series.get(&self.series)?;
+        let n = ys.len().min(run.times.len());
+        let (ts, ys) = (&run.times[..n], &ys[..n]);
+        let value = match self.kind {
+            OutputKind::Final => *ys.last()?,
+            OutputKind::Max => ys
+                .iter()
+                .copied()
+                .filter(|v| v.is_finite())
+                .reduce(f64::max)?,
+            OutputKind::Min => ys
+                .iter()
+                .copied()
+                .filter(|v| v.is_finite())
+                .reduce(f64::min)?,
+            OutputKind::Integral => {
+                if n < 2 {
+                    return None;
+                }
+                ts.windows(2)
+                    .zip(ys.windows(2))
+                    .map(|(t, y)| 0.5 * (t[1] - t[0]) * (y[0] + y[1]))
+                    .sum()
+            }
+            OutputKind::TimeToThreshold { threshold } => {
+                let (&t0, &y0) = (ts.first()?, ys.first()?);
+                if y0 == threshold {
+                    return Some(t0);
+                }
+                let below = y0 < threshold;
+                let crossing = ts.windows(2).zip(ys.windows(2)).find_map(|(t, y)| {
+                    ((y[1] >= threshold) == below).then(|| {
+                        let f = (threshold - y[0]) / (y[1] - y[0]);
+                        t[0] + f.clamp(0.0, 1.0) * (t[1] - t[0])
+                    })
+                });
+                crossing.unwrap_or(*ts.last()?)
+            }
+        };
+        value.is_finite().then_some(value)
+    }
+}
+
+/// Morris statistics of one factor, in output units per full range of the
+/// factor.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MorrisIndex {
+    pub path: ParamPath,
+    pub mu: f64,
+    pub mu_star: f64,
+    pub sigma: f64,
+    /// Elementary effects behind the statistics.
+    pub effects: usize,
+}
+
+/// Sobol indices of one factor, with 95% bootstrap intervals.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SobolIndex {
+    pub path: ParamPath,
+    pub first: f64,
+    pub first_ci: [f64; 2],
+    pub total: f64,
+    pub total_ci: [f64; 2],
+}
+
+/// The analysis of one output.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct OutputSensitivity {
+    pub output: ScalarOutput,
+    pub label: String,
+    /// Runs whose output entered the analysis.
+    pub runs: usize,
+    /// Output mean and variance over those runs.
+    pub mean: f64,
+    pub variance: f64,
+    /// Most influential first (by `μ*`). Empty for a Saltelli sweep.
+    pub morris: Vec<MorrisIndex>,
+    /// Most influential first (by total index). Empty for a Morris sweep.
+    pub sobol: Vec<SobolIndex>,
+}
+
+/// Sensitivity of every requested output to the sweep's factors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SensitivityReport {
+    /// `Morris` or `Saltelli`.
+    pub design: String,
+    pub factors: Vec<ParamPath>,
+    pub outputs: Vec<OutputSensitivity>,
+}
+
+/// Analyse a sensitivity sweep. `runs[i]` is member `i`'s result in design
+/// order (`None` when it failed or has not finished).
+pub fn analyze(
+    spec: &SweepSpec,
+    runs: &[Option<&RunResult>],
+    outputs: &[ScalarOutput],
+) -> Result<SensitivityReport, String> {
+    if !spec.design.is_sensitivity() {
+        return Err(format!(
+            "a {} sweep has no sensitivity layout; use a Morris or Saltelli design",
+            spec.design.label()
+        ));
+    }
+    let expected = spec.run_count().map_err(|e| e.to_string())?;
+    if runs.len() != expected {
+        return Err(format!(
+            "the design has {expected} runs but {} were given",
+            runs.len()
+        ));
+    }
+    if outputs.is_empty() {
+        return Err("no output to analyse".into());
+    }
+    let outputs = outputs
+        .iter()
+        .map(|output| {
+            let values: Vec<Option<f64>> = runs
+                .iter()
+                .map(|r| r.and_then(|r| output.evaluate(r)))
+                .collect();
+            let used: Vec<f64> = values.iter().flatten().copied().collect();
+            if used.is_empty() {
+                return Err(format!("no run yields {}", output.label()));
+            }
+            let (mean, variance) = mean_variance(&used);
+            let mut analysis = OutputSensitivity {
+                output: output.clone(),
+                label: output.label(),
+                runs: used.len(),
+                mean,
+                variance,
+                morris: Vec::new(),
+                sobol: Vec::new(),
+            };
+            match spec.design {
+                Design::Morris { .. } => analysis.morris = morris(spec, &values),
+                Design::Saltelli { .. } => analysis.sobol = sobol(spec, &values)?,
+                _ => unreachable!("checked above"),
+            }
+            Ok(analysis)
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    Ok(SensitivityReport {
+        design: spec.design.label().to_string(),
+        factors: spec.factors.iter().map(|f| f.path.clone()).collect(),
+        outputs,
+    })
+}
+
+fn mean_variance(v: &[f64]) -> (f64, f64) {
+    let n = v.len() as f64;
+    let mean = v.iter().sum::<f64>() / n;
+    let variance = if v.len() > 1 {
+        v.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0)
+    } else {
+        0.0
+    };
+    (mean, variance)
+}
+
+fn morris(spec: &SweepSpec, values: &[Option<f64>]) -> Vec<MorrisIndex> {
+    let plan = spec.morris_plan();
+    let k = spec.factors.len();
+    let Design::Morris { levels, .. } = spec.design else {
+        return Vec::new();
+    };
+    let counts: Vec<usize> = spec
+        .factors
+        .iter()
+        .map(|f| f.distribution.levels(levels).len())
+        .collect();
+    let mut effects: Vec<Vec<f64>> = vec![Vec::new(); k];
+    for (points, ys) in plan.chunks(k + 1).zip(values.chunks(k + 1)) {
+        for step in 0..k {
+            let (from, to) = (&points[step], &points[step + 1]);
+            let Some(d) = (0..k).find(|&d| from[d] != to[d]) else {
+                continue;
+            };
+            let (Some(y0), Some(y1)) = (ys[step], ys[step + 1]) else {
+                continue;
+            };
+            let delta = (to[d] as f64 - from[d] as f64) / (counts[d] - 1) as f64;
+            effects[d].push((y1 - y0) / delta);
+        }
+    }
+    let mut indices: Vec<MorrisIndex> = spec
+        .factors
+        .iter()
+        .zip(effects)
+        .map(|(f, ee)| {
+            let n = ee.len().max(1) as f64;
+            let (mu, sigma) = if ee.is_empty() {
+                (0.0, 0.0)
+            } else {
+                let (mu, var) = mean_variance(&ee);
+                (mu, var.sqrt())
+            };
+            MorrisIndex {
+                path: f.path.clone(),
+                mu,
+                mu_star: ee.iter().map(|e| e.abs()).sum::<f64>() / n,
+                sigma,
+                effects: ee.len(),
+            }
+        })
+        .collect();
+    indices.sort_by(|a, b| b.mu_star.total_cmp(&a.mu_star));
+    indices
+}
+
+fn sobol(spec: &SweepSpec, values: &[Option<f64>]) -> Result<Vec<SobolIndex>, String> {
+    let k = spec.factors.len();
+    // Complete rows only: f_A, f_B, f_AB₁ … f_ABₖ.
+    let rows: Vec<Vec<f64>> = values
+        .chunks(k + 2)
+        .filter_map(|row| row.iter().copied().collect::<Option<Vec<f64>>>())
+        .collect();
+    if rows.len() < 2 {
+        return Err(format!(
+            "{} complete Saltelli rows; at least 2 are needed",
+            rows.len()
+        ));
+    }
+    let estimate = |sample: &[usize]| -> Vec<(f64, f64)> {
+        let n = sample.len() as f64;
+        let base: Vec<f64> = sample
+            .iter()
+            .flat_map(|&j| [rows[j][0], rows[j][1]])
+            .collect();
+        let (mean, v) = mean_variance(&base);
+        (0..k)
+            .map(|i| {
+                if v <= 0.0 {
+                    return (0.0, 0.0);
+                }
+                let (mut s1, mut st) = (0.0, 0.0);
+                for &j in sample {
+                    let (fa, fb, fab) = (rows[j][0], rows[j][1], rows[j][2 + i]);
+                    // Centring `f_B` leaves the estimator unbiased and
+                    // takes the output's offset out of its variance.
+                    s1 += (fb - mean) * (fab - fa);
+                    st += (fa - fab).powi(2);
+                }
+                (s1 / n / v, st / (2.0 * n) / v)
+            })
+            .collect()
+    };
+    let all: Vec<usize> = (0..rows.len()).collect();
+    let point = estimate(&all);
+    let mut rng = ChaCha8Rng::seed_from_u64(spec.seed ^ 0x5EED_B007);
+    let mut draws: Vec<Vec<(f64, f64)>> = Vec::with_capacity(BOOTSTRAP);
+    for _ in 0..BOOTSTRAP {
+        let sample: Vec<usize> = (0..rows.len())
+            .map(|_| ((rng.random::<f64>() * rows.len() as f64) as usize).min(rows.len() - 1))
+            .collect();
+        draws.push(estimate(&sample));
+    }
+    let interval = |mut v: Vec<f64>| -> [f64; 2] {
+        v.sort_by(f64::total_cmp);
+        let at = |q: f64| v[((q * (v.len() - 1) as f64).round() as usize).min(v.len() - 1)];
+        [at(0.025), at(0.975)]
+    };
+    let mut indices: Vec<SobolIndex> = spec
+        .factors
+        .iter()
+        .enumerate()
+        .map(|(i, f)| SobolIndex {
+            path: f.path.clone(),
+            first: point[i].0,
+            first_ci: interval(draws.iter().map(|d| d[i].0).collect()),
+            total: point[i].1,
+            total_ci: interval(draws.iter().map(|d| d[i].1).collect()),
+        })
+        .collect();
+    indices.sort_by(|a, b| b.total.total_cmp(&a.total));
+    Ok(indices)
+}
+
+/// Member results of a sweep keyed by design index, as [`analyze`] wants
+/// them.
+pub fn runs_by_index<'a>(
+    count: usize,
+    members: impl IntoIterator<Item = (u32, &'a RunResult)>,
+) -> Vec<Option<&'a RunResult>> {
+    let mut runs = vec![None; count];
+    let by_index: BTreeMap<u32, &RunResult> = members.into_iter().collect();
+    for (i, run) in by_index {
+        if let Some(slot) = runs.get_mut(i as usize) {
+            *slot = Some(run);
+        }
+    }
+    runs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sweep::{Distribution, SweepFactor};
+    use crate::{ParamValue, RunMeta};
+
+    fn uniform(path: &str, low: f64, high: f64) -> SweepFactor {
+        SweepFactor {
+            path: ParamPath(path.into()),
+            distribution: Distribution::Uniform { low, high },
+        }
+    }
+
+    /// Run every point of `spec` through `f`, as a one-sample "run" `y`.
+    fn simulate(spec: &SweepSpec, f: impl Fn(&[f64]) -> f64) -> Vec<RunResult> {
+        spec.expand()
+            .unwrap()
+            .into_iter()
+            .map(|point| {
+                let x: Vec<f64> = spec
+                    .factors
+                    .iter()
+                    .map(|fac| match point[&fac.path] {
+                        ParamValue::Real(v) => v,
+                        _ => unreachable!(),
+                    })
+                    .collect();
+                RunResult {
+                    times: vec![0.0],
+                    series: BTreeMap::from([("y".to_string(), vec![f(&x)])]),
+                    meta: RunMeta::default(),
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn morris_recovers_linear_effects() {
+        let spec = SweepSpec {
+            design: Design::Morris {
+                trajectories: 20,
+                levels: 4,
+            },
+            factors: vec![
+                uniform("a", 0.0, 1.0),
+                uniform("b", 0.0, 1.0),
+                uniform("c", 0.0, 1.0),
+            ],
+            seed: 7,
+        };
+        assert_eq!(spec.run_count().unwrap(), 80);
+        let runs = simulate(&spec, |x| x[0] - 2.0 * x[1]);
+        let refs: Vec<Option<&RunResult>> = runs.iter().map(Some).collect();
+        let report = analyze(&spec, &refs, &[ScalarOutput::new("y", OutputKind::Final)]).unwrap();
+        let m = &report.outputs[0].morris;
+        assert_eq!(m[0].path.0, "b");
+        assert!((m[0].mu + 2.0).abs() < 1e-9 && (m[0].mu_star - 2.0).abs() < 1e-9);
+        assert!(m[0].sigma < 1e-9 && m[0].effects == 20);
+        assert_eq!(m[1].path.0, "a");
+        assert!((m[1].mu_star - 1.0).abs() < 1e-9);
+        assert!(m[2].mu_star < 1e-12);
+    }
+
+    #[test]
+    fn saltelli_estimates_the_ishigami_indices() {
+        use std::f64::consts::PI;
+        let spec = SweepSpec {
+            design: Design::Saltelli { samples: 2000 },
+            factors: vec![
+                uniform("x1", -PI, PI),
+                uniform("x2", -PI, PI),
+                uniform("x3", -PI, PI),
+            ],
+            seed: 11,
+        };
+        let runs = simulate(&spec, |x| {
+            x[0].sin() + 7.0 * x[1].sin().powi(2) + 0.1 * x[2].powi(4) * x[0].sin()
+        });
+        let mut refs: Vec<Option<&RunResult>> = runs.iter().map(Some).collect();
+        // A failed run drops its row, not the analysis.
+        refs[3] = None;
+        let report = analyze(&spec, &refs, &[ScalarOutput::new("y", OutputKind::Max)]).unwrap();
+        let by = |p: &str| {
+            report.outputs[0]
+                .sobol
+                .iter()
+                .find(|s| s.path.0 == p)
+                .cloned()
+                .unwrap()
+        };
+        // Analytic values for a = 7, b = 0.1.
+        for (p, s1, st) in [
+            ("x1", 0.3139, 0.5576),
+            ("x2", 0.4424, 0.4424),
+            ("x3", 0.0, 0.2437),
+        ] {
+            let s = by(p);
+            assert!((s.first - s1).abs() < 0.1, "{p}: S1 = {}", s.first);
+            assert!((s.total - st).abs() < 0.1, "{p}: ST = {}", s.total);
+            assert!(s.first_ci[0] <= s.first && s.first <= s.first_ci[1]);
+        }
+        assert_eq!(report.outputs[0].sobol[2].path.0, "x3");
+    }
+
+    #[test]
+    fn scalar_outputs_reduce_a_series() {
+        let run = RunResult {
+            times: vec![0.0, 1.0, 2.0, 3.0],
+            series: BTreeMap::from([("soc".to_string(), vec![1.0, 0.8, 0.4, 0.2])]),
+            meta: RunMeta::default(),
+        };
+        let eval = |kind| ScalarOutput::new("soc", kind).evaluate(&run).unwrap();
+        assert_eq!(eval(OutputKind::Final), 0.2);
+        assert_eq!(eval(OutputKind::Max), 1.0);
+        assert_eq!(eval(OutputKind::Min), 0.2);
+        assert!((eval(OutputKind::Integral) - 1.8).abs() < 1e-12);
+        let t = eval(OutputKind::TimeToThreshold { threshold: 0.6 });
+        assert!((t - 1.5).abs() < 1e-12);
+        // Never crossed → censored at the end of the run.
+        assert_eq!(eval(OutputKind::TimeToThreshold { threshold: 0.0 }), 3.0);
+        assert!(ScalarOutput::new("v", OutputKind::Max)
+            .evaluate(&run)
+            .is_none());
+        assert_eq!(
+            ScalarOutput::from_token("soc", "time-to-threshold", Some(0.2)).map(|o| o.label()),
+            Some("t(soc = 0.2)".to_string())
+        );
+        assert!(ScalarOutput::from_token("soc", "time_to", None).is_none());
+    }
+
+    #[test]
+    fn other_designs_are_refused() {
+        let spec = SweepSpec {
+            design: Design::MonteCarlo { samples: 4 },
+            factors: vec![uniform("a", 0.0, 1.0)],
+            seed: 0,
+        };
+        let err = analyze(&spec, &[None; 4], &[ScalarOutput::default()]).unwrap_err();
+        assert!(err.contains("Morris or Saltelli"), "{err}");
+    }
+}
@@ -0,0 +1,667 @@
+//! On-disk experiment store — the archive behind the in-memory registry.
+//!
+//! The [`ExperimentRegistry`](crate::ExperimentRegistry) is a bounded working
+//! set; the store is where a finished run outlives the session. It lives in
+//! the Twin folder so it travels with the model — a colleague opening the twin
+//! sees the same runs, and networking ships the files on the content plane
+//! like any other twin file:
+//!
+//! ```text
+//! <twin>/experiments/
+//!   index.json            StoreIndex — one IndexEntry per run + sweep families
+//!   <id>/run.json         StoredRun — definition, status, solver notes
+//!   <id>/series.lxs       trajectory, columnar (see encode_series)
+//! ```
+//!
+//! This module fixes the *format* only. It does no I/O and pulls in no JSON
+//! crate: the host serializes [`StoreIndex`] / [`StoredRun`] with serde and
+//! moves the bytes through its storage layer (`lunco-modelica`'s
+//! `experiment_store`).
+//!
+//! ## Why the index
+//!
+//! Loading a trajectory is the expensive part, and the storage layer has no
+//! directory listing on every platform. The index answers "which runs exist,
+//! for which model, with which parameters, when" from one small file, so
+//! restore and search never touch a `series.lxs` — trajectories load lazily,
+//! when a plot or a query first needs them.
+//!
+//! ## Why columnar
+//!
+//! A run is a few columns of many samples. Storing each column as contiguous
+//! little-endian `f64`s is 8 bytes a sample (JSON spends ~20), loads without
+//! parsing, and round-trips every bit, `NaN` padding included.
+
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use web_time::{SystemTime, UNIX_EPOCH};
+
+use crate::{
+    Experiment, ExperimentId, ModelRef, ParamPath, ParamValue, RunBounds, RunMeta, RunResult,
+    RunStatus, Sweep, SweepId, SweepMember, TwinId,
+};
+
+/// Store directory, relative to the twin root.
+pub const STORE_DIR: &str = "experiments";
+
+/// Bumped when [`StoreIndex`] / [`StoredRun`] / the series layout change
+/// incompatibly.
+pub const STORE_VERSION: u32 = 1;
+
+const SERIES_MAGIC: &[u8; 4] = b"LXS\x01";
+
+/// `experiments/index.json`, relative to the twin root.
+pub fn index_path() -> String {
+    format!("{STORE_DIR}/index.json")
+}
+
+/// `experiments/<id>/run.json`, relative to the twin root.
+pub fn run_path(id: ExperimentId) -> String {
+    format!("{STORE_DIR}/{}/run.json", id.as_artifact_stem())
+}
+
+/// `experiments/<id>/series.lxs`, relative to the twin root.
+pub fn series_path(id: ExperimentId) -> String {
+    format!("{STORE_DIR}/{}/series.lxs", id.as_artifact_stem())
+}
+
+fn to_unix_ms(t: SystemTime) -> u64 {
+    t.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Lower-case state word shared by the index and search filters.
+fn status_word(s: &RunStatus) -> &'static str {
+    match s {
+        RunStatus::Pending => "pending",
+        RunStatus::Queued => "queued",
+        RunStatus::Running { .. } => "running",
+        RunStatus::Done { .. } => "done",
+        RunStatus::Failed { .. } => "failed",
+        RunStatus::Cancelled => "cancelled",
+    }
+}
+
+/// Compact text for a parameter value — what search matches `name=value`
+/// against.
+fn value_text(v: &ParamValue) -> String {
+    match v {
+        ParamValue::Real(x) => x.to_string(),
+        ParamValue::Int(i) => i.to_string(),
+        ParamValue::Bool(b) => b.to_string(),
+        ParamValue::String(s) | ParamValue::Enum(s) => s.clone(),
+        ParamValue::RealArray(a) => format!(
+            "{{{}}}",
+            a.iter().map(f64::to_string).collect::<Vec<_>>().join(",")
+        ),
+    }
+}
+
+// ---------- Run record ----------
+
+/// One run's `run.json`: everything about the run except its trajectory.
+///
+/// The registry's [`TwinId`] is not stored — it scopes runs to an open
+/// document and means nothing in another session. `source` names the
+/// document instead (its path relative to the twin root, or any stable key
+/// the host chooses), and the host re-scopes the run when that document opens.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredRun {
+    pub id: ExperimentId,
+    pub model_ref: ModelRef,
+    pub name: String,
+    pub source: Option<String>,
+    pub overrides: BTreeMap<ParamPath, ParamValue>,
+    #[serde(default)]
+    pub inputs: BTreeMap<ParamPath, ParamValue>,
+    pub bounds: RunBounds,
+    pub status: RunStatus,
+    pub created_at_ms: u64,
+    pub color_hint: u8,
+    #[serde(default)]
+    pub sweep: Option<SweepMember>,
+    /// Result file a reference run was imported from.
+    #[serde(default)]
+    pub reference: Option<String>,
+    /// Wall time, sample count and solver notes of the stored trajectory.
+    /// `None` when the run ended without one.
+    #[serde(default)]
+    pub meta: Option<RunMeta>,
+}
+
+impl StoredRun {
+    pub fn from_experiment(exp: &Experiment, source: Option<String>) -> Self {
+        Self {
+            id: exp.id,
+            model_ref: exp.model_ref.clone(),
+            name: exp.name.clone(),
+            source,
+            overrides: exp.overrides.clone(),
+            inputs: exp.inputs.clone(),
+            bounds: exp.bounds.clone(),
+            status: exp.status.clone(),
+            created_at_ms: to_unix_ms(exp.created_at),
+            color_hint: exp.color_hint,
+            sweep: exp.sweep,
+            reference: exp.reference.clone(),
+            meta: exp.result.as_ref().map(|r| r.meta.clone()),
+        }
+    }
+
+    /// Rebuild the registry row under `twin_id`, trajectory not yet loaded.
+    /// A run stored mid-flight (its host quit before it finished) comes back
+    /// `Cancelled` — nothing will ever finish it.
+    pub fn into_experiment(self, twin_id: TwinId) -> Experiment {
+        let status = if self.status.is_terminal() {
+            self.status
+        } else {
+            RunStatus::Cancelled
+        };
+        Experiment {
+            id: self.id,
+            twin_id,
+            model_ref: self.model_ref,
+            name: self.name,
+            overrides: self.overrides,
+            inputs: self.inputs,
+            bounds: self.bounds,
+            status,
+            result: None,
+            created_at: UNIX_EPOCH + Duration::from_millis(self.created_at_ms),
+            color_hint: self.color_hint,
+            sweep: self.sweep,
+            reference: self.reference,
+        }
+    }
+}
+
+// ---------- Index ----------
+
+/// One run's line in `index.json` — the searchable summary of a
+/// [`StoredRun`].
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    pub id: ExperimentId,
+    pub name: String,
+    pub model: String,
+    pub source: Option<String>,
+    pub created_at_ms: u64,
+    /// `done`, `failed`, `cancelled` (or an in-flight word for a run stored
+    /// before it finished).
+    pub status: String,
+    /// Overrides and inputs as `name → value text`.
+    #[serde(default)]
+    pub params: BTreeMap<String, String>,
+    #[serde(default)]
+    pub sweep: Option<SweepId>,
+    /// Trajectory shape; both zero when no `series.lxs` was written.
+    #[serde(default)]
+    pub samples: usize,
+    #[serde(default)]
+    pub variables: usize,
+}
+
+impl IndexEntry {
+    /// Summarise `run`; `variables` is the trajectory's column count.
+    pub fn from_run(run: &StoredRun, variables: usize) -> Self {
+        Self {
+            id: run.id,
+            name: run.name.clone(),
+            model: run.model_ref.0.clone(),
+            source: run.source.clone(),
+            created_at_ms: run.created_at_ms,
+            status: status_word(&run.status).to_string(),
+            params: run
+                .overrides
+                .iter()
+                .chain(&run.inputs)
+                .map(|(k, v)| (k.0.clone(), value_text(v)))
+                .collect(),
+            sweep: run.sweep.map(|m| m.sweep),
+            samples: run.meta.as_ref().map_or(0, |m| m.sample_count),
+            variables,
+        }
+    }
+}
+
+/// A sweep family as stored: the [`Sweep`] record plus the document key its
+/// members were filed under. The record's `twin_id` is stale on reload, like
+/// a run's.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoredSweep {
+    pub source: Option<String>,
+    pub sweep: Sweep,
+}
+
+/// `index.json` — every stored run of one twin, oldest first.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StoreIndex {
+    pub version: u32,
+    #[serde(default)]
+    pub runs: Vec<IndexEntry>,
+    #[serde(default)]
+    pub sweeps: Vec<StoredSweep>,
+}
+
+impl Default for StoreIndex {
+    fn default() -> Self {
+        Self {
+            version: STORE_VERSION,
+            runs: Vec::new(),
+            sweeps: Vec::new(),
+        }
+    }
+}
+
+impl StoreIndex {
+    pub fn get(&self, id: ExperimentId) -> Option<&IndexEntry> {
+        self.runs.iter().find(|e| e.id == id)
+    }
+
+    /// Insert or replace `entry`, keeping creation order.
+    pub fn upsert(&mut self, entry: IndexEntry) {
+        match self.runs.iter().position(|e| e.id == entry.id) {
+            Some(pos) => self.runs[pos] = entry,
+            None => {
+                let at = self
+                    .runs
+                    .partition_point(|e| e.created_at_ms <= entry.created_at_ms);
+                self.runs.insert(at, entry);
+            }
+        }
+    }
+
+    /// Insert or replace a sweep family.
+    pub fn upsert_sweep(&mut self, sweep: StoredSweep) {
+        match self
+            .sweeps
+            .iter()
+            .position(|s| s.sweep.id == sweep.sweep.id)
+        {
+            Some(pos) => self.sweeps[pos] = sweep,
+            None => self.sweeps.push(sweep),
+        }
+    }
+
+    pub fn sweep(&self, id: SweepId) -> Option<&StoredSweep> {
+        self.sweeps.iter().find(|s| s.sweep.id == id)
+    }
+
+    /// Drop a run, and its place in any sweep; a family left without members
+    /// goes with it. Returns `false` if the run was not indexed.
+    pub fn remove(&mut self, id: ExperimentId) -> bool {
+        let before = self.runs.len();
+        self.runs.retain(|e| e.id != id);
+        for s in &mut self.sweeps {
+            s.sweep.members.retain(|m| *m != id);
+        }
+        self.sweeps.retain(|s| !s.sweep.members.is_empty());
+        self.runs.len() != before
+    }
+
+    /// Runs filed under `source`, oldest first.
+    pub fn for_source<'a>(&'a self, source: &'a str) -> impl Iterator<Item = &'a IndexEntry> {
+        self.runs
+            .iter()
+            .filter(move |e| e.source.as_deref() == Some(source))
+    }
+
+    /// Runs matching `query`, newest first, at most `query.limit`.
+    pub fn search(&self, query: &StoreQuery) -> Vec<&IndexEntry> {
+        let hits = self.runs.iter().rev().filter(|e| query.matches(e));
+        match query.limit {
+            Some(n) => hits.take(n).collect(),
+            None => hits.collect(),
+        }
+    }
+}
+
+/// Filter over [`StoreIndex`]. Every set field must match; an empty query
+/// matches everything.
+#[derive(Clone, Debug, Default)]
+pub struct StoreQuery {
+    /// Case-insensitive substring of the model name.
+    pub model: Option<String>,
+    /// Exact document key.
+    pub source: Option<String>,
+    /// `name` — the run overrides that parameter; `name=value` — to exactly
+    /// that value (as text, e.g. `m=2.5`).
+    pub param: Option<String>,
+    /// Created at or after (Unix ms).
+    pub since_ms: Option<u64>,
+    /// Created before (Unix ms).
+    pub until_ms: Option<u64>,
+    /// State word (`done`, `failed`, `cancelled`).
+    pub status: Option<String>,
+    /// Case-insensitive substring of the run name.
+    pub text: Option<String>,
+    pub limit: Option<usize>,
+}
+
+impl StoreQuery {
+    pub fn matches(&self, e: &IndexEntry) -> bool {
+        let contains =
+            |hay: &str, needle: &str| hay.to_lowercase().contains(&needle.to_lowercase());
+        if let Some(m) = &self.model {
+            if !contains(&e.model, m) {
+                return false;
+            }
+        }
+        if let Some(s) = &self.source {
+            if e.source.as_deref() != Some(s.as_str()) {
+                return false;
+            }
+        }
+        if let Some(p) = &self.param {
+            let hit = match p.split_once('=') {
+                Some((k, v)) => e.params.get(k.trim()).is_some_and(|x| x == v.trim()),
+                None => e.params.contains_key(p.trim()),
+            };
+            if !hit {
+                return false;
+            }
+        }
+        if self.since_ms.is_some_and(|t| e.created_at_ms < t) {
+            return false;
+        }
+        if self.until_ms.is_some_and(|t| e.created_at_ms >= t) {
+            return false;
+        }
+        if let Some(s) = &self.status {
+            if !e.status.eq_ignore_ascii_case(s) {
+                return false;
+            }
+        }
+        if let Some(t) = &self.text {
+            if !contains(&e.name, t) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+// ---------- Series ----------
+
+/// A `series.lxs` that cannot be decoded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StoreError {
+    /// Not a series file, or a layout version this build can't read.
+    BadMagic,
+    /// The file ends before its header says it should.
+    Truncated,
+    /// A column name is not UTF-8.
+    BadName,
+}
+
+impl std::fmt::Display for StoreError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not an experiment series file"),
+            Self::Truncated => write!(f, "series file is truncated"),
+            Self::BadName => write!(f, "series column name is not UTF-8"),
+        }
+    }
+}
+
+impl std::error::Error for StoreError {}
+
+/// Encode a trajectory column-wise:
+///
+/// ```text
+/// "LXS\x01"  rows: u32  cols: u32
+/// cols × (len: u32, name: [u8; len])
+/// rows × f64                     time column
+/// cols × rows × f64              one column per variable, name order
+/// ```
+///
+/// All integers and floats little-endian. A column shorter than `times` is
+/// padded with `NaN`, the same way [`RunResult::merge_delta`] pads.
+pub fn encode_series(result: &RunResult) -> Vec<u8> {
+    let rows = result.times.len();
+    let cols = result.series.len();
+    let names: usize = result.series.keys().map(|k| 4 + k.len()).sum();
+    let mut out = Vec::with_capacity(12 + names + 8 * rows * (cols + 1));
+    out.extend_from_slice(SERIES_MAGIC);
+    out.extend_from_slice(&(rows as u32).to_le_bytes());
+    out.extend_from_slice(&(cols as u32).to_le_bytes());
+    for name in result.series.keys() {
+        out.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        out.extend_from_slice(name.as_bytes());
+    }
+    for t in &result.times {
+        out.extend_from_slice(&t.to_le_bytes());
+    }
+    for column in result.series.values() {
+        for i in 0..rows {
+            let v = column.get(i).copied().unwrap_or(f64::NAN);
+            out.extend_from_slice(&v.to_le_bytes());
+        }
+    }
+    out
+}
+
+/// Decode an [`encode_series`] file back into a [`RunResult`] carrying
+/// `meta` (kept in `run.json`, not in the series).
+pub fn decode_series(bytes: &[u8], meta: RunMeta) -> Result<RunResult, StoreError> {
+    let mut cur = Cursor { bytes, at: 0 };
+    if cur.take(4)? != SERIES_MAGIC {
+        return Err(StoreError::BadMagic);
+    }
+    let rows = cur.u32()? as usize;
+    let cols = cur.u32()? as usize;
+    let mut names = Vec::with_capacity(cols.min(4096));
+    for _ in 0..cols {
+        let len = cur.u32()? as usize;
+        let name = std::str::from_utf8(cur.take(len)?).map_err(|_| StoreError::BadName)?;
+        names.push(name.to_string());
+    }
+    // Size check up front so a corrupt header can't ask for a huge buffer.
+    let need = rows
+        .checked_mul(cols + 1)
+        .and_then(|n| n.checked_mul(8))
+        .ok_or(StoreError::Truncated)?;
+    if bytes.len() - cur.at < need {
+        return Err(StoreError::Truncated);
+    }
+    let times = cur.column(rows)?;
+    let mut series = BTreeMap::new();
+    for name in names {
+        series.insert(name, cur.column(rows)?);
+    }
+    Ok(RunResult {
+        times,
+        series,
+        meta,
+    })
+}
+
+struct Cursor<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], StoreError> {
+        let end = self.at.checked_add(n).ok_or(StoreError::Truncated)?;
+        let slice = self.bytes.get(self.at..end).ok_or(StoreError::Truncated)?;
+        self.at = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, StoreError> {
+        let b = self.take(4)?;
+        Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn column(&mut self, rows: usize) -> Result<Vec<f64>, StoreError> {
+        let raw = self.take(rows * 8)?;
+        Ok(raw
+            .chunks_exact(8)
+            .map(|c| f64::from_le_bytes([c[0], c[1], c[2], c[3], c[4], c[5], c[6], c[7]]))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(name: &str, model: &str, at_ms: u64, overrides: &[(&str, f64)]) -> StoredRun {
+        StoredRun {
+            id: ExperimentId::new(),
+            model_ref: ModelRef(model.into()),
+            name: name.into(),
+            source: Some("models/Rocket.mo".into()),
+            overrides: overrides
+                .iter()
+                .map(|(k, v)| (ParamPath((*k).into()), ParamValue::Real(*v)))
+                .collect(),
+            inputs: BTreeMap::new(),
+            bounds: RunBounds::default(),
+            status: RunStatus::Done { wall_time_ms: 5 },
+            created_at_ms: at_ms,
+            color_hint: 0,
+            sweep: None,
+            reference: None,
+            meta: None,
+        }
+    }
+
+    #[test]
+    fn series_round_trips_bit_for_bit() {
+        let mut series = BTreeMap::new();
+        series.insert("h".to_string(), vec![1.0, f64::NAN, -0.0]);
+        series.insert("v".to_string(), vec![0.5]); // short column → NaN-padded
+        let result = RunResult {
+            times: vec![0.0, 0.1, 0.2],
+            series,
+            meta: RunMeta::default(),
+        };
+        let meta = RunMeta {
+            wall_time_ms: 12,
+            sample_count: 3,
+            notes: Some("solver=bdf".into()),
+        };
+        let back = decode_series(&encode_series(&result), meta).unwrap();
+        assert_eq!(back.times, result.times);
+        let h = &back.series["h"];
+        assert_eq!(h[0], 1.0);
+        assert!(h[1].is_nan());
+        assert!(h[2] == 0.0 && h[2].is_sign_negative());
+        assert_eq!(back.series["v"].len(), 3);
+        assert!(back.series["v"][2].is_nan());
+        assert_eq!(back.meta.notes.as_deref(), Some("solver=bdf"));
+    }
+
+    #[test]
+    fn damaged_series_is_refused() {
+        let result = RunResult {
+            times: vec![0.0, 1.0],
+            series: [("x".to_string(), vec![1.0, 2.0])].into(),
+            meta: RunMeta::default(),
+        };
+        let bytes = encode_series(&result);
+        assert_eq!(
+            decode_series(&bytes[..bytes.len() - 1], RunMeta::default()).unwrap_err(),
+            StoreError::Truncated
+        );
+        assert_eq!(
+            decode_series(b"{\"times\":[]}", RunMeta::default()).unwrap_err(),
+            StoreError::BadMagic
+        );
+        // A header claiming billions of rows must not allocate them.
+        let mut huge = SERIES_MAGIC.to_vec();
+        huge.extend_from_slice(&u32::MAX.to_le_bytes());
+        huge.extend_from_slice(&0u32.to_le_bytes());
+        assert_eq!(
+            decode_series(&huge, RunMeta::default()).unwrap_err(),
+            StoreError::Truncated
+        );
+    }
+
+    #[test]
+    fn search_filters_by_model_parameter_and_date() {
+        let mut index = StoreIndex::default();
+        for r in [
+            run("Run 1", "Rocket", 1_000, &[("m", 2.5)]),
+            run("Run 2", "Rocket", 2_000, &[("m", 3.0), ("isp", 300.0)]),
+            run("Run 3", "Rover", 3_000, &[]),
+        ] {
+            index.upsert(IndexEntry::from_run(&r, 2));
+        }
+        let names = |q: &StoreQuery| -> Vec<String> {
+            index.search(q).iter().map(|e| e.name.clone()).collect()
+        };
+        assert_eq!(names(&StoreQuery::default()), ["Run 3", "Run 2", "Run 1"]);
+        let rocket = StoreQuery {
+            model: Some("rock".into()),
+            ..Default::default()
+        };
+        assert_eq!(names(&rocket), ["Run 2", "Run 1"]);
+        let isp = StoreQuery {
+            param: Some("isp".into()),
+            ..Default::default()
+        };
+        assert_eq!(names(&isp), ["Run 2"]);
+        let m = StoreQuery {
+            param: Some("m=2.5".into()),
+            ..Default::default()
+        };
+        assert_eq!(names(&m), ["Run 1"]);
+        let window = StoreQuery {
+            since_ms: Some(1_500),
+            until_ms: Some(3_000),
+            ..Default::default()
+        };
+        assert_eq!(names(&window), ["Run 2"]);
+    }
+
+    #[test]
+    fn removing_the_last_member_drops_the_family() {
+        let mut index = StoreIndex::default();
+        let mut a = run("Sweep 1 #1", "Rocket", 1, &[]);
+        let sweep_id = SweepId::new();
+        a.sweep = Some(SweepMember {
+            sweep: sweep_id,
+            index: 0,
+        });
+        index.upsert(IndexEntry::from_run(&a, 0));
+        index.upsert_sweep(StoredSweep {
+            source: a.source.clone(),
+            sweep: Sweep {
+                id: sweep_id,
+                twin_id: TwinId("doc:1".into()),
+                model_ref: a.model_ref.clone(),
+                name: "Sweep 1".into(),
+                spec: crate::SweepSpec {
+                    design: crate::Design::MonteCarlo { samples: 1 },
+                    factors: Vec::new(),
+                    seed: 0,
+                },
+                base_overrides: BTreeMap::new(),
+                inputs: BTreeMap::new(),
+                bounds: RunBounds::default(),
+                members: vec![a.id],
+                created_at: UNIX_EPOCH,
+                color_hint: 0,
+            },
+        });
+        assert!(index.remove(a.id));
+        assert!(index.sweep(sweep_id).is_none());
+        assert!(!index.remove(a.id));
+    }
+
+    #[test]
+    fn a_run_stored_mid_flight_comes_back_cancelled() {
+        let mut r = run("Run 1", "Rocket", 7, &[]);
+        r.status = RunStatus::Running { t_current: 0.5 };
+        let exp = r.into_experiment(TwinId("doc:9".into()));
+        assert_eq!(exp.status, RunStatus::Cancelled);
+        assert_eq!(exp.twin_id, TwinId("doc:9".into()));
+        assert!(exp.result.is_none());
+    }
+}
@@ -0,0 +1,1098 @@
+//! Design-of-experiments sweeps — one definition, a family of runs.
+//!
+//! A [`SweepSpec`] names a [`Design`] and a list of [`SweepFactor`]s — a
+//! [`ParamPath`] and the [`Distribution`] its value is drawn from — plus the
+//! seed every random choice derives from. [`SweepSpec::expand`] turns it into
+//! one override map per run; the registry stores those runs as ordinary
+//! [`Experiment`](crate::Experiment)s tagged with a [`SweepMember`] so the
+//! family stays one object (see [`ExperimentRegistry::insert_sweep`](crate::ExperimentRegistry::insert_sweep)).
+//!
+//! ## One mapping for every design
+//!
+//! Every design except full-factorial is a set of points in the unit
+//! hypercube, one coordinate per factor, pushed through that factor's
+//! [`Distribution::quantile`]. That is what makes the designs interchangeable:
+//! a Latin hypercube over a normal factor is stratified in *probability*, not
+//! in value, and a truncated normal is sampled exactly rather than by
+//! rejection. Full-factorial is the exception — it enumerates
+//! [`Distribution::levels`], the grid an engineer would write by hand.
+//!
+//! ## Sensitivity designs
+//!
+//! [`Design::Morris`] and [`Design::Saltelli`] are laid out for
+//! [`crate::sensitivity`] rather than for coverage. A Morris design is a set
+//! of one-at-a-time trajectories over each factor's full-factorial levels;
+//! a Saltelli design is two Latin-hypercube base samples `A` and `B` plus,
+//! per factor, `A` with that factor's column taken from `B`. The analysis
+//! regenerates the layout from the spec, so a family's members carry nothing
+//! beyond their index.
+//!
+//! ## Reproducibility
+//!
+//! The same spec expands to the same runs on every machine: Monte Carlo and
+//! Latin-hypercube draws (and the Morris and Saltelli layouts) come from a
+//! ChaCha8 stream seeded with
+//! [`SweepSpec::seed`], and the Sobol sequence is digitally shifted by words
+//! drawn from the same stream (which keeps its stratification). Full-factorial
+//! ignores the seed. The seed is recorded on the sweep, so a family can be
+//! regenerated from its provenance alone.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::seq::SliceRandom;
+use rand::RngExt;
+use rand_chacha::rand_core::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{ParamPath, ParamValue};
+
+/// Upper bound on the runs one sweep may expand to. A full-factorial over a
+/// handful of factors grows geometrically; past this the definition is almost
+/// certainly a typo, and every run holds a result buffer in the registry.
+pub const MAX_SWEEP_RUNS: usize = 10_000;
+
+/// Stable id for one sweep (the family, not its members).
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub struct SweepId(pub Uuid);
+
+impl SweepId {
+    pub fn new() -> Self {
+        Self(Uuid::new_v4())
+    }
+}
+
+impl Default for SweepId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Where a sweep member came from: its family and its point in the design.
+/// `index` is the position in [`SweepSpec::expand`]'s output, so the member's
+/// overrides can be regenerated from the family's spec.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Serialize, Deserialize)]
+pub struct SweepMember {
+    pub sweep: SweepId,
+    pub index: u32,
+}
+
+/// The distribution one factor is drawn from.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Distribution {
+    /// Uniform on `[low, high]`.
+    Uniform {
+        low: f64,
+        high: f64,
+    },
+    Normal {
+        mean: f64,
+        std_dev: f64,
+    },
+    /// A normal restricted to `[low, high]` — the usual model for a toleranced
+    /// dimension that cannot physically leave its limits.
+    TruncatedNormal {
+        mean: f64,
+        std_dev: f64,
+        low: f64,
+        high: f64,
+    },
+    /// A finite set of values — the only way to sweep an `Integer`, `Boolean`
+    /// or enumeration parameter. `weights` empty = equally likely.
+    Discrete {
+        values: Vec<ParamValue>,
+        #[serde(default)]
+        weights: Vec<f64>,
+    },
+}
+
+impl Distribution {
+    fn validate(&self) -> Result<(), String> {
+        let finite = |v: f64, what: &str| {
+            if v.is_finite() {
+                Ok(())
+            } else {
+                Err(format!("{what} must be finite"))
+            }
+        };
+        match self {
+            Self::Uniform { low, high } => {
+                finite(*low, "low")?;
+                finite(*high, "high")?;
+                if low > high {
+                    return Err(format!("low {low} is above high {high}"));
+                }
+            }
+            Self::Normal { mean, std_dev } => {
+                finite(*mean, "mean")?;
+                finite(*std_dev, "std_dev")?;
+                if *std_dev < 0.0 {
+                    return Err("std_dev must not be negative".into());
+                }
+            }
+            Self::TruncatedNormal {
+                mean,
+                std_dev,
+                low,
+                high,
+            } => {
+                finite(*mean, "mean")?;
+                finite(*std_dev, "std_dev")?;
+                finite(*low, "low")?;
+                finite(*high, "high")?;
+                if *std_dev < 0.0 {
+                    return Err("std_dev must not be negative".into());
+                }
+                if low >= high {
+                    return Err(format!("low {low} must be below high {high}"));
+                }
+            }
+            Self::Discrete { values, weights } => {
+                if values.is_empty() {
+                    return Err("no values".into());
+                }
+                if !weights.is_empty() {
+                    if weights.len() != values.len() {
+                        return Err(format!(
+                            "{} weights for {} values",
+                            weights.len(),
+                            values.len()
+                        ));
+                    }
+                    if weights.iter().any(|w| !w.is_finite() || *w < 0.0) {
+                        return Err("weights must be finite and non-negative".into());
+                    }
+                    if weights.iter().sum::<f64>() <= 0.0 {
+                        return Err("weights sum to zero".into());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// The value at cumulative probability `u` (clamped into `(0, 1)`).
+    pub fn quantile(&self, u: f64) -> ParamValue {
+        let u = u.clamp(f64::EPSILON, 1.0 - f64::EPSILON);
+        match self {
+            Self::Uniform { low, high } => ParamValue::Real(low + (high - low) * u),
+            Self::Normal { mean, std_dev } => {
+                ParamValue::Real(mean + std_dev * inverse_normal_cdf(u))
+            }
+            Self::TruncatedNormal {
+                mean,
+                std_dev,
+                low,
+                high,
+            } => ParamValue::Real(truncated_normal_quantile(*mean, *std_dev, *low, *high, u)),
+            Self::Discrete { values, weights } => {
+                if weights.is_empty() {
+                    let i = ((u * values.len() as f64) as usize).min(values.len() - 1);
+                    return values[i].clone();
+                }
+                let total: f64 = weights.iter().sum();
+                let mut acc = 0.0;
+                for (v, w) in values.iter().zip(weights) {
+                    acc += w / total;
+                    if u < acc {
+                        return v.clone();
+                    }
+                }
+                // Rounding left `acc` a hair under 1: the last value with any
+                // weight owns the remainder.
+                let last = weights.iter().rposition(|w| *w > 0.0).unwrap_or(0);
+                values[last].clone()
+            }
+        }
+    }
+
+    /// The full-factorial levels: `n` evenly spaced values from `low` to `high`
+    /// for a uniform factor (the midpoint when `n` is 1), the `n` equal-
+    /// probability mid-quantiles for a (truncated) normal, and every value of a
+    /// discrete factor whatever `n` is.
+    pub fn levels(&self, n: u32) -> Vec<ParamValue> {
+        match self {
+            Self::Discrete { values, .. } => values.clone(),
+            Self::Uniform { low, high } if n > 1 => (0..n)
+                .map(|k| ParamValue::Real(low + (high - low) * k as f64 / (n - 1) as f64))
+                .collect(),
+            _ => (0..n)
+                .map(|k| self.quantile((k as f64 + 0.5) / n as f64))
+                .collect(),
+        }
+    }
+}
+
+/// How the unit hypercube is filled.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Design {
+    /// Every combination of every factor's [`levels`](Distribution::levels).
+    /// The first factor varies slowest.
+    FullFactorial { levels: u32 },
+    /// One sample per equal-probability stratum of every factor, strata paired
+    /// at random.
+    LatinHypercube { samples: u32 },
+    /// A digitally shifted Sobol low-discrepancy sequence. Up to
+    /// [`SobolSequence::MAX_DIMENSIONS`] factors.
+    Sobol { samples: u32 },
+    /// Independent pseudo-random draws.
+    MonteCarlo { samples: u32 },
+    /// Morris elementary-effects screening: `trajectories` one-at-a-time
+    /// paths over `levels` levels per factor, `factors + 1` runs each.
+    Morris { trajectories: u32, levels: u32 },
+    /// Saltelli's scheme for Sobol indices: `samples` rows of two base
+    /// matrices and of each factor's cross matrix, `samples × (factors + 2)`
+    /// runs.
+    Saltelli { samples: u32 },
+}
+
+/// Default grid of a Morris design — the usual choice with a jump of half
+/// the grid.
+pub const MORRIS_DEFAULT_LEVELS: u32 = 4;
+
+impl Design {
+    /// Parse an API token (`full_factorial`, `lhs`, `sobol`, `monte_carlo`,
+    /// `morris`, `saltelli`, and the obvious spellings of each) with its size
+    /// — levels per continuous factor for full-factorial, trajectories for
+    /// Morris (on [`MORRIS_DEFAULT_LEVELS`] levels), base samples for Saltelli,
+    /// the run count otherwise.
+    pub fn from_token(token: &str, n: u32) -> Option<Self> {
+        match token
+            .trim()
+            .to_ascii_lowercase()
+            .replace(['-', ' '], "_")
+            .as_str()
+        {
+            "full_factorial" | "factorial" | "grid" => Some(Self::FullFactorial { levels: n }),
+            "latin_hypercube" | "lhs" => Some(Self::LatinHypercube { samples: n }),
+            "sobol" => Some(Self::Sobol { samples: n }),
+            "monte_carlo" | "mc" | "random" => Some(Self::MonteCarlo { samples: n }),
+            "morris" | "elementary_effects" => Some(Self::Morris {
+                trajectories: n,
+                levels: MORRIS_DEFAULT_LEVELS,
+            }),
+            "saltelli" | "sobol_indices" => Some(Self::Saltelli { samples: n }),
+            _ => None,
+        }
+    }
+
+    /// Short display name (panel rows, log lines).
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::FullFactorial { .. } => "full factorial",
+            Self::LatinHypercube { .. } => "Latin hypercube",
+            Self::Sobol { .. } => "Sobol",
+            Self::MonteCarlo { .. } => "Monte Carlo",
+            Self::Morris { .. } => "Morris",
+            Self::Saltelli { .. } => "Saltelli",
+        }
+    }
+
+    /// Whether the design is laid out for [`crate::sensitivity`].
+    pub fn is_sensitivity(&self) -> bool {
+        matches!(self, Self::Morris { .. } | Self::Saltelli { .. })
+    }
+}
+
+/// One swept parameter.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SweepFactor {
+    pub path: ParamPath,
+    pub distribution: Distribution,
+}
+
+/// A sweep definition: what to vary, how, and from which seed.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SweepSpec {
+    pub design: Design,
+    pub factors: Vec<SweepFactor>,
+    pub seed: u64,
+}
+
+/// Why a sweep definition does not expand.
+#[derive(Clone, Debug, PartialEq)]
+pub enum SweepError {
+    NoFactors,
+    /// The same parameter is swept twice.
+    DuplicateFactor(ParamPath),
+    BadFactor {
+        path: ParamPath,
+        why: String,
+    },
+    /// A zero sample or level count.
+    Empty,
+    TooManyRuns {
+        runs: usize,
+    },
+    /// More factors than the Sobol direction-number table covers.
+    TooManyFactors {
+        factors: usize,
+        max: usize,
+    },
+}
+
+impl std::fmt::Display for SweepError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NoFactors => write!(f, "the sweep varies no parameter"),
+            Self::DuplicateFactor(p) => write!(f, "`{}` is swept twice", p.0),
+            Self::BadFactor { path, why } => write!(f, "factor `{}`: {why}", path.0),
+            Self::Empty => write!(f, "the design asks for zero runs"),
+            Self::TooManyRuns { runs } => write!(
+                f,
+                "the design expands to {runs} runs; at most {MAX_SWEEP_RUNS} are allowed"
+            ),
+            Self::TooManyFactors { factors, max } => write!(
+                f,
+                "a Sobol design covers at most {max} factors; this sweep has {factors}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for SweepError {}
+
+impl SweepSpec {
+    /// The number of runs this spec expands to, after validation.
+    pub fn run_count(&self) -> Result<usize, SweepError> {
+        if self.factors.is_empty() {
+            return Err(SweepError::NoFactors);
+        }
+        let mut seen = BTreeSet::new();
+        for f in &self.factors {
+            if !seen.insert(&f.path) {
+                return Err(SweepError::DuplicateFactor(f.path.clone()));
+            }
+            f.distribution
+                .validate()
+                .map_err(|why| SweepError::BadFactor {
+                    path: f.path.clone(),
+                    why,
+                })?;
+        }
+        let runs = match self.design {
+            Design::FullFactorial { levels } => {
+                if levels == 0 {
+                    return Err(SweepError::Empty);
+                }
+                self.factors.iter().try_fold(1usize, |acc, f| {
+                    let n = match &f.distribution {
+                        Distribution::Discrete { values, .. } => values.len(),
+                        _ => levels as usize,
+                    };
+                    acc.checked_mul(n).filter(|r| *r <= MAX_SWEEP_RUNS).ok_or(
+                        SweepError::TooManyRuns {
+                            runs: acc.saturating_mul(n),
+                        },
+                    )
+                })?
+            }
+            Design::Sobol { .. } if self.factors.len() > SobolSequence::MAX_DIMENSIONS => {
+                return Err(SweepError::TooManyFactors {
+                    factors: self.factors.len(),
+                    max: SobolSequence::MAX_DIMENSIONS,
+                });
+            }
+            Design::LatinHypercube { samples }
+            | Design::Sobol { samples }
+            | Design::MonteCarlo { samples } => samples as usize,
+            Design::Morris {
+                trajectories,
+                levels,
+            } => {
+                for f in &self.factors {
+                    let n = self.morris_levels(f, levels);
+                    if n < 2 {
+                        return Err(SweepError::BadFactor {
+                            path: f.path.clone(),
+                            why: "a Morris design needs at least two levels".into(),
+                        });
+                    }
+                }
+                (trajectories as usize).saturating_mul(self.factors.len() + 1)
+            }
+            Design::Saltelli { samples } => {
+                (samples as usize).saturating_mul(self.factors.len() + 2)
+            }
+        };
+        if runs == 0 {
+            return Err(SweepError::Empty);
+        }
+        if runs > MAX_SWEEP_RUNS {
+            return Err(SweepError::TooManyRuns { runs });
+        }
+        Ok(runs)
+    }
+
+    /// One override map per run, in design order.
+    pub fn expand(&self) -> Result<Vec<BTreeMap<ParamPath, ParamValue>>, SweepError> {
+        let runs = self.run_count()?;
+        match self.design {
+            Design::FullFactorial { levels } => return Ok(self.full_factorial(levels, runs)),
+            Design::Morris { levels, .. } => {
+                let axes: Vec<Vec<ParamValue>> = self
+                    .factors
+                    .iter()
+                    .map(|f| f.distribution.levels(levels))
+                    .collect();
+                return Ok(self
+                    .morris_plan()
+                    .into_iter()
+                    .map(|point| {
+                        self.factors
+                            .iter()
+                            .zip(&axes)
+                            .zip(point)
+                            .map(|((f, axis), k)| (f.path.clone(), axis[k].clone()))
+                            .collect()
+                    })
+                    .collect());
+            }
+            _ => {}
+        }
+        let dims = self.factors.len();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let unit: Vec<Vec<f64>> = match self.design {
+            Design::MonteCarlo { .. } => (0..runs)
+                .map(|_| (0..dims).map(|_| rng.random::<f64>()).collect())
+                .collect(),
+            Design::LatinHypercube { .. } => latin_hypercube(runs, dims, &mut rng),
+            Design::Saltelli { samples } => {
+                let samples = samples as usize;
+                let a = latin_hypercube(samples, dims, &mut rng);
+                let b = latin_hypercube(samples, dims, &mut rng);
+                let mut points = Vec::with_capacity(runs);
+                for (a, b) in a.into_iter().zip(b) {
+                    points.push(a.clone());
+                    points.push(b.clone());
+                    for d in 0..dims {
+                        let mut cross = a.clone();
+                        cross[d] = b[d];
+                        points.push(cross);
+                    }
+                }
+                points
+            }
+            Design::Sobol { .. } => {
+                let shift: Vec<u32> = (0..dims).map(|_| rng.random::<u32>()).collect();
+                let mut sobol = SobolSequence::new(dims).expect("dimension count checked above");
+                (0..runs)
+                    .map(|_| {
+                        sobol
+                            .next_point()
+                            .iter()
+                            .zip(&shift)
+                            // The half-ulp offset keeps every coordinate off 0 and 1.
+                            .map(|(x, s)| ((x ^ s) as f64 + 0.5) / 4_294_967_296.0)
+                            .collect()
+                    })
+                    .collect()
+            }
+            Design::FullFactorial { .. } | Design::Morris { .. } => {
+                unreachable!("handled above")
+            }
+        };
+        Ok(unit
+            .into_iter()
+            .map(|u| {
+                self.factors
+                    .iter()
+                    .zip(u)
+                    .map(|(f, u)| (f.path.clone(), f.distribution.quantile(u)))
+                    .collect()
+            })
+            .collect())
+    }
+
+    /// Level count of `factor` in a Morris design over `levels` levels.
+    fn morris_levels(&self, factor: &SweepFactor, levels: u32) -> usize {
+        match &factor.distribution {
+            Distribution::Discrete { values, .. } => values.len(),
+            _ => levels as usize,
+        }
+    }
+
+    /// The Morris trajectories as level indices per factor, run after run:
+    /// `factors + 1` points per trajectory, each moving one factor by half
+    /// its grid (up from the lower half, down from the upper) in a random
+    /// order from a random base point. Empty for other designs.
+    pub(crate) fn morris_plan(&self) -> Vec<Vec<usize>> {
+        let Design::Morris {
+            trajectories,
+            levels,
+        } = self.design
+        else {
+            return Vec::new();
+        };
+        let counts: Vec<usize> = self
+            .factors
+            .iter()
+            .map(|f| self.morris_levels(f, levels))
+            .collect();
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+        let mut plan = Vec::with_capacity(trajectories as usize * (counts.len() + 1));
+        for _ in 0..trajectories {
+            let mut point: Vec<usize> = counts
+                .iter()
+                .map(|&n| ((rng.random::<f64>() * n as f64) as usize).min(n - 1))
+                .collect();
+            let mut order: Vec<usize> = (0..counts.len()).collect();
+            order.shuffle(&mut rng);
+            plan.push(point.clone());
+            for d in order {
+                let jump = (counts[d] / 2).max(1);
+                point[d] = if point[d] + jump < counts[d] {
+                    point[d] + jump
+                } else {
+                    point[d] - jump
+                };
+                plan.push(point.clone());
+            }
+        }
+        plan
+    }
+
+    fn full_factorial(&self, levels: u32, runs: usize) -> Vec<BTreeMap<ParamPath, ParamValue>> {
+        let axes: Vec<Vec<ParamValue>> = self
+            .factors
+            .iter()
+            .map(|f| f.distribution.levels(levels))
+            .collect();
+        (0..runs)
+            .map(|mut i| {
+                let mut point = BTreeMap::new();
+                // Mixed-radix digits of `i`, last factor fastest.
+                for (f, axis) in self.factors.iter().zip(&axes).rev() {
+                    point.insert(f.path.clone(), axis[i % axis.len()].clone());
+                    i /= axis.len();
+                }
+                point
+            })
+            .collect()
+    }
+}
+
+/// `runs` points with one sample in every equal-probability stratum of every
+/// coordinate, strata paired at random.
+fn latin_hypercube(runs: usize, dims: usize, rng: &mut ChaCha8Rng) -> Vec<Vec<f64>> {
+    let mut points = vec![vec![0.0; dims]; runs];
+    for d in 0..dims {
+        let mut strata: Vec<usize> = (0..runs).collect();
+        strata.shuffle(rng);
+        for (point, stratum) in points.iter_mut().zip(strata) {
+            point[d] = (stratum as f64 + rng.random::<f64>()) / runs as f64;
+        }
+    }
+    points
+}
+
+/// Sobol low-discrepancy sequence in base 2, 32-bit, Gray-code order, with
+/// the Joe–Kuo (2008) direction numbers. Yields raw integer coordinates;
+/// divide by 2³² for the unit interval. The first point is the origin.
+#[derive(Clone, Debug)]
+pub struct SobolSequence {
+    directions: Vec<[u32; 32]>,
+    x: Vec<u32>,
+    index: u32,
+}
+
+/// `(s, a, m₁…mₛ)` for dimensions 2..=21 of `new-joe-kuo-6.21201`.
+const JOE_KUO: [(u32, u32, &[u32]); 20] = [
+    (1, 0, &[1]),
+    (2, 1, &[1, 3]),
+    (3, 1, &[1, 3, 1]),
+    (3, 2, &[1, 1, 1]),
+    (4, 1, &[1, 1, 3, 3]),
+    (4, 4, &[1, 3, 5, 13]),
+    (5, 2, &[1, 1, 5, 5, 17]),
+    (5, 4, &[1, 1, 5, 5, 5]),
+    (5, 7, &[1, 1, 7, 11, 19]),
+    (5, 11, &[1, 1, 5, 1, 1]),
+    (5, 13, &[1, 1, 1, 3, 11]),
+    (5, 14, &[1, 3, 5, 5, 31]),
+    (6, 1, &[1, 3, 3, 9, 7, 49]),
+    (6, 13, &[1, 1, 1, 15, 21, 21]),
+    (6, 16, &[1, 3, 1, 13, 27, 49]),
+    (6, 19, &[1, 1, 1, 15, 7, 5]),
+    (6, 22, &[1, 3, 1, 15, 13, 25]),
+    (6, 25, &[1, 1, 5, 5, 19, 61]),
+    (7, 1, &[1, 3, 7, 11, 23, 15, 103]),
+    (7, 4, &[1, 3, 7, 13, 13, 15, 69]),
+];
+
+impl SobolSequence {
+    pub const MAX_DIMENSIONS: usize = JOE_KUO.len() + 1;
+
+    /// `None` past [`MAX_DIMENSIONS`](Self::MAX_DIMENSIONS).
+    pub fn new(dims: usize) -> Option<Self> {
+        if dims > Self::MAX_DIMENSIONS {
+            return None;
+        }
+        let directions = (0..dims)
+            .map(|d| {
+                let mut v = [0u32; 32];
+                if d == 0 {
+                    for (k, vk) in v.iter_mut().enumerate() {
+                        *vk = 1 << (31 - k);
+                    }
+                    return v;
+                }
+                let (s, a, m) = JOE_KUO[d - 1];
+                let s = s as usize;
+                for (k, (vk, mk)) in v.iter_mut().zip(m).enumerate() {
+                    *vk = mk << (31 - k);
+                }
+                for k in s..32 {
+                    v[k] = v[k - s] ^ (v[k - s] >> s);
+                    for j in 1..s {
+                        if (a >> (s - 1 - j)) & 1 == 1 {
+                            v[k] ^= v[k - j];
+                        }
+                    }
+                }
+                v
+            })
+            .collect();
+        Some(Self {
+            directions,
+            x: vec![0; dims],
+            index: 0,
+        })
+    }
+
+    /// The next point's integer coordinates.
+    pub fn next_point(&mut self) -> Vec<u32> {
+        let point = self.x.clone();
+        let c = self.index.trailing_ones() as usize;
+        for (x, v) in self.x.iter_mut().zip(&self.directions) {
+            *x ^= v[c.min(31)];
+        }
+        self.index = self.index.wrapping_add(1);
+        point
+    }
+}
+
+/// Standard normal quantile — Acklam's rational approximation (relative error
+/// below 1.2e-9 across `(0, 1)`).
+pub(crate) fn inverse_normal_cdf(p: f64) -> f64 {
+    const A: [f64; 6] = [
+        -3.969_683_028_665_376e1,
+        2.209_460_984_245_205e2,
+        -2.759_285_104_469_687e2,
+        1.383_577_518_672_69e2,
+        -3.066_479_806_614_716e1,
+        2.506_628_277_459_239,
+    ];
+    const B: [f64; 5] = [
+        -5.447_609_879_822_406e1,
+        1.615_858_368_580_409e2,
+        -1.556_989_798_598_866e2,
+        6.680_131_188_771_972e1,
+        -1.328_068_155_288_572e1,
+    ];
+    const C: [f64; 6] = [
+        -7.784_894_002_430_293e-3,
+        -3.223_964_580_411_365e-1,
+        -2.400_758_277_161_838,
+        -2.549_732_539_343_734,
+        4.374_664_141_464_968,
+        2.938_163_982_698_783,
+    ];
+    const D: [f64; 4] = [
+        7.784_695_709_041_462e-3,
+        3.224_671_290_700_398e-1,
+        2.445_134_137_142_996,
+        3.754_408_661_907_416,
+    ];
+    const P_LOW: f64 = 0.02425;
+    let tail = |q: f64| {
+        (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+            / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0)
+    };
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Complementary error function (Numerical Recipes' Chebyshev fit, relative
+/// error below 1.2e-7).
+fn erfc(x: f64) -> f64 {
+    let z = x.abs();
+    let t = 1.0 / (1.0 + 0.5 * z);
+    let poly = -z * z - 1.265_512_23
+        + t * (1.000_023_68
+            + t * (0.374_091_96
+                + t * (0.096_784_18
+                    + t * (-0.186_288_06
+                        + t * (0.278_868_07
+                            + t * (-1.135_203_98
+                                + t * (1.488_515_87 + t * (-0.822_152_23 + t * 0.170_872_77))))))));
+    let r = t * poly.exp();
+    if x >= 0.0 {
+        r
+    } else {
+        2.0 - r
+    }
+}
+
+/// Inverse-CDF sample of a normal truncated to `[low, high]`. Works in whichever
+/// tail keeps the probabilities away from 1, so a window far out in the upper
+/// tail does not cancel to nothing.
+fn truncated_normal_quantile(mean: f64, std_dev: f64, low: f64, high: f64, u: f64) -> f64 {
+    if std_dev == 0.0 {
+        return mean.clamp(low, high);
+    }
+    let (za, zb) = ((low - mean) / std_dev, (high - mean) / std_dev);
+    let z = if za > 0.0 {
+        // Upper tail: survival probabilities, pa > pb.
+        let (pa, pb) = (
+            0.5 * erfc(za / std::f64::consts::SQRT_2),
+            0.5 * erfc(zb / std::f64::consts::SQRT_2),
+        );
+        if pa - pb <= 0.0 {
+            return low + (high - low) * u;
+        }
+        -inverse_normal_cdf(pa - u * (pa - pb))
+    } else {
+        let (pa, pb) = (
+            0.5 * erfc(-za / std::f64::consts::SQRT_2),
+            0.5 * erfc(-zb / std::f64::consts::SQRT_2),
+        );
+        if pb - pa <= 0.0 {
+            // No representable mass in the window: fall back to uniform on it
+            // rather than producing a value outside the limits.
+            return low + (high - low) * u;
+        }
+        inverse_normal_cdf(pa + u * (pb - pa))
+    };
+    (mean + std_dev * z).clamp(low, high)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn real(v: &ParamValue) -> f64 {
+        match v {
+            ParamValue::Real(x) => *x,
+            other => panic!("expected a Real, got {other:?}"),
+        }
+    }
+
+    fn factor(path: &str, distribution: Distribution) -> SweepFactor {
+        SweepFactor {
+            path: ParamPath(path.into()),
+            distribution,
+        }
+    }
+
+    #[test]
+    fn full_factorial_enumerates_every_combination_first_factor_slowest() {
+        let spec = SweepSpec {
+            design: Design::FullFactorial { levels: 3 },
+            factors: vec![
+                factor(
+                    "a",
+                    Distribution::Uniform {
+                        low: 0.0,
+                        high: 1.0,
+                    },
+                ),
+                factor(
+                    "b",
+                    Distribution::Discrete {
+                        values: vec![ParamValue::Bool(false), ParamValue::Bool(true)],
+                        weights: vec![],
+                    },
+                ),
+            ],
+            seed: 0,
+        };
+        let runs = spec.expand().unwrap();
+        assert_eq!(runs.len(), 6);
+        let a: Vec<f64> = runs
+            .iter()
+            .map(|r| real(&r[&ParamPath("a".into())]))
+            .collect();
+        assert_eq!(a, vec![0.0, 0.0, 0.5, 0.5, 1.0, 1.0]);
+        assert_eq!(runs[1][&ParamPath("b".into())], ParamValue::Bool(true));
+    }
+
+    #[test]
+    fn latin_hypercube_puts_one_sample_in_every_stratum() {
+        let n = 50;
+        let spec = SweepSpec {
+            design: Design::LatinHypercube { samples: n },
+            factors: vec![
+                factor(
+                    "x",
+                    Distribution::Uniform {
+                        low: 10.0,
+                        high: 20.0,
+                    },
+                ),
+                factor(
+                    "y",
+                    Distribution::Uniform {
+                        low: -1.0,
+                        high: 1.0,
+                    },
+                ),
+            ],
+            seed: 7,
+        };
+        let runs = spec.expand().unwrap();
+        for (path, low, high) in [("x", 10.0, 20.0), ("y", -1.0, 1.0)] {
+            let mut strata: Vec<usize> = runs
+                .iter()
+                .map(|r| {
+                    let u = (real(&r[&ParamPath(path.into())]) - low) / (high - low);
+                    (u * n as f64) as usize
+                })
+                .collect();
+            strata.sort_unstable();
+            assert_eq!(strata, (0..n as usize).collect::<Vec<_>>(), "{path}");
+        }
+    }
+
+    #[test]
+    fn the_same_seed_expands_to_the_same_runs() {
+        let spec = |seed| SweepSpec {
+            design: Design::MonteCarlo { samples: 20 },
+            factors: vec![factor(
+                "k",
+                Distribution::Normal {
+                    mean: 1.0,
+                    std_dev: 0.1,
+                },
+            )],
+            seed,
+        };
+        assert_eq!(spec(3).expand().unwrap(), spec(3).expand().unwrap());
+        assert_ne!(spec(3).expand().unwrap(), spec(4).expand().unwrap());
+    }
+
+    #[test]
+    fn sensitivity_designs_follow_their_layouts() {
+        let uniform = |p| {
+            factor(
+                p,
+                Distribution::Uniform {
+                    low: 0.0,
+                    high: 1.0,
+                },
+            )
+        };
+        let saltelli = SweepSpec {
+            design: Design::Saltelli { samples: 8 },
+            factors: vec![uniform("a"), uniform("b"), uniform("c")],
+            seed: 5,
+        };
+        let runs = saltelli.expand().unwrap();
+        assert_eq!(runs.len(), 8 * 5);
+        for row in runs.chunks(5) {
+            let (a, b) = (&row[0], &row[1]);
+            for (d, f) in saltelli.factors.iter().enumerate() {
+                let cross = &row[2 + d];
+                for g in &saltelli.factors {
+                    let from = if g.path == f.path { b } else { a };
+                    assert_eq!(cross[&g.path], from[&g.path]);
+                }
+            }
+        }
+
+        let morris = SweepSpec {
+            design: Design::Morris {
+                trajectories: 6,
+                levels: 4,
+            },
+            factors: vec![
+                uniform("a"),
+                factor(
+                    "n",
+                    Distribution::Discrete {
+                        values: vec![ParamValue::Int(1), ParamValue::Int(2)],
+                        weights: Vec::new(),
+                    },
+                ),
+            ],
+            seed: 5,
+        };
+        let runs = morris.expand().unwrap();
+        assert_eq!(runs.len(), 6 * 3);
+        for path in runs.chunks(3) {
+            // Each step moves exactly one factor, and each factor moves once.
+            let moved: Vec<&ParamPath> = path
+                .windows(2)
+                .map(|w| {
+                    let changed: Vec<_> = w[0].keys().filter(|k| w[0][*k] != w[1][*k]).collect();
+                    assert_eq!(changed.len(), 1);
+                    changed[0]
+                })
+                .collect();
+            assert_ne!(moved[0], moved[1]);
+            // Half the grid: two of four levels, a third of the range each.
+            let a = ParamPath("a".into());
+            let (x0, x1) = path
+                .iter()
+                .map(|p| real(&p[&a]))
+                .fold((1.0f64, 0.0f64), |(lo, hi), x| (lo.min(x), hi.max(x)));
+            assert!((x1 - x0 - 2.0 / 3.0).abs() < 1e-12);
+        }
+        let single = SweepSpec {
+            factors: vec![factor(
+                "n",
+                Distribution::Discrete {
+                    values: vec![ParamValue::Int(1)],
+                    weights: Vec::new(),
+                },
+            )],
+            ..morris
+        };
+        assert!(matches!(
+            single.run_count(),
+            Err(SweepError::BadFactor { .. })
+        ));
+    }
+
+    #[test]
+    fn sobol_projections_are_stratified() {
+        // Every one-dimensional projection of the first 2^m points of a Sobol
+        // sequence hits each of the 2^m dyadic intervals exactly once.
+        let dims = SobolSequence::MAX_DIMENSIONS;
+        let mut sobol = SobolSequence::new(dims).unwrap();
+        let points: Vec<Vec<u32>> = (0..64).map(|_| sobol.next_point()).collect();
+        for d in 0..dims {
+            let mut cells: Vec<u32> = points.iter().map(|p| p[d] >> 26).collect();
+            cells.sort_unstable();
+            assert_eq!(cells, (0..64).collect::<Vec<_>>(), "dimension {}", d + 1);
+        }
+        let mut two = SobolSequence::new(2).unwrap();
+        let first: Vec<Vec<u32>> = (0..4).map(|_| two.next_point()).collect();
+        let half = 1u32 << 31;
+        assert_eq!(
+            first,
+            vec![
+                vec![0, 0],
+                vec![half, half],
+                vec![half + (half >> 1), half >> 1],
+                vec![half >> 1, half + (half >> 1)],
+            ]
+        );
+    }
+
+    #[test]
+    fn truncated_normal_stays_inside_its_limits() {
+        let spec = SweepSpec {
+            design: Design::Sobol { samples: 256 },
+            factors: vec![
+                factor(
+                    "t",
+                    Distribution::TruncatedNormal {
+                        mean: 0.0,
+                        std_dev: 1.0,
+                        low: -0.5,
+                        high: 2.0,
+                    },
+                ),
+                // A window far out in the upper tail.
+                factor(
+                    "tail",
+                    Distribution::TruncatedNormal {
+                        mean: 0.0,
+                        std_dev: 1.0,
+                        low: 6.0,
+                        high: 7.0,
+                    },
+                ),
+            ],
+            seed: 1,
+        };
+        for run in spec.expand().unwrap() {
+            let t = real(&run[&ParamPath("t".into())]);
+            assert!((-0.5..=2.0).contains(&t), "{t}");
+            let tail = real(&run[&ParamPath("tail".into())]);
+            assert!((6.0..=7.0).contains(&tail), "{tail}");
+        }
+        let upper = Distribution::Normal {
+            mean: 5.0,
+            std_dev: 2.0,
+        }
+        .quantile(0.975);
+        assert!((real(&upper) - (5.0 + 2.0 * 1.959_964)).abs() < 1e-5);
+    }
+
+    #[test]
+    fn weighted_discrete_draws_follow_their_weights() {
+        let d = Distribution::Discrete {
+            values: vec![ParamValue::Int(1), ParamValue::Int(2)],
+            weights: vec![3.0, 1.0],
+        };
+        assert_eq!(d.quantile(0.74), ParamValue::Int(1));
+        assert_eq!(d.quantile(0.76), ParamValue::Int(2));
+        assert_eq!(d.quantile(1.0), ParamValue::Int(2));
+    }
+
+    #[test]
+    fn malformed_definitions_are_refused() {
+        let u = Distribution::Uniform {
+            low: 0.0,
+            high: 1.0,
+        };
+        let spec = |design, factors| SweepSpec {
+            design,
+            factors,
+            seed: 0,
+        };
+        assert_eq!(
+            spec(Design::MonteCarlo { samples: 4 }, vec![]).expand(),
+            Err(SweepError::NoFactors)
+        );
+        assert_eq!(
+            spec(
+                Design::MonteCarlo { samples: 4 },
+                vec![factor("a", u.clone()), factor("a", u.clone())]
+            )
+            .expand(),
+            Err(SweepError::DuplicateFactor(ParamPath("a".into())))
+        );
+        assert!(matches!(
+            spec(
+                Design::FullFactorial { levels: 11 },
+                (0..4)
+                    .map(|i| factor(&format!("p{i}"), u.clone()))
+                    .collect()
+            )
+            .expand(),
+            Err(SweepError::TooManyRuns { .. })
+        ));
+        assert!(matches!(
+            spec(
+                Design::LatinHypercube { samples: 4 },
+                vec![factor(
+                    "a",
+                    Distribution::Uniform {
+                        low: 2.0,
+                        high: 1.0
+                    }
+                )]
+            )
+            .expand(),
+            Err(SweepError::BadFactor { .. })
+        ));
+        assert_eq!(
+            spec(Design::Sobol { samples: 0 }, vec![factor("a", u)]).expand(),
+            Err(SweepError::Empty)
+        );
+    }
+}
@@ -611,6 +611,19 @@ fn node_text(spec: &BehaviorSpec) -> (String, String, bool) {
             },
             false,
         ),
+        BehaviorSpec::ReachTo {
+            effector,
+            target,
+            tolerance,
+            ..
+        } => (
+            "Reach to".into(),
+            format!(
+                "#{effector} → [{:.2}, {:.2}, {:.2}] · ±{tolerance:.2}m",
+                target[0], target[1], target[2]
+            ),
+            false,
+        ),
     }
 }
 
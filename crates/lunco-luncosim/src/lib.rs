@@ -1399,125 +1399,26 @@ fn replay_scenario_journal_timeline(
     }
 }
 
-/// Result-artifact writer: on `RunCompleted`, the host serializes the finished
-/// `RunResult` to `<twin>/results/<experiment-id>.json` so it rides the **content
-/// plane** — the twin file-walk CID's `results/` (a non-dot dir) and the manifest
-/// sync ships it to peers. Host-authoritative: a Client never ran the sim, so it
-/// writes nothing (it *receives* the artifact). The result is recovered from the
-/// registry (core writes it there before `RunCompleted` fires — same pattern as
-/// `project_run_results_to_ui`). JSON today; parquet is a deferred format swap
-/// pending a wasm-reader spike (see `NETWORKING_STATE_SYNC_TAXONOMY_DESIGN.md`).
-/// `RunResult` to `<twin>/results/<experiment-id>.json` through the cross-platform
-/// [`lunco_storage`] layer (native file / wasm WebStorage). This is **core
-/// persistence, not a networking concern** — a single-player run's results
-/// survive a restart, and when networking is on the same file rides the content
-/// plane to peers. Host/standalone only: a networked Client never ran the sim, so
-/// it writes nothing (it *receives* the artifact). Recovered from the registry
-/// (core writes it there before `RunCompleted` fires — same pattern as
-/// `project_run_results_to_ui`). JSON today; parquet is a deferred format swap.
-fn write_run_result_artifact(
-    mut completed: MessageReader<lunco_experiments::RunCompleted>,
-    registry: Res<lunco_experiments::ExperimentRegistry>,
-    workspace: Res<lunco_workspace::WorkspaceResource>,
-    role: Option<Res<lunco_core::NetworkRole>>,
-) {
-    if matches!(role.as_deref(), Some(lunco_core::NetworkRole::Client)) {
-        return;
-    }
-    for msg in completed.read() {
-        let id = msg.experiment_id;
-        let Some(result) = registry.get(id).and_then(|e| e.result.as_ref()) else {
-            continue;
-        };
-        let Some(active) = workspace.active_twin else {
-            continue;
-        };
-        let Some(twin) = workspace.twin(active) else {
-            continue;
-        };
-        // The storage layer creates parent dirs on write (FileStorage tmp+rename;
-        // WebStorage is key-based), so no explicit mkdir — all I/O goes through it.
-        let dest = twin
-            .root
-            .join("results")
-            .join(format!("{}.json", id.as_artifact_stem()));
-        match serde_json::to_vec_pretty(result) {
-            Ok(bytes) => match lunco_storage::write_file_sync(&dest, &bytes) {
-                Ok(()) => info!("[experiment] wrote result artifact {dest:?}"),
-                Err(e) => warn!("[experiment] result artifact write failed: {e}"),
-            },
-            Err(e) => warn!("[experiment] result serialize failed: {e}"),
-        }
-    }
-}
-
-/// Result-artifact loader — the consume half of persistence/ship-artifact. For
-/// each known experiment that lacks a trajectory, reads
-/// `<twin>/results/<id>.json` through [`lunco_storage`] (cross-platform, no
-/// directory listing — bounded by the registry cap) and loads it. This restores a
-/// single-player run's results after a restart AND makes a networked peer *see*
-/// the host's results once their file syncs.
-///
-/// Change-driven on [`ExperimentRegistry`] mutation (a definition synced, a run
-/// completed, a status update) — so a just-synced result file is picked up on the
-/// next registry change (e.g. the presence status flip) rather than by polling.
-fn load_run_result_artifacts(
-    mut registry: ResMut<lunco_experiments::ExperimentRegistry>,
-    workspace: Res<lunco_workspace::WorkspaceResource>,
-) {
-    let Some(active) = workspace.active_twin else {
-        return;
-    };
-    let Some(root) = workspace.twin(active).map(|t| t.root.join("results")) else {
-        return;
-    };
-    // Ids known but resultless — the only candidates worth a storage read.
-    let want: Vec<lunco_experiments::ExperimentId> = registry
-        .iter_all()
-        .filter(|e| e.result.is_none())
-        .map(|e| e.id)
-        .collect();
-    for id in want {
-        let path = root.join(format!("{}.json", id.as_artifact_stem()));
-        let Ok(bytes) = lunco_storage::read_file_sync(&path) else {
-            continue; // not present (yet)
-        };
-        match serde_json::from_slice::<lunco_experiments::RunResult>(&bytes) {
-            Ok(result) => {
-                let wall = result.meta.wall_time_ms;
-                registry.set_result(id, result);
-                registry.set_status(
-                    id,
-                    lunco_experiments::RunStatus::Done { wall_time_ms: wall },
-                );
-                info!(
-                    "[experiment] loaded result artifact for {}",
-                    id.as_artifact_stem()
-                );
-            }
-            Err(e) => warn!(
-                "[experiment] result artifact parse failed for {}: {e}",
-                id.as_artifact_stem()
-            ),
-        }
-    }
-}
-
-/// Networking distribution trigger: when a run finishes on the host, ask for an
+/// Networking distribution trigger: when a run ends on the host, ask for an
 /// immediate scenario-manifest rebuild so already-connected peers pull the
-/// just-written result artifact now (serviced by `service_manifest_rebuild_request`
-/// in lunco-networking). The write itself is the core persistence system's job;
-/// this only nudges distribution. Host-only.
+/// just-stored run (`<twin>/experiments/`) now (serviced by
+/// `service_manifest_rebuild_request` in lunco-networking). The write itself is
+/// the core experiment store's job (`lunco_modelica::experiment_store`); this
+/// only nudges distribution. Host-only.
 #[cfg(feature = "networking")]
 fn request_rebuild_after_result(
     mut completed: MessageReader<lunco_experiments::RunCompleted>,
+    mut failed: MessageReader<lunco_experiments::RunFailed>,
+    mut cancelled: MessageReader<lunco_experiments::RunCancelled>,
     role: Option<Res<lunco_core::NetworkRole>>,
     mut rebuild: ResMut<lunco_networking::sync::RequestManifestRebuild>,
 ) {
     if !matches!(role.as_deref(), Some(lunco_core::NetworkRole::Host)) {
         return;
     }
-    if completed.read().count() > 0 {
+    // Every terminal state is stored, so each one changes the shared files.
+    let finished = completed.read().count() + failed.read().count() + cancelled.read().count();
+    if finished > 0 {
         rebuild.0 = true;
     }
 }
@@ -1589,8 +1490,8 @@ fn broadcast_run_status(
 
 /// Presence apply (client): drain host-sent run-status updates into the local
 /// `ExperimentRegistry` so a synced experiment's row advances Running → Done.
-/// Won't clobber a `Done` already loaded from the result artifact (the artifact
-/// carries the trajectory; a late progress packet must not downgrade it).
+/// Won't clobber a `Done` already loaded from the experiment store (the stored
+/// series carries the trajectory; a late progress packet must not downgrade it).
 #[cfg(feature = "networking")]
 fn apply_run_status(
     mut pending: ResMut<lunco_networking::sync::PendingRunStatus>,
@@ -2714,30 +2615,6 @@ impl Plugin for SandboxCorePlugin {
         // The terrain support projection must observe that promotion before it
         // decides whether physics may resume; plugin insertion order is not a
         // valid synchronization contract for a streamed physics world.
-        // Experiment result-artifact persistence — CORE (not networking): a run's
-        // trajectory is written to `<twin>/results/<id>.json` through the
-        // cross-platform storage layer and restored on demand, so single-player run
-        // history survives a restart. Networking additionally distributes the same
-        // files via the content plane. Guarded so a config without experiments /
-        // workspace simply skips.
-        app.add_systems(
-            Update,
-            write_run_result_artifact.run_if(
-                resource_exists::<lunco_experiments::ExperimentRegistry>
-                    .and_then(resource_exists::<lunco_workspace::WorkspaceResource>),
-            ),
-        );
-        // Load half is change-driven on the registry (a definition synced, a run
-        // completed, a status flip) — so a just-arrived result file is picked up on
-        // the next registry change instead of by polling.
-        app.add_systems(
-            Update,
-            load_run_result_artifacts.run_if(
-                resource_changed::<lunco_experiments::ExperimentRegistry>
-                    .and_then(resource_exists::<lunco_workspace::WorkspaceResource>),
-            ),
-        );
-
         // Dismiss the HTML loading screen once the first frame paints (wasm-only;
         // no-op on native). Pairs with `web/index.html` → `lunco-boot.js`.
         app.add_plugins(lunco_web::WebReadyPlugin);
@@ -2826,11 +2703,11 @@ impl Plugin for SandboxCorePlugin {
             // or client app never hits a missing resource.
             app.init_resource::<lunco_networking::sync::PendingRunStatus>();
             app.init_resource::<lunco_networking::sync::RequestManifestRebuild>();
-            // Result artifacts themselves are written/loaded by the CORE persistence
-            // systems (registered unconditionally below — storage-backed, all
+            // Runs themselves are written/loaded by the CORE experiment store
+            // (`lunco_modelica::experiment_store` — storage-backed, all
             // platforms). Networking only adds the *distribution* trigger: when a
             // run finishes on the host, ask for an immediate manifest rebuild so
-            // already-connected peers pull the just-written result now.
+            // already-connected peers pull the just-stored run now.
             app.add_systems(
                 Update,
                 request_rebuild_after_result
@@ -40,9 +40,11 @@ mod jointed_tire;
 /// Control kernels live here rather than in core (see the nothing-into-core
 /// rule).
 pub mod kernels;
+mod ros_sensors;
 mod sensing;
 mod wheel_spin;
 pub use jointed_tire::{apply_jointed_tire_forces, JointedWheelTire};
+pub use ros_sensors::RosSensorSettings;
 use wheel_spin::update_wheel_spin;
 
 pub mod wheel_kinematics;
@@ -100,6 +102,9 @@ impl Plugin for LunCoMobilityPlugin {
         // Bridge avian collision / trigger-volume events onto the telemetry bus
         // so scripts can react via `on_event` instead of polling distance().
         sensing::register_collision_event_bridge(app);
+        // Odometry / IMU / point-cloud topics for ROS clients; idle until one
+        // subscribes.
+        ros_sensors::register_ros_sensors(app);
 
         app.register_type::<Suspension>()
             .register_type::<WheelRaycast>()
@@ -0,0 +1,277 @@
+//! Vehicle sensors as ROS 2 topics — odometry, IMU and a range point cloud.
+//!
+//! Every drivable body (one that owns an [`InputPorts`] surface, i.e. what a
+//! `cmd_vel` can steer) is advertised in [`RosTopics`] under its `api_id`:
+//!
+//! | topic | type | source |
+//! |---|---|---|
+//! | `/lunco/<id>/odom` | `nav_msgs/Odometry` | authoritative pose + avian velocities |
+//! | `/lunco/<id>/imu` | `sensor_msgs/Imu` | orientation, body rates, specific force |
+//! | `/lunco/<id>/points` | `sensor_msgs/PointCloud2` | a fan of raycasts from the body origin |
+//! | `/lunco/<id>/cmd_vel` | `geometry_msgs/Twist` | the input side, see `lunco_api::transports::rosbridge` |
+//!
+//! Nothing is computed for a topic with no subscriber, so the raycast fan
+//! costs nothing until a client asks for it. Readings are ground truth — the
+//! covariances are zero — because a noise model is a sensor's property, not
+//! the transport's.
+
+use avian3d::prelude::*;
+use bevy::ecs::relationship::RelationshipTarget;
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use lunco_api::transports::ros::{
+    entity_topic, Header, Imu, Odometry, PointCloud2, Pose, RosBasis, RosMessage, RosPublish,
+    RosTopics, Time as RosTime, Twist, Vector3,
+};
+use lunco_core::coords::GridPos;
+use lunco_core::{GlobalEntityId, InputPorts};
+use lunco_environment::LocalGravity;
+use lunco_physics::pose::SimulationPoseQuery;
+use std::collections::HashMap;
+
+/// Publication rates and the shape of the range fan.
+#[derive(Resource, Debug, Clone)]
+pub struct RosSensorSettings {
+    /// Odometry + IMU rate, Hz of simulation time.
+    pub rate_hz: f64,
+    /// Point-cloud rate, Hz of simulation time.
+    pub scan_rate_hz: f64,
+    /// Rays per ring, spread over 360°.
+    pub scan_columns: usize,
+    /// Rings, spread evenly over `scan_elevation` (radians, low..high).
+    pub scan_rings: usize,
+    pub scan_elevation: (f64, f64),
+    /// Metres; a ray that hits nothing in range contributes no point.
+    pub scan_range: f64,
+}
+
+impl Default for RosSensorSettings {
+    fn default() -> Self {
+        Self {
+            rate_hz: 30.0,
+            scan_rate_hz: 10.0,
+            scan_columns: 180,
+            scan_rings: 8,
+            scan_elevation: (-15f64.to_radians(), 15f64.to_radians()),
+            scan_range: 30.0,
+        }
+    }
+}
+
+const SENSOR_TOPICS: [(&str, &str); 4] = [
+    ("odom", Odometry::TYPE),
+    ("imu", Imu::TYPE),
+    ("points", PointCloud2::TYPE),
+    ("cmd_vel", Twist::TYPE),
+];
+
+/// Advertise the sensor topics of each drivable body as it gains an id, and
+/// withdraw them when it goes.
+pub(crate) fn advertise_vehicle_sensors(
+    topics: Res<RosTopics>,
+    // Either may arrive second: the id is minted when the API registry sees
+    // the entity, which need not be the frame its port surface is seeded.
+    added: Query<
+        (Entity, &GlobalEntityId),
+        (
+            With<InputPorts>,
+            Or<(Added<InputPorts>, Added<GlobalEntityId>)>,
+        ),
+    >,
+    mut removed: RemovedComponents<InputPorts>,
+    mut known: Local<HashMap<Entity, u64>>,
+) {
+    for (entity, gid) in &added {
+        for (leaf, ty) in SENSOR_TOPICS {
+            topics.advertise(entity_topic(gid.get(), leaf), ty);
+        }
+        known.insert(entity, gid.get());
+    }
+    for entity in removed.read() {
+        if let Some(id) = known.remove(&entity) {
+            for (leaf, _) in SENSOR_TOPICS {
+                topics.unadvertise(&entity_topic(id, leaf));
+            }
+        }
+    }
+}
+
+/// Frame ids, tf-prefixed per vehicle so two rovers never share a frame.
+fn frames(id: u64) -> (String, String) {
+    (format!("lunco/{id}/odom"), format!("lunco/{id}/base_link"))
+}
+
+/// Odometry and IMU for every subscribed vehicle, at `rate_hz`.
+#[allow(clippy::type_complexity)]
+pub(crate) fn publish_vehicle_odometry(
+    mut commands: Commands,
+    topics: Res<RosTopics>,
+    settings: Res<RosSensorSettings>,
+    time: Res<Time>,
+    poses: SimulationPoseQuery,
+    vehicles: Query<
+        (
+            Entity,
+            &GlobalEntityId,
+            Option<&LinearVelocity>,
+            Option<&AngularVelocity>,
+            Option<&LocalGravity>,
+        ),
+        With<InputPorts>,
+    >,
+    mut last: Local<f64>,
+    // Previous (sim secs, world velocity) per body, for the accelerometer.
+    mut previous: Local<HashMap<Entity, (f64, DVec3)>>,
+) {
+    let now = time.elapsed_secs_f64();
+    if now - *last < 1.0 / settings.rate_hz {
+        return;
+    }
+    *last = now;
+    previous.retain(|e, _| vehicles.contains(*e));
+    for (entity, gid, lin, ang, gravity) in &vehicles {
+        let id = gid.get();
+        let (odom_topic, imu_topic) = (entity_topic(id, "odom"), entity_topic(id, "imu"));
+        let (want_odom, want_imu) = (
+            topics.is_subscribed(&odom_topic),
+            topics.is_subscribed(&imu_topic),
+        );
+        if !want_odom && !want_imu {
+            previous.remove(&entity);
+            continue;
+        }
+        // Not seeded yet: no reading beats a made-up one.
+        let Some((position, rotation)) = poses.pose(entity) else {
+            continue;
+        };
+        let rot = rotation.0;
+        let v = lin.map_or(DVec3::ZERO, |v| v.0);
+        let w = ang.map_or(DVec3::ZERO, |w| w.0);
+        let (odom_frame, base_frame) = frames(id);
+        let stamp = RosTime::from_secs(now);
+        let body = |world: DVec3| RosBasis::vector(rot.inverse() * world);
+
+        if want_odom {
+            let odom = Odometry::exact(
+                Header {
+                    stamp,
+                    frame_id: odom_frame,
+                },
+                base_frame.clone(),
+                Pose {
+                    position: RosBasis::vector(position.0),
+                    orientation: RosBasis::quaternion(rot),
+                },
+                Twist {
+                    linear: body(v),
+                    angular: body(w),
+                },
+            );
+            commands.trigger(RosPublish::new(odom_topic, &odom));
+        }
+
+        // Specific force: what an accelerometer at rest reads is −g (up).
+        let accel = match previous.insert(entity, (now, v)) {
+            Some((t0, v0)) if now > t0 => (v - v0) / (now - t0),
+            _ => DVec3::ZERO,
+        };
+        if want_imu {
+            let g = gravity.map_or(DVec3::ZERO, |g| g.0);
+            let imu = Imu {
+                header: Header {
+                    stamp,
+                    frame_id: base_frame,
+                },
+                orientation: RosBasis::quaternion(rot),
+                orientation_covariance: [0.0; 9],
+                angular_velocity: body(w),
+                angular_velocity_covariance: [0.0; 9],
+                linear_acceleration: body(accel - g),
+                linear_acceleration_covariance: [0.0; 9],
+            };
+            commands.trigger(RosPublish::new(imu_topic, &imu));
+        }
+    }
+}
+
+/// The range fan for every subscribed vehicle, at `scan_rate_hz`. Rays start
+/// at the body origin and skip the body's own colliders.
+pub(crate) fn publish_vehicle_points(
+    mut commands: Commands,
+    topics: Res<RosTopics>,
+    settings: Res<RosSensorSettings>,
+    time: Res<Time>,
+    poses: SimulationPoseQuery,
+    spatial: lunco_physics::GridSpatialQuery,
+    vehicles: Query<(Entity, &GlobalEntityId, Option<&RigidBodyColliders>), With<InputPorts>>,
+    mut last: Local<f64>,
+) {
+    let now = time.elapsed_secs_f64();
+    if now - *last < 1.0 / settings.scan_rate_hz {
+        return;
+    }
+    *last = now;
+    let s = &*settings;
+    for (entity, gid, own) in &vehicles {
+        let topic = entity_topic(gid.get(), "points");
+        if !topics.is_subscribed(&topic) {
+            continue;
+        }
+        let Some((origin, rotation)) = poses.pose(entity) else {
+            continue;
+        };
+        let filter = SpatialQueryFilter::from_excluded_entities(
+            std::iter::once(entity).chain(own.into_iter().flat_map(|c| c.iter())),
+        );
+        let mut points = Vec::with_capacity(s.scan_columns * s.scan_rings);
+        for ring in 0..s.scan_rings {
+            let t = if s.scan_rings > 1 {
+                ring as f64 / (s.scan_rings - 1) as f64
+            } else {
+                0.5
+            };
+            let el = s.scan_elevation.0 + t * (s.scan_elevation.1 - s.scan_elevation.0);
+            for col in 0..s.scan_columns {
+                let az = std::f64::consts::TAU * col as f64 / s.scan_columns as f64;
+                // Built in the body's ROS frame (FLU), cast in ours.
+                let ros = Vector3 {
+                    x: el.cos() * az.cos(),
+                    y: el.cos() * az.sin(),
+                    z: el.sin(),
+                };
+                let world = rotation.0 * RosBasis::to_canonical(ros);
+                let Ok(dir) = Dir3::new(world.as_vec3()) else {
+                    continue;
+                };
+                if let Some(hit) =
+                    spatial.cast_ray_grid(GridPos(origin.0), dir, s.scan_range, true, &filter)
+                {
+                    let d = hit.distance;
+                    points.push([(ros.x * d) as f32, (ros.y * d) as f32, (ros.z * d) as f32]);
+                }
+            }
+        }
+        let (_, base_frame) = frames(gid.get());
+        let cloud = PointCloud2::xyz(
+            Header {
+                stamp: RosTime::from_secs(now),
+                frame_id: base_frame,
+            },
+            &points,
+        );
+        commands.trigger(RosPublish::new(topic, &cloud));
+    }
+}
+
+pub(crate) fn register_ros_sensors(app: &mut App) {
+    app.init_resource::<RosTopics>()
+        .init_resource::<RosSensorSettings>()
+        .add_systems(
+            Update,
+            (
+                advertise_vehicle_sensors,
+                publish_vehicle_odometry,
+                publish_vehicle_points,
+            ),
+        );
+}
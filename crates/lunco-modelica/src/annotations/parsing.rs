@@ -258,7 +258,11 @@ pub fn extract_icon_via_engine(
 // Inner helpers
 // ---------------------------------------------------------------------------
 
-fn build_extends_candidates(class_name: &str, base_name: &str, imports: &[Import]) -> Vec<String> {
+pub(crate) fn build_extends_candidates(
+    class_name: &str,
+    base_name: &str,
+    imports: &[Import],
+) -> Vec<String> {
     let mut out = Vec::new();
     out.push(base_name.to_string());
 
@@ -239,6 +239,45 @@ pub struct ApiModification {
     pub value: String,
 }
 
+/// One swept parameter of `RunSweep`. `distribution` selects which of the
+/// other fields apply: `uniform` (`low`, `high`), `normal` (`mean`,
+/// `std_dev`), `truncated_normal` (all four) or `discrete` (`values`, with
+/// optional `weights` — equally likely when empty).
+#[derive(Reflect, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ApiSweepFactor {
+    pub name: String,
+    pub distribution: String,
+    pub low: Option<f64>,
+    pub high: Option<f64>,
+    pub mean: Option<f64>,
+    pub std_dev: Option<f64>,
+    /// Discrete values, parsed like override values (`true`, `3`, `2.5`, …).
+    #[serde(default)]
+    pub values: Vec<String>,
+    #[serde(default)]
+    pub weights: Vec<f64>,
+}
+
+/// One fitted parameter of `EstimateParameters`, searched within
+/// `[lower, upper]` from `initial` (the middle of the bounds when unset).
+#[derive(Reflect, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ApiEstimatedParam {
+    pub name: String,
+    pub lower: f64,
+    pub upper: f64,
+    pub initial: Option<f64>,
+}
+
+/// One signal `EstimateParameters` fits: model variable `name` against the
+/// measured series `measured` (the same name when unset), weighted by
+/// `weight` (1 when unset).
+#[derive(Reflect, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct ApiFitSignal {
+    pub name: String,
+    pub measured: Option<String>,
+    pub weight: Option<f64>,
+}
+
 #[derive(Reflect, Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub enum ApiClassKind {
     #[default]
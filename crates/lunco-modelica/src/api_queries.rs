@@ -63,7 +63,14 @@ impl Plugin for ModelicaApiQueriesPlugin {
         registry.register(CompileStatusProvider);
         registry.register(RunStatusProvider);
         registry.register(ListRunsProvider);
+        registry.register(ListSweepsProvider);
+        registry.register(GetSensitivityProvider);
+        registry.register(SearchExperimentsProvider);
         registry.register(GetExperimentResultProvider);
+        #[cfg(feature = "ui")]
+        registry.register(GetLinearizationProvider);
+        #[cfg(feature = "ui")]
+        registry.register(GetEstimationProvider);
         registry.register(GetDocumentSourceProvider);
         registry.register(DescribeModelProvider);
         registry.register(SnapshotVariablesProvider);
@@ -835,6 +842,243 @@ impl ApiQueryProvider for ListRunsProvider {
     }
 }
 
+/// `ListSweeps` — every sweep family: its definition (design, factors, seed),
+/// the shared bounds, and member progress by state. `sweep_id` narrows to one
+/// family and adds its member ids in design order; `doc` keeps only families
+/// whose runs came from that document.
+struct ListSweepsProvider;
+
+impl ApiQueryProvider for ListSweepsProvider {
+    fn name(&self) -> &'static str {
+        "ListSweeps"
+    }
+
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        let filter_doc = parse_doc_id(params, "doc").map(|d| d.raw());
+        let filter_sweep = params.get("sweep_id").and_then(|v| v.as_str());
+        let id_to_doc: std::collections::HashMap<ExperimentId, u64> = world
+            .get_resource::<ExperimentSources>()
+            .map(|s| s.0.iter().map(|(k, v)| (*k, v.raw())).collect())
+            .unwrap_or_default();
+        let Some(registry) = world.get_resource::<ExperimentRegistry>() else {
+            return ApiResponse::ok(serde_json::json!({"sweeps": [], "count": 0}));
+        };
+        let mut rows: Vec<serde_json::Value> = Vec::new();
+        for sweep in registry.iter_sweeps() {
+            if filter_sweep.is_some_and(|t| sweep.id.0.to_string() != t) {
+                continue;
+            }
+            if let Some(want) = filter_doc {
+                if !sweep
+                    .members
+                    .iter()
+                    .any(|m| id_to_doc.get(m) == Some(&want))
+                {
+                    continue;
+                }
+            }
+            let mut states = serde_json::Map::new();
+            for exp in registry.sweep_members(sweep.id) {
+                let n = states
+                    .entry(run_state_label(&exp.status))
+                    .or_insert(serde_json::json!(0));
+                *n = serde_json::json!(n.as_u64().unwrap_or(0) + 1);
+            }
+            let created_ms = sweep
+                .created_at
+                .duration_since(web_time::UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(0);
+            let mut row = serde_json::json!({
+                "sweep_id": sweep.id.0.to_string(),
+                "name": sweep.name,
+                "model": sweep.model_ref.0,
+                "design": sweep.spec.design,
+                "factors": sweep.spec.factors,
+                "seed": sweep.spec.seed,
+                "runs": sweep.members.len(),
+                "states": states,
+                "bounds": {
+                    "t_start": sweep.bounds.t_start,
+                    "t_end": sweep.bounds.t_end,
+                    "dt": sweep.bounds.dt,
+                    "n_intervals": sweep.bounds.n_intervals,
+                    "tolerance": sweep.bounds.tolerance,
+                    "solver": sweep.bounds.solver,
+                },
+                "created_at_ms": created_ms,
+            });
+            if filter_sweep.is_some() {
+                row["members"] = serde_json::json!(sweep
+                    .members
+                    .iter()
+                    .map(|m| m.0.to_string())
+                    .collect::<Vec<_>>());
+            }
+            rows.push(row);
+        }
+        let count = rows.len();
+        ApiResponse::ok(serde_json::json!({
+            "sweeps": rows,
+            "count": count,
+        }))
+    }
+}
+
+/// `GetSensitivity` — Morris elementary effects or Sobol indices of a
+/// `morris` / `saltelli` sweep, from the members that have finished.
+///
+/// Params:
+///   - `sweep_id` (string)     — the sweep family.
+///   - `outputs` (object[])    — `[{series, kind, threshold}]`; `kind` is
+///                               `final` (default), `max`, `min`,
+///                               `integral` or `time_to_threshold`.
+///   - `series` (string|string[]), `kind`, `threshold` — shorthand for one
+///                               kind over one or more series.
+struct GetSensitivityProvider;
+
+impl ApiQueryProvider for GetSensitivityProvider {
+    fn name(&self) -> &'static str {
+        "GetSensitivity"
+    }
+
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        let Some(target) = params.get("sweep_id").and_then(|v| v.as_str()) else {
+            return err_missing_field("sweep_id");
+        };
+        let output = |series: &str, v: &serde_json::Value| {
+            let kind = v.get("kind").and_then(|k| k.as_str()).unwrap_or("final");
+            let threshold = v.get("threshold").and_then(|t| t.as_f64());
+            lunco_experiments::ScalarOutput::from_token(series, kind, threshold).ok_or_else(|| {
+                format!(
+                    "`{series}`: unknown output kind `{kind}` (final, max, min, integral or \
+                     time_to_threshold with `threshold`)"
+                )
+            })
+        };
+        let outputs: Result<Vec<_>, String> = match (params.get("outputs"), params.get("series")) {
+            (Some(serde_json::Value::Array(rows)), _) => rows
+                .iter()
+                .map(|row| match row.get("series").and_then(|s| s.as_str()) {
+                    Some(series) => output(series, row),
+                    None => Err("every output needs a `series`".to_string()),
+                })
+                .collect(),
+            (_, Some(serde_json::Value::String(series))) => output(series, params).map(|o| vec![o]),
+            (_, Some(serde_json::Value::Array(names))) => names
+                .iter()
+                .filter_map(|n| n.as_str())
+                .map(|series| output(series, params))
+                .collect(),
+            _ => Err("provide `outputs` or `series`".to_string()),
+        };
+        let outputs = match outputs {
+            Ok(o) if !o.is_empty() => o,
+            Ok(_) => {
+                return ApiResponse::error(
+                    ApiErrorCode::DeserializationError,
+                    "no outputs requested".to_string(),
+                )
+            }
+            Err(why) => return ApiResponse::error(ApiErrorCode::DeserializationError, why),
+        };
+        let Some((id, members)) = world.get_resource::<ExperimentRegistry>().and_then(|r| {
+            let sweep = r.iter_sweeps().find(|s| s.id.0.to_string() == target)?;
+            Some((sweep.id, sweep.members.clone()))
+        }) else {
+            return ApiResponse::error(ApiErrorCode::EntityNotFound, format!("no sweep {target}"));
+        };
+        // Members restored from the twin's store keep their trajectories on
+        // disk until asked for.
+        for member in members {
+            crate::experiment_store::ensure_series(world, member);
+        }
+        let registry = world.resource::<ExperimentRegistry>();
+        match registry.sweep_sensitivity(id, &outputs) {
+            Ok(report) => ApiResponse::ok(serde_json::json!({
+                "sweep_id": target,
+                "report": report,
+            })),
+            Err(why) => ApiResponse::error(ApiErrorCode::DeserializationError, why),
+        }
+    }
+}
+
+/// `SearchExperiments` — query the twin's on-disk experiment store by
+/// `model` (substring), `param` (`name` or `name=value`), `since_ms` /
+/// `until_ms`, `status` and `text` (run name). `doc` picks the twin and keeps
+/// only that document's runs; without it the active twin's whole store is
+/// searched. Newest first, capped at `limit` (default 50). `loaded` tells
+/// whether the run is already in the session registry — pass an unloaded id
+/// to `RestoreExperiment` to bring it back.
+struct SearchExperimentsProvider;
+
+impl ApiQueryProvider for SearchExperimentsProvider {
+    fn name(&self) -> &'static str {
+        "SearchExperiments"
+    }
+
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        let doc = parse_doc_id(params, "doc");
+        let text = |key: &str| {
+            params
+                .get(key)
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        };
+        let mut query = lunco_experiments::StoreQuery {
+            model: text("model"),
+            param: text("param"),
+            since_ms: params.get("since_ms").and_then(|v| v.as_u64()),
+            until_ms: params.get("until_ms").and_then(|v| v.as_u64()),
+            status: text("status"),
+            text: text("text"),
+            limit: Some(params.get("limit").and_then(|v| v.as_u64()).unwrap_or(50) as usize),
+            ..Default::default()
+        };
+        if let Some(doc) = doc {
+            let Some(location) = crate::experiment_store::location_for_doc(world, doc) else {
+                return ApiResponse::error(
+                    ApiErrorCode::EntityNotFound,
+                    format!(
+                        "doc {} has no experiment store (unsaved or not in a twin)",
+                        doc.raw()
+                    ),
+                );
+            };
+            query.source = Some(location.source);
+        }
+        let Some(hits) = crate::experiment_store::search(world, doc, &query) else {
+            return ApiResponse::ok(serde_json::json!({"runs": [], "count": 0}));
+        };
+        let registry = world.get_resource::<ExperimentRegistry>();
+        let rows: Vec<serde_json::Value> = hits
+            .iter()
+            .map(|e| {
+                serde_json::json!({
+                    "experiment_id": e.id.0.to_string(),
+                    "name": e.name,
+                    "model": e.model,
+                    "source": e.source,
+                    "created_at_ms": e.created_at_ms,
+                    "state": e.status,
+                    "params": e.params,
+                    "sweep_id": e.sweep.map(|s| s.0.to_string()),
+                    "samples": e.samples,
+                    "variables": e.variables,
+                    "loaded": registry.is_some_and(|r| r.get(e.id).is_some()),
+                })
+            })
+            .collect();
+        let count = rows.len();
+        ApiResponse::ok(serde_json::json!({
+            "runs": rows,
+            "count": count,
+        }))
+    }
+}
+
 /// Build the `latest_run` pointer attached to `CompileStatus`. Picks
 /// the most-recently-created experiment whose source doc matches.
 /// Returns `null` when no run has been dispatched for the doc.
@@ -916,6 +1160,13 @@ fn run_summary(exp: &lunco_experiments::Experiment, doc_id: Option<u64>) -> serd
         ovr.insert(k.0.clone(), param_value_json(v));
     }
     obj.insert("overrides".into(), serde_json::Value::Object(ovr));
+    if let Some(member) = exp.sweep {
+        obj.insert(
+            "sweep_id".into(),
+            serde_json::Value::String(member.sweep.0.to_string()),
+        );
+        obj.insert("sweep_index".into(), serde_json::Value::from(member.index));
+    }
     obj.insert(
         "bounds".into(),
         serde_json::json!({
@@ -1029,6 +1280,9 @@ impl ApiQueryProvider for GetExperimentResultProvider {
             .and_then(|v| v.as_u64())
             .map(|n| n.max(2) as usize);
 
+        // A run restored from the twin's experiment store has its trajectory
+        // on disk until something asks for it.
+        crate::experiment_store::ensure_series(world, id);
         let Some(registry) = world.get_resource::<ExperimentRegistry>() else {
             return ApiResponse::error(
                 ApiErrorCode::EntityNotFound,
@@ -1109,6 +1363,104 @@ impl ApiQueryProvider for GetExperimentResultProvider {
     }
 }
 
+// ─── GetLinearization ──────────────────────────────────────────────────
+
+/// The newest `LinearizeModel` result, for `doc` when given: the state-space
+/// matrices with their names and operating point, plus the poles.
+#[cfg(feature = "ui")]
+struct GetLinearizationProvider;
+
+#[cfg(feature = "ui")]
+impl ApiQueryProvider for GetLinearizationProvider {
+    fn name(&self) -> &'static str {
+        "GetLinearization"
+    }
+
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        let doc = parse_doc_id(params, "doc");
+        let Some(entry) = world
+            .get_resource::<crate::ui::commands::Linearizations>()
+            .and_then(|l| l.latest(doc))
+        else {
+            return ApiResponse::error(
+                ApiErrorCode::EntityNotFound,
+                match doc {
+                    Some(doc) => format!("no linearization for doc {}", doc.raw()),
+                    None => "no linearization yet — run LinearizeModel".to_string(),
+                },
+            );
+        };
+        match &entry.outcome {
+            Ok(system) => {
+                let poles: Vec<[f64; 2]> = system.poles().iter().map(|p| [p.re, p.im]).collect();
+                ApiResponse::ok(serde_json::json!({
+                    "id": entry.id,
+                    "doc": entry.doc.raw(),
+                    "model": entry.model,
+                    "ok": true,
+                    "system": system,
+                    "poles": poles,
+                }))
+            }
+            Err(why) => ApiResponse::ok(serde_json::json!({
+                "id": entry.id,
+                "doc": entry.doc.raw(),
+                "model": entry.model,
+                "ok": false,
+                "error": why,
+            })),
+        }
+    }
+}
+
+// ─── GetEstimation ─────────────────────────────────────────────────────
+
+/// The newest `EstimateParameters` result, for `doc` when given: fitted
+/// values with standard errors and 95% intervals, correlations, cost history
+/// and per-signal residuals, plus the id of the run filed at the fit.
+#[cfg(feature = "ui")]
+struct GetEstimationProvider;
+
+#[cfg(feature = "ui")]
+impl ApiQueryProvider for GetEstimationProvider {
+    fn name(&self) -> &'static str {
+        "GetEstimation"
+    }
+
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        let doc = parse_doc_id(params, "doc");
+        let Some(entry) = world
+            .get_resource::<crate::ui::commands::Estimations>()
+            .and_then(|e| e.latest(doc))
+        else {
+            return ApiResponse::error(
+                ApiErrorCode::EntityNotFound,
+                match doc {
+                    Some(doc) => format!("no estimation for doc {}", doc.raw()),
+                    None => "no estimation yet — run EstimateParameters".to_string(),
+                },
+            );
+        };
+        match &entry.outcome {
+            Ok(report) => ApiResponse::ok(serde_json::json!({
+                "id": entry.id,
+                "doc": entry.doc.raw(),
+                "model": entry.model,
+                "ok": true,
+                "experiment_id": entry.experiment.map(|id| id.0.to_string()),
+                "report": report,
+            })),
+            Err(why) => ApiResponse::ok(serde_json::json!({
+                "id": entry.id,
+                "doc": entry.doc.raw(),
+                "model": entry.model,
+                "ok": false,
+                "error": why,
+            })),
+        }
+    }
+}
+
 // ─── GetDocumentSource (spec 033 P0, US 1.6) ───────────────────────────
 
 struct GetDocumentSourceProvider;
@@ -5,7 +5,9 @@
 //!   - **native** (`cargo run --bin lunica [-- --api <port>]`) — a winit
 //!     desktop window with the merged-titlebar chrome, the optional HTTP
 //!     `--api` bridge, the OS clipboard, and a real background worker
-//!     thread for rumoca compiles.
+//!     thread for rumoca compiles. `--lsp` instead runs the Modelica
+//!     language server on stdio ([`lunco_modelica::lsp`]) for external
+//!     editors.
 //!   - **wasm** (`scripts/build_web.sh build lunica`) — renders into
 //!     the `<canvas id="bevy">` from `web/index.html`, embeds the bundled
 //!     models via `include_str!`, supports `?example=<file>` deep-linking,
@@ -80,6 +82,19 @@ fn main() {
         );
     }
 
+    // ── Native-only: `--lsp` runs the Modelica language server ──────
+    //
+    // Stdio belongs to the LSP client from here on: no window, no Bevy
+    // app, and nothing may print to stdout but protocol frames.
+    #[cfg(not(target_arch = "wasm32"))]
+    if std::env::args().any(|a| a == "--lsp") {
+        let code = lunco_modelica::lsp::run_stdio().unwrap_or_else(|e| {
+            eprintln!("[lunica] language server I/O error: {e}");
+            1
+        });
+        std::process::exit(code);
+    }
+
     // ── wasm-only: route panics to the browser console ──────────────
     #[cfg(target_arch = "wasm32")]
     console_error_panic_hook::set_once();
@@ -0,0 +1,170 @@
+//! `modelica_regress` — regression-guard Modelica models against reference
+//! results.
+//!
+//! Runs every case of a suite file (model, bounds, overrides, signals,
+//! reference), checks each signal against its reference through a tolerance
+//! tube and reports per-signal pass/fail with the largest deviation. Exits
+//! non-zero when anything fails, so it drops straight into CI. Runs go
+//! through the Experiments runner's solve path (`lunco_modelica::regression`),
+//! so a case reproduces exactly what a Fast Run in the workbench produces.
+//!
+//! ## Usage
+//!
+//! ```bash
+//! modelica_regress <SUITE.json> [OPTIONS]
+//!
+//! OPTIONS:
+//!       --junit PATH    Write a JUnit XML report
+//!       --html PATH     Write an HTML report with a diff plot per signal
+//!       --bless         Write each run over its reference instead of checking
+//!   -h, --help          Show help
+//! ```
+//!
+//! ## Suite file
+//!
+//! ```json
+//! {
+//!   "name": "rocket",
+//!   "tube": { "rel": 1e-3, "abs": 1e-6, "time": 0.0 },
+//!   "cases": [
+//!     {
+//!       "name": "nominal",
+//!       "model": "models/AnnotatedRocketStage.mo",
+//!       "class": "RocketStage",
+//!       "t_end": 10.0,
+//!       "n_intervals": 500,
+//!       "overrides": { "dry_mass": 1200.0 },
+//!       "signals": ["airframe.altitude", "airframe.velocity"],
+//!       "reference": "ref/rocket_nominal.mat",
+//!       "signal_tubes": { "airframe.velocity": { "rel": 5e-3, "time": 0.01 } }
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! Paths are relative to the suite file. Omitted bounds come from the class's
+//! `experiment(...)` annotation; omitted `signals` check every reference
+//! variable the run also has. References are MAT v4 (`.mat`, what Dymola and
+//! OpenModelica write), CSV, or — with the `hdf5` feature — MAT v7.3 / HDF5.
+
+// Native-only CLI: reads models and references off disk. Body lives in
+// `mod native`; wasm32 sees only the stub `main` at the bottom.
+#[cfg(not(target_arch = "wasm32"))]
+mod native {
+    use std::path::PathBuf;
+
+    struct Options {
+        suite: PathBuf,
+        junit: Option<PathBuf>,
+        html: Option<PathBuf>,
+        bless: bool,
+    }
+
+    impl Options {
+        fn parse() -> Self {
+            let mut iter = std::env::args().skip(1);
+            let mut suite: Option<PathBuf> = None;
+            let mut junit = None;
+            let mut html = None;
+            let mut bless = false;
+            while let Some(arg) = iter.next() {
+                match arg.as_str() {
+                    "-h" | "--help" => {
+                        print_help();
+                        std::process::exit(0);
+                    }
+                    "--junit" => {
+                        let v = iter
+                            .next()
+                            .unwrap_or_else(|| die("--junit requires a path"));
+                        junit = Some(PathBuf::from(v));
+                    }
+                    "--html" => {
+                        let v = iter.next().unwrap_or_else(|| die("--html requires a path"));
+                        html = Some(PathBuf::from(v));
+                    }
+                    "--bless" => bless = true,
+                    other if other.starts_with('-') => die(&format!("unknown option `{other}`")),
+                    other if suite.is_none() => suite = Some(PathBuf::from(other)),
+                    other => die(&format!("unexpected argument `{other}`")),
+                }
+            }
+            let Some(suite) = suite else {
+                print_help();
+                std::process::exit(2);
+            };
+            Self {
+                suite,
+                junit,
+                html,
+                bless,
+            }
+        }
+    }
+
+    fn print_help() {
+        println!("Usage: modelica_regress <SUITE.json> [OPTIONS]");
+        println!();
+        println!("Options:");
+        println!("      --junit PATH    Write a JUnit XML report");
+        println!("      --html PATH     Write an HTML report with a diff plot per signal");
+        println!("      --bless         Write each run over its reference instead of checking");
+        println!("  -h, --help          Show help");
+    }
+
+    fn die(msg: &str) -> ! {
+        eprintln!("[modelica_regress] error: {msg}");
+        std::process::exit(2);
+    }
+
+    pub(crate) fn main() {
+        // Same shared rumoca cache as `modelica_run` / `msl_indexer --warm`.
+        if std::env::var_os("RUMOCA_CACHE_DIR").is_none() {
+            let target = lunco_assets::cache_dir().join("rumoca");
+            std::env::set_var("RUMOCA_CACHE_DIR", &target);
+        }
+        let opts = Options::parse();
+        let report = lunco_modelica::regression::run_suite(&opts.suite, opts.bless)
+            .unwrap_or_else(|e| die(&e));
+        lunco_modelica::regression::write_reports(
+            &report,
+            opts.junit.as_deref(),
+            opts.html.as_deref(),
+        )
+        .unwrap_or_else(|e| die(&e));
+
+        for case in &report.cases {
+            if let Some(e) = &case.error {
+                eprintln!("[modelica_regress] {} ERROR {e}", case.name);
+                continue;
+            }
+            for s in &case.signals {
+                let verdict = if s.passed { "ok  " } else { "FAIL" };
+                eprintln!(
+                    "[modelica_regress] {} {verdict} {}: {}",
+                    case.name,
+                    s.name,
+                    s.summary()
+                );
+            }
+        }
+        let (checked, failed, errored) = report.counts();
+        eprintln!(
+            "[modelica_regress] {}: {} case(s), {checked} signal(s), {failed} failed, {errored} error(s) — {}",
+            report.name,
+            report.cases.len(),
+            if report.passed() { "PASS" } else { "FAIL" }
+        );
+        if !report.passed() {
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn main() {
+    native::main();
+}
+
+#[cfg(target_arch = "wasm32")]
+fn main() {}
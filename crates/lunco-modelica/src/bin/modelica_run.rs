@@ -23,7 +23,8 @@
 //! OPTIONS:
 //!   -d, --duration SECS    Simulation duration in seconds [default: 10.0]
 //!   -t, --dt SECS          Fixed-step timestep [default: 0.01]
-//!       --output PATH      Write per-step CSV (header + one row per step)
+//!       --output PATH      Write per-step CSV (header + one row per step);
+//!                          a `.mat` path writes a MAT v4 trajectory instead
 //!       --input NAME=VAL   Set a runtime input value before step 0 (repeatable)
 //!       --record VAR,VAR   Comma-separated variables to record (default: all observables)
 //!   -v, --verbose          Per-step progress logging
@@ -192,7 +193,8 @@ mod native {
         println!("OPTIONS:");
         println!("  -d, --duration SECS  Simulation duration in seconds [default: 10.0]");
         println!("  -t, --dt SECS        Fixed-step timestep [default: 0.01]");
-        println!("      --output PATH    Write per-step CSV (header + one row per step)");
+        println!("      --output PATH    Write per-step CSV (header + one row per step);");
+        println!("                       a .mat path writes a MAT v4 trajectory instead");
         println!("      --input N=V      Set runtime input N to V before stepping (repeatable)");
         println!("      --record VARS    Comma-separated variables to record [default: all]");
         println!("  -v, --verbose        Per-step progress logging");
@@ -341,7 +343,30 @@ mod native {
         // Open the CSV writer up-front so any IO issue surfaces before
         // the user pays for a long simulation. `BufWriter` so per-step
         // writes don't hammer the syscall layer.
+        // A `.mat` output is one MAT v4 trajectory, written once at the end —
+        // the samples collect in a `RunResult` instead of streaming.
+        let mat_output = opts
+            .output
+            .as_ref()
+            .filter(|p| p.extension().is_some_and(|e| e.eq_ignore_ascii_case("mat")));
+        let mut trajectory: Option<lunco_experiments::RunResult> = mat_output.map(|_| {
+            let mut r = lunco_experiments::RunResult {
+                times: vec![stepper.time()],
+                series: Default::default(),
+                meta: Default::default(),
+            };
+            let init_map: HashMap<&str, f64> = initial_state
+                .iter()
+                .map(|(n, v)| (n.as_str(), *v))
+                .collect();
+            for c in &columns {
+                let v = init_map.get(c.as_str()).copied().unwrap_or(f64::NAN);
+                r.series.insert(c.clone(), vec![v]);
+            }
+            r
+        });
         let mut csv_writer: Option<std::io::BufWriter<std::fs::File>> = match &opts.output {
+            Some(_) if mat_output.is_some() => None,
             Some(path) => {
                 let f = std::fs::File::create(path)
                     .unwrap_or_else(|e| die(&format!("failed to create {}: {e}", path.display())));
@@ -383,8 +408,8 @@ mod native {
             }
             steps_done += 1;
 
-            // Sample current state and write to CSV.
-            if let Some(w) = csv_writer.as_mut() {
+            // Sample current state and write to CSV (or the trajectory).
+            if csv_writer.is_some() || trajectory.is_some() {
                 let state: Vec<(String, f64)> = match stepper.state() {
                     Ok(s) => s
                         .values
@@ -403,12 +428,23 @@ mod native {
                     .iter()
                     .map(|(n, v): &(String, f64)| (n.as_str(), *v))
                     .collect();
-                write!(w, "{:.9}", stepper.time()).unwrap();
-                for c in &columns {
-                    let v = map.get(c.as_str()).copied().unwrap_or(f64::NAN);
-                    write!(w, ",{}", format_num(v)).unwrap();
+                if let Some(r) = trajectory.as_mut() {
+                    r.times.push(stepper.time());
+                    for c in &columns {
+                        let v = map.get(c.as_str()).copied().unwrap_or(f64::NAN);
+                        if let Some(col) = r.series.get_mut(c) {
+                            col.push(v);
+                        }
+                    }
+                }
+                if let Some(w) = csv_writer.as_mut() {
+                    write!(w, "{:.9}", stepper.time()).unwrap();
+                    for c in &columns {
+                        let v = map.get(c.as_str()).copied().unwrap_or(f64::NAN);
+                        write!(w, ",{}", format_num(v)).unwrap();
+                    }
+                    writeln!(w).unwrap();
                 }
-                writeln!(w).unwrap();
             }
 
             if opts.verbose {
@@ -473,7 +509,11 @@ mod native {
         if let Some(err) = step_err {
             eprintln!("[modelica_run] WARN: simulation aborted — {err}");
         }
-        if let Some(path) = &opts.output {
+        if let (Some(path), Some(r)) = (mat_output, &trajectory) {
+            std::fs::write(path, lunco_experiments::encode_mat4(r))
+                .unwrap_or_else(|e| die(&format!("failed to write {}: {e}", path.display())));
+            eprintln!("[modelica_run] wrote MAT v4 → {}", path.display());
+        } else if let Some(path) = &opts.output {
             eprintln!("[modelica_run] wrote CSV → {}", path.display());
         }
         eprintln!(
@@ -761,6 +761,50 @@ impl ModelicaEngine {
     pub fn has_class(&mut self, qualified: &str) -> bool {
         self.session.class_lookup_query(qualified).is_some()
     }
+
+    /// [`Self::class_def`] plus the session URI of the file that declares
+    /// it — `doc-N.mo` for an open document (see [`Self::uri_for`]), the
+    /// library file path otherwise. Used by go-to-definition, which has to
+    /// land in a file rather than on a detached `ClassDef`.
+    pub fn class_source(
+        &mut self,
+        qualified: &str,
+    ) -> Option<(String, rumoca_compile::parsing::ast::ClassDef)> {
+        let class = self.class_def(qualified)?;
+        // `class_def` leaves the containing file reachable through
+        // `class_to_uris`: exact for an indexed top-level class or a
+        // bundle hit, a dotted prefix for a nested one.
+        let mut candidates: Vec<(usize, String)> = self
+            .class_to_uris
+            .iter()
+            .filter(|(q, _)| qualified == q.as_str() || qualified.starts_with(&format!("{}.", q)))
+            .flat_map(|(q, uris)| uris.iter().map(move |uri| (q.len(), uri.clone())))
+            .collect();
+        candidates.sort_by(|a, b| b.0.cmp(&a.0));
+        candidates.into_iter().find_map(|(_, uri)| {
+            let parsed = self.session.parsed_file_query(&uri)?;
+            crate::diagram::find_class_by_qualified_name(parsed, qualified)?;
+            Some((uri, class.clone()))
+        })
+    }
+
+    /// Qualified names of the classes indexed from installed documents and
+    /// library files: each file's top-level classes, plus any class
+    /// [`Self::class_def`] has already bridged to its file. The bulk MSL set
+    /// is not listed until looked up — it bypasses `index_ast_classes`.
+    pub fn indexed_class_names(&self) -> impl Iterator<Item = &str> {
+        self.class_to_uris.keys().map(String::as_str)
+    }
+
+    /// Drop one file previously added through [`Self::load_library_files`]
+    /// — e.g. a workspace file that is now open as a document and must not
+    /// declare its classes twice.
+    pub fn remove_library_file(&mut self, uri: &str) {
+        self.remove_class_uri_mappings(uri);
+        self.session.remove_document(uri);
+        self.class_uri_misses.clear();
+        crate::icon_memo::invalidate_source_memos();
+    }
 }
 
 // No Bevy adapter here yet. When the auto-sync system lands (it
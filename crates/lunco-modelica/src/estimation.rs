@@ -0,0 +1,87 @@
+//! Parameter estimation against a compiled model. Native only: every cost
+//! evaluation is a full batch run.
+//!
+//! The fitting itself — cost, optimizers, confidence, report — lives in
+//! `lunco_experiments::estimation`; this module supplies its `simulate`
+//! callback. The model is compiled once by the caller; each evaluation clones
+//! the DAE, binds the trial values over the caller's own overrides and runs it
+//! through the same [`drive_run`] the Experiments runner uses, so the fitted
+//! values reproduce in an ordinary run. Front end: the `EstimateParameters`
+//! command.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use lunco_experiments::estimation::{estimate, html_report};
+use lunco_experiments::{
+    EstimationReport, EstimationSpec, ParamPath, ParamValue, RunBounds, RunResult,
+};
+use rumoca_compile::compile::Dae;
+
+use crate::experiments_runner::{apply_value_bindings_to_dae, drive_run};
+use crate::regression::CollectSink;
+
+/// Output points per run when neither the annotation nor the caller fixes
+/// the grid; the measured samples are compared by interpolation, so the grid
+/// must resolve the response, not match the measurement.
+const MIN_OUTPUT_INTERVALS: u32 = 500;
+
+/// Fit `spec` against `measured`. `bounds` is the run window the model would
+/// otherwise use (its `experiment` annotation); its end is moved to cover the
+/// last measured sample. `base` holds overrides applied under the fitted
+/// parameters on every run.
+pub fn estimate_model(
+    dae: &Dae,
+    bounds: &RunBounds,
+    base: &BTreeMap<ParamPath, ParamValue>,
+    model: &str,
+    spec: &EstimationSpec,
+    measured: &RunResult,
+) -> Result<EstimationReport, String> {
+    spec.validate(measured)?;
+    let Some((first, last)) = spec.time_span(measured) else {
+        return Err("the measured signals have no samples".into());
+    };
+    if first < bounds.t_start {
+        return Err(format!(
+            "the measurement starts at model time {first}, before the run's start {} \
+             (adjust the time offset)",
+            bounds.t_start
+        ));
+    }
+    let mut bounds = bounds.clone();
+    bounds.t_end = last;
+    if bounds.dt.is_none() && bounds.n_intervals.is_none() {
+        let samples = u32::try_from(measured.times.len()).unwrap_or(u32::MAX);
+        bounds.n_intervals = Some(samples.max(MIN_OUTPUT_INTERVALS));
+    }
+    estimate(model, spec, measured, |trial| {
+        let mut bindings = base.clone();
+        bindings.extend(trial.iter().map(|(k, v)| (k.clone(), v.clone())));
+        let mut dae = dae.clone();
+        apply_value_bindings_to_dae(&mut dae, &bindings)?;
+        let mut sink = CollectSink(None);
+        drive_run(&dae, &bounds, web_time::Instant::now(), &mut sink);
+        sink.0
+            .unwrap_or_else(|| Err("the run ended without a result".into()))
+    })
+}
+
+/// Write `report` as HTML (measured/fitted and residual plots) or JSON, by
+/// extension.
+pub fn write_report(path: &Path, report: &EstimationReport) -> Result<(), String> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let bytes = match ext.as_str() {
+        "html" | "htm" => html_report(report).into_bytes(),
+        "json" => serde_json::to_vec_pretty(report).map_err(|e| e.to_string())?,
+        other => {
+            return Err(format!(
+                "unknown estimation report format `.{other}` (html or json)"
+            ))
+        }
+    };
+    std::fs::write(path, bytes).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
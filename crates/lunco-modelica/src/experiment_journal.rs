@@ -11,9 +11,9 @@
 //! them through the registry's public mutators.
 //!
 //! Scope: only the *definition* is journaled (create / rename / bounds / params
-//! / delete). Run **status** rides the ephemeral presence plane and run
-//! **results** ride the content plane as CID'd artifacts — see
-//! `NETWORKING_STATE_SYNC_TAXONOMY_DESIGN.md`.
+//! / delete, plus a sweep family's create / delete). Run **status** rides the
+//! ephemeral presence plane and run **results** ride the content plane as CID'd
+//! artifacts — see `NETWORKING_STATE_SYNC_TAXONOMY_DESIGN.md`.
 
 use std::collections::BTreeMap;
 use std::time::Duration;
@@ -22,7 +22,7 @@ use lunco_doc::DocumentId;
 use lunco_doc_bevy::JournalResource;
 use lunco_experiments::{
     Experiment, ExperimentId, ExperimentRegistry, ModelRef, ParamPath, ParamValue, RunBounds,
-    RunStatus, TwinId,
+    RunStatus, Sweep, SweepId, SweepMember, TwinId,
 };
 use lunco_twin_journal::{AuthorTag, DomainKind, OpPayload};
 use serde::{Deserialize, Serialize};
@@ -45,6 +45,10 @@ pub enum ExperimentOp {
         color_hint: u8,
         /// Millis since the Unix epoch, so the peer's row sorts identically.
         created_at_ms: u64,
+        /// Sweep provenance, so a replayed member stays in its family (and
+        /// exempt from the per-twin cap).
+        #[serde(default)]
+        sweep: Option<SweepMember>,
     },
     SetName {
         id: ExperimentId,
@@ -62,6 +66,14 @@ pub enum ExperimentOp {
     Delete {
         id: ExperimentId,
     },
+    /// A sweep family record. Recorded before its members' `Create`s; the
+    /// members replay through those.
+    CreateSweep {
+        sweep: Sweep,
+    },
+    DeleteSweep {
+        id: SweepId,
+    },
 }
 
 impl OpPayload for ExperimentOp {
@@ -80,6 +92,10 @@ impl ExperimentOp {
             | ExperimentOp::SetBounds { id, .. }
             | ExperimentOp::SetParams { id, .. }
             | ExperimentOp::Delete { id } => *id,
+            // A sweep is keyed by its own uuid; ids are v4, so it cannot
+            // collide with a run's.
+            ExperimentOp::CreateSweep { sweep } => ExperimentId(sweep.id.0),
+            ExperimentOp::DeleteSweep { id } => ExperimentId(id.0),
         }
     }
 }
@@ -110,6 +126,7 @@ fn create_op(exp: &Experiment) -> ExperimentOp {
         bounds: exp.bounds.clone(),
         color_hint: exp.color_hint,
         created_at_ms,
+        sweep: exp.sweep,
     }
 }
 
@@ -142,6 +159,23 @@ pub fn record_delete(journal: &JournalResource, id: ExperimentId) {
     record(journal, &op, &op);
 }
 
+/// Record a sweep family that was just inserted locally. Call before the
+/// members' [`record_create`]s so a replaying peer has the family first.
+pub fn record_create_sweep(journal: &JournalResource, sweep: &Sweep) {
+    let forward = ExperimentOp::CreateSweep {
+        sweep: sweep.clone(),
+    };
+    let inverse = ExperimentOp::DeleteSweep { id: sweep.id };
+    record(journal, &forward, &inverse);
+}
+
+/// Record that a sweep family left the registry, after its members'
+/// [`record_delete`]s. Same self-inverse stance as `record_delete`.
+pub fn record_delete_sweep(journal: &JournalResource, id: SweepId) {
+    let op = ExperimentOp::DeleteSweep { id };
+    record(journal, &op, &op);
+}
+
 /// Apply a definition edit locally **and** record it (with a typed inverse read
 /// from current state). The one funnel for rename / bounds / params / delete so
 /// the edit journals + syncs by design. `journal` is `None` before the journal
@@ -169,6 +203,10 @@ pub fn apply_and_record(
         // is a delete (create shouldn't reach here — use `record_create`).
         ExperimentOp::Delete { .. } => create_op(e),
         ExperimentOp::Create { .. } => ExperimentOp::Delete { id: op.target() },
+        // Sweep ops go through `record_create_sweep` / `record_delete_sweep`;
+        // a sweep id never names a run, so this closure does not see them.
+        ExperimentOp::CreateSweep { sweep } => ExperimentOp::DeleteSweep { id: sweep.id },
+        ExperimentOp::DeleteSweep { .. } => op.clone(),
     });
     apply_op(registry, &op);
     if let Some(journal) = journal {
@@ -210,6 +248,7 @@ fn apply_op(registry: &mut ExperimentRegistry, op: &ExperimentOp) {
             bounds,
             color_hint,
             created_at_ms,
+            sweep,
         } => {
             let created_at =
                 web_time::SystemTime::UNIX_EPOCH + Duration::from_millis(*created_at_ms);
@@ -225,6 +264,8 @@ fn apply_op(registry: &mut ExperimentRegistry, op: &ExperimentOp) {
                 result: None,
                 created_at,
                 color_hint: *color_hint,
+                sweep: *sweep,
+                reference: None,
             });
         }
         ExperimentOp::SetName { id, name } => {
@@ -243,6 +284,12 @@ fn apply_op(registry: &mut ExperimentRegistry, op: &ExperimentOp) {
         ExperimentOp::Delete { id } => {
             registry.delete(*id);
         }
+        ExperimentOp::CreateSweep { sweep } => {
+            registry.insert_sweep_with_id(sweep.clone());
+        }
+        ExperimentOp::DeleteSweep { id } => {
+            registry.delete_sweep(*id);
+        }
     }
 }
 
@@ -292,6 +339,53 @@ mod tests {
         assert_eq!(b.get(id).unwrap().name, "renamed");
     }
 
+    #[test]
+    fn a_replayed_sweep_keeps_its_members_in_the_family() {
+        use lunco_experiments::{Design, Distribution, SweepFactor, SweepSpec};
+        let mut a = ExperimentRegistry::new();
+        let sweep_id = a
+            .insert_sweep(
+                TwinId("t".into()),
+                ModelRef("M".into()),
+                None,
+                SweepSpec {
+                    design: Design::LatinHypercube { samples: 3 },
+                    factors: vec![SweepFactor {
+                        path: ParamPath("k".into()),
+                        distribution: Distribution::Uniform {
+                            low: 0.0,
+                            high: 1.0,
+                        },
+                    }],
+                    seed: 42,
+                },
+                Default::default(),
+                Default::default(),
+                empty_bounds(),
+            )
+            .unwrap();
+        let sweep = a.sweep(sweep_id).unwrap().clone();
+        let mut ops = vec![ExperimentOp::CreateSweep {
+            sweep: sweep.clone(),
+        }];
+        ops.extend(a.sweep_members(sweep_id).into_iter().map(create_op));
+
+        let mut b = ExperimentRegistry::new();
+        for op in &ops {
+            assert!(replay_experiment_op(
+                &mut b,
+                &serde_json::to_value(op).unwrap()
+            ));
+        }
+        let replayed = b.sweep(sweep_id).expect("family replayed");
+        assert_eq!(replayed.spec.seed, 42);
+        assert_eq!(b.sweep_members(sweep_id).len(), 3);
+        assert!(b
+            .sweep_members(sweep_id)
+            .iter()
+            .all(|e| e.sweep.map(|m| m.sweep) == Some(sweep_id)));
+    }
+
     #[test]
     fn bad_payload_is_rejected_softly() {
         let mut r = ExperimentRegistry::new();
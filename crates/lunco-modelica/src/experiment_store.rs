@@ -0,0 +1,572 @@
+//! Persistent experiment store — finished runs written to the Twin folder and
+//! brought back when their document reopens.
+//!
+//! The format (index, `run.json`, columnar `series.lxs`) lives in
+//! [`lunco_experiments::store`]; this module does the I/O through
+//! [`lunco_storage`] (native file / wasm WebStorage) and the Bevy wiring:
+//!
+//! - **Write** — [`persist_finished_runs`]: every run that ends (done, failed,
+//!   cancelled) is filed under `<twin>/experiments/`, keyed by its document's
+//!   path inside the twin. Host/standalone only: a networked Client never ran
+//!   the sim; it *receives* the files, which ride the content plane like any
+//!   other twin file.
+//! - **Restore** — on [`DocumentOpened`](lunco_doc_bevy::DocumentOpened) /
+//!   [`TwinAdded`](lunco_workspace::TwinAdded), the document's stored runs come
+//!   back into the registry as definitions only: the newest
+//!   [`REGISTRY_CAP_PER_TWIN`] single runs plus every stored sweep family. Older
+//!   runs stay on disk, reachable through `SearchExperiments` and
+//!   `RestoreExperiment`.
+//! - **Load** — trajectories are read lazily: [`load_demanded_series`] serves
+//!   the ids in [`SeriesDemand`] (the runs a plot shows), and
+//!   [`ensure_series`] serves synchronous readers (result queries, CSV export).
+//!
+//! Registry eviction only drops a run from the working set; deleting a run
+//! (`DeleteExperiment`) also removes it from the store ([`forget_runs`]).
+
+use std::collections::{BTreeMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use bevy::prelude::*;
+use lunco_doc::{DocumentId, DocumentOrigin};
+use lunco_experiments::store::{self, IndexEntry, StoreIndex, StoreQuery, StoredRun, StoredSweep};
+use lunco_experiments::{
+    ExperimentId, ExperimentRegistry, RunCancelled, RunCompleted, RunFailed, RunResult, RunStatus,
+    REGISTRY_CAP_PER_TWIN,
+};
+
+use crate::experiments_runner::{doc_for_twin, twin_id_for_doc, ExperimentSources};
+use crate::state::ModelicaDocumentRegistry;
+
+/// Runs whose trajectory something wants on screen. Drained by
+/// [`load_demanded_series`]; an id stays until its series loads (it may not
+/// have synced from the host yet) or the run leaves the registry.
+#[derive(Resource, Default, Debug)]
+pub struct SeriesDemand(pub HashSet<ExperimentId>);
+
+/// Where a document's runs are filed: the twin folder holding the store and
+/// the document's key inside it.
+#[derive(Clone, Debug)]
+pub struct StoreLocation {
+    pub root: PathBuf,
+    /// Path relative to the twin root (`/`-separated), or
+    /// `bundled:<file>` for a shipped example.
+    pub source: String,
+}
+
+fn locate(
+    workspace: &lunco_workspace::WorkspaceResource,
+    origin: &DocumentOrigin,
+) -> Option<StoreLocation> {
+    match origin {
+        DocumentOrigin::File { path, .. } => {
+            let handle = lunco_storage::StorageHandle::File(path.clone());
+            let twin = workspace
+                .twins()
+                .find_map(|(_, t)| t.find_owning(&handle))?;
+            let rel = path.strip_prefix(&twin.root).ok()?;
+            let source = rel
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            Some(StoreLocation {
+                root: twin.root.clone(),
+                source,
+            })
+        }
+        // A bundled example has no folder of its own; its runs are filed in
+        // the twin the user is working in.
+        DocumentOrigin::Bundled { filename } => {
+            let twin = workspace.active_twin.and_then(|t| workspace.twin(t))?;
+            Some(StoreLocation {
+                root: twin.root.clone(),
+                source: format!("bundled:{filename}"),
+            })
+        }
+        // Nothing stable to file an Untitled document's runs under.
+        DocumentOrigin::Untitled { .. } => None,
+    }
+}
+
+fn origin_in(
+    docs: Option<&ModelicaDocumentRegistry>,
+    workspace: &lunco_workspace::WorkspaceResource,
+    doc: DocumentId,
+) -> Option<DocumentOrigin> {
+    docs.and_then(|r| r.host(doc))
+        .map(|h| h.document().origin().clone())
+        .or_else(|| workspace.document(doc).map(|e| e.origin.clone()))
+}
+
+/// The store location for `doc`, if it has one.
+pub fn location_for_doc(world: &World, doc: DocumentId) -> Option<StoreLocation> {
+    let workspace = world.get_resource::<lunco_workspace::WorkspaceResource>()?;
+    let origin = origin_in(
+        world.get_resource::<ModelicaDocumentRegistry>(),
+        workspace,
+        doc,
+    )?;
+    locate(workspace, &origin)
+}
+
+/// The twin folder whose store a query addresses: `doc`'s twin when given,
+/// else the active twin.
+pub fn store_root(world: &World, doc: Option<DocumentId>) -> Option<PathBuf> {
+    match doc {
+        Some(doc) => location_for_doc(world, doc).map(|l| l.root),
+        None => {
+            let ws = world.get_resource::<lunco_workspace::WorkspaceResource>()?;
+            ws.active_twin
+                .and_then(|t| ws.twin(t))
+                .map(|t| t.root.clone())
+        }
+    }
+}
+
+/// Read `<root>/experiments/index.json`. Missing → empty. A corrupt index is
+/// kept as `index.json.bad` before starting fresh, so the next write can't
+/// silently destroy what a human might still recover.
+pub fn read_index(root: &Path) -> StoreIndex {
+    let path = root.join(store::index_path());
+    let Ok(bytes) = lunco_storage::read_file_sync(&path) else {
+        return StoreIndex::default();
+    };
+    match serde_json::from_slice(&bytes) {
+        Ok(index) => index,
+        Err(e) => {
+            warn!("[experiment-store] index {path:?} unreadable ({e}); starting a fresh one");
+            let _ = lunco_storage::write_file_sync(&path.with_extension("json.bad"), &bytes);
+            StoreIndex::default()
+        }
+    }
+}
+
+fn write_index(root: &Path, index: &StoreIndex) {
+    let path = root.join(store::index_path());
+    match serde_json::to_vec_pretty(index) {
+        Ok(bytes) => {
+            if let Err(e) = lunco_storage::write_file_sync(&path, &bytes) {
+                warn!("[experiment-store] index write failed: {e}");
+            }
+        }
+        Err(e) => warn!("[experiment-store] index serialize failed: {e}"),
+    }
+}
+
+fn read_run(root: &Path, id: ExperimentId) -> Option<StoredRun> {
+    let bytes = lunco_storage::read_file_sync(&root.join(store::run_path(id))).ok()?;
+    match serde_json::from_slice(&bytes) {
+        Ok(run) => Some(run),
+        Err(e) => {
+            warn!(
+                "[experiment-store] run record {} unreadable: {e}",
+                id.as_artifact_stem()
+            );
+            None
+        }
+    }
+}
+
+fn write_run_record(root: &Path, run: &StoredRun) -> bool {
+    match serde_json::to_vec_pretty(run) {
+        Ok(bytes) => {
+            match lunco_storage::write_file_sync(&root.join(store::run_path(run.id)), &bytes) {
+                Ok(()) => true,
+                Err(e) => {
+                    warn!("[experiment-store] run record write failed: {e}");
+                    false
+                }
+            }
+        }
+        Err(e) => {
+            warn!("[experiment-store] run record serialize failed: {e}");
+            false
+        }
+    }
+}
+
+/// Read a stored trajectory. Falls back to the `results/<id>.json` artifact
+/// earlier builds wrote, so their runs still plot.
+fn read_series(root: &Path, id: ExperimentId) -> Option<RunResult> {
+    if let Ok(bytes) = lunco_storage::read_file_sync(&root.join(store::series_path(id))) {
+        let meta = read_run(root, id).and_then(|r| r.meta).unwrap_or_default();
+        return match store::decode_series(&bytes, meta) {
+            Ok(result) => Some(result),
+            Err(e) => {
+                warn!(
+                    "[experiment-store] series {} unreadable: {e}",
+                    id.as_artifact_stem()
+                );
+                None
+            }
+        };
+    }
+    let legacy = root
+        .join("results")
+        .join(format!("{}.json", id.as_artifact_stem()));
+    let bytes = lunco_storage::read_file_sync(&legacy).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+fn doc_of(
+    sources: &ExperimentSources,
+    registry: &ExperimentRegistry,
+    id: ExperimentId,
+) -> Option<DocumentId> {
+    sources
+        .0
+        .get(&id)
+        .copied()
+        .or_else(|| doc_for_twin(&registry.get(id)?.twin_id))
+}
+
+/// Store writer: files every run that ended this frame. One index
+/// read-modify-write per twin folder however many runs finished together (a
+/// sweep's members often do).
+#[allow(clippy::too_many_arguments)]
+pub fn persist_finished_runs(
+    mut completed: MessageReader<RunCompleted>,
+    mut failed: MessageReader<RunFailed>,
+    mut cancelled: MessageReader<RunCancelled>,
+    registry: Res<ExperimentRegistry>,
+    workspace: Res<lunco_workspace::WorkspaceResource>,
+    docs: Option<Res<ModelicaDocumentRegistry>>,
+    sources: Res<ExperimentSources>,
+    role: Option<Res<lunco_core::NetworkRole>>,
+) {
+    let finished: Vec<ExperimentId> = completed
+        .read()
+        .map(|m| m.experiment_id)
+        .chain(failed.read().map(|m| m.experiment_id))
+        .chain(cancelled.read().map(|m| m.experiment_id))
+        .collect();
+    if finished.is_empty() || matches!(role.as_deref(), Some(lunco_core::NetworkRole::Client)) {
+        return;
+    }
+    let mut touched: BTreeMap<PathBuf, StoreIndex> = BTreeMap::new();
+    for id in finished {
+        let Some(exp) = registry.get(id) else {
+            continue;
+        };
+        let origin = doc_of(&sources, &registry, id)
+            .and_then(|doc| origin_in(docs.as_deref(), &workspace, doc));
+        let Some(loc) = origin.and_then(|o| locate(&workspace, &o)) else {
+            continue;
+        };
+        let run = StoredRun::from_experiment(exp, Some(loc.source.clone()));
+        let mut variables = 0;
+        if let Some(result) = &exp.result {
+            let dest = loc.root.join(store::series_path(id));
+            match lunco_storage::write_file_sync(&dest, &store::encode_series(result)) {
+                Ok(()) => variables = result.series.len(),
+                Err(e) => warn!("[experiment-store] series write failed: {e}"),
+            }
+        }
+        if !write_run_record(&loc.root, &run) {
+            continue;
+        }
+        let index = touched
+            .entry(loc.root.clone())
+            .or_insert_with(|| read_index(&loc.root));
+        index.upsert(IndexEntry::from_run(&run, variables));
+        if let Some(sweep) = exp.sweep.and_then(|m| registry.sweep(m.sweep)) {
+            index.upsert_sweep(StoredSweep {
+                source: Some(loc.source.clone()),
+                sweep: sweep.clone(),
+            });
+        }
+        info!(
+            "[experiment-store] stored {} under {:?}",
+            id.as_artifact_stem(),
+            loc.root
+        );
+    }
+    for (root, index) in touched {
+        write_index(&root, &index);
+    }
+}
+
+/// Rewrite a stored run's record after a definition edit (a rename), so the
+/// store doesn't bring back the old name. No-op for a run that isn't stored.
+pub fn refresh_stored(world: &mut World, id: ExperimentId) {
+    if matches!(
+        world.get_resource::<lunco_core::NetworkRole>(),
+        Some(lunco_core::NetworkRole::Client)
+    ) {
+        return;
+    }
+    let Some(exp) = world
+        .get_resource::<ExperimentRegistry>()
+        .and_then(|r| r.get(id))
+    else {
+        return;
+    };
+    let doc = world
+        .get_resource::<ExperimentSources>()
+        .and_then(|s| s.0.get(&id).copied())
+        .or_else(|| doc_for_twin(&exp.twin_id));
+    let Some(loc) = doc.and_then(|d| location_for_doc(world, d)) else {
+        return;
+    };
+    let mut index = read_index(&loc.root);
+    let Some(variables) = index.get(id).map(|e| e.variables) else {
+        return;
+    };
+    let mut run = StoredRun::from_experiment(exp, Some(loc.source.clone()));
+    if run.meta.is_none() {
+        // Trajectory not loaded — keep the stored one's notes.
+        run.meta = read_run(&loc.root, id).and_then(|r| r.meta);
+    }
+    if write_run_record(&loc.root, &run) {
+        index.upsert(IndexEntry::from_run(&run, variables));
+        write_index(&loc.root, &index);
+    }
+}
+
+/// Bring `doc`'s stored runs back into the registry (definitions only). Runs
+/// already in the registry — a second `DocumentOpened`, a journal replay —
+/// are left alone.
+pub fn restore_for_doc(world: &mut World, doc: DocumentId) {
+    let Some(loc) = location_for_doc(world, doc) else {
+        return;
+    };
+    let index = read_index(&loc.root);
+    let Some(registry) = world.get_resource::<ExperimentRegistry>() else {
+        return;
+    };
+    let mut singles: Vec<&IndexEntry> = index
+        .for_source(&loc.source)
+        .filter(|e| e.sweep.is_none() && registry.get(e.id).is_none())
+        .collect();
+    // Keep the newest, like the registry's own cap would have.
+    let skip = singles.len().saturating_sub(REGISTRY_CAP_PER_TWIN);
+    singles.drain(..skip);
+    let families: Vec<&StoredSweep> = index
+        .sweeps
+        .iter()
+        .filter(|s| s.source.as_deref() == Some(loc.source.as_str()))
+        .collect();
+    let members: HashSet<ExperimentId> = families
+        .iter()
+        .flat_map(|s| s.sweep.members.iter().copied())
+        .collect();
+    let wanted: Vec<ExperimentId> = index
+        .for_source(&loc.source)
+        .filter(|e| {
+            registry.get(e.id).is_none()
+                && (members.contains(&e.id) || singles.iter().any(|s| s.id == e.id))
+        })
+        .map(|e| e.id)
+        .collect();
+    if wanted.is_empty() {
+        return;
+    }
+
+    let twin = twin_id_for_doc(doc);
+    let runs: Vec<StoredRun> = wanted
+        .iter()
+        .filter_map(|id| read_run(&loc.root, *id))
+        .collect();
+    let restored: HashSet<ExperimentId> = runs.iter().map(|r| r.id).collect();
+    let mut registry = world.resource_mut::<ExperimentRegistry>();
+    for stored in &families {
+        if registry.sweep(stored.sweep.id).is_some() {
+            continue;
+        }
+        let mut sweep = stored.sweep.clone();
+        sweep.twin_id = twin.clone();
+        sweep.members.retain(|m| restored.contains(m));
+        if !sweep.members.is_empty() {
+            registry.insert_sweep_with_id(sweep);
+        }
+    }
+    for run in runs {
+        registry.insert_with_id(run.into_experiment(twin.clone()));
+    }
+    if let Some(mut sources) = world.get_resource_mut::<ExperimentSources>() {
+        for id in &restored {
+            sources.0.insert(*id, doc);
+        }
+    }
+    info!(
+        "[experiment-store] restored {} run(s) for {}",
+        restored.len(),
+        loc.source
+    );
+}
+
+/// Bring one archived run back into `doc`'s registry scope — the follow-up to
+/// a `SearchExperiments` hit older than the restore window.
+pub fn restore_run(world: &mut World, doc: DocumentId, id: ExperimentId) -> Result<(), String> {
+    if world
+        .get_resource::<ExperimentRegistry>()
+        .is_some_and(|r| r.get(id).is_some())
+    {
+        return Ok(());
+    }
+    let loc = location_for_doc(world, doc)
+        .ok_or_else(|| "the document is not inside a twin folder".to_string())?;
+    let run = read_run(&loc.root, id)
+        .ok_or_else(|| format!("no stored run {} in {:?}", id.as_artifact_stem(), loc.root))?;
+    let mut exp = run.into_experiment(twin_id_for_doc(doc));
+    // Restored alone, it is no longer part of a family on screen.
+    exp.sweep = None;
+    world
+        .resource_mut::<ExperimentRegistry>()
+        .insert_with_id(exp);
+    world.resource_mut::<ExperimentSources>().0.insert(id, doc);
+    world.resource_mut::<SeriesDemand>().0.insert(id);
+    Ok(())
+}
+
+pub fn on_document_opened_restore_runs(
+    trigger: On<lunco_doc_bevy::DocumentOpened>,
+    mut commands: Commands,
+) {
+    let doc = trigger.event().doc;
+    // Deferred so the document host + workspace entry are in place first.
+    commands.queue(move |world: &mut World| restore_for_doc(world, doc));
+}
+
+/// A twin opened after its documents (session restore) — their runs could not
+/// be located until now.
+pub fn on_twin_added_restore_runs(
+    _trigger: On<lunco_workspace::TwinAdded>,
+    mut commands: Commands,
+) {
+    commands.queue(|world: &mut World| {
+        let docs: Vec<DocumentId> = world
+            .get_resource::<ModelicaDocumentRegistry>()
+            .map(|r| r.docs().map(|(id, _)| id).collect())
+            .unwrap_or_default();
+        for doc in docs {
+            restore_for_doc(world, doc);
+        }
+    });
+}
+
+/// Lazy trajectory loader. Reads the series of every demanded run that has
+/// none yet; a file that isn't there (a peer still syncing it) is retried on
+/// the next registry change. A run that loads but was never seen to finish
+/// here (a peer's) is marked done.
+pub fn load_demanded_series(
+    mut demand: ResMut<SeriesDemand>,
+    mut registry: ResMut<ExperimentRegistry>,
+    workspace: Res<lunco_workspace::WorkspaceResource>,
+    docs: Option<Res<ModelicaDocumentRegistry>>,
+    sources: Res<ExperimentSources>,
+) {
+    let mut settled: Vec<ExperimentId> = Vec::new();
+    let mut loaded: Vec<(ExperimentId, RunResult)> = Vec::new();
+    for &id in &demand.0 {
+        match registry.get(id) {
+            None => settled.push(id),
+            Some(e) if e.result.is_some() => settled.push(id),
+            Some(_) => {
+                let origin = doc_of(&sources, &registry, id)
+                    .and_then(|doc| origin_in(docs.as_deref(), &workspace, doc));
+                let Some(loc) = origin.and_then(|o| locate(&workspace, &o)) else {
+                    settled.push(id);
+                    continue;
+                };
+                if let Some(result) = read_series(&loc.root, id) {
+                    loaded.push((id, result));
+                }
+            }
+        }
+    }
+    // Only touch the resources when something resolved, so an unresolved id
+    // doesn't re-trigger this system every frame.
+    if settled.is_empty() && loaded.is_empty() {
+        return;
+    }
+    for (id, result) in loaded {
+        let wall = result.meta.wall_time_ms;
+        registry.set_result(id, result);
+        if !registry.get(id).is_some_and(|e| e.status.is_terminal()) {
+            registry.set_status(id, RunStatus::Done { wall_time_ms: wall });
+        }
+        settled.push(id);
+    }
+    for id in settled {
+        demand.0.remove(&id);
+    }
+}
+
+/// Load `id`'s trajectory now if it is stored but not in memory. Returns
+/// whether the run has a result afterwards.
+pub fn ensure_series(world: &mut World, id: ExperimentId) -> bool {
+    let Some(exp) = world
+        .get_resource::<ExperimentRegistry>()
+        .and_then(|r| r.get(id))
+    else {
+        return false;
+    };
+    if exp.result.is_some() {
+        return true;
+    }
+    let doc = world
+        .get_resource::<ExperimentSources>()
+        .and_then(|s| s.0.get(&id).copied())
+        .or_else(|| doc_for_twin(&exp.twin_id));
+    let Some(loc) = doc.and_then(|d| location_for_doc(world, d)) else {
+        return false;
+    };
+    let Some(result) = read_series(&loc.root, id) else {
+        return false;
+    };
+    world
+        .resource_mut::<ExperimentRegistry>()
+        .set_result(id, result);
+    true
+}
+
+/// Remove deleted runs from their store. Call before the runs' document
+/// mapping is purged — it is how a run finds its twin folder.
+pub fn forget_runs(world: &mut World, ids: &[ExperimentId]) {
+    if ids.is_empty()
+        || matches!(
+            world.get_resource::<lunco_core::NetworkRole>(),
+            Some(lunco_core::NetworkRole::Client)
+        )
+    {
+        return;
+    }
+    let mut by_root: BTreeMap<PathBuf, Vec<ExperimentId>> = BTreeMap::new();
+    for &id in ids {
+        let doc = world
+            .get_resource::<ExperimentSources>()
+            .and_then(|s| s.0.get(&id).copied());
+        if let Some(loc) = doc.and_then(|d| location_for_doc(world, d)) {
+            by_root.entry(loc.root).or_default().push(id);
+        }
+    }
+    for (root, ids) in by_root {
+        let mut index = read_index(&root);
+        let mut changed = false;
+        for id in ids {
+            changed |= index.remove(id);
+            let _ = lunco_storage::delete_file_sync(&root.join(store::series_path(id)));
+            let _ = lunco_storage::delete_file_sync(&root.join(store::run_path(id)));
+        }
+        if changed {
+            write_index(&root, &index);
+        }
+    }
+}
+
+/// Search the store of `doc`'s twin (or the active twin).
+pub fn search(
+    world: &World,
+    doc: Option<DocumentId>,
+    query: &StoreQuery,
+) -> Option<Vec<IndexEntry>> {
+    let root = store_root(world, doc)?;
+    Some(
+        read_index(&root)
+            .search(query)
+            .into_iter()
+            .cloned()
+            .collect(),
+    )
+}
@@ -297,6 +297,14 @@ impl ModelicaRunner {
         }
     }
 
+    /// Compile `source` the way a Fast Run of it would: through the runner's
+    /// persistent compiler and compile-once DAE cache. Blocks for the
+    /// compile; call it off the main thread.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn compile(&self, source: &ModelSource) -> Result<Arc<Dae>, String> {
+        compile_cached(&self.state, source)
+    }
+
     /// `true` when no scheduler slot is free — i.e. starting another run
     /// right now would queue rather than execute immediately. UI uses this
     /// to reflect a saturated runner. (A click while saturated now queues
@@ -634,6 +642,59 @@ fn dae_cache_key(src: &ModelSource) -> u64 {
     h.finish()
 }
 
+/// Compile `source` through the runner's persistent compiler, serving and
+/// filling the compile-once DAE cache.
+#[cfg(not(target_arch = "wasm32"))]
+fn compile_cached(
+    state: &Arc<Mutex<RunnerState>>,
+    source: &ModelSource,
+) -> Result<Arc<Dae>, String> {
+    // Persistent runner compiler: clone the handle out of `state` (brief
+    // lock), then compile under the compiler's OWN lock so the multi-second
+    // compile never holds `state` and stall the scheduler / parallel runs.
+    // MSL installs once into this session (lazily) instead of per run.
+    let compiler_handle = match state.lock() {
+        Ok(s) => s.compiler.clone(),
+        Err(_) => return Err("runner state poisoned".to_string()),
+    };
+
+    // Compile-once: compile the CLEAN model source (no inputs/overrides baked
+    // in) a single time and cache the resulting DAE. Inputs and overrides are
+    // applied to the DAE afterwards (see `apply_value_bindings_to_dae`), so a
+    // whole parameter/input sweep shares ONE compile and recompiles zero times.
+    let key = dae_cache_key(source);
+    let cached = state
+        .lock()
+        .ok()
+        .and_then(|s| s.dae_cache.get(&key).map(|(_, dae)| dae.clone()));
+    match cached {
+        Some(d) => Ok(d),
+        None => {
+            let compiled = {
+                let mut compiler = compiler_handle.lock().unwrap_or_else(|e| e.into_inner());
+                compiler.compile_str_multi(
+                    &source.model_name,
+                    &source.source,
+                    &source.filename,
+                    &source.extras,
+                )
+            };
+            match compiled {
+                Ok(d) => {
+                    let dae = d.dae.clone();
+                    if let Ok(mut s) = state.lock() {
+                        let ident: ModelIdent =
+                            (source.model_name.clone(), source.filename.clone());
+                        s.dae_cache.insert(key, (ident, dae.clone()));
+                    }
+                    Ok(dae)
+                }
+                Err(e) => Err(format!("compile failed: {e}")),
+            }
+        }
+    }
+}
+
 /// Apply value bindings — both parameter overrides AND experiment input
 /// values — directly to a compiled DAE by rebinding each target variable's
 /// `start` to a literal. This is THE single place run values are injected;
@@ -729,62 +790,17 @@ fn run_inner(
         }
     };
 
-    // Persistent runner compiler: clone the handle out of `state` (brief
-    // lock), then compile under the compiler's OWN lock so the multi-second
-    // compile never holds `state` and stall the scheduler / parallel runs.
-    // MSL installs once into this session (lazily) instead of per run.
-    let compiler_handle = match state.lock() {
-        Ok(s) => s.compiler.clone(),
-        Err(_) => {
+    let base_dae = match compile_cached(&state, &source) {
+        Ok(dae) => dae,
+        Err(error) => {
             let _ = tx.send(RunUpdate::Failed {
-                error: "runner state poisoned".to_string(),
+                error,
                 partial: None,
             });
             return;
         }
     };
 
-    // Compile-once: compile the CLEAN model source (no inputs/overrides baked
-    // in) a single time and cache the resulting DAE. Inputs and overrides are
-    // applied to the DAE afterwards (see `apply_value_bindings_to_dae`), so a
-    // whole parameter/input sweep shares ONE compile and recompiles zero times.
-    let key = dae_cache_key(&source);
-    let cached = state
-        .lock()
-        .ok()
-        .and_then(|s| s.dae_cache.get(&key).map(|(_, dae)| dae.clone()));
-    let base_dae: Arc<Dae> = match cached {
-        Some(d) => d,
-        None => {
-            let compiled = {
-                let mut compiler = compiler_handle.lock().unwrap_or_else(|e| e.into_inner());
-                compiler.compile_str_multi(
-                    &source.model_name,
-                    &source.source,
-                    &source.filename,
-                    &source.extras,
-                )
-            };
-            match compiled {
-                Ok(d) => {
-                    let dae = d.dae.clone();
-                    if let Ok(mut s) = state.lock() {
-                        let ident: ModelIdent = (source.model_name, source.filename);
-                        s.dae_cache.insert(key, (ident, dae.clone()));
-                    }
-                    dae
-                }
-                Err(e) => {
-                    let _ = tx.send(RunUpdate::Failed {
-                        error: format!("compile failed: {e}"),
-                        partial: None,
-                    });
-                    return;
-                }
-            }
-        }
-    };
-
     if cancel.load(Ordering::SeqCst) {
         let _ = tx.send(RunUpdate::Cancelled);
         return;
@@ -1640,6 +1656,22 @@ pub struct PendingHandles(pub Vec<RunHandle>);
 #[derive(Resource, Default)]
 pub struct ExperimentSources(pub std::collections::HashMap<ExperimentId, lunco_doc::DocumentId>);
 
+/// Registry scope for one document's runs (`doc:<raw id>`). Session-local:
+/// document ids are minted per session, which is why the on-disk store keys
+/// runs by document path instead (see [`crate::experiment_store`]).
+pub fn twin_id_for_doc(doc: lunco_doc::DocumentId) -> lunco_experiments::TwinId {
+    lunco_experiments::TwinId(format!("doc:{}", doc.raw()))
+}
+
+/// Inverse of [`twin_id_for_doc`]; `None` for a scope no document minted.
+pub fn doc_for_twin(twin: &lunco_experiments::TwinId) -> Option<lunco_doc::DocumentId> {
+    twin.0
+        .strip_prefix("doc:")?
+        .parse()
+        .ok()
+        .map(lunco_doc::DocumentId::new)
+}
+
 /// Per-document playback entity: holds the latest completed run's
 /// time-series in `SignalRegistry` so canvas plot tiles can resolve
 /// `(entity, path)` lookups without a live cosim entity.
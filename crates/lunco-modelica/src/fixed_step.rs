@@ -5,13 +5,32 @@
 //! internal steps from error estimates and event boundaries. Prediction needs a
 //! stronger contract, so this module owns the integration loop explicitly.
 //!
-//! The backend is intentionally qualified. It accepts continuous, event-free,
-//! external-table-free models and evaluates them with classical RK4 at exactly
-//! four derivative stages per fixed step. Event/discrete models are rejected at
-//! construction because silently sampling them would be a semantic error, not a
-//! solver improvement.
+//! Every configured step ends on the same lattice point on every peer. Inside a
+//! step the loop may split at an event, but where it splits is a pure function
+//! of the step's start state, parameters and inputs — never of an error estimate:
+//!
+//! - **Zero crossings.** The model's root conditions are evaluated at both ends
+//!   of the step. A sign change is localized on the step's cubic Hermite
+//!   interpolant by exactly [`EVENT_BISECTIONS`] bisections — a fixed count, not
+//!   a tolerance — and integration restarts from the event instant.
+//! - **Time events.** Scheduled, dynamic and clock-driven event times are known
+//!   before the step is taken; the step is split exactly there.
+//! - **Discrete and `when` updates.** Applied by the runtime at each event
+//!   instant (`pre`, `reinit`, event iteration), so a clutch, a saturation with
+//!   hysteresis or a sample-and-hold reads the same discrete state on every peer.
+//! - **External tables.** Interpolated inside the derivative evaluation, as in
+//!   the adaptive session. Breakpoints are not events.
+//!
+//! A step holds at most [`MAX_EVENTS_PER_STEP`] events. A model that chatters
+//! past that fails the step instead of spending unbounded work in the frame loop.
+//!
+//! Between events, [`FixedStepMethod`] picks the formula: classical RK4 at
+//! exactly four derivative stages per (sub)step, or two-stage Radau IIA for
+//! stiff islands, whose Newton solve has a fixed iteration cap.
 
 use indexmap::IndexMap;
+use lunco_experiments::linear::{solve, JacobianMethod};
+use lunco_experiments::solver::SolverId;
 use rumoca_compile::compile::Dae;
 use rumoca_eval_solve::SolveRuntime;
 use rumoca_ir_solve::SolveModel;
@@ -20,9 +39,54 @@ use rumoca_sim::{SessionState, SimOptions, SimulationDiagnosticError};
 const ALGEBRAIC_TOL: f64 = 1.0e-10;
 const ALGEBRAIC_MAX_ITERS: usize = 256;
 
-/// A fixed-step RK4 session over a continuous, event-free Rumoca solve model.
+/// Bisections of a zero-crossing bracket: localizes the event to `dt / 2⁴⁰`
+/// with a work bound that does not depend on the model.
+pub const EVENT_BISECTIONS: usize = 40;
+/// Events one fixed step may hold before it is reported as chattering.
+pub const MAX_EVENTS_PER_STEP: usize = 16;
+
+const RADAU_NEWTON_MAX_ITERS: usize = 8;
+const RADAU_NEWTON_TOL: f64 = 1.0e-10;
+/// Two-stage Radau IIA: nodes and coefficient matrix. Stiffly accurate, so the
+/// last stage is the step's end point.
+const RADAU_C: [f64; 2] = [1.0 / 3.0, 1.0];
+const RADAU_A: [[f64; 2]; 2] = [[5.0 / 12.0, -1.0 / 12.0], [3.0 / 4.0, 1.0 / 4.0]];
+
+/// The formula a [`FixedStepSession`] integrates with between events.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FixedStepMethod {
+    /// Classical RK4, four derivative stages per step. The prediction default.
+    #[default]
+    Rk4,
+    /// Two-stage Radau IIA (order 3, L-stable), for stiff islands that RK4
+    /// would need a smaller `dt` to keep stable. Newton iterations on a
+    /// forward-difference Jacobian, capped at a fixed count per step.
+    Radau3,
+}
+
+impl FixedStepMethod {
+    /// The method a registered solver id names, if it names one of these.
+    pub fn for_solver(id: &SolverId) -> Option<Self> {
+        match id.as_str() {
+            "fixedrk4" => Some(Self::Rk4),
+            "fixedradau3" => Some(Self::Radau3),
+            _ => None,
+        }
+    }
+
+    /// The solver id this method is registered under, for diagnostics.
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Rk4 => "fixed-rk4",
+            Self::Radau3 => "fixed-radau3",
+        }
+    }
+}
+
+/// A fixed-step session over a Rumoca solve model, events included.
 pub struct FixedStepSession {
     runtime: SolveRuntime,
+    method: FixedStepMethod,
     state: Vec<f64>,
     initial_state: Vec<f64>,
     params: Vec<f64>,
@@ -32,15 +96,21 @@ pub struct FixedStepSession {
     step_index: u64,
     t_end: f64,
     fixed_dt: f64,
+    has_roots: bool,
+    has_time_events: bool,
 }
 
 impl FixedStepSession {
     /// Lower and prepare a DAE for the fixed-step backend.
-    pub fn new(dae: &Dae, options: SimOptions) -> Result<Self, SimulationDiagnosticError> {
+    pub fn new(
+        dae: &Dae,
+        method: FixedStepMethod,
+        options: SimOptions,
+    ) -> Result<Self, SimulationDiagnosticError> {
         let lower_started = web_time::Instant::now();
         let model = rumoca_sim::lower_for_simulation_with_overrides(dae, &options)?;
         let lower_elapsed = lower_started.elapsed();
-        Self::from_solve_model(&model, options, Some(lower_elapsed))
+        Self::from_solve_model(&model, method, options, Some(lower_elapsed))
     }
 
     /// Prepare a fixed-step session from an already-lowered solve model.
@@ -52,56 +122,66 @@ impl FixedStepSession {
     /// the executable solve representation into the session.
     pub fn from_solve_model(
         model: &SolveModel,
+        method: FixedStepMethod,
         options: SimOptions,
         lower_elapsed: Option<web_time::Duration>,
     ) -> Result<Self, SimulationDiagnosticError> {
+        let id = method.id();
         let fixed_dt = options.dt.ok_or_else(|| {
-            SimulationDiagnosticError::Solver(
-                "fixed-rk4 requires an explicit positive SimOptions::dt".into(),
-            )
+            SimulationDiagnosticError::Solver(format!(
+                "{id} requires an explicit positive SimOptions::dt"
+            ))
         })?;
         if !fixed_dt.is_finite() || fixed_dt <= 0.0 {
-            return Err(SimulationDiagnosticError::Solver(
-                "fixed-rk4 requires a finite positive SimOptions::dt".into(),
-            ));
+            return Err(SimulationDiagnosticError::Solver(format!(
+                "{id} requires a finite positive SimOptions::dt"
+            )));
         }
         if !options.t_start.is_finite() || !options.t_end.is_finite() {
-            return Err(SimulationDiagnosticError::Solver(
-                "fixed-rk4 requires finite simulation bounds".into(),
-            ));
+            return Err(SimulationDiagnosticError::Solver(format!(
+                "{id} requires finite simulation bounds"
+            )));
         }
         if options.t_end < options.t_start {
-            return Err(SimulationDiagnosticError::Solver(
-                "fixed-rk4 requires t_end >= t_start".into(),
-            ));
+            return Err(SimulationDiagnosticError::Solver(format!(
+                "{id} requires t_end >= t_start"
+            )));
         }
 
-        reject_unsupported_constructs(model)?;
         let runtime_started = web_time::Instant::now();
         let runtime = SolveRuntime::new(model)?;
         let runtime_elapsed = runtime_started.elapsed();
         let state_count = model.state_scalar_count();
         if model.initial_y.len() < state_count {
             return Err(SimulationDiagnosticError::Solver(format!(
-                "fixed-rk4 initial solver vector has {} values, but the model requires {} state values",
+                "{id} initial solver vector has {} values, but the model requires {} state values",
                 model.initial_y.len(),
                 state_count,
             )));
         }
+        let events = &model.problem.events;
+        let has_roots =
+            !events.root_conditions.is_empty() || !events.scheduled_root_conditions.is_empty();
+        let has_time_events = !events.scheduled_time_events.is_empty()
+            || !events.dynamic_time_event_names.is_empty()
+            || !model.problem.clocks.periodic_event_schedules.is_empty();
         log::info!(
-            "[fixed-rk4] prepared solve runtime: lower={} runtime={runtime_elapsed:?} \
-             states={} solver_slots={} algebraic_slots={}",
+            "[{id}] prepared solve runtime: lower={} runtime={runtime_elapsed:?} \
+             states={} solver_slots={} algebraic_slots={} roots={has_roots} \
+             time_events={has_time_events} tables={}",
             lower_elapsed
                 .map(|elapsed| format!("{elapsed:?}"))
                 .unwrap_or_else(|| "cached".to_string()),
             state_count,
             model.solver_scalar_count(),
             model.solver_scalar_count().saturating_sub(state_count),
+            model.external_tables.len(),
         );
         let initial_state = model.initial_y[..state_count].to_vec();
 
         Ok(Self {
             runtime,
+            method,
             state: initial_state.clone(),
             initial_state,
             params: model.parameters.clone(),
@@ -111,9 +191,15 @@ impl FixedStepSession {
             step_index: 0,
             t_end: options.t_end,
             fixed_dt,
+            has_roots,
+            has_time_events,
         })
     }
 
+    pub fn method(&self) -> FixedStepMethod {
+        self.method
+    }
+
     /// Set one runtime input. Input values are part of the solve parameter tail,
     /// exactly as in Rumoca's adaptive session.
     pub fn set_input(&mut self, name: &str, value: f64) -> Result<(), SimulationDiagnosticError> {
@@ -153,21 +239,22 @@ impl FixedStepSession {
         if dt <= 0.0 {
             return Ok(());
         }
+        let id = self.method.id();
         if dt.to_bits() != self.fixed_dt.to_bits() {
             return Err(SimulationDiagnosticError::Solver(format!(
-                "fixed-rk4 received dt={dt:?}, but its configured step is {:?}",
+                "{id} received dt={dt:?}, but its configured step is {:?}",
                 self.fixed_dt,
             )));
         }
         let next_time = self.time_origin + (self.step_index as f64 + 1.0) * self.fixed_dt;
         if next_time > self.t_end && next_time - self.t_end > self.fixed_dt * 1.0e-12 {
             return Err(SimulationDiagnosticError::Solver(format!(
-                "fixed-rk4 step at t={} would cross t_end={}",
+                "{id} step at t={} would cross t_end={}",
                 self.time(),
                 self.t_end,
             )));
         }
-        self.rk4_step()
+        self.advance_step()
     }
 
     pub fn advance_to(&mut self, target_time: f64) -> Result<(), SimulationDiagnosticError> {
@@ -180,8 +267,8 @@ impl FixedStepSession {
         let tolerance = self.fixed_dt * 1.0e-12;
         if !steps.is_finite() || (represented - (target_time - self.time())).abs() > tolerance {
             return Err(SimulationDiagnosticError::Solver(format!(
-                "fixed-rk4 cannot advance from {} to {}: target is not on the configured step lattice ({:?})",
-                self.time(), target_time, self.fixed_dt,
+                "{} cannot advance from {} to {}: target is not on the configured step lattice ({:?})",
+                self.method.id(), self.time(), target_time, self.fixed_dt,
             )));
         }
         for _ in 0..steps as usize {
@@ -193,7 +280,8 @@ impl FixedStepSession {
     pub fn reset(&mut self, t_start: f64) -> Result<(), SimulationDiagnosticError> {
         if !t_start.is_finite() || t_start > self.t_end {
             return Err(SimulationDiagnosticError::Solver(format!(
-                "fixed-rk4 reset time {t_start} is outside its finite simulation bounds"
+                "{} reset time {t_start} is outside its finite simulation bounds",
+                self.method.id(),
             )));
         }
         self.state.clone_from(&self.initial_state);
@@ -205,12 +293,7 @@ impl FixedStepSession {
     }
 
     pub fn time(&self) -> f64 {
-        let time = self.time_origin + self.step_index as f64 * self.fixed_dt;
-        if (time - self.t_end).abs() <= self.fixed_dt * 1.0e-12 {
-            self.t_end
-        } else {
-            time
-        }
+        self.lattice_time(self.step_index)
     }
 
     pub fn get(&self, name: &str) -> Result<Option<f64>, SimulationDiagnosticError> {
@@ -250,33 +333,176 @@ impl FixedStepSession {
         &self.runtime.model.visible_names
     }
 
-    fn rk4_step(&mut self) -> Result<(), SimulationDiagnosticError> {
-        let h = self.fixed_dt;
-        let t = self.time();
-        let y0 = self.state.clone();
-        let k1 = self.derivatives(t, &y0)?;
-        let y2 = stage_state(&y0, &k1, h * 0.5);
+    /// Time of lattice point `index`, snapped to `t_end` at the last one so
+    /// accumulated rounding cannot leave the final step a hair short.
+    fn lattice_time(&self, index: u64) -> f64 {
+        let time = self.time_origin + index as f64 * self.fixed_dt;
+        if (time - self.t_end).abs() <= self.fixed_dt * 1.0e-12 {
+            self.t_end
+        } else {
+            time
+        }
+    }
+
+    /// One lattice step: integrate sub-interval by sub-interval, stopping at
+    /// each time event and each localized zero crossing to apply its update.
+    fn advance_step(&mut self) -> Result<(), SimulationDiagnosticError> {
+        let id = self.method.id();
+        let step_end = self.lattice_time(self.step_index + 1);
+        let mut t = self.time();
+        let mut indicators = self.root_indicators(t, &self.state)?;
+        let mut events = 0usize;
+        while t < step_end {
+            let timed = self
+                .next_time_event(t)?
+                .filter(|&event| event > t && event <= step_end);
+            let t_next = timed.unwrap_or(step_end);
+            let y_next = self.substep(t, &self.state, t_next - t)?;
+            let next_indicators = self.root_indicators(t_next, &y_next)?;
+            if crossed(&indicators, &next_indicators) {
+                let (event_time, event_state) = self.localize(t, t_next, &y_next, &indicators)?;
+                t = event_time;
+                self.state = event_state;
+            } else {
+                t = t_next;
+                self.state = y_next;
+                if timed.is_none() {
+                    indicators = next_indicators;
+                    continue;
+                }
+            }
+            events += 1;
+            if events > MAX_EVENTS_PER_STEP {
+                return Err(SimulationDiagnosticError::Solver(format!(
+                    "{id} hit more than {MAX_EVENTS_PER_STEP} events in the step ending at \
+                     t={step_end}; the model chatters at t={t}",
+                )));
+            }
+            self.apply_event(t)?;
+            indicators = self.root_indicators(t, &self.state)?;
+        }
+        self.step_index = self.step_index.checked_add(1).ok_or_else(|| {
+            SimulationDiagnosticError::Solver(format!("{id} step index overflowed"))
+        })?;
+        Ok(())
+    }
+
+    /// Integrate `state` from `time` over `h` with the session's method.
+    fn substep(
+        &self,
+        time: f64,
+        state: &[f64],
+        h: f64,
+    ) -> Result<Vec<f64>, SimulationDiagnosticError> {
+        let next = match self.method {
+            FixedStepMethod::Rk4 => self.rk4(time, state, h)?,
+            FixedStepMethod::Radau3 => self.radau3(time, state, h)?,
+        };
+        if next.iter().any(|value| !value.is_finite()) {
+            return Err(SimulationDiagnosticError::Solver(format!(
+                "{} produced a non-finite state at t={time}",
+                self.method.id(),
+            )));
+        }
+        Ok(next)
+    }
+
+    fn rk4(&self, t: f64, y0: &[f64], h: f64) -> Result<Vec<f64>, SimulationDiagnosticError> {
+        let k1 = self.derivatives(t, y0)?;
+        let y2 = stage_state(y0, &k1, h * 0.5);
         let k2 = self.derivatives(t + h * 0.5, &y2)?;
-        let y3 = stage_state(&y0, &k2, h * 0.5);
+        let y3 = stage_state(y0, &k2, h * 0.5);
         let k3 = self.derivatives(t + h * 0.5, &y3)?;
-        let y4 = stage_state(&y0, &k3, h);
+        let y4 = stage_state(y0, &k3, h);
         let k4 = self.derivatives(t + h, &y4)?;
-        for (state, (((a, b), c), d)) in self
-            .state
-            .iter_mut()
+        Ok(y0
+            .iter()
             .zip(k1.iter().zip(&k2).zip(&k3).zip(&k4))
-        {
-            *state += (h / 6.0) * (*a + 2.0 * *b + 2.0 * *c + *d);
-            if !state.is_finite() {
-                return Err(SimulationDiagnosticError::Solver(
-                    "fixed-rk4 produced a non-finite state".into(),
-                ));
+            .map(|(y, (((a, b), c), d))| *y + (h / 6.0) * (*a + 2.0 * *b + 2.0 * *c + *d))
+            .collect())
+    }
+
+    /// Two-stage Radau IIA in stage-increment form: solve
+    /// `Z = h (A ⊗ I) F(y0 + Z)` by simplified Newton with the iteration matrix
+    /// `I - h (A ⊗ J)` frozen at `(t, y0)`.
+    fn radau3(&self, t: f64, y0: &[f64], h: f64) -> Result<Vec<f64>, SimulationDiagnosticError> {
+        let n = y0.len();
+        let f0 = self.derivatives(t, y0)?;
+        let jac = JacobianMethod::Forward.jacobian(y0, &f0, |y| self.derivatives(t, y))?;
+        let mut iteration = vec![vec![0.0; 2 * n]; 2 * n];
+        for (bi, a_row) in RADAU_A.iter().enumerate() {
+            for (bj, a) in a_row.iter().enumerate() {
+                for i in 0..n {
+                    for j in 0..n {
+                        let identity = if bi == bj && i == j { 1.0 } else { 0.0 };
+                        iteration[bi * n + i][bj * n + j] = identity - h * a * jac[i][j];
+                    }
+                }
             }
         }
-        self.step_index = self.step_index.checked_add(1).ok_or_else(|| {
-            SimulationDiagnosticError::Solver("fixed-rk4 step index overflowed".into())
-        })?;
-        Ok(())
+        let scale = 1.0 + y0.iter().fold(0.0_f64, |m, y| m.max(y.abs()));
+        let mut z = vec![0.0; 2 * n];
+        for _ in 0..RADAU_NEWTON_MAX_ITERS {
+            let mut stages = Vec::with_capacity(2);
+            for (k, c) in RADAU_C.iter().enumerate() {
+                let y = stage_state(y0, &z[k * n..(k + 1) * n], 1.0);
+                stages.push(self.derivatives(t + c * h, &y)?);
+            }
+            let residual = (0..2 * n)
+                .map(|row| {
+                    let (bi, i) = (row / n, row % n);
+                    let image = RADAU_A[bi][0] * stages[0][i] + RADAU_A[bi][1] * stages[1][i];
+                    h * image - z[row]
+                })
+                .collect();
+            let Some(delta) = solve(iteration.clone(), residual) else {
+                return Err(SimulationDiagnosticError::Solver(format!(
+                    "fixed-radau3 iteration matrix is singular at t={t}"
+                )));
+            };
+            let mut largest = 0.0_f64;
+            for (slot, d) in z.iter_mut().zip(&delta) {
+                *slot += d;
+                largest = largest.max(d.abs());
+            }
+            if largest <= RADAU_NEWTON_TOL * scale {
+                return Ok(stage_state(y0, &z[n..], 1.0));
+            }
+        }
+        Err(SimulationDiagnosticError::Solver(format!(
+            "fixed-radau3 Newton did not converge in {RADAU_NEWTON_MAX_ITERS} iterations at \
+             t={t}; the step is too large for this model"
+        )))
+    }
+
+    /// Find the first zero crossing in `(t, t_next]` by bisecting the step's
+    /// Hermite interpolant a fixed number of times. Returns the right end of
+    /// the final bracket — the first point known to be past the crossing.
+    fn localize(
+        &self,
+        t: f64,
+        t_next: f64,
+        y_next: &[f64],
+        before: &[f64],
+    ) -> Result<(f64, Vec<f64>), SimulationDiagnosticError> {
+        let h = t_next - t;
+        let y0 = &self.state;
+        let f0 = self.derivatives(t, y0)?;
+        let f1 = self.derivatives(t_next, y_next)?;
+        let (mut lo, mut hi) = (0.0_f64, 1.0_f64);
+        for _ in 0..EVENT_BISECTIONS {
+            let mid = 0.5 * (lo + hi);
+            let y = hermite(y0, &f0, y_next, &f1, h, mid);
+            if crossed(before, &self.root_indicators(t + mid * h, &y)?) {
+                hi = mid;
+            } else {
+                lo = mid;
+            }
+        }
+        if hi == 1.0 {
+            return Ok((t_next, y_next.to_vec()));
+        }
+        Ok((t + hi * h, hermite(y0, &f0, y_next, &f1, h, hi)))
     }
 
     fn derivatives(&self, time: f64, state: &[f64]) -> Result<Vec<f64>, SimulationDiagnosticError> {
@@ -291,6 +517,53 @@ impl FixedStepSession {
             .map_err(|error| SimulationDiagnosticError::Solver(error.to_string()))
     }
 
+    /// Zero-crossing indicators — root and scheduled-root conditions — in the
+    /// runtime's fixed order. Empty, and free, for a model without any.
+    fn root_indicators(
+        &self,
+        time: f64,
+        state: &[f64],
+    ) -> Result<Vec<f64>, SimulationDiagnosticError> {
+        if !self.has_roots {
+            return Ok(Vec::new());
+        }
+        self.runtime
+            .eval_root_conditions(
+                time,
+                state,
+                &self.params,
+                ALGEBRAIC_TOL,
+                ALGEBRAIC_MAX_ITERS,
+            )
+            .map_err(|error| SimulationDiagnosticError::Solver(error.to_string()))
+    }
+
+    /// The earliest scheduled, dynamic or clock event strictly after `time`.
+    fn next_time_event(&self, time: f64) -> Result<Option<f64>, SimulationDiagnosticError> {
+        if !self.has_time_events {
+            return Ok(None);
+        }
+        self.runtime
+            .next_time_event(time, &self.state, &self.params)
+            .map_err(|error| SimulationDiagnosticError::Solver(error.to_string()))
+    }
+
+    /// Run the event instant at `time`: discrete and `when` assignments, event
+    /// actions and `reinit`, iterated by the runtime until discrete values
+    /// settle. Discrete values live in the parameter vector, so [`Self::reset`]
+    /// restores them with the parameters.
+    fn apply_event(&mut self, time: f64) -> Result<(), SimulationDiagnosticError> {
+        self.runtime
+            .apply_event_update(
+                time,
+                &mut self.state,
+                &mut self.params,
+                ALGEBRAIC_TOL,
+                ALGEBRAIC_MAX_ITERS,
+            )
+            .map_err(|error| SimulationDiagnosticError::Solver(error.to_string()))
+    }
+
     fn visible_values(&self) -> Result<IndexMap<String, f64>, SimulationDiagnosticError> {
         let solver_y = self
             .runtime
@@ -327,54 +600,72 @@ fn stage_state(state: &[f64], derivative: &[f64], scale: f64) -> Vec<f64> {
         .collect()
 }
 
-fn reject_unsupported_constructs(
-    model: &rumoca_ir_solve::SolveModel,
-) -> Result<(), SimulationDiagnosticError> {
-    let problem = &model.problem;
-    let unsupported = [
-        (!problem.events.root_conditions.is_empty(), "root events"),
-        (
-            !problem.events.scheduled_root_conditions.is_empty(),
-            "scheduled root events",
-        ),
-        (
-            !problem.events.scheduled_time_events.is_empty(),
-            "scheduled time events",
-        ),
-        (
-            !problem.events.dynamic_time_event_names.is_empty()
-                || !problem.events.dynamic_time_event_rhs.is_empty(),
-            "dynamic time events",
-        ),
-        (
-            !problem.events.action_conditions.is_empty(),
-            "event actions",
-        ),
-        (!problem.events.actions.is_empty(), "event actions"),
-        (
-            !problem.discrete.runtime_assignment_rhs.is_empty()
-                || !problem.discrete.runtime_assignment_targets.is_empty()
-                || !problem.discrete.rhs.is_empty()
-                || !problem.discrete.update_targets.is_empty()
-                || !problem.discrete.pre_modes.is_empty()
-                || !problem.discrete.observation_refresh.is_empty(),
-            "discrete assignments",
-        ),
-        (
-            !problem.clocks.periodic_event_schedules.is_empty(),
-            "clock schedules",
-        ),
-        (!model.external_tables.is_empty(), "external tables"),
-    ];
-    let unsupported = unsupported
-        .into_iter()
-        .filter_map(|(present, name)| present.then_some(name))
-        .collect::<Vec<_>>();
-    if unsupported.is_empty() {
-        return Ok(());
+/// Whether any indicator changed side of zero between two evaluations.
+fn crossed(before: &[f64], after: &[f64]) -> bool {
+    before
+        .iter()
+        .zip(after)
+        .any(|(before, after)| (*before > 0.0) != (*after > 0.0))
+}
+
+/// Cubic Hermite interpolant of one step at fraction `theta`, from the end
+/// states and end derivatives — as accurate as the RK4 step it interpolates.
+fn hermite(y0: &[f64], f0: &[f64], y1: &[f64], f1: &[f64], h: f64, theta: f64) -> Vec<f64> {
+    let t2 = theta * theta;
+    let t3 = t2 * theta;
+    let h00 = 2.0 * t3 - 3.0 * t2 + 1.0;
+    let h10 = t3 - 2.0 * t2 + theta;
+    let h01 = -2.0 * t3 + 3.0 * t2;
+    let h11 = t3 - t2;
+    y0.iter()
+        .zip(f0)
+        .zip(y1.iter().zip(f1))
+        .map(|((y0, f0), (y1, f1))| h00 * y0 + h10 * h * f0 + h01 * y1 + h11 * h * f1)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The interpolant reproduces a cubic exactly, so a bisection on it lands
+    /// where the continuous trajectory crosses, not where a chord would.
+    #[test]
+    fn hermite_reproduces_a_cubic() {
+        let y = |t: f64| t * t * t - 2.0 * t;
+        let dy = |t: f64| 3.0 * t * t - 2.0;
+        let (t0, h) = (0.5, 0.25);
+        for theta in [0.0, 0.3, 0.5, 0.9, 1.0] {
+            let got = hermite(&[y(t0)], &[dy(t0)], &[y(t0 + h)], &[dy(t0 + h)], h, theta)[0];
+            assert!((got - y(t0 + theta * h)).abs() < 1e-14, "theta={theta}");
+        }
+    }
+
+    #[test]
+    fn a_crossing_is_a_change_of_side_not_a_change_of_value() {
+        assert!(!crossed(&[1.0, -1.0], &[0.5, -3.0]));
+        assert!(crossed(&[1.0, -1.0], &[1.0, 0.5]));
+        // Touching zero from above counts; staying at or below it does not.
+        assert!(crossed(&[1.0], &[0.0]));
+        assert!(!crossed(&[0.0], &[-1.0]));
+    }
+
+    #[test]
+    fn solver_ids_name_their_methods() {
+        assert_eq!(
+            FixedStepMethod::for_solver(&SolverId::from("fixed-rk4")),
+            Some(FixedStepMethod::Rk4)
+        );
+        assert_eq!(
+            FixedStepMethod::for_solver(&SolverId::from("fixed-radau3")),
+            Some(FixedStepMethod::Radau3)
+        );
+        assert_eq!(FixedStepMethod::for_solver(&SolverId::from("rk45")), None);
+        for method in [FixedStepMethod::Rk4, FixedStepMethod::Radau3] {
+            assert_eq!(
+                FixedStepMethod::for_solver(&SolverId::from(method.id())),
+                Some(method)
+            );
+        }
     }
-    Err(SimulationDiagnosticError::Solver(format!(
-        "fixed-rk4 only supports continuous event-free models; unsupported constructs: {}",
-        unsupported.join(", "),
-    )))
 }
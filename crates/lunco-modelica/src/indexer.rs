@@ -378,7 +378,7 @@ static WS_RE: std::sync::LazyLock<regex::Regex> =
 /// Unescapes Modelica string escapes, strips HTML tags and common
 /// entities, collapses whitespace, and keeps only the first
 /// paragraph (so a multi-screen MSL doc fits in a card tagline).
-fn clean_info_text(raw: &str) -> String {
+pub(crate) fn clean_info_text(raw: &str) -> String {
     // Modelica string escapes we actually see in MSL.
     let mut s = String::with_capacity(raw.len());
     let mut chars = raw.chars();
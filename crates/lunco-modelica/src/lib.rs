@@ -161,6 +161,8 @@ pub mod fixed_step;
 /// selection itself lives there; this module is only where rumoca's two-axis
 /// option shape is expressed, once.
 pub mod lint;
+/// Unit inference over equations — the `unit_mismatches` lint fact.
+pub mod unit_check;
 /// `ModelTabs` registry (was `ui::panels::model_view::tabs`).
 pub mod model_tabs;
 /// Modelica tab registry data types (was `ui::panels::model_view::types`).
@@ -203,6 +205,29 @@ pub mod engine_resource;
 /// experiment setups sync + persist. Run results ride the content plane; run
 /// status rides presence.
 pub mod experiment_journal;
+/// Persistent experiment store — finished runs filed under
+/// `<twin>/experiments/`, restored when their document reopens, trajectories
+/// loaded lazily. Format in `lunco_experiments::store`.
+pub mod experiment_store;
+/// Result files in and out — CSV / MAT v4 / MAT v7.3 / HDF5 export, and
+/// import of a Modelica-tool result as a read-only reference run.
+pub mod result_files;
+/// Regression runner — simulate a suite's cases and check them against
+/// reference results with tolerance tubes; JUnit XML + HTML reports.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod regression;
+/// Linearization — state-space `(A, B, C, D)` around a simulated or
+/// steady-state operating point, by finite-difference Jacobians.
+pub mod linearize;
+/// Parameter estimation — fit parameters to measured trajectories by
+/// repeated runs of one compiled DAE; fit report with confidence intervals.
+#[cfg(not(target_arch = "wasm32"))]
+pub mod estimation;
+/// Modelica language server (`lunica --lsp`) — diagnostics, completion,
+/// hover, go-to-definition, symbols, rename and formatting over stdio,
+/// answered by a [`engine::ModelicaEngine`].
+#[cfg(not(target_arch = "wasm32"))]
+pub mod lsp;
 /// Modelica adapter to the canonical Twin journal in
 /// `lunco-twin-journal`. Records each applied [`crate::document::ModelicaOp`] as a
 /// summary entry alongside its inverse. See module docs for the
@@ -1772,6 +1797,7 @@ fn build_modelica_core(app: &mut App) {
     app.init_resource::<experiments_runner::ExperimentDrafts>();
     app.init_resource::<experiments_runner::ExperimentSources>();
     app.init_resource::<experiments_runner::PlaybackEntities>();
+    app.init_resource::<experiment_store::SeriesDemand>();
     // Persisted concurrency cap for parallel Fast Runs (settings.json
     // `experiments.max_parallel`; None = platform auto). The apply system
     // pushes it into the runner on startup and on later edits.
@@ -1784,6 +1810,23 @@ fn build_modelica_core(app: &mut App) {
             experiments_runner::drain_pending_handles,
         ),
     );
+    // Experiment store: file finished runs into the twin folder, restore a
+    // document's runs when it (or its twin) opens, and load trajectories on
+    // demand. Guarded so a host without a workspace simply skips.
+    app.add_observer(experiment_store::on_document_opened_restore_runs)
+        .add_observer(experiment_store::on_twin_added_restore_runs);
+    app.add_systems(
+        Update,
+        (
+            experiment_store::persist_finished_runs
+                .after(experiments_runner::drain_pending_handles),
+            experiment_store::load_demanded_series.run_if(
+                resource_changed::<experiment_store::SeriesDemand>
+                    .or_else(resource_changed::<lunco_experiments::ExperimentRegistry>),
+            ),
+        )
+            .run_if(resource_exists::<lunco_workspace::WorkspaceResource>),
+    );
 
     // Keep the winit event loop pumping at full speed while any sim
     // is active, even when the window loses focus. Otherwise the
@@ -0,0 +1,401 @@
+//! Linearization — a compiled model's state-space form `(A, B, C, D)` around
+//! an operating point.
+//!
+//! The model is lowered to rumoca's solve IR exactly as a simulation would
+//! lower it, and the Jacobians are taken on the same [`SolveRuntime`] the
+//! fixed-step integrator evaluates: `A = ∂ẋ/∂x` and `B = ∂ẋ/∂u` from the
+//! state derivatives, `C = ∂y/∂x` and `D = ∂y/∂u` from the visible values.
+//! Inputs are the solve layout's input slots, so the `u` of the linear model
+//! is the `input` a cosim wire or `SetModelInput` drives. The runtime exposes
+//! evaluation only, so the Jacobians are finite differences
+//! ([`JacobianMethod`]); Richardson extrapolation gets within round-off of an
+//! analytic Jacobian for smooth models.
+//!
+//! The operating point is either the state reached by simulating to a time,
+//! or a steady state found by a damped Newton (Levenberg–Marquardt) search
+//! on `ẋ = 0` with the inputs held — which also copes with the singular
+//! Jacobian of a free integrator (a rover's position), settling on the
+//! nearest equilibrium rather than refusing.
+//!
+//! The state-space algebra itself (poles, zeros, frequency response, MAT
+//! export) is backend-agnostic and lives in `lunco_experiments::linear`.
+//! Front end: the `LinearizeModel` command.
+
+use std::collections::HashSet;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::Path;
+
+use lunco_experiments::linear::{solve, JacobianMethod, OperatingPoint, StateSpace};
+use rumoca_compile::compile::Dae;
+use rumoca_eval_solve::SolveRuntime;
+use rumoca_sim::SimOptions;
+
+const ALGEBRAIC_TOL: f64 = 1.0e-10;
+const ALGEBRAIC_MAX_ITERS: usize = 256;
+
+/// Steady state is accepted at `max |ẋ| ≤ STEADY_TOL · max(1, max |x|)`.
+const STEADY_TOL: f64 = 1.0e-9;
+const STEADY_MAX_ITERS: usize = 200;
+
+/// What to linearize and where.
+#[derive(Clone, Debug, Default)]
+pub struct LinearizeSpec {
+    /// Start of the model's time axis (the `experiment` `StartTime`).
+    pub t_start: f64,
+    /// Operating time. Without `steady_state` the model is simulated from
+    /// `t_start` to here; with it, this is only the `t` the equations see.
+    pub time: f64,
+    /// Search for `ẋ = 0` instead of taking the simulated state.
+    pub steady_state: bool,
+    /// Input names; empty → every top-level input.
+    pub inputs: Vec<String>,
+    /// Output names (any visible variable); empty → the top-level outputs,
+    /// or the states when the model declares none.
+    pub outputs: Vec<String>,
+    /// Input values held at the operating point (`name → value`); inputs
+    /// not listed keep their declared default.
+    pub input_values: Vec<(String, f64)>,
+    pub jacobian: JacobianMethod,
+}
+
+/// Linearize `dae` (named `model` in the result). Parameter overrides are
+/// applied to the DAE beforehand, as for a run.
+pub fn linearize(dae: &Dae, model: &str, spec: &LinearizeSpec) -> Result<StateSpace, String> {
+    let options = SimOptions {
+        t_start: spec.t_start,
+        t_end: spec.time.max(spec.t_start),
+        ..Default::default()
+    };
+    let solve_model = rumoca_sim::lower_for_simulation_with_overrides(dae, &options)
+        .map_err(|e| format!("lowering failed: {e}"))?;
+    let runtime =
+        SolveRuntime::new(&solve_model).map_err(|e| format!("solve runtime failed: {e}"))?;
+    let n = solve_model.state_scalar_count();
+    if solve_model.initial_y.len() < n {
+        return Err(format!(
+            "initial solver vector has {} values for {n} states",
+            solve_model.initial_y.len()
+        ));
+    }
+    let probe = Probe {
+        runtime: &runtime,
+        time: spec.time,
+    };
+    let mut params = solve_model.parameters.clone();
+    let mut x = solve_model.initial_y[..n].to_vec();
+
+    // ── Names ──
+    let layout = &runtime.model.problem.solve_layout;
+    for (name, value) in &spec.input_values {
+        let slot = layout.input_parameter_index(name).ok_or_else(|| {
+            format!(
+                "`{name}` is not an input (inputs: {})",
+                layout.input_scalar_names().join(", ")
+            )
+        })?;
+        params[slot] = *value;
+    }
+    let inputs: Vec<String> = if spec.inputs.is_empty() {
+        layout.input_scalar_names().to_vec()
+    } else {
+        spec.inputs.clone()
+    };
+    let input_slots = inputs
+        .iter()
+        .map(|name| {
+            layout.input_parameter_index(name).ok_or_else(|| {
+                format!(
+                    "`{name}` is not an input (inputs: {})",
+                    layout.input_scalar_names().join(", ")
+                )
+            })
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+    let states = state_names(dae, &probe, &x, &params)?;
+    let visible = &runtime.model.visible_names;
+    let outputs: Vec<String> = if !spec.outputs.is_empty() {
+        spec.outputs.clone()
+    } else {
+        let declared: Vec<String> = expand(
+            dae.variables
+                .outputs
+                .iter()
+                .map(|(name, v)| (name.to_string(), v.size())),
+        )
+        .into_iter()
+        .filter(|name| visible.contains(name))
+        .collect();
+        if declared.is_empty() {
+            states.clone()
+        } else {
+            declared
+        }
+    };
+    let output_slots = outputs
+        .iter()
+        .map(|name| {
+            visible
+                .iter()
+                .position(|v| v == name)
+                .ok_or_else(|| format!("`{name}` is not a variable of {model}"))
+        })
+        .collect::<Result<Vec<usize>, String>>()?;
+
+    // ── Operating point ──
+    if spec.time > spec.t_start && n > 0 {
+        x = simulated_state(
+            dae,
+            options,
+            spec.time - spec.t_start,
+            &spec.input_values,
+            &states,
+        )?;
+    }
+    if spec.steady_state && n > 0 {
+        x = steady_state(&probe, x, &params, spec.jacobian)?;
+    }
+    let u0: Vec<f64> = input_slots.iter().map(|&k| params[k]).collect();
+
+    // ── Jacobians ──
+    let method = spec.jacobian;
+    let f0 = probe.derivatives(&x, &params)?;
+    let y0 = probe.outputs(&x, &params, &output_slots)?;
+    let a = method.jacobian(&x, &f0, |x| probe.derivatives(x, &params))?;
+    let c = method.jacobian(&x, &y0, |x| probe.outputs(x, &params, &output_slots))?;
+    let with_inputs = |u: &[f64]| {
+        let mut p = params.clone();
+        for (&k, v) in input_slots.iter().zip(u) {
+            p[k] = *v;
+        }
+        p
+    };
+    // Each Jacobian column is the derivative along one input, so `B` comes
+    // out `n × m` as is.
+    let b = method.jacobian(&u0, &f0, |u| probe.derivatives(&x, &with_inputs(u)))?;
+    let d = method.jacobian(&u0, &y0, |u| {
+        probe.outputs(&x, &with_inputs(u), &output_slots)
+    })?;
+
+    let system = StateSpace {
+        model: model.to_string(),
+        states,
+        inputs,
+        outputs,
+        a,
+        b,
+        c,
+        d,
+        operating_point: OperatingPoint {
+            time: spec.time,
+            steady_state: spec.steady_state,
+            residual: max_abs(&f0),
+            states: x,
+            inputs: u0,
+            outputs: y0,
+        },
+        jacobian: method,
+    };
+    system.validate()?;
+    Ok(system)
+}
+
+/// Write `system` as JSON (`.json`) or MAT v4 (`.mat`), by extension.
+#[cfg(not(target_arch = "wasm32"))]
+pub fn write_state_space(path: &Path, system: &StateSpace) -> Result<(), String> {
+    let ext = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default();
+    let bytes = match ext.as_str() {
+        "json" => serde_json::to_vec_pretty(system).map_err(|e| e.to_string())?,
+        "mat" => system.encode_mat4(),
+        other => {
+            return Err(format!(
+                "unknown linear-model format `.{other}` (json or mat)"
+            ))
+        }
+    };
+    std::fs::write(path, bytes).map_err(|e| format!("failed to write {}: {e}", path.display()))
+}
+
+/// Evaluation of one lowered model at a fixed time.
+struct Probe<'a> {
+    runtime: &'a SolveRuntime,
+    time: f64,
+}
+
+impl Probe<'_> {
+    fn derivatives(&self, x: &[f64], params: &[f64]) -> Result<Vec<f64>, String> {
+        self.runtime
+            .eval_state_derivatives(self.time, x, params, ALGEBRAIC_TOL, ALGEBRAIC_MAX_ITERS)
+            .map_err(|e| e.to_string())
+    }
+
+    fn visible(&self, x: &[f64], params: &[f64]) -> Result<Vec<f64>, String> {
+        let solver_y = self
+            .runtime
+            .full_solver_y(self.time, x, params, ALGEBRAIC_TOL, ALGEBRAIC_MAX_ITERS)
+            .map_err(|e| e.to_string())?;
+        self.runtime
+            .visible_values(&solver_y, params, self.time)
+            .map_err(|e| e.to_string())
+    }
+
+    fn outputs(&self, x: &[f64], params: &[f64], slots: &[usize]) -> Result<Vec<f64>, String> {
+        let all = self.visible(x, params)?;
+        Ok(slots.iter().map(|&k| all[k]).collect())
+    }
+}
+
+/// Scalar names for `(name, size)` pairs: arrays expand to `name[1]`, ….
+fn expand(vars: impl Iterator<Item = (String, usize)>) -> Vec<String> {
+    let mut out = Vec::new();
+    for (name, size) in vars {
+        if size <= 1 {
+            out.push(name);
+        } else {
+            out.extend((1..=size).map(|k| format!("{name}[{k}]")));
+        }
+    }
+    out
+}
+
+/// Name each slot of the solver state vector. The solve layout does not
+/// name its state slots, so each is identified by nudging it and seeing
+/// which visible variable moves by exactly the nudge — preferring the DAE's
+/// own state at that position, so an alias (`y = x`) never steals the name.
+/// A slot nothing tracks is named `x[k]`.
+fn state_names(dae: &Dae, probe: &Probe, x: &[f64], params: &[f64]) -> Result<Vec<String>, String> {
+    let expected = expand(
+        dae.variables
+            .states
+            .iter()
+            .map(|(name, v)| (name.to_string(), v.size())),
+    );
+    let names = &probe.runtime.model.visible_names;
+    let base = probe.visible(x, params)?;
+    let mut taken = HashSet::new();
+    let mut out = Vec::with_capacity(x.len());
+    for i in 0..x.len() {
+        let nudge = 1e-3 * x[i].abs().max(1.0);
+        let mut moved = x.to_vec();
+        moved[i] += nudge;
+        let after = probe.visible(&moved, params)?;
+        let tracks = |k: usize| {
+            (base[k] - x[i]).abs() <= 1e-9 * x[i].abs().max(1.0)
+                && (after[k] - base[k] - nudge).abs() <= 1e-9 * nudge.max(1.0)
+        };
+        let preferred = expected
+            .get(i)
+            .and_then(|want| names.iter().position(|n| n == want))
+            .filter(|&k| tracks(k));
+        let found = preferred
+            .or_else(|| (0..names.len()).find(|&k| !taken.contains(&names[k]) && tracks(k)));
+        let name = found.map_or_else(|| format!("x[{}]", i + 1), |k| names[k].clone());
+        taken.insert(name.clone());
+        out.push(name);
+    }
+    Ok(out)
+}
+
+/// Simulate from the start for `span` seconds with `inputs` held and read
+/// the state back by name.
+fn simulated_state(
+    dae: &Dae,
+    options: SimOptions,
+    span: f64,
+    inputs: &[(String, f64)],
+    states: &[String],
+) -> Result<Vec<f64>, String> {
+    let mut session =
+        crate::simulation_session::cli(dae, options).map_err(|e| format!("simulation: {e}"))?;
+    for (name, value) in inputs {
+        session
+            .set_input(name, *value)
+            .map_err(|e| format!("input `{name}`: {e}"))?;
+    }
+    session
+        .step(span)
+        .map_err(|e| format!("simulation to the operating point failed: {e}"))?;
+    let reached = session
+        .state()
+        .map_err(|e| format!("simulation state unreadable: {e}"))?;
+    states
+        .iter()
+        .map(|name| {
+            reached.values.get(name).copied().ok_or_else(|| {
+                format!(
+                    "state `{name}` is not observable after simulating; \
+                     linearize at steady state or at the start time instead"
+                )
+            })
+        })
+        .collect()
+}
+
+fn max_abs(v: &[f64]) -> f64 {
+    v.iter().fold(0.0, |m, e| m.max(e.abs()))
+}
+
+/// Levenberg–Marquardt on `ẋ(x) = 0`: each step solves
+/// `(JᵀJ + λ·diag(JᵀJ)) δ = −Jᵀf`, shrinking `λ` after an improving step and
+/// growing it after a rejected one.
+fn steady_state(
+    probe: &Probe,
+    mut x: Vec<f64>,
+    params: &[f64],
+    method: JacobianMethod,
+) -> Result<Vec<f64>, String> {
+    let mut f = probe.derivatives(&x, params)?;
+    let mut lambda: f64 = 1e-3;
+    for _ in 0..STEADY_MAX_ITERS {
+        if max_abs(&f) <= STEADY_TOL * max_abs(&x).max(1.0) {
+            return Ok(x);
+        }
+        let jac = method.jacobian(&x, &f, |x| probe.derivatives(x, params))?;
+        let n = x.len();
+        let jtj: Vec<Vec<f64>> = (0..n)
+            .map(|i| {
+                (0..n)
+                    .map(|j| jac.iter().map(|row| row[i] * row[j]).sum())
+                    .collect()
+            })
+            .collect();
+        let jtf: Vec<f64> = (0..n)
+            .map(|i| -jac.iter().zip(&f).map(|(row, fk)| row[i] * fk).sum::<f64>())
+            .collect();
+        let mut improved = false;
+        while lambda < 1e12 {
+            let mut damped = jtj.clone();
+            for (i, row) in damped.iter_mut().enumerate() {
+                row[i] += lambda * jtj[i][i].max(1e-12);
+            }
+            let Some(step) = solve(damped, jtf.clone()) else {
+                lambda *= 10.0;
+                continue;
+            };
+            let trial: Vec<f64> = x.iter().zip(&step).map(|(a, b)| a + b).collect();
+            match probe.derivatives(&trial, params) {
+                Ok(ft) if max_abs(&ft) < max_abs(&f) => {
+                    x = trial;
+                    f = ft;
+                    lambda = (lambda / 10.0).max(1e-12);
+                    improved = true;
+                    break;
+                }
+                _ => lambda *= 10.0,
+            }
+        }
+        if !improved {
+            break;
+        }
+    }
+    if max_abs(&f) <= STEADY_TOL * max_abs(&x).max(1.0) {
+        return Ok(x);
+    }
+    Err(format!(
+        "no steady state found: max |ẋ| stalls at {:.3e} — the model may have no \
+         equilibrium for these inputs",
+        max_abs(&f)
+    ))
+}
@@ -42,12 +42,15 @@ pub const MODELICA_LINT_DOMAIN: &str = "modelica";
 /// conditional_equations: [ #{ name: "m_dot", kind: "algebraic",
 ///                            form: "if-expression", line: 42 }, … ]
 /// conditional_algebraic_names: [ "m_dot", … ]   // the silent-zero set
+/// unit_mismatches: [ #{ class: "Battery", line: 12, subject: "max(...)",
+///                      lhs: "1", rhs: "1/s", kind: "dimension" }, … ]
 /// ```
 ///
 /// `shadowed` and `conditional_algebraic_names` are computed here rather than
 /// left to the policy because set intersection and nested filtering in rhai run
 /// into the expression-complexity cap that makes a whole policy fail to
-/// compile — the same trap the drivetrain rules hit.
+/// compile — the same trap the drivetrain rules hit. `unit_mismatches` is the
+/// output of [`crate::unit_check`]: inference a script could not do at all.
 ///
 /// `ast` is the best-effort parse the caller already holds (the recovering
 /// parser's `best_effort()`); an empty `StoredDefinition` is legitimate input and
@@ -90,6 +93,20 @@ pub fn modelica_facts(
         .map(ConditionalEquation::to_fact)
         .collect();
 
+    let unit_entries: Vec<H> = crate::unit_check::unit_mismatches(ast)
+        .iter()
+        .map(|m| {
+            H::map([
+                ("class", H::Str(m.class.clone())),
+                ("line", H::Int(m.line)),
+                ("subject", H::Str(m.subject.clone())),
+                ("lhs", H::Str(m.lhs.clone())),
+                ("rhs", H::Str(m.rhs.clone())),
+                ("kind", H::str(m.kind.as_str())),
+            ])
+        })
+        .collect();
+
     H::map([
         ("model", H::Str(model.to_string())),
         ("params", H::Array(param_entries)),
@@ -105,6 +122,7 @@ pub fn modelica_facts(
         ("shadowed", H::Array(shadowed)),
         ("conditional_equations", H::Array(conditional_entries)),
         ("conditional_algebraic_names", H::Array(silent_zero)),
+        ("unit_mismatches", H::Array(unit_entries)),
     ])
 }
 
@@ -500,5 +518,25 @@ mod tests {
             key(&facts, "conditional_algebraic_names"),
             &H::Array(Vec::new())
         );
+        assert_eq!(key(&facts, "unit_mismatches"), &H::Array(Vec::new()));
+    }
+
+    /// A unit clash reaches the facts with everything a message needs — which
+    /// equation, what was compared, and the two units as the author wrote them.
+    #[test]
+    fn a_unit_clash_is_a_fact_with_both_units() {
+        let facts = facts_of_source(
+            "model M\n  Real n(unit=\"rpm\");\n  Real w(unit=\"rad/s\");\nequation\n  w = n;\n  n = 60;\nend M;\n",
+        );
+        let H::Array(entries) = key(&facts, "unit_mismatches") else {
+            panic!("unit_mismatches is an array")
+        };
+        assert_eq!(entries.len(), 1, "{entries:?}");
+        let m = &entries[0];
+        assert_eq!(key(m, "subject"), &H::Str("w".to_string()));
+        assert_eq!(key(m, "lhs"), &H::Str("rad/s".to_string()));
+        assert_eq!(key(m, "rhs"), &H::Str("rpm".to_string()));
+        assert_eq!(key(m, "kind"), &H::Str("scale".to_string()));
+        assert_eq!(key(m, "line"), &H::Int(5));
     }
 }
@@ -0,0 +1,893 @@
+//! The language features: diagnostics, completion, hover, definition,
+//! document symbols, rename and formatting.
+//!
+//! Names resolve the way the rest of the crate resolves them (MLS §5.3
+//! subset): a component of the enclosing class, then a member inherited
+//! through `extends`, then a class — through the enclosing classes'
+//! imports and scope chain ([`build_extends_candidates`]), checked against
+//! the engine with [`ModelicaEngine::class_def`].
+
+use std::path::PathBuf;
+
+use rumoca_compile::parsing::ast::{ClassDef, Import};
+use serde_json::{json, Value};
+
+use super::text::{self, LineIndex, Occurrence};
+use super::{OpenDoc, ResponseError, Server, INVALID_PARAMS, REQUEST_FAILED};
+use crate::annotations::parsing::build_extends_candidates;
+use crate::ast_extract::{class_full_text_span, class_kind_label, within_package};
+use crate::engine::{InheritedCausality, InheritedMember, InheritedVariability, ModelicaEngine};
+use crate::index::{Causality, ComponentEntry, Variability};
+
+/// Predefined types (MLS §4.9) and builtin classes — never looked up.
+const BUILTIN_TYPES: &[&str] = &[
+    "Real",
+    "Integer",
+    "Boolean",
+    "String",
+    "StateSelect",
+    "AssertionLevel",
+    "ExternalObject",
+    "Clock",
+];
+
+/// Builtin functions and operators offered by completion (MLS §3.7).
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    "abs",
+    "sign",
+    "sqrt",
+    "div",
+    "mod",
+    "rem",
+    "ceil",
+    "floor",
+    "integer",
+    "min",
+    "max",
+    "sum",
+    "product",
+    "sin",
+    "cos",
+    "tan",
+    "asin",
+    "acos",
+    "atan",
+    "atan2",
+    "sinh",
+    "cosh",
+    "tanh",
+    "exp",
+    "log",
+    "log10",
+    "der",
+    "pre",
+    "edge",
+    "change",
+    "reinit",
+    "initial",
+    "terminal",
+    "noEvent",
+    "smooth",
+    "sample",
+    "delay",
+    "homotopy",
+    "assert",
+    "terminate",
+    "size",
+    "ndims",
+    "zeros",
+    "ones",
+    "fill",
+    "linspace",
+    "cat",
+    "transpose",
+    "cross",
+    "identity",
+    "diagonal",
+    "connect",
+];
+
+/// Longest documentation excerpt a hover shows.
+const HOVER_DOC_CHARS: usize = 600;
+
+// LSP enum values used below.
+const SEVERITY_ERROR: u8 = 1;
+const SEVERITY_WARNING: u8 = 2;
+const SEVERITY_INFO: u8 = 3;
+const SEVERITY_HINT: u8 = 4;
+
+/// Where a name is being resolved: the classes enclosing an offset,
+/// outermost first, keyed as in the document's index (no `within`).
+struct Scope<'a> {
+    chain: Vec<(String, &'a ClassDef)>,
+    within: Option<String>,
+}
+
+impl<'a> Scope<'a> {
+    fn at(doc: &'a OpenDoc, offset: usize) -> Self {
+        let mut chain = Vec::new();
+        let mut classes: Vec<(String, &ClassDef)> = doc
+            .ast
+            .classes
+            .iter()
+            .map(|(k, c)| (k.clone(), c))
+            .collect();
+        while let Some((key, class)) = classes.into_iter().find(|(_, class)| {
+            let (start, end) = class_full_text_span(class, &doc.text);
+            (start..=end).contains(&offset)
+        }) {
+            classes = class
+                .iter_classes()
+                .map(|(name, nested)| (format!("{key}.{name}"), nested))
+                .collect();
+            chain.push((key, class));
+        }
+        Self {
+            chain,
+            within: within_package(&doc.ast),
+        }
+    }
+
+    /// The innermost class's index key.
+    fn key(&self) -> Option<&str> {
+        self.chain.last().map(|(key, _)| key.as_str())
+    }
+
+    /// Engine-qualified name of an index key.
+    fn qualify(&self, key: &str) -> String {
+        match &self.within {
+            Some(within) => format!("{within}.{key}"),
+            None => key.to_string(),
+        }
+    }
+
+    /// The innermost class's engine-qualified name.
+    fn qualified(&self) -> Option<String> {
+        self.key().map(|key| self.qualify(key))
+    }
+
+    /// Imports of every enclosing class — imports are lexically scoped.
+    fn imports(&self) -> Vec<Import> {
+        self.chain
+            .iter()
+            .flat_map(|(_, class)| class.imports.iter().cloned())
+            .collect()
+    }
+
+    /// Whether `name` is a class nested in one of the enclosing classes —
+    /// typically a `replaceable package Medium`, whose members only exist
+    /// after redeclaration.
+    fn declares_class(&self, name: &str) -> bool {
+        self.chain
+            .iter()
+            .any(|(_, class)| class.classes.contains_key(name))
+    }
+
+    /// Resolve the class reference `name` written in this scope.
+    fn resolve_class(&self, engine: &mut ModelicaEngine, name: &str) -> Option<(String, ClassDef)> {
+        let candidates = match self.qualified() {
+            Some(ctx) => build_extends_candidates(&ctx, name, &self.imports()),
+            None => vec![name.to_string()],
+        };
+        // The bare name comes first in the candidate list but is the LAST
+        // resort here: rumoca suffix-matches a bare lookup.
+        let (bare, scoped) = candidates.split_first()?;
+        scoped
+            .iter()
+            .chain(std::iter::once(bare))
+            .find_map(|q| engine.class_def(q).map(|class| (q.clone(), class)))
+    }
+}
+
+/// What a name in a document refers to.
+enum Symbol {
+    /// A component declared in the document, by index key of its class.
+    Component {
+        class: String,
+        entry: ComponentEntry,
+    },
+    /// A member of class `owner` — inherited through `extends`, or reached
+    /// through a component of that type (`body.frame_a`).
+    Member {
+        owner: String,
+        member: InheritedMember,
+    },
+    Class {
+        qualified: String,
+        class: ClassDef,
+    },
+}
+
+/// Resolve the dotted name under `offset`, cut at the cursor's segment.
+/// Returns the symbol and the byte span of that segment.
+fn symbol_at(
+    engine: &mut ModelicaEngine,
+    doc: &OpenDoc,
+    offset: usize,
+) -> Option<(Symbol, usize, usize)> {
+    let (name, seg_start, seg_end) = text::name_at(&doc.text, offset)?;
+    let scope = Scope::at(doc, offset);
+    let symbol = resolve_name(engine, doc, &scope, &name)?;
+    Some((symbol, seg_start, seg_end))
+}
+
+fn resolve_name(
+    engine: &mut ModelicaEngine,
+    doc: &OpenDoc,
+    scope: &Scope,
+    name: &str,
+) -> Option<Symbol> {
+    let mut segments = name.split('.');
+    let head = segments.next()?;
+    let local = scope
+        .key()
+        .and_then(|key| doc.index.find_component(key, head))
+        .map(|entry| Symbol::Component {
+            class: entry.class.clone(),
+            entry: entry.clone(),
+        });
+    let mut symbol = match local {
+        Some(symbol) => symbol,
+        None => {
+            let inherited = scope.qualified().and_then(|owner| {
+                let member = engine
+                    .inherited_members_typed(&owner)
+                    .into_iter()
+                    .find(|m| m.name == head)?;
+                Some(Symbol::Member { owner, member })
+            });
+            match inherited {
+                Some(symbol) => symbol,
+                None => {
+                    let (qualified, class) = scope.resolve_class(engine, name)?;
+                    return Some(Symbol::Class { qualified, class });
+                }
+            }
+        }
+    };
+    for segment in segments {
+        let type_name = match &symbol {
+            Symbol::Component { entry, .. } => entry.type_name.clone(),
+            Symbol::Member { member, .. } => member.type_name.clone(),
+            Symbol::Class { .. } => return None,
+        };
+        let (owner, _) = scope.resolve_class(engine, &type_name)?;
+        let member = engine
+            .inherited_members_typed(&owner)
+            .into_iter()
+            .find(|m| m.name == segment)?;
+        symbol = Symbol::Member { owner, member };
+    }
+    Some(symbol)
+}
+
+/// LSP location of a byte span in a file the engine knows by `session_uri`:
+/// an open document (`doc-N.mo`), or a library file by path.
+fn location(
+    engine: &ModelicaEngine,
+    docs: &std::collections::HashMap<String, OpenDoc>,
+    session_uri: &str,
+    start: usize,
+    end: usize,
+) -> Option<Value> {
+    if let Some((uri, doc)) = docs
+        .iter()
+        .find(|(_, d)| engine.uri_for(d.id) == session_uri)
+    {
+        return Some(json!({ "uri": uri, "range": doc.lines.range(&doc.text, start, end) }));
+    }
+    let path = library_file(session_uri)?;
+    let source = std::fs::read_to_string(&path).ok()?;
+    let lines = LineIndex::new(&source);
+    Some(json!({ "uri": text::path_to_uri(&path), "range": lines.range(&source, start, end) }))
+}
+
+/// On-disk file of a library URI. Workspace files are keyed by absolute
+/// path; MSL bundle URIs are the paths the indexer scanned, which may be
+/// relative to its working directory — those are re-rooted at the MSL
+/// source root by their longest suffix that exists there.
+fn library_file(uri: &str) -> Option<PathBuf> {
+    let path = PathBuf::from(uri);
+    if path.is_absolute() && path.is_file() {
+        return Some(path);
+    }
+    let root = lunco_assets::msl_source_root_path()?;
+    let parts: Vec<_> = path.components().collect();
+    (0..parts.len())
+        .map(|skip| root.join(parts[skip..].iter().collect::<PathBuf>()))
+        .find(|candidate| candidate.is_file())
+}
+
+fn variability_prefix(variability: InheritedVariability) -> &'static str {
+    match variability {
+        InheritedVariability::Constant => "constant ",
+        InheritedVariability::Parameter => "parameter ",
+        InheritedVariability::Discrete => "discrete ",
+        InheritedVariability::Continuous => "",
+    }
+}
+
+fn causality_prefix(causality: InheritedCausality) -> &'static str {
+    match causality {
+        InheritedCausality::Input => "input ",
+        InheritedCausality::Output => "output ",
+        InheritedCausality::Internal => "",
+    }
+}
+
+fn local_variability(variability: Variability) -> InheritedVariability {
+    match variability {
+        Variability::Constant => InheritedVariability::Constant,
+        Variability::Parameter => InheritedVariability::Parameter,
+        Variability::Discrete => InheritedVariability::Discrete,
+        Variability::Continuous => InheritedVariability::Continuous,
+    }
+}
+
+fn local_causality(causality: Causality) -> InheritedCausality {
+    match causality {
+        Causality::Input => InheritedCausality::Input,
+        Causality::Output => InheritedCausality::Output,
+        Causality::None => InheritedCausality::Internal,
+    }
+}
+
+/// Markdown for a hover: a Modelica code line, then prose.
+fn hover_markdown(code: &str, prose: &[String]) -> Value {
+    let mut value = format!("```modelica\n{code}\n```");
+    for paragraph in prose.iter().filter(|p| !p.is_empty()) {
+        value.push_str("\n\n");
+        value.push_str(paragraph);
+    }
+    json!({ "contents": { "kind": "markdown", "value": value } })
+}
+
+fn class_description(class: &ClassDef) -> String {
+    class
+        .description
+        .iter()
+        .map(|t| t.text.as_ref().trim_matches('"'))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn completion_kind_for_class(class: &ClassDef) -> u8 {
+    match class_kind_label(class) {
+        "package" => 9,
+        "function" => 3,
+        "record" => 22,
+        "connector" => 8,
+        _ => 7,
+    }
+}
+
+fn symbol_kind_for_class(class: &ClassDef) -> u8 {
+    match class_kind_label(class) {
+        "package" => 4,
+        "function" => 12,
+        "record" => 23,
+        "connector" => 11,
+        "operator" => 25,
+        _ => 5,
+    }
+}
+
+fn member_item(name: &str, type_name: &str, variability: InheritedVariability) -> Value {
+    let kind = match variability {
+        InheritedVariability::Constant | InheritedVariability::Parameter => 21,
+        _ => 6,
+    };
+    json!({ "label": name, "kind": kind, "detail": format!("{}{type_name}", variability_prefix(variability)) })
+}
+
+/// A named argument or modification (`R = 10` inside `Resistor r(R = 10)`
+/// or `f(x, tol = 1e-6)`): the name belongs to the callee, not to the
+/// enclosing scope.
+fn is_named_argument(source: &str, occurrence: &Occurrence) -> bool {
+    let before = source[..occurrence.start].trim_end();
+    let after = source[occurrence.end..].trim_start();
+    (before.ends_with('(') || before.ends_with(','))
+        && after.starts_with('=')
+        && !after.starts_with("==")
+}
+
+/// Parse errors, and — once the MSL is in the engine, so a miss means
+/// something — component and `extends` types that do not resolve.
+pub(super) fn diagnostics(
+    engine: &mut ModelicaEngine,
+    doc: &OpenDoc,
+    msl_loaded: bool,
+) -> Vec<Value> {
+    let mut out: Vec<Value> = doc
+        .parse_errors
+        .iter()
+        .map(|d| {
+            let start = match (d.line, d.col) {
+                (Some(line), Some(col)) => doc.lines.offset_of_line_col(&doc.text, line, col),
+                _ => 0,
+            };
+            let end =
+                text::name_at(&doc.text, start).map_or(start, |(_, _, seg_end)| seg_end.max(start));
+            let severity = match d.severity {
+                lunco_doc::DiagnosticSeverity::Error => SEVERITY_ERROR,
+                lunco_doc::DiagnosticSeverity::Warning => SEVERITY_WARNING,
+                lunco_doc::DiagnosticSeverity::Info => SEVERITY_INFO,
+                lunco_doc::DiagnosticSeverity::Hint => SEVERITY_HINT,
+            };
+            json!({
+                "range": doc.lines.range(&doc.text, start, end),
+                "severity": severity,
+                "source": "rumoca",
+                "message": d.message,
+            })
+        })
+        .collect();
+    if !msl_loaded {
+        return out;
+    }
+    // Each class's references are resolved from inside that class.
+    let mut pending: Vec<&ClassDef> = doc.ast.classes.values().collect();
+    while let Some(class) = pending.pop() {
+        pending.extend(class.classes.values());
+        let at = class.name.location.start as usize;
+        let scope = Scope::at(doc, at);
+        let references = class
+            .extends
+            .iter()
+            .map(|e| &e.base_name)
+            .chain(class.iter_components().map(|(_, c)| &c.type_name));
+        for reference in references {
+            let name = reference.to_string();
+            let head = name.split('.').next().unwrap_or_default();
+            if BUILTIN_TYPES.contains(&name.as_str()) || scope.declares_class(head) {
+                continue;
+            }
+            if scope.resolve_class(engine, &name).is_some() {
+                continue;
+            }
+            let (Some(first), Some(last)) = (reference.name.first(), reference.name.last()) else {
+                continue;
+            };
+            out.push(json!({
+                "range": doc.lines.range(
+                    &doc.text,
+                    first.location.start as usize,
+                    last.location.end as usize,
+                ),
+                "severity": SEVERITY_WARNING,
+                "source": "lunica",
+                "message": format!("unresolved type `{name}`"),
+            }));
+        }
+    }
+    out
+}
+
+impl Server {
+    pub(super) fn completion(&mut self, uri: &str, position: &Value) -> Value {
+        let Some(doc) = self.docs.get(uri) else {
+            return Value::Null;
+        };
+        let engine = &mut self.engine;
+        let offset = doc.lines.offset_of(&doc.text, position);
+        let scope = Scope::at(doc, offset);
+        let mut items: Vec<Value> = Vec::new();
+
+        if let Some(qualifier) = text::qualifier_before(&doc.text, offset) {
+            // Member access: what the qualifier names decides the members.
+            match resolve_name(engine, doc, &scope, &qualifier) {
+                Some(Symbol::Component { entry, .. }) => {
+                    if let Some((owner, _)) = scope.resolve_class(engine, &entry.type_name) {
+                        items.extend(
+                            engine
+                                .inherited_members_typed(&owner)
+                                .iter()
+                                .map(|m| member_item(&m.name, &m.type_name, m.variability)),
+                        );
+                    }
+                }
+                Some(Symbol::Member { member, .. }) => {
+                    if let Some((owner, _)) = scope.resolve_class(engine, &member.type_name) {
+                        items.extend(
+                            engine
+                                .inherited_members_typed(&owner)
+                                .iter()
+                                .map(|m| member_item(&m.name, &m.type_name, m.variability)),
+                        );
+                    }
+                }
+                Some(Symbol::Class { qualified, class }) => {
+                    let mut seen = std::collections::HashSet::new();
+                    for (name, nested) in class.iter_classes() {
+                        if seen.insert(name.to_string()) {
+                            items.push(json!({
+                                "label": name,
+                                "kind": completion_kind_for_class(nested),
+                                "detail": class_kind_label(nested),
+                            }));
+                        }
+                    }
+                    // Packages split over files (`Blocks/package.mo` +
+                    // `Blocks/Continuous.mo`) list their other children as
+                    // separately indexed top-level classes.
+                    let prefix = format!("{qualified}.");
+                    let mut children: Vec<String> = engine
+                        .indexed_class_names()
+                        .filter_map(|q| q.strip_prefix(&prefix))
+                        .chain(
+                            crate::msl_remote::parsed_msl_bundle()
+                                .into_iter()
+                                .flat_map(|bundle| bundle.iter())
+                                .filter_map(|(_, ast)| {
+                                    let within = within_package(ast)?;
+                                    (within == qualified).then_some(ast.classes.keys())
+                                })
+                                .flatten()
+                                .map(String::as_str),
+                        )
+                        .map(|rest| rest.split('.').next().unwrap_or(rest).to_string())
+                        .collect();
+                    children.sort();
+                    children.dedup();
+                    for child in children {
+                        if seen.insert(child.clone()) {
+                            items.push(json!({ "label": child, "kind": 7 }));
+                        }
+                    }
+                    items.extend(
+                        engine
+                            .inherited_members_typed(&qualified)
+                            .iter()
+                            .map(|m| member_item(&m.name, &m.type_name, m.variability)),
+                    );
+                }
+                None => {}
+            }
+            return json!({ "isIncomplete": false, "items": items });
+        }
+
+        // Scope completion: this class's components, then names in scope.
+        if let Some(key) = scope.key() {
+            items.extend(
+                doc.index
+                    .components_in_class(key)
+                    .map(|c| member_item(&c.name, &c.type_name, local_variability(c.variability))),
+            );
+        }
+        if let Some(qualified) = scope.qualified() {
+            items.extend(
+                engine
+                    .inherited_members_typed(&qualified)
+                    .iter()
+                    .map(|m| member_item(&m.name, &m.type_name, m.variability)),
+            );
+        }
+        let mut classes: Vec<(String, u8)> = Vec::new();
+        for (_, class) in &scope.chain {
+            for (name, nested) in class.iter_classes() {
+                classes.push((name.to_string(), completion_kind_for_class(nested)));
+            }
+        }
+        for import in scope.imports() {
+            match import {
+                Import::Qualified { path, .. } => {
+                    if let Some(last) = path.name.last() {
+                        classes.push((last.text.to_string(), 7));
+                    }
+                }
+                Import::Renamed { alias, .. } => classes.push((alias.text.to_string(), 7)),
+                Import::Selective { names, .. } => {
+                    classes.extend(names.iter().map(|n| (n.text.to_string(), 7)));
+                }
+                Import::Unqualified { .. } => {}
+            }
+        }
+        classes.extend(
+            engine
+                .indexed_class_names()
+                .filter(|q| !q.contains('.'))
+                .map(|q| (q.to_string(), 7)),
+        );
+        classes.push(("Modelica".into(), 9));
+        classes.sort();
+        classes.dedup_by(|a, b| a.0 == b.0);
+        items.extend(
+            classes
+                .into_iter()
+                .map(|(label, kind)| json!({ "label": label, "kind": kind })),
+        );
+        items.extend(
+            BUILTIN_TYPES
+                .iter()
+                .map(|t| json!({ "label": t, "kind": 7, "detail": "builtin type" })),
+        );
+        items.extend(
+            BUILTIN_FUNCTIONS
+                .iter()
+                .map(|f| json!({ "label": f, "kind": 3, "detail": "builtin" })),
+        );
+        items.extend(
+            text::KEYWORDS
+                .iter()
+                .map(|k| json!({ "label": k, "kind": 14 })),
+        );
+        json!({ "isIncomplete": false, "items": items })
+    }
+
+    pub(super) fn hover(&mut self, uri: &str, position: &Value) -> Value {
+        let Some(doc) = self.docs.get(uri) else {
+            return Value::Null;
+        };
+        let offset = doc.lines.offset_of(&doc.text, position);
+        let Some((symbol, start, end)) = symbol_at(&mut self.engine, doc, offset) else {
+            return Value::Null;
+        };
+        let mut hover = match symbol {
+            Symbol::Component { class, entry } => {
+                let binding = entry
+                    .binding
+                    .as_ref()
+                    .map(|b| format!(" = {b}"))
+                    .unwrap_or_default();
+                hover_markdown(
+                    &format!(
+                        "{}{}{} {}{binding}",
+                        causality_prefix(local_causality(entry.causality)),
+                        variability_prefix(local_variability(entry.variability)),
+                        entry.type_name,
+                        entry.name,
+                    ),
+                    &[
+                        entry.description.clone(),
+                        format!("*declared in* `{class}`"),
+                    ],
+                )
+            }
+            Symbol::Member { owner, member } => {
+                let binding = member
+                    .default_value
+                    .as_ref()
+                    .map(|b| format!(" = {b}"))
+                    .unwrap_or_default();
+                hover_markdown(
+                    &format!(
+                        "{}{}{} {}{binding}",
+                        causality_prefix(member.causality),
+                        variability_prefix(member.variability),
+                        member.type_name,
+                        member.name,
+                    ),
+                    &[format!("*member of* `{owner}`")],
+                )
+            }
+            Symbol::Class { qualified, class } => {
+                let partial = if class.partial { "partial " } else { "" };
+                let info = crate::doc_extract::extract_documentation(&class.annotation)
+                    .0
+                    .map(|info| {
+                        let info = crate::indexer::clean_info_text(&info);
+                        match info.char_indices().nth(HOVER_DOC_CHARS) {
+                            Some((cut, _)) => format!("{}…", &info[..cut]),
+                            None => info,
+                        }
+                    })
+                    .unwrap_or_default();
+                hover_markdown(
+                    &format!("{partial}{} {qualified}", class_kind_label(&class)),
+                    &[class_description(&class), info],
+                )
+            }
+        };
+        hover["range"] = doc.lines.range(&doc.text, start, end);
+        hover
+    }
+
+    pub(super) fn definition(&mut self, uri: &str, position: &Value) -> Value {
+        let Some(doc) = self.docs.get(uri) else {
+            return Value::Null;
+        };
+        let offset = doc.lines.offset_of(&doc.text, position);
+        let Some((symbol, _, _)) = symbol_at(&mut self.engine, doc, offset) else {
+            return Value::Null;
+        };
+        let target = match symbol {
+            Symbol::Component { entry, .. } => {
+                return entry.source_range.map_or(
+                    Value::Null,
+                    |r| json!({ "uri": uri, "range": doc.lines.range(&doc.text, r.start, r.end) }),
+                );
+            }
+            Symbol::Member { owner, member } => {
+                let declaring = self
+                    .engine
+                    .session_mut()
+                    .class_component_member_info_query(&owner, &member.name)
+                    .map_or(owner, |(declaring, _)| declaring);
+                self.engine
+                    .class_source(&declaring)
+                    .and_then(|(file, class)| {
+                        let comp = class.components.get(&member.name)?;
+                        let loc = &comp.name_token.location;
+                        Some((file, loc.start as usize, loc.end as usize))
+                    })
+            }
+            Symbol::Class { qualified, .. } => {
+                self.engine.class_source(&qualified).map(|(file, class)| {
+                    let loc = &class.name.location;
+                    (file, loc.start as usize, loc.end as usize)
+                })
+            }
+        };
+        target
+            .and_then(|(file, start, end)| location(&self.engine, &self.docs, &file, start, end))
+            .unwrap_or(Value::Null)
+    }
+
+    pub(super) fn document_symbols(&self, uri: &str) -> Value {
+        let Some(doc) = self.docs.get(uri) else {
+            return Value::Null;
+        };
+        fn class_symbol(doc: &OpenDoc, name: &str, class: &ClassDef) -> Value {
+            let (start, end) = class_full_text_span(class, &doc.text);
+            let name_loc = &class.name.location;
+            let mut children: Vec<Value> = class
+                .iter_components()
+                .map(|(name, comp)| {
+                    use rumoca_compile::parsing::Variability as V;
+                    let kind = match comp.variability {
+                        V::Parameter(_) | V::Constant(_) => 14,
+                        _ => 13,
+                    };
+                    let loc = &comp.name_token.location;
+                    let range = doc
+                        .lines
+                        .range(&doc.text, loc.start as usize, loc.end as usize);
+                    json!({
+                        "name": name,
+                        "detail": comp.type_name.to_string(),
+                        "kind": kind,
+                        "range": range,
+                        "selectionRange": range,
+                    })
+                })
+                .collect();
+            children.extend(
+                class
+                    .iter_classes()
+                    .map(|(nested_name, nested)| class_symbol(doc, nested_name, nested)),
+            );
+            json!({
+                "name": name,
+                "detail": class_kind_label(class),
+                "kind": symbol_kind_for_class(class),
+                "range": doc.lines.range(&doc.text, start, end),
+                "selectionRange": doc.lines.range(
+                    &doc.text,
+                    name_loc.start as usize,
+                    name_loc.end as usize,
+                ),
+                "children": children,
+            })
+        }
+        Value::Array(
+            doc.ast
+                .classes
+                .iter()
+                .map(|(name, class)| class_symbol(doc, name, class))
+                .collect(),
+        )
+    }
+
+    /// The span a rename at `position` would change, or `None` when the
+    /// name is not declared in an open document (MSL and workspace
+    /// library classes are read-only here).
+    fn rename_target(&mut self, uri: &str, position: &Value) -> Option<(Symbol, usize, usize)> {
+        let doc = self.docs.get(uri)?;
+        let offset = doc.lines.offset_of(&doc.text, position);
+        let (symbol, start, end) = symbol_at(&mut self.engine, doc, offset)?;
+        match &symbol {
+            Symbol::Component { .. } => {}
+            Symbol::Class { qualified, .. } => {
+                let (file, _) = self.engine.class_source(qualified)?;
+                let engine = &self.engine;
+                if !self.docs.values().any(|d| engine.uri_for(d.id) == file) {
+                    return None;
+                }
+            }
+            Symbol::Member { .. } => return None,
+        }
+        Some((symbol, start, end))
+    }
+
+    pub(super) fn prepare_rename(&mut self, uri: &str, position: &Value) -> Value {
+        let Some((_, start, end)) = self.rename_target(uri, position) else {
+            return Value::Null;
+        };
+        let doc = &self.docs[uri];
+        json!({
+            "range": doc.lines.range(&doc.text, start, end),
+            "placeholder": &doc.text[start..end],
+        })
+    }
+
+    /// Lexical rename. A component is renamed inside its declaring class
+    /// (not where it is reached as `other.name` from elsewhere); a class
+    /// declared in an open document is renamed in every open document,
+    /// wherever a reference to it — bare or dotted — is spelled out.
+    pub(super) fn rename(
+        &mut self,
+        uri: &str,
+        position: &Value,
+        new_name: &str,
+    ) -> Result<Value, ResponseError> {
+        if !text::is_identifier(new_name) {
+            return Err((
+                INVALID_PARAMS,
+                format!("`{new_name}` is not a valid Modelica identifier"),
+            ));
+        }
+        let Some((symbol, _, _)) = self.rename_target(uri, position) else {
+            return Err((
+                REQUEST_FAILED,
+                "only names declared in an open document can be renamed".into(),
+            ));
+        };
+        let edit = |doc: &OpenDoc, o: &Occurrence| json!({ "range": doc.lines.range(&doc.text, o.start, o.end), "newText": new_name });
+        let mut changes = serde_json::Map::new();
+        match symbol {
+            Symbol::Component { class, entry } => {
+                let doc = &self.docs[uri];
+                let Some(class_def) =
+                    crate::diagram::find_class_by_qualified_name(&doc.ast, &class)
+                else {
+                    return Ok(Value::Null);
+                };
+                let (start, end) = class_full_text_span(class_def, &doc.text);
+                let edits: Vec<Value> = text::occurrences(&doc.text, start, end, &entry.name)
+                    .iter()
+                    .filter(|o| !o.after_dot && !is_named_argument(&doc.text, o))
+                    .map(|o| edit(doc, o))
+                    .collect();
+                changes.insert(uri.to_string(), Value::Array(edits));
+            }
+            Symbol::Class { qualified, .. } => {
+                let short = crate::ast_extract::short_name(&qualified).to_string();
+                for (doc_uri, doc) in &self.docs {
+                    let edits: Vec<Value> = text::occurrences(&doc.text, 0, doc.text.len(), &short)
+                        .iter()
+                        .filter(|o| !is_named_argument(&doc.text, o))
+                        .filter(|o| {
+                            // The spelled-out reference must end the qualified
+                            // name: `Pkg.Pump` and `Pump` both name `Lib.Pkg.Pump`.
+                            text::name_at(&doc.text, o.start).is_some_and(|(written, _, _)| {
+                                qualified == written || qualified.ends_with(&format!(".{written}"))
+                            })
+                        })
+                        .map(|o| edit(doc, o))
+                        .collect();
+                    if !edits.is_empty() {
+                        changes.insert(doc_uri.clone(), Value::Array(edits));
+                    }
+                }
+            }
+            Symbol::Member { .. } => return Ok(Value::Null),
+        }
+        Ok(json!({ "changes": changes }))
+    }
+
+    pub(super) fn format(&self, uri: &str) -> Result<Value, ResponseError> {
+        let Some(doc) = self.docs.get(uri) else {
+            return Ok(Value::Null);
+        };
+        let options = rumoca_tool_fmt::FormatOptions::default();
+        let formatted = rumoca_tool_fmt::format_with_source_name(&doc.text, &options, uri)
+            .map_err(|e| (REQUEST_FAILED, format!("format failed: {e}")))?;
+        if formatted == doc.text {
+            return Ok(json!([]));
+        }
+        Ok(json!([{
+            "range": doc.lines.range(&doc.text, 0, doc.text.len()),
+            "newText": formatted,
+        }]))
+    }
+}
@@ -0,0 +1,416 @@
+//! Modelica language server — `lunica --lsp`.
+//!
+//! Speaks the Language Server Protocol over stdio, so a `.mo` file opened
+//! in any LSP-capable editor gets the workbench editor's Modelica
+//! knowledge: parse diagnostics (plus unresolved component types once the
+//! MSL is loaded), completion, hover with class documentation,
+//! go-to-definition into open files, the workspace and the MSL, document
+//! symbols, rename, and formatting.
+//!
+//! Every answer comes from one [`ModelicaEngine`] — the session the
+//! workbench queries for name resolution and inheritance-merged members —
+//! so the server and the editor cannot disagree about a name. Open
+//! documents are installed as engine documents on every change; the
+//! pre-parsed MSL bundle (the one the workbench boots from) and the other
+//! `.mo` files under the workspace root are library sources. Formatting is
+//! `rumoca_tool_fmt`, the formatter behind the workbench's Format Document.
+//!
+//! The protocol layer is hand-rolled on `serde_json` ([`transport`]): full
+//! document sync, UTF-16 positions, no client-side requests. Native only —
+//! it reads the workspace and the MSL from disk.
+
+mod features;
+mod text;
+mod transport;
+
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+
+use lunco_doc::{Diagnostic, DocumentId};
+use rumoca_compile::parsing::ast::StoredDefinition;
+use serde_json::{json, Value};
+
+use crate::engine::ModelicaEngine;
+use crate::index::ModelicaIndex;
+use text::LineIndex;
+
+/// Workspace `.mo` files loaded as a library on `initialized`. A root that
+/// holds more than this is almost certainly not a Modelica workspace (a
+/// home directory, an MSL checkout); the rest resolve once opened.
+const MAX_WORKSPACE_FILES: usize = 2000;
+
+// JSON-RPC / LSP error codes.
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_NOT_INITIALIZED: i64 = -32002;
+const REQUEST_FAILED: i64 = -32803;
+
+type ResponseError = (i64, String);
+
+/// One document the client has open.
+struct OpenDoc {
+    id: DocumentId,
+    version: Option<i64>,
+    text: String,
+    lines: LineIndex,
+    /// Best-effort parse of `text` — what the engine holds for `id`.
+    ast: StoredDefinition,
+    index: ModelicaIndex,
+    parse_errors: Vec<Diagnostic>,
+}
+
+/// The language server state machine. [`Server::handle`] takes one
+/// client message and returns what to send back; [`run_stdio`] is the
+/// loop around it.
+pub struct Server {
+    engine: ModelicaEngine,
+    /// Open documents by LSP URI.
+    docs: HashMap<String, OpenDoc>,
+    next_doc: u64,
+    root: Option<PathBuf>,
+    /// Workspace files installed as library sources (engine URI = path).
+    workspace: HashSet<String>,
+    msl_loaded: bool,
+    initialized: bool,
+    shutdown: bool,
+    exit: Option<i32>,
+}
+
+impl Default for Server {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Server {
+    pub fn new() -> Self {
+        Self {
+            engine: ModelicaEngine::new(),
+            docs: HashMap::new(),
+            next_doc: 1,
+            root: None,
+            workspace: HashSet::new(),
+            msl_loaded: false,
+            initialized: false,
+            shutdown: false,
+            exit: None,
+        }
+    }
+
+    /// Exit code once the client has sent `exit`: 0 after a `shutdown`
+    /// request, 1 otherwise (as the protocol specifies).
+    pub fn exit_code(&self) -> Option<i32> {
+        self.exit
+    }
+
+    /// Handle one client message — request or notification — and return
+    /// the messages to send back, in order.
+    pub fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let Some(method) = message["method"].as_str() else {
+            // A response to a server request; this server sends none.
+            return Vec::new();
+        };
+        let params = &message["params"];
+        match message.get("id") {
+            Some(id) => {
+                let reply = match self.request(method, params) {
+                    Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+                    Err((code, msg)) => error_response(id, code, &msg),
+                };
+                vec![reply]
+            }
+            None => self.notification(method, params),
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, ResponseError> {
+        if method == "initialize" {
+            return Ok(self.initialize(params));
+        }
+        if !self.initialized {
+            return Err((SERVER_NOT_INITIALIZED, "initialize first".into()));
+        }
+        if self.shutdown {
+            return Err((INVALID_REQUEST, "the server is shutting down".into()));
+        }
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match method {
+            "shutdown" => {
+                self.shutdown = true;
+                Ok(Value::Null)
+            }
+            "textDocument/completion" => Ok(self.completion(uri, &params["position"])),
+            "textDocument/hover" => Ok(self.hover(uri, &params["position"])),
+            "textDocument/definition" => Ok(self.definition(uri, &params["position"])),
+            "textDocument/documentSymbol" => Ok(self.document_symbols(uri)),
+            "textDocument/prepareRename" => Ok(self.prepare_rename(uri, &params["position"])),
+            "textDocument/rename" => {
+                let new_name = params["newName"].as_str().unwrap_or_default();
+                self.rename(uri, &params["position"], new_name)
+            }
+            "textDocument/formatting" => self.format(uri),
+            _ => Err((METHOD_NOT_FOUND, format!("unsupported method `{method}`"))),
+        }
+    }
+
+    fn notification(&mut self, method: &str, params: &Value) -> Vec<Value> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let version = params["textDocument"]["version"].as_i64();
+        match method {
+            "initialized" => self.load_libraries(),
+            "exit" => {
+                self.exit = Some(if self.shutdown { 0 } else { 1 });
+                Vec::new()
+            }
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.update(uri, text.to_string(), version);
+                vec![self.publish_diagnostics(uri)]
+            }
+            "textDocument/didChange" => {
+                // Full sync: the last change carries the whole text.
+                let Some(text) = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str())
+                else {
+                    return Vec::new();
+                };
+                self.update(uri, text.to_string(), version);
+                vec![self.publish_diagnostics(uri)]
+            }
+            "textDocument/didClose" => {
+                self.close(uri);
+                vec![notification(
+                    "textDocument/publishDiagnostics",
+                    json!({ "uri": uri, "diagnostics": [] }),
+                )]
+            }
+            // `didSave`, `$/cancelRequest`, `$/setTrace`, … need nothing.
+            _ => Vec::new(),
+        }
+    }
+
+    fn initialize(&mut self, params: &Value) -> Value {
+        self.initialized = true;
+        self.root = params["workspaceFolders"][0]["uri"]
+            .as_str()
+            .or_else(|| params["rootUri"].as_str())
+            .and_then(text::uri_to_path)
+            .or_else(|| params["rootPath"].as_str().map(PathBuf::from));
+        json!({
+            "capabilities": {
+                "textDocumentSync": { "openClose": true, "change": 1 },
+                "completionProvider": { "triggerCharacters": ["."] },
+                "hoverProvider": true,
+                "definitionProvider": true,
+                "documentSymbolProvider": true,
+                "renameProvider": { "prepareProvider": true },
+                "documentFormattingProvider": true,
+            },
+            "serverInfo": { "name": "lunica", "version": env!("CARGO_PKG_VERSION") },
+        })
+    }
+
+    /// Install the MSL bundle and the workspace's `.mo` files into the
+    /// engine. Runs once, on `initialized`, so the handshake itself
+    /// returns at once.
+    fn load_libraries(&mut self) -> Vec<Value> {
+        let mut out = Vec::new();
+        match crate::msl_remote::parsed_msl_bundle() {
+            Some(bundle) => {
+                let defs: Vec<(String, StoredDefinition)> =
+                    bundle.iter().map(|(u, d)| (u.clone(), d.clone())).collect();
+                let count = defs.len();
+                self.engine.session_mut().replace_parsed_source_set(
+                    "msl",
+                    rumoca_compile::compile::SourceRootKind::DurableExternal,
+                    defs,
+                    None,
+                );
+                self.msl_loaded = true;
+                out.push(log_message(3, format!("loaded {count} MSL files")));
+            }
+            None => out.push(log_message(
+                2,
+                format!(
+                    "no pre-parsed MSL bundle at {} — MSL classes will not resolve",
+                    lunco_assets::msl_dir().join("parsed-msl.bin").display()
+                ),
+            )),
+        }
+        if let Some(root) = self.root.clone() {
+            let mut paths = Vec::new();
+            collect_workspace_files(&root, &mut paths);
+            let open: HashSet<PathBuf> = self
+                .docs
+                .keys()
+                .filter_map(|u| text::uri_to_path(u))
+                .collect();
+            let files: Vec<(String, String)> = paths
+                .iter()
+                .filter(|p| !open.contains(*p))
+                .filter_map(|p| {
+                    Some((
+                        p.to_string_lossy().into_owned(),
+                        std::fs::read_to_string(p).ok()?,
+                    ))
+                })
+                .collect();
+            self.workspace = files.iter().map(|(uri, _)| uri.clone()).collect();
+            let loaded = self.engine.load_library_files(
+                "workspace",
+                &format!("workspace:{}", root.display()),
+                files,
+            );
+            out.push(log_message(
+                3,
+                format!("loaded {loaded} workspace files from {}", root.display()),
+            ));
+        }
+        // Documents opened before the libraries landed resolve differently now.
+        let uris: Vec<String> = self.docs.keys().cloned().collect();
+        out.extend(uris.iter().map(|uri| self.publish_diagnostics(uri)));
+        out
+    }
+
+    /// Reparse `uri` from `text` and reinstall it in the engine.
+    fn update(&mut self, uri: &str, text: String, version: Option<i64>) {
+        let recovery = rumoca_phase_parse::parse_to_syntax(&text, uri);
+        let parse_errors = recovery
+            .parse_errors()
+            .iter()
+            .map(|e| crate::document::parse_diag_from_error(e, &text))
+            .collect();
+        let ast = recovery.best_effort().clone();
+        let mut index = ModelicaIndex::new();
+        index.rebuild_from_ast(&ast, &text);
+        let id = match self.docs.get(uri) {
+            Some(doc) => doc.id,
+            None => {
+                // The workspace copy of this file must not declare its
+                // classes a second time next to the live buffer.
+                if let Some(path) = self.workspace_path(uri) {
+                    self.engine.remove_library_file(&path);
+                }
+                let id = DocumentId::new(self.next_doc);
+                self.next_doc += 1;
+                id
+            }
+        };
+        self.engine.install_parsed_ast(id, ast.clone());
+        self.docs.insert(
+            uri.to_string(),
+            OpenDoc {
+                id,
+                version,
+                lines: LineIndex::new(&text),
+                text,
+                ast,
+                index,
+                parse_errors,
+            },
+        );
+    }
+
+    fn close(&mut self, uri: &str) {
+        let Some(doc) = self.docs.remove(uri) else {
+            return;
+        };
+        self.engine.close_document(doc.id);
+        // Hand a workspace file back to the library, as saved on disk.
+        if let Some(path) = self.workspace_path(uri) {
+            if let Ok(source) = std::fs::read_to_string(&path) {
+                self.engine
+                    .load_library_files("workspace", "workspace", vec![(path, source)]);
+            }
+        }
+    }
+
+    /// The library URI of `uri` when it is one of the loaded workspace files.
+    fn workspace_path(&self, uri: &str) -> Option<String> {
+        let path = text::uri_to_path(uri)?.to_string_lossy().into_owned();
+        self.workspace.contains(&path).then_some(path)
+    }
+
+    fn publish_diagnostics(&mut self, uri: &str) -> Value {
+        let Some(doc) = self.docs.get(uri) else {
+            return notification(
+                "textDocument/publishDiagnostics",
+                json!({ "uri": uri, "diagnostics": [] }),
+            );
+        };
+        let diagnostics = features::diagnostics(&mut self.engine, doc, self.msl_loaded);
+        notification(
+            "textDocument/publishDiagnostics",
+            json!({ "uri": uri, "version": doc.version, "diagnostics": diagnostics }),
+        )
+    }
+}
+
+/// Run the server on stdin/stdout until the client sends `exit` (or
+/// closes the stream). Returns the process exit code.
+pub fn run_stdio() -> io::Result<i32> {
+    let stdin = io::stdin();
+    let mut reader = stdin.lock();
+    let mut writer = io::stdout().lock();
+    let mut server = Server::new();
+    loop {
+        let message = match transport::read_message(&mut reader) {
+            Ok(Some(message)) => message,
+            Ok(None) => return Ok(1),
+            Err(e) if e.kind() == io::ErrorKind::InvalidData => {
+                let reply = error_response(&Value::Null, PARSE_ERROR, &e.to_string());
+                transport::write_message(&mut writer, &reply)?;
+                continue;
+            }
+            Err(e) => return Err(e),
+        };
+        for reply in server.handle(&message) {
+            transport::write_message(&mut writer, &reply)?;
+        }
+        if let Some(code) = server.exit_code() {
+            return Ok(code);
+        }
+    }
+}
+
+fn collect_workspace_files(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if out.len() >= MAX_WORKSPACE_FILES {
+            return;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if crate::package_tree::scanner::should_skip(&name) {
+            continue;
+        }
+        let path = entry.path();
+        if entry.file_type().is_ok_and(|t| t.is_dir()) {
+            collect_workspace_files(&path, out);
+        } else if path.extension().is_some_and(|e| e == "mo") {
+            out.push(path);
+        }
+    }
+}
+
+fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// `window/logMessage` — `kind` 1 error, 2 warning, 3 info.
+fn log_message(kind: u8, message: String) -> Value {
+    notification(
+        "window/logMessage",
+        json!({ "type": kind, "message": message }),
+    )
+}
+
+fn error_response(id: &Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
@@ -0,0 +1,384 @@
+//! Source-text helpers: LSP positions (line + UTF-16 column) ↔ byte
+//! offsets, the dotted name under the cursor, and a lexical scan for
+//! identifier occurrences that skips strings, comments and quoted names.
+
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Value};
+
+/// Modelica reserved words (MLS §2.3.3). Offered by completion, refused
+/// as rename targets.
+pub(crate) const KEYWORDS: &[&str] = &[
+    "algorithm",
+    "and",
+    "annotation",
+    "block",
+    "break",
+    "class",
+    "connect",
+    "connector",
+    "constant",
+    "constrainedby",
+    "der",
+    "discrete",
+    "each",
+    "else",
+    "elseif",
+    "elsewhen",
+    "encapsulated",
+    "end",
+    "enumeration",
+    "equation",
+    "expandable",
+    "extends",
+    "external",
+    "false",
+    "final",
+    "flow",
+    "for",
+    "function",
+    "if",
+    "import",
+    "impure",
+    "in",
+    "initial",
+    "inner",
+    "input",
+    "loop",
+    "model",
+    "not",
+    "operator",
+    "or",
+    "outer",
+    "output",
+    "package",
+    "parameter",
+    "partial",
+    "protected",
+    "public",
+    "pure",
+    "record",
+    "redeclare",
+    "replaceable",
+    "return",
+    "stream",
+    "then",
+    "true",
+    "type",
+    "when",
+    "while",
+    "within",
+];
+
+/// Line starts of one text, for offset ↔ position conversion.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub(crate) fn new(text: &str) -> Self {
+        let mut starts = vec![0];
+        starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self { starts }
+    }
+
+    /// LSP `{line, character}` for a byte offset; `character` counts UTF-16
+    /// code units, as the protocol's default position encoding requires.
+    pub(crate) fn position(&self, text: &str, offset: usize) -> Value {
+        let offset = floor_char_boundary(text, offset.min(text.len()));
+        let line = self.starts.partition_point(|&s| s <= offset) - 1;
+        let character: usize = text[self.starts[line]..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+        json!({ "line": line, "character": character })
+    }
+
+    /// LSP `{start, end}` for a byte range.
+    pub(crate) fn range(&self, text: &str, start: usize, end: usize) -> Value {
+        json!({ "start": self.position(text, start), "end": self.position(text, end) })
+    }
+
+    /// Byte offset of an LSP position, clamped to the line (and text) end.
+    pub(crate) fn offset(&self, text: &str, line: usize, character: usize) -> usize {
+        let Some(&start) = self.starts.get(line) else {
+            return text.len();
+        };
+        let end = self
+            .starts
+            .get(line + 1)
+            .map_or(text.len(), |&next| next - 1);
+        let mut units = 0;
+        for (i, c) in text[start..end].char_indices() {
+            if units >= character {
+                return start + i;
+            }
+            units += c.len_utf16();
+        }
+        end
+    }
+
+    /// Byte offset of the `params.position` of a request.
+    pub(crate) fn offset_of(&self, text: &str, position: &Value) -> usize {
+        let field = |k: &str| position[k].as_u64().unwrap_or(0) as usize;
+        self.offset(text, field("line"), field("character"))
+    }
+
+    /// Byte offset of a 1-based `(line, column)` pair whose column counts
+    /// characters — the convention of [`lunco_doc::Diagnostic`].
+    pub(crate) fn offset_of_line_col(&self, text: &str, line: u32, col: u32) -> usize {
+        let Some(&start) = self.starts.get(line.saturating_sub(1) as usize) else {
+            return text.len();
+        };
+        text[start..]
+            .char_indices()
+            .take_while(|(_, c)| *c != '\n')
+            .nth(col.saturating_sub(1) as usize)
+            .map_or_else(
+                || text[start..].find('\n').map_or(text.len(), |n| start + n),
+                |(i, _)| start + i,
+            )
+    }
+}
+
+fn floor_char_boundary(text: &str, mut offset: usize) -> usize {
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    offset
+}
+
+fn is_ident_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_'
+}
+
+/// The dotted name under `offset`, cut at the end of the segment the
+/// cursor is on: `Modelica.Blocks.Continuous` with the cursor in `Blocks`
+/// gives `Modelica.Blocks`. Returns the name and the byte span of that
+/// last segment.
+pub(crate) fn name_at(text: &str, offset: usize) -> Option<(String, usize, usize)> {
+    let bytes = text.as_bytes();
+    let offset = offset.min(bytes.len());
+    let mut seg_start = offset;
+    while seg_start > 0 && is_ident_byte(bytes[seg_start - 1]) {
+        seg_start -= 1;
+    }
+    let mut seg_end = offset;
+    while seg_end < bytes.len() && is_ident_byte(bytes[seg_end]) {
+        seg_end += 1;
+    }
+    if seg_start == seg_end || bytes[seg_start].is_ascii_digit() {
+        return None;
+    }
+    let mut start = seg_start;
+    while start > 1 && bytes[start - 1] == b'.' && is_ident_byte(bytes[start - 2]) {
+        let mut s = start - 1;
+        while s > 0 && is_ident_byte(bytes[s - 1]) {
+            s -= 1;
+        }
+        start = s;
+    }
+    Some((text[start..seg_end].to_string(), seg_start, seg_end))
+}
+
+/// The dotted qualifier right before a completion trigger: for
+/// `  x = body.fr|` this is `Some("body")` (the partial `fr` is the
+/// client's filter text); `None` when the cursor is not after a dot.
+pub(crate) fn qualifier_before(text: &str, offset: usize) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut i = offset.min(bytes.len());
+    while i > 0 && is_ident_byte(bytes[i - 1]) {
+        i -= 1;
+    }
+    if i == 0 || bytes[i - 1] != b'.' {
+        return None;
+    }
+    name_at(text, i - 1).map(|(name, _, _)| name)
+}
+
+/// Whether `name` is a plain (unquoted) Modelica identifier that is not a
+/// keyword.
+pub(crate) fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some(c) if c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && !KEYWORDS.contains(&name)
+}
+
+/// One lexical occurrence of an identifier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Occurrence {
+    pub(crate) start: usize,
+    pub(crate) end: usize,
+    /// Preceded by `.` — a member access (`other.name`), not a reference
+    /// resolved in the enclosing scope.
+    pub(crate) after_dot: bool,
+}
+
+/// Every occurrence of the identifier `name` in `text[from..to]`, skipping
+/// string literals, comments and quoted identifiers.
+pub(crate) fn occurrences(text: &str, from: usize, to: usize, name: &str) -> Vec<Occurrence> {
+    let bytes = text.as_bytes();
+    let to = to.min(bytes.len());
+    let mut out = Vec::new();
+    let mut i = from.min(to);
+    let mut last_significant: Option<u8> = None;
+    while i < to {
+        let b = bytes[i];
+        match b {
+            b'"' | b'\'' => {
+                i += 1;
+                while i < to && bytes[i] != b {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+                i += 1;
+                last_significant = Some(b);
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < to && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < to && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            _ if b.is_ascii_alphabetic() || b == b'_' => {
+                let start = i;
+                while i < to && is_ident_byte(bytes[i]) {
+                    i += 1;
+                }
+                if &text[start..i] == name {
+                    out.push(Occurrence {
+                        start,
+                        end: i,
+                        after_dot: last_significant == Some(b'.'),
+                    });
+                }
+                last_significant = Some(b'a');
+            }
+            _ if b.is_ascii_digit() => {
+                // Numbers, including `1e-3`, never start an identifier.
+                while i < to && (is_ident_byte(bytes[i]) || bytes[i] == b'.') {
+                    i += 1;
+                }
+                last_significant = Some(b'0');
+            }
+            _ => {
+                if !b.is_ascii_whitespace() {
+                    last_significant = Some(b);
+                }
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Path of a `file://` URI, percent-decoded. `None` for any other scheme
+/// (`untitled:` buffers have no file behind them).
+pub(crate) fn uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    // Drop an authority (`file://host/…`); only the local host is meaningful.
+    let rest = &rest[rest.find('/')?..];
+    let bytes = rest.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = || std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok();
+        match hex().and_then(|h| u8::from_str_radix(h, 16).ok()) {
+            Some(b) if bytes[i] == b'%' => {
+                decoded.push(b);
+                i += 3;
+            }
+            _ => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    let path = String::from_utf8(decoded).ok()?;
+    // `file:///C:/x` → `C:/x`.
+    let drive = matches!(path.as_bytes(), [b'/', d, b':', ..] if d.is_ascii_alphabetic());
+    Some(PathBuf::from(if drive { &path[1..] } else { &path[..] }))
+}
+
+/// `file://` URI of an absolute path.
+pub(crate) fn path_to_uri(path: &Path) -> String {
+    let path = path.to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for b in path.bytes() {
+        if b.is_ascii_alphanumeric() || b"/-_.~:".contains(&b) {
+            uri.push(b as char);
+        } else {
+            uri.push_str(&format!("%{b:02X}"));
+        }
+    }
+    uri
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions_count_utf16_units() {
+        let text = "model M \"ω·τ\"\n  Real x;\nend M;";
+        let lines = LineIndex::new(text);
+        let tau = text.find('τ').unwrap();
+        assert_eq!(
+            lines.position(text, tau),
+            json!({"line": 0, "character": 11})
+        );
+        assert_eq!(lines.offset(text, 0, 11), tau);
+        let x = text.find("x;").unwrap();
+        assert_eq!(lines.position(text, x), json!({"line": 1, "character": 7}));
+        assert_eq!(lines.offset(text, 1, 7), x);
+        assert_eq!(lines.offset(text, 1, 99), text.find("\nend").unwrap());
+        assert_eq!(
+            lines.offset_of_line_col(text, 3, 5),
+            text.find("M;").unwrap()
+        );
+    }
+
+    #[test]
+    fn name_under_cursor_stops_at_the_cursor_segment() {
+        let text = "  Modelica.Blocks.Continuous.PID pid;";
+        let at = text.find("Blocks").unwrap() + 2;
+        let (name, start, end) = name_at(text, at).unwrap();
+        assert_eq!(name, "Modelica.Blocks");
+        assert_eq!(&text[start..end], "Blocks");
+        assert_eq!(qualifier_before("x = body.fr", 11).as_deref(), Some("body"));
+        assert_eq!(qualifier_before("x = a.b.", 8).as_deref(), Some("a.b"));
+        assert_eq!(qualifier_before("x = 1.", 6), None);
+    }
+
+    #[test]
+    fn occurrences_skip_strings_comments_and_member_access() {
+        let text = "Real k \"k gain\"; // k\n/* k */ y = k * other.k + 1e-3k;";
+        let found = occurrences(text, 0, text.len(), "k");
+        assert_eq!(found.len(), 3);
+        assert_eq!(found[0].start, 5);
+        assert!(!found[1].after_dot);
+        assert!(found[2].after_dot);
+        assert!(is_identifier("gain_2"));
+        assert!(!is_identifier("model"));
+        assert!(!is_identifier("2x"));
+    }
+
+    #[test]
+    fn file_uris_round_trip() {
+        let path = Path::new("/work/My Models/Pump–v2.mo");
+        let uri = path_to_uri(path);
+        assert_eq!(uri, "file:///work/My%20Models/Pump%E2%80%93v2.mo");
+        assert_eq!(uri_to_path(&uri).as_deref(), Some(path));
+        assert_eq!(uri_to_path("untitled:Untitled-1"), None);
+    }
+}
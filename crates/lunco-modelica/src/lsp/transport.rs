@@ -0,0 +1,89 @@
+//! LSP base protocol: `Content-Length`-framed JSON-RPC messages.
+
+use std::io::{self, BufRead, Write};
+
+use serde_json::Value;
+
+/// Largest body a frame may declare. Far above any real request (a whole
+/// package's source is a few MiB); it stops a corrupt or hostile header from
+/// making us allocate whatever it claims.
+const MAX_CONTENT_LENGTH: usize = 64 * 1024 * 1024;
+
+/// Read one message. `Ok(None)` at end of input; a frame whose body is not
+/// JSON is an `InvalidData` error (the stream is unrecoverable past it only
+/// if the length was wrong, which the header parse already rejects). So is a
+/// `Content-Length` above [`MAX_CONTENT_LENGTH`], before anything is allocated.
+pub(crate) fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut length: Option<usize> = None;
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end_matches(['\r', '\n']);
+        if header.is_empty() {
+            if length.is_some() {
+                break;
+            }
+            // Stray blank line between frames.
+            continue;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.trim().eq_ignore_ascii_case("content-length") {
+                length = Some(value.trim().parse().map_err(|_| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("bad Content-Length `{}`", value.trim()),
+                    )
+                })?);
+            }
+        }
+    }
+    let length = length.unwrap_or_default();
+    if length > MAX_CONTENT_LENGTH {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("Content-Length {length} exceeds the {MAX_CONTENT_LENGTH}-byte limit"),
+        ));
+    }
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+/// Write one message and flush — the client waits on every reply.
+pub(crate) fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = serde_json::to_vec(message)?;
+    write!(writer, "Content-Length: {}\r\n\r\n", body.len())?;
+    writer.write_all(&body)?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn frames_round_trip() {
+        let mut out = Vec::new();
+        write_message(&mut out, &json!({"jsonrpc": "2.0", "id": 1, "result": "π"})).unwrap();
+        write_message(&mut out, &json!({"jsonrpc": "2.0", "method": "exit"})).unwrap();
+        let mut reader = io::Cursor::new(out);
+        let first = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(first["result"], "π");
+        let second = read_message(&mut reader).unwrap().unwrap();
+        assert_eq!(second["method"], "exit");
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn an_oversized_frame_is_rejected_before_reading_its_body() {
+        let header = format!("Content-Length: {}\r\n\r\n", MAX_CONTENT_LENGTH + 1);
+        let err = read_message(&mut io::Cursor::new(header)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+}
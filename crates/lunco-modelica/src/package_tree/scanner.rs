@@ -80,7 +80,7 @@ fn scan_twin_children(dir: &Path) -> Vec<TwinNode> {
     out
 }
 
-fn should_skip(name: &str) -> bool {
+pub(crate) fn should_skip(name: &str) -> bool {
     name.starts_with('.')
         || matches!(
             name,
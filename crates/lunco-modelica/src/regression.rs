@@ -0,0 +1,201 @@
+//! Regression runner — simulate each case of a suite and judge it against its
+//! reference result. Native only: it reads models and references off disk.
+//!
+//! The judgement and the report formats live in
+//! `lunco_experiments::regression`; this module does the Modelica part —
+//! compile, bind overrides, run through the same [`drive_run`] the Experiments
+//! runner uses (so a regression run is numerically the run a user gets) — and
+//! the file I/O. Front ends: the `modelica_regress` CLI and the
+//! `RunRegressionSuite` command.
+
+use std::path::{Path, PathBuf};
+
+use lunco_experiments::regression::{check_case, html_report, junit_xml};
+use lunco_experiments::{CaseReport, CaseSpec, RunResult, RunUpdate, SuiteReport, SuiteSpec};
+
+use crate::experiments_runner::{apply_value_bindings_to_dae, drive_run, RunSink};
+use crate::result_files::{read_result, write_result, ResultExportFormat};
+use crate::ModelicaCompiler;
+
+/// Read a suite file (JSON, see [`SuiteSpec`]).
+pub fn load_suite(path: &Path) -> Result<SuiteSpec, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+    let mut suite: SuiteSpec = serde_json::from_str(&text)
+        .map_err(|e| format!("{} is not a regression suite: {e}", path.display()))?;
+    if suite.name.is_empty() {
+        suite.name = path
+            .file_stem()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "regression".into());
+    }
+    Ok(suite)
+}
+
+/// Run every case of the suite at `path`. With `bless`, each case's run is
+/// written over its reference instead of being judged — how references are
+/// created and deliberately updated.
+pub fn run_suite(path: &Path, bless: bool) -> Result<SuiteReport, String> {
+    let suite = load_suite(path)?;
+    let base = path.parent().unwrap_or(Path::new("."));
+    crate::solver_backends::ensure_builtin_solvers();
+    let mut compiler = ModelicaCompiler::new();
+    let cases = suite
+        .cases
+        .iter()
+        .map(|case| {
+            let report = run_case(&mut compiler, &suite, case, base, bless);
+            match &report.error {
+                Some(e) => log::warn!("[regress] {}: {e}", case.name),
+                None if report.passed() => log::info!("[regress] {}: pass", case.name),
+                None => log::warn!("[regress] {}: signal(s) outside the tube", case.name),
+            }
+            report
+        })
+        .collect();
+    Ok(SuiteReport {
+        name: suite.name,
+        cases,
+    })
+}
+
+/// Write the JUnit XML and/or HTML renderings of `report`.
+pub fn write_reports(
+    report: &SuiteReport,
+    junit: Option<&Path>,
+    html: Option<&Path>,
+) -> Result<(), String> {
+    if let Some(path) = junit {
+        std::fs::write(path, junit_xml(report))
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    }
+    if let Some(path) = html {
+        std::fs::write(path, html_report(report))
+            .map_err(|e| format!("failed to write {}: {e}", path.display()))?;
+    }
+    Ok(())
+}
+
+fn run_case(
+    compiler: &mut ModelicaCompiler,
+    suite: &SuiteSpec,
+    case: &CaseSpec,
+    base: &Path,
+    bless: bool,
+) -> CaseReport {
+    let started = web_time::Instant::now();
+    let mut report = CaseReport {
+        name: case.name.clone(),
+        model: case.model.clone(),
+        ..CaseReport::default()
+    };
+    let reference_path = resolve(base, &case.reference);
+    let outcome = simulate_case(compiler, case, base).and_then(|actual| {
+        if bless {
+            let format = reference_path
+                .extension()
+                .and_then(|e| ResultExportFormat::parse(&e.to_string_lossy()))
+                .unwrap_or(ResultExportFormat::Mat4);
+            write_result(&reference_path, &actual, format)
+                .map_err(|e| format!("failed to write {}: {e}", reference_path.display()))?;
+            log::info!(
+                "[regress] {} blessed → {}",
+                case.name,
+                reference_path.display()
+            );
+            return Ok((actual.clone(), actual));
+        }
+        let reference = read_result(&reference_path)
+            .map_err(|e| format!("reference {}: {e}", reference_path.display()))?;
+        Ok((reference, actual))
+    });
+    match outcome {
+        Ok((reference, actual)) => {
+            report.signals = check_case(case, suite.tube, &reference, &actual);
+            report.reference = Some(reference);
+            report.actual = Some(actual);
+        }
+        Err(e) => report.error = Some(e),
+    }
+    report.wall_time_ms = started.elapsed().as_millis() as u64;
+    report
+}
+
+/// Compile the case's model, bind its overrides and run it to completion.
+fn simulate_case(
+    compiler: &mut ModelicaCompiler,
+    case: &CaseSpec,
+    base: &Path,
+) -> Result<RunResult, String> {
+    let model_path = resolve(base, &case.model);
+    let source = std::fs::read_to_string(&model_path)
+        .map_err(|e| format!("failed to read {}: {e}", model_path.display()))?;
+    let filename = model_path.display().to_string();
+
+    // Class and bounds come from the document the same way the workbench
+    // resolves them: the ranked simulation candidates, the class's
+    // `experiment(...)` annotation under the case's own fields.
+    let ast = rumoca_phase_parse::parse_to_ast(&source, &filename)
+        .map_err(|e| format!("{} does not parse: {e:?}", model_path.display()))?;
+    let mut index = crate::index::ModelicaIndex::new();
+    index.rebuild_from_ast(&ast, &source);
+    let candidates = index.simulation_candidates();
+    let class = match &case.class {
+        Some(requested) => crate::sim_target::resolve_requested_class(requested, &candidates)
+            .map_err(|e| format!("class `{requested}` {e}"))?,
+        None => crate::sim_target::default_class(None, &candidates)
+            .ok_or_else(|| format!("{} has no simulatable class", model_path.display()))?,
+    };
+    let annotated = index
+        .classes
+        .get(&class)
+        .and_then(|c| c.experiment.as_ref())
+        .and_then(crate::sim_target::bounds_from_experiment)
+        .unwrap_or_else(crate::sim_target::default_bounds);
+    let bounds = case.bounds(annotated);
+
+    let compiled = compiler
+        .compile_str(&class, &source, &filename)
+        .map_err(|e| format!("compile failed: {e}"))?;
+    let bindings = case.bindings();
+    let dae = if bindings.is_empty() {
+        compiled.dae.clone()
+    } else {
+        let mut d = (*compiled.dae).clone();
+        apply_value_bindings_to_dae(&mut d, &bindings)
+            .map_err(|e| format!("parameter/input binding failed: {e}"))?;
+        std::sync::Arc::new(d)
+    };
+
+    let mut sink = CollectSink(None);
+    drive_run(&dae, &bounds, web_time::Instant::now(), &mut sink);
+    sink.0
+        .unwrap_or_else(|| Err("the run ended without a result".into()))
+}
+
+fn resolve(base: &Path, path: &str) -> PathBuf {
+    let p = Path::new(path);
+    if p.is_absolute() {
+        p.to_path_buf()
+    } else {
+        base.join(p)
+    }
+}
+
+/// [`RunSink`] that keeps the final outcome; a regression (or estimation) run
+/// is never cancelled.
+pub(crate) struct CollectSink(pub(crate) Option<Result<RunResult, String>>);
+
+impl RunSink for CollectSink {
+    fn is_cancelled(&mut self) -> bool {
+        false
+    }
+    fn emit(&mut self, update: RunUpdate) {
+        match update {
+            RunUpdate::Completed(result) => self.0 = Some(Ok(result)),
+            RunUpdate::Failed { error, .. } => self.0 = Some(Err(error)),
+            RunUpdate::Cancelled => self.0 = Some(Err("cancelled".into())),
+            RunUpdate::Progress { .. } => {}
+        }
+    }
+}
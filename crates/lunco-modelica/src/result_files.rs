@@ -0,0 +1,320 @@
+//! Result files in and out: export a run as CSV or as a Modelica-tool
+//! trajectory (MAT v4 / v7.3, HDF5), and import such a file as a read-only
+//! *reference run* to overlay on our own results.
+//!
+//! The trajectory formats themselves live in `lunco_experiments::result_file`
+//! (MAT v4) and `result_hdf5` (MAT v7.3 / HDF5, `hdf5` feature, native only);
+//! this module picks the format, does the file I/O and files an imported run
+//! under its document the same way a simulated run is.
+
+use std::path::Path;
+
+use bevy::prelude::*;
+use lunco_doc::DocumentId;
+use lunco_experiments::{
+    ExperimentId, ExperimentRegistry, ModelRef, ResultFileKind, RunCompleted, RunResult,
+};
+
+use crate::experiments_runner::{twin_id_for_doc, ExperimentSources};
+
+/// What an export writes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ResultExportFormat {
+    /// `time` + one column per variable.
+    Csv,
+    /// Dymola / OpenModelica `binTrans` trajectory, MAT v4.
+    Mat4,
+    /// The same trajectory as MAT v7.3 (HDF5 with MATLAB's header).
+    Mat73,
+    /// The same trajectory as a plain HDF5 file.
+    Hdf5,
+}
+
+impl ResultExportFormat {
+    pub const ALL: [Self; 4] = [Self::Csv, Self::Mat4, Self::Mat73, Self::Hdf5];
+
+    /// Parse an API / command spelling (`csv`, `mat`, `mat4`, `mat73`,
+    /// `hdf5`, `h5`).
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "csv" => Some(Self::Csv),
+            "mat" | "mat4" | "matv4" => Some(Self::Mat4),
+            "mat73" | "mat7.3" | "matv73" => Some(Self::Mat73),
+            "hdf5" | "h5" => Some(Self::Hdf5),
+            _ => None,
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Mat4 | Self::Mat73 => "mat",
+            Self::Hdf5 => "h5",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Csv => "CSV",
+            Self::Mat4 => "MAT v4",
+            Self::Mat73 => "MAT v7.3",
+            Self::Hdf5 => "HDF5",
+        }
+    }
+
+    /// Whether this build can write the format — the HDF5-backed ones need
+    /// the `hdf5` feature and a native target.
+    pub fn available(self) -> bool {
+        match self {
+            Self::Csv | Self::Mat4 => true,
+            Self::Mat73 | Self::Hdf5 => HDF5_AVAILABLE,
+        }
+    }
+}
+
+const HDF5_AVAILABLE: bool = cfg!(all(feature = "hdf5", not(target_arch = "wasm32")));
+
+/// Render `result` as CSV: a `time` column, then every variable in name
+/// order. `NaN` / out-of-range samples are empty cells.
+pub fn encode_csv(result: &RunResult) -> String {
+    let mut text = String::new();
+    text.push_str("time");
+    for v in result.series.keys() {
+        text.push(',');
+        // Quote names that contain commas / quotes; Modelica dotted
+        // paths normally don't, but be defensive.
+        push_csv_field(&mut text, v);
+    }
+    text.push('\n');
+    for (i, t) in result.times.iter().enumerate() {
+        text.push_str(&format!("{t}"));
+        for col in result.series.values() {
+            text.push(',');
+            match col.get(i) {
+                Some(x) if x.is_finite() => text.push_str(&format!("{x}")),
+                _ => {}
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// Append `s` as a single CSV field to `out`, RFC-4180 quoting when it
+/// contains a comma, double-quote, or newline (doubling embedded quotes).
+pub fn push_csv_field(out: &mut String, s: &str) {
+    if s.contains(',') || s.contains('"') || s.contains('\n') {
+        out.push('"');
+        for c in s.chars() {
+            if c == '"' {
+                out.push('"');
+            }
+            out.push(c);
+        }
+        out.push('"');
+    } else {
+        out.push_str(s);
+    }
+}
+
+/// Parse a CSV trajectory — the first column is time, the header names the
+/// variables, empty cells are `NaN`. Reads what [`encode_csv`] writes (and
+/// the per-step CSV of `modelica_run`).
+pub fn decode_csv(text: &str) -> Result<RunResult, String> {
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty());
+    let header = split_csv_line(lines.next().ok_or("empty CSV")?);
+    if header.len() < 2 {
+        return Err("CSV needs a time column and at least one variable".into());
+    }
+    let mut times = Vec::new();
+    let mut columns: Vec<Vec<f64>> = vec![Vec::new(); header.len() - 1];
+    for (row, line) in lines.enumerate() {
+        let fields = split_csv_line(line);
+        let t = fields
+            .first()
+            .and_then(|f| f.trim().parse::<f64>().ok())
+            .ok_or_else(|| format!("row {}: no time value", row + 2))?;
+        times.push(t);
+        for (i, column) in columns.iter_mut().enumerate() {
+            let cell = fields.get(i + 1).map(|f| f.trim()).unwrap_or("");
+            column.push(cell.parse().unwrap_or(f64::NAN));
+        }
+    }
+    let series = header.into_iter().skip(1).zip(columns).collect();
+    Ok(RunResult {
+        meta: lunco_experiments::RunMeta {
+            sample_count: times.len(),
+            notes: Some("imported from CSV".into()),
+            ..Default::default()
+        },
+        times,
+        series,
+    })
+}
+
+/// Split one CSV line into fields, undoing [`push_csv_field`]'s quoting.
+fn split_csv_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            c => field.push(c),
+        }
+    }
+    fields.push(field);
+    fields
+}
+
+/// The bytes of an in-memory export — `None` for the HDF5-backed formats,
+/// which the library writes straight to a file ([`write_result`]).
+pub fn encode_result(result: &RunResult, format: ResultExportFormat) -> Option<Vec<u8>> {
+    match format {
+        ResultExportFormat::Csv => Some(encode_csv(result).into_bytes()),
+        ResultExportFormat::Mat4 => Some(lunco_experiments::encode_mat4(result)),
+        ResultExportFormat::Mat73 | ResultExportFormat::Hdf5 => None,
+    }
+}
+
+/// Write `result` to `path` in `format`.
+pub fn write_result(
+    path: &Path,
+    result: &RunResult,
+    format: ResultExportFormat,
+) -> Result<(), String> {
+    if let Some(bytes) = encode_result(result, format) {
+        return lunco_storage::write_file_sync(path, &bytes).map_err(|e| e.to_string());
+    }
+    write_hdf5(path, result, format == ResultExportFormat::Mat73)
+}
+
+#[cfg(all(feature = "hdf5", not(target_arch = "wasm32")))]
+fn write_hdf5(path: &Path, result: &RunResult, mat73: bool) -> Result<(), String> {
+    lunco_experiments::result_hdf5::write_hdf5(path, result, mat73).map_err(|e| e.to_string())
+}
+
+#[cfg(not(all(feature = "hdf5", not(target_arch = "wasm32"))))]
+fn write_hdf5(_path: &Path, _result: &RunResult, _mat73: bool) -> Result<(), String> {
+    Err("this build has no HDF5 support (the `hdf5` feature, native only)".into())
+}
+
+/// Read a MAT v4, MAT v7.3 or HDF5 trajectory from `path`, or a CSV one
+/// when the file is `.csv`.
+pub fn read_result(path: &Path) -> Result<RunResult, String> {
+    let bytes = lunco_storage::read_file_sync(path).map_err(|e| e.to_string())?;
+    if path
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case("csv"))
+    {
+        return decode_csv(&String::from_utf8_lossy(&bytes));
+    }
+    match lunco_experiments::sniff(&bytes).map_err(|e| e.to_string())? {
+        ResultFileKind::Mat4 => lunco_experiments::decode_mat4(&bytes).map_err(|e| e.to_string()),
+        ResultFileKind::Mat73 | ResultFileKind::Hdf5 => read_hdf5(path),
+    }
+}
+
+#[cfg(all(feature = "hdf5", not(target_arch = "wasm32")))]
+fn read_hdf5(path: &Path) -> Result<RunResult, String> {
+    lunco_experiments::result_hdf5::read_hdf5(path).map_err(|e| e.to_string())
+}
+
+#[cfg(not(all(feature = "hdf5", not(target_arch = "wasm32"))))]
+fn read_hdf5(_path: &Path) -> Result<RunResult, String> {
+    Err("MAT v7.3 / HDF5 file, but this build has no HDF5 support \
+         (the `hdf5` feature, native only) — save it as MAT v4"
+        .into())
+}
+
+/// Import the trajectory at `path` as a reference run of `doc`: it joins the
+/// document's run list (and every plot that shows it) like a finished run,
+/// named after the file. The completion message makes the plots pick it up
+/// and the experiment store file it.
+pub fn import_reference(
+    world: &mut World,
+    doc: DocumentId,
+    path: &Path,
+    name: Option<String>,
+) -> Result<ExperimentId, String> {
+    let result = read_result(path)?;
+    if result.series.is_empty() {
+        return Err("the file holds no variables".into());
+    }
+    let file = path
+        .file_name()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let stem = path
+        .file_stem()
+        .map(|f| f.to_string_lossy().into_owned())
+        .unwrap_or_else(|| file.clone());
+    let twin_id = twin_id_for_doc(doc);
+    let mut registry = world.resource_mut::<ExperimentRegistry>();
+    // File it under the model the document's runs are for, so it sorts and
+    // groups with them; a document with no runs yet falls back to the file.
+    let model_ref = registry
+        .list_for_twin(&twin_id)
+        .iter()
+        .filter(|e| e.reference.is_none())
+        .max_by_key(|e| e.created_at)
+        .map(|e| e.model_ref.clone())
+        .unwrap_or_else(|| ModelRef(stem.clone()));
+    let id = registry.insert_reference(
+        twin_id,
+        model_ref,
+        name.filter(|n| !n.trim().is_empty()).unwrap_or(stem),
+        file,
+        result,
+    );
+    world.resource_mut::<ExperimentSources>().0.insert(id, doc);
+    world.write_message(RunCompleted { experiment_id: id });
+    Ok(id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn export_formats_parse_their_spellings() {
+        assert_eq!(
+            ResultExportFormat::parse("MAT"),
+            Some(ResultExportFormat::Mat4)
+        );
+        assert_eq!(
+            ResultExportFormat::parse("mat7.3"),
+            Some(ResultExportFormat::Mat73)
+        );
+        assert_eq!(
+            ResultExportFormat::parse("h5"),
+            Some(ResultExportFormat::Hdf5)
+        );
+        assert_eq!(ResultExportFormat::parse("xlsx"), None);
+        assert!(ResultExportFormat::Mat4.available());
+    }
+
+    #[test]
+    fn csv_round_trips_quoted_names_and_blank_nans() {
+        let mut series = std::collections::BTreeMap::new();
+        series.insert("b".to_string(), vec![1.0, f64::NAN]);
+        series.insert("a,x".to_string(), vec![2.0, 3.0]);
+        let result = RunResult {
+            times: vec![0.0, 0.5],
+            series,
+            meta: Default::default(),
+        };
+        let text = encode_csv(&result);
+        assert_eq!(text, "time,\"a,x\",b\n0,2,1\n0.5,3,\n");
+        let back = decode_csv(&text).unwrap();
+        assert_eq!(back.times, result.times);
+        assert_eq!(back.series["a,x"], vec![2.0, 3.0]);
+        assert!(back.series["b"][1].is_nan());
+    }
+}
@@ -4,8 +4,8 @@ use rumoca_compile::compile::Dae;
 use rumoca_ir_solve::SolveModel;
 use rumoca_sim::{SimOptions, SimulationDiagnosticError, SimulationSession};
 
-use crate::fixed_step::FixedStepSession;
-use lunco_experiments::solver::{SolverId, SolverSpec};
+use crate::fixed_step::{FixedStepMethod, FixedStepSession};
+use lunco_experiments::solver::SolverSpec;
 
 /// Live Modelica stepper selected by the authoritative solver capability.
 pub enum LiveStepper {
@@ -104,8 +104,8 @@ pub fn live_from_solve_model(
     spec: &SolverSpec,
     options: SimOptions,
 ) -> Result<LiveStepper, SimulationDiagnosticError> {
-    if spec.id == SolverId::from("fixedrk4") {
-        FixedStepSession::from_solve_model(solve_model, options, None)
+    if let Some(method) = FixedStepMethod::for_solver(&spec.id) {
+        FixedStepSession::from_solve_model(solve_model, method, options, None)
             .map(|session| LiveStepper::Fixed(Box::new(session)))
     } else {
         rumoca_solver_rk45::SimulationSession::new(solve_model, options)
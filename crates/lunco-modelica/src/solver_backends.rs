@@ -80,8 +80,8 @@ pub fn ensure_builtin_solvers() {
 
         // This is not Rumoca's adaptive RkLike session. The live construction
         // boundary dispatches this capability to lunco-modelica's explicit RK4
-        // loop over Rumoca's lowered derivative runtime. Its qualified model
-        // contract is continuous, event-free, and external-table-free.
+        // loop over Rumoca's lowered derivative runtime, which localizes events
+        // inside the fixed step by a fixed number of bisections.
         solver::register(SolverSpec {
             id: SolverId::from("fixed-rk4"),
             caps: SolverCaps {
@@ -92,7 +92,20 @@ pub fn ensure_builtin_solvers() {
             // Ordinary authoritative live models continue to use RK45. This
             // backend is selected when prediction requires the stronger caps.
             rank: 5,
-            label: "Fixed RK4 (prediction-safe continuous models)".into(),
+            label: "Fixed RK4 (prediction-safe, events and tables)".into(),
+        });
+        // The same fixed-step loop with two-stage Radau IIA between events, for
+        // stiff islands. Its Newton solve has a fixed iteration cap, so per-step
+        // work stays bounded. Ranked below fixed-rk4: it is chosen by name.
+        solver::register(SolverSpec {
+            id: SolverId::from("fixed-radau3"),
+            caps: SolverCaps {
+                usable_live: true,
+                fixed_step: true,
+                deterministic: true,
+            },
+            rank: 4,
+            label: "Fixed Radau IIA (prediction-safe, stiff)".into(),
         });
     });
 }
@@ -114,10 +127,10 @@ pub fn rumoca_options(
         "esdirk34" => (M::Bdf, D::Esdirk34),
         "trbdf2" => (M::Bdf, D::TrBdf2),
         "rk45" => (M::RkLike, D::Bdf),
-        // The fixed-rk4 session owns the integration loop. These options only
+        // The fixed-step session owns the integration loop. These options only
         // carry the solver-neutral Rumoca lowering mode and tolerances into the
         // common DAE-to-SolveModel path.
-        "fixedrk4" => (M::RkLike, D::Bdf),
+        "fixedrk4" | "fixedradau3" => (M::RkLike, D::Bdf),
         _ => {
             return Err(SolverError::Incapable {
                 asked: spec.id.clone(),
@@ -664,6 +664,76 @@ fn compile_overlay_source(document: &crate::document::ModelicaDocument) -> Strin
     }
 }
 
+/// Source snapshot and target class for an analysis of `doc` that compiles
+/// outside the interactive session (linearization, estimation, …), plus the
+/// class's `experiment(...)` annotation when it has one. Class resolution is
+/// `dispatch_experiment`'s minus the picker: an explicit `class` resolves
+/// through the shared resolver, otherwise the shared default applies.
+pub(super) fn batch_model_source(
+    world: &World,
+    doc: DocumentId,
+    class: Option<&str>,
+) -> Result<
+    (
+        crate::experiments_runner::ModelSource,
+        Option<crate::annotations::Experiment>,
+    ),
+    String,
+> {
+    let registry = world.resource::<ModelicaDocumentRegistry>();
+    let host = registry
+        .host(doc)
+        .ok_or_else(|| format!("doc {} not in registry", doc.raw()))?;
+    let document = host.document();
+    let source = compile_overlay_source(document);
+    let filename = document.origin().session_uri();
+    let index = document.index();
+    let candidates = index.simulation_candidates();
+    let model_name = match class {
+        Some(req) => crate::sim_target::resolve_requested_class(req, &candidates)
+            .map_err(|e| format!("class `{req}` {e}. Candidates: [{}]", candidates.join(", ")))?,
+        None => crate::sim_default::default_simulation_class(world, doc)
+            .ok_or_else(|| format!("doc {} has no compilable top-level class", doc.raw()))?,
+    };
+    let leaf = model_name.rsplit('.').next().unwrap_or(&model_name);
+    let experiment = index
+        .classes
+        .values()
+        .find(|c| c.name == model_name)
+        .or_else(|| {
+            index
+                .classes
+                .values()
+                .find(|c| c.name.rsplit('.').next() == Some(leaf))
+        })
+        .and_then(|c| c.experiment);
+    // Same `within` qualification and bundled-sibling re-attachment as
+    // `dispatch_experiment`.
+    let (model_name, extras) = match crate::ui::duplicate::within_package(&source) {
+        Some(pkg) => {
+            let extras = crate::ui::class_source::bundled_source_for(&pkg)
+                .map(|s| vec![(format!("{pkg}.mo"), s.to_string())])
+                .unwrap_or_default();
+            let name = if model_name.starts_with(&format!("{pkg}.")) {
+                model_name
+            } else {
+                format!("{pkg}.{model_name}")
+            };
+            (name, extras)
+        }
+        None => (model_name, Vec::new()),
+    };
+    Ok((
+        crate::experiments_runner::ModelSource {
+            model_name,
+            source,
+            filename,
+            extras,
+        },
+        experiment,
+    ))
+}
+
 // ─── on_compile_model ─────────────────────────────────────────────────────
 
 #[on_command(CompileModel)]
@@ -1573,7 +1643,7 @@ fn parse_param_value(txt: &str) -> Option<lunco_experiments::ParamValue> {
 }
 
 /// Turn API `[{name, value}]` rows into a typed param map (skips empties).
-fn param_map_from_mods(
+pub(super) fn param_map_from_mods(
     mods: &[crate::api::ApiModification],
 ) -> std::collections::BTreeMap<lunco_experiments::ParamPath, lunco_experiments::ParamValue> {
     let mut map = std::collections::BTreeMap::new();
@@ -1593,8 +1663,12 @@ fn param_map_from_mods(
 /// any UI draft (command wins), inserts the experiment, and dispatches it.
 ///
 /// `label`, when set, replaces the auto-generated "Run N" name so sweep rows
-/// are identifiable in `ListRuns`. Returns the new experiment id, or `None`
-/// when dispatch can't proceed (no doc, ambiguous class → picker, etc.).
+/// are identifiable in `ListRuns`. With `sweep` set, the composed overrides
+/// become the family's base and one run is dispatched per design point
+/// (`label` then names the family). Returns the (first) new experiment id, or
+/// `None` when dispatch can't proceed (no doc, ambiguous class → picker, a
+/// sweep that does not expand, etc.).
+#[allow(clippy::too_many_arguments)]
 fn dispatch_experiment(
     world: &mut World,
     raw: DocumentId,
@@ -1609,6 +1683,7 @@ fn dispatch_experiment(
     >,
     cmd_bounds: BoundsOverride,
     label: Option<String>,
+    sweep: Option<lunco_experiments::SweepSpec>,
 ) -> Option<lunco_experiments::ExperimentId> {
     use lunco_experiments::ExperimentRunner;
     {
@@ -1859,77 +1934,136 @@ fn dispatch_experiment(
             bounds.h0 = Some(h);
         }
 
-        // Insert experiment + dispatch run. Scope to the originating
-        // doc so multi-tab workflows keep run histories separate
-        // (Model A's runs ≠ Model B's runs).
+        // Insert the experiment (or the sweep's whole family) + dispatch.
+        // Scope to the originating doc so multi-tab workflows keep run
+        // histories separate (Model A's runs ≠ Model B's runs).
         let twin_id = crate::ui::doc_pin::twin_id_for_doc(doc);
-        let exp_id = {
+        let journal = world
+            .get_resource::<lunco_doc_bevy::JournalResource>()
+            .cloned();
+        let inserted = {
             let mut reg = world.resource_mut::<lunco_experiments::ExperimentRegistry>();
-            let id = reg.insert_new(twin_id, model_ref, overrides, inputs, bounds);
-            // Apply a caller-supplied label so sweep rows are identifiable
-            // in ListRuns (e.g. "Isp=300") instead of the auto "Run N".
-            if let Some(name) = label {
-                if let Some(e) = reg.get_mut(id) {
-                    e.name = name;
+            match sweep {
+                None => {
+                    let id = reg.insert_new(twin_id, model_ref, overrides, inputs, bounds);
+                    // Apply a caller-supplied label so sweep rows are identifiable
+                    // in ListRuns (e.g. "Isp=300") instead of the auto "Run N".
+                    if let Some(name) = label {
+                        if let Some(e) = reg.get_mut(id) {
+                            e.name = name;
+                        }
+                    }
+                    Ok((vec![id], None))
                 }
+                Some(spec) => reg
+                    .insert_sweep(twin_id, model_ref, label, spec, overrides, inputs, bounds)
+                    .map(|sweep_id| {
+                        let sweep = reg.sweep(sweep_id).cloned();
+                        let members = sweep.as_ref().map(|s| s.members.clone());
+                        (members.unwrap_or_default(), sweep)
+                    }),
             }
-            id
         };
-        let exp = world
-            .resource::<lunco_experiments::ExperimentRegistry>()
-            .get(exp_id)
-            .cloned();
-        let Some(exp) = exp else {
-            bevy::log::error!("[dispatch_experiment] experiment vanished after insert");
-            return None;
+        let exp_ids = match inserted {
+            Ok((ids, None)) => ids,
+            Ok((ids, Some(sweep))) => {
+                // The family goes into the journal before its members so a
+                // replaying peer groups them.
+                if let Some(journal) = &journal {
+                    crate::experiment_journal::record_create_sweep(journal, &sweep);
+                }
+                bevy::log::info!(
+                    "[dispatch_experiment] sweep '{}' ({}, seed {}) → {} run(s) for class '{}'",
+                    sweep.name,
+                    sweep.spec.design.label(),
+                    sweep.spec.seed,
+                    ids.len(),
+                    model_name
+                );
+                if let Some(mut console) =
+                    world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
+                {
+                    console.info(format!(
+                        "▶ Sweep: '{}' — {} {} run(s) of '{}'",
+                        sweep.name,
+                        ids.len(),
+                        sweep.spec.design.label(),
+                        model_name
+                    ));
+                }
+                ids
+            }
+            Err(why) => {
+                bevy::log::error!("[dispatch_experiment] sweep refused: {why}");
+                if let Some(mut console) =
+                    world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
+                {
+                    console.error(format!("✗ Sweep refused: {why}"));
+                }
+                return None;
+            }
         };
 
-        // Journal the experiment *definition* (create) so the setup syncs across
-        // peers + persists. Run status/results are NOT journaled — they ride the
-        // presence / content planes respectively.
-        if let Some(journal) = world
-            .get_resource::<lunco_doc_bevy::JournalResource>()
-            .cloned()
-        {
-            crate::experiment_journal::record_create(&journal, &exp);
-        }
+        let mut first = None;
+        for exp_id in exp_ids {
+            let exp = world
+                .resource::<lunco_experiments::ExperimentRegistry>()
+                .get(exp_id)
+                .cloned();
+            let Some(exp) = exp else {
+                bevy::log::error!("[dispatch_experiment] experiment vanished after insert");
+                return None;
+            };
 
-        let handle = runner_res.0.run_fast(&exp);
-        // Remember which document started this run so failures can be
-        // routed back into the doc's CompileStates + Console.
-        world
-            .resource_mut::<crate::experiments_runner::ExperimentSources>()
-            .0
-            .insert(exp_id, doc);
-        // Store the handle so a draining system can pump updates into
-        // registry status.
-        world
-            .resource_mut::<crate::experiments_runner::PendingHandles>()
-            .0
-            .push(handle);
-        // Mark the run Queued. The scheduler may start it immediately (then
-        // its first progress update flips it to Running via
-        // drain_pending_handles) or hold it behind the concurrency cap, in
-        // which case it stays Queued until a slot frees — letting the panel
-        // show "N running · M queued".
-        world
-            .resource_mut::<lunco_experiments::ExperimentRegistry>()
-            .set_status(exp_id, lunco_experiments::RunStatus::Queued);
-        bevy::log::info!(
-            "[dispatch_experiment] dispatched run {:?} '{}' for class '{}'",
-            exp_id,
-            exp.name,
-            model_name
-        );
-        if let Some(mut console) =
-            world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
-        {
-            console.info(format!(
-                "▶ Run: '{}' (t={:.2}→{:.2}s)",
-                model_name, exp.bounds.t_start, exp.bounds.t_end
-            ));
+            // Journal the experiment *definition* (create) so the setup syncs across
+            // peers + persists. Run status/results are NOT journaled — they ride the
+            // presence / content planes respectively.
+            if let Some(journal) = &journal {
+                crate::experiment_journal::record_create(journal, &exp);
+            }
+
+            let handle = runner_res.0.run_fast(&exp);
+            // Remember which document started this run so failures can be
+            // routed back into the doc's CompileStates + Console.
+            world
+                .resource_mut::<crate::experiments_runner::ExperimentSources>()
+                .0
+                .insert(exp_id, doc);
+            // Store the handle so a draining system can pump updates into
+            // registry status.
+            world
+                .resource_mut::<crate::experiments_runner::PendingHandles>()
+                .0
+                .push(handle);
+            // Mark the run Queued. The scheduler may start it immediately (then
+            // its first progress update flips it to Running via
+            // drain_pending_handles) or hold it behind the concurrency cap, in
+            // which case it stays Queued until a slot frees — letting the panel
+            // show "N running · M queued".
+            world
+                .resource_mut::<lunco_experiments::ExperimentRegistry>()
+                .set_status(exp_id, lunco_experiments::RunStatus::Queued);
+            // A sweep announced itself once above; one line per member would
+            // bury the console.
+            if exp.sweep.is_none() {
+                bevy::log::info!(
+                    "[dispatch_experiment] dispatched run {:?} '{}' for class '{}'",
+                    exp_id,
+                    exp.name,
+                    model_name
+                );
+                if let Some(mut console) =
+                    world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
+                {
+                    console.info(format!(
+                        "▶ Run: '{}' (t={:.2}→{:.2}s)",
+                        model_name, exp.bounds.t_start, exp.bounds.t_end
+                    ));
+                }
+            }
+            first.get_or_insert(exp_id);
         }
-        Some(exp_id)
+        first
     }
 }
 
@@ -1980,6 +2114,7 @@ pub fn on_fast_run_active_model(trigger: On<FastRunActiveModel>, mut commands: C
             Default::default(),
             cmd_bounds,
             None,
+            None,
         );
     });
 }
@@ -2145,17 +2280,195 @@ pub fn on_run_experiment(trigger: On<RunExperiment>, mut commands: Commands) {
             inputs,
             cmd_bounds,
             label,
+            None,
+        );
+    });
+}
+
+/// Define + dispatch a design-of-experiments sweep: one family of batch runs
+/// expanded from `design` over `factors`, every member sharing `overrides`,
+/// `inputs` and bounds (a swept factor wins over an override of the same
+/// name). The family is one object — `ListSweeps`, one row in the
+/// Experiments panel — and is exempt from the per-twin run cap; its members
+/// are ordinary runs in `ListRuns`, tagged with `sweep_id`. The model compiles
+/// once and the members run in parallel.
+#[Command(default)]
+pub struct RunSweep {
+    /// Target document. Unassigned → the active document.
+    pub doc: DocumentId,
+    /// Target class. `None` → drilled-in class or sole non-package class.
+    pub class: Option<String>,
+    /// `full_factorial`, `latin_hypercube` (`lhs`), `sobol`, `monte_carlo`,
+    /// or one of the sensitivity designs `morris` / `saltelli` (see
+    /// `GetSensitivity`).
+    pub design: String,
+    /// Run count for Latin hypercube / Sobol / Monte Carlo; levels per
+    /// continuous factor for full factorial (a discrete factor contributes
+    /// all its values); trajectories for Morris (`k + 1` runs each); base
+    /// samples for Saltelli (`k + 2` runs each).
+    pub samples: u32,
+    /// Grid levels per factor for `morris`. `None` = 4.
+    pub levels: Option<u32>,
+    /// Swept parameters `[{name, distribution, …}]`.
+    pub factors: Vec<crate::api::ApiSweepFactor>,
+    /// Seed for every random choice in the design. `None` = drawn fresh; the
+    /// seed used is recorded on the sweep either way.
+    pub seed: Option<u64>,
+    /// Parameter overrides every member carries `[{name, value}]`.
+    pub overrides: Vec<crate::api::ApiModification>,
+    /// Runtime input overrides `[{name, value}]`.
+    pub inputs: Vec<crate::api::ApiModification>,
+    pub t_start: Option<f64>,
+    pub t_end: Option<f64>,
+    /// Output step in seconds (Modelica `Interval`). Mutually exclusive with
+    /// `n_intervals`.
+    pub dt: Option<f64>,
+    /// Output point count as a number of intervals; takes precedence over
+    /// `dt` when set.
+    pub n_intervals: Option<u32>,
+    pub tolerance: Option<f64>,
+    /// Pin the solver to a registered id, as for `RunExperiment`.
+    pub solver: Option<String>,
+    pub h0: Option<f64>,
+    /// Family name (members are `<label> #k`). Defaults to auto "Sweep N".
+    pub label: Option<String>,
+}
+
+/// Turn one API factor row into a typed [`SweepFactor`](lunco_experiments::SweepFactor).
+/// Names the field that is missing rather than defaulting it — a sweep over a
+/// silently-zero bound is worse than no sweep.
+fn sweep_factor_from_api(
+    f: &crate::api::ApiSweepFactor,
+) -> Result<lunco_experiments::SweepFactor, String> {
+    use lunco_experiments::Distribution;
+    let need = |v: Option<f64>, field: &str| {
+        v.ok_or_else(|| format!("factor `{}`: `{}` needs `{field}`", f.name, f.distribution))
+    };
+    let distribution = match f
+        .distribution
+        .trim()
+        .to_ascii_lowercase()
+        .replace(['-', ' '], "_")
+        .as_str()
+    {
+        "uniform" => Distribution::Uniform {
+            low: need(f.low, "low")?,
+            high: need(f.high, "high")?,
+        },
+        "normal" | "gaussian" => Distribution::Normal {
+            mean: need(f.mean, "mean")?,
+            std_dev: need(f.std_dev, "std_dev")?,
+        },
+        "truncated_normal" | "truncated" => Distribution::TruncatedNormal {
+            mean: need(f.mean, "mean")?,
+            std_dev: need(f.std_dev, "std_dev")?,
+            low: need(f.low, "low")?,
+            high: need(f.high, "high")?,
+        },
+        "discrete" => Distribution::Discrete {
+            values: f
+                .values
+                .iter()
+                .filter_map(|v| parse_param_value(v))
+                .collect(),
+            weights: f.weights.clone(),
+        },
+        other => {
+            return Err(format!(
+                "factor `{}`: unknown distribution `{other}` (uniform, normal, \
+                 truncated_normal or discrete)",
+                f.name
+            ))
+        }
+    };
+    Ok(lunco_experiments::SweepFactor {
+        path: lunco_experiments::ParamPath(f.name.clone()),
+        distribution,
+    })
+}
+
+#[on_command(RunSweep)]
+pub fn on_run_sweep(trigger: On<RunSweep>, mut commands: Commands) {
+    let ev = trigger.event();
+    let raw = ev.doc;
+    let explicit_class = ev.class.clone();
+    let overrides = param_map_from_mods(&ev.overrides);
+    let inputs = param_map_from_mods(&ev.inputs);
+    let label = ev.label.clone();
+    let Some(mut design) = lunco_experiments::Design::from_token(&ev.design, ev.samples) else {
+        refuse_run(
+            "RunSweep",
+            format!(
+                "unknown design `{}` (full_factorial, latin_hypercube, sobol, monte_carlo, \
+                 morris or saltelli)",
+                ev.design
+            ),
+            &mut commands,
+        );
+        return;
+    };
+    if let (lunco_experiments::Design::Morris { levels, .. }, Some(n)) = (&mut design, ev.levels) {
+        *levels = n;
+    }
+    let factors = match ev
+        .factors
+        .iter()
+        .map(sweep_factor_from_api)
+        .collect::<Result<Vec<_>, _>>()
+    {
+        Ok(f) => f,
+        Err(why) => {
+            refuse_run("RunSweep", why, &mut commands);
+            return;
+        }
+    };
+    let spec = lunco_experiments::SweepSpec {
+        design,
+        factors,
+        seed: ev
+            .seed
+            .unwrap_or_else(|| uuid::Uuid::new_v4().as_u128() as u64),
+    };
+    let solver = match parse_solver_arg(ev.solver.as_deref()) {
+        Ok(s) => s,
+        Err(why) => {
+            refuse_run("RunSweep", why, &mut commands);
+            return;
+        }
+    };
+    let cmd_bounds = BoundsOverride {
+        t_start: ev.t_start,
+        t_end: ev.t_end,
+        dt: ev.dt,
+        n_intervals: ev.n_intervals,
+        tolerance: ev.tolerance,
+        solver,
+        h0: ev.h0,
+    };
+    commands.queue(move |world: &mut World| {
+        dispatch_experiment(
+            world,
+            raw,
+            explicit_class,
+            overrides,
+            inputs,
+            cmd_bounds,
+            label,
+            Some(spec),
         );
     });
 }
 
 /// Cancel in-flight batch run(s). Signals the runner's cancel flag, which is
 /// honored at compile boundaries and on every solver step; the run then ends
-/// `Cancelled`. Target a specific run by `experiment_id`, or set `all`.
+/// `Cancelled`. Target a specific run by `experiment_id`, every member of a
+/// sweep by `sweep_id`, or set `all`.
 #[Command(default)]
 pub struct CancelExperiment {
     /// Cancel one run by id (uuid string). Ignored when `all` is set.
     pub experiment_id: Option<String>,
+    /// Cancel every in-flight member of this sweep (uuid string).
+    pub sweep_id: Option<String>,
     /// Cancel every in-flight run.
     pub all: bool,
 }
@@ -2163,26 +2476,42 @@ pub struct CancelExperiment {
 #[on_command(CancelExperiment)]
 pub fn on_cancel_experiment(trigger: On<CancelExperiment>, mut commands: Commands) {
     let target = trigger.event().experiment_id.clone();
+    let sweep = trigger.event().sweep_id.clone();
     let all = trigger.event().all;
     commands.queue(move |world: &mut World| {
+        let members: std::collections::HashSet<lunco_experiments::ExperimentId> = sweep
+            .as_deref()
+            .and_then(|t| {
+                let reg = world.get_resource::<lunco_experiments::ExperimentRegistry>()?;
+                let s = reg.iter_sweeps().find(|s| s.id.0.to_string() == t)?;
+                Some(s.members.iter().copied().collect())
+            })
+            .unwrap_or_default();
         let handles = world.resource::<crate::experiments_runner::PendingHandles>();
         let mut n = 0u32;
         for h in handles.0.iter() {
-            if all || target.as_deref() == Some(h.run_id.0.to_string().as_str()) {
+            if all
+                || target.as_deref() == Some(h.run_id.0.to_string().as_str())
+                || members.contains(&h.run_id)
+            {
                 h.cancel();
                 n += 1;
             }
         }
-        bevy::log::info!("[CancelExperiment] signalled {n} run(s) (all={all}, id={target:?})");
+        bevy::log::info!(
+            "[CancelExperiment] signalled {n} run(s) (all={all}, id={target:?}, sweep={sweep:?})"
+        );
     });
 }
 
 /// Remove experiment record(s) from the registry. Terminal runs only —
 /// in-flight runs (via id / `all`) are skipped; cancel them first. Scope by
-/// `experiment_id`, `doc` (every run for that doc's twin), or `all`.
+/// `experiment_id`, `sweep_id` (a sweep's finished members, and the family
+/// once none remain), `doc` (every run for that doc's twin), or `all`.
 #[Command(default)]
 pub struct DeleteExperiment {
     pub experiment_id: Option<String>,
+    pub sweep_id: Option<String>,
     pub doc: Option<DocumentId>,
     pub all: bool,
 }
@@ -2220,6 +2549,7 @@ pub(crate) fn purge_experiment_side_state(
 #[on_command(DeleteExperiment)]
 pub fn on_delete_experiment(trigger: On<DeleteExperiment>, mut commands: Commands) {
     let target = trigger.event().experiment_id.clone();
+    let sweep = trigger.event().sweep_id.clone();
     let doc = trigger.event().doc;
     let all = trigger.event().all;
     commands.queue(move |world: &mut World| {
@@ -2227,6 +2557,8 @@ pub fn on_delete_experiment(trigger: On<DeleteExperiment>, mut commands: Command
             .get_resource::<lunco_doc_bevy::JournalResource>()
             .cloned();
         let mut reg = world.resource_mut::<lunco_experiments::ExperimentRegistry>();
+        let sweeps_before: std::collections::HashSet<lunco_experiments::SweepId> =
+            reg.iter_sweeps().map(|s| s.id).collect();
         // Snapshot ids before deletion so we can compute exactly which runs
         // were removed and purge their side-state (doc mapping + per-plot
         // visibility), matching the UI delete path.
@@ -2250,6 +2582,14 @@ pub fn on_delete_experiment(trigger: On<DeleteExperiment>, mut commands: Command
                     removed += 1;
                 }
             }
+        } else if let Some(t) = sweep.as_deref() {
+            let id = reg
+                .iter_sweeps()
+                .find(|s| s.id.0.to_string() == t)
+                .map(|s| s.id);
+            if let Some(id) = id {
+                removed = reg.delete_sweep(id).len();
+            }
         } else if let Some(doc) = doc {
             let twin = crate::ui::doc_pin::twin_id_for_doc(doc);
             removed = reg.delete_for_twin(&twin);
@@ -2259,17 +2599,27 @@ pub fn on_delete_experiment(trigger: On<DeleteExperiment>, mut commands: Command
             reg.iter_all().map(|e| e.id).collect();
         let purged: Vec<lunco_experiments::ExperimentId> =
             before.difference(&live).copied().collect();
+        let sweeps_live: std::collections::HashSet<lunco_experiments::SweepId> =
+            reg.iter_sweeps().map(|s| s.id).collect();
         // Journal each removal (Delete) so the deletion syncs + persists. Done
         // after the registry mutation (which keeps its counter cleanup); replay
-        // is idempotent.
+        // is idempotent. A family that emptied goes after its members.
         if let Some(journal) = &journal {
             for id in &purged {
                 crate::experiment_journal::record_delete(journal, *id);
             }
+            for id in sweeps_before.difference(&sweeps_live) {
+                crate::experiment_journal::record_delete_sweep(journal, *id);
+            }
         }
+        // Drop them from the twin's experiment store too, or they would come
+        // back on the next open. Before the purge: the store finds a run's
+        // twin folder through its document mapping.
+        crate::experiment_store::forget_runs(world, &purged);
         crate::ui::commands::compile::purge_experiment_side_state(world, &purged);
         bevy::log::info!(
-            "[DeleteExperiment] removed {removed} run(s) (all={all}, id={target:?}, doc={doc:?})"
+            "[DeleteExperiment] removed {removed} run(s) (all={all}, id={target:?}, \
+             sweep={sweep:?}, doc={doc:?})"
         );
     });
 }
@@ -2307,12 +2657,240 @@ pub fn on_rename_experiment(trigger: On<RenameExperiment>, mut commands: Command
                     crate::experiment_journal::ExperimentOp::SetName { id, name },
                 );
                 bevy::log::info!("[RenameExperiment] {target} → renamed");
+                crate::experiment_store::refresh_stored(world, id);
             }
             None => bevy::log::warn!("[RenameExperiment] no run with id {target}"),
         }
     });
 }
 
+/// Bring a stored run back into a document's run list — for runs older than
+/// what reopening the document restores (find them with `SearchExperiments`).
+/// Its trajectory loads on first use.
+#[Command(default)]
+pub struct RestoreExperiment {
+    /// Target document. Unassigned → the active document.
+    pub doc: DocumentId,
+    /// Stored run id (uuid string).
+    pub experiment_id: String,
+}
+
+#[on_command(RestoreExperiment)]
+pub fn on_restore_experiment(trigger: On<RestoreExperiment>, mut commands: Commands) {
+    let raw = trigger.event().doc;
+    let target = trigger.event().experiment_id.clone();
+    commands.queue(move |world: &mut World| {
+        let Some(doc) = resolve_doc_or_active(world, raw) else {
+            bevy::log::warn!("[RestoreExperiment] no document to restore into");
+            return;
+        };
+        let Some(id) = lunco_experiments::ExperimentId::from_artifact_stem(target.trim()) else {
+            bevy::log::warn!("[RestoreExperiment] `{target}` is not a run id");
+            return;
+        };
+        match crate::experiment_store::restore_run(world, doc, id) {
+            Ok(()) => bevy::log::info!("[RestoreExperiment] {target} restored"),
+            Err(why) => bevy::log::warn!("[RestoreExperiment] {target}: {why}"),
+        }
+    });
+}
+
+/// Write a finished run's trajectory to a file — `csv`, `mat` (MAT v4, the
+/// Dymola / OpenModelica layout), `mat73` or `hdf5` (the last two need the
+/// `hdf5` feature). The headless twin of the Experiments panel's Export
+/// entries, which ask for the path with a save dialog instead.
+#[Command(default)]
+pub struct ExportExperiment {
+    /// Run id (uuid string).
+    pub experiment_id: String,
+    /// Format spelling; empty → taken from the path's extension.
+    pub format: String,
+    /// Destination file.
+    pub path: String,
+}
+
+#[on_command(ExportExperiment)]
+pub fn on_export_experiment(trigger: On<ExportExperiment>, mut commands: Commands) {
+    let ev = trigger.event();
+    let (target, format, path) = (ev.experiment_id.clone(), ev.format.clone(), ev.path.clone());
+    commands.queue(move |world: &mut World| {
+        use crate::result_files::ResultExportFormat;
+        if path.trim().is_empty() {
+            bevy::log::warn!("[ExportExperiment] no path given");
+            return;
+        }
+        let path = std::path::PathBuf::from(path.trim());
+        let spelled = if format.trim().is_empty() {
+            path.extension()
+                .map(|e| e.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        } else {
+            format
+        };
+        let Some(format) = ResultExportFormat::parse(&spelled) else {
+            bevy::log::warn!("[ExportExperiment] unknown format `{spelled}`");
+            return;
+        };
+        let Some(id) = lunco_experiments::ExperimentId::from_artifact_stem(target.trim()) else {
+            bevy::log::warn!("[ExportExperiment] `{target}` is not a run id");
+            return;
+        };
+        crate::experiment_store::ensure_series(world, id);
+        let result = world
+            .resource::<lunco_experiments::ExperimentRegistry>()
+            .get(id)
+            .and_then(|e| e.result.clone());
+        let Some(result) = result else {
+            bevy::log::warn!("[ExportExperiment] {target} has no result");
+            return;
+        };
+        match crate::result_files::write_result(&path, &result, format) {
+            Ok(()) => bevy::log::info!(
+                "[ExportExperiment] {target} → {} ({})",
+                path.display(),
+                format.label()
+            ),
+            Err(why) => bevy::log::warn!("[ExportExperiment] {target}: {why}"),
+        }
+    });
+}
+
+/// Import a MAT v4 / MAT v7.3 / HDF5 (or CSV) trajectory (Dymola, OpenModelica, or
+/// our own export) as a read-only reference run of a document, to overlay
+/// on its simulated runs. An empty `path` opens a file dialog first.
+#[Command(default)]
+pub struct ImportReferenceRun {
+    /// Target document. Unassigned → the active document.
+    pub doc: DocumentId,
+    /// Result file to read; empty → pick one.
+    pub path: String,
+    /// Display name; empty → the file name.
+    pub name: String,
+}
+
+#[on_command(ImportReferenceRun)]
+pub fn on_import_reference_run(trigger: On<ImportReferenceRun>, mut commands: Commands) {
+    let ev = trigger.event();
+    let (raw, path, name) = (ev.doc, ev.path.clone(), ev.name.clone());
+    commands.queue(move |world: &mut World| {
+        let Some(doc) = resolve_doc_or_active(world, raw) else {
+            bevy::log::warn!("[ImportReferenceRun] no document to import into");
+            return;
+        };
+        if path.trim().is_empty() {
+            world.trigger(lunco_workbench::picker::PickHandle {
+                mode: lunco_workbench::picker::PickMode::OpenFile(
+                    lunco_workbench::picker::OpenFilter::new(
+                        "Result files",
+                        &["mat", "h5", "hdf5", "csv"],
+                    ),
+                ),
+                on_resolved: lunco_workbench::picker::PickFollowUp::ImportResult(doc),
+            });
+            return;
+        }
+        let path = std::path::PathBuf::from(path.trim());
+        let outcome = crate::result_files::import_reference(world, doc, &path, Some(name.clone()));
+        let mut console = world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>();
+        match outcome {
+            Ok(_) => {
+                bevy::log::info!("[ImportReferenceRun] {} imported", path.display());
+                if let Some(console) = console.as_mut() {
+                    console.info(format!("📎 Imported reference run from {}", path.display()));
+                }
+            }
+            Err(why) => {
+                bevy::log::warn!("[ImportReferenceRun] {}: {why}", path.display());
+                if let Some(console) = console.as_mut() {
+                    console.error(format!("Import {}: {why}", path.display()));
+                }
+            }
+        }
+    });
+}
+
+/// Run a regression suite (see `lunco_modelica::regression`) on a background
+/// thread: each case is simulated and its signals checked against the stored
+/// reference through tolerance tubes. The JUnit XML and HTML reports land
+/// next to the suite unless paths are given; the verdict is logged. Native
+/// only — the CLI twin is `modelica_regress`.
+#[Command(default)]
+pub struct RunRegressionSuite {
+    /// Suite file (JSON).
+    pub path: String,
+    /// JUnit XML report; empty → `<suite>.junit.xml` beside the suite.
+    pub junit: String,
+    /// HTML report; empty → `<suite>.html` beside the suite.
+    pub html: String,
+    /// Write each run over its reference instead of checking it.
+    pub bless: bool,
+}
+
+#[on_command(RunRegressionSuite)]
+pub fn on_run_regression_suite(trigger: On<RunRegressionSuite>) {
+    let ev = trigger.event().clone();
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let suite = std::path::PathBuf::from(ev.path.trim());
+        let beside = |ext: &str, given: &str| {
+            if given.trim().is_empty() {
+                suite.with_extension(ext)
+            } else {
+                std::path::PathBuf::from(given.trim())
+            }
+        };
+        let junit = beside("junit.xml", &ev.junit);
+        let html = beside("html", &ev.html);
+        let bless = ev.bless;
+        let _ = std::thread::Builder::new()
+            .name("regression-suite".into())
+            .spawn(move || {
+                let outcome = crate::regression::run_suite(&suite, bless).and_then(|report| {
+                    crate::regression::write_reports(&report, Some(&junit), Some(&html))?;
+                    Ok(report)
+                });
+                match outcome {
+                    Ok(report) => {
+                        let (checked, failed, errored) = report.counts();
+                        bevy::log::info!(
+                            "[RunRegressionSuite] {}: {checked} signal(s), {failed} failed, \
+                             {errored} error(s) — {} (report: {})",
+                            report.name,
+                            if report.passed() { "PASS" } else { "FAIL" },
+                            html.display()
+                        );
+                    }
+                    Err(why) => bevy::log::warn!("[RunRegressionSuite] {why}"),
+                }
+            });
+    }
+    #[cfg(target_arch = "wasm32")]
+    bevy::log::warn!(
+        "[RunRegressionSuite] {}: regression suites need the native build",
+        ev.path
+    );
+}
+
+/// Picker follow-up for [`ImportReferenceRun`]: the workbench leaves
+/// `ImportResult` to us, so re-fire the command with the chosen path.
+fn on_import_result_picked(
+    trigger: On<lunco_workbench::picker::PickResolved>,
+    mut commands: Commands,
+) {
+    let ev = trigger.event();
+    let lunco_workbench::picker::PickFollowUp::ImportResult(doc) = ev.follow_up else {
+        return;
+    };
+    let Some(path) = ev.handle.as_file_path() else {
+        return;
+    };
+    commands.trigger(ImportReferenceRun {
+        doc,
+        path: path.display().to_string(),
+        name: String::new(),
+    });
+}
+
 #[on_command(ResetActiveModel)]
 pub fn on_reset_active_model(trigger: On<ResetActiveModel>, mut commands: Commands) {
     let raw = trigger.event().doc;
@@ -2364,6 +2942,7 @@ impl Plugin for CompileCommandsPlugin {
                 bevy_egui::EguiPrimaryContextPass,
                 (render_compile_class_picker, render_fast_run_setup),
             );
+        app.add_observer(on_import_result_picked);
         register_all_commands(app);
     }
 }
@@ -2380,7 +2959,12 @@ register_commands!(
     on_fast_run_active_model,
     on_confirm_class_picker,
     on_run_experiment,
+    on_run_sweep,
     on_cancel_experiment,
     on_delete_experiment,
     on_rename_experiment,
+    on_restore_experiment,
+    on_export_experiment,
+    on_import_reference_run,
+    on_run_regression_suite,
 );
@@ -0,0 +1,403 @@
+//! `EstimateParameters` — fit a document's model parameters to measured data
+//! (see [`crate::estimation`]): a reference or finished run, or the retained
+//! telemetry history.
+//!
+//! The compile and the fit run on a background thread; finished fits come
+//! back over a channel, land in [`Estimations`] (where `GetEstimation` reads
+//! them) and are filed as an ordinary run at the fitted values, so the fit
+//! overlays the measurement in the Experiments panel and plots.
+
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use lunco_core::{on_command, Command};
+use lunco_doc::DocumentId;
+use lunco_experiments::estimation::measured_from_samples;
+use lunco_experiments::{
+    EstimatedParam, EstimationReport, EstimationSpec, FitSignal, Optimizer, ParamPath, ParamValue,
+    RunBounds, RunResult,
+};
+
+use super::resolve_doc_or_active;
+
+/// `measured` value selecting the telemetry history instead of a run.
+const TELEMETRY: &str = "telemetry";
+
+/// Fit parameters so the model reproduces measured signals.
+#[Command(default)]
+pub struct EstimateParameters {
+    /// Target document. Unassigned → the active document.
+    pub doc: DocumentId,
+    /// Target class. `None` → drilled-in class or the default simulation class.
+    pub class: Option<String>,
+    /// The measurement: a run id (uuid string — an imported reference or a
+    /// finished run), or `telemetry` for the retained telemetry history, whose
+    /// channels the signals' `measured` names select by path.
+    pub measured: String,
+    /// Parameters to fit `[{name, lower, upper, initial}]`.
+    pub parameters: Vec<crate::api::ApiEstimatedParam>,
+    /// Signals the fit is judged on `[{name, measured, weight}]`.
+    pub signals: Vec<crate::api::ApiFitSignal>,
+    /// `nelder_mead` (default), `lbfgsb` or `cma_es`.
+    pub optimizer: String,
+    /// Run budget for the search. `None` → 400.
+    pub max_evaluations: Option<u32>,
+    /// Seed for CMA-ES draws.
+    pub seed: Option<u64>,
+    /// Measured time minus model time — where the model's `t = 0` falls on
+    /// the measurement's clock.
+    pub time_offset: f64,
+    /// Parameter overrides `[{name, value}]` held fixed under the fit.
+    pub overrides: Vec<crate::api::ApiModification>,
+    /// Write the fit report here; `.html` or `.json` by extension. Empty →
+    /// none.
+    pub report: String,
+}
+
+/// One finished fit.
+#[derive(Clone, Debug)]
+pub struct EstimationEntry {
+    /// Sequence number, 1-based, in request order.
+    pub id: u64,
+    pub doc: DocumentId,
+    pub model: String,
+    pub outcome: Result<EstimationReport, String>,
+    /// The run filed at the fitted values, once drained.
+    pub experiment: Option<lunco_experiments::ExperimentId>,
+}
+
+/// A fit on its way back from its thread, with what filing its run needs.
+struct Finished {
+    entry: EstimationEntry,
+    bounds: RunBounds,
+    overrides: BTreeMap<ParamPath, ParamValue>,
+}
+
+/// Finished fits, newest last, and the channel the background threads
+/// report on.
+#[derive(Resource)]
+pub struct Estimations {
+    pub entries: Vec<EstimationEntry>,
+    next_id: u64,
+    tx: crossbeam_channel::Sender<Finished>,
+    rx: crossbeam_channel::Receiver<Finished>,
+}
+
+impl Default for Estimations {
+    fn default() -> Self {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        Self {
+            entries: Vec::new(),
+            next_id: 1,
+            tx,
+            rx,
+        }
+    }
+}
+
+impl Estimations {
+    /// The newest entry, for `doc` when given.
+    pub fn latest(&self, doc: Option<DocumentId>) -> Option<&EstimationEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| doc.is_none_or(|d| e.doc == d))
+    }
+}
+
+fn spec_from_command(ev: &EstimateParameters) -> Result<EstimationSpec, String> {
+    let optimizer = Optimizer::from_token(&ev.optimizer).ok_or_else(|| {
+        format!(
+            "unknown optimizer `{}` (nelder_mead, lbfgsb, cma_es)",
+            ev.optimizer
+        )
+    })?;
+    let defaults = EstimationSpec::default();
+    Ok(EstimationSpec {
+        parameters: ev
+            .parameters
+            .iter()
+            .map(|p| EstimatedParam {
+                path: ParamPath(p.name.clone()),
+                lower: p.lower,
+                upper: p.upper,
+                initial: p.initial,
+            })
+            .collect(),
+        signals: ev
+            .signals
+            .iter()
+            .map(|s| FitSignal {
+                name: s.name.clone(),
+                measured: s.measured.clone().filter(|m| !m.trim().is_empty()),
+                weight: s.weight.unwrap_or(1.0),
+            })
+            .collect(),
+        optimizer,
+        max_evaluations: ev
+            .max_evaluations
+            .map_or(defaults.max_evaluations, |n| n as usize),
+        seed: ev.seed.unwrap_or_default(),
+        time_offset: ev.time_offset,
+        ..defaults
+    })
+}
+
+/// The measured data: a run's trajectory, or the telemetry channels the
+/// spec's signals name.
+fn measured_data(
+    world: &mut World,
+    measured: &str,
+    spec: &EstimationSpec,
+) -> Result<RunResult, String> {
+    let measured = measured.trim();
+    if measured.eq_ignore_ascii_case(TELEMETRY) {
+        return telemetry_data(world, spec);
+    }
+    let Some(id) = lunco_experiments::ExperimentId::from_artifact_stem(measured) else {
+        return Err(format!(
+            "`{measured}` is neither a run id nor `{TELEMETRY}`"
+        ));
+    };
+    crate::experiment_store::ensure_series(world, id);
+    world
+        .resource::<lunco_experiments::ExperimentRegistry>()
+        .get(id)
+        .and_then(|e| e.result.clone())
+        .ok_or_else(|| format!("run {measured} has no result"))
+}
+
+fn telemetry_data(world: &World, spec: &EstimationSpec) -> Result<RunResult, String> {
+    let Some(signals) = world.get_resource::<lunco_signal::SignalRegistry>() else {
+        return Err("no telemetry is being recorded".into());
+    };
+    let mut channels = Vec::new();
+    for wanted in spec.signals.iter().map(FitSignal::measured_name) {
+        let mut matches = signals.iter_scalar().filter(|(s, _)| s.path == wanted);
+        let Some((_, history)) = matches.next() else {
+            return Err(format!("no telemetry channel `{wanted}`"));
+        };
+        if matches.next().is_some() {
+            return Err(format!(
+                "telemetry channel `{wanted}` is recorded for more than one entity"
+            ));
+        }
+        let samples = history.iter().map(|s| (s.time, s.value)).collect();
+        channels.push((wanted.to_string(), samples));
+    }
+    Ok(measured_from_samples(channels))
+}
+
+#[on_command(EstimateParameters)]
+pub fn on_estimate_parameters(trigger: On<EstimateParameters>, mut commands: Commands) {
+    let ev = trigger.event().clone();
+    commands.queue(move |world: &mut World| {
+        let Some(doc) = resolve_doc_or_active(world, ev.doc) else {
+            bevy::log::warn!("[EstimateParameters] no active document");
+            return;
+        };
+        let spec = match spec_from_command(&ev) {
+            Ok(spec) => spec,
+            Err(why) => {
+                bevy::log::warn!("[EstimateParameters] {why}");
+                return;
+            }
+        };
+        let measured = match measured_data(world, &ev.measured, &spec)
+            .and_then(|m| spec.validate(&m).map(|()| m))
+        {
+            Ok(m) => m,
+            Err(why) => {
+                bevy::log::warn!("[EstimateParameters] {why}");
+                return;
+            }
+        };
+        let (source, experiment) =
+            match super::compile::batch_model_source(world, doc, ev.class.as_deref()) {
+                Ok(found) => found,
+                Err(why) => {
+                    bevy::log::warn!("[EstimateParameters] {why}");
+                    return;
+                }
+            };
+        let bounds = experiment
+            .as_ref()
+            .and_then(crate::sim_target::bounds_from_experiment)
+            .unwrap_or_else(crate::sim_target::default_bounds);
+        let overrides = super::compile::param_map_from_mods(&ev.overrides);
+        let Some(mut est) = world.get_resource_mut::<Estimations>() else {
+            return;
+        };
+        let id = est.next_id;
+        est.next_id += 1;
+        let tx = est.tx.clone();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(runner) = world
+                .get_resource::<crate::ModelicaRunnerResource>()
+                .map(|r| r.0.clone())
+            else {
+                bevy::log::error!("[EstimateParameters] runner resource missing");
+                return;
+            };
+            bevy::log::info!(
+                "[EstimateParameters] {}: fitting {} parameter(s) to {} signal(s) with {}",
+                source.model_name,
+                spec.parameters.len(),
+                spec.signals.len(),
+                spec.optimizer.label()
+            );
+            let report_path = ev.report.trim().to_string();
+            let _ = std::thread::Builder::new()
+                .name("estimate".into())
+                .spawn(move || {
+                    let model = source.model_name.clone();
+                    let outcome = runner.compile(&source).and_then(|dae| {
+                        let report = crate::estimation::estimate_model(
+                            &dae, &bounds, &overrides, &model, &spec, &measured,
+                        )?;
+                        if !report_path.is_empty() {
+                            crate::estimation::write_report(
+                                std::path::Path::new(&report_path),
+                                &report,
+                            )?;
+                        }
+                        Ok(report)
+                    });
+                    let entry = EstimationEntry {
+                        id,
+                        doc,
+                        model,
+                        outcome,
+                        experiment: None,
+                    };
+                    let _ = tx.send(Finished {
+                        entry,
+                        bounds,
+                        overrides,
+                    });
+                });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (source, spec, measured);
+            let entry = EstimationEntry {
+                id,
+                doc,
+                model: String::new(),
+                outcome: Err("parameter estimation needs the native build".into()),
+                experiment: None,
+            };
+            let _ = tx.send(Finished {
+                entry,
+                bounds,
+                overrides,
+            });
+        }
+    });
+}
+
+/// File finished fits, report them, and add each fitted run to its
+/// document's runs.
+pub(super) fn drain_estimations(world: &mut World) {
+    let finished: Vec<_> = match world.get_resource::<Estimations>() {
+        Some(est) => est.rx.try_iter().collect(),
+        None => return,
+    };
+    for Finished {
+        mut entry,
+        bounds,
+        mut overrides,
+    } in finished
+    {
+        let mut fitted = None;
+        match &mut entry.outcome {
+            Ok(report) => {
+                for p in &report.parameters {
+                    bevy::log::info!(
+                        "[EstimateParameters] {}: {} = {:.6e}{}{}",
+                        entry.model,
+                        p.path.0,
+                        p.value,
+                        p.std_error
+                            .map(|se| format!(" ± {se:.2e}"))
+                            .unwrap_or_default(),
+                        if p.at_bound { " (at bound)" } else { "" }
+                    );
+                }
+                bevy::log::info!(
+                    "[EstimateParameters] {}: cost {:.4e} → {:.4e} in {} runs{}",
+                    entry.model,
+                    report.cost_initial,
+                    report.cost,
+                    report.evaluations,
+                    if report.converged {
+                        ""
+                    } else {
+                        " (run budget exhausted)"
+                    }
+                );
+                if let Some(mut console) =
+                    world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
+                {
+                    console.info(format!(
+                        "🎯 Fitted {} parameter(s) of {} ({})",
+                        report.parameters.len(),
+                        entry.model,
+                        report.optimizer.label()
+                    ));
+                }
+                if let Some(run) = report.run.take() {
+                    overrides.extend(report.overrides());
+                    fitted = Some((format!("Fit ({})", report.optimizer.label()), run));
+                }
+            }
+            Err(why) => {
+                bevy::log::warn!("[EstimateParameters] {}: {why}", entry.model);
+                if let Some(mut console) =
+                    world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
+                {
+                    console.error(format!("Estimate {}: {why}", entry.model));
+                }
+            }
+        }
+        if let Some((label, run)) = fitted {
+            entry.experiment = Some(file_fitted_run(
+                world, &entry, label, overrides, bounds, run,
+            ));
+        }
+        world.resource_mut::<Estimations>().entries.push(entry);
+    }
+}
+
+/// Add the run at the fitted values as a finished experiment of the
+/// document.
+fn file_fitted_run(
+    world: &mut World,
+    entry: &EstimationEntry,
+    label: String,
+    overrides: BTreeMap<ParamPath, ParamValue>,
+    bounds: RunBounds,
+    run: RunResult,
+) -> lunco_experiments::ExperimentId {
+    use crate::experiments_runner::{twin_id_for_doc, ExperimentSources};
+    let wall_time_ms = run.meta.wall_time_ms;
+    let mut registry = world.resource_mut::<lunco_experiments::ExperimentRegistry>();
+    let id = registry.insert_new(
+        twin_id_for_doc(entry.doc),
+        lunco_experiments::ModelRef(entry.model.clone()),
+        overrides,
+        BTreeMap::new(),
+        bounds,
+    );
+    registry.set_name(id, label);
+    registry.set_result(id, run);
+    registry.set_status(id, lunco_experiments::RunStatus::Done { wall_time_ms });
+    world
+        .resource_mut::<ExperimentSources>()
+        .0
+        .insert(id, entry.doc);
+    world.write_message(lunco_experiments::RunCompleted { experiment_id: id });
+    id
+}
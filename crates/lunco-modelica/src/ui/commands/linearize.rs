@@ -0,0 +1,307 @@
+//! `LinearizeModel` — state-space `(A, B, C, D)` of a document's model around
+//! an operating point (see [`crate::linearize`]), opened as Bode / Nyquist /
+//! pole-zero plots and optionally exported as JSON or MAT.
+//!
+//! The compile and the Jacobians run on a background thread; finished
+//! linearizations come back over a channel, land in [`Linearizations`]
+//! (where `GetLinearization` reads them) and open their plot tabs.
+
+use bevy::prelude::*;
+use lunco_core::{on_command, Command};
+use lunco_doc::DocumentId;
+use lunco_experiments::linear::StateSpace;
+
+use super::resolve_doc_or_active;
+
+/// Linearize a model around an operating point. The operating point is the
+/// state reached by simulating to `time` (the start state when unset), or —
+/// with `steady_state` — an equilibrium `ẋ = 0` with the inputs held.
+#[Command(default)]
+pub struct LinearizeModel {
+    /// Target document. Unassigned → the active document.
+    pub doc: DocumentId,
+    /// Target class. `None` → drilled-in class or the default simulation class.
+    pub class: Option<String>,
+    /// Operating time in seconds. `None` → the `experiment` `StartTime`.
+    pub time: Option<f64>,
+    /// Linearize at a steady state instead of the simulated state.
+    pub steady_state: bool,
+    /// Inputs `u` of the linear model. Empty → every top-level input.
+    pub inputs: Vec<String>,
+    /// Outputs `y`, any variable. Empty → the top-level outputs (the states
+    /// when the model declares none).
+    pub outputs: Vec<String>,
+    /// Input values held at the operating point `[{name, value}]`.
+    pub input_values: Vec<crate::api::ApiModification>,
+    /// Parameter overrides `[{name, value}]`, applied as for a run.
+    pub overrides: Vec<crate::api::ApiModification>,
+    /// Finite-difference scheme: `central` (default), `forward`, `richardson`.
+    pub jacobian: String,
+    /// Write the matrices here; `.json` or `.mat` by extension. Empty → none.
+    pub export: String,
+    /// Plot kinds to open, comma-separated from `bode`, `nyquist`,
+    /// `pole_zero`. Empty → `bode,pole_zero`; `none` → no plots.
+    pub plots: String,
+}
+
+/// One finished linearization.
+#[derive(Clone, Debug)]
+pub struct LinearizationEntry {
+    /// Sequence number, 1-based, in request order.
+    pub id: u64,
+    pub doc: DocumentId,
+    pub model: String,
+    pub outcome: Result<StateSpace, String>,
+}
+
+/// Finished linearizations, newest last, and the channel the background
+/// threads report on.
+#[derive(Resource)]
+pub struct Linearizations {
+    pub entries: Vec<LinearizationEntry>,
+    next_id: u64,
+    tx: crossbeam_channel::Sender<(LinearizationEntry, Vec<lunco_viz::VizKindId>)>,
+    rx: crossbeam_channel::Receiver<(LinearizationEntry, Vec<lunco_viz::VizKindId>)>,
+}
+
+impl Default for Linearizations {
+    fn default() -> Self {
+        let (tx, rx) = crossbeam_channel::unbounded();
+        Self {
+            entries: Vec::new(),
+            next_id: 1,
+            tx,
+            rx,
+        }
+    }
+}
+
+impl Linearizations {
+    /// The newest entry, for `doc` when given.
+    pub fn latest(&self, doc: Option<DocumentId>) -> Option<&LinearizationEntry> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|e| doc.is_none_or(|d| e.doc == d))
+    }
+}
+
+fn plot_kinds(spec: &str) -> Result<Vec<lunco_viz::VizKindId>, String> {
+    let spec = spec.trim();
+    if spec.eq_ignore_ascii_case("none") {
+        return Ok(Vec::new());
+    }
+    let spec = if spec.is_empty() {
+        "bode,pole_zero"
+    } else {
+        spec
+    };
+    spec.split(',')
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .map(|t| match t.to_ascii_lowercase().as_str() {
+            "bode" => Ok(crate::ui::linear_plots::BODE_PLOT_KIND),
+            "nyquist" => Ok(crate::ui::linear_plots::NYQUIST_PLOT_KIND),
+            "pole_zero" | "pzmap" => Ok(crate::ui::linear_plots::POLE_ZERO_PLOT_KIND),
+            other => Err(format!(
+                "unknown plot `{other}` (bode, nyquist, pole_zero, none)"
+            )),
+        })
+        .collect()
+}
+
+#[on_command(LinearizeModel)]
+pub fn on_linearize_model(trigger: On<LinearizeModel>, mut commands: Commands) {
+    let ev = trigger.event().clone();
+    commands.queue(move |world: &mut World| {
+        let Some(doc) = resolve_doc_or_active(world, ev.doc) else {
+            bevy::log::warn!("[LinearizeModel] no active document");
+            return;
+        };
+        let Some(jacobian) = lunco_experiments::linear::JacobianMethod::from_token(&ev.jacobian)
+        else {
+            bevy::log::warn!(
+                "[LinearizeModel] unknown jacobian `{}` (central, forward, richardson)",
+                ev.jacobian
+            );
+            return;
+        };
+        let plots = match plot_kinds(&ev.plots) {
+            Ok(p) => p,
+            Err(why) => {
+                bevy::log::warn!("[LinearizeModel] {why}");
+                return;
+            }
+        };
+        let (source, experiment) =
+            match super::compile::batch_model_source(world, doc, ev.class.as_deref()) {
+                Ok(found) => found,
+                Err(why) => {
+                    bevy::log::warn!("[LinearizeModel] {why}");
+                    return;
+                }
+            };
+        let mut input_values = Vec::new();
+        for m in &ev.input_values {
+            match m.value.trim().parse::<f64>() {
+                Ok(v) => input_values.push((m.name.clone(), v)),
+                Err(_) => {
+                    bevy::log::warn!(
+                        "[LinearizeModel] input `{}`: `{}` is not a number",
+                        m.name,
+                        m.value
+                    );
+                    return;
+                }
+            }
+        }
+        let t_start = experiment.and_then(|e| e.start_time).unwrap_or(0.0);
+        let spec = crate::linearize::LinearizeSpec {
+            t_start,
+            time: ev.time.unwrap_or(t_start),
+            steady_state: ev.steady_state,
+            inputs: ev.inputs.clone(),
+            outputs: ev.outputs.clone(),
+            input_values,
+            jacobian,
+        };
+        let Some(mut lin) = world.get_resource_mut::<Linearizations>() else {
+            return;
+        };
+        let id = lin.next_id;
+        lin.next_id += 1;
+        let tx = lin.tx.clone();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let Some(runner) = world
+                .get_resource::<crate::ModelicaRunnerResource>()
+                .map(|r| r.0.clone())
+            else {
+                bevy::log::error!("[LinearizeModel] runner resource missing");
+                return;
+            };
+            let overrides = super::compile::param_map_from_mods(&ev.overrides);
+            let export = ev.export.trim().to_string();
+            let _ = std::thread::Builder::new()
+                .name("linearize".into())
+                .spawn(move || {
+                    let model = source.model_name.clone();
+                    let outcome = runner.compile(&source).and_then(|dae| {
+                        let mut dae = (*dae).clone();
+                        crate::experiments_runner::apply_value_bindings_to_dae(
+                            &mut dae, &overrides,
+                        )?;
+                        let system = crate::linearize::linearize(&dae, &model, &spec)?;
+                        if !export.is_empty() {
+                            crate::linearize::write_state_space(
+                                std::path::Path::new(&export),
+                                &system,
+                            )?;
+                        }
+                        Ok(system)
+                    });
+                    let entry = LinearizationEntry {
+                        id,
+                        doc,
+                        model,
+                        outcome,
+                    };
+                    let _ = tx.send((entry, plots));
+                });
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            let _ = (source, spec, plots);
+            let entry = LinearizationEntry {
+                id,
+                doc,
+                model: String::new(),
+                outcome: Err("linearization needs the native build".into()),
+            };
+            let _ = tx.send((entry, Vec::new()));
+        }
+    });
+}
+
+/// File finished linearizations, report them, and open their plots.
+pub(super) fn drain_linearizations(world: &mut World) {
+    let finished: Vec<_> = match world.get_resource::<Linearizations>() {
+        Some(lin) => lin.rx.try_iter().collect(),
+        None => return,
+    };
+    for (entry, plots) in finished {
+        match &entry.outcome {
+            Ok(system) => {
+                let op = &system.operating_point;
+                bevy::log::info!(
+                    "[LinearizeModel] {}: {} state(s), {} input(s), {} output(s) at {} \
+                     (max |ẋ| = {:.2e})",
+                    entry.model,
+                    system.states.len(),
+                    system.inputs.len(),
+                    system.outputs.len(),
+                    if op.steady_state {
+                        "steady state".to_string()
+                    } else {
+                        format!("t = {}", op.time)
+                    },
+                    op.residual
+                );
+                if let Some(mut console) =
+                    world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
+                {
+                    console.info(format!(
+                        "📐 Linearized {} ({} states)",
+                        entry.model,
+                        system.states.len()
+                    ));
+                }
+                if !system.inputs.is_empty() && !system.outputs.is_empty() {
+                    open_plots(world, system, &plots);
+                }
+            }
+            Err(why) => {
+                bevy::log::warn!("[LinearizeModel] {}: {why}", entry.model);
+                if let Some(mut console) =
+                    world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
+                {
+                    console.error(format!("Linearize {}: {why}", entry.model));
+                }
+            }
+        }
+        world.resource_mut::<Linearizations>().entries.push(entry);
+    }
+}
+
+fn open_plots(world: &mut World, system: &StateSpace, kinds: &[lunco_viz::VizKindId]) {
+    use lunco_viz::{view::ViewTarget, viz::VisualizationConfig, VisualizationRegistry};
+    let style = crate::ui::linear_plots::LinearSystemStyle {
+        system: system.clone(),
+        ..Default::default()
+    }
+    .to_value();
+    for kind in kinds {
+        let what = if *kind == crate::ui::linear_plots::BODE_PLOT_KIND {
+            "Bode"
+        } else if *kind == crate::ui::linear_plots::NYQUIST_PLOT_KIND {
+            "Nyquist"
+        } else {
+            "Pole-zero"
+        };
+        let mut registry = world.resource_mut::<VisualizationRegistry>();
+        let id = registry.allocate_id();
+        registry.insert(VisualizationConfig {
+            id,
+            title: format!("{what} — {}", system.model),
+            kind: kind.clone(),
+            view: ViewTarget::Panel2D,
+            inputs: Vec::new(),
+            style: style.clone(),
+        });
+        world.commands().trigger(lunco_workbench::OpenTab {
+            kind: lunco_viz::VIZ_PANEL_KIND,
+            instance: id.0,
+        });
+    }
+}
@@ -7,9 +7,11 @@ use lunco_doc::DocumentId;
 pub mod compile;
 pub mod diagram;
 pub mod doc;
+pub mod estimate;
 pub mod inspect;
 pub mod intent;
 pub mod lifecycle;
+pub mod linearize;
 pub mod nav;
 pub mod plot;
 pub mod sim;
@@ -30,12 +32,14 @@ pub use compile::{
 };
 pub use diagram::{AddCanvasPlot, MoveComponent};
 pub use doc::{FormatDocument, Redo, SaveActiveDocument, SaveActiveDocumentAs, Undo};
+pub use estimate::{EstimateParameters, EstimationEntry, Estimations};
 pub use inspect::InspectActiveDoc;
 pub use lifecycle::drain_open_file_results;
 pub use lifecycle::{
     ClassAction, CloseDialogState, CreateNewScratchModel, DuplicateModelFromReadOnly, GetFile,
     Open, OpenClass, OpenInNewView, PendingCloseAfterSave, PendingTabCloseScopes, TabCloseScope,
 };
+pub use linearize::{LinearizationEntry, Linearizations, LinearizeModel};
 pub use nav::{
     AutoArrangeDiagram, FitCanvas, FocusComponent, FocusDocumentByName, PanCanvas, SetViewMode,
     SetZoom,
@@ -53,6 +57,8 @@ impl Plugin for ModelicaCommandsPlugin {
             .init_resource::<PendingCloseAfterSave>()
             .init_resource::<PendingTabCloseScopes>()
             .init_resource::<lifecycle::AppCloseFlow>()
+            .init_resource::<Linearizations>()
+            .init_resource::<Estimations>()
             .add_observer(lifecycle::finish_close_after_save)
             .add_observer(lifecycle::on_document_closed_cleanup)
             .add_observer(crate::ui::uri_handler::on_modelica_uri_clicked)
@@ -77,6 +83,8 @@ impl Plugin for ModelicaCommandsPlugin {
                     status::publish_unsaved_modelica_docs,
                     lifecycle::on_window_close_requested,
                     lifecycle::finalize_app_close,
+                    linearize::drain_linearizations,
+                    estimate::drain_estimations,
                 ),
             )
             .add_systems(
@@ -99,11 +107,13 @@ register_commands!(
     lifecycle::on_close_document,
     lifecycle::on_create_new_scratch_model,
     lifecycle::on_duplicate_model_from_read_only,
+    estimate::on_estimate_parameters,
     nav::on_fit_canvas,
     nav::on_focus_component,
     nav::on_focus_document_by_name,
     doc::on_format_document,
     lifecycle::on_get_file,
+    linearize::on_linearize_model,
     inspect::on_inspect_active_doc,
     diagram::on_move_component,
     lifecycle::on_new_modelica_document,
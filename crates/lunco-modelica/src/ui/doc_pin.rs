@@ -48,7 +48,7 @@ impl DocPinState {
 /// instead of one mixed pile (different variable namespaces, no
 /// useful cross-comparison).
 pub fn twin_id_for_doc(doc: DocumentId) -> TwinId {
-    TwinId(format!("doc:{}", doc.raw()))
+    crate::experiments_runner::twin_id_for_doc(doc)
 }
 
 /// Active document from the workspace (most-recently-focused tab).
@@ -0,0 +1,83 @@
+//! `bode_plot` — magnitude (dB) and phase (°) of a linear model against
+//! frequency, two stacked charts sharing a log-frequency axis.
+//!
+//! Binds no signals; the model is a [`LinearSystemStyle`] in the config's
+//! style (see [`linear_plots`](super)).
+//!
+//! [`LinearSystemStyle`]: super::LinearSystemStyle
+
+use bevy_egui::egui;
+use egui_plot::{Line, Plot, PlotPoints};
+
+use lunco_viz::{Panel2DCtx, RoleSpec, ViewKind, Visualization, VisualizationConfig, VizKindId};
+
+pub const BODE_PLOT_KIND: VizKindId = VizKindId::new_static("bode_plot");
+
+#[derive(Default)]
+pub struct BodePlot;
+
+impl Visualization for BodePlot {
+    fn kind_id(&self) -> VizKindId {
+        BODE_PLOT_KIND
+    }
+    fn display_name(&self) -> &'static str {
+        "Bode plot (linear model)"
+    }
+    fn role_schema(&self) -> &'static [RoleSpec] {
+        &[]
+    }
+    fn compatible_views(&self) -> &'static [ViewKind] {
+        &[ViewKind::Panel2D]
+    }
+
+    fn render_panel_2d(&self, ctx: &mut Panel2DCtx, config: &VisualizationConfig) {
+        let Some(analysis) = super::header(ctx, config) else {
+            return;
+        };
+        let theme = lunco_theme::active(ctx.ui.ctx());
+        let color = lunco_viz::signal::color_for_signal(&theme, &analysis.pair_label());
+
+        // X is log10(ω); the tick formatter relabels marks as real rad/s.
+        let magnitude: Vec<[f64; 2]> = analysis
+            .response
+            .iter()
+            .map(|p| [p.omega.log10(), p.magnitude_db])
+            .collect();
+        let phase: Vec<[f64; 2]> = analysis
+            .response
+            .iter()
+            .map(|p| [p.omega.log10(), p.phase_deg])
+            .collect();
+
+        let size = ctx.ui.available_size_before_wrap();
+        let half = ((size.y - ctx.ui.spacing().item_spacing.y) / 2.0).max(40.0);
+        let link = egui::Id::new(("bode_link", config.id.raw()));
+        let label = analysis.pair_label();
+        let chart = |name: &'static str, unit: &'static str| {
+            Plot::new((name, config.id.raw()))
+                .width(size.x)
+                .height(half)
+                .link_axis(link, [true, false])
+                .link_cursor(link, [true, false])
+                .x_axis_formatter(|mark, _range| lunco_viz::plot_fmt::log_y_tick(mark.value))
+                .y_axis_label(unit)
+                .label_formatter(move |pos| {
+                    let point = match pos {
+                        egui_plot::HoverPosition::NearDataPoint { position, .. } => position,
+                        egui_plot::HoverPosition::Elsewhere { position } => position,
+                    };
+                    Some(format!(
+                        "ω = {} rad/s\n{:.3} {unit}",
+                        lunco_viz::plot_fmt::log_y_tick(point.x),
+                        point.y
+                    ))
+                })
+        };
+        chart("bode_magnitude", "dB").show(ctx.ui, |plot_ui| {
+            plot_ui.line(Line::new(label.clone(), PlotPoints::new(magnitude)).color(color));
+        });
+        chart("bode_phase", "°").show(ctx.ui, |plot_ui| {
+            plot_ui.line(Line::new(label, PlotPoints::new(phase)).color(color));
+        });
+    }
+}
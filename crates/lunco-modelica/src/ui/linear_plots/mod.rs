@@ -0,0 +1,218 @@
+//! Linear-model visualization kinds — [`bode_plot`], [`nyquist_plot`] and
+//! [`pole_zero_plot`] — and the plumbing they share. They live here rather
+//! than in `lunco-viz`, which stays domain-agnostic; [`register`] adds them to
+//! its catalog.
+//!
+//! These kinds plot a [`StateSpace`] rather than signal histories, so they
+//! bind no roles: the system itself rides in `VisualizationConfig.style` as
+//! [`LinearSystemStyle`], which keeps a saved workspace self-contained (the
+//! plot survives without the linearization that produced it). The style also
+//! picks the input/output pair a SISO view shows; the toolbar here edits it.
+
+pub mod bode_plot;
+pub mod nyquist_plot;
+pub mod pole_zero_plot;
+
+pub use bode_plot::{BodePlot, BODE_PLOT_KIND};
+pub use nyquist_plot::{NyquistPlot, NYQUIST_PLOT_KIND};
+pub use pole_zero_plot::{PoleZeroPlot, POLE_ZERO_PLOT_KIND};
+
+use std::sync::Arc;
+
+use bevy::prelude::*;
+use bevy_egui::egui;
+use lunco_experiments::linear::{log_frequencies, Complex, FrequencyPoint, StateSpace};
+use lunco_viz::{AppVizExt, Panel2DCtx, VisualizationConfig, VisualizationRegistry, VizId};
+use serde::{Deserialize, Serialize};
+
+/// Frequency points per response curve.
+const RESPONSE_POINTS: usize = 400;
+
+/// Register the three kinds and the pair-picker observer.
+pub fn register(app: &mut App) {
+    app.register_visualization::<BodePlot>()
+        .register_visualization::<NyquistPlot>()
+        .register_visualization::<PoleZeroPlot>()
+        .add_observer(on_linear_system_pair_requested);
+}
+
+/// Options stashed in [`VisualizationConfig::style`] for every linear-system
+/// kind.
+#[derive(Default, Debug, Clone, Serialize, Deserialize)]
+pub struct LinearSystemStyle {
+    pub system: StateSpace,
+    /// Index into `system.inputs`.
+    #[serde(default)]
+    pub input: usize,
+    /// Index into `system.outputs`.
+    #[serde(default)]
+    pub output: usize,
+    /// Frequency range, rad/s. `None` = a decade beyond the slowest and
+    /// fastest pole/zero.
+    #[serde(default)]
+    pub omega_min: Option<f64>,
+    #[serde(default)]
+    pub omega_max: Option<f64>,
+}
+
+impl LinearSystemStyle {
+    fn load(config: &VisualizationConfig) -> Self {
+        serde_json::from_value(config.style.clone()).unwrap_or_default()
+    }
+    fn save(&self, config: &mut VisualizationConfig) {
+        config.style = serde_json::to_value(self).unwrap_or(serde_json::Value::Null);
+    }
+    /// The style as a config blob, for hosts that open one of these plots.
+    pub fn to_value(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or(serde_json::Value::Null)
+    }
+}
+
+/// What the views draw, derived from the style once and cached until the
+/// style blob changes — an eigenvalue solve and a 400-point frequency sweep
+/// are too much to redo every frame.
+pub(crate) struct Analysis {
+    pub style: LinearSystemStyle,
+    pub response: Vec<FrequencyPoint>,
+    pub poles: Vec<Complex>,
+    pub zeros: Vec<Complex>,
+}
+
+impl Analysis {
+    fn of(style: LinearSystemStyle) -> Self {
+        let sys = &style.system;
+        let (lo, hi) = sys.frequency_range(style.input, style.output);
+        let omegas = log_frequencies(
+            style.omega_min.unwrap_or(lo),
+            style.omega_max.unwrap_or(hi),
+            RESPONSE_POINTS,
+        );
+        Self {
+            response: sys.frequency_response(style.input, style.output, &omegas),
+            poles: sys.poles(),
+            zeros: sys.zeros(style.input, style.output),
+            style,
+        }
+    }
+
+    /// `"u → y"` label of the selected pair.
+    pub fn pair_label(&self) -> String {
+        let sys = &self.style.system;
+        format!(
+            "{} → {}",
+            sys.inputs.get(self.style.input).map_or("?", String::as_str),
+            sys.outputs
+                .get(self.style.output)
+                .map_or("?", String::as_str)
+        )
+    }
+}
+
+fn analysis_cached(ctx: &egui::Context, config: &VisualizationConfig) -> Arc<Analysis> {
+    let id = egui::Id::new(("linear_system_analysis", config.id.raw()));
+    if let Some(cached) = ctx.data(|d| d.get_temp::<Arc<(serde_json::Value, Arc<Analysis>)>>(id)) {
+        if cached.0 == config.style {
+            return cached.1.clone();
+        }
+    }
+    let fresh = Arc::new(Analysis::of(LinearSystemStyle::load(config)));
+    ctx.data_mut(|d| d.insert_temp(id, Arc::new((config.style.clone(), fresh.clone()))));
+    fresh
+}
+
+/// Select a different input/output pair, applied after the paint pass.
+#[derive(Event, Clone, Copy)]
+pub(crate) struct LinearSystemPairRequested {
+    pub(crate) viz: VizId,
+    input: usize,
+    output: usize,
+}
+
+pub(crate) fn on_linear_system_pair_requested(
+    trigger: On<LinearSystemPairRequested>,
+    mut registry: ResMut<VisualizationRegistry>,
+) {
+    let request = trigger.event();
+    let Some(cfg) = registry.get_mut(request.viz) else {
+        return;
+    };
+    let mut style = LinearSystemStyle::load(cfg);
+    style.input = request.input;
+    style.output = request.output;
+    style.save(cfg);
+}
+
+/// Toolbar (input/output pickers when the system is MIMO, plus the model and
+/// operating point) and the cached analysis. `None` when there is nothing to
+/// plot; the empty state has been drawn.
+pub(crate) fn header(ctx: &mut Panel2DCtx, config: &VisualizationConfig) -> Option<Arc<Analysis>> {
+    let analysis = analysis_cached(ctx.ui.ctx(), config);
+    let muted = ctx
+        .wb
+        .resource::<lunco_theme::Theme>()
+        .map(|t| t.tokens.text_subdued)
+        .unwrap_or(egui::Color32::DARK_GRAY);
+    let sys = &analysis.style.system;
+    if sys.inputs.is_empty() || sys.outputs.is_empty() || sys.validate().is_err() {
+        ctx.ui.vertical_centered(|ui| {
+            ui.add_space(20.0);
+            ui.label(egui::RichText::new("No linear model.").color(muted));
+            ui.label(
+                egui::RichText::new("Run LinearizeModel with at least one input and output.")
+                    .size(10.0)
+                    .color(muted),
+            );
+        });
+        return None;
+    }
+
+    let (mut input, mut output) = (analysis.style.input, analysis.style.output);
+    ctx.ui.horizontal(|ui| {
+        pick(
+            ui,
+            ("lin_in", config.id.raw()),
+            "u",
+            &sys.inputs,
+            &mut input,
+        );
+        pick(
+            ui,
+            ("lin_out", config.id.raw()),
+            "y",
+            &sys.outputs,
+            &mut output,
+        );
+        let op = &sys.operating_point;
+        let at = if op.steady_state {
+            format!("steady state (|ẋ| ≤ {:.1e})", op.residual)
+        } else {
+            format!("t = {}", op.time)
+        };
+        ui.label(egui::RichText::new(format!("{} · {at}", sys.model)).color(muted));
+    });
+    if (input, output) != (analysis.style.input, analysis.style.output) {
+        ctx.wb.trigger(LinearSystemPairRequested {
+            viz: config.id,
+            input,
+            output,
+        });
+        return None;
+    }
+    Some(analysis)
+}
+
+/// A name picker; a single name is just shown.
+fn pick(ui: &mut egui::Ui, id: impl std::hash::Hash, tag: &str, names: &[String], at: &mut usize) {
+    if names.len() == 1 {
+        ui.label(format!("{tag}: {}", names[0]));
+        return;
+    }
+    let current = names.get(*at).map_or("?", String::as_str);
+    egui::ComboBox::from_id_salt(id)
+        .selected_text(format!("{tag}: {current}"))
+        .show_ui(ui, |ui| {
+            for (k, name) in names.iter().enumerate() {
+                ui.selectable_value(at, k, name.as_str());
+            }
+        });
+}
@@ -0,0 +1,69 @@
+//! `nyquist_plot` — the frequency response of a linear model in the complex
+//! plane: `G(jω)` for ω > 0 solid, its mirror image (ω < 0) faint, and the
+//! critical point −1 marked, so encirclements read off directly.
+//!
+//! Binds no signals; the model is a [`LinearSystemStyle`] in the config's
+//! style (see [`linear_plots`](super)).
+//!
+//! [`LinearSystemStyle`]: super::LinearSystemStyle
+
+use egui_plot::{Line, MarkerShape, Plot, PlotPoints, Points};
+
+use lunco_viz::{Panel2DCtx, RoleSpec, ViewKind, Visualization, VisualizationConfig, VizKindId};
+
+pub const NYQUIST_PLOT_KIND: VizKindId = VizKindId::new_static("nyquist_plot");
+
+#[derive(Default)]
+pub struct NyquistPlot;
+
+impl Visualization for NyquistPlot {
+    fn kind_id(&self) -> VizKindId {
+        NYQUIST_PLOT_KIND
+    }
+    fn display_name(&self) -> &'static str {
+        "Nyquist plot (linear model)"
+    }
+    fn role_schema(&self) -> &'static [RoleSpec] {
+        &[]
+    }
+    fn compatible_views(&self) -> &'static [ViewKind] {
+        &[ViewKind::Panel2D]
+    }
+
+    fn render_panel_2d(&self, ctx: &mut Panel2DCtx, config: &VisualizationConfig) {
+        let Some(analysis) = super::header(ctx, config) else {
+            return;
+        };
+        let theme = lunco_theme::active(ctx.ui.ctx());
+        let label = analysis.pair_label();
+        let color = lunco_viz::signal::color_for_signal(&theme, &label);
+
+        let positive: Vec<[f64; 2]> = analysis
+            .response
+            .iter()
+            .map(|p| [p.value.re, p.value.im])
+            .collect();
+        let negative: Vec<[f64; 2]> = positive.iter().rev().map(|p| [p[0], -p[1]]).collect();
+
+        let size = ctx.ui.available_size_before_wrap();
+        Plot::new(("nyquist_plot", config.id.raw()))
+            .width(size.x)
+            .height(size.y)
+            .data_aspect(1.0)
+            .x_axis_label("Re")
+            .y_axis_label("Im")
+            .show(ctx.ui, |plot_ui| {
+                plot_ui.line(
+                    Line::new(format!("{label} (ω < 0)"), PlotPoints::new(negative))
+                        .color(color.gamma_multiply(0.35)),
+                );
+                plot_ui.line(Line::new(label, PlotPoints::new(positive)).color(color));
+                plot_ui.points(
+                    Points::new("−1", PlotPoints::new(vec![[-1.0, 0.0]]))
+                        .shape(MarkerShape::Plus)
+                        .radius(6.0)
+                        .color(theme.tokens.error),
+                );
+            });
+    }
+}
@@ -0,0 +1,87 @@
+//! `pole_zero_plot` — the poles (×) of a linear model and the zeros (○) of the
+//! selected input/output pair in the complex plane. Poles belong to the whole
+//! model; zeros to the pair, so switching the pair moves only the circles.
+//!
+//! Binds no signals; the model is a [`LinearSystemStyle`] in the config's
+//! style (see [`linear_plots`](super)).
+//!
+//! [`LinearSystemStyle`]: super::LinearSystemStyle
+
+use egui_plot::{Legend, MarkerShape, Plot, PlotPoints, Points};
+
+use lunco_viz::{Panel2DCtx, RoleSpec, ViewKind, Visualization, VisualizationConfig, VizKindId};
+
+pub const POLE_ZERO_PLOT_KIND: VizKindId = VizKindId::new_static("pole_zero_plot");
+
+#[derive(Default)]
+pub struct PoleZeroPlot;
+
+impl Visualization for PoleZeroPlot {
+    fn kind_id(&self) -> VizKindId {
+        POLE_ZERO_PLOT_KIND
+    }
+    fn display_name(&self) -> &'static str {
+        "Pole-zero map (linear model)"
+    }
+    fn role_schema(&self) -> &'static [RoleSpec] {
+        &[]
+    }
+    fn compatible_views(&self) -> &'static [ViewKind] {
+        &[ViewKind::Panel2D]
+    }
+
+    fn render_panel_2d(&self, ctx: &mut Panel2DCtx, config: &VisualizationConfig) {
+        let Some(analysis) = super::header(ctx, config) else {
+            return;
+        };
+        let theme = lunco_theme::active(ctx.ui.ctx());
+        let plane = |roots: &[lunco_experiments::Complex]| -> Vec<[f64; 2]> {
+            roots
+                .iter()
+                .filter(|r| r.re.is_finite() && r.im.is_finite())
+                .map(|r| [r.re, r.im])
+                .collect()
+        };
+        let poles = plane(&analysis.poles);
+        let zeros = plane(&analysis.zeros);
+        // An unstable pole is the thing to spot, so poles take the error
+        // colour as soon as one crosses into the right half-plane.
+        let unstable = poles.iter().any(|p| p[0] > 0.0);
+        let pole_color = if unstable {
+            theme.tokens.error
+        } else {
+            theme.tokens.accent
+        };
+
+        let size = ctx.ui.available_size_before_wrap();
+        Plot::new(("pole_zero_plot", config.id.raw()))
+            .width(size.x)
+            .height(size.y)
+            .data_aspect(1.0)
+            .x_axis_label("Re")
+            .y_axis_label("Im")
+            .legend(Legend::default())
+            .label_formatter(|pos| {
+                let point = match pos {
+                    egui_plot::HoverPosition::NearDataPoint { position, .. } => position,
+                    egui_plot::HoverPosition::Elsewhere { position } => position,
+                };
+                Some(format!("{:.4} {:+.4}j", point.x, point.y))
+            })
+            .show(ctx.ui, |plot_ui| {
+                plot_ui.points(
+                    Points::new(format!("poles ({})", poles.len()), PlotPoints::new(poles))
+                        .shape(MarkerShape::Cross)
+                        .radius(6.0)
+                        .color(pole_color),
+                );
+                plot_ui.points(
+                    Points::new(format!("zeros ({})", zeros.len()), PlotPoints::new(zeros))
+                        .shape(MarkerShape::Circle)
+                        .filled(false)
+                        .radius(6.0)
+                        .color(theme.tokens.success),
+                );
+            });
+    }
+}
@@ -89,6 +89,8 @@ pub mod icon_paint;
 pub mod image_loader;
 /// Debounced AST reparse driver — see module docs.
 pub mod input_activity;
+/// Bode / Nyquist / pole-zero views of a `LinearizeModel` result.
+pub mod linear_plots;
 pub mod panels;
 pub mod solver_picker;
 pub mod text_node;
@@ -627,6 +629,11 @@ impl Plugin for ModelicaUiPlugin {
         use lunco_settings::AppSettingsExt;
         app.register_settings_section::<panels::journal::JournalPanelSettings>();
 
+        // `LinearizeModel`'s plot kinds. They plot a
+        // `lunco_experiments::StateSpace`, so they register from here into
+        // `lunco-viz`'s catalog rather than living in that domain-agnostic crate.
+        linear_plots::register(app);
+
         // Document hot-exit: persist every open Modelica buffer into the
         // per-Twin workspace-state and restore it (with unsaved edits) on
         // next launch. The workbench owns the file + lifecycle; this
@@ -877,6 +884,11 @@ impl Plugin for ModelicaUiPlugin {
             .register_panel(panels::experiments::ExperimentsPanel)
             .init_resource::<panels::experiments::ExperimentVisibility>()
             .init_resource::<panels::experiments::PlotPanelStates>()
+            .add_systems(
+                Update,
+                panels::experiments::demand_visible_series
+                    .run_if(resource_changed::<panels::experiments::PlotPanelStates>),
+            )
             .init_resource::<doc_pin::DocPinState>()
             .init_resource::<panels::experiments::ActivePlot>()
             .register_panel(panels::canvas_diagram::CanvasDiagramPanel)
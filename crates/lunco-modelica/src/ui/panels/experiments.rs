@@ -14,12 +14,16 @@ use std::collections::BTreeMap;
 
 use bevy::prelude::*;
 use bevy_egui::egui;
-use egui_plot::{Legend, Line, LineStyle, Plot, PlotPoints, VLine};
+use egui_plot::{Bar, BarChart, Legend, Line, LineStyle, Plot, PlotPoints, VLine};
 use lunco_doc::DocumentId;
-use lunco_experiments::{ExperimentId, ExperimentRegistry, RunStatus};
+use lunco_experiments::{
+    ExperimentId, ExperimentRegistry, OutputSensitivity, RunStatus, ScalarOutput, SweepId, TwinId,
+};
 use lunco_viz::viz::VizId;
 use lunco_workbench::{Panel, PanelCtx, PanelId, PanelSlot};
 
+use crate::result_files::ResultExportFormat;
+
 pub const EXPERIMENTS_PANEL_ID: PanelId = PanelId("modelica_experiments");
 
 #[derive(Event, Clone, Copy, Debug)]
@@ -30,6 +34,7 @@ pub(crate) struct LoadExperimentRequested {
 #[derive(Event, Clone, Copy, Debug)]
 pub(crate) struct ExportExperimentRequested {
     pub(crate) id: ExperimentId,
+    pub(crate) format: ResultExportFormat,
 }
 
 #[derive(Event, Clone, Copy, Debug)]
@@ -57,9 +62,9 @@ pub(crate) fn on_export_experiment_requested(
     trigger: On<ExportExperimentRequested>,
     mut commands: Commands,
 ) {
-    let id = trigger.id;
+    let (id, format) = (trigger.id, trigger.format);
     commands.queue(move |world: &mut World| {
-        export_experiment_csv(world, id);
+        export_experiment(world, id, format);
     });
 }
 
@@ -130,6 +135,48 @@ pub struct ExperimentVisibility {
     /// which one is focused. Mirrors Dymola's "current plot window"
     /// pin.
     pub target_plot: Option<VizId>,
+    /// Sweep families the user has unfolded. A sweep renders as one
+    /// header row; its member runs only appear once expanded, so a
+    /// 200-point Monte Carlo doesn't bury the hand-made runs.
+    pub expanded_sweeps: std::collections::HashSet<SweepId>,
+    /// Sensitivity chart under the run table, opened from a Morris /
+    /// Saltelli sweep's header. `None` → no chart.
+    pub sensitivity: Option<SensitivityView>,
+}
+
+/// Output kinds offered by the sensitivity chart: token for
+/// [`ScalarOutput::from_token`], display name.
+const SENSITIVITY_KINDS: [(&str, &str); 5] = [
+    ("final", "final value"),
+    ("max", "maximum"),
+    ("min", "minimum"),
+    ("integral", "integral"),
+    ("time_to_threshold", "time to threshold"),
+];
+
+/// One sweep's sensitivity chart: the scalar output each member is reduced
+/// to, and the last analysis with what it was computed from — recomputed
+/// only when the output changes or another member finishes.
+#[derive(Debug, Clone)]
+pub struct SensitivityView {
+    pub sweep: SweepId,
+    pub series: String,
+    /// A [`SENSITIVITY_KINDS`] token.
+    pub kind: &'static str,
+    pub threshold: f64,
+    cached: Option<(ScalarOutput, usize, Result<OutputSensitivity, String>)>,
+}
+
+impl SensitivityView {
+    pub fn new(sweep: SweepId) -> Self {
+        Self {
+            sweep,
+            series: String::new(),
+            kind: SENSITIVITY_KINDS[0].0,
+            threshold: 0.0,
+            cached: None,
+        }
+    }
 }
 
 /// Per-plot-panel state — picked variables, scrub cursor, and the
@@ -297,6 +344,37 @@ impl PlotPanelStates {
     }
 }
 
+/// Ask the experiment store for the trajectory of every run a plot shows,
+/// and of every member of the sweep the sensitivity chart analyses.
+/// Runs restored from disk carry no series until something needs one; this
+/// is what makes ticking a stored run's visibility box load its curves.
+pub fn demand_visible_series(
+    plots: Res<PlotPanelStates>,
+    visibility: Option<Res<ExperimentVisibility>>,
+    registry: Option<Res<ExperimentRegistry>>,
+    mut demand: ResMut<crate::experiment_store::SeriesDemand>,
+) {
+    let Some(registry) = registry else { return };
+    let live = ExperimentId::live();
+    let charted = visibility
+        .as_ref()
+        .and_then(|v| v.sensitivity.as_ref())
+        .and_then(|view| registry.sweep(view.sweep))
+        .map(|s| s.members.clone())
+        .unwrap_or_default();
+    let missing: Vec<ExperimentId> = plots
+        .by_viz
+        .values()
+        .flat_map(|s| s.visible_experiments.iter().copied())
+        .chain(charted)
+        .filter(|id| *id != live && !demand.0.contains(id))
+        .filter(|id| registry.get(*id).is_some_and(|e| e.result.is_none()))
+        .collect();
+    if !missing.is_empty() {
+        demand.0.extend(missing);
+    }
+}
+
 /// Most-recently-rendered plot panel. Used by canvas overlay /
 /// telemetry / runner so global readers can pick a sensible default
 /// plot when they need per-plot state. Updated on every plot render.
@@ -383,7 +461,12 @@ impl Panel for ExperimentsPanel {
                         }
                     ),
                     overrides: format_overrides_summary(&e.overrides),
-                    status: status_label(&e.status),
+                    status: if e.reference.is_some() {
+                        "📎 reference".into()
+                    } else {
+                        status_label(&e.status)
+                    },
+                    reference: e.reference.clone(),
                     duration_ms: match &e.status {
                         RunStatus::Done { wall_time_ms } => Some(*wall_time_ms),
                         _ => None,
@@ -395,6 +478,7 @@ impl Panel for ExperimentsPanel {
                         }),
                     is_terminal: e.status.is_terminal(),
                     color_hint: e.color_hint,
+                    sweep: e.sweep.map(|m| m.sweep),
                     sample_count: e
                         .result
                         .as_ref()
@@ -420,6 +504,47 @@ impl Panel for ExperimentsPanel {
             None => Vec::new(),
         };
 
+        // One header per sweep family. Members keep their own rows
+        // (shown when the family is expanded); the header carries the
+        // aggregate status so a large design stays one line.
+        let families: Vec<Family> = ctx
+            .resource::<ExperimentRegistry>()
+            .map(|reg| {
+                reg.sweeps_for_twin(&twin)
+                    .iter()
+                    .map(|s| {
+                        let members = reg.sweep_members(s.id);
+                        let count = |f: fn(&RunStatus) -> bool| {
+                            members.iter().filter(|e| f(&e.status)).count()
+                        };
+                        Family {
+                            id: s.id,
+                            name: s.name.clone(),
+                            design: s.spec.design.label(),
+                            factors: s
+                                .spec
+                                .factors
+                                .iter()
+                                .map(|f| f.path.0.clone())
+                                .collect::<Vec<_>>()
+                                .join(", "),
+                            seed: s.spec.seed,
+                            sensitivity: s.spec.design.is_sensitivity(),
+                            color_hint: s.color_hint,
+                            members: members.iter().map(|e| e.id).collect(),
+                            done: count(|st| matches!(st, RunStatus::Done { .. })),
+                            failed: count(|st| matches!(st, RunStatus::Failed { .. })),
+                            active: count(|st| !st.is_terminal()),
+                        }
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let expanded_sweeps = ctx
+            .resource::<ExperimentVisibility>()
+            .map(|v| v.expanded_sweeps.clone())
+            .unwrap_or_default();
+
         // Add the "Interactive Live" row if an interactive simulation
         // is active for the current document. Resolve the doc's
         // simulator entity through the registry (O(1)) and read its
@@ -440,10 +565,12 @@ impl Panel for ExperimentsPanel {
                         if m.paused { "⏸" } else { "▶" },
                         m.current_time
                     ),
+                    reference: None,
                     duration_ms: None,
                     error: None,
                     is_terminal: false,
                     color_hint: 255, // distinct color index for live
+                    sweep: None,
                     sample_count: 0,
                     var_count: 0,
                     progress: None,
@@ -515,17 +642,40 @@ impl Panel for ExperimentsPanel {
                         );
                     }
                 }
+                if active_doc.is_some() {
+                    ui.add_space(6.0);
+                    import_reference_button(ui, ctx, doc_id);
+                }
             });
             return;
         }
 
         ui.horizontal_wrapped(|ui| {
             ui.weak(format!("{} experiment(s)", rows.len()));
+            import_reference_button(ui, ctx, doc_id);
             // Show EVERY non-terminal run's live status (running /
             // queued) — when several experiments run at once the user
             // needs to see all of them, not just one summary. Falls back
             // to the most recent terminal outcome when nothing is active.
-            let active: Vec<&Row> = rows.iter().filter(|r| !r.is_terminal).collect();
+            let active: Vec<&Row> = rows
+                .iter()
+                .filter(|r| !r.is_terminal && r.sweep.is_none())
+                .collect();
+            let active_families: Vec<&Family> =
+                families.iter().filter(|f| f.active > 0).collect();
+            for f in &active_families {
+                ui.separator();
+                ui.label(
+                    egui::RichText::new(format!(
+                        "▶ {} {}/{} done",
+                        f.name,
+                        f.done + f.failed,
+                        f.members.len()
+                    ))
+                    .color(col_success)
+                    .strong(),
+                );
+            }
             if !active.is_empty() {
                 for r in active {
                     ui.separator();
@@ -540,7 +690,11 @@ impl Panel for ExperimentsPanel {
                     };
                     ui.label(egui::RichText::new(txt).color(color).strong());
                 }
-            } else if let Some(last) = rows.iter().rev().find(|r| r.is_terminal) {
+            } else if let Some(last) = rows
+                .iter()
+                .rev()
+                .find(|r| r.is_terminal && active_families.is_empty())
+            {
                 ui.separator();
                 let (txt, color) = if let Some(_err) = &last.error {
                     (format!("⚠ {} failed", last.name), col_error)
@@ -557,12 +711,18 @@ impl Panel for ExperimentsPanel {
         let mut toggle: Option<ExperimentId> = None;
         let mut delete: Option<ExperimentId> = None;
         let mut cancel: Option<ExperimentId> = None;
+        // Family-level actions from a sweep header row.
+        let mut toggle_family: Option<(Vec<ExperimentId>, bool)> = None;
+        let mut expand_family: Option<SweepId> = None;
+        let mut cancel_sweep: Option<SweepId> = None;
+        let mut delete_sweep: Option<SweepId> = None;
+        let mut open_sensitivity: Option<SweepId> = None;
         // Selected row → load its setup into the draft. Right-click
         // gives Re-run / Duplicate. Both work on terminal rows; for
         // running rows ⊘ Cancel is the only useful action.
         let mut load_into_draft: Option<ExperimentId> = None;
         let mut rerun: Option<ExperimentId> = None;
-        let mut export_csv: Option<ExperimentId> = None;
+        let mut export: Option<(ExperimentId, ResultExportFormat)> = None;
         // Inline rename state changes batched after Grid::show to
         // avoid double-borrow of ExperimentVisibility.
         let mut start_rename: Option<(ExperimentId, String)> = None;
@@ -614,7 +774,145 @@ impl Panel for ExperimentsPanel {
                         .map(|s| s.visible(target_viz))
                         .unwrap_or_default();
 
+                    // Sweep members render under their family header, which
+                    // takes the slot of the family's first run so the list
+                    // stays chronological.
+                    let mut headed: std::collections::HashSet<SweepId> =
+                        std::collections::HashSet::new();
+                    let mut ordered: Vec<(&Row, Option<&Family>)> = Vec::new();
                     for row in &rows {
+                        let Some(sid) = row.sweep else {
+                            ordered.push((row, None));
+                            continue;
+                        };
+                        if !headed.insert(sid) {
+                            continue;
+                        }
+                        let Some(family) = families.iter().find(|f| f.id == sid) else {
+                            ordered.push((row, None));
+                            continue;
+                        };
+                        ordered.push((row, Some(family)));
+                        if expanded_sweeps.contains(&sid) {
+                            ordered.extend(
+                                rows.iter()
+                                    .filter(|r| r.sweep == Some(sid))
+                                    .map(|r| (r, None)),
+                            );
+                        }
+                    }
+
+                    for (row, family) in ordered {
+                        if let Some(f) = family {
+                            let shown = f
+                                .members
+                                .iter()
+                                .filter(|id| visibility_snapshot.contains(id))
+                                .count();
+                            let mut all_visible = shown == f.members.len();
+                            let family_box = ui.checkbox(&mut all_visible, "");
+                            if family_box.changed() {
+                                toggle_family = Some((f.members.clone(), all_visible));
+                            }
+                            family_box.on_hover_text(format!(
+                                "{shown} of {} runs shown in this plot",
+                                f.members.len()
+                            ));
+                            let (r, g, b) = palette_color(f.color_hint);
+                            ui.colored_label(egui::Color32::from_rgb(r, g, b), "■");
+                            let expanded = expanded_sweeps.contains(&f.id);
+                            let header = ui
+                                .add(
+                                    egui::Label::new(
+                                        egui::RichText::new(format!(
+                                            "{} {} · {} × {}",
+                                            if expanded { "▾" } else { "▸" },
+                                            f.name,
+                                            f.design,
+                                            f.members.len()
+                                        ))
+                                        .strong(),
+                                    )
+                                    .sense(egui::Sense::click()),
+                                )
+                                .on_hover_text(format!(
+                                    "Factors: {}\nClick to show / hide the member runs. \
+                                     Right-click: Cancel all / Delete sweep{}.",
+                                    f.factors,
+                                    if f.sensitivity { " / Sensitivity" } else { "" }
+                                ));
+                            if header.clicked() {
+                                expand_family = Some(f.id);
+                            }
+                            header.context_menu(|ui| {
+                                if f.sensitivity
+                                    && ui
+                                        .button("📊 Sensitivity")
+                                        .on_hover_text(
+                                            "Chart which factors move an output \
+                                             (from the members finished so far)",
+                                        )
+                                        .clicked()
+                                {
+                                    open_sensitivity = Some(f.id);
+                                    ui.close();
+                                }
+                                if f.active > 0
+                                    && ui
+                                        .button("⊘ Cancel all")
+                                        .on_hover_text("Stop every queued or running member")
+                                        .clicked()
+                                {
+                                    cancel_sweep = Some(f.id);
+                                    ui.close();
+                                }
+                                if ui
+                                    .button("✕ Delete sweep")
+                                    .on_hover_text("Remove every finished member of this sweep")
+                                    .clicked()
+                                {
+                                    delete_sweep = Some(f.id);
+                                    ui.close();
+                                }
+                            });
+                            ui.label(format!("seed {}", f.seed));
+                            let total = f.members.len().max(1);
+                            let finished = f.done + f.failed;
+                            let status = if f.failed > 0 {
+                                egui::RichText::new(format!(
+                                    "{}/{} done · {} failed",
+                                    f.done,
+                                    f.members.len(),
+                                    f.failed
+                                ))
+                                .color(col_error)
+                            } else if f.active > 0 {
+                                egui::RichText::new(format!("{}/{} done", f.done, f.members.len()))
+                                    .color(col_warning)
+                            } else {
+                                egui::RichText::new(format!("{}/{} done", f.done, f.members.len()))
+                            };
+                            ui.horizontal(|ui| {
+                                ui.label(status);
+                                if f.active > 0 {
+                                    ui.add(
+                                        egui::ProgressBar::new(finished as f32 / total as f32)
+                                            .desired_width(60.0)
+                                            .desired_height(8.0),
+                                    );
+                                }
+                            });
+                            ui.label("");
+                            if f.active > 0 {
+                                if ui.small_button("⊘").on_hover_text("Cancel all").clicked() {
+                                    cancel_sweep = Some(f.id);
+                                }
+                            } else if ui.small_button("✕").on_hover_text("Delete sweep").clicked() {
+                                delete_sweep = Some(f.id);
+                            }
+                            ui.end_row();
+                            continue;
+                        }
                         let mut visible = visibility_snapshot.contains(&row.id);
                         if ui.checkbox(&mut visible, "").changed() {
                             toggle = Some(row.id);
@@ -655,12 +953,20 @@ impl Panel for ExperimentsPanel {
                                 start_rename = Some((row.id, buf));
                             }
                         } else {
-                            let name_label = egui::Label::new(&row.name)
+                            let name_text = if row.sweep.is_some() {
+                                format!("    {}", row.name)
+                            } else {
+                                row.name.clone()
+                            };
+                            let name_label = egui::Label::new(name_text)
                                 .sense(egui::Sense::click());
                             let name_resp = ui
                                 .add(name_label)
                                 .on_hover_text(if row.id.is_live() {
                                     "Interactive realtime simulation state."
+                                } else if row.reference.is_some() {
+                                    "Imported reference run (read-only). \
+                                     Double-click or right-click → Rename."
                                 } else {
                                     "Click: load this run's setup into the draft. \
                                      Double-click or right-click → Rename. \
@@ -669,7 +975,10 @@ impl Panel for ExperimentsPanel {
                             if !row.id.is_live() {
                                 if name_resp.double_clicked() {
                                     start_rename = Some((row.id, row.name.clone()));
-                                } else if name_resp.clicked() && row.is_terminal {
+                                } else if name_resp.clicked()
+                                    && row.is_terminal
+                                    && row.reference.is_none()
+                                {
                                     load_into_draft = Some(row.id);
                                 }
                                 name_resp.context_menu(|ui| {
@@ -679,25 +988,30 @@ impl Panel for ExperimentsPanel {
                                     }
                                     ui.separator();
                                     if row.is_terminal {
-                                        if ui.button("▶ Re-run with same setup").on_hover_text("Run again with identical bounds and parameter overrides").clicked() {
+                                        if row.reference.is_none() && ui.button("▶ Re-run with same setup").on_hover_text("Run again with identical bounds and parameter overrides").clicked() {
                                             rerun = Some(row.id);
                                             ui.close();
                                         }
-                                        if ui.button("Duplicate into Setup").on_hover_text("Load this run's setup into the draft so you can tweak it").clicked() {
+                                        if row.reference.is_none() && ui.button("Duplicate into Setup").on_hover_text("Load this run's setup into the draft so you can tweak it").clicked() {
                                             load_into_draft = Some(row.id);
                                             ui.close();
                                         }
-                                        if ui
-                                            .button("Export CSV…")
-                                            .on_hover_text(
-                                                "Save this run's full trajectory \
-                                                 (time + every recorded variable) \
-                                                 to a CSV file.",
-                                            )
-                                            .clicked()
-                                        {
-                                            export_csv = Some(row.id);
-                                            ui.close();
+                                        for format in ResultExportFormat::ALL {
+                                            if !format.available() {
+                                                continue;
+                                            }
+                                            if ui
+                                                .button(format!("Export {}…", format.label()))
+                                                .on_hover_text(
+                                                    "Save this run's full trajectory \
+                                                     (time + every recorded variable) \
+                                                     to a file.",
+                                                )
+                                                .clicked()
+                                            {
+                                                export = Some((row.id, format));
+                                                ui.close();
+                                            }
                                         }
                                         ui.separator();
                                         if ui.button("✕ Delete").on_hover_text("Remove this run from the list").clicked() {
@@ -747,6 +1061,8 @@ impl Panel for ExperimentsPanel {
                         }).inner;
                         if let Some(err) = &row.error {
                             status_widget.on_hover_text(err);
+                        } else if let Some(file) = &row.reference {
+                            status_widget.on_hover_text(format!("Imported from {file}"));
                         }
                         let sample_text = if row.var_count > 0 {
                             format!("{}×{}", row.sample_count, row.var_count)
@@ -820,6 +1136,52 @@ impl Panel for ExperimentsPanel {
                 states.toggle_visible(target_viz, id);
             });
         }
+        if let Some((ids, on)) = toggle_family {
+            let target_viz = {
+                let pinned = ctx
+                    .resource::<ExperimentVisibility>()
+                    .and_then(|v| v.target_plot);
+                pinned.unwrap_or_else(|| {
+                    ctx
+                        .resource::<ActivePlot>()
+                        .copied()
+                        .unwrap_or_default()
+                        .or_default()
+                })
+            };
+            ctx.resource_scope::<PlotPanelStates, _>(|_, states| {
+                for id in ids {
+                    states.set_visible(target_viz, id, on);
+                }
+            });
+        }
+        if let Some(sid) = expand_family {
+            ctx.resource_scope::<ExperimentVisibility, _>(|_, visibility| {
+                if !visibility.expanded_sweeps.remove(&sid) {
+                    visibility.expanded_sweeps.insert(sid);
+                }
+            });
+        }
+        if let Some(sid) = open_sensitivity {
+            ctx.resource_scope::<ExperimentVisibility, _>(|_, visibility| {
+                visibility.sensitivity = Some(SensitivityView::new(sid));
+            });
+        }
+        if let Some(sid) = cancel_sweep {
+            ctx.trigger(crate::ui::commands::compile::CancelExperiment {
+                experiment_id: None,
+                all: false,
+                sweep_id: Some(sid.0.to_string()),
+            });
+        }
+        if let Some(sid) = delete_sweep {
+            ctx.trigger(crate::ui::commands::compile::DeleteExperiment {
+                experiment_id: None,
+                doc: None,
+                all: false,
+                sweep_id: Some(sid.0.to_string()),
+            });
+        }
         if let Some(id) = delete {
             // Route through the typed `DeleteExperiment` command — its
             // observer deletes from the registry AND purges doc-mapping +
@@ -829,6 +1191,7 @@ impl Panel for ExperimentsPanel {
                 experiment_id: Some(id.0.to_string()),
                 doc: None,
                 all: false,
+                sweep_id: None,
             });
         }
         if let Some(id) = cancel {
@@ -849,8 +1212,8 @@ impl Panel for ExperimentsPanel {
         if let Some(id) = load_into_draft {
             ctx.trigger(LoadExperimentRequested { id });
         }
-        if let Some(id) = export_csv {
-            ctx.trigger(ExportExperimentRequested { id });
+        if let Some((id, format)) = export {
+            ctx.trigger(ExportExperimentRequested { id, format });
         }
         if let Some(id) = rerun {
             // Load setup, then dispatch a new Fast Run with it.
@@ -859,6 +1222,8 @@ impl Panel for ExperimentsPanel {
             ctx.trigger(RerunExperimentRequested { id });
         }
 
+        self.render_sensitivity_section(ui, ctx, &twin);
+
         // Plot + variable picker now live in the Graphs panel — this
         // panel is the run *list* / comparison-source. See the Source
         // toggle in panels::graphs.
@@ -867,6 +1232,175 @@ impl Panel for ExperimentsPanel {
 }
 
 impl ExperimentsPanel {
+    /// Tornado chart of the open [`SensitivityView`]: Morris `μ*` / `σ` or
+    /// Sobol first / total indices per factor, most influential on top.
+    /// Renders only for a sweep of this twin; closes itself when the sweep
+    /// is deleted.
+    fn render_sensitivity_section(&self, ui: &mut egui::Ui, ctx: &mut PanelCtx, twin: &TwinId) {
+        let tokens = &ctx.resource_expect::<lunco_theme::Theme>().tokens;
+        let (col_first, col_second, col_error) = (tokens.accent, tokens.warning, tokens.error);
+        ctx.resource_scope::<ExperimentVisibility, _>(|ctx, visibility| {
+            let Some(registry) = ctx.resource::<ExperimentRegistry>() else {
+                return;
+            };
+            let Some(view) = visibility.sensitivity.as_mut() else {
+                return;
+            };
+            let Some(sweep) = registry.sweep(view.sweep) else {
+                visibility.sensitivity = None;
+                return;
+            };
+            if &sweep.twin_id != twin {
+                return;
+            }
+            let finished: Vec<_> = registry
+                .sweep_members(view.sweep)
+                .into_iter()
+                .filter(|e| matches!(e.status, RunStatus::Done { .. }))
+                .filter_map(|e| e.result.as_ref())
+                .collect();
+            let series: Vec<String> = finished
+                .first()
+                .map(|r| r.series.keys().cloned().collect())
+                .unwrap_or_default();
+            if !series.contains(&view.series) {
+                view.series = series.first().cloned().unwrap_or_default();
+            }
+
+            ui.separator();
+            let mut close = false;
+            ui.horizontal(|ui| {
+                ui.strong(format!(
+                    "📊 Sensitivity — {} · {}",
+                    sweep.name,
+                    sweep.spec.design.label()
+                ));
+                if ui
+                    .small_button("✕")
+                    .on_hover_text("Close the chart")
+                    .clicked()
+                {
+                    close = true;
+                }
+            });
+            if close {
+                visibility.sensitivity = None;
+                return;
+            }
+            if series.is_empty() {
+                ui.weak("Waiting for the first member to finish…");
+                return;
+            }
+            ui.horizontal(|ui| {
+                egui::ComboBox::from_id_salt(("sensitivity_kind", view.sweep))
+                    .selected_text(
+                        SENSITIVITY_KINDS
+                            .iter()
+                            .find(|(token, _)| *token == view.kind)
+                            .map_or(view.kind, |(_, name)| *name),
+                    )
+                    .show_ui(ui, |ui| {
+                        for (token, name) in SENSITIVITY_KINDS {
+                            ui.selectable_value(&mut view.kind, token, name);
+                        }
+                    });
+                ui.label("of");
+                egui::ComboBox::from_id_salt(("sensitivity_series", view.sweep))
+                    .selected_text(view.series.as_str())
+                    .show_ui(ui, |ui| {
+                        for name in &series {
+                            ui.selectable_value(&mut view.series, name.clone(), name);
+                        }
+                    });
+                if view.kind == "time_to_threshold" {
+                    ui.label("crossing");
+                    ui.add(egui::DragValue::new(&mut view.threshold).speed(0.01));
+                }
+            });
+
+            let Some(output) =
+                ScalarOutput::from_token(&view.series, view.kind, Some(view.threshold))
+            else {
+                return;
+            };
+            let stale =
+                !matches!(&view.cached, Some((o, n, _)) if *o == output && *n == finished.len());
+            if stale {
+                let analysis = registry
+                    .sweep_sensitivity(view.sweep, std::slice::from_ref(&output))
+                    .and_then(|mut report| {
+                        report.outputs.pop().ok_or_else(|| "no output".to_string())
+                    });
+                view.cached = Some((output, finished.len(), analysis));
+            }
+            let Some((_, _, analysis)) = &view.cached else {
+                return;
+            };
+            let o = match analysis {
+                Ok(o) => o,
+                Err(why) => {
+                    ui.colored_label(col_error, why);
+                    return;
+                }
+            };
+            ui.weak(format!(
+                "{} · {} of {} runs · mean {:.4} · variance {:.4}",
+                o.label,
+                o.runs,
+                sweep.members.len(),
+                o.mean,
+                o.variance
+            ));
+            if !o.morris.is_empty() {
+                tornado_chart(
+                    ui,
+                    ("sensitivity_morris", view.sweep),
+                    &o.morris
+                        .iter()
+                        .map(|m| m.path.0.clone())
+                        .collect::<Vec<_>>(),
+                    [
+                        (
+                            "μ*",
+                            col_first,
+                            o.morris
+                                .iter()
+                                .map(|m| (m.mu_star, String::new()))
+                                .collect(),
+                        ),
+                        (
+                            "σ",
+                            col_second,
+                            o.morris
+                                .iter()
+                                .map(|m| (m.sigma, format!("μ = {:.4}", m.mu)))
+                                .collect(),
+                        ),
+                    ],
+                );
+            } else {
+                let ci = |c: [f64; 2]| format!("95% [{:.3}, {:.3}]", c[0], c[1]);
+                tornado_chart(
+                    ui,
+                    ("sensitivity_sobol", view.sweep),
+                    &o.sobol.iter().map(|s| s.path.0.clone()).collect::<Vec<_>>(),
+                    [
+                        (
+                            "first order",
+                            col_first,
+                            o.sobol.iter().map(|s| (s.first, ci(s.first_ci))).collect(),
+                        ),
+                        (
+                            "total",
+                            col_second,
+                            o.sobol.iter().map(|s| (s.total, ci(s.total_ci))).collect(),
+                        ),
+                    ],
+                );
+            }
+        });
+    }
+
     /// Persistent Setup section at the top of the Experiments panel.
     /// Compact bounds + inputs + Run button. Edits persist into the
     /// per-`ModelRef` draft; the toolbar's ⏩ Fast button reads the
@@ -2535,10 +3069,9 @@ fn render_experiments_plot_inner(
 /// Routes through `lunco_storage::FileStorage` so the same call site
 /// will work when an OPFS / browser-download backend lands for wasm.
 /// Cancelling the picker is a silent no-op; errors land in Console.
-fn export_experiment_csv(world: &mut World, id: ExperimentId) {
-    use crate::ui::panels::csv_export;
-
-    let (file_stem, csv_text) = {
+fn export_experiment(world: &mut World, id: ExperimentId, format: ResultExportFormat) {
+    crate::experiment_store::ensure_series(world, id);
+    let (file_stem, result) = {
         let registry = match world.get_resource::<ExperimentRegistry>() {
             Some(r) => r,
             None => return,
@@ -2548,35 +3081,13 @@ fn export_experiment_csv(world: &mut World, id: ExperimentId) {
             if let Some(mut console) =
                 world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
             {
-                console.error("CSV export: experiment has no result yet (still running or failed)");
+                console.error(format!(
+                    "{} export: experiment has no result yet (still running or failed)",
+                    format.label()
+                ));
             }
             return;
         };
-        let mut text = String::new();
-        // Header row.
-        text.push_str("time");
-        let mut var_order: Vec<&String> = result.series.keys().collect();
-        var_order.sort();
-        for v in &var_order {
-            text.push(',');
-            // Quote names that contain commas / quotes; Modelica
-            // dotted paths normally don't, but be defensive.
-            csv_export::push_csv_field(&mut text, v);
-        }
-        text.push('\n');
-        // Data rows.
-        for (i, t) in result.times.iter().enumerate() {
-            text.push_str(&format!("{t}"));
-            for v in &var_order {
-                text.push(',');
-                let val = result.series.get(*v).and_then(|col| col.get(i));
-                match val {
-                    Some(x) if x.is_finite() => text.push_str(&format!("{x}")),
-                    _ => {} // empty cell for NaN / out-of-range
-                }
-            }
-            text.push('\n');
-        }
         // Filename suggestion: <model>_<run>_<unix_ts>. Unix seconds
         // is unambiguous across timezones and easy to glob; the run
         // name is included for readability when filing multiple
@@ -2602,22 +3113,56 @@ fn export_experiment_csv(world: &mut World, id: ExperimentId) {
                 }
             })
             .collect();
-        (safe_name, text)
+        (safe_name, result.clone())
     };
 
-    // Shared save dialog + write + error-console boilerplate (CQ-111).
-    // Emit the experiment-specific success line only when it actually wrote.
-    if csv_export::save_csv_via_dialog(world, &format!("{file_stem}.csv"), csv_text.as_bytes())
-        .is_some()
-    {
-        if let Some(mut console) =
-            world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>()
-        {
-            console.info(format!("✓ Exported experiment to {file_stem}.csv"));
+    let ext = format.extension();
+    let hint = lunco_workbench::picker::SaveHint {
+        suggested_name: Some(format!("{file_stem}.{ext}")),
+        start_dir: None,
+        filters: vec![lunco_workbench::picker::OpenFilter::new(
+            format.label(),
+            &[ext],
+        )],
+    };
+    let Some(handle) = lunco_workbench::picker::pick_save_blocking(&hint) else {
+        return; // user cancelled
+    };
+    let Some(path) = handle.as_file_path() else {
+        return;
+    };
+    let outcome = crate::result_files::write_result(path, &result, format);
+    if let Some(mut console) = world.get_resource_mut::<crate::ui::panels::console::ConsoleLog>() {
+        match outcome {
+            Ok(()) => console.info(format!(
+                "✓ Exported experiment ({}) to {}",
+                format.label(),
+                path.display()
+            )),
+            Err(e) => console.error(format!("{} export: write failed: {e}", format.label())),
         }
     }
 }
 
+/// "Import reference…" — pick a MAT / HDF5 result file and add it to the
+/// document's runs as a read-only reference (`ImportReferenceRun`).
+fn import_reference_button(ui: &mut egui::Ui, ctx: &mut PanelCtx, doc: DocumentId) {
+    if ui
+        .small_button("📥 Import reference…")
+        .on_hover_text(
+            "Add a Dymola / OpenModelica result (.mat, MAT v4; .mat v7.3 / .h5 \
+             with HDF5 support) as a read-only run to overlay on yours.",
+        )
+        .clicked()
+    {
+        ctx.trigger(crate::ui::commands::compile::ImportReferenceRun {
+            doc,
+            path: String::new(),
+            name: String::new(),
+        });
+    }
+}
+
 /// Copy a completed experiment's bounds + inputs + overrides into
 /// the per-`ModelRef` draft. The toolbar's bounds readout, the
 /// inline Setup section, and the Setup modal all read from that
@@ -2797,10 +3342,15 @@ struct Row {
     /// Bounds column so users can scan which experiments deviated.
     overrides: String,
     status: String,
+    /// Source file name when this is an imported reference run —
+    /// read-only: no re-run, no load into the draft.
+    reference: Option<String>,
     duration_ms: Option<u64>,
     error: Option<String>,
     is_terminal: bool,
     color_hint: u8,
+    /// Family this run belongs to, when it was generated by a sweep.
+    sweep: Option<SweepId>,
     sample_count: usize,
     var_count: usize,
     /// Progress fraction in `[0, 1]` while a run is in flight.
@@ -2810,6 +3360,69 @@ struct Row {
     progress: Option<f32>,
 }
 
+/// Horizontal bar pairs, one row per factor in the order given (the first
+/// on top). Each bar is `(value, extra hover text)`.
+fn tornado_chart(
+    ui: &mut egui::Ui,
+    id: impl std::hash::Hash,
+    factors: &[String],
+    pairs: [(&str, egui::Color32, Vec<(f64, String)>); 2],
+) {
+    let n = factors.len();
+    let names = factors.to_vec();
+    Plot::new(id)
+        .height((n as f32 * 22.0).clamp(120.0, 640.0))
+        .legend(Legend::default())
+        .allow_drag(false)
+        .allow_zoom(false)
+        .allow_scroll(false)
+        .allow_boxed_zoom(false)
+        .include_x(0.0)
+        .y_axis_formatter(move |mark, _range| {
+            let row = n as f64 - 1.0 - mark.value;
+            if row < 0.0 || (row - row.round()).abs() > 1e-6 {
+                return String::new();
+            }
+            names.get(row.round() as usize).cloned().unwrap_or_default()
+        })
+        .show(ui, |plot_ui| {
+            for (k, (name, color, values)) in pairs.into_iter().enumerate() {
+                let offset = if k == 0 { 0.2 } else { -0.2 };
+                let bars = values
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, (value, extra))| {
+                        let hover = format!("{}\n{name} = {value:.4}", factors[i]);
+                        Bar::new((n - 1 - i) as f64 + offset, value)
+                            .width(0.38)
+                            .name(if extra.is_empty() {
+                                hover
+                            } else {
+                                format!("{hover}\n{extra}")
+                            })
+                    })
+                    .collect();
+                plot_ui.bar_chart(BarChart::new(name, bars).color(color).horizontal());
+            }
+        });
+}
+
+/// Aggregate view of one sweep family for its header row.
+struct Family {
+    id: SweepId,
+    name: String,
+    design: &'static str,
+    factors: String,
+    seed: u64,
+    /// Morris / Saltelli design — offers the sensitivity chart.
+    sensitivity: bool,
+    color_hint: u8,
+    members: Vec<ExperimentId>,
+    done: usize,
+    failed: usize,
+    active: usize,
+}
+
 fn format_overrides_summary(
     overrides: &std::collections::BTreeMap<
         lunco_experiments::ParamPath,
@@ -413,7 +413,7 @@ fn export_graph_to_csv(world: &mut World, viz_id: VizId) {
     let mut csv = String::from("time");
     for col in &columns {
         csv.push(',');
-        crate::ui::panels::csv_export::push_csv_field(&mut csv, &col.label);
+        crate::result_files::push_csv_field(&mut csv, &col.label);
     }
     csv.push('\n');
 
@@ -4,7 +4,6 @@ pub mod canvas_diagram;
 pub mod canvas_projection;
 pub mod code_editor;
 pub mod console;
-pub mod csv_export;
 pub mod diagnostics;
 pub mod experiments;
 pub mod graphs;
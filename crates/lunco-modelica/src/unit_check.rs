@@ -0,0 +1,631 @@
+//! Unit inference over a model's equations — the dimension check behind the
+//! `unit-inconsistent-equation` lint.
+//!
+//! Modelica lets every variable SAY its unit (`Real w(unit = "rad/s")`, or a
+//! `Modelica.Units.SI.*` type) and then never checks that the equations agree
+//! with what was said. `der(soc) = soc_rate` with `soc` in `1` and `soc_rate` in
+//! `1/s` is fine; `der(soc) = soc` compiles, runs, and is off by a hidden
+//! `1 s` that nobody wrote down. This pass propagates declared units through each
+//! equation and reports the ones whose two sides — or whose terms — cannot be
+//! the same physical quantity.
+//!
+//! # What it does and does not claim
+//!
+//! It is an inference, and it only speaks when it is sure:
+//!
+//! * **Numeric literals are wildcards**, as in the MLS and in OpenModelica's
+//!   checker: `0.1 * level` might be `0.1 [1/s] * level`, so a product or
+//!   quotient with a literal in it has NO known unit. `1.0 - soc` is still `1`
+//!   — a literal added to a quantity takes that quantity's unit.
+//! * An **undeclared** variable is unknown, never `1`. Unknown poisons a product
+//!   but not a sum: in `a + b` with `a` known, the sum is `a`'s unit whatever
+//!   `b` turns out to be.
+//! * Resolution is **within the file**: a component's own unit, components
+//!   inherited through `extends` of a class in the same file, and dotted
+//!   references into a same-file sub-component's class. Anything outside (an
+//!   MSL connector, another file's model) is unknown and checks nothing.
+//!
+//! So a model with no units declared produces no findings, and a finding is
+//! always a pair of DECLARED units that disagree — the dimension-wise check, plus
+//! a scale check when both sides are exact (`km + m`, `rpm = rad/s`).
+
+use lunco_core::units::{Dimension, Unit};
+use rumoca_compile::parsing::ast::{Component, Equation, TerminalType};
+use rumoca_compile::parsing::ir_core::OpUnary;
+use rumoca_compile::parsing::{ClassDef, Expression, OpBinary, StoredDefinition};
+use std::collections::HashMap;
+
+/// How two units disagree.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MismatchKind {
+    /// Different physical dimensions — no conversion exists.
+    Dimension,
+    /// Same dimension, different scale or offset, with nothing converting.
+    Scale,
+    /// A function that needs a dimensionless argument (`sin`, `exp`, `log`)
+    /// got a dimensioned one.
+    Argument,
+}
+
+impl MismatchKind {
+    /// The fact spelling: `"dimension"`, `"scale"`, `"argument"`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MismatchKind::Dimension => "dimension",
+            MismatchKind::Scale => "scale",
+            MismatchKind::Argument => "argument",
+        }
+    }
+}
+
+/// One inconsistent equation. At most one per equation: the first disagreement
+/// found makes everything above it meaningless, so reporting the cascade would
+/// only bury the cause.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UnitMismatch {
+    /// The class the equation is written in.
+    pub class: String,
+    /// 1-based source line, `0` when the parser lost the location.
+    pub line: i64,
+    /// What was compared: `der(soc)` for the sides of an equation, `max(...)`
+    /// for a function's arguments, `+` for the terms of a sum.
+    pub subject: String,
+    /// Unit of the first operand — declared text where there is one, base
+    /// dimensions otherwise.
+    pub lhs: String,
+    /// Unit of the second operand.
+    pub rhs: String,
+    /// How they disagree.
+    pub kind: MismatchKind,
+}
+
+/// Every unit-inconsistent equation in `ast`, nested classes included.
+pub fn unit_mismatches(ast: &StoredDefinition) -> Vec<UnitMismatch> {
+    let mut classes = ClassIndex::default();
+    for (name, class) in &ast.classes {
+        classes.add(name, class);
+    }
+    let mut out = Vec::new();
+    for (name, class) in &ast.classes {
+        check_class(name, class, &classes, &mut out);
+    }
+    out
+}
+
+fn check_class(
+    name: &str,
+    class: &ClassDef,
+    classes: &ClassIndex<'_>,
+    out: &mut Vec<UnitMismatch>,
+) {
+    let scope = Scope::of(class, classes);
+    for eq in &class.equations {
+        check_equation(eq, name, &scope, out);
+    }
+    for (nested_name, nested) in &class.classes {
+        check_class(nested_name, nested, classes, out);
+    }
+}
+
+fn check_equation(eq: &Equation, class: &str, scope: &Scope<'_>, out: &mut Vec<UnitMismatch>) {
+    match eq {
+        Equation::Simple { lhs, rhs } => {
+            let mut infer = Inference { scope, found: None };
+            let l = infer.expr(lhs);
+            let r = infer.expr(rhs);
+            infer.compare(&describe(lhs), &l, &r, MismatchKind::Dimension);
+            if let Some(found) = infer.found {
+                out.push(UnitMismatch {
+                    class: class.to_string(),
+                    line: eq
+                        .get_location()
+                        .map(|l| i64::from(l.start_line))
+                        .unwrap_or(0),
+                    ..found
+                });
+            }
+        }
+        Equation::If {
+            cond_blocks,
+            else_block,
+        } => {
+            for block in cond_blocks {
+                for inner in &block.eqs {
+                    check_equation(inner, class, scope, out);
+                }
+            }
+            for inner in else_block.iter().flatten() {
+                check_equation(inner, class, scope, out);
+            }
+        }
+        Equation::When(blocks) => {
+            for block in blocks {
+                for inner in &block.eqs {
+                    check_equation(inner, class, scope, out);
+                }
+            }
+        }
+        Equation::For { equations, .. } => {
+            for inner in equations {
+                check_equation(inner, class, scope, out);
+            }
+        }
+        // A connect joins connector members whose units live on the connector
+        // class — usually an MSL one this pass cannot see.
+        _ => {}
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Declarations
+// ---------------------------------------------------------------------------
+
+/// Every class in the file by its short name. A name declared twice (two
+/// nested `Port`s in different packages) resolves to nothing rather than to
+/// whichever came last.
+#[derive(Default)]
+struct ClassIndex<'a> {
+    by_name: HashMap<&'a str, Option<&'a ClassDef>>,
+}
+
+impl<'a> ClassIndex<'a> {
+    fn add(&mut self, name: &'a str, class: &'a ClassDef) {
+        self.by_name
+            .entry(name)
+            .and_modify(|seen| *seen = None)
+            .or_insert(Some(class));
+        for (nested_name, nested) in &class.classes {
+            self.add(nested_name, nested);
+        }
+    }
+
+    fn get(&self, type_name: &str) -> Option<&'a ClassDef> {
+        let leaf = type_name.rsplit('.').next().unwrap_or(type_name);
+        self.by_name.get(leaf).copied().flatten()
+    }
+}
+
+/// `extends` chains deeper than this are a cycle the parser let through.
+const MAX_EXTENDS_DEPTH: usize = 16;
+
+/// The components visible in one class: its own plus everything inherited from
+/// same-file bases.
+struct Scope<'a> {
+    components: HashMap<&'a str, &'a Component>,
+    classes: &'a ClassIndex<'a>,
+}
+
+impl<'a> Scope<'a> {
+    fn of(class: &'a ClassDef, classes: &'a ClassIndex<'a>) -> Scope<'a> {
+        let mut components = HashMap::new();
+        collect_components(class, classes, 0, &mut components);
+        Scope {
+            components,
+            classes,
+        }
+    }
+
+    /// The unit a (possibly dotted) reference declares.
+    fn unit_of(&self, parts: &[&str]) -> Inferred {
+        if parts == ["time"] && !self.components.contains_key("time") {
+            return Inferred::known(Unit::parse("s").ok(), Some("s"));
+        }
+        let Some((first, rest)) = parts.split_first() else {
+            return Inferred::Unknown;
+        };
+        let Some(mut component) = self.components.get(*first).copied() else {
+            return Inferred::Unknown;
+        };
+        for part in rest {
+            let Some(class) = self.classes.get(&component.type_name.to_string()) else {
+                return Inferred::Unknown;
+            };
+            let mut inner = HashMap::new();
+            collect_components(class, self.classes, 0, &mut inner);
+            let Some(next) = inner.get(*part).copied() else {
+                return Inferred::Unknown;
+            };
+            component = next;
+        }
+        declared_unit(component)
+    }
+}
+
+fn collect_components<'a>(
+    class: &'a ClassDef,
+    classes: &ClassIndex<'a>,
+    depth: usize,
+    out: &mut HashMap<&'a str, &'a Component>,
+) {
+    if depth > MAX_EXTENDS_DEPTH {
+        return;
+    }
+    for ext in &class.extends {
+        if let Some(base) = classes.get(&ext.base_name.to_string()) {
+            collect_components(base, classes, depth + 1, out);
+        }
+    }
+    for (name, component) in &class.components {
+        out.insert(name.as_str(), component);
+    }
+}
+
+/// A component's unit: its own `unit` modifier, else the unit its MSL SI type
+/// stands for. A unit string this crate cannot parse (`"MB"`, `"Ah"`) is
+/// unknown, not an error — it is a label, and the lint has nothing to say
+/// about it.
+fn declared_unit(component: &Component) -> Inferred {
+    let text = match component.modifications.get("unit") {
+        Some(Expression::Terminal {
+            terminal_type: TerminalType::String,
+            token,
+            ..
+        }) => Some(token.text.trim_matches('"').to_string()),
+        _ => si_type_unit(&component.type_name.to_string()).map(str::to_string),
+    };
+    match text {
+        Some(text) => Inferred::known(Unit::parse(&text).ok(), Some(&text)),
+        None => Inferred::Unknown,
+    }
+}
+
+/// The unit behind a `Modelica.Units.SI` type. Only qualified spellings count:
+/// a bare `Velocity` may be anybody's class.
+fn si_type_unit(type_name: &str) -> Option<&'static str> {
+    let leaf = [
+        "Modelica.Units.SI.",
+        "Modelica.SIunits.",
+        "Units.SI.",
+        "SI.",
+    ]
+    .iter()
+    .find_map(|prefix| type_name.strip_prefix(prefix))?;
+    Some(match leaf {
+        "Length" | "Distance" | "Position" | "Height" | "Radius" | "Diameter" => "m",
+        "Area" => "m2",
+        "Volume" => "m3",
+        "Time" => "s",
+        "Frequency" => "Hz",
+        "Mass" => "kg",
+        "Density" => "kg/m3",
+        "Velocity" => "m/s",
+        "Acceleration" => "m/s2",
+        "Angle" => "rad",
+        "AngularVelocity" => "rad/s",
+        "AngularAcceleration" => "rad/s2",
+        "Force" => "N",
+        "Torque" => "N.m",
+        "Inertia" | "MomentOfInertia" => "kg.m2",
+        "Pressure" | "AbsolutePressure" => "Pa",
+        "Energy" | "Work" => "J",
+        "Power" => "W",
+        "MassFlowRate" => "kg/s",
+        "VolumeFlowRate" => "m3/s",
+        "Temperature" | "ThermodynamicTemperature" | "TemperatureDifference" => "K",
+        "HeatFlowRate" => "W",
+        "ThermalConductance" => "W/K",
+        "HeatCapacity" => "J/K",
+        "SpecificHeatCapacity" => "J/(kg.K)",
+        "SpecificEnergy" | "SpecificEnthalpy" => "J/kg",
+        "Voltage" | "ElectricPotential" => "V",
+        "Current" | "ElectricCurrent" => "A",
+        "ElectricCharge" => "C",
+        "Resistance" => "Ohm",
+        "Conductance" => "S",
+        "Capacitance" => "F",
+        "Inductance" => "H",
+        "MagneticFlux" => "Wb",
+        "TranslationalSpringConstant" => "N/m",
+        "TranslationalDampingConstant" => "N.s/m",
+        "RotationalSpringConstant" => "N.m/rad",
+        "RotationalDampingConstant" => "N.m.s/rad",
+        _ => return None,
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Inference
+// ---------------------------------------------------------------------------
+
+/// What an expression's unit is known to be.
+#[derive(Debug, Clone)]
+enum Inferred {
+    /// A declared unit, carried with its declared spelling while it is still a
+    /// bare reference (for messages).
+    Known(Unit, Option<String>),
+    /// A numeric literal: any unit the context needs.
+    Literal,
+    /// Nothing declared, or nothing this pass can follow.
+    Unknown,
+}
+
+impl Inferred {
+    fn known(unit: Option<Unit>, label: Option<&str>) -> Inferred {
+        match unit {
+            Some(unit) => Inferred::Known(unit, label.map(str::to_string)),
+            None => Inferred::Unknown,
+        }
+    }
+
+    fn unit(unit: Unit) -> Inferred {
+        Inferred::Known(unit, None)
+    }
+}
+
+fn show(unit: &Unit, label: &Option<String>) -> String {
+    match label {
+        Some(label) => label.clone(),
+        None if (unit.scale - 1.0).abs() < 1e-12 => unit.dimension.to_string(),
+        None => format!("{} × {}", unit.scale, unit.dimension),
+    }
+}
+
+struct Inference<'s, 'a> {
+    scope: &'s Scope<'a>,
+    found: Option<UnitMismatch>,
+}
+
+impl Inference<'_, '_> {
+    /// Record the first disagreement between two operands that must share a
+    /// unit. `kind` is the severity of a dimension clash in this position; a
+    /// scale-only clash is always [`MismatchKind::Scale`].
+    fn compare(&mut self, subject: &str, a: &Inferred, b: &Inferred, kind: MismatchKind) {
+        if self.found.is_some() {
+            return;
+        }
+        let (Inferred::Known(ua, la), Inferred::Known(ub, lb)) = (a, b) else {
+            return;
+        };
+        let kind = match ua.conversion_to(ub) {
+            None => kind,
+            Some(c) if !c.is_identity() => MismatchKind::Scale,
+            Some(_) => return,
+        };
+        self.found = Some(UnitMismatch {
+            class: String::new(),
+            line: 0,
+            subject: subject.to_string(),
+            lhs: show(ua, la),
+            rhs: show(ub, lb),
+            kind,
+        });
+    }
+
+    /// Operands of a sum, `min`/`max`, `atan2`: must agree; the result is
+    /// whichever is known.
+    fn same(&mut self, subject: &str, a: Inferred, b: Inferred) -> Inferred {
+        self.compare(subject, &a, &b, MismatchKind::Dimension);
+        match (a, b) {
+            (Inferred::Known(u, _), _) | (_, Inferred::Known(u, _)) => Inferred::unit(u),
+            (Inferred::Literal, Inferred::Literal) => Inferred::Literal,
+            _ => Inferred::Unknown,
+        }
+    }
+
+    /// Argument of `sin`, `exp`, `log`: must be dimensionless.
+    fn dimensionless_arg(&mut self, name: &str, arg: Inferred) -> Inferred {
+        if let Inferred::Known(u, label) = &arg {
+            if !u.is_dimensionless() && self.found.is_none() {
+                self.found = Some(UnitMismatch {
+                    class: String::new(),
+                    line: 0,
+                    subject: format!("{name}(...)"),
+                    lhs: show(u, label),
+                    rhs: Dimension::ONE.to_string(),
+                    kind: MismatchKind::Argument,
+                });
+            }
+        }
+        Inferred::unit(Unit::ONE)
+    }
+
+    fn expr(&mut self, expr: &Expression) -> Inferred {
+        match expr {
+            Expression::Terminal {
+                terminal_type: TerminalType::UnsignedReal | TerminalType::UnsignedInteger,
+                ..
+            } => Inferred::Literal,
+            Expression::ComponentReference(cr) => {
+                let parts: Vec<&str> = cr.parts.iter().map(|p| p.ident.text.as_ref()).collect();
+                self.scope.unit_of(&parts)
+            }
+            Expression::Unary {
+                op: OpUnary::Minus | OpUnary::Plus,
+                rhs,
+                ..
+            } => self.expr(rhs),
+            Expression::Parenthesized { inner, .. } => self.expr(inner),
+            Expression::Binary { op, lhs, rhs, .. } => {
+                let a = self.expr(lhs);
+                let b = self.expr(rhs);
+                match op {
+                    OpBinary::Add => self.same("+", a, b),
+                    OpBinary::Sub => self.same("-", a, b),
+                    OpBinary::Mul => match (a, b) {
+                        (Inferred::Known(x, _), Inferred::Known(y, _)) => Inferred::unit(x.mul(&y)),
+                        (Inferred::Literal, Inferred::Literal) => Inferred::Literal,
+                        _ => Inferred::Unknown,
+                    },
+                    OpBinary::Div => match (a, b) {
+                        (Inferred::Known(x, _), Inferred::Known(y, _)) => Inferred::unit(x.div(&y)),
+                        (Inferred::Literal, Inferred::Literal) => Inferred::Literal,
+                        _ => Inferred::Unknown,
+                    },
+                    OpBinary::Exp => match (a, integer_literal(rhs)) {
+                        (Inferred::Known(x, _), Some(n)) => Inferred::unit(x.powi(n)),
+                        (Inferred::Literal, _) => Inferred::Literal,
+                        (Inferred::Known(x, _), None) if x.is_dimensionless() => {
+                            Inferred::unit(Unit::ONE)
+                        }
+                        _ => Inferred::Unknown,
+                    },
+                    // Comparisons, logic, element-wise operators: the operands
+                    // were still walked for mismatches of their own.
+                    _ => Inferred::Unknown,
+                }
+            }
+            Expression::FunctionCall { comp, args, .. } => self.call(&comp.to_string(), args),
+            _ => Inferred::Unknown,
+        }
+    }
+
+    fn call(&mut self, name: &str, args: &[Expression]) -> Inferred {
+        let mut values: Vec<Inferred> = args.iter().map(|a| self.expr(a)).collect();
+        let leaf = name.rsplit('.').next().unwrap_or(name);
+        match (leaf, values.len()) {
+            ("der", 1) => match values.remove(0) {
+                Inferred::Known(u, _) => {
+                    Inferred::unit(u.div(&Unit::parse("s").expect("`s` is a unit")))
+                }
+                _ => Inferred::Unknown,
+            },
+            ("pre" | "noEvent" | "abs" | "floor" | "ceil" | "homotopy" | "delay", n) if n >= 1 => {
+                values.remove(0)
+            }
+            ("smooth", 2) => values.remove(1),
+            ("min" | "max", 2) => {
+                let b = values.remove(1);
+                let a = values.remove(0);
+                self.same(&format!("{leaf}(...)"), a, b)
+            }
+            ("atan2", 2) => {
+                let b = values.remove(1);
+                let a = values.remove(0);
+                self.same("atan2(...)", a, b);
+                Inferred::unit(Unit::ONE)
+            }
+            ("sign", 1) => Inferred::unit(Unit::ONE),
+            ("sqrt", 1) => match values.remove(0) {
+                Inferred::Known(u, _) => match u.dimension.root(2) {
+                    Some(dimension) => Inferred::unit(Unit {
+                        scale: u.scale.sqrt(),
+                        offset: 0.0,
+                        dimension,
+                    }),
+                    None => Inferred::Unknown,
+                },
+                Inferred::Literal => Inferred::Literal,
+                Inferred::Unknown => Inferred::Unknown,
+            },
+            (
+                "sin" | "cos" | "tan" | "asin" | "acos" | "atan" | "sinh" | "cosh" | "tanh" | "exp"
+                | "log" | "log10",
+                1,
+            ) => {
+                let arg = values.remove(0);
+                self.dimensionless_arg(leaf, arg)
+            }
+            _ => Inferred::Unknown,
+        }
+    }
+}
+
+/// `2`, `-1`, `2.0` as an exponent.
+fn integer_literal(expr: &Expression) -> Option<i32> {
+    match expr {
+        Expression::Terminal {
+            terminal_type: TerminalType::UnsignedInteger | TerminalType::UnsignedReal,
+            token,
+            ..
+        } => {
+            let value: f64 = token.text.parse().ok()?;
+            (value.fract() == 0.0 && value.abs() < 64.0).then_some(value as i32)
+        }
+        Expression::Unary {
+            op: OpUnary::Minus,
+            rhs,
+            ..
+        } => integer_literal(rhs).map(|n| -n),
+        Expression::Parenthesized { inner, .. } => integer_literal(inner),
+        _ => None,
+    }
+}
+
+/// How an equation's left-hand side reads in a message.
+fn describe(lhs: &Expression) -> String {
+    match lhs {
+        Expression::ComponentReference(cr) => cr.to_string(),
+        Expression::FunctionCall { comp, args, .. } => match args.first() {
+            Some(Expression::ComponentReference(cr)) if args.len() == 1 => {
+                format!("{comp}({cr})")
+            }
+            _ => format!("{comp}(...)"),
+        },
+        _ => "equation".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mismatches(src: &str) -> Vec<UnitMismatch> {
+        let syntax = rumoca_phase_parse::parse_to_syntax(src, "M.mo");
+        unit_mismatches(syntax.best_effort())
+    }
+
+    #[test]
+    fn consistent_declared_units_report_nothing() {
+        let found = mismatches(
+            "model M\n  parameter Real m(unit=\"kg\") = 2;\n  Real v(unit=\"m/s\");\n  Real F(unit=\"N\");\n  Real p(unit=\"kg.m/s\");\nequation\n  F = m * der(v);\n  p = m * v;\n  v = 1.0 - 0.5 * time;\nend M;\n",
+        );
+        assert!(found.is_empty(), "{found:?}");
+    }
+
+    #[test]
+    fn a_force_equated_to_a_momentum_is_a_dimension_mismatch() {
+        let found = mismatches(
+            "model M\n  parameter Real m(unit=\"kg\") = 2;\n  Real v(unit=\"m/s\");\n  Real F(unit=\"N\");\nequation\n  F = m * v;\n  der(v) = 1;\nend M;\n",
+        );
+        assert_eq!(found.len(), 1, "{found:?}");
+        assert_eq!(found[0].kind, MismatchKind::Dimension);
+        assert_eq!(found[0].subject, "F");
+        assert_eq!(found[0].lhs, "N");
+        assert_eq!(found[0].line, 6);
+    }
+
+    /// The silent-factor case: `rpm` and `rad/s` are one dimension, so only
+    /// the scale check can see it.
+    #[test]
+    fn rpm_equated_to_rad_per_second_is_a_scale_mismatch() {
+        let found = mismatches(
+            "model M\n  Real n(unit=\"rpm\");\n  Real w(unit=\"rad/s\");\nequation\n  w = n;\n  n = 100;\nend M;\n",
+        );
+        assert_eq!(found.len(), 1, "{found:?}");
+        assert_eq!(found[0].kind, MismatchKind::Scale);
+        assert_eq!(
+            (found[0].lhs.as_str(), found[0].rhs.as_str()),
+            ("rad/s", "rpm")
+        );
+    }
+
+    /// Terms of a sum and arguments of `max` are checked, not only the sides.
+    #[test]
+    fn mismatched_terms_inside_an_expression_are_found() {
+        let found = mismatches(
+            "model M\n  Real soc(unit=\"1\");\n  Real rate(unit=\"1/s\");\nequation\n  der(soc) = max(-soc, rate);\n  rate = 0;\nend M;\n",
+        );
+        assert_eq!(found.len(), 1, "{found:?}");
+        assert_eq!(found[0].subject, "max(...)");
+    }
+
+    /// Literals and undeclared variables never produce a finding: `0.1` might
+    /// carry `1/s`, `k` might be anything.
+    #[test]
+    fn literals_and_undeclared_variables_are_not_guessed() {
+        let found = mismatches(
+            "model M\n  Real x(unit=\"m\");\n  Real k;\nequation\n  der(x) = -0.1 * x + k;\n  k = sin(x / 2);\nend M;\n",
+        );
+        assert!(found.is_empty(), "{found:?}");
+    }
+
+    #[test]
+    fn dotted_references_resolve_through_same_file_classes() {
+        let found = mismatches(
+            "model Tank\n  Real level(unit=\"m\");\nequation\n  der(level) = 0;\nend Tank;\nmodel Plant\n  Tank tank;\n  Real p(unit=\"Pa\");\nequation\n  p = tank.level;\nend Plant;\n",
+        );
+        assert_eq!(found.len(), 1, "{found:?}");
+        assert_eq!(found[0].class, "Plant");
+        assert_eq!((found[0].lhs.as_str(), found[0].rhs.as_str()), ("Pa", "m"));
+    }
+}
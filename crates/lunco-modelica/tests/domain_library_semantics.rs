@@ -17,7 +17,9 @@ fn battery_discharge_current_reduces_soc() {
         "Battery Pin.i is negative while supplying positive-current loads, so discharge must reduce SoC"
     );
     assert!(
-        source.contains("der(soc) = max(-soc, min(1.0 - soc, soc_rate));"),
+        source.contains(
+            "der(soc) = max(-soc / soc_horizon, min((1.0 - soc) / soc_horizon, soc_rate));"
+        ),
         "finite storage must keep the battery state inside the physical [0, 1] interval"
     );
     assert!(
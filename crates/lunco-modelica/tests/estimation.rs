@@ -0,0 +1,96 @@
+use std::collections::BTreeMap;
+
+use lunco_experiments::{
+    EstimatedParam, EstimationSpec, FitSignal, Optimizer, ParamPath, RunMeta, RunResult,
+};
+use lunco_modelica::{estimation::estimate_model, sim_target::default_bounds, ModelicaCompiler};
+
+/// `τ·ẋ = k − x` from rest: `x = k·(1 − e^(−t/τ))`.
+fn first_order() -> &'static str {
+    "model Lag\n  parameter Real k = 1;\n  parameter Real tau = 2;\n  Real x(start = 0);\n\
+     equation\n  tau * der(x) = k - x;\nend Lag;"
+}
+
+fn measured(k: f64, tau: f64, offset: f64) -> RunResult {
+    let times: Vec<f64> = (0..=40).map(|i| i as f64 * 0.05).collect();
+    let x = times.iter().map(|t| k * (1.0 - (-t / tau).exp())).collect();
+    RunResult {
+        times: times.iter().map(|t| t + offset).collect(),
+        series: BTreeMap::from([("x_meas".to_string(), x)]),
+        meta: RunMeta::default(),
+    }
+}
+
+fn spec() -> EstimationSpec {
+    EstimationSpec {
+        parameters: vec![
+            EstimatedParam {
+                path: ParamPath("k".into()),
+                lower: 0.1,
+                upper: 10.0,
+                initial: Some(1.0),
+            },
+            EstimatedParam {
+                path: ParamPath("tau".into()),
+                lower: 0.05,
+                upper: 5.0,
+                initial: Some(2.0),
+            },
+        ],
+        signals: vec![FitSignal {
+            name: "x".into(),
+            measured: Some("x_meas".into()),
+            weight: 1.0,
+        }],
+        optimizer: Optimizer::NelderMead,
+        max_evaluations: 300,
+        time_offset: 10.0,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn first_order_lag_is_fitted_to_its_response() {
+    let mut compiler = ModelicaCompiler::new();
+    let compiled = compiler
+        .compile_str("Lag", first_order(), "lag.mo")
+        .expect("first-order lag compiles");
+    let report = estimate_model(
+        &compiled.dae,
+        &default_bounds(),
+        &BTreeMap::new(),
+        "Lag",
+        &spec(),
+        &measured(3.0, 0.8, 10.0),
+    )
+    .expect("fits");
+
+    let k = report.parameters[0].value;
+    let tau = report.parameters[1].value;
+    assert!((k - 3.0).abs() < 1e-2, "k = {k}");
+    assert!((tau - 0.8).abs() < 1e-2, "tau = {tau}");
+    assert!(report.cost < 1e-3 * report.cost_initial);
+    let run = report.run.as_ref().expect("fitted run");
+    assert!(*run.times.last().unwrap() >= 2.0 - 1e-9);
+    assert_eq!(report.signals[0].residuals.len(), 41);
+}
+
+#[test]
+fn a_measurement_before_the_run_start_is_refused() {
+    let mut compiler = ModelicaCompiler::new();
+    let compiled = compiler
+        .compile_str("Lag", first_order(), "lag.mo")
+        .expect("first-order lag compiles");
+    let mut spec = spec();
+    spec.time_offset = 11.0;
+    let err = estimate_model(
+        &compiled.dae,
+        &default_bounds(),
+        &BTreeMap::new(),
+        "Lag",
+        &spec,
+        &measured(3.0, 0.8, 10.0),
+    )
+    .unwrap_err();
+    assert!(err.contains("time offset"), "{err}");
+}
@@ -1,6 +1,6 @@
 use lunco_experiments::solver::{self, RuntimeProfile, SolverParams, SolverRequest};
 use lunco_modelica::{
-    fixed_step::FixedStepSession,
+    fixed_step::{FixedStepMethod, FixedStepSession},
     simulation_session::{self, LiveStepper},
     solver_backends, ModelicaCompiler,
 };
@@ -14,6 +14,16 @@ fn input_ramp_model() -> &'static str {
     "model FixedInputRamp\n  input Real u = 0;\n  Real x(start=0);\nequation\n  der(x) = u;\nend FixedInputRamp;"
 }
 
+/// A latch: the rate flips once `x` passes 0.255, halfway through a step.
+fn latch_model() -> &'static str {
+    "model FixedLatch\n  Real x(start=0);\n  discrete Real rate(start=1, fixed=true);\nequation\n  der(x) = rate;\n  when x > 0.255 then\n    rate = -1;\n  end when;\nend FixedLatch;"
+}
+
+/// Relaxation with a 1 ms time constant: stiff at a 10 ms step.
+fn stiff_model() -> &'static str {
+    "model FixedStiff\n  Real x(start=0);\nequation\n  der(x) = -1000 * (x - 1);\nend FixedStiff;"
+}
+
 fn options() -> SimOptions {
     SimOptions {
         solver_mode: SimSolverMode::RkLike,
@@ -30,7 +40,8 @@ fn fixed_rk4_uses_exactly_the_configured_step_lattice() {
     let compiled = compiler
         .compile_str("FixedRamp", ramp_model(), "fixed_ramp.mo")
         .expect("fixed ramp compiles");
-    let mut session = FixedStepSession::new(&compiled.dae, options()).expect("fixed session");
+    let mut session = FixedStepSession::new(&compiled.dae, FixedStepMethod::Rk4, options())
+        .expect("fixed session");
 
     for _ in 0..10 {
         session.step(0.01).expect("one fixed step");
@@ -48,8 +59,10 @@ fn fixed_rk4_repeats_the_same_operation_sequence() {
     let compiled = compiler
         .compile_str("FixedRamp", ramp_model(), "fixed_ramp.mo")
         .expect("fixed ramp compiles");
-    let mut left = FixedStepSession::new(&compiled.dae, options()).expect("left session");
-    let mut right = FixedStepSession::new(&compiled.dae, options()).expect("right session");
+    let mut left = FixedStepSession::new(&compiled.dae, FixedStepMethod::Rk4, options())
+        .expect("left session");
+    let mut right = FixedStepSession::new(&compiled.dae, FixedStepMethod::Rk4, options())
+        .expect("right session");
 
     for _ in 0..100 {
         left.step(0.01).expect("left fixed step");
@@ -185,3 +198,105 @@ fn predicted_live_runtime_is_bitwise_deterministic_over_an_input_schedule() {
         }
     }
 }
+
+/// A `when` inside a step is localized, not sampled at the lattice: the rate
+/// flips at x = 0.255 rather than at the next step boundary (0.26), and two
+/// sessions agree on the result bit for bit.
+#[test]
+fn fixed_rk4_localizes_an_event_inside_a_step() {
+    let mut compiler = ModelicaCompiler::new();
+    let compiled = compiler
+        .compile_str("FixedLatch", latch_model(), "fixed_latch.mo")
+        .expect("fixed latch compiles");
+    let mut left = FixedStepSession::new(&compiled.dae, FixedStepMethod::Rk4, options())
+        .expect("event models are accepted");
+    let mut right = FixedStepSession::new(&compiled.dae, FixedStepMethod::Rk4, options())
+        .expect("event models are accepted");
+
+    for _ in 0..30 {
+        left.step(0.01).expect("left fixed step");
+        right.step(0.01).expect("right fixed step");
+    }
+
+    let x = left.get("x").expect("read x").expect("x is visible");
+    assert!((x - 0.21).abs() < 1.0e-9, "x={x}");
+    let rate = left
+        .get("rate")
+        .expect("read rate")
+        .expect("rate is visible");
+    assert_eq!(rate, -1.0);
+    assert_eq!(
+        x.to_bits(),
+        right
+            .get("x")
+            .expect("right x")
+            .expect("right visible")
+            .to_bits()
+    );
+}
+
+/// At a step RK4 cannot survive, Radau IIA settles on the equilibrium.
+#[test]
+fn fixed_radau3_integrates_a_stiff_island_at_the_configured_step() {
+    let mut compiler = ModelicaCompiler::new();
+    let compiled = compiler
+        .compile_str("FixedStiff", stiff_model(), "fixed_stiff.mo")
+        .expect("fixed stiff compiles");
+    let mut radau = FixedStepSession::new(&compiled.dae, FixedStepMethod::Radau3, options())
+        .expect("radau session");
+    let mut rk4 =
+        FixedStepSession::new(&compiled.dae, FixedStepMethod::Rk4, options()).expect("rk4 session");
+
+    let mut rk4_failed = false;
+    for _ in 0..100 {
+        radau.step(0.01).expect("radau fixed step");
+        rk4_failed |= rk4.step(0.01).is_err();
+    }
+
+    let x = radau.get("x").expect("read x").expect("x is visible");
+    assert!((x - 1.0).abs() < 1.0e-6, "x={x}");
+    let rk4_x = rk4.get("x").ok().flatten().unwrap_or(f64::NAN);
+    assert!(
+        rk4_failed || (rk4_x - 1.0).abs() > 1.0,
+        "RK4 at h*lambda = -10 should be unstable, got x={rk4_x}"
+    );
+}
+
+/// The stiff backend is not the default, but a predicted model may name it.
+#[test]
+fn an_authored_radau3_request_serves_a_predicted_model() {
+    let mut compiler = ModelicaCompiler::new();
+    let compiled = compiler
+        .compile_str("FixedStiff", stiff_model(), "fixed_stiff.mo")
+        .expect("fixed stiff compiles");
+
+    solver_backends::ensure_builtin_solvers();
+    let spec = solver::resolve(&SolverRequest {
+        profile: RuntimeProfile {
+            live: true,
+            predicted: true,
+        },
+        authored: Some(solver::SolverId::from("fixed-radau3")),
+    })
+    .expect("fixed-radau3 is fixed-step deterministic");
+    let options = solver_backends::rumoca_options(
+        &spec,
+        &SolverParams {
+            atol: 1.0e-6,
+            rtol: 1.0e-6,
+            h0: Some(0.01),
+            t_start: 0.0,
+            t_end: 1.0,
+        },
+    )
+    .expect("fixed-radau3 options are valid");
+    let mut stepper = simulation_session::live(&compiled.dae, &spec, options)
+        .expect("the authored backend constructs");
+
+    let LiveStepper::Fixed(session) = &stepper else {
+        panic!("fixed-radau3 must construct the fixed-step session");
+    };
+    assert_eq!(session.method(), FixedStepMethod::Radau3);
+    stepper.step(0.01).expect("radau prediction step");
+    assert_eq!(stepper.time().to_bits(), 0.01_f64.to_bits());
+}
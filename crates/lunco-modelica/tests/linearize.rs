@@ -0,0 +1,96 @@
+use lunco_experiments::linear::JacobianMethod;
+use lunco_modelica::{
+    linearize::{linearize, LinearizeSpec},
+    ModelicaCompiler,
+};
+
+/// `m·ẍ = u − c·ẋ − k·x`, `y = x`: `A = [0 1; −k/m −c/m]`, `B = [0; 1/m]`,
+/// `C = [1 0]`, `D = 0`.
+fn mass_spring_damper() -> &'static str {
+    "model Msd\n  parameter Real m = 2;\n  parameter Real c = 0.5;\n  parameter Real k = 8;\n  \
+     input Real u = 0;\n  output Real y;\n  Real x(start = 0);\n  Real v(start = 0);\n\
+     equation\n  der(x) = v;\n  m * der(v) = u - c * v - k * x;\n  y = x;\nend Msd;"
+}
+
+fn assert_close(got: &[Vec<f64>], want: &[&[f64]], what: &str) {
+    assert_eq!(got.len(), want.len(), "{what} rows");
+    for (row, expected) in got.iter().zip(want) {
+        assert_eq!(row.len(), expected.len(), "{what} columns");
+        for (g, w) in row.iter().zip(expected.iter()) {
+            assert!((g - w).abs() < 1e-6, "{what}: got {got:?}, want {want:?}");
+        }
+    }
+}
+
+#[test]
+fn mass_spring_damper_linearizes_to_its_matrices() {
+    let mut compiler = ModelicaCompiler::new();
+    let compiled = compiler
+        .compile_str("Msd", mass_spring_damper(), "msd.mo")
+        .expect("mass-spring-damper compiles");
+    let system = linearize(
+        &compiled.dae,
+        "Msd",
+        &LinearizeSpec {
+            jacobian: JacobianMethod::Richardson,
+            ..Default::default()
+        },
+    )
+    .expect("linearizes");
+
+    assert_eq!(system.states, ["x", "v"]);
+    assert_eq!(system.inputs, ["u"]);
+    assert_eq!(system.outputs, ["y"]);
+    assert_close(&system.a, &[&[0.0, 1.0], &[-4.0, -0.25]], "A");
+    assert_close(&system.b, &[&[0.0], &[0.5]], "B");
+    assert_close(&system.c, &[&[1.0, 0.0]], "C");
+    assert_close(&system.d, &[&[0.0]], "D");
+
+    // Underdamped: −c/2m ± j·√(k/m − (c/2m)²).
+    let im = (4.0f64 - 0.125 * 0.125).sqrt();
+    let mut poles = system.poles();
+    poles.sort_by(|a, b| a.im.total_cmp(&b.im));
+    assert_eq!(poles.len(), 2);
+    for (p, want_im) in poles.iter().zip([-im, im]) {
+        assert!((p.re + 0.125).abs() < 1e-6, "pole {p:?}");
+        assert!((p.im - want_im).abs() < 1e-6, "pole {p:?}");
+    }
+}
+
+#[test]
+fn steady_state_holds_the_input() {
+    let mut compiler = ModelicaCompiler::new();
+    let compiled = compiler
+        .compile_str("Msd", mass_spring_damper(), "msd.mo")
+        .expect("mass-spring-damper compiles");
+    let system = linearize(
+        &compiled.dae,
+        "Msd",
+        &LinearizeSpec {
+            steady_state: true,
+            input_values: vec![("u".into(), 4.0)],
+            ..Default::default()
+        },
+    )
+    .expect("linearizes at steady state");
+
+    // k·x = u → x = 0.5, at rest.
+    let op = &system.operating_point;
+    assert!(op.steady_state);
+    assert!((op.states[0] - 0.5).abs() < 1e-6, "x = {}", op.states[0]);
+    assert!(op.states[1].abs() < 1e-6, "v = {}", op.states[1]);
+    assert_eq!(op.inputs, [4.0]);
+    assert!((op.outputs[0] - 0.5).abs() < 1e-6);
+    // The model is linear, so the matrices do not move with the point.
+    assert_close(&system.a, &[&[0.0, 1.0], &[-4.0, -0.25]], "A");
+
+    let unknown = linearize(
+        &compiled.dae,
+        "Msd",
+        &LinearizeSpec {
+            inputs: vec!["w".into()],
+            ..Default::default()
+        },
+    );
+    assert!(unknown.unwrap_err().contains("not an input"));
+}
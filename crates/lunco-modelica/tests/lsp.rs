@@ -0,0 +1,158 @@
+//! The language server driven message by message through `Server::handle`.
+//! No `initialized` notification is sent, so no MSL or workspace library is
+//! loaded — everything here resolves between the open documents.
+
+use lunco_modelica::lsp::Server;
+use serde_json::{json, Value};
+
+const TANK_URI: &str = "file:///work/Demo/Tank.mo";
+const PLANT_URI: &str = "file:///work/Plant.mo";
+
+const TANK: &str = "within Demo;\n\
+model Tank \"A leaky tank\"\n  \
+parameter Real area = 2 \"Cross-section\";\n  \
+Real level(start = 1);\n\
+equation\n  \
+area * der(level) = -0.1 * level;\n\
+end Tank;\n";
+
+const PLANT: &str = "model Plant\n  Demo.Tank tank;\nequation\n  tank.level = 1;\nend Plant;\n";
+
+fn request(server: &mut Server, id: u64, method: &str, params: Value) -> Value {
+    let replies = server.handle(&json!({
+        "jsonrpc": "2.0", "id": id, "method": method, "params": params,
+    }));
+    assert_eq!(replies.len(), 1, "{method}: {replies:?}");
+    replies.into_iter().next().unwrap()
+}
+
+fn at(uri: &str, line: u32, character: u32) -> Value {
+    json!({ "textDocument": { "uri": uri }, "position": { "line": line, "character": character } })
+}
+
+fn open(server: &mut Server, uri: &str, text: &str) -> Value {
+    let out = server.handle(&json!({
+        "jsonrpc": "2.0",
+        "method": "textDocument/didOpen",
+        "params": { "textDocument": { "uri": uri, "languageId": "modelica", "version": 1, "text": text } },
+    }));
+    assert_eq!(out[0]["method"], "textDocument/publishDiagnostics");
+    out[0]["params"]["diagnostics"].clone()
+}
+
+fn started() -> Server {
+    let mut server = Server::new();
+    let init = request(&mut server, 1, "initialize", json!({ "rootUri": null }));
+    assert_eq!(init["result"]["capabilities"]["hoverProvider"], true);
+    assert_eq!(open(&mut server, TANK_URI, TANK), json!([]));
+    assert_eq!(open(&mut server, PLANT_URI, PLANT), json!([]));
+    server
+}
+
+#[test]
+fn requests_before_initialize_are_refused() {
+    let mut server = Server::new();
+    let reply = request(&mut server, 1, "textDocument/hover", at(TANK_URI, 0, 0));
+    assert_eq!(reply["error"]["code"], -32002);
+}
+
+#[test]
+fn hover_and_definition_resolve_components_and_classes() {
+    let mut server = started();
+
+    let hover = request(&mut server, 2, "textDocument/hover", at(TANK_URI, 5, 3));
+    let text = hover["result"]["contents"]["value"].as_str().unwrap();
+    assert!(text.contains("parameter Real area = 2"), "{text}");
+    assert!(text.contains("Cross-section"), "{text}");
+
+    let def = request(
+        &mut server,
+        3,
+        "textDocument/definition",
+        at(TANK_URI, 5, 14),
+    );
+    assert_eq!(def["result"]["uri"], TANK_URI);
+    assert_eq!(
+        def["result"]["range"]["start"],
+        json!({ "line": 3, "character": 7 })
+    );
+
+    // `Demo.Tank` in another document lands on the class name.
+    let def = request(
+        &mut server,
+        4,
+        "textDocument/definition",
+        at(PLANT_URI, 1, 8),
+    );
+    assert_eq!(def["result"]["uri"], TANK_URI);
+    assert_eq!(
+        def["result"]["range"]["start"],
+        json!({ "line": 1, "character": 6 })
+    );
+
+    let completion = request(
+        &mut server,
+        5,
+        "textDocument/completion",
+        at(PLANT_URI, 3, 7),
+    );
+    let labels: Vec<&str> = completion["result"]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|i| i["label"].as_str())
+        .collect();
+    assert!(
+        labels.contains(&"area") && labels.contains(&"level"),
+        "{labels:?}"
+    );
+}
+
+#[test]
+fn symbols_rename_and_formatting() {
+    let mut server = started();
+
+    let symbols = request(
+        &mut server,
+        2,
+        "textDocument/documentSymbol",
+        json!({ "textDocument": { "uri": TANK_URI } }),
+    );
+    let tank = &symbols["result"][0];
+    assert_eq!(tank["name"], "Tank");
+    let children: Vec<&str> = tank["children"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|c| c["name"].as_str())
+        .collect();
+    assert_eq!(children, ["area", "level"]);
+
+    let mut params = at(TANK_URI, 5, 3);
+    params["newName"] = json!("model");
+    let reply = request(&mut server, 3, "textDocument/rename", params.clone());
+    assert_eq!(reply["error"]["code"], -32602);
+
+    params["newName"] = json!("gain");
+    let reply = request(&mut server, 4, "textDocument/rename", params);
+    let edits = reply["result"]["changes"][TANK_URI].as_array().unwrap();
+    assert_eq!(edits.len(), 2, "{edits:?}");
+    assert!(edits.iter().all(|e| e["newText"] == "gain"));
+
+    let reply = request(
+        &mut server,
+        5,
+        "textDocument/formatting",
+        json!({ "textDocument": { "uri": TANK_URI }, "options": { "tabSize": 2, "insertSpaces": true } }),
+    );
+    assert!(reply["result"].is_array(), "{reply}");
+
+    let reply = request(&mut server, 6, "workspace/symbol", json!({ "query": "" }));
+    assert_eq!(reply["error"]["code"], -32601);
+
+    request(&mut server, 7, "shutdown", Value::Null);
+    assert!(server
+        .handle(&json!({ "jsonrpc": "2.0", "method": "exit" }))
+        .is_empty());
+    assert_eq!(server.exit_code(), Some(0));
+}
@@ -0,0 +1,72 @@
+//! The regression runner end to end: bless a reference from a run, check the
+//! same case against it, then check a perturbed case and see it fail.
+
+use std::path::PathBuf;
+
+use lunco_modelica::regression::{run_suite, write_reports};
+
+fn scratch_dir(tag: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("lunco_regress_{tag}_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("scratch dir");
+    dir
+}
+
+fn write_suite(dir: &std::path::Path, gain: f64) -> PathBuf {
+    std::fs::write(
+        dir.join("Decay.mo"),
+        "model Decay\n  parameter Real k = 1;\n  Real x(start=1, fixed=true);\n\
+         equation\n  der(x) = -k*x;\nend Decay;",
+    )
+    .expect("model");
+    let suite = dir.join("suite.json");
+    std::fs::write(
+        &suite,
+        format!(
+            r#"{{
+              "name": "decay",
+              "tube": {{ "rel": 1e-3, "abs": 1e-6 }},
+              "cases": [{{
+                "name": "nominal",
+                "model": "Decay.mo",
+                "t_end": 2.0,
+                "n_intervals": 100,
+                "overrides": {{ "k": {gain} }},
+                "signals": ["x"],
+                "reference": "ref/decay.mat"
+              }}]
+            }}"#
+        ),
+    )
+    .expect("suite");
+    suite
+}
+
+#[test]
+fn blessed_reference_passes_and_a_changed_parameter_fails() {
+    let dir = scratch_dir("bless");
+    std::fs::create_dir_all(dir.join("ref")).unwrap();
+    let suite = write_suite(&dir, 1.0);
+
+    let blessed = run_suite(&suite, true).expect("bless runs");
+    assert!(blessed.passed(), "{:?}", blessed.cases[0].error);
+    assert!(dir.join("ref/decay.mat").exists());
+
+    let same = run_suite(&suite, false).expect("check runs");
+    assert!(same.passed(), "{:?}", same.cases[0].signals);
+    assert_eq!(same.cases[0].signals[0].max_deviation, 0.0);
+
+    let suite = write_suite(&dir, 1.1);
+    let changed = run_suite(&suite, false).expect("check runs");
+    assert!(!changed.passed());
+    let x = &changed.cases[0].signals[0];
+    assert!(x.max_deviation > 1e-2, "{x:?}");
+
+    let junit = dir.join("report.xml");
+    let html = dir.join("report.html");
+    write_reports(&changed, Some(&junit), Some(&html)).expect("reports");
+    let xml = std::fs::read_to_string(&junit).unwrap();
+    assert!(xml.contains("failures=\"1\""), "{xml}");
+    assert!(std::fs::read_to_string(&html).unwrap().contains("<svg"));
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -3294,6 +3294,9 @@ impl Plugin for SpawnCommandPlugin {
         // Runtime waypoint creation and collision-sensor arrival are shared by
         // the GUI click path and the deterministic headless scene runner.
         crate::runtime_waypoint::register(app);
+        // Owned vessels as MAVLink systems. Publishes into the void unless the
+        // API was started with `--mavlink`; costs nothing with no vessel owned.
+        crate::mavlink_vehicle::register(app);
         // The READ verb for the same entities. Registered here so any binary with
         // the scene verbs answers `QueryEntity` too — the headless server included.
         crate::entity_query::register(app);
@@ -54,6 +54,9 @@ pub mod doc_resolve;
 pub mod entity_query;
 /// `RunLint` — lint the loaded scene on demand, through the authored rules.
 pub mod lint_command;
+/// Vehicle state for the MAVLink transport (heartbeat, attitude, geodetic
+/// position, battery) for every possessed or autopilot-owned vessel.
+pub mod mavlink_vehicle;
 /// Runtime-only waypoint command and shared collision-sensor arrival path.
 pub mod runtime_waypoint;
 /// Shaders as a journaled, synced, live-editable domain (WGSL twin of rhai's
@@ -0,0 +1,268 @@
+//! Vehicle state as MAVLink — what a ground station needs to draw a rover on
+//! the map and show its battery.
+//!
+//! The exposed set is the `SessionRegistry`'s owner table: a vessel someone has
+//! possessed, or an engaged autopilot has claimed, is a MAVLink system; the
+//! transport opens its socket and the GCS sees a vehicle appear. Released, it
+//! goes away again. Per vehicle:
+//!
+//! | message | rate | source |
+//! |---|---|---|
+//! | `HEARTBEAT` | `status_rate_hz` | ground rover; `AUTO` while an autopilot drives it, `MANUAL_INPUT` otherwise |
+//! | `ATTITUDE` | `rate_hz` | [`SurfacePoseQuery`] site rotation + avian body rates |
+//! | `GLOBAL_POSITION_INT` | `rate_hz` | [`SurfacePoseQuery`] geodetic lat/lon/height, avian velocity in NED |
+//! | `SYS_STATUS`, `BATTERY_STATUS` | `status_rate_hz` | the vessel's battery telemetry channels |
+//!
+//! Attitude and position need the scene's site frame: a scene with no (or an
+//! ambiguous) `SiteAnchor` has no latitude to report, so only the heartbeat and
+//! battery go out — a GCS then shows the vehicle connected but unplaced, which
+//! is the truth. The inbound half (sticks, position targets, missions) is the
+//! transport's, as commands; see `lunco_api::transports::mavlink_udp`.
+
+use avian3d::prelude::{AngularVelocity, LinearVelocity};
+use bevy::math::DVec3;
+use bevy::prelude::*;
+use lunco_api::transports::mavlink::{
+    Attitude, BatteryStatus, GlobalPositionInt, Heartbeat, MavMessage, MavlinkPublish,
+    MavlinkVehicles, NedBasis, SysStatus, MAV_AUTOPILOT_GENERIC, MAV_MODE_FLAG_AUTO_ENABLED,
+    MAV_MODE_FLAG_GUIDED_ENABLED, MAV_MODE_FLAG_MANUAL_INPUT_ENABLED, MAV_MODE_FLAG_SAFETY_ARMED,
+    MAV_STATE_ACTIVE, MAV_TYPE_GROUND_ROVER,
+};
+use lunco_autopilot::Autopilot;
+use lunco_celestial::SurfacePoseQuery;
+use lunco_core::telemetry::{SampledParameter, TelemetryValue};
+use lunco_core::{GlobalEntityId, InputPorts, SessionRegistry};
+use std::collections::{HashMap, HashSet};
+
+/// Publication rates, Hz of simulation time.
+#[derive(Resource, Debug, Clone)]
+pub struct MavlinkVehicleSettings {
+    /// `ATTITUDE` + `GLOBAL_POSITION_INT`.
+    pub rate_hz: f64,
+    /// `HEARTBEAT`, `SYS_STATUS`, `BATTERY_STATUS`. MAVLink expects a 1 Hz
+    /// heartbeat; a GCS declares the link lost after a few missed ones.
+    pub status_rate_hz: f64,
+}
+
+impl Default for MavlinkVehicleSettings {
+    fn default() -> Self {
+        Self {
+            rate_hz: 10.0,
+            status_rate_hz: 1.0,
+        }
+    }
+}
+
+/// The last battery reading per vehicle gid, from telemetry.
+#[derive(Debug, Clone, Copy, Default)]
+struct BatteryReading {
+    /// 0..=1.
+    soc: Option<f64>,
+    volts: Option<f64>,
+    amps: Option<f64>,
+}
+
+#[derive(Resource, Debug, Default)]
+pub(crate) struct MavlinkBatteryCache(HashMap<u64, BatteryReading>);
+
+/// Keep the transport's vehicle table equal to the owned set.
+pub(crate) fn sync_mavlink_vehicles(
+    registry: Option<Res<SessionRegistry>>,
+    vehicles: Res<MavlinkVehicles>,
+    mut cache: ResMut<MavlinkBatteryCache>,
+) {
+    let Some(registry) = registry.filter(|r| r.is_changed()) else {
+        return;
+    };
+    vehicles.sync(registry.snapshot().into_iter().map(|(gid, _)| gid));
+    cache.0.retain(|gid, _| vehicles.system_of(*gid).is_some());
+}
+
+/// Route battery channels to the vehicle they measure. A channel is matched
+/// by its last dotted segment (`soc`, `battery.soc`, `battery_voltage`, …) and
+/// attributed to the nearest exposed vehicle among the sampled entity and its
+/// ancestors, so a battery modelled as a child sim component still counts.
+pub(crate) fn cache_battery_telemetry(
+    trigger: On<SampledParameter>,
+    vehicles: Res<MavlinkVehicles>,
+    q_gid: Query<&GlobalEntityId>,
+    q_parents: Query<&ChildOf>,
+    mut cache: ResMut<MavlinkBatteryCache>,
+) {
+    let sample = trigger.event();
+    let TelemetryValue::F64(value) = sample.value else {
+        return;
+    };
+    let leaf = sample.name.rsplit('.').next().unwrap_or_default();
+    let field: fn(&mut BatteryReading, f64) = match leaf {
+        // Authored as a fraction or as percent; 0..=1 is a fraction.
+        "soc" | "battery_soc" | "state_of_charge" => |b, v| {
+            b.soc = Some(if v <= 1.0 {
+                v.max(0.0)
+            } else {
+                (v / 100.0).min(1.0)
+            })
+        },
+        "battery_voltage" | "voltage" => |b, v| b.volts = Some(v),
+        "battery_current" => |b, v| b.amps = Some(v),
+        _ => return,
+    };
+    let mut entity = sample.source;
+    for _ in 0..8 {
+        if let Ok(gid) = q_gid.get(entity) {
+            if vehicles.system_of(gid.get()).is_some() {
+                field(cache.0.entry(gid.get()).or_default(), value);
+                return;
+            }
+        }
+        let Ok(parent) = q_parents.get(entity) else {
+            return;
+        };
+        entity = parent.parent();
+    }
+}
+
+/// Everything outbound, for every exposed vehicle.
+#[allow(clippy::type_complexity, clippy::too_many_arguments)]
+pub(crate) fn publish_mavlink_state(
+    mut commands: Commands,
+    vehicles: Res<MavlinkVehicles>,
+    settings: Res<MavlinkVehicleSettings>,
+    battery: Res<MavlinkBatteryCache>,
+    time: Res<Time>,
+    surface: SurfacePoseQuery,
+    q_vessels: Query<
+        (
+            Entity,
+            &GlobalEntityId,
+            Option<&LinearVelocity>,
+            Option<&AngularVelocity>,
+        ),
+        With<InputPorts>,
+    >,
+    q_autopilots: Query<&Autopilot>,
+    mut last: Local<(f64, f64)>,
+) {
+    let exposed: HashSet<u64> = vehicles.vehicles().into_iter().map(|(g, _)| g).collect();
+    if exposed.is_empty() {
+        return;
+    }
+    let now = time.elapsed_secs_f64();
+    let state_due = now - last.0 >= 1.0 / settings.rate_hz;
+    let status_due = now - last.1 >= 1.0 / settings.status_rate_hz;
+    if state_due {
+        last.0 = now;
+    }
+    if status_due {
+        last.1 = now;
+    }
+    if !state_due && !status_due {
+        return;
+    }
+    let time_boot_ms = (now * 1000.0) as u32;
+    let autopiloted: HashSet<Entity> = q_autopilots
+        .iter()
+        .filter(|ap| ap.engaged)
+        .map(|ap| ap.vessel)
+        .collect();
+
+    for (entity, gid, lin, ang) in &q_vessels {
+        let gid = gid.get();
+        if !exposed.contains(&gid) {
+            continue;
+        }
+        let mut send = |message: MavMessage| commands.trigger(MavlinkPublish { gid, message });
+
+        if status_due {
+            let mode = if autopiloted.contains(&entity) {
+                MAV_MODE_FLAG_AUTO_ENABLED | MAV_MODE_FLAG_GUIDED_ENABLED
+            } else {
+                MAV_MODE_FLAG_MANUAL_INPUT_ENABLED
+            };
+            send(MavMessage::Heartbeat(Heartbeat {
+                custom_mode: 0,
+                mav_type: MAV_TYPE_GROUND_ROVER,
+                autopilot: MAV_AUTOPILOT_GENERIC,
+                base_mode: MAV_MODE_FLAG_SAFETY_ARMED | mode,
+                system_status: MAV_STATE_ACTIVE,
+            }));
+            let b = battery.0.get(&gid).copied().unwrap_or_default();
+            let millivolts = b
+                .volts
+                .map_or(u16::MAX, |v| (v * 1000.0).clamp(0.0, 65534.0) as u16);
+            let centiamps = b
+                .amps
+                .map_or(-1, |a| (a * 100.0).clamp(-32768.0, 32767.0) as i16);
+            let remaining = b.soc.map_or(-1, |s| (s * 100.0).round() as i8);
+            send(MavMessage::SysStatus(SysStatus {
+                sensors_present: 0,
+                sensors_enabled: 0,
+                sensors_health: 0,
+                load: 0,
+                voltage_battery: millivolts,
+                current_battery: centiamps,
+                battery_remaining: remaining,
+            }));
+            let mut voltages = [u16::MAX; 10];
+            voltages[0] = millivolts;
+            send(MavMessage::BatteryStatus(BatteryStatus {
+                current_consumed: -1,
+                energy_consumed: -1,
+                temperature: i16::MAX,
+                voltages,
+                current_battery: centiamps,
+                id: 0,
+                battery_function: 0,
+                battery_type: 0,
+                battery_remaining: remaining,
+            }));
+        }
+
+        if !state_due {
+            continue;
+        }
+        // No unique site frame → no latitude; see the module docs.
+        let Some(pose) = surface.get(entity) else {
+            continue;
+        };
+        let rot = pose.site_rotation;
+        let w = ang.map_or(DVec3::ZERO, |w| w.0);
+        let rates = NedBasis::vector(rot.inverse() * w);
+        let (roll, pitch, yaw) = NedBasis::euler(rot);
+        send(MavMessage::Attitude(Attitude {
+            time_boot_ms,
+            roll: roll as f32,
+            pitch: pitch as f32,
+            yaw: yaw as f32,
+            rollspeed: rates.x as f32,
+            pitchspeed: rates.y as f32,
+            yawspeed: rates.z as f32,
+        }));
+        // Avian's frame IS the site frame, so its velocity is site ENU.
+        let v = NedBasis::vector(lin.map_or(DVec3::ZERO, |v| v.0)) * 100.0;
+        let cm_s = |c: f64| c.clamp(-32768.0, 32767.0) as i16;
+        let g = pose.geodetic;
+        send(MavMessage::GlobalPositionInt(GlobalPositionInt {
+            time_boot_ms,
+            lat: (g.lat_deg * 1e7).round() as i32,
+            lon: (g.lon_deg * 1e7).round() as i32,
+            alt: (g.height_m * 1000.0).round() as i32,
+            relative_alt: (pose.site_position.0.y * 1000.0).round() as i32,
+            vx: cm_s(v.x),
+            vy: cm_s(v.y),
+            vz: cm_s(v.z),
+            hdg: (yaw.to_degrees().rem_euclid(360.0) * 100.0) as u16 % 36000,
+        }));
+    }
+}
+
+pub(crate) fn register(app: &mut App) {
+    app.init_resource::<MavlinkVehicles>()
+        .init_resource::<MavlinkVehicleSettings>()
+        .init_resource::<MavlinkBatteryCache>()
+        .add_observer(cache_battery_telemetry)
+        .add_systems(
+            Update,
+            (sync_mavlink_vehicles, publish_mavlink_state).chain(),
+        );
+}
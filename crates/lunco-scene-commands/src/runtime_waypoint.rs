@@ -12,6 +12,8 @@ use lunco_api::queries::{ApiQueryProvider, ApiQueryRegistry};
 use lunco_api::registry::ApiEntityRegistry;
 use lunco_api::schema::ApiResponse;
 use lunco_autopilot::usd_tree::{append_waypoint_leaf, BehaviorXml, ReachedWaypoints};
+use lunco_celestial::registry::CelestialBodyRegistry;
+use lunco_celestial::{geodetic_to_local, Geodetic, GeodeticAnchor, SiteAnchor, SurfacePoseQuery};
 use lunco_core::paths::prim_path_matches;
 use lunco_core::{
     on_command, register_commands, Command, ControlBinding, GlobalEntityId, InputPorts, Severity,
@@ -37,6 +39,35 @@ pub struct AddRuntimeWaypoint {
     pub position: [f64; 3],
 }
 
+/// [`AddRuntimeWaypoint`] addressed by latitude and longitude on the scene's
+/// body — what a ground station's map click or mission item carries.
+///
+/// Resolved through the scene's one `SiteAnchor` (the same site frame the
+/// position readback uses), so a scene without a unique anchor rejects it.
+#[Command]
+pub struct AddGeodeticWaypoint {
+    /// Spawned rover root receiving the waypoint.
+    pub target: Entity,
+    /// Degrees, +north.
+    pub lat_deg: f64,
+    /// Degrees, +east.
+    pub lon_deg: f64,
+    /// Metres above the body's mean sphere. Omitted ⇒ the target's own current
+    /// height: a rover's waypoint belongs on the ground it is driving on.
+    #[serde(default)]
+    #[reflect(default)]
+    pub height_m: Option<f64>,
+}
+
+/// Drop a vessel's runtime route: its markers, their arrival keys and the
+/// patrol they fed. The next [`AddRuntimeWaypoint`] starts a fresh route at
+/// index 0 — what a ground station's mission re-upload means.
+#[Command]
+pub struct ClearRuntimeWaypoints {
+    /// Spawned rover root whose runtime route is dropped.
+    pub target: Entity,
+}
+
 /// Binds a spawned marker to the route index that created it.
 #[derive(Component, Clone, Copy, Debug, PartialEq, Eq)]
 pub struct RuntimeWaypointBinding {
@@ -63,9 +94,11 @@ pub struct RuntimeWaypointSpawner<'w, 's> {
     >,
 }
 
+const RUNTIME_WAYPOINT_PREFIX: &str = "/__runtime_waypoint_";
+
 /// The synthetic key used by the live route and by the arrival set.
 pub fn runtime_waypoint_key(index: usize) -> String {
-    format!("/__runtime_waypoint_{index}")
+    format!("{RUNTIME_WAYPOINT_PREFIX}{index}")
 }
 
 /// Extend a runtime-only patrol without changing an authored behaviour shape.
@@ -245,6 +278,89 @@ fn on_add_runtime_waypoint(
     );
 }
 
+#[on_command(AddGeodeticWaypoint)]
+fn on_add_geodetic_waypoint(
+    trigger: On<AddGeodeticWaypoint>,
+    q_site: Query<&GeodeticAnchor, With<SiteAnchor>>,
+    bodies: Option<Res<CelestialBodyRegistry>>,
+    surface: SurfacePoseQuery,
+    mut commands: Commands,
+) {
+    let cmd = trigger.event();
+    if !(cmd.lat_deg.is_finite() && cmd.lon_deg.is_finite()) {
+        warn!("[waypoint] geodetic waypoint rejected: non-finite latitude/longitude");
+        return;
+    }
+    let mut sites = q_site.iter();
+    let (Some(site), None) = (sites.next(), sites.next()) else {
+        warn!("[waypoint] geodetic waypoint rejected: the scene has no unique site anchor");
+        return;
+    };
+    let Some(radius_m) = bodies
+        .as_deref()
+        .and_then(|b| b.get(site.body))
+        .map(|b| b.radius_m)
+    else {
+        warn!(
+            "[waypoint] geodetic waypoint rejected: site body {} is not registered",
+            site.body
+        );
+        return;
+    };
+    let height_m = cmd.height_m.unwrap_or(site.geodetic.height_m);
+    let mut local = geodetic_to_local(
+        &site.geodetic,
+        radius_m,
+        &Geodetic::new(cmd.lat_deg, cmd.lon_deg, height_m),
+    );
+    if cmd.height_m.is_none() {
+        if let Some(pose) = surface.get(cmd.target) {
+            local.y = pose.site_position.0.y;
+        }
+    }
+    commands.trigger(AddRuntimeWaypoint {
+        target: cmd.target,
+        position: local.to_array(),
+    });
+}
+
+#[on_command(ClearRuntimeWaypoints)]
+fn on_clear_runtime_waypoints(
+    trigger: On<ClearRuntimeWaypoints>,
+    q_markers: Query<(Entity, &RuntimeWaypointBinding)>,
+    q_reached: Query<&ReachedWaypoints>,
+    q_specs: Query<(), With<lunco_autopilot::AutopilotBehaviorSpec>>,
+    mut commands: Commands,
+) {
+    let cmd = trigger.event();
+    let mut markers = 0;
+    for (marker, binding) in &q_markers {
+        if binding.vessel == cmd.target {
+            commands.entity(marker).try_despawn();
+            markers += 1;
+        }
+    }
+    // Authored arrivals stay: only the keys this module minted go with the route.
+    if let Ok(reached) = q_reached.get(cmd.target) {
+        let kept = reached
+            .0
+            .iter()
+            .filter(|key| !key.starts_with(RUNTIME_WAYPOINT_PREFIX))
+            .cloned()
+            .collect();
+        commands
+            .entity(cmd.target)
+            .try_insert(ReachedWaypoints(kept));
+    }
+    if q_specs.get(cmd.target).is_ok() {
+        commands.trigger(lunco_autopilot::ClearPatrol { vessel: cmd.target });
+    }
+    info!(
+        "[waypoint] runtime route of {:?} cleared ({markers} markers)",
+        cmd.target
+    );
+}
+
 /// Structured readback used by the production scene test and API clients.
 pub struct RuntimeWaypointStatusProvider;
 
@@ -494,7 +610,11 @@ fn collect_targets(value: &serde_json::Value, out: &mut Vec<String>) {
     }
 }
 
-register_commands!(on_add_runtime_waypoint);
+register_commands!(
+    on_add_runtime_waypoint,
+    on_add_geodetic_waypoint,
+    on_clear_runtime_waypoints
+);
 
 pub fn register(app: &mut App) {
     register_all_commands(app);
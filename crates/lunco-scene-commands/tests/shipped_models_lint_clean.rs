@@ -121,6 +121,31 @@ fn a_when_equation_is_not_flagged_as_a_conditional_observable() {
     );
 }
 
+/// An equation whose declared units disagree is caught. Its own teeth test for
+/// the same reason as the conditional rule: the one shipped offender was fixed
+/// when the rule landed, so the sweep passes whether the rule fires or not.
+#[test]
+fn a_unit_inconsistent_equation_is_caught() {
+    register_modelica_lint_policy();
+
+    let src = "model M\n  parameter Real m(unit=\"kg\") = 2;\n  Real v(unit=\"m/s\");\n  Real F(unit=\"N\");\nequation\n  F = m * v;\n  der(v) = 1.0;\nend M;\n";
+    let syntax = rumoca_phase_parse::parse_to_syntax(src, "M.mo");
+    let ast = syntax.best_effort();
+    let model = lunco_modelica::ast_extract::extract_model_name_from_ast(ast).unwrap_or_default();
+
+    let findings = lunco_lint::run_lint(
+        lunco_modelica::lint::MODELICA_LINT_DOMAIN,
+        lunco_modelica::lint::modelica_facts(&model, &BTreeMap::new(), &BTreeMap::new(), ast),
+    );
+
+    assert!(
+        findings
+            .iter()
+            .any(|f| f.rule == "unit-inconsistent-equation" && f.subject == "F"),
+        "the shipped policy must flag `N = kg.m/s`: {findings:?}"
+    );
+}
+
 /// A model whose inputs and parameters are disjoint is clean. This is what keeps
 /// the rule from being a tax on every ordinary model.
 #[test]
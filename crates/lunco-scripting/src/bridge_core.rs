@@ -325,6 +325,13 @@ impl Drop for WorldScope {
     }
 }
 
+/// True while a script evaluation holds the world, i.e. a command dispatched
+/// right now was issued by a script's [`cmd`] (the command journal tags such
+/// entries as script-authored).
+pub fn script_in_scope() -> bool {
+    WORLD_PTR.with(|p| !p.get().is_null())
+}
+
 /// Set the session the current script acts on behalf of, for [`cmd`]
 /// authorization. `None` = host-trusted (no gate). The scenario driver sets this
 /// per-entity from its `ScriptAuthority`; the `RunRhai` drain sets it per-eval.
@@ -0,0 +1,578 @@
+//! The **executed-command journal** — every `#[Command]` the dispatcher ran,
+//! recorded into the Twin journal as a `DomainKind::Command` op, plus the two
+//! consumers that make the record mean something:
+//!
+//! - **Replay** — `ReplayCommandJournal` re-issues the recorded stream against a
+//!   freshly loaded twin, each command at its recorded sim-tick offset and as the
+//!   session it originally ran as.
+//! - **Planned vs. actual** — the `TimelineExecution { name }` query lines a
+//!   stored timeline's steps up against the commands its last run issued.
+//!
+//! Recording taps [`CommandDispatched`], which `api_command_dispatcher` fires once
+//! per command that actually executed, so every path that funnels through the
+//! dispatcher (HTTP, MCP, rhai `cmd()`, the REPL, remote API callers) is covered
+//! with no per-command code. Commands triggered as typed events directly (UI
+//! hotkeys, raycast possession) never reach the dispatcher and are not recorded.
+//!
+//! What an entry carries is what replay needs: the command name, its *wire*
+//! params (global ids, stable across a reload for scene-authored entities), the
+//! [`SimTick`] it ran at, the session it ran as, and whether a script issued it.
+//! The journal's `AuthorTag` names the user (`local`, or the remote principal)
+//! and the tool (`rhai` for script-issued, `api` otherwise).
+//!
+//! Two deliberate limits, per `docs/architecture/command-journal.md`:
+//!
+//! - **No undo.** The recorded inverse is [`CommandOp::ReplayOnly`]; nothing
+//!   pushes these entries onto an undo stack.
+//! - **No journal-plane consumer.** Entries replicate with the journal like any
+//!   other, but no peer re-applies them — the command already crossed on the
+//!   command path, and applying it twice is exactly the hazard the design warns
+//!   about. Only the explicit replayer re-issues them.
+
+#![cfg(feature = "rhai")]
+
+use bevy::prelude::*;
+use lunco_api::executor::{ApiCommandEvent, ApiCommandOrigins, CommandDispatched};
+use lunco_api::queries::{ApiQueryProvider, ApiQueryRegistry};
+use lunco_api::schema::{ApiErrorCode, ApiResponse};
+use lunco_core::session::SessionRbac;
+use lunco_core::{on_command, register_commands, Ack, Command, OpId, SessionId, SimTick};
+use lunco_doc::DocumentId;
+use lunco_doc_bevy::JournalResource;
+use lunco_twin_journal::{AuthorTag, DomainKind, EntryKind, OpPayload};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashSet, VecDeque};
+
+/// One executed command, as recorded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExecutedCommand {
+    pub command: String,
+    /// Wire params as the caller sent them (global ids, not `Entity` bits).
+    pub params: serde_json::Value,
+    /// The [`SimTick`] the command ran at.
+    pub tick: u64,
+    /// The session it was applied as; `0` = local / host-trusted.
+    pub session: u64,
+    /// Issued by a script's `cmd()` rather than by a caller directly.
+    pub scripted: bool,
+}
+
+/// The `DomainKind::Command` op vocabulary.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CommandOp {
+    Executed(ExecutedCommand),
+    /// The recorded inverse of every `Executed`: commands declare no undo, so
+    /// the entry is replay-only.
+    ReplayOnly,
+}
+
+impl OpPayload for CommandOp {
+    fn domain(&self) -> DomainKind {
+        DomainKind::Command
+    }
+}
+
+/// The one document the command stream is journaled under. Folded from a fixed
+/// name (FNV-1a-64, like the registration docs) so every peer keys it the same.
+fn command_stream_doc() -> DocumentId {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in b"lunco.command-stream" {
+        h ^= *b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    DocumentId::new(h)
+}
+
+/// The recorded command stream, in journal (merged) order.
+pub fn executed_commands(journal: &JournalResource) -> Vec<ExecutedCommand> {
+    journal.with_read(|j| {
+        j.merged_order()
+            .into_iter()
+            .filter_map(|entry| match &entry.kind {
+                EntryKind::Op {
+                    domain: DomainKind::Command,
+                    op,
+                    ..
+                } => match serde_json::from_value(op.clone()) {
+                    Ok(CommandOp::Executed(c)) => Some(c),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    })
+}
+
+// ── Recording ────────────────────────────────────────────────────────────────
+
+/// Observer: journal every command the dispatcher ran. Skips the commands the
+/// replayer itself re-issues (they are already in the stream) and the replay
+/// command (re-issuing it from a replay would recurse).
+pub(crate) fn record_dispatched_command(
+    trigger: On<CommandDispatched>,
+    journal: Option<Res<JournalResource>>,
+    tick: Option<Res<SimTick>>,
+    rbac: Option<Res<SessionRbac>>,
+    mut replay: ResMut<CommandReplay>,
+) {
+    let event = trigger.event();
+    if replay.issued.remove(&event.id) || event.command == REPLAY_COMMAND {
+        return;
+    }
+    let Some(journal) = journal else {
+        return;
+    };
+    let session = event.origin.unwrap_or(SessionId::LOCAL);
+    let scripted = crate::bridge_core::script_in_scope();
+    let user = if session == SessionId::LOCAL {
+        AuthorTag::local_user().user
+    } else {
+        rbac.as_ref()
+            .and_then(|r| r.sessions.get(&session.0))
+            .map(|s| s.username.clone())
+            .unwrap_or_else(|| format!("session-{}", session.0))
+    };
+    let author = AuthorTag {
+        user,
+        tool: if scripted { "rhai" } else { "api" }.into(),
+    };
+    let op = CommandOp::Executed(ExecutedCommand {
+        command: event.command.clone(),
+        params: event.params.clone(),
+        tick: tick.map_or(0, |t| t.0),
+        session: session.0,
+        scripted,
+    });
+    journal.with_write(|j| {
+        if let Err(e) = j.record_op(
+            author,
+            command_stream_doc(),
+            &op,
+            &CommandOp::ReplayOnly,
+            None,
+        ) {
+            warn!("[command-journal] record_op failed: {e}");
+        }
+    });
+}
+
+// ── Replay ───────────────────────────────────────────────────────────────────
+
+const REPLAY_COMMAND: &str = "ReplayCommandJournal";
+
+/// The replay in progress: commands still to issue, each at a tick offset from
+/// the replay's start, and the ids issued on the last driven tick (so the
+/// recorder skips them).
+#[derive(Resource, Default)]
+pub struct CommandReplay {
+    queue: VecDeque<(u64, ExecutedCommand)>,
+    /// The [`SimTick`] offset zero maps to; set on the first driven tick.
+    start: Option<u64>,
+    issued: HashSet<u64>,
+}
+
+impl CommandReplay {
+    /// Commands not yet re-issued.
+    pub fn remaining(&self) -> usize {
+        self.queue.len()
+    }
+}
+
+/// Lay a recorded stream out on one timeline: offsets from the first command,
+/// preserving the recorded spacing. The stream can span several sessions of the
+/// twin and `SimTick` restarts at every load, so a tick that goes backwards
+/// starts a new session — it follows the previous command immediately instead
+/// of being scheduled into the past.
+pub fn replay_schedule(
+    stream: impl IntoIterator<Item = ExecutedCommand>,
+) -> Vec<(u64, ExecutedCommand)> {
+    let mut previous: Option<(u64, u64)> = None; // (tick, offset)
+    stream
+        .into_iter()
+        .map(|c| {
+            let offset = match previous {
+                Some((tick, offset)) => offset + c.tick.saturating_sub(tick),
+                None => 0,
+            };
+            previous = Some((c.tick, offset));
+            (offset, c)
+        })
+        .collect()
+}
+
+/// Re-issue the twin's recorded command stream, each command at its recorded
+/// sim-tick offset from now and as the session it originally ran as. Meant for a
+/// freshly loaded twin: the journal reloads with it, the scene is back at its
+/// authored state, and the replay drives it through the same commands again.
+/// Replacing a replay in progress restarts it.
+#[Command(default)]
+pub struct ReplayCommandJournal {
+    /// Also re-issue the commands scripts issued. Off by default: replaying the
+    /// `RunScenario` / `RunStoredTimeline` that started a script runs it again,
+    /// and the script issues its own commands a second time.
+    pub include_scripted: bool,
+}
+
+#[on_command(ReplayCommandJournal)]
+fn on_replay_command_journal(
+    _t: On<ReplayCommandJournal>,
+    journal: Option<Res<JournalResource>>,
+    mut replay: ResMut<CommandReplay>,
+) -> Result<Ack, String> {
+    let journal = journal.ok_or("ReplayCommandJournal: no journal in this app")?;
+    let stream = executed_commands(&journal)
+        .into_iter()
+        .filter(|c| cmd.include_scripted || !c.scripted);
+    let schedule = replay_schedule(stream);
+    let span = schedule.last().map_or(0, |(offset, _)| *offset);
+    let count = schedule.len();
+    *replay = CommandReplay {
+        queue: schedule.into(),
+        start: None,
+        issued: HashSet::new(),
+    };
+    info!("[command-journal] replaying {count} commands over {span} ticks");
+    let mut ack = Ack::new(OpId::new());
+    ack.assigned = serde_json::json!({ "commands": count, "ticks": span });
+    Ok(ack)
+}
+
+/// Issue every queued command whose offset has come due. Keyed off [`SimTick`],
+/// so a paused sim pauses the replay with it.
+pub(crate) fn drive_command_replay(
+    tick: Res<SimTick>,
+    mut replay: ResMut<CommandReplay>,
+    mut origins: ResMut<ApiCommandOrigins>,
+    mut commands: Commands,
+) {
+    // Every command issued last tick has been dispatched by now, and the
+    // recorder took the id of each one that ran. What is left was rejected
+    // (authz, bad params, a `Rejected` outcome) and will never come back, so
+    // drop it instead of letting it pile up for the rest of the session.
+    if !replay.issued.is_empty() {
+        replay.issued.clear();
+    }
+    if replay.queue.is_empty() {
+        return;
+    }
+    let start = *replay.start.get_or_insert(tick.0);
+    let elapsed = tick.0.saturating_sub(start);
+    while replay.queue.front().is_some_and(|(at, _)| *at <= elapsed) {
+        let Some((_, c)) = replay.queue.pop_front() else {
+            break;
+        };
+        let id = OpId::new().0;
+        if c.session != SessionId::LOCAL.0 {
+            origins.insert(id, SessionId(c.session));
+        }
+        replay.issued.insert(id);
+        commands.trigger(ApiCommandEvent {
+            command: c.command,
+            params: c.params,
+            id,
+        });
+    }
+    if replay.queue.is_empty() {
+        info!("[command-journal] replay finished");
+    }
+}
+
+// ── Planned vs. actual ───────────────────────────────────────────────────────
+
+/// The command a timeline step is expected to issue. Drive and brake steps issue
+/// a stream of `SetPorts`; `emit`/`wait`/`wait_event` issue none.
+fn planned_command(step: &serde_json::Value) -> Option<&str> {
+    if let Some(name) = step.get("cmd").and_then(serde_json::Value::as_str) {
+        return Some(name);
+    }
+    if step.get("possess").is_some() {
+        return Some("PossessVessel");
+    }
+    ["move_to", "move_to_entity", "brake"]
+        .iter()
+        .any(|k| step.get(*k).is_some())
+        .then_some("SetPorts")
+}
+
+/// The step's operation keyword, for display.
+fn step_op(step: &serde_json::Value) -> &str {
+    const OPS: [&str; 8] = [
+        "move_to",
+        "move_to_entity",
+        "possess",
+        "brake",
+        "cmd",
+        "emit",
+        "wait",
+        "wait_event",
+    ];
+    OPS.into_iter()
+        .find(|k| step.get(*k).is_some())
+        .unwrap_or("?")
+}
+
+/// Line `steps` up against `executed` (the script-issued commands since the
+/// timeline's run, in order). Each command step takes the first not-yet-matched
+/// command of its name after the previous match; a `cmd` step also reports the
+/// planned params the executed command disagreed on. Script-issued commands
+/// between the first and last match that no step claimed — other than
+/// `SetPorts`, which drive steps issue continuously — are reported as unplanned.
+/// With several scripts running at once their commands interleave here, so the
+/// diff is exact only for a timeline that ran alone.
+pub fn diff_timeline(
+    steps: &[serde_json::Value],
+    executed: &[ExecutedCommand],
+) -> serde_json::Value {
+    let mut cursor = 0;
+    let mut claimed = HashSet::new();
+    let mut rows = Vec::with_capacity(steps.len());
+    let (mut done, mut missing) = (0, 0);
+    for (index, step) in steps.iter().enumerate() {
+        let op = step_op(step);
+        let Some(expects) = planned_command(step) else {
+            rows.push(serde_json::json!({ "index": index, "op": op, "status": "not_a_command" }));
+            continue;
+        };
+        let found = executed[cursor..]
+            .iter()
+            .position(|c| c.command == expects)
+            .map(|i| cursor + i);
+        let Some(at) = found else {
+            missing += 1;
+            rows.push(serde_json::json!({
+                "index": index, "op": op, "expects": expects, "status": "missing"
+            }));
+            continue;
+        };
+        done += 1;
+        claimed.insert(at);
+        cursor = at + 1;
+        let actual = &executed[at];
+        let mismatched: Vec<&String> = match step.get("params").and_then(|p| p.as_object()) {
+            Some(planned) if op == "cmd" => planned
+                .iter()
+                .filter(|(k, v)| actual.params.get(k.as_str()) != Some(*v))
+                .map(|(k, _)| k)
+                .collect(),
+            _ => Vec::new(),
+        };
+        rows.push(serde_json::json!({
+            "index": index,
+            "op": op,
+            "expects": expects,
+            "status": "executed",
+            "tick": actual.tick,
+            "params_mismatch": mismatched
+        }));
+    }
+    let span = claimed.iter().min().zip(claimed.iter().max());
+    let unplanned: Vec<serde_json::Value> = span
+        .map(|(&first, &last)| {
+            (first..=last)
+                .filter(|i| !claimed.contains(i) && executed[*i].command != "SetPorts")
+                .map(|i| serde_json::json!({ "command": executed[i].command, "tick": executed[i].tick }))
+                .collect()
+        })
+        .unwrap_or_default();
+    serde_json::json!({
+        "steps": rows,
+        "executed": done,
+        "missing": missing,
+        "unplanned": unplanned
+    })
+}
+
+/// `TimelineExecution { name }` → the planned-vs-actual diff of the stored
+/// timeline `name` against its most recent recorded `RunStoredTimeline`:
+/// `{ name, run_tick, steps: [{ index, op, expects?, status, tick?,
+/// params_mismatch? }], executed, missing, unplanned: [{ command, tick }] }`.
+struct TimelineExecutionProvider;
+impl ApiQueryProvider for TimelineExecutionProvider {
+    fn name(&self) -> &'static str {
+        "TimelineExecution"
+    }
+    fn execute(&self, world: &mut World, params: &serde_json::Value) -> ApiResponse {
+        let Some(name) = params.get("name").and_then(serde_json::Value::as_str) else {
+            return ApiResponse::error(
+                ApiErrorCode::DeserializationError,
+                "TimelineExecution: `name` required".to_string(),
+            );
+        };
+        let Some(timeline) = world
+            .get_resource::<crate::timelines::TimelineStore>()
+            .and_then(|s| s.get(name).map(str::to_string))
+        else {
+            return ApiResponse::error(
+                ApiErrorCode::EntityNotFound,
+                format!("timeline '{name}' not found"),
+            );
+        };
+        let steps = match crate::commands::parse_timeline_steps(&timeline) {
+            Ok((serde_json::Value::Array(steps), _)) => steps,
+            Ok(_) => Vec::new(),
+            Err(e) => {
+                return ApiResponse::error(
+                    ApiErrorCode::InternalError,
+                    format!("TimelineExecution: {e}"),
+                )
+            }
+        };
+        let stream = world
+            .get_resource::<JournalResource>()
+            .map(executed_commands)
+            .unwrap_or_default();
+        let Some(run) = stream.iter().rposition(|c| {
+            c.command == "RunStoredTimeline"
+                && c.params.get("name").and_then(serde_json::Value::as_str) == Some(name)
+        }) else {
+            return ApiResponse::error(
+                ApiErrorCode::EntityNotFound,
+                format!("timeline '{name}' has no recorded run"),
+            );
+        };
+        let executed: Vec<ExecutedCommand> = stream[run + 1..]
+            .iter()
+            .filter(|c| c.scripted)
+            .cloned()
+            .collect();
+        let mut diff = diff_timeline(&steps, &executed);
+        diff["name"] = serde_json::json!(name);
+        diff["run_tick"] = serde_json::json!(stream[run].tick);
+        ApiResponse::ok(diff)
+    }
+}
+
+/// Register the planned-vs-actual query into the API query registry.
+pub fn register_queries(app: &mut App) {
+    app.init_resource::<ApiQueryRegistry>();
+    app.world_mut()
+        .resource_mut::<ApiQueryRegistry>()
+        .register(TimelineExecutionProvider);
+}
+
+register_commands!(on_replay_command_journal);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn exec(command: &str, tick: u64, params: serde_json::Value) -> ExecutedCommand {
+        ExecutedCommand {
+            command: command.into(),
+            params,
+            tick,
+            session: 0,
+            scripted: true,
+        }
+    }
+
+    #[test]
+    fn command_op_round_trips_and_declares_domain() {
+        let op = CommandOp::Executed(exec("PossessVessel", 12, json!({ "target": 7 })));
+        assert_eq!(op.domain(), DomainKind::Command);
+        let back: CommandOp = serde_json::from_value(serde_json::to_value(&op).unwrap()).unwrap();
+        let CommandOp::Executed(c) = back else {
+            panic!("decodes as Executed");
+        };
+        assert_eq!(c.tick, 12);
+        assert_eq!(c.params, json!({ "target": 7 }));
+    }
+
+    /// Spacing is preserved, and a tick that restarts (a reload between
+    /// recordings) continues from the previous offset instead of going back.
+    #[test]
+    fn schedule_keeps_spacing_across_tick_restarts() {
+        let stream = [
+            exec("A", 100, json!({})),
+            exec("B", 130, json!({})),
+            exec("C", 5, json!({})),
+            exec("D", 25, json!({})),
+        ];
+        let offsets: Vec<u64> = replay_schedule(stream)
+            .into_iter()
+            .map(|(o, _)| o)
+            .collect();
+        assert_eq!(offsets, vec![0, 30, 30, 50]);
+    }
+
+    /// A replayed command the dispatcher rejects never fires
+    /// `CommandDispatched`; its id must not outlive the tick it was issued on.
+    #[test]
+    fn rejected_replay_ids_do_not_linger() {
+        let mut app = App::new();
+        app.insert_resource(SimTick(0))
+            .init_resource::<CommandReplay>()
+            .init_resource::<ApiCommandOrigins>()
+            .add_observer(record_dispatched_command)
+            // Stand-in dispatcher: runs `Accepted`, rejects everything else.
+            .add_observer(|t: On<ApiCommandEvent>, mut commands: Commands| {
+                let e = t.event();
+                if e.command == "Accepted" {
+                    commands.trigger(CommandDispatched {
+                        id: e.id,
+                        command: e.command.clone(),
+                        params: e.params.clone(),
+                        origin: None,
+                    });
+                }
+            })
+            .add_systems(Update, drive_command_replay);
+        app.world_mut().resource_mut::<CommandReplay>().queue = replay_schedule([
+            exec("Accepted", 0, json!({})),
+            exec("Rejected", 0, json!({})),
+        ])
+        .into();
+
+        app.update();
+        let replay = app.world().resource::<CommandReplay>();
+        assert_eq!(replay.remaining(), 0);
+        assert_eq!(replay.issued.len(), 1, "the rejected id is still pending");
+
+        app.update();
+        assert!(app.world().resource::<CommandReplay>().issued.is_empty());
+    }
+
+    #[test]
+    fn diff_matches_steps_in_order_and_flags_gaps() {
+        let steps = vec![
+            json!({ "possess": "/rover" }),
+            json!({ "move_to": [1.0, 0.0, 2.0] }),
+            json!({ "wait": 2.0 }),
+            json!({ "cmd": "SetTimeWarp", "params": { "warp": 4.0 } }),
+            json!({ "cmd": "SpawnEntity", "params": {} }),
+        ];
+        let executed = [
+            exec("PossessVessel", 10, json!({ "target": 7 })),
+            exec("SetPorts", 11, json!({})),
+            exec("FocusTarget", 12, json!({})),
+            exec("SetPorts", 13, json!({})),
+            exec("SetTimeWarp", 40, json!({ "warp": 2.0 })),
+        ];
+        let diff = diff_timeline(&steps, &executed);
+        let status: Vec<&str> = diff["steps"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|r| r["status"].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            status,
+            [
+                "executed",
+                "executed",
+                "not_a_command",
+                "executed",
+                "missing"
+            ]
+        );
+        assert_eq!(diff["steps"][3]["tick"], 40);
+        assert_eq!(diff["steps"][3]["params_mismatch"], json!(["warp"]));
+        assert_eq!(diff["executed"], 3);
+        assert_eq!(diff["missing"], 1);
+        assert_eq!(
+            diff["unplanned"],
+            json!([{ "command": "FocusTarget", "tick": 12 }])
+        );
+    }
+}
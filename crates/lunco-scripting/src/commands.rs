@@ -574,10 +574,11 @@ fn push_rhai_string(s: &str, out: &mut String) {
 
 /// Parse a timeline JSON string into its `steps` array value + step count.
 /// Accepts a bare `[ ...steps ]` array or an object with a `steps` array. Shared
-/// by `RunTimeline` (execute), `RunStoredTimeline`, and `RegisterTimeline`
-/// (validate-before-store). Errors are caller-prefixed.
+/// by `RunTimeline` (execute), `RunStoredTimeline`, `RegisterTimeline`
+/// (validate-before-store) and the `TimelineExecution` diff. Errors are
+/// caller-prefixed.
 #[cfg(feature = "rhai")]
-fn parse_timeline_steps(timeline: &str) -> Result<(serde_json::Value, usize), String> {
+pub(crate) fn parse_timeline_steps(timeline: &str) -> Result<(serde_json::Value, usize), String> {
     let parsed: serde_json::Value =
         serde_json::from_str(timeline).map_err(|e| format!("`timeline` is not valid JSON: {e}"))?;
     let steps = match &parsed {
@@ -904,6 +905,15 @@ pub(crate) fn register_command_policies(app: &mut App) {
         reg.register("RegisterTimeline", EXEC);
         reg.register("RunStoredTimeline", EXEC);
         reg.register("RegisterToolLibrary", EXEC);
+        // Re-issues other sessions' recorded commands AS those sessions, so it
+        // carries every role they held → `Owner` only.
+        reg.register(
+            "ReplayCommandJournal",
+            CommandPolicy {
+                min_role: AuthorityRole::Owner,
+                ownership_gated: false,
+            },
+        );
     }
     #[cfg(feature = "python")]
     reg.register("RunPython", EXEC);
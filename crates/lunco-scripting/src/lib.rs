@@ -11,6 +11,10 @@ pub mod bridge_core;
 /// for editor completion / hover / docs.
 #[cfg(feature = "rhai")]
 pub mod catalog;
+/// Executed-command journal (`DomainKind::Command`): the recorder, the
+/// `ReplayCommandJournal` replayer, and the `TimelineExecution` planned-vs-actual diff.
+#[cfg(feature = "rhai")]
+pub mod command_journal;
 pub mod commands;
 /// Scripting adapter onto the unified diagnostics store (`ScriptStatus` query).
 #[cfg(feature = "rhai")]
@@ -415,6 +419,16 @@ impl Plugin for LunCoScriptingPlugin {
             timelines::register_queries(app);
             #[cfg(not(target_arch = "wasm32"))]
             app.add_observer(timelines::load_timelines_on_twin_added);
+            // Executed-command journal: record every dispatched command into the
+            // Twin journal, replay the stream on demand, diff timelines against it.
+            // `SimTick` is lunco-time's; idempotent init so the replay clock
+            // exists in apps without it (it then never advances).
+            app.init_resource::<lunco_core::SimTick>()
+                .init_resource::<command_journal::CommandReplay>()
+                .add_observer(command_journal::record_dispatched_command)
+                .add_systems(FixedUpdate, command_journal::drive_command_replay);
+            command_journal::register_all_commands(app);
+            command_journal::register_queries(app);
             diagnostics::register_queries(app);
             // Authoring catalog: ScriptingCatalog aggregates the full callable
             // surface (verbs + commands + queries + tools + prelude) for editor
@@ -0,0 +1,311 @@
+//! Long-duration **illumination layers** — the terrain-side half of the site
+//! illumination bake: data for `TerrainField`, a drape for the overlay.
+//!
+//! The bake itself (the horizon march swept over an ephemeris window) lives in
+//! `lunco-environment`, and the sweep it marches is built by `lunco-celestial`
+//! from the ephemeris. This crate sees neither, so the app glue mirrors the
+//! result in as [`TerrainIlluminationLayers`] — the same move as
+//! [`TileShadowCache`](crate::TileShadowCache). From there:
+//!
+//! - [`TerrainField`](crate::query) serves `illumination` / `longest_darkness` /
+//!   `earth_visibility` regions exactly like the geometric fields — the raster a
+//!   landing-site planner must use;
+//! - [`upload_illumination_drapes`] colours each layer through the shared hazard
+//!   ramp ([`hazard_color`]) into an RGBA8 drape;
+//! - [`bind_illumination_drape_to_tiles`] binds the layer picked by
+//!   [`TerrainOverlayParams::illumination_layer`](crate::overlay::TerrainOverlayParams)
+//!   into the tiles' `mineral` slot — the UNLIT drape plane, so a permanently
+//!   shadowed crater floor reads as clearly as the sunlit rim.
+//!
+//! The layers cover the horizon heightfield's footprint, which for a streamed
+//! terrain is the whole DEM square — the same whole-DEM planar UV the tiles
+//! address every other drape with.
+
+use std::sync::Arc;
+
+use bevy::asset::RenderAssetUsages;
+use bevy::prelude::*;
+use lunco_materials::{ParamValue, ShaderLook, TextureLayer};
+use lunco_terrain_core::hazard_color;
+use wgpu_types::{Extent3d, TextureDimension, TextureFormat};
+
+use crate::derived_layers::TerrainAuthoredMaps;
+use crate::overlay::TerrainOverlayParams;
+use crate::stream_viz::{apply_authored_maps_to_look, set_param, LodTiles, TerrainShaderMode};
+
+/// Darkness at which the `longest_darkness` drape saturates to red: one full
+/// lunar night (half a synodic month). A site whose worst night is a whole
+/// night has no polar advantage left.
+pub const LUNAR_NIGHT_HOURS: f32 = 354.4;
+
+/// One of the three baked illumination rasters. The [`id`](Self::id) is the
+/// `TerrainField` field name and the `SetTerrainOverlay` layer name.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub enum IlluminationLayer {
+    /// Mean visible fraction of the solar disk over the window, percent.
+    Illumination,
+    /// Longest unbroken dark run over the window, hours.
+    LongestDarkness,
+    /// Mean visible fraction of Earth's disk over the window, percent.
+    EarthVisibility,
+}
+
+impl IlluminationLayer {
+    pub const ALL: [Self; 3] = [
+        Self::Illumination,
+        Self::LongestDarkness,
+        Self::EarthVisibility,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            Self::Illumination => "illumination",
+            Self::LongestDarkness => "longest_darkness",
+            Self::EarthVisibility => "earth_visibility",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.id() == id)
+    }
+
+    /// Hazard weight of a raster value — `0` green (good for a site), `1` red.
+    fn hazard(self, v: f32) -> f32 {
+        match self {
+            Self::Illumination | Self::EarthVisibility => 1.0 - v / 100.0,
+            Self::LongestDarkness => v / LUNAR_NIGHT_HOURS,
+        }
+    }
+}
+
+/// The baked illumination rasters for a terrain, in its DEM frame. Row-major
+/// `res × res`, texel `(ix, iz)` at the texel centre
+/// `min + ((ix, iz) + 0.5) / res * size`. Written by the app glue when a bake
+/// lands; replaced wholesale by the next one.
+#[derive(Component, Clone)]
+pub struct TerrainIlluminationLayers {
+    pub res: usize,
+    /// DEM-frame `(x, z)` of the footprint's minimum corner, metres.
+    pub min: [f64; 2],
+    /// Footprint side lengths along `x` and `z`, metres.
+    pub size: [f64; 2],
+    pub start_jd: f64,
+    pub end_jd: f64,
+    pub step_hours: f64,
+    pub illumination_pct: Arc<[f32]>,
+    pub longest_darkness_hours: Arc<[f32]>,
+    pub earth_visibility_pct: Arc<[f32]>,
+}
+
+impl TerrainIlluminationLayers {
+    pub fn raster(&self, layer: IlluminationLayer) -> &[f32] {
+        match layer {
+            IlluminationLayer::Illumination => &self.illumination_pct,
+            IlluminationLayer::LongestDarkness => &self.longest_darkness_hours,
+            IlluminationLayer::EarthVisibility => &self.earth_visibility_pct,
+        }
+    }
+
+    /// Whether the axis-aligned square `center ± half` lies inside the footprint.
+    pub fn covers(&self, center: [f64; 2], half: f64) -> bool {
+        (0..2).all(|i| {
+            center[i] - half >= self.min[i] && center[i] + half <= self.min[i] + self.size[i]
+        })
+    }
+
+    /// Bilinear read between texel centres (clamped at the border half-texel);
+    /// `None` outside the footprint.
+    pub fn sample(&self, layer: IlluminationLayer, x: f64, z: f64) -> Option<f32> {
+        let res = self.res;
+        let data = self.raster(layer);
+        if res == 0 || data.len() != res * res {
+            return None;
+        }
+        let u = (x - self.min[0]) / self.size[0];
+        let v = (z - self.min[1]) / self.size[1];
+        if !(0.0..=1.0).contains(&u) || !(0.0..=1.0).contains(&v) {
+            return None;
+        }
+        let max = (res - 1) as f64;
+        let gx = (u * res as f64 - 0.5).clamp(0.0, max);
+        let gz = (v * res as f64 - 0.5).clamp(0.0, max);
+        let (x0, z0) = (gx as usize, gz as usize);
+        let (x1, z1) = ((x0 + 1).min(res - 1), (z0 + 1).min(res - 1));
+        let (fx, fz) = (gx.fract() as f32, gz.fract() as f32);
+        let at = |x: usize, z: usize| data[z * res + x];
+        let top = at(x0, z0) + (at(x1, z0) - at(x0, z0)) * fx;
+        let bot = at(x0, z1) + (at(x1, z1) - at(x0, z1)) * fx;
+        Some(top + (bot - top) * fz)
+    }
+}
+
+/// The colourised drapes of a terrain's [`TerrainIlluminationLayers`], one
+/// linear RGBA8 texture per layer. Rebuilt whenever the layers change.
+#[derive(Component, Clone)]
+pub struct TerrainIlluminationDrapes {
+    pub illumination: Handle<Image>,
+    pub longest_darkness: Handle<Image>,
+    pub earth_visibility: Handle<Image>,
+}
+
+impl TerrainIlluminationDrapes {
+    pub fn get(&self, layer: IlluminationLayer) -> &Handle<Image> {
+        match layer {
+            IlluminationLayer::Illumination => &self.illumination,
+            IlluminationLayer::LongestDarkness => &self.longest_darkness,
+            IlluminationLayer::EarthVisibility => &self.earth_visibility,
+        }
+    }
+
+    fn contains(&self, handle: &Handle<Image>) -> bool {
+        IlluminationLayer::ALL
+            .into_iter()
+            .any(|l| self.get(l) == handle)
+    }
+}
+
+/// Colour one layer through the hazard ramp into an RGBA8 drape texture.
+fn drape_image(layers: &TerrainIlluminationLayers, layer: IlluminationLayer) -> Image {
+    let data: Vec<u8> = layers
+        .raster(layer)
+        .iter()
+        .flat_map(|&v| hazard_color(layer.hazard(v)).map(|c| (c * 255.0).round() as u8))
+        .collect();
+    Image::new(
+        Extent3d {
+            width: layers.res as u32,
+            height: layers.res as u32,
+            depth_or_array_layers: 1,
+        },
+        TextureDimension::D2,
+        data,
+        TextureFormat::Rgba8Unorm,
+        RenderAssetUsages::RENDER_WORLD,
+    )
+}
+
+/// Upload the three drapes when a terrain's layers land or are replaced.
+/// Render-gated by data like the derived maps: no `Assets<Image>`, no drapes.
+pub fn upload_illumination_drapes(
+    mut commands: Commands,
+    images: Option<ResMut<Assets<Image>>>,
+    changed: Query<(Entity, &TerrainIlluminationLayers), Changed<TerrainIlluminationLayers>>,
+) {
+    let Some(mut images) = images else { return };
+    for (entity, layers) in &changed {
+        if layers.res == 0 {
+            continue;
+        }
+        let mut add = |layer| images.add(drape_image(layers, layer));
+        let drapes = TerrainIlluminationDrapes {
+            illumination: add(IlluminationLayer::Illumination),
+            longest_darkness: add(IlluminationLayer::LongestDarkness),
+            earth_visibility: add(IlluminationLayer::EarthVisibility),
+        };
+        commands.entity(entity).try_insert(drapes);
+    }
+}
+
+/// Keep every resident Lit tile's `mineral` slot on the selected illumination
+/// drape, and hand it back to the authored drape when the layer is cleared.
+///
+/// Runs every frame but writes only on a mismatch, so it also covers tiles
+/// spawned since the last frame and an authored-map restate that took the slot
+/// back — without threading the drape through the tile build.
+#[allow(clippy::type_complexity)]
+pub fn bind_illumination_drape_to_tiles(
+    params: Res<TerrainOverlayParams>,
+    terrains: Query<(
+        &LodTiles,
+        &TerrainIlluminationDrapes,
+        Option<&TerrainAuthoredMaps>,
+    )>,
+    mut looks: Query<&mut ShaderLook>,
+) {
+    let layer = params.illumination_layer.filter(|_| params.enabled);
+    let weight = ParamValue::F32(params.opacity.clamp(0.0, 1.0));
+    for (tiles, drapes, authored) in &terrains {
+        if tiles.shader_mode() != TerrainShaderMode::Lit {
+            continue;
+        }
+        let want = layer.map(|l| drapes.get(l));
+        for entity in tiles.tile_entities() {
+            let Ok(mut look) = looks.get_mut(entity) else {
+                continue;
+            };
+            let bound = look.textures.get(&TextureLayer::Mineral);
+            match want {
+                Some(drape) => {
+                    if bound != Some(drape) || look.values.get("weight_mineral") != Some(&weight) {
+                        look.textures.insert(TextureLayer::Mineral, drape.clone());
+                        set_param(&mut look, "weight_mineral", weight);
+                    }
+                }
+                None => {
+                    if bound.is_some_and(|h| drapes.contains(h)) {
+                        let fallback = TerrainAuthoredMaps::default();
+                        apply_authored_maps_to_look(&mut look, authored.unwrap_or(&fallback));
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Register the drape upload and tile binding. The binding runs after every
+/// system that builds or restates a tile look, so its write is the last word.
+pub fn register(app: &mut App) {
+    app.register_type::<IlluminationLayer>();
+    app.add_systems(
+        Update,
+        (
+            upload_illumination_drapes,
+            bind_illumination_drape_to_tiles
+                .after(crate::stream_viz::update_lod_tiles)
+                .after(crate::stream_viz::bind_authored_maps_to_tiles),
+        )
+            .chain(),
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layers() -> TerrainIlluminationLayers {
+        // 2×2 over x,z ∈ [0, 20]: texel centres at 5 and 15 m.
+        TerrainIlluminationLayers {
+            res: 2,
+            min: [0.0, 0.0],
+            size: [20.0, 20.0],
+            start_jd: 0.0,
+            end_jd: 1.0,
+            step_hours: 6.0,
+            illumination_pct: Arc::from([0.0, 100.0, 0.0, 100.0]),
+            longest_darkness_hours: Arc::from([24.0; 4]),
+            earth_visibility_pct: Arc::from([50.0; 4]),
+        }
+    }
+
+    #[test]
+    fn samples_between_texel_centres_and_refuses_outside() {
+        let l = layers();
+        let at = |x, z| l.sample(IlluminationLayer::Illumination, x, z);
+        assert_eq!(at(5.0, 5.0), Some(0.0));
+        assert_eq!(at(15.0, 5.0), Some(100.0));
+        assert_eq!(at(10.0, 12.0), Some(50.0));
+        // The border half-texel holds the edge value rather than extrapolating.
+        assert_eq!(at(0.0, 0.0), Some(0.0));
+        assert_eq!(at(-1.0, 5.0), None);
+        assert!(l.covers([10.0, 10.0], 10.0));
+        assert!(!l.covers([10.0, 10.0], 10.5));
+    }
+
+    #[test]
+    fn layer_ids_round_trip() {
+        for layer in IlluminationLayer::ALL {
+            assert_eq!(IlluminationLayer::from_id(layer.id()), Some(layer));
+        }
+        assert_eq!(IlluminationLayer::from_id("slope"), None);
+    }
+}
@@ -43,6 +43,7 @@ pub mod band;
 pub mod collider_ring;
 pub mod derived_layers;
 pub mod georef;
+pub mod illumination;
 pub mod oracle;
 pub mod overlay;
 pub mod plugin;
@@ -70,6 +71,7 @@ pub use collider_ring::{ColliderTileOf, ColliderTiles, TerrainColliderRing};
 pub use dem::{decode_geotiff_f64, height_grid_from_geotiff, read_geotiff_transform, DemError};
 pub use derived_layers::{DerivedLayersBuilt, TerrainAuthoredMaps, TerrainDerivedMaps};
 pub use georef::{TerrainGeoref, DEFAULT_ANCHOR_BODY};
+pub use illumination::{IlluminationLayer, TerrainIlluminationDrapes, TerrainIlluminationLayers};
 /// The base raster [`SurfaceOracle`] composes over.
 ///
 /// Re-exported because it is already part of this crate's PUBLIC surface —
@@ -30,6 +30,7 @@ use bevy::prelude::*;
 use lunco_core::{on_command, register_commands, Command};
 use lunco_materials::{ParamValue, ShaderLook};
 
+use crate::illumination::IlluminationLayer;
 use crate::stream_viz::{LodTiles, TerrainShaderMode};
 
 /// The overlay's shader uniforms — the compact, per-material form of
@@ -107,6 +108,9 @@ pub struct TerrainOverlayParams {
     /// shader and so cannot show where a detail boundary sits relative to the
     /// production look). Still requires `enabled`.
     pub lod_depth: bool,
+    /// Drape a baked illumination layer instead (see [`crate::illumination`]).
+    /// Takes precedence over the slope / LOD views; still requires `enabled`.
+    pub illumination_layer: Option<IlluminationLayer>,
 }
 
 impl Default for TerrainOverlayParams {
@@ -119,15 +123,18 @@ impl Default for TerrainOverlayParams {
             cliff_deg: 30.0,
             opacity: 0.6,
             lod_depth: false,
+            illumination_layer: None,
         }
     }
 }
 
 impl TerrainOverlayParams {
     /// The shader-facing uniforms for the current state — [`OverlayUniforms::OFF`]
-    /// when disabled, so a build never leaks a stale colour.
+    /// when disabled, so a build never leaks a stale colour. Also OFF while an
+    /// illumination layer is draped: that view is a texture in the `mineral`
+    /// slot, and a slope tint under it would muddy both.
     pub fn uniforms(&self) -> OverlayUniforms {
-        if !self.enabled {
+        if !self.enabled || self.illumination_layer.is_some() {
             return OverlayUniforms::OFF;
         }
         OverlayUniforms {
@@ -161,6 +168,9 @@ pub struct SetTerrainOverlay {
     pub opacity: Option<f32>,
     /// Switch the overlay to the LOD-depth view (still needs `enabled`).
     pub lod_depth: Option<bool>,
+    /// Drape a baked illumination layer — `illumination`, `longest_darkness` or
+    /// `earth_visibility`; `none` returns to the slope / LOD views.
+    pub illumination_layer: Option<String>,
 }
 
 #[on_command(SetTerrainOverlay)]
@@ -185,15 +195,34 @@ fn on_set_terrain_overlay(
     if let Some(opacity) = ev.opacity {
         params.opacity = opacity.clamp(0.0, 1.0);
     }
+    if let Some(layer) = ev.illumination_layer.as_deref() {
+        match layer {
+            "" | "none" => params.illumination_layer = None,
+            id => match IlluminationLayer::from_id(id) {
+                Some(layer) => params.illumination_layer = Some(layer),
+                None => warn!(
+                    "[terrain-overlay] unknown illumination layer `{id}` \
+                     (illumination|longest_darkness|earth_visibility|none); keeping the current view"
+                ),
+            },
+        }
+    }
     debug!(
         "[terrain-overlay] enabled={} lod_depth={} safe={}° cliff={}° opacity={}",
         params.enabled, params.lod_depth, params.safe_deg, params.cliff_deg, params.opacity
     );
-    if before.enabled != params.enabled || before.lod_depth != params.lod_depth {
+    if before.enabled != params.enabled
+        || before.lod_depth != params.lod_depth
+        || before.illumination_layer != params.illumination_layer
+    {
         info!(
             "[seminar] terrain overlay toggle: enabled={} mode={} safe={:.1}° cliff={:.1}° opacity={:.2}",
             params.enabled,
-            if params.lod_depth { "lod" } else { "slope" },
+            match params.illumination_layer {
+                Some(layer) => layer.id(),
+                None if params.lod_depth => "lod",
+                None => "slope",
+            },
             params.safe_deg,
             params.cliff_deg,
             params.opacity,
@@ -287,6 +316,32 @@ mod tests {
         assert_eq!(app.world().resource::<TerrainOverlayParams>().opacity, 0.0);
     }
 
+    /// Draping an illumination layer silences the slope tint; `none` restores it.
+    #[test]
+    fn illumination_layer_replaces_the_slope_view() {
+        let mut app = test_app();
+        app.world_mut().trigger(SetTerrainOverlay {
+            enabled: Some(true),
+            illumination_layer: Some("longest_darkness".to_string()),
+            ..default()
+        });
+        app.world_mut().flush();
+        let p = *app.world().resource::<TerrainOverlayParams>();
+        assert_eq!(
+            p.illumination_layer,
+            Some(IlluminationLayer::LongestDarkness)
+        );
+        assert_eq!(p.uniforms().mode, overlay_mode::OFF);
+
+        app.world_mut().trigger(SetTerrainOverlay {
+            illumination_layer: Some("none".to_string()),
+            ..default()
+        });
+        app.world_mut().flush();
+        let p = *app.world().resource::<TerrainOverlayParams>();
+        assert_eq!(p.uniforms().mode, overlay_mode::SLOPE_HAZARD);
+    }
+
     /// And an explicit `enabled: false` still disarms it.
     #[test]
     fn explicit_disable_still_works() {
@@ -67,6 +67,10 @@ impl Plugin for TerrainSurfacePlugin {
         // command + live-sync system that paints the slope-hazard transfer over the lit
         // tiles (in-material shading plane of Data→Transfer→Blend). See `crate::overlay`.
         crate::overlay::register(app);
+        // Baked illumination layers: colourise them into drapes and bind the one
+        // the overlay selects. Inert until the app glue publishes a bake. See
+        // `crate::illumination`.
+        crate::illumination::register(app);
         // P3b: bake DEM-derived surface (rough/AO/hazard) + normal layers off the
         // main thread and publish them as `TerrainDerivedMaps`. Inert headless
         // (gated on render assets existing). See `crate::derived_layers`.
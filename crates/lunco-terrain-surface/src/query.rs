@@ -37,6 +37,7 @@ use lunco_terrain_core::{
     Square, SurfaceField,
 };
 
+use crate::illumination::{IlluminationLayer, TerrainIlluminationLayers};
 use crate::oracle::SurfaceOracle;
 use crate::stream_viz::{DemHeightField, TerrainDetailDemands, TerrainStreamStatus};
 
@@ -57,6 +58,12 @@ fn field_by_id(id: &str) -> Option<Box<dyn SurfaceField>> {
     }
 }
 
+/// What a `TerrainField` id resolved to.
+enum FieldSource {
+    Geometric(Box<dyn SurfaceField>),
+    Illumination(IlluminationLayer),
+}
+
 /// `TerrainHeight` — analytic elevation / normal / slope at a world `(x, z)`,
 /// read straight from the DEM height field (no physics raycast).
 ///
@@ -160,6 +167,11 @@ impl ApiQueryProvider for TerrainHeightProvider {
 /// the texel centre. `{ found: false }` when the complete requested square is not
 /// covered by one DEM. A finite footprint is required so a field never silently
 /// turns an outside region into repeated edge terrain.
+///
+/// The baked illumination layers (`illumination` and `earth_visibility` in percent,
+/// `longest_darkness` in hours — see [`crate::illumination`]) answer through the same
+/// call, resampled from the bake, plus `window: { start_jd, end_jd, step_hours }`.
+/// They are `{ found: false }` until a bake covering the square has landed.
 pub struct TerrainFieldProvider;
 
 impl ApiQueryProvider for TerrainFieldProvider {
@@ -172,11 +184,20 @@ impl ApiQueryProvider for TerrainFieldProvider {
             .get("field")
             .and_then(serde_json::Value::as_str)
             .unwrap_or("slope");
-        let Some(field) = field_by_id(field_id) else {
-            return ApiResponse::error(
-                ApiErrorCode::DeserializationError,
-                format!("TerrainField: unknown field `{field_id}` (slope|aspect|elevation)"),
-            );
+        // A geometric field is materialised from the oracle; a baked
+        // illumination layer is resampled from its bake.
+        let source = match (field_by_id(field_id), IlluminationLayer::from_id(field_id)) {
+            (Some(field), _) => FieldSource::Geometric(field),
+            (None, Some(layer)) => FieldSource::Illumination(layer),
+            (None, None) => {
+                return ApiResponse::error(
+                    ApiErrorCode::DeserializationError,
+                    format!(
+                        "TerrainField: unknown field `{field_id}` \
+                     (slope|aspect|elevation|illumination|longest_darkness|earth_visibility)"
+                    ),
+                )
+            }
         };
         let (Some(x), Some(z), Some(half)) = (
             params.get("x").and_then(serde_json::Value::as_f64),
@@ -208,6 +229,13 @@ impl ApiQueryProvider for TerrainFieldProvider {
         // Wire params are raw scalars; typed the instant they become a point.
         let center = GridPos(DVec3::new(x, 0.0, z));
 
+        let field = match source {
+            FieldSource::Geometric(field) => field,
+            FieldSource::Illumination(layer) => {
+                return illumination_field(world, layer, center, half, res)
+            }
+        };
+
         // Snapshot DEM terrains, releasing the world borrow (see `TerrainHeight`).
         let mut q = world.query::<(Entity, &DemHeightField)>();
         let terrains: Vec<Arc<SurfaceOracle>> = q.iter(world).map(|(_, hf)| hf.0.clone()).collect();
@@ -248,6 +276,52 @@ impl ApiQueryProvider for TerrainFieldProvider {
     }
 }
 
+/// The illumination arm of [`TerrainFieldProvider`]: resample the first baked
+/// layer set whose footprint covers the square. The layers live in the DEM frame,
+/// which is the grid frame, so the centre addresses them directly.
+fn illumination_field(
+    world: &mut World,
+    layer: IlluminationLayer,
+    center: GridPos,
+    half: f64,
+    res: usize,
+) -> ApiResponse {
+    let mut q = world.query::<&TerrainIlluminationLayers>();
+    let [cx, cz] = [center.0.x, center.0.z];
+    let Some(layers) = q.iter(world).find(|l| l.covers([cx, cz], half)) else {
+        return ApiResponse::ok(serde_json::json!({ "found": false }));
+    };
+    let size = 2.0 * half;
+    let mut data = Vec::with_capacity(res * res);
+    for iz in 0..res {
+        let z = cz - half + (iz as f64 + 0.5) / res as f64 * size;
+        for ix in 0..res {
+            let x = cx - half + (ix as f64 + 0.5) / res as f64 * size;
+            data.push(layers.sample(layer, x, z).unwrap_or(f32::NAN));
+        }
+    }
+    let (mut min, mut max) = (f32::INFINITY, f32::NEG_INFINITY);
+    for &v in &data {
+        min = min.min(v);
+        max = max.max(v);
+    }
+    ApiResponse::ok(serde_json::json!({
+        "found": true,
+        "field": layer.id(),
+        "res": res,
+        "half": half,
+        "center": [cx, cz],
+        "min": min,
+        "max": max,
+        "data": data,
+        "window": {
+            "start_jd": layers.start_jd,
+            "end_jd": layers.end_jd,
+            "step_hours": layers.step_hours,
+        },
+    }))
+}
+
 /// Read a `[x,y,z]` array or `{x,y,z}` map into a bare [`DVec3`] — for wire
 /// values that are frame-free vectors (`dir`). `None` if malformed.
 fn parse_vec3(v: Option<&serde_json::Value>) -> Option<DVec3> {
@@ -641,6 +715,41 @@ mod tests {
         assert_eq!(d["res"], json!(FIELD_MAX_RES as u64));
     }
 
+    #[test]
+    fn field_serves_baked_illumination_layers() {
+        let mut world = World::new();
+        // No bake yet → not found, rather than an error or a zero raster.
+        let none = ok_data(TerrainFieldProvider.execute(
+            &mut world,
+            &json!({"field": "illumination", "x": 0.0, "z": 0.0, "half": 5.0}),
+        ));
+        assert_eq!(none["found"], json!(false));
+
+        world.spawn(TerrainIlluminationLayers {
+            res: 2,
+            min: [-10.0, -10.0],
+            size: [20.0, 20.0],
+            start_jd: 2_461_000.0,
+            end_jd: 2_461_354.0,
+            step_hours: 6.0,
+            illumination_pct: Arc::from([80.0; 4]),
+            longest_darkness_hours: Arc::from([48.0; 4]),
+            earth_visibility_pct: Arc::from([10.0, 20.0, 30.0, 40.0]),
+        });
+        let d = ok_data(TerrainFieldProvider.execute(
+            &mut world,
+            &json!({"field": "longest_darkness", "x": 0.0, "z": 0.0, "half": 5.0, "res": 2}),
+        ));
+        assert_eq!(d["found"], json!(true));
+        assert_eq!(d["data"], json!([48.0, 48.0, 48.0, 48.0]));
+        assert_eq!(d["window"]["step_hours"], json!(6.0));
+        let e = ok_data(TerrainFieldProvider.execute(
+            &mut world,
+            &json!({"field": "earth_visibility", "x": 0.0, "z": 0.0, "half": 10.0, "res": 2}),
+        ));
+        assert_eq!(e["data"], json!([10.0, 20.0, 30.0, 40.0]));
+    }
+
     // ── TerrainRaycast ───────────────────────────────────────────────────────
     // Reuses `tilted_terrain`: height = 0.1·x over x∈[−10,10], flat in z.
 
@@ -149,6 +149,11 @@ pub enum DomainKind {
     /// Named mission **timeline** registration (`RegisterTimeline` — declarative
     /// step data stored in the `TimelineStore`). Journaled for the same reason.
     Timeline,
+    /// An executed `#[Command]` (`SetPorts`, `PossessVessel`, a spawn, time
+    /// control …) with the sim tick it ran at and the session it ran as. Replay-only:
+    /// no peer consumes this domain on the journal plane (the command already
+    /// replicated on the command path), and it has no undo inverse.
+    Command,
     Other(String),
 }
 
@@ -54,6 +54,7 @@ use bevy::math::{DQuat, DVec3};
 use bevy::mesh::VertexAttributeValues;
 use bevy::prelude::*;
 use lunco_core::coords::GridPos;
+use lunco_usd_bevy::model::ModelCollisionMesh;
 pub use lunco_usd_bevy::{effective_purpose, Purpose};
 use lunco_usd_bevy::{
     instance_key, local_transform_at, read_primitive_axis, read_shape_dims, read_usd_mesh_indexed,
@@ -384,6 +385,7 @@ impl Plugin for UsdAvianPlugin {
                 (
                     build_terrain_mesh_colliders
                         .run_if(any_with_component::<PendingTerrainCollider>),
+                    build_model_colliders.run_if(any_with_component::<PendingModelCollider>),
                     enforce_kinematic_on_animated,
                     filtered_pairs::enable_shared_tire_contact_hooks,
                     filtered_pairs::enable_static_friction_contact_hooks,
@@ -1030,10 +1032,98 @@ fn add_collider_from_usd(
 ) -> Result<(), TransformReadError> {
     if let Some(collider) = build_collider_from_usd(reader, sdf_path)? {
         commands.entity(entity).try_insert(collider);
+    } else {
+        defer_model_collider(commands, entity, reader, sdf_path)?;
+    }
+    Ok(())
+}
+
+/// A collider waiting on the vendor mesh (`.glb`/`.gltf`/`.obj`/`.stl`) its
+/// prim references. The geometry is the
+/// [`ModelCollisionMesh`](lunco_usd_bevy::model::ModelCollisionMesh) usd-bevy
+/// loads for the visual, so collider and visual come from the same file.
+#[derive(Component)]
+struct PendingModelCollider {
+    /// `physics:approximation`, when `PhysicsMeshCollisionAPI` is applied.
+    approximation: Option<String>,
+    /// The prim's composed local scale, pre-applied like any other collider.
+    scale: Vec3,
+}
+
+/// Parks a binary-asset prim for [`build_model_colliders`]. A no-op for any
+/// other prim — a shape with no collider stays without one.
+///
+/// Under a rigid body the collider goes on the prim's OWN entity, not into the
+/// ancestor's compound: the compound is built now and the mesh is not loaded
+/// yet. Avian attaches a child collider to its nearest body all the same.
+fn defer_model_collider(
+    commands: &mut Commands,
+    entity: Entity,
+    reader: &StageView<'_>,
+    sdf_path: &SdfPath,
+) -> Result<(), TransformReadError> {
+    if reader.binary_asset_uri(sdf_path).is_none() {
+        return Ok(());
     }
+    let scale =
+        local_transform_at(reader, sdf_path, 0.0)?.map_or(Vec3::ONE, |transform| transform.scale);
+    let approximation = reader
+        .has_api_schema(sdf_path, ptok::API_MESH_COLLISION)
+        .then(|| reader.text(sdf_path, ptok::A_APPROXIMATION))
+        .flatten();
+    commands.entity(entity).try_insert(PendingModelCollider {
+        approximation,
+        scale,
+    });
     Ok(())
 }
 
+/// Builds the collider of a binary-asset prim once its collision mesh loads,
+/// with the same `physics:approximation` contract as a native `UsdGeomMesh`:
+/// unauthored / `none` → trimesh, `convexHull`, `convexDecomposition`, and
+/// anything else → no collider rather than a different shape.
+fn build_model_colliders(
+    q: Query<(
+        Entity,
+        &PendingModelCollider,
+        &ModelCollisionMesh,
+        &UsdPrimPath,
+    )>,
+    meshes: Res<Assets<Mesh>>,
+    asset_server: Res<AssetServer>,
+    mut commands: Commands,
+) {
+    for (entity, pending, mesh_h, prim) in &q {
+        let Some(mesh) = meshes.get(&mesh_h.0) else {
+            if asset_server.load_state(&mesh_h.0).is_failed() {
+                warn!(
+                    "[usd-avian] {}: collision mesh failed to load — no collider built",
+                    prim.path
+                );
+                commands.entity(entity).remove::<PendingModelCollider>();
+            }
+            continue;
+        };
+        let collider = match pending.approximation.as_deref() {
+            Some("convexHull") => Collider::convex_hull_from_mesh(mesh),
+            Some("convexDecomposition") => Collider::convex_decomposition_from_mesh(mesh),
+            None | Some("none") => Collider::trimesh_from_mesh(mesh),
+            Some(other) => {
+                warn!(
+                    "[usd-avian] {}: physics:approximation `{other}` is not supported for mesh assets — no collider built",
+                    prim.path
+                );
+                None
+            }
+        };
+        let mut e = commands.entity(entity);
+        if let Some(c) = collider {
+            e.try_insert(apply_collider_scale(c, pending.scale));
+        }
+        e.remove::<PendingModelCollider>();
+    }
+}
+
 fn log_malformed_collider_transform(sdf_path: &SdfPath, error: &TransformReadError) {
     error!(
         "[usd-avian] {sdf_path} has malformed collider transform; refusing collider projection: {error}"
@@ -1673,6 +1763,8 @@ fn extract_avian_prim(
                 return;
             }
             apply_collision_groups(commands, entity, groups, sdf_path);
+        } else if let Err(error) = defer_model_collider(commands, entity, reader, sdf_path) {
+            log_malformed_collider_transform(sdf_path, &error);
         }
         commands.entity(entity).try_insert(UsdAvianProcessed);
     } else {
@@ -97,6 +97,8 @@ pub mod camera_path;
 pub mod canonical;
 pub mod curve_sweep;
 pub mod lathe;
+/// OBJ/STL geometry and per-format materials for binary `references` arcs.
+pub mod model;
 pub mod mount;
 pub mod nurbs;
 pub mod program;
@@ -214,6 +216,10 @@ impl Plugin for UsdBevyPlugin {
             // through the same (web-ready) asset source the live world uses.
             .init_asset::<UsdSourceText>()
             .register_asset_loader(UsdSourceTextLoader)
+            // Vendor meshes: OBJ/STL geometry, and the looks of all four binary
+            // formats (see `model.rs` for why glTF reaches it only by type).
+            .init_asset::<model::ModelAsset>()
+            .register_asset_loader(model::ModelLoader)
             .register_type::<UsdPrimPath>()
             .register_type::<UsdAnimated>()
             .register_type::<UsdResetXformStack>()
@@ -419,6 +425,8 @@ impl Plugin for UsdBevyPlugin {
                     // spawn is mid-flight.
                     resolve_usd_instance_identities,
                     hide_glb_placeholder_meshes,
+                    model::spawn_model_parts,
+                    model::apply_model_looks,
                     poll_diagnostic_label_font,
                     reveal_placeholder_on_failure,
                     bake_pending_labels,
@@ -1463,16 +1471,31 @@ fn instantiate_usd_prim_from_stage(
         // - `lunco:assetMode = "scene"`: load the full glTF scene and
         //   attach as a `WorldAssetRoot` child. Preserves hierarchy,
         //   materials, and lights at the cost of being opaque to the
-        //   USD prim-path tree.
+        //   USD prim-path tree. OBJ/STL have no Bevy scene; their parts
+        //   become children instead (`model::spawn_model_parts`).
         if let Some(asset_uri) = reader.binary_asset_uri(&sdf_path) {
             let mode = reader
                 .text(&sdf_path, "lunco:assetMode")
                 .unwrap_or_else(|| "scene".to_string());
             let label = reader.text(&sdf_path, "lunco:assetLabel");
+            let format = lunco_assets::mesh_import::MeshFormat::from_uri(&asset_uri);
+            let own_geometry = format.is_some_and(|f| f.has_own_geometry());
+
+            // The collider comes from the same file as the visual, whatever the
+            // mode — `lunco-usd-avian` builds it once this mesh loads.
+            if reader.has_api_schema(&sdf_path, "PhysicsCollisionAPI") {
+                let collision_label =
+                    model::single_mesh_label(format, label.as_deref().filter(|_| mode == "mesh"));
+                commands
+                    .entity(entity)
+                    .try_insert(model::ModelCollisionMesh(
+                        asset_server.load(format!("{asset_uri}#{collision_label}")),
+                    ));
+            }
 
             match mode.as_str() {
                 "mesh" => {
-                    let label = label.unwrap_or_else(|| "Mesh0/Primitive0".to_string());
+                    let label = model::single_mesh_label(format, label.as_deref());
                     let path = format!("{asset_uri}#{label}");
                     let mesh_h: Handle<Mesh> = asset_server.load(&path);
                     // Single-mesh path keeps `lunco-usd-avian` collider
@@ -1492,6 +1515,21 @@ fn instantiate_usd_prim_from_stage(
                             err.attribute
                         );
                     }
+                    // No authored material: the file's own is the better look
+                    // than the flat default `apply_standard_material` fell back to.
+                    if resolve_bound_shader(reader, &sdf_path).is_none()
+                        && !reader.has_authored_attribute(&sdf_path, "primvars:displayColor")
+                    {
+                        commands.entity(entity).try_insert(model::PendingModelLook {
+                            model: asset_server.load(&asset_uri),
+                            part: label,
+                        });
+                    }
+                }
+                _ if own_geometry => {
+                    commands
+                        .entity(entity)
+                        .try_insert(model::ModelRoot(asset_server.load(&asset_uri)));
                 }
                 _ => {
                     let label = label.unwrap_or_else(|| "Scene0".to_string());
@@ -0,0 +1,462 @@
+//! Vendor meshes referenced from USD — OBJ and STL geometry, and the materials
+//! of all four binary formats — projected as `Mesh3d` + [`PbrLook`].
+//!
+//! A `.glb`/`.gltf`/`.obj`/`.stl` arc composes to an empty stub (openusd has no
+//! file-format plugins), and the binary branch of the visual projection reads
+//! the authored URI back off the prim. glTF geometry is Bevy's glTF loader's
+//! job; this module adds what that loader cannot give a render-free crate:
+//!
+//! - **[`ModelAsset`]** — the decoded model, via [`ModelLoader`]. For OBJ/STL it
+//!   owns the geometry: one labelled `Part{i}` mesh per material plus a `Merged`
+//!   mesh of everything (what `lunco:assetMode = "mesh"` and the collider use).
+//!   For glTF it carries only the looks, one part per `Mesh{m}/Primitive{p}`.
+//! - **`scene` mode** (the default) on OBJ/STL spawns one child per part, each
+//!   with its own look — the equivalent of a glTF `WorldAssetRoot`.
+//! - **`mesh` mode** keeps the single-`Mesh3d` contract. An authored USD
+//!   material (`material:binding` or `primvars:displayColor`) still wins; only a
+//!   prim that authors none takes the asset's look, once the asset arrives.
+//! - **[`ModelCollisionMesh`]** is stamped on a binary prim with
+//!   `PhysicsCollisionAPI`, so `lunco-usd-avian` can build the collider —
+//!   trimesh, or `physics:approximation = "convexHull"` — from the SAME asset.
+//!
+//! The format decoding itself is [`lunco_assets::mesh_import`].
+
+use bevy::asset::{io::Reader, AssetLoader, LoadContext, RenderAssetUsages};
+use bevy::prelude::*;
+use bevy_mesh::{Indices, PrimitiveTopology};
+use lunco_assets::asset_path::{anchor_of, canonicalize};
+use lunco_assets::mesh_import::{
+    apply_mtl, gltf_materials, parse_obj, parse_stl, ImportedAlpha, ImportedMaterial, ImportedMesh,
+    MeshFormat,
+};
+use lunco_render::{PbrLook, PbrTextures, SurfaceAlpha};
+
+/// Label of the all-parts mesh of an OBJ/STL [`ModelAsset`].
+pub const MERGED_LABEL: &str = "Merged";
+
+/// The glTF primitive a single-mesh read takes when `lunco:assetLabel` is unset
+/// — the same default the `mesh` branch has always used.
+pub const GLTF_DEFAULT_PRIMITIVE: &str = "Mesh0/Primitive0";
+
+/// One drawable piece of a model.
+#[derive(Debug, Clone)]
+pub struct ModelPart {
+    /// `Part{i}` (OBJ/STL) or `Mesh{m}/Primitive{p}` (glTF).
+    pub label: String,
+    /// `None` for glTF, whose geometry Bevy's glTF loader owns.
+    pub mesh: Option<Handle<Mesh>>,
+    /// `None` when the file states no material (STL, an OBJ without `usemtl`).
+    pub look: Option<PbrLook>,
+}
+
+/// A vendor model decoded for projection.
+#[derive(Asset, TypePath, Debug, Clone)]
+pub struct ModelAsset {
+    pub parts: Vec<ModelPart>,
+}
+
+impl ModelAsset {
+    /// The look of the part a single-mesh read shows. `Merged` has no material
+    /// of its own; it takes the first part's.
+    pub fn look_for(&self, label: &str) -> Option<&PbrLook> {
+        if label == MERGED_LABEL {
+            return self.parts.iter().find_map(|p| p.look.as_ref());
+        }
+        self.parts
+            .iter()
+            .find(|p| p.label == label)
+            .and_then(|p| p.look.as_ref())
+    }
+}
+
+/// Loads [`ModelAsset`]s. Claims `.obj` and `.stl` by extension. `.glb`/`.gltf`
+/// stay Bevy's glTF loader's — a labelled load (`#Scene0`) has no type to
+/// disambiguate by — and reach this loader only through the typed
+/// `load::<ModelAsset>`, for which it is the sole candidate.
+#[derive(Default, TypePath)]
+pub struct ModelLoader;
+
+impl AssetLoader for ModelLoader {
+    type Asset = ModelAsset;
+    type Settings = ();
+    type Error = anyhow::Error;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &Self::Settings,
+        load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await?;
+        let anchor = anchor_of(load_context.path());
+        let format = MeshFormat::from_uri(&anchor)
+            .ok_or_else(|| anyhow::anyhow!("`{anchor}` is not a mesh format"))?;
+
+        if !format.has_own_geometry() {
+            let table = gltf_materials(&bytes)?;
+            let looks: Vec<PbrLook> = table
+                .materials
+                .iter()
+                .map(|m| look(m, load_context, &anchor))
+                .collect();
+            let parts = table
+                .primitives
+                .iter()
+                .enumerate()
+                .flat_map(|(m, prims)| prims.iter().enumerate().map(move |(p, mat)| (m, p, *mat)))
+                .map(|(m, p, mat)| ModelPart {
+                    label: format!("Mesh{m}/Primitive{p}"),
+                    mesh: None,
+                    look: mat.and_then(|i| looks.get(i).cloned()),
+                })
+                .collect();
+            return Ok(ModelAsset { parts });
+        }
+
+        let mut model = match format {
+            MeshFormat::Obj => parse_obj(std::str::from_utf8(&bytes)?)?,
+            _ => parse_stl(&bytes)?,
+        };
+        for lib in model.material_libs.clone() {
+            let path = canonicalize(&lib, &anchor);
+            match load_context
+                .read_asset_bytes(bevy::asset::AssetPath::parse(&path).into_owned())
+                .await
+            {
+                Ok(mtl) => apply_mtl(&mut model, &String::from_utf8_lossy(&mtl)),
+                // A missing library is a cosmetic loss, not a missing model: the
+                // geometry still loads, grey.
+                Err(e) => warn!("[usd-bevy] `{anchor}`: material library `{path}` unreadable: {e}"),
+            }
+        }
+
+        let mut merged = ImportedMesh::default();
+        let mut parts = Vec::with_capacity(model.meshes.len());
+        for (i, part) in model.meshes.iter().enumerate() {
+            let base = merged.positions.len() as u32;
+            merged.positions.extend(&part.positions);
+            merged
+                .indices
+                .extend(part.indices.iter().map(|&k| k + base));
+            let label = format!("Part{i}");
+            let mesh = load_context.add_labeled_asset(label.clone(), to_mesh(part));
+            parts.push(ModelPart {
+                label,
+                mesh: Some(mesh),
+                look: part
+                    .material
+                    .map(|m| look(&model.materials[m], load_context, &anchor)),
+            });
+        }
+        load_context.add_labeled_asset(MERGED_LABEL.to_string(), to_mesh(&merged));
+        Ok(ModelAsset { parts })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["obj", "stl"]
+    }
+}
+
+fn to_mesh(part: &ImportedMesh) -> Mesh {
+    let mut mesh = Mesh::new(
+        PrimitiveTopology::TriangleList,
+        RenderAssetUsages::default(),
+    );
+    let n = part.positions.len();
+    mesh.insert_attribute(Mesh::ATTRIBUTE_POSITION, part.positions.clone());
+    // ShaderMaterial / StandardMaterial both expect a UV channel.
+    let uvs = if part.uvs.len() == n {
+        part.uvs.clone()
+    } else {
+        vec![[0.0, 0.0]; n]
+    };
+    mesh.insert_attribute(Mesh::ATTRIBUTE_UV_0, uvs);
+    mesh.insert_indices(Indices::U32(part.indices.clone()));
+    if part.normals.len() == n {
+        mesh.insert_attribute(Mesh::ATTRIBUTE_NORMAL, part.normals.clone());
+    } else {
+        mesh.compute_normals();
+    }
+    mesh
+}
+
+fn look(m: &ImportedMaterial, load_context: &mut LoadContext<'_>, anchor: &str) -> PbrLook {
+    let [r, g, b, a] = m.base_color;
+    let [er, eg, eb] = m.emissive;
+    PbrLook {
+        base_color: LinearRgba::new(r, g, b, a),
+        emissive: LinearRgba::rgb(er, eg, eb),
+        metallic: m.metallic,
+        perceptual_roughness: m.roughness,
+        alpha: match m.alpha {
+            ImportedAlpha::Opaque => SurfaceAlpha::Opaque,
+            ImportedAlpha::Mask(t) => SurfaceAlpha::Mask(t),
+            ImportedAlpha::Blend => SurfaceAlpha::Blend,
+        },
+        double_sided: m.double_sided,
+        textures: PbrTextures {
+            base_color: m
+                .base_color_texture
+                .as_deref()
+                .map(|t| load_context.load(canonicalize(t, anchor))),
+            ..default()
+        },
+        ..default()
+    }
+}
+
+/// On an OBJ/STL prim in `scene` mode: the model whose parts become children.
+#[derive(Component)]
+pub struct ModelRoot(pub Handle<ModelAsset>);
+
+/// On a `mesh`-mode prim with no authored material: take the look of `part`
+/// once `model` loads.
+#[derive(Component)]
+pub struct PendingModelLook {
+    pub model: Handle<ModelAsset>,
+    pub part: String,
+}
+
+/// The mesh a binary-asset collider is built from. Stamped on prims with
+/// `PhysicsCollisionAPI`; `lunco-usd-avian` reads it.
+#[derive(Component)]
+pub struct ModelCollisionMesh(pub Handle<Mesh>);
+
+/// The single mesh of a binary asset: `lunco:assetLabel` if authored, else
+/// `Merged` for OBJ/STL and the first primitive for glTF.
+pub fn single_mesh_label(format: Option<MeshFormat>, authored: Option<&str>) -> String {
+    match (authored, format) {
+        (Some(label), _) => label.to_string(),
+        (None, Some(f)) if f.has_own_geometry() => MERGED_LABEL.to_string(),
+        (None, _) => GLTF_DEFAULT_PRIMITIVE.to_string(),
+    }
+}
+
+/// Spawns one child per part under each [`ModelRoot`] once its model loads.
+pub(crate) fn spawn_model_parts(
+    mut commands: Commands,
+    roots: Query<(Entity, &ModelRoot, &crate::UsdPrimPath)>,
+    models: Res<Assets<ModelAsset>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, root, prim) in &roots {
+        let Some(model) = models.get(&root.0) else {
+            if asset_server.load_state(&root.0).is_failed() {
+                error!("[usd-bevy] {}: mesh asset failed to load", prim.path);
+                commands.entity(entity).remove::<ModelRoot>();
+            }
+            continue;
+        };
+        for part in &model.parts {
+            let Some(mesh) = part.mesh.clone() else {
+                continue;
+            };
+            commands.spawn((
+                Name::new(part.label.clone()),
+                Mesh3d(mesh),
+                part.look.clone().unwrap_or_default(),
+                Transform::IDENTITY,
+                Visibility::Inherited,
+                ChildOf(entity),
+            ));
+        }
+        commands.entity(entity).remove::<ModelRoot>();
+    }
+}
+
+/// Replaces the default look of a `mesh`-mode prim with its asset's.
+pub(crate) fn apply_model_looks(
+    mut commands: Commands,
+    pending: Query<(Entity, &PendingModelLook)>,
+    models: Res<Assets<ModelAsset>>,
+    asset_server: Res<AssetServer>,
+) {
+    for (entity, p) in &pending {
+        match models.get(&p.model) {
+            Some(model) => {
+                if let Some(look) = model.look_for(&p.part) {
+                    commands.entity(entity).try_insert(look.clone());
+                }
+            }
+            // A model whose looks cannot be read still has its geometry (a
+            // separate load); it keeps the default look.
+            None if asset_server.load_state(&p.model).is_failed() => {}
+            None => continue,
+        }
+        commands.entity(entity).remove::<PendingModelLook>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bevy::asset::{AssetApp, AssetPlugin};
+
+    fn app() -> App {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .add_plugins(AssetPlugin::default())
+            .init_asset::<Mesh>()
+            .init_asset::<ModelAsset>()
+            .add_systems(Update, (spawn_model_parts, apply_model_looks));
+        app
+    }
+
+    fn tinted(r: f32) -> PbrLook {
+        PbrLook {
+            base_color: LinearRgba::rgb(r, 0.0, 0.0),
+            ..default()
+        }
+    }
+
+    fn add_model(app: &mut App, parts: Vec<ModelPart>) -> Handle<ModelAsset> {
+        app.world_mut()
+            .resource_mut::<Assets<ModelAsset>>()
+            .add(ModelAsset { parts })
+    }
+
+    fn add_mesh(app: &mut App) -> Handle<Mesh> {
+        app.world_mut()
+            .resource_mut::<Assets<Mesh>>()
+            .add(Mesh::new(PrimitiveTopology::TriangleList, default()))
+    }
+
+    fn prim(path: &str) -> crate::UsdPrimPath {
+        crate::UsdPrimPath {
+            stage_handle: Handle::default(),
+            path: path.to_string(),
+        }
+    }
+
+    #[test]
+    fn single_mesh_label_prefers_the_authored_label() {
+        assert_eq!(
+            single_mesh_label(Some(MeshFormat::Obj), Some("Part1")),
+            "Part1"
+        );
+        assert_eq!(single_mesh_label(Some(MeshFormat::Stl), None), MERGED_LABEL);
+        assert_eq!(single_mesh_label(Some(MeshFormat::Obj), None), MERGED_LABEL);
+        assert_eq!(
+            single_mesh_label(Some(MeshFormat::Glb), None),
+            GLTF_DEFAULT_PRIMITIVE
+        );
+        assert_eq!(single_mesh_label(None, None), GLTF_DEFAULT_PRIMITIVE);
+    }
+
+    #[test]
+    fn multi_part_model_spawns_one_child_per_part_with_its_look() {
+        let mut app = app();
+        let (a, b) = (add_mesh(&mut app), add_mesh(&mut app));
+        let model = add_model(
+            &mut app,
+            vec![
+                ModelPart {
+                    label: "Part0".into(),
+                    mesh: Some(a.clone()),
+                    look: Some(tinted(1.0)),
+                },
+                ModelPart {
+                    label: "Part1".into(),
+                    mesh: Some(b.clone()),
+                    look: None,
+                },
+                // A glTF part: its geometry is the glTF loader's, not a child.
+                ModelPart {
+                    label: "Mesh0/Primitive0".into(),
+                    mesh: None,
+                    look: Some(tinted(0.5)),
+                },
+            ],
+        );
+        let root = app
+            .world_mut()
+            .spawn((ModelRoot(model), prim("/Rover/Arm")))
+            .id();
+
+        app.update();
+
+        assert!(app.world().get::<ModelRoot>(root).is_none());
+        let mut children: Vec<(String, Handle<Mesh>, PbrLook)> = app
+            .world_mut()
+            .query::<(&Name, &Mesh3d, &PbrLook, &ChildOf)>()
+            .iter(app.world())
+            .filter(|(.., c)| c.parent() == root)
+            .map(|(n, m, l, _)| (n.to_string(), m.0.clone(), l.clone()))
+            .collect();
+        children.sort_by(|x, y| x.0.cmp(&y.0));
+        assert_eq!(children.len(), 2, "one child per part that owns geometry");
+        assert_eq!(children[0].0, "Part0");
+        assert_eq!(children[0].1, a);
+        assert_eq!(children[0].2, tinted(1.0));
+        assert_eq!(children[1].0, "Part1");
+        assert_eq!(children[1].1, b);
+        assert_eq!(
+            children[1].2,
+            PbrLook::default(),
+            "no material: default look"
+        );
+    }
+
+    #[test]
+    fn single_mesh_model_takes_the_first_part_look_for_merged() {
+        let mut app = app();
+        let mesh = add_mesh(&mut app);
+        let model = add_model(
+            &mut app,
+            vec![ModelPart {
+                label: "Part0".into(),
+                mesh: Some(mesh),
+                look: Some(tinted(0.25)),
+            }],
+        );
+        let prim = app
+            .world_mut()
+            .spawn((
+                PbrLook::default(),
+                PendingModelLook {
+                    model,
+                    part: single_mesh_label(Some(MeshFormat::Obj), None),
+                },
+            ))
+            .id();
+
+        app.update();
+
+        assert!(app.world().get::<PendingModelLook>(prim).is_none());
+        assert_eq!(app.world().get::<PbrLook>(prim), Some(&tinted(0.25)));
+    }
+
+    #[test]
+    fn model_without_a_look_leaves_the_default_in_place() {
+        let mut app = app();
+        let mesh = add_mesh(&mut app);
+        // An STL: geometry, no material.
+        let model = add_model(
+            &mut app,
+            vec![ModelPart {
+                label: "Part0".into(),
+                mesh: Some(mesh),
+                look: None,
+            }],
+        );
+        let prim = app
+            .world_mut()
+            .spawn((
+                PbrLook::default(),
+                PendingModelLook {
+                    model,
+                    part: MERGED_LABEL.to_string(),
+                },
+            ))
+            .id();
+
+        app.update();
+
+        assert!(
+            app.world().get::<PendingModelLook>(prim).is_none(),
+            "a loaded model settles the prim even when it has no look"
+        );
+        assert_eq!(app.world().get::<PbrLook>(prim), Some(&PbrLook::default()));
+    }
+}
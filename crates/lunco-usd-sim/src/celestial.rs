@@ -22,6 +22,9 @@
 //! int    lunco:libration:primary = 399          # a libration point of a PAIR:
 //! int    lunco:libration:secondary = 301        #   Earth-Moon L1 (a parked relay)
 //! token  lunco:libration:point = "L1"           #   L1..L5
+//! int    lunco:halo:primary = 399               # a halo orbit of a PAIR's L1/L2:
+//! int    lunco:halo:secondary = 301             #   + point/branch/periodDays/
+//!                                               #   periluneKm/epochJd/phase
 //! ```
 //!
 //! A root prim (path depth 1) authoring an anchor is the scene's **site
@@ -30,6 +33,7 @@
 
 use bevy::prelude::*;
 
+use lunco_celestial::cr3bp::{HaloBranch, HaloOrbit};
 use lunco_celestial::frames::LPoint;
 use lunco_celestial::geo::{Geodetic, GeodeticAnchor, SiteAnchor};
 use lunco_celestial::kepler::{KeplerOrbit, KeplerianElements};
@@ -521,7 +525,12 @@ pub fn insert_celestial_comms_components(
             }
             let frame = read_authored_token(reader, sdf_path, "lunco:trajectory:frame")?
                 .unwrap_or_else(|| "Inertial".to_string());
-            if !matches!(frame.as_str(), "Inertial" | "BodyFixed") {
+            if !matches!(frame.as_str(), "Inertial" | "BodyFixed" | "Synodic") {
+                return Err(());
+            }
+            let secondary_id = read_authored_i32(reader, sdf_path, "lunco:trajectory:secondaryId")?
+                .filter(|id| *id != 0);
+            if frame == "Synodic" && secondary_id.is_none() {
                 return Err(());
             }
             let user_visible =
@@ -547,6 +556,7 @@ pub fn insert_celestial_comms_components(
                 sampling_days,
                 sampling_step,
                 frame,
+                secondary_id,
                 user_visible,
                 start_epoch_jd,
                 end_epoch_jd,
@@ -716,6 +726,73 @@ pub fn insert_celestial_comms_components(
         ),
     }
 
+    // --- Halo orbit (a relay flying an L1/L2 halo or NRHO of a pair) ---
+    //
+    // The fourth placement kind. Keyed on `primary` like the libration point; the
+    // orbit itself is solved by `lunco_celestial::cr3bp` once the component lands.
+    let halo = match read_authored_i32(reader, sdf_path, "lunco:halo:primary") {
+        Ok(None) => Ok(None),
+        Ok(Some(primary)) if primary != 0 => (|| {
+            let defaults = HaloOrbit::default();
+            let secondary =
+                read_authored_i32(reader, sdf_path, "lunco:halo:secondary")?.ok_or(())?;
+            if secondary == 0 {
+                return Err(());
+            }
+            let point = match read_authored_token(reader, sdf_path, "lunco:halo:point")? {
+                Some(token) => LPoint::from_token(&token).ok_or(())?,
+                None => defaults.point,
+            };
+            if !matches!(point, LPoint::L1 | LPoint::L2) {
+                return Err(());
+            }
+            let branch = match read_authored_token(reader, sdf_path, "lunco:halo:branch")? {
+                Some(token) => HaloBranch::from_token(&token).ok_or(())?,
+                None => defaults.branch,
+            };
+            let perilune_km = read_real_strict(reader, sdf_path, "lunco:halo:periluneKm")?;
+            // An authored perilune with no authored period selects by perilune.
+            let period_days = read_real_strict(reader, sdf_path, "lunco:halo:periodDays")?
+                .unwrap_or(if perilune_km.is_some() {
+                    0.0
+                } else {
+                    defaults.period_days
+                });
+            let perilune_m = perilune_km.unwrap_or(0.0) * 1000.0;
+            let epoch_jd = read_real_strict(reader, sdf_path, "lunco:halo:epochJd")?.unwrap_or(0.0);
+            let phase = read_real_strict(reader, sdf_path, "lunco:halo:phase")?.unwrap_or(0.0);
+            if period_days < 0.0 || perilune_m < 0.0 || (period_days == 0.0 && perilune_m == 0.0) {
+                return Err(());
+            }
+            Ok(HaloOrbit {
+                primary,
+                secondary,
+                point,
+                branch,
+                period_days,
+                perilune_m,
+                epoch_jd,
+                phase,
+            })
+        })()
+        .map(Some),
+        Ok(Some(_)) | Err(()) => Err(()),
+    };
+    match halo {
+        Ok(Some(orbit)) => {
+            info!(
+                "[usd-celestial] halo {}: {:?} {:?} of pair {}/{}",
+                prim_path_str, orbit.branch, orbit.point, orbit.primary, orbit.secondary
+            );
+            commands.entity(entity).try_insert(orbit);
+        }
+        Ok(None) => {}
+        Err(()) => warn!(
+            "[usd-celestial] {} has malformed halo attributes; orbit ignored",
+            prim_path_str
+        ),
+    }
+
     // --- Solar-pose tracking marker (generic celestial placement) ---
     // A scene-local subsystem prim (a rover-mounted antenna, a panel) opts in so
     // the pose system tracks its solar-frame position; anchored/orbiting prims
@@ -481,6 +481,67 @@ pub(crate) fn process_usd_cosim_prims(
                 },
                 declared_outputs,
             ));
+            // Equivalent-aluminium shielding scales the radiation outputs. An
+            // omitted attribute is unshielded; a malformed one is refused rather
+            // than read as zero, which would overstate nothing but hide the typo.
+            match read_authored_real(&view, &sdf_path, "lunco:radiation:shielding") {
+                Ok(Some(g_cm2)) if g_cm2 >= 0.0 => {
+                    commands
+                        .entity(entity)
+                        .try_insert(lunco_environment::RadiationShielding {
+                            areal_density_g_cm2: g_cm2 as f32,
+                        });
+                }
+                Ok(None) => {}
+                _ => warn!(
+                    "[usd-cosim] {sdf_path}: `lunco:radiation:shielding` must be a non-negative \
+                     real (g/cm2); the probe is treated as unshielded"
+                ),
+            }
+            // A dose-equivalent limit arms the probe's one-shot
+            // `radiation.dose_limit` telemetry. Zero or omitted sets none.
+            match read_authored_real(&view, &sdf_path, "lunco:radiation:doseLimit") {
+                Ok(Some(sievert)) if sievert > 0.0 => {
+                    commands
+                        .entity(entity)
+                        .try_insert(lunco_environment::RadiationDoseLimit { sievert });
+                }
+                Ok(Some(sievert)) if sievert == 0.0 => {}
+                Ok(None) => {}
+                _ => warn!(
+                    "[usd-cosim] {sdf_path}: `lunco:radiation:doseLimit` must be a non-negative \
+                     real (Sv); the probe has no dose limit"
+                ),
+            }
+            // A solar-array face turns the probe's sun sample into an area
+            // sample. Width alone opts in; a malformed footprint is refused and
+            // the probe keeps its single-point sample.
+            let solar = |name: &str| read_authored_real(&view, &sdf_path, name);
+            match (
+                solar("lunco:solar:width"),
+                solar("lunco:solar:length"),
+                solar("lunco:solar:samples"),
+            ) {
+                (Ok(None), _, _) => {}
+                (Ok(Some(width)), Ok(length), Ok(samples))
+                    if width > 0.0
+                        && length.is_none_or(|l| l > 0.0)
+                        && samples.is_none_or(|n| n >= 1.0) =>
+                {
+                    let default = lunco_environment::SolarArrayFootprint::default();
+                    commands
+                        .entity(entity)
+                        .try_insert(lunco_environment::SolarArrayFootprint {
+                            width: width as f32,
+                            length: length.unwrap_or(width) as f32,
+                            samples: samples.map_or(default.samples, |n| n.round() as u32),
+                        });
+                }
+                _ => warn!(
+                    "[usd-cosim] {sdf_path}: `lunco:solar:width`/`length` must be positive and \
+                     `lunco:solar:samples` at least 1; the probe samples its origin only"
+                ),
+            }
             // A stage may finish composing after the prim's Added event. Force
             // the native USD wiring cache to resolve connections from this
             // newly published source interface in the same update cycle.
@@ -555,7 +616,7 @@ fn read_authored_telemetry_string(
     }
 }
 
-fn read_authored_telemetry_real(
+fn read_authored_real(
     view: &lunco_usd_bevy::StageView<'_>,
     path: &SdfPath,
     attribute: &str,
@@ -635,24 +696,22 @@ fn project_usd_telemetry(
                     .unwrap_or_default();
                 let description =
                     read_authored_telemetry_string(&view, &path, "lunco:telemetry:description")?;
-                let rate_hz =
-                    match read_authored_telemetry_real(&view, &path, "lunco:telemetry:rateHz")? {
-                        None | Some(0.0) => None,
-                        Some(value) if value > 0.0 => Some(value),
-                        Some(_) => return Err(()),
-                    };
+                let rate_hz = match read_authored_real(&view, &path, "lunco:telemetry:rateHz")? {
+                    None | Some(0.0) => None,
+                    Some(value) if value > 0.0 => Some(value),
+                    Some(_) => return Err(()),
+                };
                 let enabled =
                     match read_authored_bool_strict(&view, &path, "lunco:telemetry:enabled") {
                         Ok(Some(value)) => value,
                         Ok(None) => true,
                         Err(()) => return Err(()),
                     };
-                let deadband =
-                    match read_authored_telemetry_real(&view, &path, "lunco:telemetry:deadband")? {
-                        None | Some(0.0) => None,
-                        Some(value) if value > 0.0 => Some(value),
-                        Some(_) => return Err(()),
-                    };
+                let deadband = match read_authored_real(&view, &path, "lunco:telemetry:deadband")? {
+                    None | Some(0.0) => None,
+                    Some(value) if value > 0.0 => Some(value),
+                    Some(_) => return Err(()),
+                };
                 let retention = match view.scalar::<i64>(&path, "lunco:telemetry:retention") {
                     Some(0) | None
                         if !view.has_authored_attribute(&path, "lunco:telemetry:retention") =>
@@ -1289,6 +1348,19 @@ pub(crate) fn dispatch_loaded_modelica_sources(
         // `wrap_modelica_into_simcomponent` copies into `SimComponent::inputs` —
         // the port surface a wire writes to.
         let interface = parse_model_interface(&src.text, "cosim-dispatch.mo");
+        // The `unit` each port declares, for the cosim wire-unit check. Only the
+        // port surface: an internal variable's unit is the lint's business.
+        let port_units: HashMap<String, String> = interface
+            .variable_metadata
+            .iter()
+            .filter(|(name, _)| {
+                interface.inputs.contains_key(*name) || interface.outputs.contains(*name)
+            })
+            .filter_map(|(name, meta)| Some((name.clone(), meta.unit.clone()?)))
+            .collect();
+        commands
+            .entity(entity)
+            .try_insert(lunco_cosim::PortUnits { units: port_units });
         let model_name = interface.model_name.unwrap_or_else(|| "Model".into());
         let mut parameters = interface.parameters;
         let mut inputs = interface.inputs;
@@ -10,6 +10,7 @@
 use bevy::prelude::*;
 use lunco_api::queries::{ApiQueryProvider, ApiQueryRegistry};
 use lunco_api::schema::ApiResponse;
+use lunco_core::units::WireUnits;
 use lunco_cosim::CosimDiagnostics;
 
 /// `GetBrokenConnections` — backs `GET /api/diagnostics`. Reports the co-sim
@@ -20,13 +21,16 @@ use lunco_cosim::CosimDiagnostics;
 ///
 /// `broken` contains only terminal failures. `pending` contains structural or
 /// compiling endpoints, which are expected during assembly and never emit a
-/// warning. When the cosim engine isn't installed it degrades to
-/// `cosim_tracked: false` rather than a hopeful empty "all clear".
+/// warning. `unit_mismatches` are bound wires whose declared port units their
+/// factor/offset does not reconcile — they carry values, wrongly scaled, so they
+/// are reported beside the faults rather than counted among them. When the
+/// cosim engine isn't installed it degrades to `cosim_tracked: false` rather
+/// than a hopeful empty "all clear".
 ///
 /// params: none · returns:
 /// `{ cosim_tracked, broken_count, pending_count, algebraic_loop_count,
-///    fault_count, broken: [...], pending: [...], algebraic_loops: [...],
-///    runtime_fault: ... }`
+///    unit_mismatch_count, fault_count, broken: [...], pending: [...],
+///    algebraic_loops: [...], unit_mismatches: [...], runtime_fault: ... }`
 pub struct BrokenConnectionsProvider;
 
 impl ApiQueryProvider for BrokenConnectionsProvider {
@@ -79,11 +83,43 @@ impl ApiQueryProvider for BrokenConnectionsProvider {
                     .collect::<Vec<_>>()
             })
             .unwrap_or_default();
+        let unit_mismatches = diag
+            .map(|d| {
+                d.unit_mismatches
+                    .iter()
+                    .map(|m| {
+                        let (verdict, needed) = match &m.verdict {
+                            WireUnits::Incompatible { .. } => ("incompatible", None),
+                            WireUnits::MissingScale(c) => ("missing-scale", Some(c)),
+                            WireUnits::WrongScale(c) => ("wrong-scale", Some(c)),
+                            WireUnits::Consistent | WireUnits::Unchecked => ("consistent", None),
+                        };
+                        serde_json::json!({
+                            "source_bits": m.source.to_bits(),
+                            "source_global_id": m.source_id.map(|g| g.get()),
+                            "source_port": m.source_port,
+                            "source_unit": m.source_unit,
+                            "target_bits": m.target.to_bits(),
+                            "target_global_id": m.target_id.map(|g| g.get()),
+                            "target_port": m.target_port,
+                            "target_unit": m.target_unit,
+                            "factor": m.scale,
+                            "offset": m.offset,
+                            "verdict": verdict,
+                            "needed_factor": needed.map(|c| c.factor),
+                            "needed_offset": needed.map(|c| c.offset),
+                            "detail": m.detail(),
+                        })
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default();
         ApiResponse::ok(serde_json::json!({
             "cosim_tracked": diag.is_some(),
             "broken_count": broken.len(),
             "pending_count": pending.len(),
             "algebraic_loop_count": algebraic_loops.len(),
+            "unit_mismatch_count": unit_mismatches.len(),
             // Keep the historical count semantics: one entry per terminal
             // connection/runtime fault. A rejected algebraic loop is exposed
             // in `algebraic_loops` and its terminal effect in `runtime_fault`;
@@ -92,6 +128,7 @@ impl ApiQueryProvider for BrokenConnectionsProvider {
             "broken": broken,
             "pending": pending,
             "algebraic_loops": algebraic_loops,
+            "unit_mismatches": unit_mismatches,
             "runtime_fault": runtime_fault,
         }))
     }
@@ -41,6 +41,9 @@ pub mod registry;
 pub mod runtime_persistence;
 pub mod schema;
 pub mod twin_projection;
+/// URDF ⇄ UsdPhysics: ROS robot descriptions in as Z-up vessel layers, and any
+/// rigid-body vessel out as URDF for `robot_state_publisher`, RViz and Gazebo.
+pub mod urdf;
 #[cfg(feature = "ui")]
 pub mod ui;
 
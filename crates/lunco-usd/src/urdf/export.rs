@@ -0,0 +1,725 @@
+//! A composed vessel → [`UrdfRobot`].
+//!
+//! Reads the LIVE composed stage through [`StageView`], the same reader the
+//! physics bridge uses, so what is exported is what simulates: references,
+//! variants and scene overrides are already resolved. Every world pose comes
+//! from [`world_transform`] (canonical, Y-up) and crosses to URDF's Z-up through
+//! the Z-up USD spoke run backwards.
+
+use super::{
+    inertia_tensor, write_urdf, UrdfDynamics, UrdfError, UrdfGeometry, UrdfInertial, UrdfJoint,
+    UrdfJointKind, UrdfLimit, UrdfLink, UrdfMaterial, UrdfMimic, UrdfPose, UrdfRobot, UrdfShape,
+    WORLD_LINK,
+};
+use bevy::math::{DMat3, DQuat, DVec3};
+use lunco_usd_avian::{resolve_joint_body_path, world_transform};
+use lunco_usd_bevy::{ConventionTransform, StageMetrics, StageView, UpAxis, UsdRead};
+use openusd::sdf::Path as SdfPath;
+use std::collections::{HashMap, HashSet};
+
+/// A written URDF plus the robot it was written from.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrdfExport {
+    pub urdf: String,
+    pub robot: UrdfRobot,
+    /// What the vessel has that URDF cannot say (spherical joints, capsules,
+    /// closed loops broken open). Empty on a lossless export.
+    pub warnings: Vec<String>,
+}
+
+/// Export the vessel rooted at `root` as URDF text.
+pub fn export_urdf(view: &StageView<'_>, root: &SdfPath) -> Result<UrdfExport, UrdfError> {
+    let (robot, warnings) = robot_from_stage(view, root)?;
+    Ok(UrdfExport {
+        urdf: write_urdf(&robot),
+        robot,
+        warnings,
+    })
+}
+
+/// A rigid frame in canonical world space.
+#[derive(Clone, Copy)]
+struct Frame {
+    pos: DVec3,
+    rot: DQuat,
+}
+
+impl Frame {
+    const IDENTITY: Self = Self {
+        pos: DVec3::ZERO,
+        rot: DQuat::IDENTITY,
+    };
+
+    fn then(self, pos: DVec3, rot: DQuat) -> Self {
+        Self {
+            pos: self.pos + self.rot * pos,
+            rot: (self.rot * rot).normalize(),
+        }
+    }
+
+    /// `self⁻¹ · other`.
+    fn relative(self, other: Frame) -> Frame {
+        let inv = self.rot.inverse();
+        Frame {
+            pos: inv * (other.pos - self.pos),
+            rot: (inv * other.rot).normalize(),
+        }
+    }
+}
+
+/// Canonical → URDF (Z-up, metres): the Z-up USD spoke, backwards. Vectors go
+/// through `stage_dir_d`; a frame-local rotation conjugates by the same basis
+/// change, exactly as the import reader conjugates `localRot`.
+struct ToUrdf {
+    conv: ConventionTransform,
+    basis: DQuat,
+}
+
+impl ToUrdf {
+    fn new() -> Self {
+        let conv = ConventionTransform::from_stage_metrics(&StageMetrics {
+            meters_per_unit: 1.0,
+            up_axis: UpAxis::Z,
+        });
+        let basis = DQuat::from_mat3(&DMat3::from_cols(
+            conv.stage_dir_d(DVec3::X),
+            conv.stage_dir_d(DVec3::Y),
+            conv.stage_dir_d(DVec3::Z),
+        ));
+        Self { conv, basis }
+    }
+
+    fn pose(&self, f: Frame) -> UrdfPose {
+        UrdfPose::from_parts(
+            self.conv.stage_point_d(f.pos),
+            self.basis * f.rot * self.basis.inverse(),
+        )
+    }
+
+    fn dir(&self, v: DVec3) -> DVec3 {
+        self.conv.stage_dir_d(v)
+    }
+
+    fn tensor(&self, t: DMat3) -> DMat3 {
+        let m = DMat3::from_quat(self.basis);
+        m * t * m.transpose()
+    }
+}
+
+struct JointRead {
+    path: SdfPath,
+    name: String,
+    usd_type: String,
+    /// Empty = anchored to the world.
+    body0: String,
+    body1: String,
+    /// The joint frame on the child side, in body1's frame. The parent side
+    /// coincides with it at rest, which is all URDF can record.
+    frame1: (DVec3, DQuat),
+    /// In the joint frame, canonical; `frame1`'s rotation takes it into body1's.
+    axis: DVec3,
+}
+
+/// Read the vessel rooted at `root` into a URDF model, plus the warnings for
+/// anything dropped on the way.
+pub fn robot_from_stage(
+    view: &StageView<'_>,
+    root: &SdfPath,
+) -> Result<(UrdfRobot, Vec<String>), UrdfError> {
+    if !view.has_prim(root) {
+        return Err(UrdfError::Invalid(format!("no prim at `{root}`")));
+    }
+    let conv = lunco_usd_bevy::stage_convention(view)
+        .map_err(|e| UrdfError::Invalid(format!("stage metrics: {e}")))?;
+    let to_urdf = ToUrdf::new();
+    let mut warnings = Vec::new();
+
+    let mut prims = Vec::new();
+    descendants(view, root, &mut prims);
+    let bodies: Vec<&SdfPath> = prims
+        .iter()
+        .filter(|p| view.has_api_schema(p, "PhysicsRigidBodyAPI"))
+        .collect();
+    let body_set: HashSet<String> = bodies.iter().map(|p| p.to_string()).collect();
+    if bodies.is_empty() {
+        return Err(UrdfError::Invalid(format!(
+            "`{root}` holds no PhysicsRigidBodyAPI prim"
+        )));
+    }
+
+    // Link names: the URDF name an import recorded, else the prim name —
+    // de-duplicated, since nested prims may share a leaf name.
+    let mut used = HashSet::new();
+    used.insert(WORLD_LINK.to_string());
+    let link_name: HashMap<String, String> = bodies
+        .iter()
+        .map(|p| (p.to_string(), unique(&mut used, &urdf_name(view, p))))
+        .collect();
+
+    let world = |p: &SdfPath| -> Result<Frame, UrdfError> {
+        let t = world_transform(view, p).map_err(|e| UrdfError::Invalid(e.to_string()))?;
+        Ok(Frame {
+            pos: t.translation.as_dvec3(),
+            rot: t.rotation.as_dquat().normalize(),
+        })
+    };
+
+    // ── Joints ────────────────────────────────────────────────────────────
+    let mut joints_read = Vec::new();
+    let mut gears = Vec::new();
+    let mut joint_used = HashSet::new();
+    for p in &prims {
+        let Some(ty) = view.type_name(p) else {
+            continue;
+        };
+        match ty.as_str() {
+            "PhysicsRevoluteJoint" | "PhysicsPrismaticJoint" | "PhysicsFixedJoint" => {}
+            "PhysxPhysicsGearJoint" => {
+                gears.push(p.clone());
+                continue;
+            }
+            t if t.starts_with("Physics") && t.ends_with("Joint") => {
+                warnings.push(format!(
+                    "`{p}` is a {t}, which URDF cannot express; skipped"
+                ));
+                continue;
+            }
+            _ => continue,
+        }
+        if view.boolean(p, "physics:jointEnabled") == Some(false) {
+            continue;
+        }
+        let resolve = |rel: &str| -> String {
+            view.rel_target(p, rel)
+                .and_then(|t| resolve_joint_body_path(view, &t))
+                .filter(|b| body_set.contains(b))
+                .unwrap_or_default()
+        };
+        let (body0, body1) = (resolve("physics:body0"), resolve("physics:body1"));
+        if body1.is_empty() {
+            warnings.push(format!(
+                "`{p}` moves no body of this vessel (its body1 is outside `{root}`); skipped"
+            ));
+            continue;
+        }
+        let local = |name: &str| -> Option<DVec3> {
+            view.has_authored_attribute(p, name)
+                .then(|| view.value::<[f32; 3]>(p, name))
+                .flatten()
+                .map(|v| conv.point_d(DVec3::new(v[0] as f64, v[1] as f64, v[2] as f64)))
+        };
+        let local_rot = |name: &str| -> DQuat {
+            view.value::<openusd::gf::Quatf>(p, name)
+                .filter(|_| view.has_authored_attribute(p, name))
+                .map(|q| {
+                    conv.rotation_d(
+                        DQuat::from_xyzw(q.x as f64, q.y as f64, q.z as f64, q.w as f64)
+                            .normalize(),
+                    )
+                })
+                .unwrap_or(DQuat::IDENTITY)
+        };
+        let cardinal = match view.text(p, "physics:axis").as_deref() {
+            Some("Y") => DVec3::Y,
+            Some("Z") => DVec3::Z,
+            _ => DVec3::X,
+        };
+        joints_read.push(JointRead {
+            path: p.clone(),
+            name: unique(&mut joint_used, &urdf_name(view, p)),
+            usd_type: ty,
+            body0,
+            body1,
+            frame1: (
+                local("physics:localPos1").unwrap_or(DVec3::ZERO),
+                local_rot("physics:localRot1"),
+            ),
+            axis: conv.dir_d(cardinal).normalize(),
+        });
+    }
+
+    // ── Tree ──────────────────────────────────────────────────────────────
+    let mut incoming: HashMap<&str, &JointRead> = HashMap::new();
+    for j in &joints_read {
+        if incoming.insert(j.body1.as_str(), j).is_some() {
+            return Err(UrdfError::NotATree(format!(
+                "`{}` is moved by more than one joint",
+                j.body1
+            )));
+        }
+    }
+    let roots: Vec<&str> = bodies
+        .iter()
+        .map(|p| p.as_str())
+        .filter(|b| !incoming.contains_key(b))
+        .collect();
+    let world_anchored = joints_read.iter().any(|j| j.body0.is_empty());
+    if roots.is_empty() {
+        return Err(UrdfError::NotATree(
+            "every body is moved by a joint — the vessel is a closed loop".into(),
+        ));
+    }
+    // URDF has one root. Several free bodies (or a joint to the world) hang off
+    // a `world` frame, the free ones through `floating` joints.
+    let use_world = world_anchored || roots.len() > 1;
+
+    // Each link's URDF frame: the body's own frame, moved — for a jointed
+    // child — onto the joint anchor, since URDF puts a child link's origin at
+    // its joint. The ROTATION stays the body's: URDF states the axis in the
+    // link frame, so there is no need to turn the link onto the joint's X, and
+    // keeping it means an imported robot exports with its original frames.
+    let mut link_frame: HashMap<String, Frame> = HashMap::new();
+    for p in &bodies {
+        let key = p.to_string();
+        let w = world(p)?;
+        let f = match incoming.get(key.as_str()) {
+            Some(j) => w.then(j.frame1.0, DQuat::IDENTITY),
+            None => w,
+        };
+        link_frame.insert(key, f);
+    }
+
+    // Cycles: every body must reach a root by following its incoming joint.
+    for p in &bodies {
+        let mut cur = p.as_str();
+        let mut steps = 0;
+        while let Some(j) = incoming.get(cur) {
+            if j.body0.is_empty() {
+                break;
+            }
+            cur = j.body0.as_str();
+            steps += 1;
+            if steps > bodies.len() {
+                return Err(UrdfError::NotATree(format!(
+                    "`{p}` is part of a closed kinematic loop"
+                )));
+            }
+        }
+    }
+
+    // ── Links ─────────────────────────────────────────────────────────────
+    let mut links = Vec::new();
+    if use_world {
+        links.push(UrdfLink {
+            name: WORLD_LINK.to_string(),
+            ..Default::default()
+        });
+    }
+    for p in &bodies {
+        let key = p.to_string();
+        let frame = link_frame[&key];
+        let body = world(p)?;
+        let name = link_name[&key].clone();
+        let inertial = inertial(view, p, &conv, frame, body, &to_urdf);
+        let (visuals, collisions) = shapes(
+            view,
+            p,
+            &body_set,
+            &conv,
+            frame,
+            &to_urdf,
+            &name,
+            &mut warnings,
+        )?;
+        links.push(UrdfLink {
+            name,
+            inertial,
+            visuals,
+            collisions,
+        });
+    }
+
+    // ── Joints ────────────────────────────────────────────────────────────
+    let mut joints = Vec::new();
+    let frame_of = |body: &str| -> Frame {
+        if body.is_empty() {
+            Frame::IDENTITY
+        } else {
+            link_frame[body]
+        }
+    };
+    let name_of = |body: &str| -> String {
+        if body.is_empty() {
+            WORLD_LINK.to_string()
+        } else {
+            link_name[body].clone()
+        }
+    };
+    for j in &joints_read {
+        let parent = frame_of(&j.body0);
+        let child = link_frame[&j.body1];
+        let real = |name: &str| view.real(&j.path, name);
+        let limits = (
+            real("physics:lowerLimit").filter(|v| v.is_finite()),
+            real("physics:upperLimit").filter(|v| v.is_finite()),
+        );
+        let urdf_extra = (real("lunco:urdf:effort"), real("lunco:urdf:velocity"));
+        let (kind, limit) = match j.usd_type.as_str() {
+            "PhysicsRevoluteJoint" => match limits {
+                (Some(lo), Some(hi)) => (
+                    UrdfJointKind::Revolute,
+                    Some(UrdfLimit {
+                        lower: lo.to_radians(),
+                        upper: hi.to_radians(),
+                        effort: urdf_extra.0.unwrap_or(0.0),
+                        velocity: urdf_extra.1.unwrap_or(0.0),
+                    }),
+                ),
+                (None, None) => (
+                    UrdfJointKind::Continuous,
+                    (urdf_extra.0.is_some() || urdf_extra.1.is_some()).then(|| UrdfLimit {
+                        effort: urdf_extra.0.unwrap_or(0.0),
+                        velocity: urdf_extra.1.unwrap_or(0.0),
+                        ..Default::default()
+                    }),
+                ),
+                _ => {
+                    warnings.push(format!(
+                        "`{}` is limited on one side only; URDF cannot say that, so it is exported continuous",
+                        j.path
+                    ));
+                    (UrdfJointKind::Continuous, None)
+                }
+            },
+            "PhysicsPrismaticJoint" => {
+                let limit = match limits {
+                    (Some(lo), Some(hi)) => Some(UrdfLimit {
+                        lower: conv.length(lo),
+                        upper: conv.length(hi),
+                        effort: urdf_extra.0.unwrap_or(0.0),
+                        velocity: urdf_extra.1.unwrap_or(0.0),
+                    }),
+                    _ => {
+                        warnings.push(format!(
+                            "`{}` is an unlimited slider; URDF requires prismatic limits, none were written",
+                            j.path
+                        ));
+                        None
+                    }
+                };
+                (UrdfJointKind::Prismatic, limit)
+            }
+            _ => (UrdfJointKind::Fixed, None),
+        };
+        let dynamics = match (real("lunco:urdf:damping"), real("lunco:urdf:friction")) {
+            (None, None) => None,
+            (d, f) => Some(UrdfDynamics {
+                damping: d.unwrap_or(0.0),
+                friction: f.unwrap_or(0.0),
+            }),
+        };
+        joints.push(UrdfJoint {
+            name: j.name.clone(),
+            kind,
+            parent: name_of(&j.body0),
+            child: name_of(&j.body1),
+            origin: to_urdf.pose(parent.relative(child)),
+            axis: to_urdf.dir(j.frame1.1 * j.axis).normalize(),
+            limit,
+            mimic: None,
+            dynamics,
+        });
+    }
+    if use_world {
+        for r in &roots {
+            let name = link_name[*r].clone();
+            joints.push(UrdfJoint {
+                name: unique(&mut joint_used, &format!("{name}_floating")),
+                kind: UrdfJointKind::Floating,
+                parent: WORLD_LINK.to_string(),
+                child: name,
+                origin: to_urdf.pose(link_frame[*r]),
+                axis: DVec3::X,
+                limit: None,
+                mimic: None,
+                dynamics: None,
+            });
+        }
+    }
+
+    // ── Gears → mimics ────────────────────────────────────────────────────
+    let joint_index: HashMap<String, usize> = joints_read
+        .iter()
+        .enumerate()
+        .map(|(i, j)| (j.path.to_string(), i))
+        .collect();
+    for g in &gears {
+        let hinge = |rel: &str| {
+            view.rel_target(g, rel)
+                .and_then(|t| joint_index.get(&t).copied())
+        };
+        let (Some(a), Some(b)) = (
+            hinge("physxGearJoint:hinge0"),
+            hinge("physxGearJoint:hinge1"),
+        ) else {
+            warnings.push(format!(
+                "gear `{g}` does not couple two joints of this vessel; skipped"
+            ));
+            continue;
+        };
+        let ratio = view.real(g, "physxGearJoint:gearRatio").unwrap_or(1.0);
+        let offset = view
+            .real(g, "drive:angular:physics:targetPosition")
+            .unwrap_or(0.0);
+        if joints[a].mimic.is_some() {
+            warnings.push(format!(
+                "`{}` is geared twice; only the first gear is exported as its mimic",
+                joints[a].name
+            ));
+            continue;
+        }
+        joints[a].mimic = Some(UrdfMimic {
+            joint: joints[b].name.clone(),
+            multiplier: ratio,
+            offset,
+        });
+    }
+
+    Ok((
+        UrdfRobot {
+            name: urdf_name(view, root),
+            links,
+            joints,
+        },
+        warnings,
+    ))
+}
+
+/// Active descendants of `root`, depth first, `root` included.
+fn descendants(view: &StageView<'_>, root: &SdfPath, out: &mut Vec<SdfPath>) {
+    if !view.is_active(root) {
+        return;
+    }
+    out.push(root.clone());
+    for c in view.children(root) {
+        descendants(view, &c, out);
+    }
+}
+
+/// The URDF name an import recorded (`lunco:urdf:name`), else the prim name.
+fn urdf_name(view: &StageView<'_>, p: &SdfPath) -> String {
+    view.text(p, "lunco:urdf:name")
+        .unwrap_or_else(|| leaf(p).to_string())
+}
+
+fn leaf(p: &SdfPath) -> &str {
+    p.as_str().rsplit('/').next().unwrap_or_default()
+}
+
+fn unique(used: &mut HashSet<String>, base: &str) -> String {
+    let mut candidate = base.to_string();
+    let mut n = 2;
+    while !used.insert(candidate.clone()) {
+        candidate = format!("{base}_{n}");
+        n += 1;
+    }
+    candidate
+}
+
+/// The body's `PhysicsMassAPI`, re-expressed about the link frame. `None` when
+/// no mass is authored — URDF then reads the link as massless, which is also
+/// what an unauthored USD mass defers to the colliders for.
+fn inertial(
+    view: &StageView<'_>,
+    p: &SdfPath,
+    conv: &ConventionTransform,
+    frame: Frame,
+    body: Frame,
+    to_urdf: &ToUrdf,
+) -> Option<UrdfInertial> {
+    let mass = view.real(p, "physics:mass").filter(|m| m.is_finite())?;
+    let vec3 = |name: &str| -> Option<DVec3> {
+        view.value::<[f32; 3]>(p, name)
+            .map(|v| DVec3::new(v[0] as f64, v[1] as f64, v[2] as f64))
+    };
+    let com = vec3("physics:centerOfMass")
+        .map(|c| conv.point_d(c))
+        .unwrap_or(DVec3::ZERO);
+    // The tensor is built in the STAGE's body basis, then the basis changes as
+    // a whole — the diagonal alone cannot be converted, its axes move with it.
+    let diag = vec3("physics:diagonalInertia").unwrap_or(DVec3::ZERO);
+    let axes = view
+        .value::<openusd::gf::Quatf>(p, "physics:principalAxes")
+        .map(|q| DQuat::from_xyzw(q.x as f64, q.y as f64, q.z as f64, q.w as f64).normalize())
+        .unwrap_or(DQuat::IDENTITY);
+    let basis = DMat3::from_cols(
+        conv.dir_d(DVec3::X),
+        conv.dir_d(DVec3::Y),
+        conv.dir_d(DVec3::Z),
+    );
+    let k = conv.length(1.0);
+    let tensor_body = basis * inertia_tensor(diag, axes) * basis.transpose() * (k * k);
+
+    let rel = frame.relative(body);
+    let r = DMat3::from_quat(rel.rot);
+    let tensor_link = to_urdf.tensor(r * tensor_body * r.transpose());
+    let com_link = to_urdf.pose(rel.then(com, DQuat::IDENTITY)).xyz;
+    let t = tensor_link;
+    Some(UrdfInertial {
+        origin: UrdfPose {
+            xyz: com_link,
+            rpy: DVec3::ZERO,
+        },
+        mass,
+        inertia: [
+            t.x_axis.x, t.y_axis.x, t.z_axis.x, t.y_axis.y, t.z_axis.y, t.z_axis.z,
+        ],
+    })
+}
+
+/// The body's geometry as `(visuals, collisions)`. Walks the body's subtree
+/// without descending into nested bodies (their geometry is theirs), and
+/// classifies by the same `purpose` rule the collider builder applies: a
+/// `proxy` collider displaces every `render` one, `guide` is neither.
+#[allow(clippy::too_many_arguments)]
+fn shapes(
+    view: &StageView<'_>,
+    body: &SdfPath,
+    bodies: &HashSet<String>,
+    conv: &ConventionTransform,
+    frame: Frame,
+    to_urdf: &ToUrdf,
+    link: &str,
+    warnings: &mut Vec<String>,
+) -> Result<(Vec<UrdfShape>, Vec<UrdfShape>), UrdfError> {
+    use lunco_usd_bevy::{effective_purpose, Purpose};
+
+    let mut prims = Vec::new();
+    let mut stack = vec![body.clone()];
+    while let Some(p) = stack.pop() {
+        if p != *body && bodies.contains(&p.to_string()) {
+            continue;
+        }
+        if !view.is_active(&p) {
+            continue;
+        }
+        prims.push(p.clone());
+        stack.extend(view.children(&p).into_iter().rev());
+    }
+    let collides = |p: &SdfPath| view.has_api_schema(p, "PhysicsCollisionAPI");
+    let has_proxy = prims
+        .iter()
+        .any(|p| collides(p) && effective_purpose(view, p) == Purpose::Proxy);
+
+    let mut visuals = Vec::new();
+    let mut collisions = Vec::new();
+    for p in &prims {
+        let ty = view.type_name(p).unwrap_or_default();
+        let mesh = view.binary_asset_uri(p);
+        if mesh.is_none() && !matches!(ty.as_str(), "Cube" | "Sphere" | "Cylinder") {
+            if matches!(
+                ty.as_str(),
+                "Capsule" | "Cone" | "Mesh" | "Plane" | "NurbsPatch"
+            ) {
+                warnings.push(format!(
+                    "link `{link}`: `{p}` is a {ty}, which URDF has no primitive for; skipped"
+                ));
+            }
+            continue;
+        }
+        let purpose = effective_purpose(view, p);
+        if purpose == Purpose::Guide {
+            continue;
+        }
+        let collision = collides(p) && !(has_proxy && purpose == Purpose::Render);
+        let visual = purpose != Purpose::Proxy && !invisible(view, p, body);
+        if !collision && !visual {
+            continue;
+        }
+
+        let t = world_transform(view, p).map_err(|e| UrdfError::Invalid(e.to_string()))?;
+        let g = Frame {
+            pos: t.translation.as_dvec3(),
+            rot: t.rotation.as_dquat().normalize(),
+        };
+        let scale = t.scale.as_dvec3().abs();
+        let mut rel = frame.relative(g);
+        let real = |name: &str, default: f64| view.real(p, name).unwrap_or(default);
+        let geometry = if let Some(uri) = mesh {
+            UrdfGeometry::Mesh {
+                filename: uri,
+                scale: to_urdf.conv.stage_scale_vec_d(scale),
+            }
+        } else {
+            match ty.as_str() {
+                // USD's fallbacks: a 2-unit cube, a unit sphere, a 2-tall Z cylinder.
+                "Cube" => UrdfGeometry::Box {
+                    size: to_urdf
+                        .conv
+                        .stage_scale_vec_d(scale * conv.length(real("size", 2.0))),
+                },
+                "Sphere" => UrdfGeometry::Sphere {
+                    radius: conv.length(real("radius", 1.0)) * scale.max_element(),
+                },
+                _ => {
+                    let axis = match view.text(p, "axis").as_deref() {
+                        Some("X") => DVec3::X,
+                        Some("Y") => DVec3::Y,
+                        _ => DVec3::Z,
+                    };
+                    let axis_c = conv.dir_d(axis).normalize();
+                    let along = (scale * axis_c.abs()).element_sum();
+                    let across = (scale * (DVec3::ONE - axis_c.abs())).max_element();
+                    // URDF cylinders run along their own Z; turn the frame so
+                    // the USD axis lands there.
+                    rel = rel.then(
+                        DVec3::ZERO,
+                        DQuat::from_rotation_arc(to_urdf.basis.inverse() * DVec3::Z, axis_c),
+                    );
+                    UrdfGeometry::Cylinder {
+                        radius: conv.length(real("radius", 1.0)) * across,
+                        length: conv.length(real("height", 2.0)) * along,
+                    }
+                }
+            }
+        };
+        let material = visual.then(|| display_material(view, p, link)).flatten();
+        let shape = UrdfShape {
+            name: Some(leaf(p).to_string()),
+            origin: to_urdf.pose(rel),
+            geometry,
+            material,
+        };
+        if collision {
+            collisions.push(UrdfShape {
+                material: None,
+                ..shape.clone()
+            });
+        }
+        if visual {
+            visuals.push(shape);
+        }
+    }
+    Ok((visuals, collisions))
+}
+
+/// `visibility = "invisible"` on the prim or any ancestor up to its body.
+fn invisible(view: &StageView<'_>, p: &SdfPath, body: &SdfPath) -> bool {
+    let mut cur = Some(p.clone());
+    while let Some(c) = cur {
+        if view.text(&c, "visibility").as_deref() == Some("invisible") {
+            return true;
+        }
+        if c == *body {
+            break;
+        }
+        cur = c.parent();
+    }
+    false
+}
+
+/// The flat colour a viewer without our materials would draw: the first
+/// `primvars:displayColor`, with `displayOpacity` as alpha.
+fn display_material(view: &StageView<'_>, p: &SdfPath, link: &str) -> Option<UrdfMaterial> {
+    let [r, g, b] = *view.points3(p, "primvars:displayColor").first()?;
+    let a = view
+        .reals(p, "primvars:displayOpacity")
+        .first()
+        .copied()
+        .unwrap_or(1.0);
+    Some(UrdfMaterial {
+        name: format!("{link}_{}", leaf(p)),
+        color: Some([r as f64, g as f64, b as f64, a]),
+        texture: None,
+    })
+}
@@ -0,0 +1,517 @@
+//! [`UrdfRobot`] → a USDA layer (see the mapping table in [`super`]).
+
+use super::{
+    principal_axes, UrdfError, UrdfGeometry, UrdfJointKind, UrdfLink, UrdfRobot, UrdfShape,
+    WORLD_LINK,
+};
+use bevy::math::{DQuat, DVec3};
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write as _;
+
+/// Stiffness of the gear that realises a `<mimic>`. A mimic is a rigid
+/// relation, so it is authored as stiff as the rocker-bogie differential
+/// (`rocker_bogie.usda`), the one other gear the scenes carry.
+const MIMIC_STIFFNESS: f64 = 100_000.0;
+
+/// How [`import_urdf`] resolves what the URDF only names.
+#[derive(Debug, Clone, Default)]
+pub struct UrdfImportOptions {
+    /// Directory a `package://<pkg>/…` mesh URI resolves under, as
+    /// `<package_root>/<pkg>/…`. Relative paths are relative to wherever the
+    /// layer is saved. `None` keeps the URI verbatim for a resolver that
+    /// understands ROS packages.
+    pub package_root: Option<String>,
+}
+
+/// The converted layer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrdfImport {
+    /// The `.usda` text. `defaultPrim` is the robot, so the layer can be
+    /// referenced into a scene without naming a prim.
+    pub usda: String,
+    /// Absolute path of the robot root prim in that layer (`/<robot>`).
+    pub root: String,
+    /// What the conversion could not carry across (unsupported joint kinds,
+    /// mimics it cannot gear, textures). Empty on a lossless import.
+    pub warnings: Vec<String>,
+}
+
+/// Parse `xml` and convert it in one step.
+pub fn import_urdf(xml: &str, options: &UrdfImportOptions) -> Result<UrdfImport, UrdfError> {
+    urdf_to_usda(&super::parse_urdf(xml)?, options)
+}
+
+/// Convert a parsed robot into a Z-up, metre USDA layer.
+pub fn urdf_to_usda(
+    robot: &UrdfRobot,
+    options: &UrdfImportOptions,
+) -> Result<UrdfImport, UrdfError> {
+    let mut warnings = Vec::new();
+    let root_name = prim_name(&robot.name);
+    let root = format!("/{root_name}");
+
+    // A frame-only root named `world` is ROS's fixed world frame, not a body:
+    // joints hanging off it anchor to the world (an empty `physics:body0`).
+    let world_is_frame = robot
+        .link(WORLD_LINK)
+        .is_none_or(|l| l.is_frame_only() && robot.parent_joint(WORLD_LINK).is_none());
+
+    // Sibling prim names: links and the `Joints` scope share the robot root.
+    let mut names = Names::default();
+    names.reserve("Joints");
+    let mut link_prim: HashMap<&str, String> = HashMap::new();
+    for link in &robot.links {
+        if link.name == WORLD_LINK && world_is_frame {
+            continue;
+        }
+        link_prim.insert(&link.name, names.take(&link.name));
+    }
+
+    let mut poses: HashMap<&str, (DVec3, DQuat)> = HashMap::new();
+    for link in &robot.links {
+        world_pose(robot, &link.name, &mut poses);
+    }
+
+    let mut out = String::new();
+    let _ = write!(
+        out,
+        "#usda 1.0\n(\n    defaultPrim = \"{root_name}\"\n    upAxis = \"Z\"\n    metersPerUnit = 1\n)\n\n"
+    );
+    let _ = writeln!(out, "def Xform \"{root_name}\" (");
+    out.push_str("    kind = \"component\"\n");
+    let _ = writeln!(
+        out,
+        "    doc = \"{}\"",
+        usd_str(&format!("Imported from the URDF robot `{}`.", robot.name))
+    );
+    out.push_str(")\n{\n");
+    if root_name != robot.name {
+        let _ = writeln!(
+            out,
+            "    custom string lunco:urdf:name = \"{}\"",
+            usd_str(&robot.name)
+        );
+    }
+
+    for link in &robot.links {
+        let Some(prim) = link_prim.get(link.name.as_str()) else {
+            continue;
+        };
+        let (pos, rot) = poses[link.name.as_str()];
+        write_link(&mut out, link, prim, pos, rot, options, &mut warnings);
+    }
+
+    out.push_str("\n    def Scope \"Joints\"\n    {\n");
+    let mut joint_prim: HashMap<&str, String> = HashMap::new();
+    let mut joint_names = Names::default();
+    for j in &robot.joints {
+        joint_prim.insert(&j.name, joint_names.take(&j.name));
+    }
+    for j in &robot.joints {
+        let usd_type = match j.kind {
+            UrdfJointKind::Revolute | UrdfJointKind::Continuous => "PhysicsRevoluteJoint",
+            UrdfJointKind::Prismatic => "PhysicsPrismaticJoint",
+            UrdfJointKind::Fixed => "PhysicsFixedJoint",
+            UrdfJointKind::Floating | UrdfJointKind::Planar => {
+                warnings.push(format!(
+                    "joint `{}` is {}, which UsdPhysics cannot express; `{}` is left unjointed",
+                    j.name,
+                    j.kind.as_str(),
+                    j.child
+                ));
+                continue;
+            }
+        };
+        let prim = &joint_prim[j.name.as_str()];
+        let _ = writeln!(out, "        def {usd_type} \"{prim}\"\n        {{");
+        if *prim != j.name {
+            let _ = writeln!(
+                out,
+                "            custom string lunco:urdf:name = \"{}\"",
+                usd_str(&j.name)
+            );
+        }
+        if let Some(parent) = link_prim.get(j.parent.as_str()) {
+            let _ = writeln!(out, "            rel physics:body0 = <{root}/{parent}>");
+        }
+        let child = link_prim.get(j.child.as_str()).ok_or_else(|| {
+            UrdfError::NotATree(format!(
+                "joint `{}` has the world frame as its child",
+                j.name
+            ))
+        })?;
+        let _ = writeln!(out, "            rel physics:body1 = <{root}/{child}>");
+
+        // The joint frame IS the child's frame at zero (URDF); its X is turned
+        // onto the URDF axis so `physics:axis` can stay cardinal.
+        let align = if j.kind == UrdfJointKind::Fixed {
+            DQuat::IDENTITY
+        } else {
+            DQuat::from_rotation_arc(DVec3::X, j.axis)
+        };
+        if j.kind != UrdfJointKind::Fixed {
+            out.push_str("            uniform token physics:axis = \"X\"\n");
+        }
+        let _ = writeln!(
+            out,
+            "            point3f physics:localPos0 = {}",
+            tuple(j.origin.xyz)
+        );
+        let _ = writeln!(
+            out,
+            "            quatf physics:localRot0 = {}",
+            quat(j.origin.rotation() * align)
+        );
+        out.push_str("            point3f physics:localPos1 = (0, 0, 0)\n");
+        let _ = writeln!(out, "            quatf physics:localRot1 = {}", quat(align));
+
+        if let Some(l) = &j.limit {
+            match j.kind {
+                UrdfJointKind::Revolute => {
+                    let _ = writeln!(
+                        out,
+                        "            float physics:lowerLimit = {}\n            float physics:upperLimit = {}",
+                        l.lower.to_degrees(),
+                        l.upper.to_degrees()
+                    );
+                }
+                UrdfJointKind::Prismatic => {
+                    let _ = writeln!(
+                        out,
+                        "            float physics:lowerLimit = {}\n            float physics:upperLimit = {}",
+                        l.lower, l.upper
+                    );
+                }
+                _ => {}
+            }
+            let _ = writeln!(
+                out,
+                "            custom double lunco:urdf:effort = {}\n            custom double lunco:urdf:velocity = {}",
+                l.effort, l.velocity
+            );
+        }
+        if let Some(d) = &j.dynamics {
+            let _ = writeln!(
+                out,
+                "            custom double lunco:urdf:damping = {}\n            custom double lunco:urdf:friction = {}",
+                d.damping, d.friction
+            );
+        }
+        out.push_str("        }\n");
+    }
+
+    // Mimics, after every hinge exists so the gear can name both.
+    for j in &robot.joints {
+        let Some(m) = &j.mimic else { continue };
+        let Some(leader) = robot.joints.iter().find(|o| o.name == m.joint) else {
+            continue;
+        };
+        let why_not = if !j.kind.is_angular() || !leader.kind.is_angular() {
+            Some("only revolute joints can be geared")
+        } else if j.parent != leader.parent {
+            Some("the two joints turn against different links")
+        } else if m.multiplier == 0.0 {
+            Some("a zero multiplier is a lock, not a gear")
+        } else {
+            None
+        };
+        if let Some(why) = why_not {
+            warnings.push(format!(
+                "mimic `{}` → `{}` was not imported: {why}",
+                j.name, m.joint
+            ));
+            continue;
+        }
+        let prim = joint_names.take(&format!("{}_mimic", j.name));
+        let _ = write!(
+            out,
+            "        def PhysxPhysicsGearJoint \"{prim}\" (\n            prepend apiSchemas = [\"PhysicsDriveAPI:angular\"]\n        )\n        {{\n"
+        );
+        let _ = writeln!(
+            out,
+            "            rel physxGearJoint:hinge0 = <{root}/Joints/{}>",
+            joint_prim[j.name.as_str()]
+        );
+        let _ = writeln!(
+            out,
+            "            rel physxGearJoint:hinge1 = <{root}/Joints/{}>",
+            joint_prim[leader.name.as_str()]
+        );
+        let _ = writeln!(
+            out,
+            "            float physxGearJoint:gearRatio = {}",
+            m.multiplier
+        );
+        let _ = writeln!(
+            out,
+            "            float drive:angular:physics:targetPosition = {}",
+            m.offset
+        );
+        let _ = writeln!(
+            out,
+            "            float drive:angular:physics:stiffness = {MIMIC_STIFFNESS}"
+        );
+        out.push_str("        }\n");
+    }
+    out.push_str("    }\n}\n");
+
+    Ok(UrdfImport {
+        usda: out,
+        root,
+        warnings,
+    })
+}
+
+/// The zero-configuration world pose of `link`: the composition of every joint
+/// origin from its root. A root link sits at the robot origin.
+fn world_pose<'a>(
+    robot: &'a UrdfRobot,
+    link: &'a str,
+    memo: &mut HashMap<&'a str, (DVec3, DQuat)>,
+) -> (DVec3, DQuat) {
+    if let Some(p) = memo.get(link) {
+        return *p;
+    }
+    let pose = match robot.parent_joint(link) {
+        // `parse_urdf` refused cycles, so this recursion terminates.
+        Some(j) => {
+            let (pp, pr) = world_pose(robot, &j.parent, memo);
+            (
+                pp + pr * j.origin.xyz,
+                (pr * j.origin.rotation()).normalize(),
+            )
+        }
+        None => (DVec3::ZERO, DQuat::IDENTITY),
+    };
+    memo.insert(link, pose);
+    pose
+}
+
+fn write_link(
+    out: &mut String,
+    link: &UrdfLink,
+    prim: &str,
+    pos: DVec3,
+    rot: DQuat,
+    options: &UrdfImportOptions,
+    warnings: &mut Vec<String>,
+) {
+    let schemas = if link.inertial.is_some() {
+        "\"PhysicsRigidBodyAPI\", \"PhysicsMassAPI\""
+    } else {
+        "\"PhysicsRigidBodyAPI\""
+    };
+    let _ = write!(
+        out,
+        "\n    def Xform \"{prim}\" (\n        prepend apiSchemas = [{schemas}]\n    )\n    {{\n"
+    );
+    if prim != link.name {
+        let _ = writeln!(
+            out,
+            "        custom string lunco:urdf:name = \"{}\"",
+            usd_str(&link.name)
+        );
+    }
+    xform(out, 8, pos, rot, None);
+    if let Some(i) = &link.inertial {
+        // URDF gives the tensor in the inertial frame; UsdPhysics wants the
+        // principal moments plus the rotation of the principal frame in the body.
+        let (diag, axes) = principal_axes(i.tensor());
+        let axes = (i.origin.rotation() * axes).normalize();
+        let _ = writeln!(out, "        float physics:mass = {}", i.mass);
+        let _ = writeln!(
+            out,
+            "        point3f physics:centerOfMass = {}",
+            tuple(i.origin.xyz)
+        );
+        let _ = writeln!(
+            out,
+            "        float3 physics:diagonalInertia = {}",
+            tuple(diag)
+        );
+        if !axes.abs_diff_eq(DQuat::IDENTITY, 1e-12) {
+            let _ = writeln!(out, "        quatf physics:principalAxes = {}", quat(axes));
+        }
+    }
+    let mut names = Names::default();
+    for (i, v) in link.visuals.iter().enumerate() {
+        let name = names.take(v.name.as_deref().unwrap_or(&format!("visual_{i}")));
+        write_shape(out, &name, v, false, options, warnings, &link.name);
+    }
+    for (i, c) in link.collisions.iter().enumerate() {
+        let name = names.take(c.name.as_deref().unwrap_or(&format!("collision_{i}")));
+        write_shape(out, &name, c, true, options, warnings, &link.name);
+    }
+    out.push_str("    }\n");
+}
+
+fn write_shape(
+    out: &mut String,
+    prim: &str,
+    shape: &UrdfShape,
+    collision: bool,
+    options: &UrdfImportOptions,
+    warnings: &mut Vec<String>,
+    link: &str,
+) {
+    let (usd_type, mesh) = match &shape.geometry {
+        UrdfGeometry::Box { .. } => ("Cube", None),
+        UrdfGeometry::Cylinder { .. } => ("Cylinder", None),
+        UrdfGeometry::Sphere { .. } => ("Sphere", None),
+        UrdfGeometry::Mesh { filename, .. } => ("Xform", Some(mesh_uri(filename, options))),
+    };
+    let mut meta = Vec::new();
+    if collision {
+        meta.push(if mesh.is_some() {
+            "prepend apiSchemas = [\"PhysicsCollisionAPI\", \"PhysicsMeshCollisionAPI\"]"
+                .to_string()
+        } else {
+            "prepend apiSchemas = [\"PhysicsCollisionAPI\"]".to_string()
+        });
+    }
+    if let Some(uri) = &mesh {
+        meta.push(format!("prepend references = @{uri}@"));
+    }
+    if meta.is_empty() {
+        let _ = writeln!(out, "        def {usd_type} \"{prim}\"\n        {{");
+    } else {
+        let _ = writeln!(out, "        def {usd_type} \"{prim}\" (");
+        for m in &meta {
+            let _ = writeln!(out, "            {m}");
+        }
+        out.push_str("        )\n        {\n");
+    }
+    if collision {
+        // Collision geometry is the physics shape; the `<visual>` is what is seen.
+        out.push_str("            token visibility = \"invisible\"\n");
+    }
+    let scale = match &shape.geometry {
+        UrdfGeometry::Box { size } => {
+            out.push_str("            double size = 1\n");
+            Some(*size)
+        }
+        UrdfGeometry::Cylinder { radius, length } => {
+            let _ = writeln!(
+                out,
+                "            double radius = {radius}\n            double height = {length}\n            uniform token axis = \"Z\""
+            );
+            None
+        }
+        UrdfGeometry::Sphere { radius } => {
+            let _ = writeln!(out, "            double radius = {radius}");
+            None
+        }
+        UrdfGeometry::Mesh { scale, .. } => {
+            if collision {
+                out.push_str("            uniform token physics:approximation = \"convexHull\"\n");
+            }
+            (*scale != DVec3::ONE).then_some(*scale)
+        }
+    };
+    xform(out, 12, shape.origin.xyz, shape.origin.rotation(), scale);
+    if let Some(m) = &shape.material {
+        if let Some([r, g, b, a]) = m.color {
+            let _ = writeln!(
+                out,
+                "            color3f[] primvars:displayColor = [({r}, {g}, {b})]"
+            );
+            if a < 1.0 {
+                let _ = writeln!(out, "            float[] primvars:displayOpacity = [{a}]");
+            }
+        }
+        if m.texture.is_some() {
+            warnings.push(format!(
+                "link `{link}`: the texture on material `{}` was not imported (colour only)",
+                m.name
+            ));
+        }
+    }
+    out.push_str("        }\n");
+}
+
+/// `package://pkg/…` → `<package_root>/pkg/…`; `file://` is stripped.
+fn mesh_uri(filename: &str, options: &UrdfImportOptions) -> String {
+    if let Some(rest) = filename.strip_prefix("package://") {
+        return match &options.package_root {
+            Some(root) => format!("{}/{rest}", root.trim_end_matches('/')),
+            None => filename.to_string(),
+        };
+    }
+    filename
+        .strip_prefix("file://")
+        .unwrap_or(filename)
+        .to_string()
+}
+
+fn xform(out: &mut String, indent: usize, pos: DVec3, rot: DQuat, scale: Option<DVec3>) {
+    let mut order = Vec::new();
+    let pad = " ".repeat(indent);
+    if pos != DVec3::ZERO {
+        let _ = writeln!(out, "{pad}double3 xformOp:translate = {}", tuple(pos));
+        order.push("\"xformOp:translate\"");
+    }
+    if !rot.abs_diff_eq(DQuat::IDENTITY, 1e-12) {
+        let _ = writeln!(out, "{pad}quatd xformOp:orient = {}", quat(rot));
+        order.push("\"xformOp:orient\"");
+    }
+    if let Some(s) = scale {
+        let _ = writeln!(out, "{pad}double3 xformOp:scale = {}", tuple(s));
+        order.push("\"xformOp:scale\"");
+    }
+    if !order.is_empty() {
+        let _ = writeln!(
+            out,
+            "{pad}uniform token[] xformOpOrder = [{}]",
+            order.join(", ")
+        );
+    }
+}
+
+fn tuple(v: DVec3) -> String {
+    format!("({}, {}, {})", v.x, v.y, v.z)
+}
+
+/// USD spells a quaternion `(w, x, y, z)`.
+fn quat(q: DQuat) -> String {
+    let q = q.normalize();
+    format!("({}, {}, {}, {})", q.w, q.x, q.y, q.z)
+}
+
+fn usd_str(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+/// A USD prim name for `name`: identifier characters only, not starting with a
+/// digit. URDF names are free text (`left-wheel.link` is common).
+fn prim_name(name: &str) -> String {
+    let mut s: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if s.is_empty() || s.starts_with(|c: char| c.is_ascii_digit()) {
+        s.insert(0, '_');
+    }
+    s
+}
+
+/// Sibling prim names, de-duplicated after sanitising (`a-b` and `a.b` both
+/// sanitise to `a_b`).
+#[derive(Default)]
+struct Names(HashSet<String>);
+
+impl Names {
+    fn reserve(&mut self, name: &str) {
+        self.0.insert(name.to_string());
+    }
+
+    fn take(&mut self, name: &str) -> String {
+        let base = prim_name(name);
+        let mut candidate = base.clone();
+        let mut n = 2;
+        while !self.0.insert(candidate.clone()) {
+            candidate = format!("{base}_{n}");
+            n += 1;
+        }
+        candidate
+    }
+}
@@ -0,0 +1,394 @@
+//! URDF ⇄ UsdPhysics interchange (spec 017, user story 2).
+//!
+//! A URDF robot is an **import format**, not a second runtime description
+//! (doc 38 §8.7): [`import_urdf`] converts it ONCE into a USD layer that the
+//! existing bridges already understand, and from then on the robot is a USD
+//! asset like any other — referenced into a scene, overridden, journalled. The
+//! reverse direction, [`export_urdf`], walks a composed vessel and writes the
+//! URDF a ROS tool expects, so a robot can cross the boundary both ways.
+//!
+//! ## The mapping
+//!
+//! | URDF | USD |
+//! |---|---|
+//! | `<robot name>` | the layer's `defaultPrim`, an `Xform` |
+//! | `<link>` | `Xform` + `PhysicsRigidBodyAPI`, placed at its zero-configuration world pose |
+//! | `<inertial>` | `PhysicsMassAPI`: `physics:mass`, `centerOfMass`, `diagonalInertia`, `principalAxes` |
+//! | `<visual>` | a gprim (`Cube`/`Cylinder`/`Sphere`) or a mesh reference, `primvars:displayColor` |
+//! | `<collision>` | the same, plus `PhysicsCollisionAPI`, `visibility = "invisible"` |
+//! | `revolute` / `continuous` | `PhysicsRevoluteJoint` (limits in degrees; none for continuous) |
+//! | `prismatic` | `PhysicsPrismaticJoint` (limits in metres) |
+//! | `fixed` | `PhysicsFixedJoint` |
+//! | `<mimic>` | `PhysxPhysicsGearJoint` — `θ = multiplier·θ_mimicked + offset` |
+//! | root link `world` | an empty `physics:body0` (the joint anchors to the world) |
+//!
+//! Links are authored FLAT under the robot root, each at its world pose, with the
+//! joints in a `Joints` scope beside them — the layout `revolute_motor.usda` uses
+//! and the one the physics bridge reads without any hierarchy walk.
+//!
+//! ## Frames
+//!
+//! URDF is Z-up, X-forward, metres (REP-103). The imported layer says so
+//! (`upAxis = "Z"`, `metersPerUnit = 1`) and keeps every number in URDF's own
+//! frame; the USD spoke (`ConventionTransform`, doc 41) converts it on load like
+//! any other Z-up stage. Export runs the same spoke backwards. Nothing here
+//! rotates by hand.
+//!
+//! A UsdPhysics joint names a CARDINAL axis of its joint frame, while a URDF axis
+//! is any unit vector. The importer keeps `physics:axis = "X"` and rotates the
+//! joint frame (`physics:localRot0/1`) so its X lands on the URDF axis — the
+//! same device a raked landing leg uses.
+//!
+//! ## What does not cross
+//!
+//! `floating` and `planar` joints have no UsdPhysics counterpart; their links
+//! are imported unjointed and a warning says so. Joint `effort`/`velocity`
+//! limits and `<dynamics>` are URDF-only numbers (the bridge has no bearing
+//! friction, and `LunCoJointDampingAPI` is a rate, not N·m·s/rad); they are kept
+//! as `lunco:urdf:*` attributes so an export gives them back unchanged.
+
+mod export;
+mod import;
+mod parse;
+mod write;
+
+use bevy::math::{DMat3, DQuat, DVec3, EulerRot};
+
+pub use export::{export_urdf, robot_from_stage, UrdfExport};
+pub use import::{import_urdf, urdf_to_usda, UrdfImport, UrdfImportOptions};
+pub use parse::parse_urdf;
+pub use write::write_urdf;
+
+/// Why a URDF could not be read, converted or exported.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UrdfError {
+    /// The document is not well-formed XML.
+    Xml(String),
+    /// Well-formed XML that is not a valid URDF (missing `<robot>`, a joint
+    /// without a child, an unparsable number, …).
+    Invalid(String),
+    /// The links and joints do not form a tree: a link with two parents, a
+    /// cycle, or a joint naming a link that does not exist.
+    NotATree(String),
+}
+
+impl std::fmt::Display for UrdfError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Xml(msg) => write!(f, "URDF is not well-formed XML: {msg}"),
+            Self::Invalid(msg) => write!(f, "invalid URDF: {msg}"),
+            Self::NotATree(msg) => write!(f, "URDF kinematics are not a tree: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for UrdfError {}
+
+/// A parsed URDF `<robot>`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UrdfRobot {
+    pub name: String,
+    pub links: Vec<UrdfLink>,
+    pub joints: Vec<UrdfJoint>,
+}
+
+impl UrdfRobot {
+    /// The link named `name`, if any.
+    pub fn link(&self, name: &str) -> Option<&UrdfLink> {
+        self.links.iter().find(|l| l.name == name)
+    }
+
+    /// The joint whose child is `link`, if any.
+    pub fn parent_joint(&self, link: &str) -> Option<&UrdfJoint> {
+        self.joints.iter().find(|j| j.child == link)
+    }
+}
+
+/// A URDF `<origin xyz rpy>` — a rigid transform, rotation as fixed-axis
+/// roll-pitch-yaw (`R = Rz(yaw)·Ry(pitch)·Rx(roll)`).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UrdfPose {
+    pub xyz: DVec3,
+    pub rpy: DVec3,
+}
+
+impl UrdfPose {
+    pub const IDENTITY: Self = Self {
+        xyz: DVec3::ZERO,
+        rpy: DVec3::ZERO,
+    };
+
+    pub fn from_parts(xyz: DVec3, rotation: DQuat) -> Self {
+        let (yaw, pitch, roll) = rotation.normalize().to_euler(EulerRot::ZYX);
+        Self {
+            xyz,
+            rpy: DVec3::new(roll, pitch, yaw),
+        }
+    }
+
+    pub fn rotation(&self) -> DQuat {
+        DQuat::from_euler(EulerRot::ZYX, self.rpy.z, self.rpy.y, self.rpy.x)
+    }
+}
+
+/// A `<link>`.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UrdfLink {
+    pub name: String,
+    pub inertial: Option<UrdfInertial>,
+    pub visuals: Vec<UrdfShape>,
+    pub collisions: Vec<UrdfShape>,
+}
+
+impl UrdfLink {
+    /// A link with no mass and no geometry — a pure frame, like ROS's `world`.
+    pub fn is_frame_only(&self) -> bool {
+        self.inertial.is_none() && self.visuals.is_empty() && self.collisions.is_empty()
+    }
+}
+
+/// An `<inertial>`: mass and the inertia tensor about the centre of mass,
+/// expressed in the `origin` frame.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UrdfInertial {
+    pub origin: UrdfPose,
+    pub mass: f64,
+    /// `ixx, ixy, ixz, iyy, iyz, izz` (kg·m²).
+    pub inertia: [f64; 6],
+}
+
+impl UrdfInertial {
+    /// The symmetric tensor in the `origin` frame.
+    pub fn tensor(&self) -> DMat3 {
+        let [xx, xy, xz, yy, yz, zz] = self.inertia;
+        DMat3::from_cols(
+            DVec3::new(xx, xy, xz),
+            DVec3::new(xy, yy, yz),
+            DVec3::new(xz, yz, zz),
+        )
+    }
+}
+
+/// One `<visual>` or `<collision>` element. Collisions never carry a material.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrdfShape {
+    pub name: Option<String>,
+    pub origin: UrdfPose,
+    pub geometry: UrdfGeometry,
+    pub material: Option<UrdfMaterial>,
+}
+
+/// A `<geometry>` — the four URDF shape kinds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum UrdfGeometry {
+    Box {
+        size: DVec3,
+    },
+    /// Along the shape frame's Z.
+    Cylinder {
+        radius: f64,
+        length: f64,
+    },
+    Sphere {
+        radius: f64,
+    },
+    /// `filename` as authored (`package://…`, `file://…` or relative).
+    Mesh {
+        filename: String,
+        scale: DVec3,
+    },
+}
+
+/// A `<material>`, already resolved against the robot's named materials.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct UrdfMaterial {
+    pub name: String,
+    /// Linear `r, g, b, a`.
+    pub color: Option<[f64; 4]>,
+    pub texture: Option<String>,
+}
+
+/// The URDF joint `type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UrdfJointKind {
+    Revolute,
+    Continuous,
+    Prismatic,
+    Fixed,
+    Floating,
+    Planar,
+}
+
+impl UrdfJointKind {
+    pub fn parse(s: &str) -> Option<Self> {
+        Some(match s {
+            "revolute" => Self::Revolute,
+            "continuous" => Self::Continuous,
+            "prismatic" => Self::Prismatic,
+            "fixed" => Self::Fixed,
+            "floating" => Self::Floating,
+            "planar" => Self::Planar,
+            _ => return None,
+        })
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Revolute => "revolute",
+            Self::Continuous => "continuous",
+            Self::Prismatic => "prismatic",
+            Self::Fixed => "fixed",
+            Self::Floating => "floating",
+            Self::Planar => "planar",
+        }
+    }
+
+    /// Whether the joint's value is an angle (rad) rather than a length (m).
+    pub fn is_angular(self) -> bool {
+        matches!(self, Self::Revolute | Self::Continuous)
+    }
+}
+
+/// A `<limit>`. `lower`/`upper` are rad or m by joint kind.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UrdfLimit {
+    pub lower: f64,
+    pub upper: f64,
+    pub effort: f64,
+    pub velocity: f64,
+}
+
+/// A `<mimic>`: `value = multiplier · value(joint) + offset`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrdfMimic {
+    pub joint: String,
+    pub multiplier: f64,
+    pub offset: f64,
+}
+
+/// A `<dynamics>`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct UrdfDynamics {
+    pub damping: f64,
+    pub friction: f64,
+}
+
+/// A `<joint>`. `origin` places the child link's frame in the parent's at zero
+/// joint value; `axis` is a unit vector in that (joint = child) frame.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UrdfJoint {
+    pub name: String,
+    pub kind: UrdfJointKind,
+    pub parent: String,
+    pub child: String,
+    pub origin: UrdfPose,
+    pub axis: DVec3,
+    pub limit: Option<UrdfLimit>,
+    pub mimic: Option<UrdfMimic>,
+    pub dynamics: Option<UrdfDynamics>,
+}
+
+/// The root link name ROS uses for the fixed world frame.
+pub const WORLD_LINK: &str = "world";
+
+/// Diagonalise a symmetric inertia tensor: `(principal moments, principal axes)`
+/// with `tensor = R·diag·Rᵀ`, `R` a proper rotation. Cyclic Jacobi — a 3×3
+/// converges in a handful of sweeps.
+pub(crate) fn principal_axes(tensor: DMat3) -> (DVec3, DQuat) {
+    let mut a = [
+        [tensor.x_axis.x, tensor.y_axis.x, tensor.z_axis.x],
+        [tensor.x_axis.y, tensor.y_axis.y, tensor.z_axis.y],
+        [tensor.x_axis.z, tensor.y_axis.z, tensor.z_axis.z],
+    ];
+    let mut v = [[1.0, 0.0, 0.0], [0.0, 1.0, 0.0], [0.0, 0.0, 1.0]];
+    for _ in 0..32 {
+        let off = a[0][1].abs() + a[0][2].abs() + a[1][2].abs();
+        if off <= 1e-15 * (a[0][0].abs() + a[1][1].abs() + a[2][2].abs()).max(1e-300) {
+            break;
+        }
+        for (p, q) in [(0, 1), (0, 2), (1, 2)] {
+            if a[p][q].abs() <= f64::MIN_POSITIVE {
+                continue;
+            }
+            let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+            let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+            let c = 1.0 / (t * t + 1.0).sqrt();
+            let s = t * c;
+            for k in 0..3 {
+                let (akp, akq) = (a[k][p], a[k][q]);
+                a[k][p] = c * akp - s * akq;
+                a[k][q] = s * akp + c * akq;
+            }
+            for k in 0..3 {
+                let (apk, aqk) = (a[p][k], a[q][k]);
+                a[p][k] = c * apk - s * aqk;
+                a[q][k] = s * apk + c * aqk;
+            }
+            for row in v.iter_mut() {
+                let (vp, vq) = (row[p], row[q]);
+                row[p] = c * vp - s * vq;
+                row[q] = s * vp + c * vq;
+            }
+        }
+    }
+    let mut axes = DMat3::from_cols(
+        DVec3::new(v[0][0], v[1][0], v[2][0]),
+        DVec3::new(v[0][1], v[1][1], v[2][1]),
+        DVec3::new(v[0][2], v[1][2], v[2][2]),
+    );
+    // Eigenvectors come back as an orthonormal basis of either handedness; a
+    // quaternion can only say the proper one.
+    if axes.determinant() < 0.0 {
+        axes.z_axis = -axes.z_axis;
+    }
+    (
+        DVec3::new(a[0][0], a[1][1], a[2][2]),
+        DQuat::from_mat3(&axes).normalize(),
+    )
+}
+
+/// The inverse of [`principal_axes`]: `R·diag·Rᵀ`.
+pub(crate) fn inertia_tensor(diagonal: DVec3, axes: DQuat) -> DMat3 {
+    let r = DMat3::from_quat(axes);
+    r * DMat3::from_diagonal(diagonal) * r.transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rpy_round_trips_through_a_quaternion() {
+        let pose = UrdfPose {
+            xyz: DVec3::new(1.0, 2.0, 3.0),
+            rpy: DVec3::new(0.3, -0.4, 1.2),
+        };
+        let back = UrdfPose::from_parts(pose.xyz, pose.rotation());
+        assert!(
+            (back.rpy - pose.rpy).abs().max_element() < 1e-12,
+            "{back:?}"
+        );
+        // Fixed-axis roll first: a pure roll turns +Y toward +Z.
+        let roll = UrdfPose {
+            xyz: DVec3::ZERO,
+            rpy: DVec3::new(std::f64::consts::FRAC_PI_2, 0.0, 0.0),
+        };
+        assert!((roll.rotation() * DVec3::Y - DVec3::Z).length() < 1e-12);
+    }
+
+    #[test]
+    fn principal_axes_rebuild_the_tensor() {
+        let inertial = UrdfInertial {
+            origin: UrdfPose::IDENTITY,
+            mass: 2.0,
+            inertia: [0.4, 0.05, -0.02, 0.3, 0.01, 0.2],
+        };
+        let tensor = inertial.tensor();
+        let (diag, axes) = principal_axes(tensor);
+        let rebuilt = inertia_tensor(diag, axes);
+        for (a, b) in rebuilt.to_cols_array().iter().zip(tensor.to_cols_array()) {
+            assert!((a - b).abs() < 1e-12, "{rebuilt:?} vs {tensor:?}");
+        }
+        assert!(diag.min_element() > 0.0);
+    }
+}
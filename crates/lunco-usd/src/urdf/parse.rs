@@ -0,0 +1,484 @@
+//! URDF XML → [`UrdfRobot`].
+//!
+//! The document is read into a small element tree first and interpreted second:
+//! URDF references forward (a `<visual>` may name a `<material>` declared at the
+//! bottom of the file), so a single streaming pass would have to guess.
+
+use super::{
+    UrdfDynamics, UrdfError, UrdfGeometry, UrdfInertial, UrdfJoint, UrdfJointKind, UrdfLimit,
+    UrdfLink, UrdfMaterial, UrdfMimic, UrdfPose, UrdfRobot, UrdfShape, WORLD_LINK,
+};
+use bevy::math::DVec3;
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::{HashMap, HashSet};
+
+/// Nesting accepted before the document is refused. A URDF is four levels deep
+/// (`robot/link/visual/geometry/box`); anything near this is not a robot.
+const MAX_DEPTH: usize = 32;
+
+struct Element {
+    tag: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Element>,
+}
+
+impl Element {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+
+    fn child(&self, tag: &str) -> Option<&Element> {
+        self.children.iter().find(|c| c.tag == tag)
+    }
+
+    fn children<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a Element> + 'a {
+        self.children.iter().filter(move |c| c.tag == tag)
+    }
+
+    fn required(&self, key: &str) -> Result<&str, UrdfError> {
+        self.attr(key).ok_or_else(|| {
+            UrdfError::Invalid(format!("<{}> is missing its `{key}` attribute", self.tag))
+        })
+    }
+}
+
+/// Parse a URDF document.
+///
+/// Validates what the conversion relies on — every joint names existing links,
+/// no link has two parents, there is no cycle — so a returned robot is always a
+/// tree (or a forest, for links left unjointed).
+pub fn parse_urdf(xml: &str) -> Result<UrdfRobot, UrdfError> {
+    let root = read_tree(xml)?;
+    if root.tag != "robot" {
+        return Err(UrdfError::Invalid(format!(
+            "root element is <{}>, expected <robot>",
+            root.tag
+        )));
+    }
+    let name = root.attr("name").unwrap_or("robot").to_string();
+
+    // Top-level named materials; a `<visual>` may reference one by name alone.
+    let mut materials = HashMap::new();
+    for m in root.children("material") {
+        let material = material(m, &HashMap::new())?;
+        materials.insert(material.name.clone(), material);
+    }
+
+    let mut links = Vec::new();
+    let mut seen = HashSet::new();
+    for l in root.children("link") {
+        let link = link(l, &materials)?;
+        if !seen.insert(link.name.clone()) {
+            return Err(UrdfError::Invalid(format!(
+                "link `{}` is declared twice",
+                link.name
+            )));
+        }
+        links.push(link);
+    }
+    let mut joints = Vec::new();
+    for j in root.children("joint") {
+        joints.push(joint(j)?);
+    }
+
+    let robot = UrdfRobot {
+        name,
+        links,
+        joints,
+    };
+    check_tree(&robot)?;
+    Ok(robot)
+}
+
+fn read_tree(xml: &str) -> Result<Element, UrdfError> {
+    let mut reader = Reader::from_str(xml);
+    let mut stack: Vec<Element> = Vec::new();
+    let mut root: Option<Element> = None;
+    loop {
+        match reader
+            .read_event()
+            .map_err(|e| UrdfError::Xml(e.to_string()))?
+        {
+            Event::Start(e) => {
+                if stack.len() >= MAX_DEPTH {
+                    return Err(UrdfError::Xml(format!(
+                        "nested deeper than {MAX_DEPTH} elements"
+                    )));
+                }
+                stack.push(element(&e)?);
+            }
+            Event::Empty(e) => {
+                finish(element(&e)?, &mut stack, &mut root)?;
+            }
+            Event::End(_) => {
+                let el = stack
+                    .pop()
+                    .ok_or_else(|| UrdfError::Xml("unbalanced XML".into()))?;
+                finish(el, &mut stack, &mut root)?;
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    if !stack.is_empty() {
+        return Err(UrdfError::Xml("unbalanced XML".into()));
+    }
+    root.ok_or_else(|| UrdfError::Xml("document has no root element".into()))
+}
+
+/// Attach a closed element to its parent, or make it the document root.
+fn finish(e: Element, stack: &mut [Element], root: &mut Option<Element>) -> Result<(), UrdfError> {
+    match stack.last_mut() {
+        Some(parent) => parent.children.push(e),
+        None if root.is_none() => *root = Some(e),
+        None => return Err(UrdfError::Xml("more than one root element".into())),
+    }
+    Ok(())
+}
+
+fn element(e: &BytesStart) -> Result<Element, UrdfError> {
+    let mut attrs = Vec::new();
+    for a in e.attributes() {
+        let a = a.map_err(|x| UrdfError::Xml(x.to_string()))?;
+        let k = String::from_utf8_lossy(a.key.as_ref()).into_owned();
+        let v = a
+            .normalized_value(quick_xml::XmlVersion::Implicit1_0)
+            .map_err(|x| UrdfError::Xml(x.to_string()))?
+            .into_owned();
+        attrs.push((k, v));
+    }
+    Ok(Element {
+        tag: String::from_utf8_lossy(e.name().as_ref()).into_owned(),
+        attrs,
+        children: Vec::new(),
+    })
+}
+
+fn number(el: &Element, key: &str, text: &str) -> Result<f64, UrdfError> {
+    let v: f64 = text.trim().parse().map_err(|_| {
+        UrdfError::Invalid(format!("<{} {key}=\"{text}\"> is not a number", el.tag))
+    })?;
+    if !v.is_finite() {
+        return Err(UrdfError::Invalid(format!(
+            "<{} {key}=\"{text}\"> is not finite",
+            el.tag
+        )));
+    }
+    Ok(v)
+}
+
+fn numbers<const N: usize>(el: &Element, key: &str, text: &str) -> Result<[f64; N], UrdfError> {
+    let parts: Vec<&str> = text.split_whitespace().collect();
+    if parts.len() != N {
+        return Err(UrdfError::Invalid(format!(
+            "<{} {key}=\"{text}\"> needs {N} numbers",
+            el.tag
+        )));
+    }
+    let mut out = [0.0; N];
+    for (slot, part) in out.iter_mut().zip(parts) {
+        *slot = number(el, key, part)?;
+    }
+    Ok(out)
+}
+
+fn opt_number(el: &Element, key: &str, default: f64) -> Result<f64, UrdfError> {
+    el.attr(key).map_or(Ok(default), |v| number(el, key, v))
+}
+
+fn vec3(el: &Element, key: &str, default: DVec3) -> Result<DVec3, UrdfError> {
+    el.attr(key).map_or(Ok(default), |v| {
+        numbers::<3>(el, key, v).map(DVec3::from_array)
+    })
+}
+
+fn origin(parent: &Element) -> Result<UrdfPose, UrdfError> {
+    let Some(o) = parent.child("origin") else {
+        return Ok(UrdfPose::IDENTITY);
+    };
+    Ok(UrdfPose {
+        xyz: vec3(o, "xyz", DVec3::ZERO)?,
+        rpy: vec3(o, "rpy", DVec3::ZERO)?,
+    })
+}
+
+fn material(
+    el: &Element,
+    named: &HashMap<String, UrdfMaterial>,
+) -> Result<UrdfMaterial, UrdfError> {
+    let name = el.attr("name").unwrap_or_default().to_string();
+    let color = match el.child("color") {
+        Some(c) => Some(numbers::<4>(c, "rgba", c.required("rgba")?)?),
+        None => None,
+    };
+    let texture = el
+        .child("texture")
+        .and_then(|t| t.attr("filename"))
+        .map(str::to_string);
+    if color.is_none() && texture.is_none() {
+        // A bare `<material name="x"/>` is a reference to a top-level one.
+        if let Some(m) = named.get(&name) {
+            return Ok(m.clone());
+        }
+    }
+    Ok(UrdfMaterial {
+        name,
+        color,
+        texture,
+    })
+}
+
+fn geometry(parent: &Element) -> Result<UrdfGeometry, UrdfError> {
+    let g = parent
+        .child("geometry")
+        .ok_or_else(|| UrdfError::Invalid(format!("<{}> has no <geometry>", parent.tag)))?;
+    let shape = g
+        .children
+        .first()
+        .ok_or_else(|| UrdfError::Invalid("<geometry> is empty".into()))?;
+    Ok(match shape.tag.as_str() {
+        "box" => UrdfGeometry::Box {
+            size: DVec3::from_array(numbers::<3>(shape, "size", shape.required("size")?)?),
+        },
+        "cylinder" => UrdfGeometry::Cylinder {
+            radius: number(shape, "radius", shape.required("radius")?)?,
+            length: number(shape, "length", shape.required("length")?)?,
+        },
+        "sphere" => UrdfGeometry::Sphere {
+            radius: number(shape, "radius", shape.required("radius")?)?,
+        },
+        "mesh" => UrdfGeometry::Mesh {
+            filename: shape.required("filename")?.to_string(),
+            scale: vec3(shape, "scale", DVec3::ONE)?,
+        },
+        other => {
+            return Err(UrdfError::Invalid(format!(
+                "unsupported geometry <{other}>"
+            )))
+        }
+    })
+}
+
+fn shape(el: &Element, named: &HashMap<String, UrdfMaterial>) -> Result<UrdfShape, UrdfError> {
+    Ok(UrdfShape {
+        name: el.attr("name").map(str::to_string),
+        origin: origin(el)?,
+        geometry: geometry(el)?,
+        material: el
+            .child("material")
+            .map(|m| material(m, named))
+            .transpose()?,
+    })
+}
+
+fn link(el: &Element, named: &HashMap<String, UrdfMaterial>) -> Result<UrdfLink, UrdfError> {
+    let inertial = match el.child("inertial") {
+        Some(i) => {
+            let mass = i
+                .child("mass")
+                .ok_or_else(|| UrdfError::Invalid("<inertial> has no <mass>".into()))?;
+            let mut inertia = [0.0; 6];
+            if let Some(t) = i.child("inertia") {
+                for (slot, key) in inertia
+                    .iter_mut()
+                    .zip(["ixx", "ixy", "ixz", "iyy", "iyz", "izz"])
+                {
+                    *slot = opt_number(t, key, 0.0)?;
+                }
+            }
+            Some(UrdfInertial {
+                origin: origin(i)?,
+                mass: number(mass, "value", mass.required("value")?)?,
+                inertia,
+            })
+        }
+        None => None,
+    };
+    Ok(UrdfLink {
+        name: el.required("name")?.to_string(),
+        inertial,
+        visuals: el
+            .children("visual")
+            .map(|v| shape(v, named))
+            .collect::<Result<_, _>>()?,
+        collisions: el
+            .children("collision")
+            .map(|c| {
+                // Collision geometry has no appearance; a stray <material> is dropped.
+                shape(c, named).map(|s| UrdfShape {
+                    material: None,
+                    ..s
+                })
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+fn joint(el: &Element) -> Result<UrdfJoint, UrdfError> {
+    let name = el.required("name")?.to_string();
+    let kind_text = el.required("type")?;
+    let kind = UrdfJointKind::parse(kind_text).ok_or_else(|| {
+        UrdfError::Invalid(format!("joint `{name}` has unknown type `{kind_text}`"))
+    })?;
+    let link_of = |tag: &str| -> Result<String, UrdfError> {
+        el.child(tag)
+            .and_then(|c| c.attr("link"))
+            .map(str::to_string)
+            .ok_or_else(|| UrdfError::Invalid(format!("joint `{name}` has no <{tag} link>")))
+    };
+    let axis = match el.child("axis") {
+        Some(a) => vec3(a, "xyz", DVec3::X)?,
+        None => DVec3::X,
+    };
+    let axis = axis
+        .try_normalize()
+        .ok_or_else(|| UrdfError::Invalid(format!("joint `{name}` has a zero-length axis")))?;
+    let limit = match el.child("limit") {
+        Some(l) => Some(UrdfLimit {
+            lower: opt_number(l, "lower", 0.0)?,
+            upper: opt_number(l, "upper", 0.0)?,
+            effort: opt_number(l, "effort", 0.0)?,
+            velocity: opt_number(l, "velocity", 0.0)?,
+        }),
+        None => None,
+    };
+    let mimic = match el.child("mimic") {
+        Some(m) => Some(UrdfMimic {
+            joint: m.required("joint")?.to_string(),
+            multiplier: opt_number(m, "multiplier", 1.0)?,
+            offset: opt_number(m, "offset", 0.0)?,
+        }),
+        None => None,
+    };
+    let dynamics = match el.child("dynamics") {
+        Some(d) => Some(UrdfDynamics {
+            damping: opt_number(d, "damping", 0.0)?,
+            friction: opt_number(d, "friction", 0.0)?,
+        }),
+        None => None,
+    };
+    Ok(UrdfJoint {
+        kind,
+        parent: link_of("parent")?,
+        child: link_of("child")?,
+        origin: origin(el)?,
+        axis,
+        limit,
+        mimic,
+        dynamics,
+        name,
+    })
+}
+
+fn check_tree(robot: &UrdfRobot) -> Result<(), UrdfError> {
+    let links: HashSet<&str> = robot.links.iter().map(|l| l.name.as_str()).collect();
+    let mut parent_of: HashMap<&str, &str> = HashMap::new();
+    let mut joint_names = HashSet::new();
+    for j in &robot.joints {
+        if !joint_names.insert(j.name.as_str()) {
+            return Err(UrdfError::Invalid(format!(
+                "joint `{}` is declared twice",
+                j.name
+            )));
+        }
+        for end in [&j.parent, &j.child] {
+            // ROS robots commonly name a `world` parent without declaring it.
+            if !links.contains(end.as_str()) && end != WORLD_LINK {
+                return Err(UrdfError::NotATree(format!(
+                    "joint `{}` names missing link `{end}`",
+                    j.name
+                )));
+            }
+        }
+        if parent_of.insert(&j.child, &j.parent).is_some() {
+            return Err(UrdfError::NotATree(format!(
+                "link `{}` is the child of more than one joint",
+                j.child
+            )));
+        }
+    }
+    for j in &robot.joints {
+        if let Some(m) = &j.mimic {
+            if !joint_names.contains(m.joint.as_str()) {
+                return Err(UrdfError::Invalid(format!(
+                    "joint `{}` mimics missing joint `{}`",
+                    j.name, m.joint
+                )));
+            }
+        }
+    }
+    for start in parent_of.keys() {
+        let mut cur = *start;
+        for _ in 0..=parent_of.len() {
+            match parent_of.get(cur) {
+                Some(p) => cur = p,
+                None => break,
+            }
+            if cur == *start {
+                return Err(UrdfError::NotATree(format!(
+                    "link `{start}` is its own ancestor"
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ARM: &str = r#"<?xml version="1.0"?>
+<robot name="arm">
+  <link name="base">
+    <visual><geometry><box size="0.2 0.2 0.1"/></geometry><material name="grey"/></visual>
+  </link>
+  <link name="upper">
+    <inertial><mass value="1.5"/><inertia ixx="0.1" iyy="0.2" izz="0.3"/></inertial>
+  </link>
+  <joint name="shoulder" type="revolute">
+    <parent link="base"/><child link="upper"/>
+    <origin xyz="0 0 0.1" rpy="0 0 1.5707963"/>
+    <axis xyz="0 0 2"/>
+    <limit lower="-1" upper="1" effort="10" velocity="2"/>
+  </joint>
+  <material name="grey"><color rgba="0.5 0.5 0.5 1"/></material>
+</robot>"#;
+
+    #[test]
+    fn reads_links_joints_and_forward_materials() {
+        let robot = parse_urdf(ARM).unwrap();
+        assert_eq!(robot.name, "arm");
+        assert_eq!(robot.links.len(), 2);
+        let grey = robot.links[0].visuals[0].material.as_ref().unwrap();
+        assert_eq!(
+            grey.color,
+            Some([0.5, 0.5, 0.5, 1.0]),
+            "named material resolves"
+        );
+        let j = &robot.joints[0];
+        assert_eq!(j.kind, UrdfJointKind::Revolute);
+        assert_eq!(j.axis, DVec3::Z, "axis is normalised");
+        assert_eq!(j.limit.unwrap().upper, 1.0);
+        assert_eq!(robot.link("upper").unwrap().inertial.unwrap().mass, 1.5);
+    }
+
+    #[test]
+    fn a_link_with_two_parents_is_refused() {
+        let xml = ARM.replace(
+            "<material name=\"grey\"><color",
+            "<joint name=\"again\" type=\"fixed\"><parent link=\"base\"/><child link=\"upper\"/></joint>\n  <material name=\"grey\"><color",
+        );
+        assert!(matches!(parse_urdf(&xml), Err(UrdfError::NotATree(_))));
+    }
+
+    #[test]
+    fn a_missing_link_is_refused() {
+        let xml = ARM.replace("<child link=\"upper\"/>", "<child link=\"nope\"/>");
+        assert!(matches!(parse_urdf(&xml), Err(UrdfError::NotATree(_))));
+    }
+}
@@ -0,0 +1,166 @@
+//! [`UrdfRobot`] → URDF XML.
+//!
+//! Hand-formatted rather than driven through an XML writer: URDF is flat and
+//! attribute-only, and ROS tooling diffs these files, so the layout is kept
+//! deterministic and close to what `xacro` emits.
+
+use super::{UrdfGeometry, UrdfJointKind, UrdfPose, UrdfRobot, UrdfShape};
+use bevy::math::DVec3;
+use std::fmt::Write as _;
+
+/// Serialize a robot as a URDF document.
+pub fn write_urdf(robot: &UrdfRobot) -> String {
+    let mut out = String::from("<?xml version=\"1.0\"?>\n");
+    let _ = writeln!(out, "<robot name=\"{}\">", esc(&robot.name));
+    for link in &robot.links {
+        if link.is_frame_only() {
+            let _ = writeln!(out, "  <link name=\"{}\"/>", esc(&link.name));
+            continue;
+        }
+        let _ = writeln!(out, "  <link name=\"{}\">", esc(&link.name));
+        if let Some(i) = &link.inertial {
+            out.push_str("    <inertial>\n");
+            origin(&mut out, 6, &i.origin);
+            let _ = writeln!(out, "      <mass value=\"{}\"/>", i.mass);
+            let [xx, xy, xz, yy, yz, zz] = i.inertia;
+            let _ = writeln!(
+                out,
+                "      <inertia ixx=\"{xx}\" ixy=\"{xy}\" ixz=\"{xz}\" iyy=\"{yy}\" iyz=\"{yz}\" izz=\"{zz}\"/>"
+            );
+            out.push_str("    </inertial>\n");
+        }
+        for v in &link.visuals {
+            shape(&mut out, "visual", v);
+        }
+        for c in &link.collisions {
+            shape(&mut out, "collision", c);
+        }
+        out.push_str("  </link>\n");
+    }
+    for j in &robot.joints {
+        let _ = writeln!(
+            out,
+            "  <joint name=\"{}\" type=\"{}\">",
+            esc(&j.name),
+            j.kind.as_str()
+        );
+        let _ = writeln!(out, "    <parent link=\"{}\"/>", esc(&j.parent));
+        let _ = writeln!(out, "    <child link=\"{}\"/>", esc(&j.child));
+        origin(&mut out, 4, &j.origin);
+        if !matches!(j.kind, UrdfJointKind::Fixed | UrdfJointKind::Floating) {
+            let _ = writeln!(out, "    <axis xyz=\"{}\"/>", v3(j.axis));
+        }
+        if let Some(l) = &j.limit {
+            // `continuous` takes effort/velocity only; lower/upper are ignored by
+            // every parser and would read as a contradiction.
+            if j.kind == UrdfJointKind::Continuous {
+                let _ = writeln!(
+                    out,
+                    "    <limit effort=\"{}\" velocity=\"{}\"/>",
+                    l.effort, l.velocity
+                );
+            } else {
+                let _ = writeln!(
+                    out,
+                    "    <limit lower=\"{}\" upper=\"{}\" effort=\"{}\" velocity=\"{}\"/>",
+                    l.lower, l.upper, l.effort, l.velocity
+                );
+            }
+        }
+        if let Some(d) = &j.dynamics {
+            let _ = writeln!(
+                out,
+                "    <dynamics damping=\"{}\" friction=\"{}\"/>",
+                d.damping, d.friction
+            );
+        }
+        if let Some(m) = &j.mimic {
+            let _ = writeln!(
+                out,
+                "    <mimic joint=\"{}\" multiplier=\"{}\" offset=\"{}\"/>",
+                esc(&m.joint),
+                m.multiplier,
+                m.offset
+            );
+        }
+        out.push_str("  </joint>\n");
+    }
+    out.push_str("</robot>\n");
+    out
+}
+
+fn shape(out: &mut String, tag: &str, s: &UrdfShape) {
+    match &s.name {
+        Some(name) => {
+            let _ = writeln!(out, "    <{tag} name=\"{}\">", esc(name));
+        }
+        None => {
+            let _ = writeln!(out, "    <{tag}>");
+        }
+    }
+    origin(out, 6, &s.origin);
+    out.push_str("      <geometry>\n");
+    let _ = match &s.geometry {
+        UrdfGeometry::Box { size } => writeln!(out, "        <box size=\"{}\"/>", v3(*size)),
+        UrdfGeometry::Cylinder { radius, length } => writeln!(
+            out,
+            "        <cylinder radius=\"{radius}\" length=\"{length}\"/>"
+        ),
+        UrdfGeometry::Sphere { radius } => {
+            writeln!(out, "        <sphere radius=\"{radius}\"/>")
+        }
+        UrdfGeometry::Mesh { filename, scale } if *scale == DVec3::ONE => {
+            writeln!(out, "        <mesh filename=\"{}\"/>", esc(filename))
+        }
+        UrdfGeometry::Mesh { filename, scale } => writeln!(
+            out,
+            "        <mesh filename=\"{}\" scale=\"{}\"/>",
+            esc(filename),
+            v3(*scale)
+        ),
+    };
+    out.push_str("      </geometry>\n");
+    if let Some(m) = &s.material {
+        let _ = writeln!(out, "      <material name=\"{}\">", esc(&m.name));
+        if let Some([r, g, b, a]) = m.color {
+            let _ = writeln!(out, "        <color rgba=\"{r} {g} {b} {a}\"/>");
+        }
+        if let Some(t) = &m.texture {
+            let _ = writeln!(out, "        <texture filename=\"{}\"/>", esc(t));
+        }
+        out.push_str("      </material>\n");
+    }
+    let _ = writeln!(out, "    </{tag}>");
+}
+
+/// `<origin>`, omitted when it is the identity (the URDF default).
+fn origin(out: &mut String, indent: usize, pose: &UrdfPose) {
+    let (xyz, rpy) = (clean(pose.xyz), clean(pose.rpy));
+    if xyz == DVec3::ZERO && rpy == DVec3::ZERO {
+        return;
+    }
+    let _ = writeln!(
+        out,
+        "{:indent$}<origin xyz=\"{}\" rpy=\"{}\"/>",
+        "",
+        v3(xyz),
+        v3(rpy)
+    );
+}
+
+fn v3(v: DVec3) -> String {
+    format!("{} {} {}", v.x, v.y, v.z)
+}
+
+/// Round float noise to zero so an identity pose that went through a quaternion
+/// writes `0`, not `1.2e-17`.
+fn clean(v: DVec3) -> DVec3 {
+    v.map(|c| if c.abs() < 1e-12 { 0.0 } else { c })
+}
+
+fn esc(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
@@ -0,0 +1,130 @@
+//! URDF → USD → URDF through the real composer.
+//!
+//! The unit tests in `src/urdf/` pin the parser and the maths; this one pins the
+//! seam that matters: an imported layer composes into a stage the physics
+//! bridge reads as the same robot, and exporting that stage gives the robot
+//! back — same tree, same joint origins, same limits.
+
+use lunco_usd::urdf::{export_urdf, import_urdf, parse_urdf, UrdfImportOptions, UrdfJointKind};
+use lunco_usd_bevy::{CanonicalStage, SdfPath, StageRecipe, UsdRead};
+
+const ARM: &str = r#"<?xml version="1.0"?>
+<robot name="arm">
+  <material name="grey"><color rgba="0.5 0.5 0.5 1"/></material>
+  <link name="base">
+    <inertial>
+      <mass value="4"/>
+      <inertia ixx="0.1" ixy="0" ixz="0" iyy="0.1" iyz="0" izz="0.2"/>
+    </inertial>
+    <visual>
+      <geometry><cylinder radius="0.1" length="0.05"/></geometry>
+      <material name="grey"/>
+    </visual>
+    <collision>
+      <geometry><cylinder radius="0.1" length="0.05"/></geometry>
+    </collision>
+  </link>
+  <link name="upper">
+    <inertial>
+      <origin xyz="0 0 0.2" rpy="0 0 0"/>
+      <mass value="1"/>
+      <inertia ixx="0.02" ixy="0" ixz="0" iyy="0.02" iyz="0" izz="0.001"/>
+    </inertial>
+    <visual>
+      <origin xyz="0 0 0.2" rpy="0 0 0"/>
+      <geometry><box size="0.05 0.05 0.4"/></geometry>
+    </visual>
+  </link>
+  <link name="tool">
+    <collision>
+      <geometry><sphere radius="0.03"/></geometry>
+    </collision>
+  </link>
+  <joint name="shoulder" type="revolute">
+    <parent link="base"/>
+    <child link="upper"/>
+    <origin xyz="0 0 0.05" rpy="0 0 1.5707963267948966"/>
+    <axis xyz="0 1 0"/>
+    <limit lower="-1.2" upper="1.2" effort="20" velocity="2"/>
+    <dynamics damping="0.5" friction="0"/>
+  </joint>
+  <joint name="wrist" type="continuous">
+    <parent link="upper"/>
+    <child link="tool"/>
+    <origin xyz="0 0 0.4" rpy="0 0 0"/>
+    <axis xyz="0 0 1"/>
+  </joint>
+</robot>
+"#;
+
+#[test]
+fn imported_urdf_composes_and_exports_back() {
+    let import = import_urdf(ARM, &UrdfImportOptions::default()).expect("import");
+    assert!(import.warnings.is_empty(), "{:?}", import.warnings);
+
+    let stage = CanonicalStage::from_recipe(&StageRecipe::from_source("arm.usda", &import.usda))
+        .expect("the imported layer composes");
+    let view = stage.view();
+    let root = SdfPath::new(&import.root).unwrap();
+    for link in ["base", "upper", "tool"] {
+        let p = SdfPath::new(&format!("{}/{link}", import.root)).unwrap();
+        assert!(
+            view.has_api_schema(&p, "PhysicsRigidBodyAPI"),
+            "{link} is a body"
+        );
+    }
+    let shoulder = SdfPath::new(&format!("{}/Joints/shoulder", import.root)).unwrap();
+    assert_eq!(
+        view.type_name(&shoulder).as_deref(),
+        Some("PhysicsRevoluteJoint")
+    );
+    let upper = view.real(&shoulder, "physics:upperLimit").unwrap();
+    assert!(
+        (upper - 1.2f64.to_degrees()).abs() < 1e-3,
+        "limits cross as degrees"
+    );
+
+    let export = export_urdf(&view, &root).expect("export");
+    assert!(export.warnings.is_empty(), "{:?}", export.warnings);
+    let original = parse_urdf(ARM).unwrap();
+    let back = parse_urdf(&export.urdf).expect("exported URDF parses");
+    let names = |r: &lunco_usd::urdf::UrdfRobot| -> Vec<String> {
+        r.links.iter().map(|l| l.name.clone()).collect()
+    };
+    assert_eq!(names(&back), names(&original));
+
+    for name in ["shoulder", "wrist"] {
+        let a = original.joints.iter().find(|j| j.name == name).unwrap();
+        let b = back.joints.iter().find(|j| j.name == name).unwrap();
+        assert_eq!((a.kind, &a.parent, &a.child), (b.kind, &b.parent, &b.child));
+        // Link frames survive the trip: the importer turns only the JOINT onto
+        // USD's cardinal axis, never the link.
+        assert!(
+            a.origin.xyz.abs_diff_eq(b.origin.xyz, 1e-5),
+            "{name} origin"
+        );
+        assert!(
+            a.origin.rotation().abs_diff_eq(b.origin.rotation(), 1e-5),
+            "{name} rpy"
+        );
+        assert!(a.axis.abs_diff_eq(b.axis, 1e-5), "{name} axis");
+    }
+    let shoulder = back.joints.iter().find(|j| j.name == "shoulder").unwrap();
+    let limit = shoulder.limit.unwrap();
+    assert!((limit.upper - 1.2).abs() < 1e-5 && (limit.effort - 20.0).abs() < 1e-9);
+    assert_eq!(shoulder.dynamics.map(|d| d.damping), Some(0.5));
+    assert_eq!(
+        back.joints.iter().find(|j| j.name == "wrist").unwrap().kind,
+        UrdfJointKind::Continuous
+    );
+
+    let base = back.link("base").unwrap();
+    assert!((base.inertial.unwrap().mass - 4.0).abs() < 1e-6);
+    assert_eq!((base.visuals.len(), base.collisions.len()), (1, 1));
+    let tool = back.link("tool").unwrap();
+    assert_eq!(
+        (tool.visuals.len(), tool.collisions.len()),
+        (0, 1),
+        "collision-only stays hidden"
+    );
+}
@@ -616,6 +616,8 @@ fn on_pick_resolved(trigger: On<PickResolved>, mut commands: Commands) {
         PickFollowUp::SaveAsTwin => {
             commands.trigger(SaveAsTwin { folder: path });
         }
+        // Domain-owned follow-up; its crate observes `PickResolved` itself.
+        PickFollowUp::ImportResult(_) => {}
     }
 }
 
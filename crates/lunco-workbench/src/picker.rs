@@ -153,6 +153,10 @@ pub enum PickFollowUp {
     /// Resolve → trigger `SaveAsTwin { folder }` to promote the
     /// current session into a Twin at the chosen folder.
     SaveAsTwin,
+    /// Resolve → the owning domain imports the chosen result file as a
+    /// reference run of this document. Not dispatched by the workbench:
+    /// the domain crate handles it in its own `PickResolved` observer.
+    ImportResult(DocumentId),
 }
 
 /// Request to show a system file dialog.